@@ -9,6 +9,10 @@
 //!   # Generate a new keypair (do this ONCE, save the output!)
 //!   cargo run -- --generate-keys
 //!
+//!   # RSA-4096 instead of Ed25519, for customers whose policy requires RSA
+//!   # (build with --release, key generation is slow in debug builds)
+//!   cargo run --release -- --generate-keys --algorithm rsa
+//!
 //!   # Generate a license key
 //!   cargo run -- --private-key <KEY> --customer "john@acme.com" --expires "2026-12-31"
 //!
@@ -18,27 +22,86 @@
 //!     --company "ACME Corp" \
 //!     --expires "2026-12-31" \
 //!     --features "premium,export,api" \
-//!     --seats 5
+//!     --seats 5 \
+//!     --grace-days 14
+//!
+//!   # Sign with an RSA private key
+//!   cargo run -- --algorithm rsa --private-key <RSA_KEY> --customer "john@acme.com" --expires "2026-12-31"
+//!
+//!   # Export a signed compliance report for a customer's license key
+//!   cargo run -- --export-license-report report.json \
+//!     --license-key <LICENSE_KEY> --public-key <PUBLIC_KEY>
 
 use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
 use chrono::Utc;
 use clap::Parser;
+use clap::ValueEnum;
 use ed25519_dalek::{Signer, SigningKey, Verifier, VerifyingKey};
+use hmac::{Hmac, Mac};
 use rand::rngs::OsRng;
+use rsa::pkcs8::{DecodePrivateKey, DecodePublicKey, EncodePrivateKey, EncodePublicKey};
+use rsa::signature::{RandomizedSigner, SignatureEncoding};
+use rsa::traits::PublicKeyParts;
+use rsa::{RsaPrivateKey, RsaPublicKey};
 use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
 /// License key prefix
 const LICENSE_PREFIX: &str = "ABF-";
 
+/// Product identifier (must match the app's PRODUCT_ID)
+const PRODUCT_ID: &str = "amsterdam-bike-fleet";
+
+/// Reports from this tool weren't generated on a customer machine
+const GENERATOR_FINGERPRINT: &str = "license-generator";
+
+/// RSA modulus size for `--algorithm rsa`
+const RSA_KEY_BITS: usize = 4096;
+
+/// Payload version of the keys this tool generates (v2: algorithm byte first)
+const LICENSE_VERSION: u32 = 2;
+
+/// Signature algorithm of a license (must match the app's SignatureAlgorithm)
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum Algorithm {
+    Ed25519,
+    /// RSA-4096 with PSS padding and SHA-256
+    Rsa,
+}
+
+impl Algorithm {
+    /// First byte of a v2 license key
+    fn version_byte(self) -> u8 {
+        match self {
+            Algorithm::Ed25519 => 0x01,
+            Algorithm::Rsa => 0x02,
+        }
+    }
+
+    fn from_version_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0x01 => Some(Algorithm::Ed25519),
+            0x02 => Some(Algorithm::Rsa),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Parser, Debug)]
 #[command(name = "license-generator")]
 #[command(about = "Generate signed license keys for Amsterdam Bike Fleet")]
 struct Args {
-    /// Generate a new Ed25519 keypair
+    /// Generate a new keypair for --algorithm
     #[arg(long)]
     generate_keys: bool,
 
-    /// Private key (base64 encoded, 32 bytes)
+    /// Signature algorithm for --generate-keys and new licenses
+    #[arg(long, value_enum, default_value_t = Algorithm::Ed25519)]
+    algorithm: Algorithm,
+
+    /// Private key (base64 encoded: 32 bytes for Ed25519, PKCS#8 DER for RSA)
     #[arg(long)]
     private_key: Option<String>,
 
@@ -62,13 +125,26 @@ struct Args {
     #[arg(long)]
     seats: Option<u32>,
 
+    /// Days the license keeps working after it expires (optional)
+    #[arg(long)]
+    grace_days: Option<u32>,
+
     /// Verify an existing license key
     #[arg(long)]
     verify: Option<String>,
 
-    /// Public key for verification (base64 encoded)
+    /// Public key for verification (base64 encoded: 32 bytes for Ed25519,
+    /// SubjectPublicKeyInfo DER for RSA)
     #[arg(long)]
     public_key: Option<String>,
+
+    /// Write a signed compliance report for --license-key to this JSON file
+    #[arg(long, value_name = "OUTPUT_JSON")]
+    export_license_report: Option<PathBuf>,
+
+    /// License key to report on (used with --export-license-report)
+    #[arg(long)]
+    license_key: Option<String>,
 }
 
 /// License payload structure (must match the app's LicenseInfo)
@@ -82,15 +158,36 @@ struct LicensePayload {
     features: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     seats: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    grace_period_days: Option<u32>,
     issued: String,
     version: u32,
 }
 
+/// Compliance report (must match the app's LicenseReport)
+#[derive(Debug, Serialize)]
+struct LicenseReport {
+    license_valid: bool,
+    customer: Option<String>,
+    company: Option<String>,
+    product: String,
+    expires: Option<String>,
+    days_remaining: Option<i64>,
+    features: Vec<String>,
+    seats: Option<u32>,
+    machine_fingerprint: String,
+    report_generated_at: String,
+    report_signature: String,
+}
+
 fn main() {
     let args = Args::parse();
 
     if args.generate_keys {
-        generate_keypair();
+        match args.algorithm {
+            Algorithm::Ed25519 => generate_keypair(),
+            Algorithm::Rsa => generate_rsa_keypair(),
+        }
         return;
     }
 
@@ -104,6 +201,19 @@ fn main() {
         return;
     }
 
+    if let Some(output) = args.export_license_report {
+        let license_key = args.license_key.unwrap_or_else(|| {
+            eprintln!("Error: --license-key is required to export a license report");
+            std::process::exit(1);
+        });
+        let public_key = args.public_key.unwrap_or_else(|| {
+            eprintln!("Error: --public-key is required to export a license report");
+            std::process::exit(1);
+        });
+        export_license_report(&license_key, &public_key, &output);
+        return;
+    }
+
     // Generate a license key
     let private_key = args.private_key.unwrap_or_else(|| {
         eprintln!("Error: --private-key is required to generate a license");
@@ -127,12 +237,14 @@ fn main() {
         .unwrap_or_default();
 
     generate_license(
+        args.algorithm,
         &private_key,
         &customer,
         args.company,
         &expires,
         features,
         args.seats,
+        args.grace_days,
     );
 }
 
@@ -172,38 +284,87 @@ fn generate_keypair() {
     println!("  const PUBLIC_KEY_BASE64: &str = \"{}\";", public_key_b64);
 }
 
+/// Print an RSA-4096 keypair: PKCS#8 private key and SubjectPublicKeyInfo
+/// public key, both DER in URL-safe base64
+fn generate_rsa_keypair() {
+    println!("Generating RSA-{} keypair (this can take a while)...", RSA_KEY_BITS);
+    println!();
+
+    let private_key = RsaPrivateKey::new(&mut OsRng, RSA_KEY_BITS).unwrap_or_else(|e| {
+        eprintln!("Error: RSA key generation failed: {}", e);
+        std::process::exit(1);
+    });
+    let private_der = private_key
+        .to_pkcs8_der()
+        .expect("Failed to encode private key");
+    let public_der = private_key
+        .to_public_key()
+        .to_public_key_der()
+        .expect("Failed to encode public key");
+
+    let private_key_b64 = URL_SAFE_NO_PAD.encode(private_der.as_bytes());
+    let public_key_b64 = URL_SAFE_NO_PAD.encode(public_der.as_bytes());
+
+    println!("SECURITY WARNING: save the private key securely and NEVER share or commit it.");
+    println!();
+    println!("PRIVATE KEY (KEEP SECRET!):");
+    println!("{}", private_key_b64);
+    println!();
+    println!("PUBLIC KEY (embed in src-tauri/src/license.rs):");
+    println!("{}", public_key_b64);
+    println!();
+    println!("Next steps:");
+    println!("1. Open src-tauri/src/license.rs");
+    println!("2. Replace the placeholder in the RSA_PUBLIC_KEY_BASE64 constant");
+    println!("3. Sign licenses with --algorithm rsa --private-key <PRIVATE KEY>");
+}
+
+/// `[version byte] + payload + signature`, signed with `private_key_b64`
+fn sign_license(
+    algorithm: Algorithm,
+    private_key_b64: &str,
+    payload_bytes: &[u8],
+) -> Result<Vec<u8>, String> {
+    let private_key_bytes = URL_SAFE_NO_PAD
+        .decode(private_key_b64)
+        .map_err(|e| format!("Invalid private key format: {}", e))?;
+
+    let signature = match algorithm {
+        Algorithm::Ed25519 => {
+            let key_bytes: [u8; 32] = private_key_bytes.as_slice().try_into().map_err(|_| {
+                format!("Private key must be 32 bytes, got {}", private_key_bytes.len())
+            })?;
+            SigningKey::from_bytes(&key_bytes)
+                .sign(payload_bytes)
+                .to_bytes()
+                .to_vec()
+        }
+        Algorithm::Rsa => {
+            let private_key = RsaPrivateKey::from_pkcs8_der(&private_key_bytes)
+                .map_err(|e| format!("Invalid RSA private key: {}", e))?;
+            rsa::pss::BlindedSigningKey::<Sha256>::new(private_key)
+                .sign_with_rng(&mut OsRng, payload_bytes)
+                .to_vec()
+        }
+    };
+
+    let mut combined = vec![algorithm.version_byte()];
+    combined.extend_from_slice(payload_bytes);
+    combined.extend_from_slice(&signature);
+    Ok(combined)
+}
+
+#[allow(clippy::too_many_arguments)]
 fn generate_license(
+    algorithm: Algorithm,
     private_key_b64: &str,
     customer: &str,
     company: Option<String>,
     expires: &str,
     features: Vec<String>,
     seats: Option<u32>,
+    grace_period_days: Option<u32>,
 ) {
-    // Decode private key
-    let private_key_bytes = match URL_SAFE_NO_PAD.decode(private_key_b64) {
-        Ok(bytes) => bytes,
-        Err(e) => {
-            eprintln!("Error: Invalid private key format: {}", e);
-            std::process::exit(1);
-        }
-    };
-
-    if private_key_bytes.len() != 32 {
-        eprintln!(
-            "Error: Private key must be 32 bytes, got {}",
-            private_key_bytes.len()
-        );
-        std::process::exit(1);
-    }
-
-    let signing_key = SigningKey::from_bytes(
-        private_key_bytes
-            .as_slice()
-            .try_into()
-            .expect("Invalid key length"),
-    );
-
     // Create license payload
     let payload = LicensePayload {
         customer: customer.to_string(),
@@ -212,19 +373,19 @@ fn generate_license(
         expires: expires.to_string(),
         features,
         seats,
+        grace_period_days,
         issued: Utc::now().format("%Y-%m-%d").to_string(),
-        version: 1,
+        version: LICENSE_VERSION,
     };
 
     let payload_json = serde_json::to_string(&payload).expect("Failed to serialize payload");
-    let payload_bytes = payload_json.as_bytes();
-
-    // Sign the payload
-    let signature = signing_key.sign(payload_bytes);
 
-    // Combine payload + signature
-    let mut combined = payload_bytes.to_vec();
-    combined.extend_from_slice(&signature.to_bytes());
+    // Sign the payload: [version byte][payload][signature]
+    let combined = sign_license(algorithm, private_key_b64, payload_json.as_bytes())
+        .unwrap_or_else(|e| {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        });
 
     // Encode as base64
     let encoded = URL_SAFE_NO_PAD.encode(&combined);
@@ -248,6 +409,9 @@ fn generate_license(
     if let Some(seats) = payload.seats {
         println!("Seats:    {}", seats);
     }
+    if let Some(days) = payload.grace_period_days {
+        println!("Grace:    {} days", days);
+    }
     println!();
     println!("┌─ LICENSE KEY ────────────────────────────────────────────────┐");
     println!("│");
@@ -276,60 +440,16 @@ fn verify_license(license_key: &str, public_key_b64: &str) {
     println!("Verifying license key...");
     println!();
 
-    // Decode public key
-    let public_key_bytes = match URL_SAFE_NO_PAD.decode(public_key_b64) {
-        Ok(bytes) => bytes,
-        Err(e) => {
-            eprintln!("Error: Invalid public key format: {}", e);
-            std::process::exit(1);
-        }
-    };
-
-    let verifying_key = match VerifyingKey::from_bytes(
-        public_key_bytes
-            .as_slice()
-            .try_into()
-            .expect("Invalid key length"),
-    ) {
-        Ok(key) => key,
-        Err(e) => {
-            eprintln!("Error: Invalid public key: {}", e);
-            std::process::exit(1);
-        }
-    };
-
-    // Remove prefix
-    let key_data = license_key.strip_prefix(LICENSE_PREFIX).unwrap_or(license_key);
-
-    // Remove dashes
-    let key_clean: String = key_data.chars().filter(|c| *c != '-').collect();
-
-    // Decode
-    let decoded = match URL_SAFE_NO_PAD.decode(&key_clean) {
+    let decoded = match decode_license_key(license_key) {
         Ok(bytes) => bytes,
         Err(e) => {
-            eprintln!("Error: Invalid license format: {}", e);
+            eprintln!("Error: {}", e);
             std::process::exit(1);
         }
     };
 
-    if decoded.len() < 65 {
-        eprintln!("Error: License key too short");
-        std::process::exit(1);
-    }
-
-    // Split payload and signature
-    let signature_start = decoded.len() - 64;
-    let payload_bytes = &decoded[..signature_start];
-    let signature_bytes = &decoded[signature_start..];
-
-    // Verify signature
-    let signature = ed25519_dalek::Signature::from_bytes(
-        signature_bytes.try_into().expect("Invalid signature length"),
-    );
-
-    match verifying_key.verify(payload_bytes, &signature) {
-        Ok(()) => {
+    match check_signature(&decoded, public_key_b64) {
+        Ok(payload_bytes) => {
             println!("✅ Signature VALID");
             println!();
 
@@ -350,15 +470,180 @@ fn verify_license(license_key: &str, public_key_b64: &str) {
                     if let Some(seats) = payload.seats {
                         println!("  Seats:    {}", seats);
                     }
+                    if let Some(days) = payload.grace_period_days {
+                        println!("  Grace:    {} days", days);
+                    }
                 }
                 Err(e) => {
                     eprintln!("Warning: Could not parse payload: {}", e);
                 }
             }
         }
-        Err(_) => {
-            println!("❌ Signature INVALID");
+        Err(e) => {
+            println!("❌ Signature INVALID ({})", e);
             std::process::exit(1);
         }
     }
 }
+
+fn export_license_report(license_key: &str, public_key_b64: &str, output: &Path) {
+    let payload = match verified_payload(license_key, public_key_b64) {
+        Ok(payload) => Some(payload),
+        Err(e) => {
+            eprintln!("Warning: License is not valid: {}", e);
+            None
+        }
+    };
+
+    let expiry = payload.as_ref().and_then(|p| parse_expiry(&p.expires));
+    // Like the app, a license inside its grace period still counts as valid
+    let grace = chrono::Duration::days(
+        payload.as_ref().and_then(|p| p.grace_period_days).unwrap_or(0).into(),
+    );
+    let license_valid = expiry.is_some_and(|expiry| Utc::now() <= expiry + grace);
+    let days_remaining = expiry.map(|expiry| (expiry - Utc::now()).num_days());
+
+    let mut report = LicenseReport {
+        license_valid,
+        customer: payload.as_ref().map(|p| p.customer.clone()),
+        company: payload.as_ref().and_then(|p| p.company.clone()),
+        product: payload
+            .as_ref()
+            .map_or_else(|| PRODUCT_ID.to_string(), |p| p.product.clone()),
+        expires: payload.as_ref().map(|p| p.expires.clone()),
+        days_remaining,
+        features: payload.as_ref().map(|p| p.features.clone()).unwrap_or_default(),
+        seats: payload.as_ref().and_then(|p| p.seats),
+        machine_fingerprint: GENERATOR_FINGERPRINT.to_string(),
+        report_generated_at: Utc::now().to_rfc3339(),
+        report_signature: String::new(),
+    };
+    report.report_signature = sign_report(&report, license_key);
+
+    let json = serde_json::to_string_pretty(&report).expect("Failed to serialize report");
+    if let Err(e) = std::fs::write(output, json) {
+        eprintln!("Error: Failed to write {}: {}", output.display(), e);
+        std::process::exit(1);
+    }
+
+    println!("License report written to {}", output.display());
+    println!("  Valid:     {}", report.license_valid);
+    println!("  Signature: {}", report.report_signature);
+}
+
+/// Decode a license key and check its signature and product
+fn verified_payload(license_key: &str, public_key_b64: &str) -> Result<LicensePayload, String> {
+    let decoded = decode_license_key(license_key)?;
+    let payload_bytes = check_signature(&decoded, public_key_b64)?;
+
+    let payload: LicensePayload = serde_json::from_slice(payload_bytes)
+        .map_err(|e| format!("Could not parse payload: {}", e))?;
+    if payload.product != PRODUCT_ID && payload.product != "*" {
+        return Err(format!("License is for a different product: {}", payload.product));
+    }
+
+    Ok(payload)
+}
+
+/// Base64-decode a license key, with or without prefix and dashes
+fn decode_license_key(license_key: &str) -> Result<Vec<u8>, String> {
+    let key_data = license_key.strip_prefix(LICENSE_PREFIX).unwrap_or(license_key);
+    let key_clean: String = key_data.chars().filter(|c| *c != '-').collect();
+    URL_SAFE_NO_PAD
+        .decode(&key_clean)
+        .map_err(|e| format!("Invalid license format: {}", e))
+}
+
+/// Verify a decoded license key and return its payload bytes
+///
+/// v2 keys name their algorithm in the first byte; v1 keys start with the
+/// payload's `{` and are Ed25519. `public_key_b64` must be of that algorithm.
+fn check_signature<'a>(decoded: &'a [u8], public_key_b64: &str) -> Result<&'a [u8], String> {
+    let (algorithm, signed) = match decoded.split_first() {
+        Some((&byte, rest)) if byte != b'{' => (
+            Algorithm::from_version_byte(byte)
+                .ok_or_else(|| format!("Unknown license version byte: {:#04x}", byte))?,
+            rest,
+        ),
+        _ => (Algorithm::Ed25519, decoded),
+    };
+
+    let public_key_bytes = URL_SAFE_NO_PAD
+        .decode(public_key_b64)
+        .map_err(|e| format!("Invalid public key format: {}", e))?;
+
+    match algorithm {
+        Algorithm::Ed25519 => {
+            let verifying_key = VerifyingKey::from_bytes(
+                public_key_bytes
+                    .as_slice()
+                    .try_into()
+                    .map_err(|_| "Public key must be 32 bytes".to_string())?,
+            )
+            .map_err(|e| format!("Invalid public key: {}", e))?;
+
+            if signed.len() <= ed25519_dalek::SIGNATURE_LENGTH {
+                return Err("License key too short".to_string());
+            }
+            let (payload_bytes, signature_bytes) =
+                signed.split_at(signed.len() - ed25519_dalek::SIGNATURE_LENGTH);
+            let signature = ed25519_dalek::Signature::from_slice(signature_bytes)
+                .map_err(|_| "Signature invalid".to_string())?;
+            verifying_key
+                .verify(payload_bytes, &signature)
+                .map_err(|_| "Signature invalid".to_string())?;
+            Ok(payload_bytes)
+        }
+        Algorithm::Rsa => {
+            let public_key = RsaPublicKey::from_public_key_der(&public_key_bytes)
+                .map_err(|e| format!("Invalid RSA public key: {}", e))?;
+
+            if signed.len() <= public_key.size() {
+                return Err("License key too short".to_string());
+            }
+            let (payload_bytes, signature_bytes) = signed.split_at(signed.len() - public_key.size());
+            let signature = rsa::pss::Signature::try_from(signature_bytes)
+                .map_err(|_| "Signature invalid".to_string())?;
+            rsa::pss::VerifyingKey::<Sha256>::new(public_key)
+                .verify(payload_bytes, &signature)
+                .map_err(|_| "Signature invalid".to_string())?;
+            Ok(payload_bytes)
+        }
+    }
+}
+
+/// Expiry instant (same rules as the app: RFC 3339 or end of YYYY-MM-DD)
+fn parse_expiry(expires: &str) -> Option<chrono::DateTime<Utc>> {
+    match chrono::DateTime::parse_from_rfc3339(expires) {
+        Ok(dt) => Some(dt.with_timezone(&Utc)),
+        Err(_) => Some(
+            chrono::NaiveDate::parse_from_str(expires, "%Y-%m-%d")
+                .ok()?
+                .and_hms_opt(23, 59, 59)?
+                .and_utc(),
+        ),
+    }
+}
+
+/// HMAC-SHA256 over the report without `report_signature` (same as the app)
+fn sign_report(report: &LicenseReport, license_key: &str) -> String {
+    let fields: BTreeMap<String, serde_json::Value> =
+        match serde_json::to_value(report).expect("Failed to serialize report") {
+            serde_json::Value::Object(fields) => fields
+                .into_iter()
+                .filter(|(key, _)| key != "report_signature")
+                .collect(),
+            _ => BTreeMap::new(),
+        };
+    let payload = serde_json::to_vec(&fields).expect("Failed to serialize report");
+
+    let mut mac = Hmac::<Sha256>::new_from_slice(license_key.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(&payload);
+
+    mac.finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
@@ -0,0 +1,55 @@
+//! Encrypt/decrypt throughput with and without payload compression
+//!
+//! Run with `cargo bench --bench crypto_compression`.
+//! The payload mimics a bincode-serialized force graph (~50KB).
+
+use amsterdam_bike_fleet_lib::crypto::{CompressionMode, SessionCrypto};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+/// Roughly the size of a 50+ node force graph layout
+const PAYLOAD_SIZE: usize = 50 * 1024;
+
+/// Build a payload with the repetition typical of serialized node data
+fn force_graph_payload() -> Vec<u8> {
+    let mut payload = Vec::with_capacity(PAYLOAD_SIZE);
+    let mut i = 0u32;
+    while payload.len() < PAYLOAD_SIZE {
+        let node = format!(
+            "DEL-{:04}|delivery|{:.3}|{:.3}|Amsterdam Centrum|#3b82f6;",
+            i,
+            (i as f64 * 7.31).sin() * 250.0,
+            (i as f64 * 3.17).cos() * 250.0,
+        );
+        payload.extend_from_slice(node.as_bytes());
+        i += 1;
+    }
+    payload.truncate(PAYLOAD_SIZE);
+    payload
+}
+
+fn bench_compression(c: &mut Criterion) {
+    let payload = force_graph_payload();
+    let session_nonce = SessionCrypto::generate_session_nonce();
+
+    let mut group = c.benchmark_group("session_crypto_50kb");
+    group.throughput(Throughput::Bytes(PAYLOAD_SIZE as u64));
+
+    for mode in [CompressionMode::None, CompressionMode::Lz4, CompressionMode::Zstd] {
+        let mut crypto = SessionCrypto::from_license("bench-license-key", &session_nonce).unwrap();
+        crypto.set_compression(mode);
+        let ciphertext = crypto.encrypt(&payload).unwrap();
+        let label = format!("{:?}", mode);
+
+        group.bench_with_input(BenchmarkId::new("encrypt", &label), &payload, |b, payload| {
+            b.iter(|| crypto.encrypt(payload).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("decrypt", &label), &ciphertext, |b, ciphertext| {
+            b.iter(|| crypto.decrypt(ciphertext).unwrap())
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_compression);
+criterion_main!(benches);
@@ -0,0 +1,295 @@
+//! Fleet Analytics Helpers
+//!
+//! # Purpose
+//! Pure statistical functions shared by the SQLite and PostgreSQL backends.
+//! The database layer fetches per-bike aggregates; the math happens here.
+//!
+//! # Why compute in Rust instead of SQL?
+//! - Same result regardless of backend (SQLite has no CORR())
+//! - Easy to unit test without a database
+//! - Keeps the algorithms inside the compiled binary
+
+use crate::models::{
+    BikeCorrelationPoint, FleetTimeSeries, HeatmapCell, HourlyUtilization, IssueRatingCorrelation,
+    TimeBucket,
+};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+/// Minimum number of data points for a meaningful correlation
+const MIN_CORRELATION_SAMPLES: usize = 3;
+
+/// Approximate length of one degree of latitude in meters
+pub const METERS_PER_DEGREE: f64 = 111_000.0;
+
+/// Most buckets one fleet time series may have (e.g. ~41 days of hours)
+pub const MAX_TIME_SERIES_BUCKETS: i64 = 1000;
+
+/// Compute the Pearson correlation coefficient of two equally sized series
+///
+/// # Returns
+/// - Coefficient in the range -1.0..=1.0
+/// - NaN if the series differ in length, have fewer than 3 points,
+///   or either series has zero variance
+pub fn pearson_correlation(xs: &[f64], ys: &[f64]) -> f64 {
+    if xs.len() != ys.len() || xs.len() < MIN_CORRELATION_SAMPLES {
+        return f64::NAN;
+    }
+
+    let n = xs.len() as f64;
+    let mean_x = xs.iter().sum::<f64>() / n;
+    let mean_y = ys.iter().sum::<f64>() / n;
+
+    let mut covariance = 0.0;
+    let mut variance_x = 0.0;
+    let mut variance_y = 0.0;
+
+    for (x, y) in xs.iter().zip(ys.iter()) {
+        let dx = x - mean_x;
+        let dy = y - mean_y;
+        covariance += dx * dy;
+        variance_x += dx * dx;
+        variance_y += dy * dy;
+    }
+
+    let denominator = (variance_x * variance_y).sqrt();
+    if denominator == 0.0 {
+        return f64::NAN;
+    }
+
+    covariance / denominator
+}
+
+/// Build an issue/rating correlation from per-bike aggregates
+///
+/// Only bikes with at least one rated delivery contribute to the sample;
+/// all bikes are still returned in `by_bike` for display.
+pub fn issue_rating_correlation(by_bike: Vec<BikeCorrelationPoint>) -> IssueRatingCorrelation {
+    let (issue_counts, ratings): (Vec<f64>, Vec<f64>) = by_bike
+        .iter()
+        .filter_map(|p| p.average_rating.map(|r| (p.issue_count as f64, r)))
+        .unzip();
+
+    IssueRatingCorrelation {
+        pearson_coefficient: pearson_correlation(&issue_counts, &ratings),
+        sample_size: issue_counts.len() as u32,
+        by_bike,
+    }
+}
+
+/// Convert a heatmap resolution in meters to a bin size in degrees
+///
+/// Uses the latitude degree length for both axes; at Amsterdam's latitude
+/// cells are therefore narrower east-west than north-south, which is fine
+/// for a density overlay.
+pub fn heatmap_bin_degrees(resolution_meters: f64) -> f64 {
+    resolution_meters / METERS_PER_DEGREE
+}
+
+/// Bin positions into a density grid
+///
+/// Each `(latitude, longitude)` is rounded to the nearest multiple of the
+/// bin size; the weight of a cell is the number of positions in it.
+/// Cells are sorted by weight (densest first).
+pub fn heatmap_cells(positions: &[(f64, f64)], resolution_meters: f64) -> Vec<HeatmapCell> {
+    let bin = heatmap_bin_degrees(resolution_meters);
+    let mut counts: HashMap<(i64, i64), u32> = HashMap::new();
+
+    for (lat, lon) in positions {
+        let key = ((lat / bin).round() as i64, (lon / bin).round() as i64);
+        *counts.entry(key).or_insert(0) += 1;
+    }
+
+    let mut cells: Vec<HeatmapCell> = counts
+        .into_iter()
+        .map(|((lat_bin, lon_bin), count)| HeatmapCell {
+            center_lat: lat_bin as f64 * bin,
+            center_lon: lon_bin as f64 * bin,
+            weight: count as f64,
+        })
+        .collect();
+
+    cells.sort_by(|a, b| {
+        b.weight
+            .total_cmp(&a.weight)
+            .then(a.center_lat.total_cmp(&b.center_lat))
+            .then(a.center_lon.total_cmp(&b.center_lon))
+    });
+    cells
+}
+
+/// Check a time series request before querying
+pub fn validate_time_series_range(
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    bucket: TimeBucket,
+) -> Result<(), String> {
+    if from >= to {
+        return Err("Time series start must be before its end".to_string());
+    }
+    let buckets = (to - bucket.start_of(from)).num_seconds() / bucket.duration().num_seconds();
+    if buckets >= MAX_TIME_SERIES_BUCKETS {
+        return Err(format!(
+            "Time series would have more than {} {} buckets",
+            MAX_TIME_SERIES_BUCKETS,
+            bucket.as_str()
+        ));
+    }
+    Ok(())
+}
+
+/// Every bucket overlapping `from..to`, oldest first
+///
+/// `measured` holds the buckets the database found activity in, keyed by
+/// `period_start`; all other buckets are zero, so charts get a gap-free axis.
+pub fn fill_time_series(
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    bucket: TimeBucket,
+    measured: Vec<FleetTimeSeries>,
+) -> Vec<FleetTimeSeries> {
+    let mut measured: HashMap<DateTime<Utc>, FleetTimeSeries> = measured
+        .into_iter()
+        .map(|entry| (entry.period_start, entry))
+        .collect();
+
+    let mut series = Vec::new();
+    let mut period_start = bucket.start_of(from);
+    while period_start < to {
+        let period_end = period_start + bucket.duration();
+        series.push(measured.remove(&period_start).unwrap_or(FleetTimeSeries {
+            period_start,
+            period_end,
+            total_deliveries: 0,
+            completed_deliveries: 0,
+            average_rating: 0.0,
+            issues_opened: 0,
+            issues_resolved: 0,
+            distance_km: 0.0,
+        }));
+        period_start = period_end;
+    }
+    series
+}
+
+/// All 24 hours of the day, from per-hour delivery counts
+///
+/// `measured` holds `(hour, active, completed, busy_bikes)` for the hours
+/// the database found deliveries in; all other hours are zero.
+pub fn hourly_utilization(
+    measured: &[(u8, u32, u32, u32)],
+    fleet_size: u32,
+) -> Vec<HourlyUtilization> {
+    (0..24)
+        .map(|hour| {
+            let (active, completed, busy_bikes) = measured
+                .iter()
+                .find(|(measured_hour, ..)| *measured_hour == hour)
+                .map_or((0, 0, 0), |&(_, active, completed, busy)| (active, completed, busy));
+            HourlyUtilization {
+                hour,
+                active_deliveries: active,
+                completed_deliveries: completed,
+                utilization_ratio: if fleet_size == 0 {
+                    0.0
+                } else {
+                    (busy_bikes as f64 / fleet_size as f64).min(1.0)
+                },
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pearson_perfect_correlation() {
+        let xs = [1.0, 2.0, 3.0, 4.0];
+        let ys = [2.0, 4.0, 6.0, 8.0];
+        assert!((pearson_correlation(&xs, &ys) - 1.0).abs() < 1e-9);
+
+        let inverse = [8.0, 6.0, 4.0, 2.0];
+        assert!((pearson_correlation(&xs, &inverse) + 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_pearson_insufficient_data() {
+        assert!(pearson_correlation(&[1.0, 2.0], &[3.0, 4.0]).is_nan());
+        assert!(pearson_correlation(&[1.0, 2.0, 3.0], &[1.0, 2.0]).is_nan());
+        assert!(pearson_correlation(&[1.0, 1.0, 1.0], &[1.0, 2.0, 3.0]).is_nan());
+    }
+
+    #[test]
+    fn test_correlation_skips_unrated_bikes() {
+        let points = vec![
+            BikeCorrelationPoint { bike_id: "BIKE-0001".to_string(), issue_count: 0, average_rating: Some(5.0) },
+            BikeCorrelationPoint { bike_id: "BIKE-0002".to_string(), issue_count: 2, average_rating: Some(4.0) },
+            BikeCorrelationPoint { bike_id: "BIKE-0003".to_string(), issue_count: 4, average_rating: Some(3.0) },
+            BikeCorrelationPoint { bike_id: "BIKE-0004".to_string(), issue_count: 9, average_rating: None },
+        ];
+
+        let result = issue_rating_correlation(points);
+        assert_eq!(result.sample_size, 3);
+        assert_eq!(result.by_bike.len(), 4);
+        assert!((result.pearson_coefficient + 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_nan_serializes_as_null() {
+        let result = issue_rating_correlation(Vec::new());
+        let json = serde_json::to_value(&result).unwrap();
+        assert!(json["pearsonCoefficient"].is_null());
+    }
+
+    #[test]
+    fn test_heatmap_groups_nearby_positions() {
+        let positions = [
+            (52.3700, 4.9000),
+            (52.37001, 4.90001), // ~1m away, same 100m cell
+            (52.3800, 4.9100),   // ~1km away, separate cell
+        ];
+
+        let cells = heatmap_cells(&positions, 100.0);
+        assert_eq!(cells.len(), 2);
+        assert_eq!(cells[0].weight, 2.0);
+        assert_eq!(cells[1].weight, 1.0);
+        assert!((cells[0].center_lat - 52.37).abs() < heatmap_bin_degrees(100.0));
+    }
+
+    #[test]
+    fn test_fill_time_series_zero_fills_weeks() {
+        let at = |s: &str| DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc);
+        // Wednesday to the Tuesday two weeks later: three ISO weeks
+        let from = at("2024-01-03T15:30:00Z");
+        let to = at("2024-01-16T08:00:00Z");
+        let measured = FleetTimeSeries {
+            period_start: at("2024-01-08T00:00:00Z"),
+            period_end: at("2024-01-15T00:00:00Z"),
+            total_deliveries: 4,
+            completed_deliveries: 2,
+            average_rating: 4.5,
+            issues_opened: 1,
+            issues_resolved: 0,
+            distance_km: 12.5,
+        };
+
+        let series = fill_time_series(from, to, TimeBucket::Week, vec![measured.clone()]);
+
+        assert_eq!(series.len(), 3);
+        assert_eq!(series[0].period_start, at("2024-01-01T00:00:00Z"));
+        assert_eq!(series[0].total_deliveries, 0);
+        assert_eq!(series[1], measured);
+        assert_eq!(series[2].period_end, at("2024-01-22T00:00:00Z"));
+    }
+
+    #[test]
+    fn test_time_series_range_limits() {
+        let from = Utc::now();
+        assert!(validate_time_series_range(from, from, TimeBucket::Day).is_err());
+        let year = from + chrono::Duration::days(365);
+        assert!(validate_time_series_range(from, year, TimeBucket::Day).is_ok());
+        assert!(validate_time_series_range(from, year, TimeBucket::Hour).is_err());
+    }
+}
@@ -0,0 +1,256 @@
+//! Secure Command Audit Trail
+//!
+//! # Purpose
+//! Persistent, tamper-evident record of every secure command, kept for
+//! compliance and forensic reporting.
+//!
+//! # Format
+//! `audit.log` in the app data directory holds one JSON `AuditEntry` per line.
+//! Each entry carries an HMAC-SHA256 (keyed with the license key) over its
+//! fields, so edited or forged lines can be detected on export.
+//!
+//! Like `LoggingMiddleware`, only the command name is recorded, never its
+//! arguments.
+
+use crate::crypto::{SecureCommand, SecureResponse};
+use crate::middleware::CommandMiddleware;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// Audit log file name inside the app data directory
+pub const AUDIT_LOG_FILE: &str = "audit.log";
+
+/// One executed secure command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub timestamp: DateTime<Utc>,
+    pub command_name: String,
+    pub success: bool,
+    /// Whole microseconds, so the signed value survives a JSON round trip
+    pub duration_us: u64,
+    pub session_id: String,
+    /// Hex HMAC-SHA256 over the fields above
+    pub hmac: String,
+}
+
+impl AuditEntry {
+    /// Create an entry signed with the license key
+    pub fn new(
+        command_name: &str,
+        success: bool,
+        duration_us: u64,
+        session_id: &str,
+        license_key: &str,
+    ) -> Self {
+        let mut entry = Self {
+            timestamp: Utc::now(),
+            command_name: command_name.to_string(),
+            success,
+            duration_us,
+            session_id: session_id.to_string(),
+            hmac: String::new(),
+        };
+        entry.hmac = entry.compute_hmac(license_key);
+        entry
+    }
+
+    /// Check that the entry was written with this license key and not modified since
+    pub fn verify_integrity(&self, license_key: &str) -> bool {
+        let Ok(expected) = hex_decode(&self.hmac) else {
+            return false;
+        };
+        self.mac(license_key).verify_slice(&expected).is_ok()
+    }
+
+    fn compute_hmac(&self, license_key: &str) -> String {
+        self.mac(license_key)
+            .finalize()
+            .into_bytes()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+
+    /// HMAC over the entry fields, `\n`-separated (command names never contain newlines)
+    fn mac(&self, license_key: &str) -> Hmac<Sha256> {
+        let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(license_key.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        let message = format!(
+            "{}\n{}\n{}\n{}\n{}",
+            self.timestamp.to_rfc3339(),
+            self.command_name,
+            self.success,
+            self.duration_us,
+            self.session_id
+        );
+        mac.update(message.as_bytes());
+        mac
+    }
+}
+
+/// Read audit entries, keeping those within `[from, to]` (bounds inclusive)
+///
+/// A missing log yields no entries. Lines that are not valid JSON entries
+/// (e.g. truncated by a crash mid-write) are skipped.
+pub fn read_entries(
+    path: &Path,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> std::io::Result<Vec<AuditEntry>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let reader = BufReader::new(std::fs::File::open(path)?);
+    let mut entries = Vec::new();
+
+    for line in reader.lines() {
+        let line = line?;
+        let Ok(entry) = serde_json::from_str::<AuditEntry>(&line) else {
+            continue;
+        };
+        if from.is_some_and(|from| entry.timestamp < from)
+            || to.is_some_and(|to| entry.timestamp > to)
+        {
+            continue;
+        }
+        entries.push(entry);
+    }
+
+    Ok(entries)
+}
+
+/// Appends every secure command to `audit.log`
+///
+/// Installed by `init_secure_session`, ahead of any registered middleware,
+/// so rejected commands are recorded too.
+///
+/// # Why a single start slot?
+/// Same as `MetricsMiddleware`: commands never overlap.
+pub struct AuditLog {
+    path: PathBuf,
+    license_key: String,
+    session_id: String,
+    started: Mutex<Option<Instant>>,
+}
+
+impl AuditLog {
+    pub fn new(path: PathBuf, license_key: &str, session_id: String) -> Self {
+        Self {
+            path,
+            license_key: license_key.to_string(),
+            session_id,
+            started: Mutex::new(None),
+        }
+    }
+
+    fn append(&self, entry: &AuditEntry) -> std::io::Result<()> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        let line = serde_json::to_string(entry)?;
+        writeln!(file, "{}", line)
+    }
+}
+
+impl CommandMiddleware for AuditLog {
+    fn name(&self) -> &'static str {
+        "audit"
+    }
+
+    fn before(&self, _cmd: &SecureCommand) -> Result<(), String> {
+        *self.started.lock().unwrap() = Some(Instant::now());
+        Ok(())
+    }
+
+    fn after(&self, cmd: &SecureCommand, response: &SecureResponse) {
+        // Keepalive pings would drown out the real commands
+        if matches!(cmd, SecureCommand::Ping { .. }) {
+            return;
+        }
+
+        let duration_us = self
+            .started
+            .lock()
+            .unwrap()
+            .take()
+            .map(|start| start.elapsed().as_micros() as u64)
+            .unwrap_or(0);
+
+        let entry = AuditEntry::new(
+            cmd.name(),
+            matches!(response, SecureResponse::Success(_)),
+            duration_us,
+            &self.session_id,
+            &self.license_key,
+        );
+
+        // Auditing must never fail the command itself
+        let _ = self.append(&entry);
+    }
+}
+
+fn hex_decode(hex: &str) -> Result<Vec<u8>, std::num::ParseIntError> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2).unwrap_or("zz"), 16))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audit_entry_integrity() {
+        let entry = AuditEntry::new("GetFleetData", true, 1500, "session-1", "license-key");
+        assert!(entry.verify_integrity("license-key"));
+        assert!(!entry.verify_integrity("other-key"));
+
+        let mut tampered = entry.clone();
+        tampered.success = false;
+        assert!(!tampered.verify_integrity("license-key"));
+
+        let mut garbled = entry;
+        garbled.hmac = "not-hex".to_string();
+        assert!(!garbled.verify_integrity("license-key"));
+    }
+
+    #[test]
+    fn test_read_entries_filters_by_date() {
+        let path = std::env::temp_dir().join(format!("audit_test_{}.log", std::process::id()));
+        let log = AuditLog::new(path.clone(), "license-key", "session-1".to_string());
+        let cmd = SecureCommand::GetIssueById { issue_id: "ISS-0001".to_string() };
+
+        log.before(&cmd).unwrap();
+        log.after(&cmd, &SecureResponse::Success(vec![]));
+
+        // Pings are not audited
+        let ping = SecureCommand::Ping { sequence: 1 };
+        log.before(&ping).unwrap();
+        log.after(&ping, &SecureResponse::Pong { sequence: 1, server_time_ms: 0 });
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(b"{truncated\n")
+            .unwrap();
+
+        let all = read_entries(&path, None, None).unwrap();
+        assert_eq!(all.len(), 1);
+        assert!(all[0].verify_integrity("license-key"));
+
+        let future = Utc::now() + chrono::Duration::hours(1);
+        assert!(read_entries(&path, Some(future), None).unwrap().is_empty());
+
+        std::fs::remove_file(&path).ok();
+    }
+}
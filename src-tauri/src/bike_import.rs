@@ -0,0 +1,175 @@
+//! Bulk Bike Import
+//!
+//! # Purpose
+//! Parses and validates the CSV used to onboard many bikes at once, so
+//! that both backends only have to insert the rows that passed.
+//!
+//! # Format
+//! A header row naming the columns `name,latitude,longitude,battery_level`
+//! (any order), then one bike per row. `battery_level` may be empty.
+//!
+//! # Validation
+//! Same field rules as `validateBikeData` in the WASM module: a non-empty
+//! name (truncated to 50 characters) and coordinates inside the Amsterdam
+//! bounding box. Battery levels must be 0-100.
+
+use crate::models::AddBikeRequest;
+use serde::Deserialize;
+
+/// (min longitude, max longitude, min latitude, max latitude), as in the WASM module
+const AMSTERDAM_BOUNDS: (f64, f64, f64, f64) = (4.7, 5.1, 52.2, 52.5);
+
+/// Longer bike names are truncated
+const MAX_NAME_LENGTH: usize = 50;
+
+const REQUIRED_COLUMNS: [&str; 4] = ["name", "latitude", "longitude", "battery_level"];
+
+/// One CSV row as written by the user, before validation
+#[derive(Debug, Deserialize)]
+struct CsvBikeRow {
+    name: String,
+    latitude: f64,
+    longitude: f64,
+    battery_level: Option<u8>,
+}
+
+/// Rows of a bike CSV that passed validation, plus one message per rejected row
+#[derive(Debug, Default)]
+pub struct ParsedBikeCsv {
+    pub bikes: Vec<AddBikeRequest>,
+    /// "Line N: reason", in file order
+    pub errors: Vec<String>,
+}
+
+/// Parse and validate a bike CSV
+///
+/// # Errors
+/// Only for an unusable file (unreadable or missing header columns); bad
+/// rows end up in `ParsedBikeCsv::errors` instead.
+pub fn parse_bike_csv(csv_content: &str) -> Result<ParsedBikeCsv, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(csv_content.as_bytes());
+
+    let headers = reader
+        .headers()
+        .map_err(|e| format!("Failed to read CSV header: {}", e))?
+        .clone();
+    let missing: Vec<&str> = REQUIRED_COLUMNS
+        .iter()
+        .filter(|column| !headers.iter().any(|h| h.eq_ignore_ascii_case(column)))
+        .copied()
+        .collect();
+    if !missing.is_empty() {
+        return Err(format!("CSV header is missing columns: {}", missing.join(", ")));
+    }
+    let headers = csv::StringRecord::from_iter(headers.iter().map(|h| h.to_ascii_lowercase()));
+
+    let mut parsed = ParsedBikeCsv::default();
+    for record in reader.records() {
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                let line = e.position().map_or(0, |p| p.line());
+                parsed.errors.push(format!("Line {}: {}", line, e));
+                continue;
+            }
+        };
+        let line = record.position().map_or(0, |p| p.line());
+
+        let row = record
+            .deserialize::<CsvBikeRow>(Some(&headers))
+            .map_err(|e| e.to_string())
+            .and_then(validate_row);
+        match row {
+            Ok(bike) => parsed.bikes.push(bike),
+            Err(reason) => parsed.errors.push(format!("Line {}: {}", line, reason)),
+        }
+    }
+
+    Ok(parsed)
+}
+
+fn validate_row(row: CsvBikeRow) -> Result<AddBikeRequest, String> {
+    let (min_lon, max_lon, min_lat, max_lat) = AMSTERDAM_BOUNDS;
+
+    if row.name.is_empty() {
+        return Err("Bike name cannot be empty".to_string());
+    }
+    if !(min_lon..=max_lon).contains(&row.longitude) {
+        return Err(format!(
+            "Longitude {} is outside Amsterdam bounds ({} - {})",
+            row.longitude, min_lon, max_lon
+        ));
+    }
+    if !(min_lat..=max_lat).contains(&row.latitude) {
+        return Err(format!(
+            "Latitude {} is outside Amsterdam bounds ({} - {})",
+            row.latitude, min_lat, max_lat
+        ));
+    }
+    if matches!(row.battery_level, Some(level) if level > 100) {
+        return Err("Battery level must be 0-100".to_string());
+    }
+
+    Ok(AddBikeRequest {
+        name: row.name.chars().take(MAX_NAME_LENGTH).collect(),
+        latitude: row.latitude,
+        longitude: row.longitude,
+        battery_level: row.battery_level,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_200_rows_identifies_error_rows() {
+        let mut csv = String::from("name,latitude,longitude,battery_level\n");
+        for i in 1..=200 {
+            let row = match i {
+                17 => ",52.37,4.89,80".to_string(),
+                42 => format!("Bike {},51.92,4.47,80", i), // Rotterdam
+                99 => format!("Bike {},52.37,4.89,150", i),
+                150 => format!("Bike {},not-a-number,4.89,80", i),
+                _ => format!("Bike {},52.37,4.89,{}", i, i % 101),
+            };
+            csv.push_str(&row);
+            csv.push('\n');
+        }
+
+        let parsed = parse_bike_csv(&csv).unwrap();
+
+        assert_eq!(parsed.bikes.len(), 196);
+        let lines: Vec<&str> = parsed
+            .errors
+            .iter()
+            .map(|e| e.split(':').next().unwrap())
+            .collect();
+        // Data row i is on line i + 1 (line 1 is the header)
+        assert_eq!(lines, vec!["Line 18", "Line 43", "Line 100", "Line 151"]);
+        assert!(parsed.errors[1].contains("outside Amsterdam bounds"));
+    }
+
+    #[test]
+    fn test_parse_accepts_empty_battery_and_truncates_names() {
+        let long_name = "x".repeat(60);
+        let csv = format!(
+            "longitude,latitude,name,battery_level\n4.89,52.37,{},\n",
+            long_name
+        );
+
+        let parsed = parse_bike_csv(&csv).unwrap();
+
+        assert!(parsed.errors.is_empty());
+        assert_eq!(parsed.bikes[0].name.len(), MAX_NAME_LENGTH);
+        assert_eq!(parsed.bikes[0].battery_level, None);
+    }
+
+    #[test]
+    fn test_parse_requires_header() {
+        let err = parse_bike_csv("Bike 1,52.37,4.89,80\n").unwrap_err();
+        assert!(err.contains("name"));
+    }
+}
@@ -0,0 +1,119 @@
+//! In-Memory Query Cache
+//!
+//! # Purpose
+//! Keeps the results of hot read queries (`get_fleet_data` runs on every
+//! animation tick) so repeated calls don't hit the database.
+//!
+//! # Eviction
+//! - Least recently used entry first once `max_entries` is reached
+//! - Entries older than `ttl` are treated as missing
+//! - Write commands call `invalidate` for the keys they affect
+
+use linked_hash_map::LinkedHashMap;
+use std::borrow::Borrow;
+use std::hash::Hash;
+use std::time::{Duration, Instant};
+
+/// Cached query result, bincode-serialized
+pub type CachedPayload = Vec<u8>;
+
+/// LRU cache with a time-to-live per entry
+#[derive(Debug)]
+pub struct QueryCache<K: Hash + Eq, V> {
+    /// Least recently used first; values carry their insertion time
+    entries: LinkedHashMap<K, (V, Instant)>,
+    max_entries: usize,
+    ttl: Duration,
+}
+
+impl<K: Hash + Eq, V: Clone> QueryCache<K, V> {
+    /// Create an empty cache
+    pub fn new(max_entries: usize, ttl: Duration) -> Self {
+        Self {
+            entries: LinkedHashMap::new(),
+            max_entries,
+            ttl,
+        }
+    }
+
+    /// Cached value of `key`, marking it most recently used
+    ///
+    /// An expired entry is dropped and reported as missing.
+    pub fn get<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let (value, inserted_at) = self.entries.get_refresh(key)?;
+        if inserted_at.elapsed() < self.ttl {
+            return Some(value.clone());
+        }
+
+        self.entries.remove(key);
+        None
+    }
+
+    /// Store a value, evicting the least recently used entry when full
+    pub fn insert(&mut self, key: K, value: V) {
+        if self.max_entries == 0 {
+            return;
+        }
+
+        self.entries.remove(&key);
+        while self.entries.len() >= self.max_entries {
+            self.entries.pop_front();
+        }
+        self.entries.insert(key, (value, Instant::now()));
+    }
+
+    /// Drop the cached value of `key`, if any
+    pub fn invalidate<Q>(&mut self, key: &Q)
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.entries.remove(key);
+    }
+
+    /// Drop every cached value
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let mut cache = QueryCache::new(2, Duration::from_secs(60));
+        cache.insert("a", 1);
+        cache.insert("b", 2);
+
+        // Reading "a" makes "b" the eviction candidate
+        assert_eq!(cache.get("a"), Some(1));
+        cache.insert("c", 3);
+
+        assert_eq!(cache.get("a"), Some(1));
+        assert_eq!(cache.get("b"), None);
+        assert_eq!(cache.get("c"), Some(3));
+    }
+
+    #[test]
+    fn test_expired_entries_are_missing() {
+        let mut cache = QueryCache::new(2, Duration::ZERO);
+        cache.insert("a", 1);
+
+        assert_eq!(cache.get("a"), None);
+    }
+
+    #[test]
+    fn test_invalidate_removes_entry() {
+        let mut cache = QueryCache::new(2, Duration::from_secs(60));
+        cache.insert("a".to_string(), 1);
+        cache.invalidate("a");
+
+        assert_eq!(cache.get("a"), None);
+    }
+}
@@ -0,0 +1,138 @@
+//! Automatic Issue Categorization
+//!
+//! # Purpose
+//! Suggests an `IssueCategory` from free-text issue descriptions so that
+//! riders and customers don't have to pick a category manually.
+//!
+//! # Approach
+//! Simple keyword scoring:
+//! 1. Split the description into lowercase words
+//! 2. Count words matching each category's keyword list (prefix match,
+//!    so "delayed" matches "delay")
+//! 3. Normalize by word count to get a 0.0 - 1.0 confidence
+//! 4. Pick the best category, falling back to `Other` below 0.3
+//!
+//! # Why keywords instead of ML?
+//! - Deterministic and explainable
+//! - No model files to ship
+//! - The same tables are mirrored in the WASM module (`suggestIssueCategory`)
+
+use crate::models::IssueCategory;
+use std::collections::HashMap;
+
+/// Minimum confidence for a keyword-based suggestion; below this we return `Other`
+pub const MIN_CATEGORY_CONFIDENCE: f64 = 0.3;
+
+/// Evaluation order for scoring (keeps tie-breaking deterministic)
+const SCORED_CATEGORIES: [IssueCategory; 5] = [
+    IssueCategory::Late,
+    IssueCategory::Damaged,
+    IssueCategory::WrongOrder,
+    IssueCategory::Rude,
+    IssueCategory::BikeProblem,
+];
+
+/// Keyword lists per category
+///
+/// Keep in sync with `CATEGORY_KEYWORDS` in wasm-lib/src/lib.rs
+fn category_keywords() -> HashMap<IssueCategory, &'static [&'static str]> {
+    let mut keywords: HashMap<IssueCategory, &'static [&'static str]> = HashMap::new();
+    keywords.insert(
+        IssueCategory::Late,
+        &["late", "wait", "delay", "minutes", "slow", "hour"],
+    );
+    keywords.insert(
+        IssueCategory::Damaged,
+        &["damage", "crushed", "spill", "leak", "broken", "cold", "squashed"],
+    );
+    keywords.insert(
+        IssueCategory::WrongOrder,
+        &["wrong", "missing", "someone", "else", "incorrect", "mixed"],
+    );
+    keywords.insert(
+        IssueCategory::Rude,
+        &["rude", "impolite", "unfriendly", "shout", "yell", "attitude", "aggressive"],
+    );
+    keywords.insert(
+        IssueCategory::BikeProblem,
+        &["tire", "tyre", "flat", "brake", "battery", "broken", "chain", "puncture"],
+    );
+    keywords
+}
+
+/// Suggest a category for an issue description
+///
+/// # Returns
+/// `(category, confidence)` where confidence is the fraction of words in the
+/// description that matched the category's keywords (0.0 - 1.0).
+/// Returns `IssueCategory::Other` when no category reaches
+/// `MIN_CATEGORY_CONFIDENCE`.
+pub fn auto_categorize_issue(description: &str) -> (IssueCategory, f64) {
+    let words: Vec<String> = description
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect();
+
+    if words.is_empty() {
+        return (IssueCategory::Other, 0.0);
+    }
+
+    let keywords = category_keywords();
+    let mut best = (IssueCategory::Other, 0.0);
+
+    for category in SCORED_CATEGORIES {
+        let category_keywords = keywords.get(&category).copied().unwrap_or(&[]);
+        let matches = words
+            .iter()
+            .filter(|w| category_keywords.iter().any(|k| w.starts_with(k)))
+            .count();
+        let score = (matches as f64 / words.len() as f64).min(1.0);
+
+        if score > best.1 {
+            best = (category, score);
+        }
+    }
+
+    if best.1 < MIN_CATEGORY_CONFIDENCE {
+        return (IssueCategory::Other, best.1);
+    }
+
+    best
+}
+
+/// Use the caller's category if given, otherwise suggest one from the description
+pub fn category_or_suggested(category: Option<IssueCategory>, description: &str) -> IssueCategory {
+    category.unwrap_or_else(|| auto_categorize_issue(description).0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_categorizes_seed_descriptions() {
+        assert_eq!(auto_categorize_issue("Delivery arrived 30 minutes late").0, IssueCategory::Late);
+        assert_eq!(auto_categorize_issue("Flat tire during delivery").0, IssueCategory::BikeProblem);
+        assert_eq!(auto_categorize_issue("Deliverer was impolite").0, IssueCategory::Rude);
+        assert_eq!(auto_categorize_issue("Crushed and damaged box").0, IssueCategory::Damaged);
+        assert_eq!(auto_categorize_issue("Received someone else's order").0, IssueCategory::WrongOrder);
+    }
+
+    #[test]
+    fn test_low_confidence_falls_back_to_other() {
+        let (category, confidence) =
+            auto_categorize_issue("General complaint about the service quality today");
+        assert_eq!(category, IssueCategory::Other);
+        assert!(confidence < MIN_CATEGORY_CONFIDENCE);
+
+        assert_eq!(auto_categorize_issue("").0, IssueCategory::Other);
+    }
+
+    #[test]
+    fn test_explicit_category_wins() {
+        let category = category_or_suggested(Some(IssueCategory::Rude), "Flat tire");
+        assert_eq!(category, IssueCategory::Rude);
+        assert_eq!(category_or_suggested(None, "Flat tire"), IssueCategory::BikeProblem);
+    }
+}
@@ -0,0 +1,80 @@
+//! Analytics Tauri Commands
+//!
+//! # Purpose
+//! Exposes management-level fleet analytics to the Angular frontend.
+//!
+//! # Why separate from issues/deliveries?
+//! - Analytics combine data from several tables
+//! - Results are aggregates, not entities
+//! - Keeps entity commands focused on CRUD-style access
+
+use crate::database::DatabaseError;
+use crate::models::{HeatmapCell, IssueCategoryStats, IssueRatingCorrelation};
+use crate::AppState;
+use tauri::State;
+
+/// Heatmap cell size used when the frontend doesn't specify one
+const DEFAULT_HEATMAP_RESOLUTION_METERS: f64 = 100.0;
+
+/// Correlate issue frequency with delivery ratings per bike
+///
+/// # Arguments
+/// - `days`: Look-back window applied to both issues and rated deliveries
+///
+/// # Returns
+/// IssueRatingCorrelation - `pearsonCoefficient` is `null` when fewer
+/// than 3 bikes have rated deliveries in the window
+#[tauri::command]
+pub fn get_issue_rating_correlation(
+    state: State<'_, AppState>,
+    days: u32,
+) -> Result<IssueRatingCorrelation, DatabaseError> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard
+        .as_ref()
+        .ok_or(DatabaseError::NotInitialized)?;
+
+    db.get_issue_rating_correlation(days)
+}
+
+/// Fleet position density for the map heatmap
+///
+/// # Arguments
+/// - `resolution_meters`: Cell size, defaults to 100m
+///
+/// # Returns
+/// Heatmap cells, densest first
+#[tauri::command]
+pub fn get_fleet_heatmap(
+    state: State<'_, AppState>,
+    resolution_meters: Option<f64>,
+) -> Result<Vec<HeatmapCell>, DatabaseError> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard
+        .as_ref()
+        .ok_or(DatabaseError::NotInitialized)?;
+
+    db.get_fleet_heatmap(resolution_meters.unwrap_or(DEFAULT_HEATMAP_RESOLUTION_METERS))
+}
+
+/// Issue categories ranked by frequency for the management overview
+///
+/// # Arguments
+/// - `limit`: Maximum number of categories
+/// - `days`: Look-back window (None = all time)
+///
+/// # Returns
+/// Vec<IssueCategoryStats> sorted by count DESC
+#[tauri::command]
+pub fn get_top_issue_categories(
+    state: State<'_, AppState>,
+    limit: u32,
+    days: Option<u32>,
+) -> Result<Vec<IssueCategoryStats>, DatabaseError> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard
+        .as_ref()
+        .ok_or(DatabaseError::NotInitialized)?;
+
+    db.get_top_issue_categories(limit, days)
+}
@@ -0,0 +1,49 @@
+//! PostgreSQL Analytics Tauri Commands
+//!
+//! Async versions of analytics commands for PostgreSQL backend.
+
+use crate::database_pg::DatabaseError;
+use crate::models::{HeatmapCell, IssueCategoryStats, IssueRatingCorrelation};
+use crate::AppState;
+use tauri::State;
+
+/// Heatmap cell size used when the frontend doesn't specify one
+const DEFAULT_HEATMAP_RESOLUTION_METERS: f64 = 100.0;
+
+/// Correlate issue frequency with delivery ratings per bike
+#[tauri::command]
+pub async fn get_issue_rating_correlation(
+    state: State<'_, AppState>,
+    days: u32,
+) -> Result<IssueRatingCorrelation, DatabaseError> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or(DatabaseError::NotInitialized)?;
+
+    db.get_issue_rating_correlation(days).await
+}
+
+/// Fleet position density for the map heatmap (defaults to 100m cells)
+#[tauri::command]
+pub async fn get_fleet_heatmap(
+    state: State<'_, AppState>,
+    resolution_meters: Option<f64>,
+) -> Result<Vec<HeatmapCell>, DatabaseError> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or(DatabaseError::NotInitialized)?;
+
+    db.get_fleet_heatmap(resolution_meters.unwrap_or(DEFAULT_HEATMAP_RESOLUTION_METERS))
+        .await
+}
+
+/// Issue categories ranked by frequency for the management overview
+#[tauri::command]
+pub async fn get_top_issue_categories(
+    state: State<'_, AppState>,
+    limit: u32,
+    days: Option<u32>,
+) -> Result<Vec<IssueCategoryStats>, DatabaseError> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or(DatabaseError::NotInitialized)?;
+
+    db.get_top_issue_categories(limit, days).await
+}
@@ -0,0 +1,200 @@
+//! Tauri commands for exporting the secure command audit trail
+
+use crate::audit::{self, AuditEntry, AUDIT_LOG_FILE};
+use crate::license::{self, EncryptedLicenseStorage};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+/// Designated export folder inside the app data directory
+const EXPORT_DIR: &str = "exports";
+
+/// Audit entry as written to the JSON export
+#[derive(Debug, Serialize)]
+struct AuditExportRow {
+    #[serde(flatten)]
+    entry: AuditEntry,
+
+    /// Only present when the export ran with the integrity check
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tampered: Option<bool>,
+}
+
+/// Export the audit trail as CSV
+///
+/// Columns: `timestamp,command_name,success,duration_ms,session_id`; the
+/// duration is fractional, from the microseconds stored in the log
+///
+/// # Arguments
+/// - `from_iso8601` / `to_iso8601`: Optional inclusive date range (RFC 3339)
+/// - `output_path`: Must be inside the home directory or the app's `exports` folder
+///
+/// # Returns
+/// Number of exported rows
+#[tauri::command]
+pub async fn export_audit_trail_csv(
+    app: AppHandle,
+    from_iso8601: Option<String>,
+    to_iso8601: Option<String>,
+    output_path: String,
+) -> Result<u32, String> {
+    let output = validate_export_path(&app, Path::new(&output_path))?;
+    let entries = read_audit_entries(&app, from_iso8601, to_iso8601)?;
+
+    let mut writer = csv::Writer::from_path(&output)
+        .map_err(|e| format!("Failed to create export file: {}", e))?;
+    writer
+        .write_record(["timestamp", "command_name", "success", "duration_ms", "session_id"])
+        .map_err(|e| e.to_string())?;
+
+    for entry in &entries {
+        writer
+            .write_record([
+                entry.timestamp.to_rfc3339(),
+                entry.command_name.clone(),
+                entry.success.to_string(),
+                (entry.duration_us as f64 / 1000.0).to_string(),
+                entry.session_id.clone(),
+            ])
+            .map_err(|e| e.to_string())?;
+    }
+    writer.flush().map_err(|e| e.to_string())?;
+
+    Ok(entries.len() as u32)
+}
+
+/// Export the audit trail as a pretty-printed JSON array
+///
+/// # Arguments
+/// - `from_iso8601` / `to_iso8601`: Optional inclusive date range (RFC 3339)
+/// - `output_path`: Must be inside the home directory or the app's `exports` folder
+/// - `integrity_check`: Verify each entry's HMAC against the stored license
+///   and mark failures with `"tampered": true`
+///
+/// # Returns
+/// Number of exported entries
+#[tauri::command]
+pub async fn export_audit_trail_json(
+    app: AppHandle,
+    from_iso8601: Option<String>,
+    to_iso8601: Option<String>,
+    output_path: String,
+    integrity_check: Option<bool>,
+) -> Result<u32, String> {
+    let output = validate_export_path(&app, Path::new(&output_path))?;
+    let entries = read_audit_entries(&app, from_iso8601, to_iso8601)?;
+
+    let license_key = if integrity_check.unwrap_or(false) {
+        Some(load_license_key(&app)?)
+    } else {
+        None
+    };
+
+    let count = entries.len() as u32;
+    let rows: Vec<AuditExportRow> = entries
+        .into_iter()
+        .map(|entry| AuditExportRow {
+            tampered: license_key
+                .as_deref()
+                .map(|key| !entry.verify_integrity(key)),
+            entry,
+        })
+        .collect();
+
+    let json = serde_json::to_string_pretty(&rows).map_err(|e| e.to_string())?;
+    std::fs::write(&output, json).map_err(|e| format!("Failed to write export file: {}", e))?;
+
+    Ok(count)
+}
+
+// ============================================================================
+// Helpers
+// ============================================================================
+
+fn app_data_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))
+}
+
+/// Read audit entries within the optional date range
+fn read_audit_entries(
+    app: &AppHandle,
+    from_iso8601: Option<String>,
+    to_iso8601: Option<String>,
+) -> Result<Vec<AuditEntry>, String> {
+    let from = from_iso8601.as_deref().map(parse_timestamp).transpose()?;
+    let to = to_iso8601.as_deref().map(parse_timestamp).transpose()?;
+
+    audit::read_entries(&app_data_dir(app)?.join(AUDIT_LOG_FILE), from, to)
+        .map_err(|e| format!("Failed to read audit log: {}", e))
+}
+
+fn parse_timestamp(value: &str) -> Result<DateTime<Utc>, String> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.with_timezone(&Utc))
+        .map_err(|e| format!("Invalid timestamp '{}': {}", value, e))
+}
+
+/// The stored license key, which also keys the audit entry HMACs
+fn load_license_key(app: &AppHandle) -> Result<String, String> {
+    let app_data_dir = app_data_dir(app)?;
+    let fingerprint = license::machine_fingerprint(&app_data_dir);
+    let storage = EncryptedLicenseStorage::new(app_data_dir);
+
+    if !storage.exists() {
+        return Err("Integrity check requires an activated license".to_string());
+    }
+
+    storage
+        .load(&fingerprint)
+        .map_err(|e| format!("Failed to load license: {}", e))
+}
+
+/// Ensure an export target is inside the home directory or the exports folder
+///
+/// # Why?
+/// The path comes from the frontend; without this check the export could
+/// overwrite arbitrary files (e.g. via `../` segments).
+fn validate_export_path(app: &AppHandle, output_path: &Path) -> Result<PathBuf, String> {
+    let export_dir = app_data_dir(app)?.join(EXPORT_DIR);
+    std::fs::create_dir_all(&export_dir).map_err(|e| e.to_string())?;
+
+    let mut allowed = vec![export_dir];
+    if let Ok(home) = app.path().home_dir() {
+        allowed.push(home);
+    }
+
+    let file_name = output_path
+        .file_name()
+        .ok_or("Export path must name a file")?;
+    let parent = match output_path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let parent = parent
+        .canonicalize()
+        .map_err(|e| format!("Export directory not found: {}", e))?;
+
+    // An existing file (or dangling link) may be a symlink out of the
+    // allowed directories, so resolve the target itself too
+    let target = parent.join(file_name);
+    let target = if target.symlink_metadata().is_ok() {
+        target
+            .canonicalize()
+            .map_err(|e| format!("Export path cannot be resolved: {}", e))?
+    } else {
+        target
+    };
+
+    let inside_allowed = allowed
+        .iter()
+        .filter_map(|dir| dir.canonicalize().ok())
+        .any(|dir| target.starts_with(dir));
+    if !inside_allowed {
+        return Err("Export path must be inside the home directory or the exports folder".to_string());
+    }
+
+    Ok(target)
+}
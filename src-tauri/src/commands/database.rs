@@ -1,33 +1,65 @@
-use crate::database::Database;
-use crate::models::DatabaseStats;
+use crate::database::{Database, SqliteConfig};
+#[cfg(feature = "pg-migration")]
+use crate::migration_tool::{migrate_sqlite_to_postgres, MigrationReport};
+#[cfg(feature = "pg-migration")]
+use crate::pg_config::DatabaseConfig;
+use crate::models::{BackupInfo, DatabaseStats, FlushResult, SeedConfig};
+use crate::slow_query_log::SlowQueryRecord;
+use crate::sync_queue::SYNC_QUEUE_FILE;
 use crate::AppState;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 use tauri::{AppHandle, Manager, State};
+use tauri_plugin_dialog::DialogExt;
+
+/// Database file name inside the app data directory
+const DATABASE_FILE: &str = "amsterdam_bike_fleet.db";
+
+/// Backup directory name inside the app data directory
+const BACKUP_DIR: &str = "backups";
+
+/// Maximum number of backups kept; older ones are pruned automatically
+const MAX_BACKUPS: usize = 10;
+
+/// Optional seed config inside the app data directory
+const SEED_CONFIG_FILE: &str = "seed_config.json";
+
+/// Directories the user picked in `choose_backup_directory`
+///
+/// `backup_database` and `restore_database` only touch files inside these
+/// or the app data directory. Kept until the app restarts.
+#[derive(Default)]
+pub struct BackupDirectories {
+    chosen: Mutex<Vec<PathBuf>>,
+}
 
 /// Initialize the SQLite database connection
 /// Creates the database file in the app data directory if it doesn't exist
+///
+/// An empty database is seeded from `seed_config.json` in the app data
+/// directory when present, otherwise with the Amsterdam demo data.
 #[tauri::command]
 pub fn init_database(
     app_handle: AppHandle,
     state: State<AppState>,
 ) -> Result<String, String> {
-    // Get the app data directory using Tauri v2 API
-    let app_data_dir = app_handle
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
-
-    // Create the directory if it doesn't exist
-    std::fs::create_dir_all(&app_data_dir).map_err(|e| e.to_string())?;
-
     // Database file path
-    let db_path = app_data_dir.join("amsterdam_bike_fleet.db");
+    let db_path = database_path(&app_handle)?;
 
     // Initialize the database
-    let db = Database::new(db_path.clone()).map_err(|e| e.to_string())?;
+    let mut db = match load_seed_config(&app_handle)? {
+        Some(seed_config) => {
+            Database::with_seed_config(db_path.clone(), SqliteConfig::default(), seed_config)
+        }
+        None => Database::new(db_path.clone(), SqliteConfig::default()),
+    }
+    .map_err(|e| e.to_string())?;
+    attach_sync_queue(&mut db, &db_path)?;
 
     // Store in app state
     let mut db_guard = state.db.lock().map_err(|e| e.to_string())?;
     *db_guard = Some(db);
+    clear_query_cache(&state);
 
     Ok(format!(
         "Database initialized successfully at: {}",
@@ -52,3 +84,535 @@ pub fn is_database_initialized(state: State<AppState>) -> Result<bool, String> {
     let db_guard = state.db.lock().map_err(|e| e.to_string())?;
     Ok(db_guard.is_some())
 }
+
+/// Seed an empty database with locale-specific demo data
+///
+/// # Arguments
+/// - `config_json`: A `SeedConfig` as JSON
+///
+/// # Errors
+/// Invalid JSON/config, or a database that already contains bikes
+#[tauri::command]
+pub fn seed_custom_data(state: State<AppState>, config_json: String) -> Result<String, String> {
+    let config: SeedConfig = serde_json::from_str(&config_json)
+        .map_err(|e| format!("Invalid seed config: {}", e))?;
+    let location_count = config.locations.len();
+
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+    let db = db_guard
+        .as_ref()
+        .ok_or("Database not initialized. Call init_database first.")?;
+
+    if db.get_stats().map_err(|e| e.to_string())?.total_bikes > 0 {
+        return Err("Database already contains data; seeding skipped".to_string());
+    }
+
+    db.seed_custom_data(config).map_err(|e| e.to_string())?;
+    clear_query_cache(&state);
+
+    Ok(format!("Seeded {} bikes from custom config", location_count))
+}
+
+/// Change journal mode, busy timeout and slow query threshold of the open
+/// connection
+///
+/// Lasts until the app restarts.
+///
+/// # Returns
+/// The resulting journal mode (`wal` or `delete`)
+#[tauri::command]
+pub fn set_sqlite_pragmas(state: State<AppState>, config: SqliteConfig) -> Result<String, String> {
+    let mut db_guard = state.db.lock().map_err(|e| e.to_string())?;
+    let db = db_guard
+        .as_mut()
+        .ok_or("Database not initialized. Call init_database first.")?;
+
+    db.set_pragmas(config).map_err(|e| e.to_string())
+}
+
+/// Get the latest full table scans and slow statements (at most 50)
+///
+/// Empty unless a slow query threshold is set, see `set_sqlite_pragmas`.
+#[tauri::command]
+pub fn get_slow_query_report(state: State<AppState>) -> Result<Vec<SlowQueryRecord>, String> {
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+
+    match db_guard.as_ref() {
+        Some(db) => Ok(db.slow_query_report()),
+        None => Err("Database not initialized. Call init_database first.".to_string()),
+    }
+}
+
+// ============================================================================
+// Backup & Restore
+// ============================================================================
+
+/// Create a timestamped backup of the database
+///
+/// Writes to `{app_data_dir}/backups/fleet_{timestamp}_{suffix}.db` and
+/// prunes the oldest backups so at most 10 are kept.
+///
+/// # Returns
+/// Path of the created backup file
+#[tauri::command]
+pub fn create_database_backup(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    custom_suffix: Option<String>,
+) -> Result<String, String> {
+    let backup_dir = backups_dir(&app_handle)?;
+    std::fs::create_dir_all(&backup_dir).map_err(|e| e.to_string())?;
+
+    let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
+    let file_name = match custom_suffix.as_deref().map(sanitize_suffix) {
+        Some(suffix) if !suffix.is_empty() => format!("fleet_{}_{}.db", timestamp, suffix),
+        _ => format!("fleet_{}.db", timestamp),
+    };
+    let backup_path = backup_dir.join(file_name);
+
+    {
+        let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+        let db = db_guard
+            .as_ref()
+            .ok_or("Database not initialized. Call init_database first.")?;
+        db.create_backup(&backup_path).map_err(|e| e.to_string())?;
+    }
+
+    prune_backups(&backup_dir)?;
+
+    Ok(backup_path.display().to_string())
+}
+
+/// List available backups, newest first
+#[tauri::command]
+pub fn list_database_backups(app_handle: AppHandle) -> Result<Vec<BackupInfo>, String> {
+    let backup_dir = backups_dir(&app_handle)?;
+    Ok(read_backups(&backup_dir)?
+        .into_iter()
+        .map(|(_, info)| info)
+        .collect())
+}
+
+/// Restore the database from a backup file
+///
+/// # Flow
+/// 1. Validate the backup lives in the backups directory
+/// 2. Close the current connection
+/// 3. Copy the backup over the main database file
+/// 4. Reopen the database (even if the copy failed, so the app stays usable)
+#[tauri::command]
+pub fn restore_from_backup(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    backup_path: String,
+) -> Result<(), String> {
+    let backup_dir = backups_dir(&app_handle)?;
+    let backup = validate_backup_path(&backup_dir, Path::new(&backup_path))?;
+    let db_path = database_path(&app_handle)?;
+
+    let mut db_guard = state.db.lock().map_err(|e| e.to_string())?;
+
+    // Drop the connection so SQLite releases the file before we overwrite it;
+    // the reopened connection keeps its pragmas
+    let sqlite_config = db_guard
+        .take()
+        .map(|db| db.sqlite_config())
+        .unwrap_or_default();
+
+    let copy_result = std::fs::copy(&backup, &db_path);
+
+    let mut db = Database::new(db_path.clone(), sqlite_config).map_err(|e| e.to_string())?;
+    attach_sync_queue(&mut db, &db_path)?;
+    *db_guard = Some(db);
+    clear_query_cache(&state);
+
+    copy_result
+        .map(|_| ())
+        .map_err(|e| format!("Failed to restore backup: {}", e))
+}
+
+// ============================================================================
+// Migration Between Machines
+// ============================================================================
+
+/// Let the user pick a directory for `backup_database`/`restore_database`
+///
+/// Opens the native folder picker. Only a directory chosen here (or the
+/// app data directory) is accepted by those commands, so the frontend
+/// cannot read or overwrite arbitrary files.
+///
+/// # Returns
+/// The chosen directory, or `None` if the user cancelled
+#[tauri::command]
+pub async fn choose_backup_directory(
+    app_handle: AppHandle,
+    directories: State<'_, BackupDirectories>,
+) -> Result<Option<String>, String> {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    app_handle
+        .dialog()
+        .file()
+        .set_title("Choose a folder for the fleet database")
+        .pick_folder(move |folder| {
+            let _ = tx.send(folder);
+        });
+
+    let Some(folder) = rx.await.map_err(|e| e.to_string())? else {
+        return Ok(None);
+    };
+    let folder = folder.into_path().map_err(|e| e.to_string())?;
+
+    directories.chosen.lock().unwrap().push(folder.clone());
+    Ok(Some(folder.display().to_string()))
+}
+
+/// Hot-copy the live database to `destination_path`, e.g. before an OS upgrade
+///
+/// Uses SQLite's online backup API, so the app keeps working meanwhile.
+/// An existing file at the destination is overwritten.
+///
+/// # Returns
+/// Size of the written copy in bytes
+#[tauri::command]
+pub fn backup_database(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    directories: State<BackupDirectories>,
+    destination_path: String,
+) -> Result<u64, String> {
+    let allowed = allowed_transfer_dirs(&app_handle, &directories)?;
+    let destination = validate_transfer_path(&allowed, Path::new(&destination_path))?;
+    if database_path(&app_handle)?.canonicalize().ok().as_ref() == Some(&destination) {
+        return Err("Cannot back up the database onto itself".to_string());
+    }
+
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+    let db = db_guard
+        .as_ref()
+        .ok_or("Database not initialized. Call init_database first.")?;
+    db.create_backup(&destination).map_err(|e| e.to_string())?;
+
+    std::fs::metadata(&destination)
+        .map(|metadata| metadata.len())
+        .map_err(|e| e.to_string())
+}
+
+/// Replace the database with a copy made by `backup_database`
+///
+/// # Flow
+/// 1. Check the source is an intact SQLite database
+/// 2. Close the current connection and copy the source over the database file
+/// 3. Reopen the database (running pending migrations) and check it again
+#[tauri::command]
+pub fn restore_database(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    directories: State<BackupDirectories>,
+    source_path: String,
+) -> Result<String, String> {
+    let allowed = allowed_transfer_dirs(&app_handle, &directories)?;
+    let source = validate_transfer_path(&allowed, Path::new(&source_path))?;
+    if !source.is_file() {
+        return Err(format!("Not a file: {}", source.display()));
+    }
+    let problems = Database::check_file_integrity(&source).map_err(|e| e.to_string())?;
+    if !problems.is_empty() {
+        return Err(format!("Source database is corrupt: {}", problems.join("; ")));
+    }
+
+    let db_path = database_path(&app_handle)?;
+    let mut db_guard = state.db.lock().map_err(|e| e.to_string())?;
+    let sqlite_config = db_guard
+        .take()
+        .map(|db| db.sqlite_config())
+        .unwrap_or_default();
+
+    let copy_result = std::fs::copy(&source, &db_path);
+
+    let mut db = Database::new(db_path.clone(), sqlite_config).map_err(|e| e.to_string())?;
+    attach_sync_queue(&mut db, &db_path)?;
+    let problems = db.integrity_check().map_err(|e| e.to_string());
+    *db_guard = Some(db);
+    clear_query_cache(&state);
+
+    copy_result.map_err(|e| format!("Failed to restore database: {}", e))?;
+    match problems? {
+        problems if problems.is_empty() => {
+            Ok(format!("Database restored from {}", source.display()))
+        }
+        problems => Err(format!("Restored database is corrupt: {}", problems.join("; "))),
+    }
+}
+
+/// Replay writes queued while the database was unavailable
+///
+/// Writes are replayed in the order they were queued. Applied ones leave
+/// the queue; failed ones stay for the next flush until they are stuck
+/// (see `FlushResult::stuck`).
+#[tauri::command]
+pub fn flush_sync_queue(state: State<AppState>) -> Result<FlushResult, String> {
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+
+    match db_guard.as_ref() {
+        Some(db) => {
+            let result = db.flush_sync_queue().map_err(|e| e.to_string())?;
+            if result.applied > 0 {
+                clear_query_cache(&state);
+            }
+            Ok(result)
+        }
+        None => Err("Database not initialized. Call init_database first.".to_string()),
+    }
+}
+
+/// Copy bikes, trips, deliveries, issues and the audit log to a PostgreSQL
+/// server, for the move to an on-premise deployment
+///
+/// Rows the server already has are skipped, so this can be run again.
+/// The server must have been started once so its schema exists.
+///
+/// # Arguments
+/// - `sqlite_path`: Database file to copy, inside the app data directory or
+///   one chosen with `choose_backup_directory`; this app's database if omitted
+/// - `pg_port`: Defaults to 5432
+#[cfg(feature = "pg-migration")]
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn run_migration(
+    app_handle: AppHandle,
+    directories: State<'_, BackupDirectories>,
+    sqlite_path: Option<String>,
+    pg_host: String,
+    pg_port: Option<u16>,
+    pg_user: String,
+    pg_password: String,
+    pg_dbname: String,
+) -> Result<MigrationReport, String> {
+    let source = match sqlite_path {
+        Some(path) => {
+            let allowed = allowed_transfer_dirs(&app_handle, &directories)?;
+            validate_transfer_path(&allowed, Path::new(&path))?
+        }
+        None => database_path(&app_handle)?,
+    };
+
+    let defaults = DatabaseConfig::default();
+    let pg_config = DatabaseConfig {
+        host: pg_host,
+        port: pg_port.unwrap_or(defaults.port),
+        user: pg_user,
+        password: pg_password,
+        dbname: pg_dbname,
+        ..defaults
+    };
+
+    migrate_sqlite_to_postgres(source, pg_config)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// Helpers
+// ============================================================================
+
+/// Open the offline sync queue next to the database file
+fn attach_sync_queue(db: &mut Database, db_path: &Path) -> Result<(), String> {
+    db.attach_sync_queue(&db_path.with_file_name(SYNC_QUEUE_FILE))
+        .map_err(|e| format!("Failed to open sync queue: {}", e))
+}
+
+/// Forget cached query results after the database was swapped out or reseeded
+fn clear_query_cache(state: &AppState) {
+    if let Ok(mut cache) = state.cache.lock() {
+        cache.clear();
+    }
+}
+
+/// Resolve the database file path, creating the app data directory if needed
+fn database_path(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    // Get the app data directory using Tauri v2 API
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+
+    // Create the directory if it doesn't exist
+    std::fs::create_dir_all(&app_data_dir).map_err(|e| e.to_string())?;
+
+    Ok(app_data_dir.join(DATABASE_FILE))
+}
+
+/// Read `seed_config.json` from the app data directory, if present
+fn load_seed_config(app_handle: &AppHandle) -> Result<Option<SeedConfig>, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+
+    let path = app_data_dir.join(SEED_CONFIG_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let json = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&json)
+        .map(Some)
+        .map_err(|e| format!("Invalid {}: {}", SEED_CONFIG_FILE, e))
+}
+
+/// Resolve the backups directory (not created here)
+fn backups_dir(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+
+    Ok(app_data_dir.join(BACKUP_DIR))
+}
+
+/// Keep only characters that are safe in a file name
+fn sanitize_suffix(suffix: &str) -> String {
+    suffix
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+        .take(64)
+        .collect()
+}
+
+/// Read backup files with their modification time, newest first
+fn read_backups(backup_dir: &Path) -> Result<Vec<(std::time::SystemTime, BackupInfo)>, String> {
+    if !backup_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut backups = Vec::new();
+    for entry in std::fs::read_dir(backup_dir).map_err(|e| e.to_string())? {
+        let entry = entry.map_err(|e| e.to_string())?;
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("db") {
+            continue;
+        }
+
+        let metadata = entry.metadata().map_err(|e| e.to_string())?;
+        let modified = metadata.modified().map_err(|e| e.to_string())?;
+
+        backups.push((
+            modified,
+            BackupInfo {
+                path: path.display().to_string(),
+                size_bytes: metadata.len(),
+                created_at: chrono::DateTime::<chrono::Utc>::from(modified).to_rfc3339(),
+            },
+        ));
+    }
+
+    backups.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+    Ok(backups)
+}
+
+/// Delete the oldest backups beyond MAX_BACKUPS
+fn prune_backups(backup_dir: &Path) -> Result<(), String> {
+    for (_, info) in read_backups(backup_dir)?.into_iter().skip(MAX_BACKUPS) {
+        std::fs::remove_file(&info.path).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// The app data directory plus the directories chosen by the user
+fn allowed_transfer_dirs(
+    app_handle: &AppHandle,
+    directories: &BackupDirectories,
+) -> Result<Vec<PathBuf>, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+
+    let mut allowed = directories.chosen.lock().unwrap().clone();
+    allowed.push(app_data_dir);
+    Ok(allowed)
+}
+
+/// Ensure a backup/restore path lies inside one of `allowed_dirs`
+///
+/// The file itself need not exist yet, but its directory must. Symlinks
+/// are resolved first, so they cannot point outside the allowed directories.
+fn validate_transfer_path(allowed_dirs: &[PathBuf], path: &Path) -> Result<PathBuf, String> {
+    let resolved = match path.canonicalize() {
+        Ok(resolved) => resolved,
+        Err(_) => {
+            let file_name = path
+                .file_name()
+                .ok_or_else(|| format!("Not a file path: {}", path.display()))?;
+            let parent = path
+                .parent()
+                .filter(|parent| !parent.as_os_str().is_empty())
+                .ok_or_else(|| format!("Path must be absolute: {}", path.display()))?
+                .canonicalize()
+                .map_err(|e| format!("Directory not found: {}", e))?;
+            parent.join(file_name)
+        }
+    };
+
+    let inside = allowed_dirs
+        .iter()
+        .filter_map(|dir| dir.canonicalize().ok())
+        .any(|dir| resolved.starts_with(dir));
+    if !inside {
+        return Err(
+            "Path must be in the app data directory or a directory chosen with choose_backup_directory"
+                .to_string(),
+        );
+    }
+
+    Ok(resolved)
+}
+
+/// Ensure a restore source exists and lives inside the backups directory
+///
+/// # Why?
+/// The path comes from the frontend; without this check any file on disk
+/// could be copied over the database.
+fn validate_backup_path(backup_dir: &Path, backup_path: &Path) -> Result<PathBuf, String> {
+    let backup_dir = backup_dir
+        .canonicalize()
+        .map_err(|_| "No backups available".to_string())?;
+    let backup = backup_path
+        .canonicalize()
+        .map_err(|e| format!("Backup not found: {}", e))?;
+
+    if !backup.starts_with(&backup_dir) || !backup.is_file() {
+        return Err("Backup must be a file in the backups directory".to_string());
+    }
+
+    Ok(backup)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Empty directory under the system temp dir
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_transfer_path_must_be_in_allowed_dir() {
+        let allowed = temp_dir("transfer_allowed");
+        let other = temp_dir("transfer_other");
+
+        let allowed_dirs = vec![allowed.clone()];
+
+        let destination = validate_transfer_path(&allowed_dirs, &allowed.join("fleet.db")).unwrap();
+        assert_eq!(destination, allowed.canonicalize().unwrap().join("fleet.db"));
+
+        assert!(validate_transfer_path(&allowed_dirs, &other.join("fleet.db")).is_err());
+        assert!(validate_transfer_path(&allowed_dirs, &allowed.join("../transfer_escape.db")).is_err());
+        assert!(validate_transfer_path(&allowed_dirs, &allowed.join("missing/fleet.db")).is_err());
+        assert!(validate_transfer_path(&allowed_dirs, Path::new("fleet.db")).is_err());
+    }
+}
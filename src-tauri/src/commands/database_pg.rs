@@ -3,10 +3,37 @@
 //! This module provides async Tauri commands for PostgreSQL operations.
 //! Used when the application is built with --features postgres.
 
-use crate::database_pg::{create_shared_database, DatabaseConfig};
-use crate::models::DatabaseStats;
+use crate::database_pg::{create_shared_database, DatabaseConfig, EventSubscriber};
+use crate::models::{DatabaseStats, PoolMetrics, SeedConfig, TableStats};
 use crate::AppState;
-use tauri::State;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// Optional seed config inside the app data directory
+const SEED_CONFIG_FILE: &str = "seed_config.json";
+
+/// Tauri event carrying a `BikeStatusEvent`
+pub const BIKE_STATUS_EVENT: &str = "bike-status-changed";
+
+/// Tauri event carrying the `PoolMetrics` sampled by the monitor
+pub const POOL_METRICS_EVENT: &str = "pool-metrics-update";
+
+/// How often the pool metrics monitor samples the pool
+const POOL_METRICS_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Latest sample of the pool metrics monitor, for `health_check`
+#[derive(Default)]
+pub struct PoolMetricsMonitor {
+    pub latest: Mutex<Option<PoolMetrics>>,
+}
+
+/// Whether the bike status listener of `subscribe_bike_events` is running
+#[derive(Default)]
+pub struct BikeEventSubscription {
+    active: AtomicBool,
+}
 
 /// Initialize the PostgreSQL database connection pool
 ///
@@ -17,6 +44,10 @@ use tauri::State;
 /// - PG_PASSWORD: Database password (required)
 /// - PG_DATABASE: Database name (default: bike_fleet)
 /// - PG_POOL_SIZE: Connection pool size (default: 16)
+/// - PG_WARMUP_TIMEOUT_SECONDS: Startup pool warmup limit (default: 30)
+///
+/// An empty database is seeded from `seed_config.json` in the app data
+/// directory when present, otherwise with the Amsterdam demo data.
 ///
 /// # Example
 /// ```bash
@@ -25,9 +56,15 @@ use tauri::State;
 /// ./amsterdam-bike-fleet
 /// ```
 #[tauri::command]
-pub async fn init_database(state: State<'_, AppState>) -> Result<String, String> {
+pub async fn init_database(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
     // Get configuration from environment
-    let config = DatabaseConfig::from_env().map_err(|e| e.to_string())?;
+    let mut config = DatabaseConfig::from_env().map_err(|e| e.to_string())?;
+    if let Some(seed_config) = load_seed_config(&app_handle)? {
+        config.seed_config = seed_config;
+    }
 
     let host = config.host.clone();
     let port = config.port;
@@ -66,6 +103,170 @@ pub fn is_database_initialized(state: State<AppState>) -> Result<bool, String> {
     Ok(db_guard.is_some())
 }
 
+/// Seed an empty database with locale-specific demo data
+#[tauri::command]
+pub async fn seed_custom_data(
+    state: State<'_, AppState>,
+    config_json: String,
+) -> Result<String, String> {
+    let config: SeedConfig = serde_json::from_str(&config_json)
+        .map_err(|e| format!("Invalid seed config: {}", e))?;
+    let location_count = config.locations.len();
+
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+    let db = db_guard
+        .as_ref()
+        .ok_or("Database not initialized. Call init_database first.")?;
+
+    if db.get_stats().await.map_err(|e| e.to_string())?.total_bikes > 0 {
+        return Err("Database already contains data; seeding skipped".to_string());
+    }
+
+    db.seed_custom_data(config).await.map_err(|e| e.to_string())?;
+
+    Ok(format!("Seeded {} bikes from custom config", location_count))
+}
+
+/// Forward bike status changes to the frontend as they happen
+///
+/// Opens a dedicated LISTEN connection (configured like `init_database`)
+/// and emits a `bike-status-changed` event per change, including changes
+/// made by other instances. Does nothing while a listener is running; once
+/// its connection drops, call again to resubscribe.
+#[tauri::command]
+pub async fn subscribe_bike_events(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    if state.db.lock().map_err(|e| e.to_string())?.is_none() {
+        return Err("Database not initialized. Call init_database first.".to_string());
+    }
+    let subscription = app_handle.state::<BikeEventSubscription>();
+    if subscription.active.load(Ordering::SeqCst) {
+        return Ok(());
+    }
+
+    let config = DatabaseConfig::from_env().map_err(|e| e.to_string())?;
+    let mut subscriber = EventSubscriber::connect(&config)
+        .await
+        .map_err(|e| format!("Failed to subscribe to bike events: {}", e))?;
+
+    // A concurrent call got there first; its listener suffices
+    if subscription.active.swap(true, Ordering::SeqCst) {
+        return Ok(());
+    }
+
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        while let Some(event) = subscriber.next_event().await {
+            let _ = app_handle.emit(BIKE_STATUS_EVENT, event);
+        }
+        app_handle
+            .state::<BikeEventSubscription>()
+            .active
+            .store(false, Ordering::SeqCst);
+    });
+
+    Ok(())
+}
+
+/// Read `seed_config.json` from the app data directory, if present
+fn load_seed_config(app_handle: &AppHandle) -> Result<Option<SeedConfig>, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+
+    let path = app_data_dir.join(SEED_CONFIG_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let json = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&json)
+        .map(Some)
+        .map_err(|e| format!("Invalid {}: {}", SEED_CONFIG_FILE, e))
+}
+
+/// Pre-establish pool connections (normally done by `init_database`)
+///
+/// Capped at the pool size.
+#[tauri::command]
+pub async fn warm_up_database_pool(
+    state: State<'_, AppState>,
+    min_connections: u32,
+) -> Result<(), String> {
+    // Clone the Arc so the lock isn't held across the await
+    let db = state
+        .db
+        .lock()
+        .map_err(|e| e.to_string())?
+        .clone()
+        .ok_or("Database not initialized. Call init_database first.")?;
+
+    db.warmup(min_connections as usize)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Get connection pool usage and replication lag
+#[tauri::command]
+pub async fn get_pool_metrics(state: State<'_, AppState>) -> Result<PoolMetrics, String> {
+    // Clone the Arc so the lock isn't held across the await
+    let db = state
+        .db
+        .lock()
+        .map_err(|e| e.to_string())?
+        .clone()
+        .ok_or("Database not initialized. Call init_database first.")?;
+
+    Ok(db.pool_metrics().await)
+}
+
+/// Get scan counts and index hit rates per table, and scans per index
+///
+/// Lets operators check that queries use the indexes meant for them, such
+/// as the partial `idx_bikes_active`.
+#[tauri::command]
+pub async fn analyze_table_stats(state: State<'_, AppState>) -> Result<TableStats, String> {
+    let db = state
+        .db
+        .lock()
+        .map_err(|e| e.to_string())?
+        .clone()
+        .ok_or("Database not initialized. Call init_database first.")?;
+
+    db.table_stats().await.map_err(|e| e.to_string())
+}
+
+/// Sample the pool metrics every 30 seconds once the database is up
+///
+/// Each sample is emitted as a `pool-metrics-update` event and kept in
+/// `PoolMetricsMonitor` for `health_check`.
+pub fn spawn_pool_metrics_monitor(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(POOL_METRICS_INTERVAL);
+        loop {
+            interval.tick().await;
+
+            let db = app_handle
+                .state::<AppState>()
+                .db
+                .lock()
+                .ok()
+                .and_then(|guard| guard.clone());
+
+            if let Some(db) = db {
+                let metrics = db.pool_metrics().await;
+                if let Ok(mut latest) = app_handle.state::<PoolMetricsMonitor>().latest.lock() {
+                    *latest = Some(metrics.clone());
+                }
+                let _ = app_handle.emit(POOL_METRICS_EVENT, metrics);
+            }
+        }
+    });
+}
+
 /// Check database health and connectivity
 ///
 /// Returns:
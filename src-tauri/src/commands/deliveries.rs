@@ -15,28 +15,53 @@
 //! which encrypts all payloads.
 
 use crate::database::DatabaseError;
-use crate::models::Delivery;
+use crate::models::{
+    CreateDeliveryRequest, Delivery, DeliveryPage, DeliverySort, DeliveryStatus, SortDirection,
+    Waypoint, DEFAULT_PAGE_SIZE,
+};
+use crate::csv_export;
+use crate::sync_queue::SyncOperation;
 use crate::AppState;
-use tauri::State;
+use chrono::{DateTime, Utc};
+use tauri::{AppHandle, State};
 
 /// Get all deliveries with optional filtering
 ///
 /// # Arguments
 /// - `bike_id`: Filter by deliverer (optional)
 /// - `status`: Filter by status: "completed", "ongoing", "upcoming" (optional)
+/// - `from_ts`/`to_ts`: Only deliveries created in this RFC 3339 window,
+///   inclusive (optional)
+/// - `sort_by`: "created_at" (default), "completed_at", "status", "rating" or
+///   "priority" (optional)
+/// - `sort_dir`: "asc" or "desc"; defaults to newest or highest first for
+///   "created_at" and "rating", ascending otherwise (optional)
+/// - `cursor`: `nextCursor` of the previous page (optional)
+/// - `limit`: Page size, 1-500 (default 50)
 ///
 /// # Returns
-/// Vec<Delivery> - List of deliveries matching filters, sorted by created_at DESC
+/// DeliveryPage - One page of deliveries matching filters, in the requested
+/// order, with the total count across pages
 ///
 /// # Why optional filters?
 /// - Flexibility: UI can show all deliveries or filtered view
 /// - Efficiency: Database-level filtering is faster than client-side
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub fn get_deliveries(
     state: State<'_, AppState>,
     bike_id: Option<String>,
     status: Option<String>,
-) -> Result<Vec<Delivery>, DatabaseError> {
+    from_ts: Option<String>,
+    to_ts: Option<String>,
+    sort_by: Option<DeliverySort>,
+    sort_dir: Option<SortDirection>,
+    cursor: Option<String>,
+    limit: Option<u32>,
+) -> Result<DeliveryPage, DatabaseError> {
+    let from = parse_timestamp_arg(from_ts.as_deref())?;
+    let to = parse_timestamp_arg(to_ts.as_deref())?;
+
     let db_guard = state.db.lock().unwrap();
     let db = db_guard
         .as_ref()
@@ -45,6 +70,12 @@ pub fn get_deliveries(
     db.get_deliveries(
         bike_id.as_deref(),
         status.as_deref(),
+        from,
+        to,
+        sort_by,
+        sort_dir,
+        cursor.as_deref(),
+        limit.unwrap_or(DEFAULT_PAGE_SIZE),
     )
 }
 
@@ -84,3 +115,145 @@ pub fn get_deliveries_for_bike(
 
     db.get_deliveries_by_bike(&bike_id)
 }
+
+/// Create a new upcoming delivery
+///
+/// # Returns
+/// The stored delivery, with its generated ID
+#[tauri::command]
+pub fn create_delivery(
+    state: State<'_, AppState>,
+    request: CreateDeliveryRequest,
+) -> Result<Delivery, DatabaseError> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard
+        .as_ref()
+        .ok_or(DatabaseError::NotInitialized)?;
+
+    db.queued_write(
+        || db.create_delivery(&request),
+        &SyncOperation::CreateDelivery(request.clone()),
+    )
+}
+
+/// Move a delivery to its next status
+///
+/// # Arguments
+/// - `delivery_id`: Delivery to update
+/// - `status`: "ongoing" (from upcoming) or "completed" (from ongoing)
+/// - `rating`: Customer score 1-5, only when completing (optional)
+///
+/// # Returns
+/// The updated delivery; `completedAt` is set when completing
+#[tauri::command]
+pub fn update_delivery_status(
+    state: State<'_, AppState>,
+    delivery_id: String,
+    status: DeliveryStatus,
+    rating: Option<u8>,
+) -> Result<Delivery, DatabaseError> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard
+        .as_ref()
+        .ok_or(DatabaseError::NotInitialized)?;
+
+    db.queued_write(
+        || db.update_delivery_status(&delivery_id, status.clone(), rating),
+        &SyncOperation::UpdateDeliveryStatus {
+            delivery_id: delivery_id.clone(),
+            status: status.clone(),
+            rating,
+        },
+    )
+}
+
+/// Delete a delivery
+///
+/// # Arguments
+/// - `delivery_id`: Delivery to delete
+/// - `cascade`: Detach associated issues (they become standalone) instead
+///   of refusing the deletion
+#[tauri::command]
+pub fn delete_delivery(
+    state: State<'_, AppState>,
+    delivery_id: String,
+    cascade: bool,
+) -> Result<(), DatabaseError> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard
+        .as_ref()
+        .ok_or(DatabaseError::NotInitialized)?;
+
+    db.delete_delivery(&delivery_id, cascade)
+}
+
+/// Append a GPS point to a delivery's route
+///
+/// # Returns
+/// ID of the new waypoint
+#[tauri::command]
+pub fn record_waypoint(
+    state: State<'_, AppState>,
+    delivery_id: String,
+    lat: f64,
+    lon: f64,
+) -> Result<String, DatabaseError> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard
+        .as_ref()
+        .ok_or(DatabaseError::NotInitialized)?;
+
+    db.add_waypoint(&delivery_id, lat, lon)
+}
+
+/// Get the recorded route of a delivery, oldest waypoint first
+#[tauri::command]
+pub fn get_delivery_route(
+    state: State<'_, AppState>,
+    delivery_id: String,
+) -> Result<Vec<Waypoint>, DatabaseError> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard
+        .as_ref()
+        .ok_or(DatabaseError::NotInitialized)?;
+
+    db.get_waypoints(&delivery_id)
+}
+
+
+/// Export deliveries created in the optional RFC 3339 window as CSV
+///
+/// Columns follow `Delivery`; rows are oldest first. Requires the "export"
+/// license feature.
+#[tauri::command]
+pub fn export_deliveries_csv(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<String, String> {
+    super::license::require_feature(&app_handle, "export")?;
+    let from = parse_timestamp_arg(from.as_deref()).map_err(|e| e.to_string())?;
+    let to = parse_timestamp_arg(to.as_deref()).map_err(|e| e.to_string())?;
+
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+    let db = db_guard
+        .as_ref()
+        .ok_or_else(|| DatabaseError::NotInitialized.to_string())?;
+
+    let deliveries = db.get_deliveries_in_range(from, to).map_err(|e| e.to_string())?;
+    csv_export::deliveries_csv(&deliveries)
+}
+
+/// Parse an optional RFC 3339 timestamp command argument as UTC
+pub(crate) fn parse_timestamp_arg(
+    value: Option<&str>,
+) -> Result<Option<DateTime<Utc>>, DatabaseError> {
+    value
+        .map(|s| {
+            DateTime::parse_from_rfc3339(s)
+                .map(|at| at.with_timezone(&Utc))
+                .map_err(|e| DatabaseError::InvalidData(format!("Invalid timestamp {}: {}", s, e)))
+        })
+        .transpose()
+}
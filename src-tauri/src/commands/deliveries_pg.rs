@@ -3,21 +3,46 @@
 //! Async versions of delivery commands for PostgreSQL backend.
 
 use crate::database_pg::DatabaseError;
-use crate::models::Delivery;
+use crate::models::{
+    CreateDeliveryRequest, Delivery, DeliveryPage, DeliverySort, DeliveryStatus, SortDirection,
+    Waypoint, DEFAULT_PAGE_SIZE,
+};
+use crate::csv_export;
 use crate::AppState;
-use tauri::State;
+use chrono::{DateTime, Utc};
+use tauri::{AppHandle, State};
 
-/// Get all deliveries with optional filtering
+/// Get one page of deliveries with optional filtering (default page size 50)
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn get_deliveries(
     state: State<'_, AppState>,
     bike_id: Option<String>,
     status: Option<String>,
-) -> Result<Vec<Delivery>, DatabaseError> {
+    from_ts: Option<String>,
+    to_ts: Option<String>,
+    sort_by: Option<DeliverySort>,
+    sort_dir: Option<SortDirection>,
+    cursor: Option<String>,
+    limit: Option<u32>,
+) -> Result<DeliveryPage, DatabaseError> {
+    let from = parse_timestamp_arg(from_ts.as_deref())?;
+    let to = parse_timestamp_arg(to_ts.as_deref())?;
+
     let db_guard = state.db.lock().unwrap();
     let db = db_guard.as_ref().ok_or(DatabaseError::NotInitialized)?;
 
-    db.get_deliveries(bike_id.as_deref(), status.as_deref()).await
+    db.get_deliveries(
+        bike_id.as_deref(),
+        status.as_deref(),
+        from,
+        to,
+        sort_by,
+        sort_dir,
+        cursor.as_deref(),
+        limit.unwrap_or(DEFAULT_PAGE_SIZE),
+    )
+    .await
 }
 
 /// Get a single delivery by ID
@@ -43,3 +68,105 @@ pub async fn get_deliveries_for_bike(
 
     db.get_deliveries_by_bike(&bike_id).await
 }
+
+/// Create a new upcoming delivery
+#[tauri::command]
+pub async fn create_delivery(
+    state: State<'_, AppState>,
+    request: CreateDeliveryRequest,
+) -> Result<Delivery, DatabaseError> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or(DatabaseError::NotInitialized)?;
+
+    db.create_delivery(&request).await
+}
+
+/// Move a delivery to its next status, optionally rating a completed one
+#[tauri::command]
+pub async fn update_delivery_status(
+    state: State<'_, AppState>,
+    delivery_id: String,
+    status: DeliveryStatus,
+    rating: Option<u8>,
+) -> Result<Delivery, DatabaseError> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or(DatabaseError::NotInitialized)?;
+
+    db.update_delivery_status(&delivery_id, status, rating).await
+}
+
+/// Delete a delivery; `cascade` detaches associated issues instead of refusing
+#[tauri::command]
+pub async fn delete_delivery(
+    state: State<'_, AppState>,
+    delivery_id: String,
+    cascade: bool,
+) -> Result<(), DatabaseError> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or(DatabaseError::NotInitialized)?;
+
+    db.delete_delivery(&delivery_id, cascade).await
+}
+
+/// Append a GPS point to a delivery's route, returning the waypoint ID
+#[tauri::command]
+pub async fn record_waypoint(
+    state: State<'_, AppState>,
+    delivery_id: String,
+    lat: f64,
+    lon: f64,
+) -> Result<String, DatabaseError> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or(DatabaseError::NotInitialized)?;
+
+    db.add_waypoint(&delivery_id, lat, lon).await
+}
+
+/// Get the recorded route of a delivery, oldest waypoint first
+#[tauri::command]
+pub async fn get_delivery_route(
+    state: State<'_, AppState>,
+    delivery_id: String,
+) -> Result<Vec<Waypoint>, DatabaseError> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or(DatabaseError::NotInitialized)?;
+
+    db.get_waypoints(&delivery_id).await
+}
+
+
+/// Export deliveries created in the optional RFC 3339 window as CSV
+///
+/// Requires the "export" license feature.
+#[tauri::command]
+pub async fn export_deliveries_csv(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<String, String> {
+    super::license::require_feature(&app_handle, "export")?;
+    let from = parse_timestamp_arg(from.as_deref()).map_err(|e| e.to_string())?;
+    let to = parse_timestamp_arg(to.as_deref()).map_err(|e| e.to_string())?;
+
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+    let db = db_guard
+        .as_ref()
+        .ok_or_else(|| DatabaseError::NotInitialized.to_string())?;
+
+    let deliveries = db.get_deliveries_in_range(from, to).await.map_err(|e| e.to_string())?;
+    csv_export::deliveries_csv(&deliveries)
+}
+
+/// Parse an optional RFC 3339 timestamp command argument as UTC
+pub(crate) fn parse_timestamp_arg(
+    value: Option<&str>,
+) -> Result<Option<DateTime<Utc>>, DatabaseError> {
+    value
+        .map(|s| {
+            DateTime::parse_from_rfc3339(s)
+                .map(|at| at.with_timezone(&Utc))
+                .map_err(|e| DatabaseError::InvalidData(format!("Invalid timestamp {}: {}", s, e)))
+        })
+        .transpose()
+}
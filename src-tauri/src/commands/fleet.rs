@@ -1,14 +1,103 @@
-use crate::models::{AddBikeRequest, Bike, BikeStatus, FleetStats, UpdateBikeStatusRequest};
+use crate::models::{
+    AddBikeRequest, AuditLogEntry, BatteryAlertSummary, Bike, BikeRetiredEvent, BikeStatistics,
+    BikeStatus, BikeSort, BikesByStatusResult, FleetEvent, FleetStats, FleetTimeSeries, HourlyUtilization, ImportResult,
+    RestaurantStats, SearchResults, SortDirection, TimeBucket, UpdateBikeStatusEvent,
+    UpdateBikeStatusRequest,
+    DEFAULT_LOW_BATTERY_THRESHOLD, DEFAULT_PAGE_SIZE,
+};
+use crate::bike_import::parse_bike_csv;
+use crate::cache::{CachedPayload, QueryCache};
+use crate::csv_export;
+use crate::sync_queue::SyncOperation;
 use crate::AppState;
-use tauri::State;
+use std::sync::Mutex;
+use std::time::Duration;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// How often expired reservations are released
+const RESERVATION_SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Query cache key of `get_fleet_data`
+const FLEET_DATA_CACHE_KEY: &str = "fleet_data";
+
+/// Tauri event carrying an `UpdateBikeStatusEvent`
+pub const BIKE_STATUS_CHANGED_EVENT: &str = "bike-status-changed";
+
+/// Tauri event carrying the added `Bike`
+pub const BIKE_ADDED_EVENT: &str = "bike-added";
+
+/// Tauri event carrying a `BikeRetiredEvent`
+pub const BIKE_RETIRED_EVENT: &str = "bike-retired";
+
+/// Where fleet change events go
+///
+/// `AppHandle` broadcasts them to all windows, so the frontend can react to
+/// writes instead of polling `get_fleet_data`; tests record them instead.
+pub(crate) trait FleetEvents {
+    fn emit_fleet_event<S: Serialize + Clone>(&self, event: &str, payload: S);
+}
+
+impl FleetEvents for AppHandle {
+    fn emit_fleet_event<S: Serialize + Clone>(&self, event: &str, payload: S) {
+        // The write has already succeeded; a window that misses the event
+        // catches up on its next fetch
+        let _ = self.emit(event, payload);
+    }
+}
+
+fn emit_bike_status_changed(events: &impl FleetEvents, bike_id: &str, new_status: BikeStatus) {
+    events.emit_fleet_event(
+        BIKE_STATUS_CHANGED_EVENT,
+        UpdateBikeStatusEvent {
+            bike_id: bike_id.to_string(),
+            new_status,
+            timestamp: chrono::Utc::now(),
+        },
+    );
+}
+
+fn emit_bike_added(events: &impl FleetEvents, bike: &Bike) {
+    events.emit_fleet_event(BIKE_ADDED_EVENT, bike);
+}
+
+fn emit_bike_retired(events: &impl FleetEvents, bike_id: &str) {
+    events.emit_fleet_event(
+        BIKE_RETIRED_EVENT,
+        BikeRetiredEvent {
+            bike_id: bike_id.to_string(),
+            timestamp: chrono::Utc::now(),
+        },
+    );
+}
+
+/// Parse an RFC 3339 command argument as UTC
+fn parse_timestamp(value: &str) -> Result<chrono::DateTime<chrono::Utc>, String> {
+    chrono::DateTime::parse_from_rfc3339(value)
+        .map(|at| at.with_timezone(&chrono::Utc))
+        .map_err(|e| format!("Invalid timestamp {}: {}", value, e))
+}
 
 /// Get all fleet data including bikes and statistics
+///
+/// Called on every animation tick, so results are served from the query
+/// cache until they expire or a write command invalidates them. Only the
+/// default order (by name) is cached; `sort_by`/`sort_dir` query directly.
 #[tauri::command]
-pub fn get_fleet_data(state: State<AppState>) -> Result<Vec<Bike>, String> {
+pub fn get_fleet_data(
+    state: State<AppState>,
+    sort_by: Option<BikeSort>,
+    sort_dir: Option<SortDirection>,
+) -> Result<Vec<Bike>, String> {
     let db_guard = state.db.lock().map_err(|e| e.to_string())?;
 
     match db_guard.as_ref() {
-        Some(db) => db.get_all_bikes().map_err(|e| e.to_string()),
+        Some(db) if sort_by.is_none() && sort_dir.is_none() => {
+            cached_fleet_data(&state.cache, || {
+                db.get_all_bikes(None, None).map_err(|e| e.to_string())
+            })
+        }
+        Some(db) => db.get_all_bikes(sort_by, sort_dir).map_err(|e| e.to_string()),
         None => {
             // Return mock data if database is not initialized
             Ok(generate_mock_fleet())
@@ -16,6 +105,33 @@ pub fn get_fleet_data(state: State<AppState>) -> Result<Vec<Bike>, String> {
     }
 }
 
+/// Fleet data from the cache, or from `load` (which is then cached) on a miss
+fn cached_fleet_data(
+    cache: &Mutex<QueryCache<String, CachedPayload>>,
+    load: impl FnOnce() -> Result<Vec<Bike>, String>,
+) -> Result<Vec<Bike>, String> {
+    let cached = cache.lock().map_err(|e| e.to_string())?.get(FLEET_DATA_CACHE_KEY);
+    if let Some(bikes) = cached.and_then(|payload| bincode::deserialize(&payload).ok()) {
+        return Ok(bikes);
+    }
+
+    let bikes = load()?;
+    let payload = bincode::serialize(&bikes).map_err(|e| e.to_string())?;
+    cache
+        .lock()
+        .map_err(|e| e.to_string())?
+        .insert(FLEET_DATA_CACHE_KEY.to_string(), payload);
+
+    Ok(bikes)
+}
+
+/// Drop the cached fleet data after a write to the bikes table
+pub(crate) fn invalidate_fleet_data(cache: &Mutex<QueryCache<String, CachedPayload>>) {
+    if let Ok(mut cache) = cache.lock() {
+        cache.invalidate(FLEET_DATA_CACHE_KEY);
+    }
+}
+
 /// Get a specific bike by ID
 #[tauri::command]
 pub fn get_bike_by_id(bike_id: String, state: State<AppState>) -> Result<Option<Bike>, String> {
@@ -31,12 +147,56 @@ pub fn get_bike_by_id(bike_id: String, state: State<AppState>) -> Result<Option<
     }
 }
 
-/// Add a new bike to the fleet
+/// Get all bikes with a given status, plus their count
+///
+/// # Arguments
+/// - `status`: One of `available`, `in_use`, `maintenance`, `charging`, `offline`, `reserved`
 #[tauri::command]
-pub fn add_bike(request: AddBikeRequest, state: State<AppState>) -> Result<Bike, String> {
+pub fn get_bikes_by_status(
+    status: String,
+    state: State<AppState>,
+) -> Result<BikesByStatusResult, String> {
+    let status = parse_bike_status(&status)?;
     let db_guard = state.db.lock().map_err(|e| e.to_string())?;
 
     match db_guard.as_ref() {
+        Some(db) => db.get_bikes_by_status(&status).map_err(|e| e.to_string()),
+        None => Err("Database not initialized. Call init_database first.".to_string()),
+    }
+}
+
+/// Get all bikes matching any of the given statuses
+#[tauri::command]
+pub fn get_bikes_by_multiple_statuses(
+    statuses: Vec<String>,
+    state: State<AppState>,
+) -> Result<Vec<Bike>, String> {
+    let statuses = statuses
+        .iter()
+        .map(|s| parse_bike_status(s))
+        .collect::<Result<Vec<_>, _>>()?;
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+
+    match db_guard.as_ref() {
+        Some(db) => db
+            .get_bikes_by_multiple_statuses(&statuses)
+            .map_err(|e| e.to_string()),
+        None => Err("Database not initialized. Call init_database first.".to_string()),
+    }
+}
+
+/// Add a new bike to the fleet
+///
+/// Emits `bike-added` with the new bike.
+#[tauri::command]
+pub fn add_bike(
+    app_handle: AppHandle,
+    request: AddBikeRequest,
+    state: State<AppState>,
+) -> Result<Bike, String> {
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+
+    let bike = match db_guard.as_ref() {
         Some(db) => db
             .add_bike(
                 &request.name,
@@ -44,14 +204,49 @@ pub fn add_bike(request: AddBikeRequest, state: State<AppState>) -> Result<Bike,
                 request.longitude,
                 request.battery_level,
             )
-            .map_err(|e| e.to_string()),
+            .map_err(|e| e.to_string())?,
+        None => return Err("Database not initialized. Call init_database first.".to_string()),
+    };
+
+    invalidate_fleet_data(&state.cache);
+    emit_bike_added(&app_handle, &bike);
+    Ok(bike)
+}
+
+/// Import bikes from CSV
+///
+/// # Format
+/// Header `name,latitude,longitude,battery_level`, one bike per row.
+/// Invalid rows are skipped and reported in `errors`; the valid rows are
+/// added in a single transaction.
+#[tauri::command]
+pub fn import_bikes_from_csv(
+    csv_content: String,
+    state: State<AppState>,
+) -> Result<ImportResult, String> {
+    let parsed = parse_bike_csv(&csv_content)?;
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+
+    match db_guard.as_ref() {
+        Some(db) => {
+            let imported = db.import_bikes(&parsed.bikes).map_err(|e| e.to_string())?;
+            invalidate_fleet_data(&state.cache);
+            Ok(ImportResult {
+                imported,
+                skipped: parsed.errors.len() as u32,
+                errors: parsed.errors,
+            })
+        }
         None => Err("Database not initialized. Call init_database first.".to_string()),
     }
 }
 
 /// Update bike status
+///
+/// Emits `bike-status-changed` with an `UpdateBikeStatusEvent`.
 #[tauri::command]
 pub fn update_bike_status(
+    app_handle: AppHandle,
     request: UpdateBikeStatusRequest,
     state: State<AppState>,
 ) -> Result<(), String> {
@@ -59,18 +254,429 @@ pub fn update_bike_status(
 
     match db_guard.as_ref() {
         Some(db) => db
-            .update_bike_status(
-                &request.bike_id,
-                &request.status,
-                request.latitude,
-                request.longitude,
-                request.battery_level,
+            .queued_write(
+                || {
+                    db.update_bike_status(
+                        &request.bike_id,
+                        &request.status,
+                        request.latitude,
+                        request.longitude,
+                        request.battery_level,
+                    )
+                },
+                &SyncOperation::UpdateBikeStatus {
+                    bike_id: request.bike_id.clone(),
+                    status: request.status.clone(),
+                    latitude: request.latitude,
+                    longitude: request.longitude,
+                    battery_level: request.battery_level,
+                },
+            )
+            .map_err(|e| e.to_string())?,
+        None => return Err("Database not initialized. Call init_database first.".to_string()),
+    }
+
+    invalidate_fleet_data(&state.cache);
+    emit_bike_status_changed(&app_handle, &request.bike_id, request.status);
+    Ok(())
+}
+
+/// Retire a bike from the fleet
+///
+/// The bike disappears from the fleet views but its deliveries and issues
+/// are kept; see `get_retired_bikes`. Emits `bike-retired`.
+#[tauri::command]
+pub fn retire_bike(
+    app_handle: AppHandle,
+    bike_id: String,
+    state: State<AppState>,
+) -> Result<(), String> {
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+
+    match db_guard.as_ref() {
+        Some(db) => db
+            .queued_write(
+                || db.retire_bike(&bike_id),
+                &SyncOperation::RetireBike {
+                    bike_id: bike_id.clone(),
+                },
             )
+            .map_err(|e| e.to_string())?,
+        None => return Err("Database not initialized. Call init_database first.".to_string()),
+    }
+
+    invalidate_fleet_data(&state.cache);
+    emit_bike_retired(&app_handle, &bike_id);
+    Ok(())
+}
+
+/// Get retired bikes, most recently retired first
+#[tauri::command]
+pub fn get_retired_bikes(state: State<AppState>) -> Result<Vec<Bike>, String> {
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+
+    match db_guard.as_ref() {
+        Some(db) => db.get_retired_bikes().map_err(|e| e.to_string()),
+        None => Err("Database not initialized. Call init_database first.".to_string()),
+    }
+}
+
+/// Start a trip for a bike
+///
+/// # Returns
+/// The trip ID to pass to `end_trip`
+#[tauri::command]
+pub fn start_trip(
+    bike_id: String,
+    start_lat: f64,
+    start_lon: f64,
+    state: State<AppState>,
+) -> Result<String, String> {
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+
+    match db_guard.as_ref() {
+        Some(db) => db
+            .start_trip(&bike_id, start_lat, start_lon)
+            .map_err(|e| e.to_string()),
+        None => Err("Database not initialized. Call init_database first.".to_string()),
+    }
+}
+
+/// End a trip, adding its distance to the bike's totals
+#[tauri::command]
+pub fn end_trip(
+    trip_id: String,
+    end_lat: f64,
+    end_lon: f64,
+    state: State<AppState>,
+) -> Result<(), String> {
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+
+    match db_guard.as_ref() {
+        Some(db) => db
+            .end_trip(&trip_id, end_lat, end_lon)
+            .map_err(|e| e.to_string())?,
+        None => return Err("Database not initialized. Call init_database first.".to_string()),
+    }
+
+    invalidate_fleet_data(&state.cache);
+    Ok(())
+}
+
+/// Reserve an available bike for a future timeslot
+///
+/// # Arguments
+/// - `expires_at_iso8601`: When the reservation lapses (RFC 3339)
+#[tauri::command]
+pub fn reserve_bike(
+    bike_id: String,
+    expires_at_iso8601: String,
+    state: State<AppState>,
+) -> Result<(), String> {
+    let expires_at = chrono::DateTime::parse_from_rfc3339(&expires_at_iso8601)
+        .map_err(|e| format!("Invalid expiry timestamp: {}", e))?
+        .with_timezone(&chrono::Utc);
+
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+
+    match db_guard.as_ref() {
+        Some(db) => db.reserve_bike(&bike_id, expires_at).map_err(|e| e.to_string())?,
+        None => return Err("Database not initialized. Call init_database first.".to_string()),
+    }
+
+    invalidate_fleet_data(&state.cache);
+    Ok(())
+}
+
+/// Cancel a bike reservation
+#[tauri::command]
+pub fn cancel_reservation(bike_id: String, state: State<AppState>) -> Result<(), String> {
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+
+    match db_guard.as_ref() {
+        Some(db) => db.cancel_reservation(&bike_id).map_err(|e| e.to_string())?,
+        None => return Err("Database not initialized. Call init_database first.".to_string()),
+    }
+
+    invalidate_fleet_data(&state.cache);
+    Ok(())
+}
+
+/// Set a bike's maintenance threshold in days, or clear it with `None`
+///
+/// Without an override the fleet-wide threshold applies.
+#[tauri::command]
+pub fn set_bike_maintenance_threshold(
+    bike_id: String,
+    threshold: Option<u32>,
+    state: State<AppState>,
+) -> Result<(), String> {
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+
+    match db_guard.as_ref() {
+        Some(db) => db
+            .set_bike_maintenance_threshold(&bike_id, threshold)
+            .map_err(|e| e.to_string())?,
+        None => return Err("Database not initialized. Call init_database first.".to_string()),
+    }
+
+    invalidate_fleet_data(&state.cache);
+    Ok(())
+}
+
+/// Get bikes due for maintenance under their effective threshold
+#[tauri::command]
+pub fn get_bikes_due_for_maintenance(state: State<AppState>) -> Result<Vec<Bike>, String> {
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+
+    match db_guard.as_ref() {
+        Some(db) => db
+            .get_bikes_due_for_maintenance(state.maintenance_threshold)
+            .map_err(|e| e.to_string()),
+        None => Err("Database not initialized. Call init_database first.".to_string()),
+    }
+}
+
+/// Get bikes whose scheduled maintenance date has passed, most overdue first
+#[tauri::command]
+pub fn get_overdue_maintenance(state: State<AppState>) -> Result<Vec<Bike>, String> {
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+
+    match db_guard.as_ref() {
+        Some(db) => db
+            .get_overdue_maintenance(chrono::Utc::now())
+            .map_err(|e| e.to_string()),
+        None => Err("Database not initialized. Call init_database first.".to_string()),
+    }
+}
+
+/// Get bikes running low on battery, lowest first
+///
+/// # Arguments
+/// - `threshold`: Battery percentage to alert below; defaults to 20
+#[tauri::command]
+pub fn get_low_battery_bikes(
+    threshold: Option<u8>,
+    state: State<AppState>,
+) -> Result<BatteryAlertSummary, String> {
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+
+    match db_guard.as_ref() {
+        Some(db) => db
+            .get_bikes_below_battery(threshold.unwrap_or(DEFAULT_LOW_BATTERY_THRESHOLD))
+            .map(BatteryAlertSummary::from_bikes)
+            .map_err(|e| e.to_string()),
+        None => Err("Database not initialized. Call init_database first.".to_string()),
+    }
+}
+
+/// Get fleet activity per hour, day or week for the dashboard charts
+///
+/// # Arguments
+/// - `from`, `to`: Time range (RFC 3339); `to` is exclusive
+#[tauri::command]
+pub fn get_fleet_time_series(
+    from: String,
+    to: String,
+    bucket: TimeBucket,
+    state: State<AppState>,
+) -> Result<Vec<FleetTimeSeries>, String> {
+    let (from, to) = (parse_timestamp(&from)?, parse_timestamp(&to)?);
+
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+
+    match db_guard.as_ref() {
+        Some(db) => db
+            .get_fleet_time_series(from, to, bucket)
+            .map_err(|e| e.to_string()),
+        None => Err("Database not initialized. Call init_database first.".to_string()),
+    }
+}
+
+/// Get delivery load per hour of the day (UTC), to find peak hours
+///
+/// # Arguments
+/// - `from`, `to`: Time range (RFC 3339); `to` is exclusive
+#[tauri::command]
+pub fn get_hourly_utilization(
+    from: String,
+    to: String,
+    state: State<AppState>,
+) -> Result<Vec<HourlyUtilization>, String> {
+    let (from, to) = (parse_timestamp(&from)?, parse_timestamp(&to)?);
+
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+
+    match db_guard.as_ref() {
+        Some(db) => db
+            .get_hourly_utilization(from, to)
+            .map_err(|e| e.to_string()),
+        None => Err("Database not initialized. Call init_database first.".to_string()),
+    }
+}
+
+/// Get delivery figures of one restaurant
+#[tauri::command]
+pub fn get_restaurant_stats(
+    restaurant_id: String,
+    state: State<AppState>,
+) -> Result<RestaurantStats, String> {
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+
+    match db_guard.as_ref() {
+        Some(db) => db
+            .get_restaurant_stats(&restaurant_id)
+            .map_err(|e| e.to_string()),
+        None => Err("Database not initialized. Call init_database first.".to_string()),
+    }
+}
+
+/// Get the restaurants with the most deliveries
+///
+/// # Arguments
+/// - `limit`: Maximum number of restaurants
+#[tauri::command]
+pub fn get_top_restaurants(
+    limit: u32,
+    state: State<AppState>,
+) -> Result<Vec<RestaurantStats>, String> {
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+
+    match db_guard.as_ref() {
+        Some(db) => db.get_top_restaurants(limit).map_err(|e| e.to_string()),
+        None => Err("Database not initialized. Call init_database first.".to_string()),
+    }
+}
+
+/// Export the fleet (retired bikes excluded) as CSV for Excel or Power BI
+///
+/// Requires the "export" license feature.
+#[tauri::command]
+pub fn export_fleet_csv(app_handle: AppHandle, state: State<AppState>) -> Result<String, String> {
+    super::license::require_feature(&app_handle, "export")?;
+
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+
+    match db_guard.as_ref() {
+        Some(db) => {
+            let bikes = db.get_all_bikes(None, None).map_err(|e| e.to_string())?;
+            csv_export::fleet_csv(&bikes)
+        }
+        None => Err("Database not initialized. Call init_database first.".to_string()),
+    }
+}
+
+/// Get recorded changes to bikes, deliveries and issues, oldest first
+///
+/// # Arguments
+/// - `table`: "bikes", "deliveries" or "issues"; all tables if omitted
+/// - `row_id`: Only changes to this row
+/// - `from`, `to`: Time range (RFC 3339); `to` is exclusive
+#[tauri::command]
+pub fn get_audit_log(
+    table: Option<String>,
+    row_id: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+    state: State<AppState>,
+) -> Result<Vec<AuditLogEntry>, String> {
+    let from = from.as_deref().map(parse_timestamp).transpose()?;
+    let to = to.as_deref().map(parse_timestamp).transpose()?;
+
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+
+    match db_guard.as_ref() {
+        Some(db) => db
+            .get_audit_log(table.as_deref(), row_id.as_deref(), from, to)
             .map_err(|e| e.to_string()),
         None => Err("Database not initialized. Call init_database first.".to_string()),
     }
 }
 
+/// Get fleet events logged after `last_id`, oldest first
+///
+/// # Arguments
+/// - `last_id`: ID of the last event already seen; 0 for the whole log
+/// - `limit`: Most events to return; defaults to 50
+#[tauri::command]
+pub fn poll_events(
+    last_id: u64,
+    limit: Option<u32>,
+    state: State<AppState>,
+) -> Result<Vec<FleetEvent>, String> {
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+
+    match db_guard.as_ref() {
+        Some(db) => db
+            .get_events_since(last_id, limit.unwrap_or(DEFAULT_PAGE_SIZE))
+            .map_err(|e| e.to_string()),
+        None => Err("Database not initialized. Call init_database first.".to_string()),
+    }
+}
+
+/// Search bikes by name and deliveries by customer or restaurant
+///
+/// # Arguments
+/// - `limit`: Most results per entity; defaults to 50
+#[tauri::command]
+pub fn search(
+    query: String,
+    limit: Option<u32>,
+    state: State<AppState>,
+) -> Result<SearchResults, String> {
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+
+    match db_guard.as_ref() {
+        Some(db) => db
+            .search(&query, limit.unwrap_or(DEFAULT_PAGE_SIZE))
+            .map_err(|e| e.to_string()),
+        None => Err("Database not initialized. Call init_database first.".to_string()),
+    }
+}
+
+/// Get the figures shown in the bike detail view
+#[tauri::command]
+pub fn get_bike_statistics(
+    bike_id: String,
+    state: State<AppState>,
+) -> Result<BikeStatistics, String> {
+    let bike = get_bike_by_id(bike_id.clone(), state.clone())?
+        .ok_or_else(|| format!("Bike not found: {}", bike_id))?;
+
+    Ok(BikeStatistics::for_bike(
+        &bike,
+        state.maintenance_threshold,
+        chrono::Utc::now(),
+    ))
+}
+
+/// Start the background task releasing expired reservations every minute
+///
+/// Ticks before `init_database` are no-ops.
+pub fn spawn_reservation_sweeper(app_handle: AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(RESERVATION_SWEEP_INTERVAL);
+
+        let state = app_handle.state::<AppState>();
+        let Ok(db_guard) = state.db.lock() else {
+            continue;
+        };
+        if let Some(db) = db_guard.as_ref() {
+            // A failed sweep is retried on the next tick
+            if let Ok(released) = db.release_expired_reservations() {
+                if released > 0 {
+                    invalidate_fleet_data(&state.cache);
+                }
+            }
+        }
+    });
+}
+
+/// Parse a bike status sent by the frontend
+fn parse_bike_status(status: &str) -> Result<BikeStatus, String> {
+    BikeStatus::from_str(status).ok_or_else(|| format!("Unknown bike status: {}", status))
+}
+
 /// Generate mock fleet data for when database is not available
 fn generate_mock_fleet() -> Vec<Bike> {
     use chrono::Utc;
@@ -103,6 +709,10 @@ fn generate_mock_fleet() -> Vec<Bike> {
             last_maintenance: None,
             total_trips: (i as u32 * 17) % 200,
             total_distance_km: (i as f64 * 12.5) % 500.0,
+            reservation_expires_at: None,
+            maintenance_threshold_override: None,
+            deleted_at: None,
+            next_maintenance_at: None,
             created_at: now,
             updated_at: now,
         })
@@ -112,7 +722,7 @@ fn generate_mock_fleet() -> Vec<Bike> {
 /// Get fleet statistics (mock implementation)
 #[tauri::command]
 pub fn get_fleet_stats(state: State<AppState>) -> Result<FleetStats, String> {
-    let bikes = get_fleet_data(state)?;
+    let bikes = get_fleet_data(state, None, None)?;
 
     let total = bikes.len() as u32;
     let available = bikes.iter().filter(|b| b.status == BikeStatus::Available).count() as u32;
@@ -120,6 +730,11 @@ pub fn get_fleet_stats(state: State<AppState>) -> Result<FleetStats, String> {
     let maintenance = bikes.iter().filter(|b| b.status == BikeStatus::Maintenance).count() as u32;
     let charging = bikes.iter().filter(|b| b.status == BikeStatus::Charging).count() as u32;
     let offline = bikes.iter().filter(|b| b.status == BikeStatus::Offline).count() as u32;
+    let reserved = bikes.iter().filter(|b| b.status == BikeStatus::Reserved).count() as u32;
+    let custom_threshold = bikes
+        .iter()
+        .filter(|b| b.maintenance_threshold_override.is_some())
+        .count() as u32;
 
     let avg_battery: f64 = bikes
         .iter()
@@ -135,7 +750,98 @@ pub fn get_fleet_stats(state: State<AppState>) -> Result<FleetStats, String> {
         bikes_in_maintenance: maintenance,
         bikes_charging: charging,
         bikes_offline: offline,
+        bikes_reserved: reserved,
         average_battery: avg_battery,
         total_trips_today: 42, // Mock value
+        bikes_with_custom_threshold: custom_threshold,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::{Cell, RefCell};
+
+    fn test_cache() -> Mutex<QueryCache<String, CachedPayload>> {
+        Mutex::new(QueryCache::new(4, Duration::from_secs(60)))
+    }
+
+    /// Records emitted events as (name, JSON payload)
+    #[derive(Default)]
+    struct RecordedEvents(RefCell<Vec<(String, serde_json::Value)>>);
+
+    impl FleetEvents for RecordedEvents {
+        fn emit_fleet_event<S: Serialize + Clone>(&self, event: &str, payload: S) {
+            let payload = serde_json::to_value(payload).unwrap();
+            self.0.borrow_mut().push((event.to_string(), payload));
+        }
+    }
+
+    #[test]
+    fn test_bike_status_change_event_payload() {
+        let events = RecordedEvents::default();
+        let before = chrono::Utc::now();
+
+        emit_bike_status_changed(&events, "BIKE-0007", BikeStatus::Maintenance);
+
+        let recorded = events.0.into_inner();
+        assert_eq!(recorded.len(), 1);
+        let (name, payload) = &recorded[0];
+        assert_eq!(name, "bike-status-changed");
+        let event: UpdateBikeStatusEvent = serde_json::from_value(payload.clone()).unwrap();
+        assert_eq!(event.bike_id, "BIKE-0007");
+        assert_eq!(event.new_status, BikeStatus::Maintenance);
+        assert!(event.timestamp >= before);
+        assert_eq!(payload["new_status"], "maintenance");
+    }
+
+    #[test]
+    fn test_bike_added_and_retired_event_payloads() {
+        let events = RecordedEvents::default();
+        let bike = generate_mock_fleet().remove(0);
+
+        emit_bike_added(&events, &bike);
+        emit_bike_retired(&events, &bike.id);
+
+        let recorded = events.0.into_inner();
+        let names: Vec<&str> = recorded.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["bike-added", "bike-retired"]);
+        assert_eq!(recorded[0].1["id"], "BIKE-0001");
+        assert_eq!(recorded[0].1["name"], bike.name.as_str());
+        let retired: BikeRetiredEvent = serde_json::from_value(recorded[1].1.clone()).unwrap();
+        assert_eq!(retired.bike_id, "BIKE-0001");
+    }
+
+    #[test]
+    fn test_rapid_fleet_data_calls_query_once() {
+        let cache = test_cache();
+        let queries = Cell::new(0);
+        let load = || {
+            queries.set(queries.get() + 1);
+            Ok(generate_mock_fleet())
+        };
+
+        let first = cached_fleet_data(&cache, load).unwrap();
+        let second = cached_fleet_data(&cache, load).unwrap();
+
+        assert_eq!(queries.get(), 1);
+        assert_eq!(first.len(), second.len());
+        assert_eq!(second[0].id, "BIKE-0001");
+    }
+
+    #[test]
+    fn test_write_invalidation_flushes_fleet_data() {
+        let cache = test_cache();
+        let queries = Cell::new(0);
+        let load = || {
+            queries.set(queries.get() + 1);
+            Ok(generate_mock_fleet())
+        };
+
+        cached_fleet_data(&cache, load).unwrap();
+        invalidate_fleet_data(&cache);
+        cached_fleet_data(&cache, load).unwrap();
+
+        assert_eq!(queries.get(), 2);
+    }
+}
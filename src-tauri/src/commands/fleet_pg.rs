@@ -2,17 +2,39 @@
 //!
 //! Async versions of fleet commands for PostgreSQL backend.
 
-use crate::models::{AddBikeRequest, Bike, BikeStatus, FleetStats, UpdateBikeStatusRequest};
+use crate::models::{
+    AddBikeRequest, AuditLogEntry, BatteryAlertSummary, Bike, BikeStatistics, BikeStatus,
+    BikeSort, BikesByStatusResult, FleetEvent, FleetStats, FleetTimeSeries, HourlyUtilization, ImportResult,
+    RestaurantStats, SearchResults, SortDirection, TimeBucket, UpdateBikeStatusRequest,
+    DEFAULT_LOW_BATTERY_THRESHOLD, DEFAULT_PAGE_SIZE,
+};
+use crate::bike_import::parse_bike_csv;
+use crate::csv_export;
 use crate::AppState;
-use tauri::State;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+/// How often expired reservations are released
+const RESERVATION_SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Parse an RFC 3339 command argument as UTC
+fn parse_timestamp(value: &str) -> Result<chrono::DateTime<chrono::Utc>, String> {
+    chrono::DateTime::parse_from_rfc3339(value)
+        .map(|at| at.with_timezone(&chrono::Utc))
+        .map_err(|e| format!("Invalid timestamp {}: {}", value, e))
+}
 
 /// Get all fleet data including bikes and statistics
 #[tauri::command]
-pub async fn get_fleet_data(state: State<'_, AppState>) -> Result<Vec<Bike>, String> {
+pub async fn get_fleet_data(
+    state: State<'_, AppState>,
+    sort_by: Option<BikeSort>,
+    sort_dir: Option<SortDirection>,
+) -> Result<Vec<Bike>, String> {
     let db_guard = state.db.lock().map_err(|e| e.to_string())?;
 
     match db_guard.as_ref() {
-        Some(db) => db.get_all_bikes().await.map_err(|e| e.to_string()),
+        Some(db) => db.get_all_bikes(sort_by, sort_dir).await.map_err(|e| e.to_string()),
         None => {
             // Return mock data if database is not initialized
             Ok(generate_mock_fleet())
@@ -38,6 +60,44 @@ pub async fn get_bike_by_id(
     }
 }
 
+/// Get all bikes with a given status, plus their count
+///
+/// # Arguments
+/// - `status`: One of `available`, `in_use`, `maintenance`, `charging`, `offline`, `reserved`
+#[tauri::command]
+pub async fn get_bikes_by_status(
+    status: String,
+    state: State<'_, AppState>,
+) -> Result<BikesByStatusResult, String> {
+    let status = parse_bike_status(&status)?;
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+
+    match db_guard.as_ref() {
+        Some(db) => db.get_bikes_by_status(&status).await.map_err(|e| e.to_string()),
+        None => Err("Database not initialized. Call init_database first.".to_string()),
+    }
+}
+
+/// Get all bikes matching any of the given statuses
+#[tauri::command]
+pub async fn get_bikes_by_multiple_statuses(
+    statuses: Vec<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<Bike>, String> {
+    let statuses = statuses
+        .iter()
+        .map(|s| parse_bike_status(s))
+        .collect::<Result<Vec<_>, _>>()?;
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+
+    match db_guard.as_ref() {
+        Some(db) => db
+            .get_bikes_by_multiple_statuses(&statuses).await
+            .map_err(|e| e.to_string()),
+        None => Err("Database not initialized. Call init_database first.".to_string()),
+    }
+}
+
 /// Add a new bike to the fleet
 #[tauri::command]
 pub async fn add_bike(
@@ -60,6 +120,34 @@ pub async fn add_bike(
     }
 }
 
+/// Import bikes from CSV
+///
+/// # Format
+/// Header `name,latitude,longitude,battery_level`, one bike per row.
+/// Invalid rows are skipped and reported in `errors`; the valid rows are
+/// added in a single transaction.
+#[tauri::command]
+pub async fn import_bikes_from_csv(
+    csv_content: String,
+    state: State<'_, AppState>,
+) -> Result<ImportResult, String> {
+    let parsed = parse_bike_csv(&csv_content)?;
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+
+    match db_guard.as_ref() {
+        Some(db) => {
+            let imported = db.import_bikes(&parsed.bikes)
+            .await.map_err(|e| e.to_string())?;
+            Ok(ImportResult {
+                imported,
+                skipped: parsed.errors.len() as u32,
+                errors: parsed.errors,
+            })
+        }
+        None => Err("Database not initialized. Call init_database first.".to_string()),
+    }
+}
+
 /// Update bike status
 #[tauri::command]
 pub async fn update_bike_status(
@@ -83,6 +171,405 @@ pub async fn update_bike_status(
     }
 }
 
+/// Retire a bike from the fleet
+///
+/// The bike disappears from the fleet views but its deliveries and issues
+/// are kept; see `get_retired_bikes`.
+#[tauri::command]
+pub async fn retire_bike(bike_id: String, state: State<'_, AppState>) -> Result<(), String> {
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+
+    match db_guard.as_ref() {
+        Some(db) => db.retire_bike(&bike_id).await.map_err(|e| e.to_string()),
+        None => Err("Database not initialized. Call init_database first.".to_string()),
+    }
+}
+
+/// Get retired bikes, most recently retired first
+#[tauri::command]
+pub async fn get_retired_bikes(state: State<'_, AppState>) -> Result<Vec<Bike>, String> {
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+
+    match db_guard.as_ref() {
+        Some(db) => db.get_retired_bikes().await.map_err(|e| e.to_string()),
+        None => Err("Database not initialized. Call init_database first.".to_string()),
+    }
+}
+
+/// Start a trip for a bike
+///
+/// # Returns
+/// The trip ID to pass to `end_trip`
+#[tauri::command]
+pub async fn start_trip(
+    bike_id: String,
+    start_lat: f64,
+    start_lon: f64,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+
+    match db_guard.as_ref() {
+        Some(db) => db
+            .start_trip(&bike_id, start_lat, start_lon)
+            .await
+            .map_err(|e| e.to_string()),
+        None => Err("Database not initialized. Call init_database first.".to_string()),
+    }
+}
+
+/// End a trip, adding its distance to the bike's totals
+#[tauri::command]
+pub async fn end_trip(
+    trip_id: String,
+    end_lat: f64,
+    end_lon: f64,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+
+    match db_guard.as_ref() {
+        Some(db) => db
+            .end_trip(&trip_id, end_lat, end_lon)
+            .await
+            .map_err(|e| e.to_string()),
+        None => Err("Database not initialized. Call init_database first.".to_string()),
+    }
+}
+
+/// Reserve an available bike for a future timeslot
+///
+/// # Arguments
+/// - `expires_at_iso8601`: When the reservation lapses (RFC 3339)
+#[tauri::command]
+pub async fn reserve_bike(
+    bike_id: String,
+    expires_at_iso8601: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let expires_at = chrono::DateTime::parse_from_rfc3339(&expires_at_iso8601)
+        .map_err(|e| format!("Invalid expiry timestamp: {}", e))?
+        .with_timezone(&chrono::Utc);
+
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+
+    match db_guard.as_ref() {
+        Some(db) => db
+            .reserve_bike(&bike_id, expires_at)
+            .await
+            .map_err(|e| e.to_string()),
+        None => Err("Database not initialized. Call init_database first.".to_string()),
+    }
+}
+
+/// Cancel a bike reservation
+#[tauri::command]
+pub async fn cancel_reservation(
+    bike_id: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+
+    match db_guard.as_ref() {
+        Some(db) => db.cancel_reservation(&bike_id).await.map_err(|e| e.to_string()),
+        None => Err("Database not initialized. Call init_database first.".to_string()),
+    }
+}
+
+/// Set a bike's maintenance threshold in days, or clear it with `None`
+///
+/// Without an override the fleet-wide threshold applies.
+#[tauri::command]
+pub async fn set_bike_maintenance_threshold(
+    bike_id: String,
+    threshold: Option<u32>,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+
+    match db_guard.as_ref() {
+        Some(db) => db
+            .set_bike_maintenance_threshold(&bike_id, threshold)
+            .await
+            .map_err(|e| e.to_string()),
+        None => Err("Database not initialized. Call init_database first.".to_string()),
+    }
+}
+
+/// Get bikes due for maintenance under their effective threshold
+#[tauri::command]
+pub async fn get_bikes_due_for_maintenance(
+    state: State<'_, AppState>,
+) -> Result<Vec<Bike>, String> {
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+
+    match db_guard.as_ref() {
+        Some(db) => db
+            .get_bikes_due_for_maintenance(state.maintenance_threshold)
+            .await
+            .map_err(|e| e.to_string()),
+        None => Err("Database not initialized. Call init_database first.".to_string()),
+    }
+}
+
+/// Get bikes whose scheduled maintenance date has passed, most overdue first
+#[tauri::command]
+pub async fn get_overdue_maintenance(
+    state: State<'_, AppState>,
+) -> Result<Vec<Bike>, String> {
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+
+    match db_guard.as_ref() {
+        Some(db) => db
+            .get_overdue_maintenance(chrono::Utc::now())
+            .await
+            .map_err(|e| e.to_string()),
+        None => Err("Database not initialized. Call init_database first.".to_string()),
+    }
+}
+
+/// Get bikes running low on battery, lowest first
+///
+/// # Arguments
+/// - `threshold`: Battery percentage to alert below; defaults to 20
+#[tauri::command]
+pub async fn get_low_battery_bikes(
+    threshold: Option<u8>,
+    state: State<'_, AppState>,
+) -> Result<BatteryAlertSummary, String> {
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+
+    match db_guard.as_ref() {
+        Some(db) => db
+            .get_bikes_below_battery(threshold.unwrap_or(DEFAULT_LOW_BATTERY_THRESHOLD))
+            .await
+            .map(BatteryAlertSummary::from_bikes)
+            .map_err(|e| e.to_string()),
+        None => Err("Database not initialized. Call init_database first.".to_string()),
+    }
+}
+
+/// Get fleet activity per hour, day or week for the dashboard charts
+///
+/// # Arguments
+/// - `from`, `to`: Time range (RFC 3339); `to` is exclusive
+#[tauri::command]
+pub async fn get_fleet_time_series(
+    from: String,
+    to: String,
+    bucket: TimeBucket,
+    state: State<'_, AppState>,
+) -> Result<Vec<FleetTimeSeries>, String> {
+    let (from, to) = (parse_timestamp(&from)?, parse_timestamp(&to)?);
+
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+
+    match db_guard.as_ref() {
+        Some(db) => db
+            .get_fleet_time_series(from, to, bucket)
+            .await
+            .map_err(|e| e.to_string()),
+        None => Err("Database not initialized. Call init_database first.".to_string()),
+    }
+}
+
+/// Get delivery load per hour of the day (UTC), to find peak hours
+///
+/// # Arguments
+/// - `from`, `to`: Time range (RFC 3339); `to` is exclusive
+#[tauri::command]
+pub async fn get_hourly_utilization(
+    from: String,
+    to: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<HourlyUtilization>, String> {
+    let (from, to) = (parse_timestamp(&from)?, parse_timestamp(&to)?);
+
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+
+    match db_guard.as_ref() {
+        Some(db) => db
+            .get_hourly_utilization(from, to)
+            .await
+            .map_err(|e| e.to_string()),
+        None => Err("Database not initialized. Call init_database first.".to_string()),
+    }
+}
+
+/// Get delivery figures of one restaurant
+#[tauri::command]
+pub async fn get_restaurant_stats(
+    restaurant_id: String,
+    state: State<'_, AppState>,
+) -> Result<RestaurantStats, String> {
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+
+    match db_guard.as_ref() {
+        Some(db) => db
+            .get_restaurant_stats(&restaurant_id)
+            .await
+            .map_err(|e| e.to_string()),
+        None => Err("Database not initialized. Call init_database first.".to_string()),
+    }
+}
+
+/// Get the restaurants with the most deliveries
+///
+/// # Arguments
+/// - `limit`: Maximum number of restaurants
+#[tauri::command]
+pub async fn get_top_restaurants(
+    limit: u32,
+    state: State<'_, AppState>,
+) -> Result<Vec<RestaurantStats>, String> {
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+
+    match db_guard.as_ref() {
+        Some(db) => db.get_top_restaurants(limit)
+            .await.map_err(|e| e.to_string()),
+        None => Err("Database not initialized. Call init_database first.".to_string()),
+    }
+}
+
+/// Export the fleet (retired bikes excluded) as CSV for Excel or Power BI
+///
+/// Requires the "export" license feature.
+#[tauri::command]
+pub async fn export_fleet_csv(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    super::license::require_feature(&app_handle, "export")?;
+
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+
+    match db_guard.as_ref() {
+        Some(db) => {
+            let bikes = db.get_all_bikes(None, None).await.map_err(|e| e.to_string())?;
+            csv_export::fleet_csv(&bikes)
+        }
+        None => Err("Database not initialized. Call init_database first.".to_string()),
+    }
+}
+
+/// Get recorded changes to bikes, deliveries and issues, oldest first
+///
+/// # Arguments
+/// - `table`: "bikes", "deliveries" or "issues"; all tables if omitted
+/// - `row_id`: Only changes to this row
+/// - `from`, `to`: Time range (RFC 3339); `to` is exclusive
+#[tauri::command]
+pub async fn get_audit_log(
+    table: Option<String>,
+    row_id: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<AuditLogEntry>, String> {
+    let from = from.as_deref().map(parse_timestamp).transpose()?;
+    let to = to.as_deref().map(parse_timestamp).transpose()?;
+
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+
+    match db_guard.as_ref() {
+        Some(db) => db
+            .get_audit_log(table.as_deref(), row_id.as_deref(), from, to)
+            .await
+            .map_err(|e| e.to_string()),
+        None => Err("Database not initialized. Call init_database first.".to_string()),
+    }
+}
+
+/// Get fleet events logged after `last_id`, oldest first
+///
+/// # Arguments
+/// - `last_id`: ID of the last event already seen; 0 for the whole log
+/// - `limit`: Most events to return; defaults to 50
+#[tauri::command]
+pub async fn poll_events(
+    last_id: u64,
+    limit: Option<u32>,
+    state: State<'_, AppState>,
+) -> Result<Vec<FleetEvent>, String> {
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+
+    match db_guard.as_ref() {
+        Some(db) => db
+            .get_events_since(last_id, limit.unwrap_or(DEFAULT_PAGE_SIZE))
+            .await
+            .map_err(|e| e.to_string()),
+        None => Err("Database not initialized. Call init_database first.".to_string()),
+    }
+}
+
+/// Search bikes by name and deliveries by customer or restaurant
+///
+/// # Arguments
+/// - `limit`: Most results per entity; defaults to 50
+#[tauri::command]
+pub async fn search(
+    query: String,
+    limit: Option<u32>,
+    state: State<'_, AppState>,
+) -> Result<SearchResults, String> {
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+
+    match db_guard.as_ref() {
+        Some(db) => db
+            .search(&query, limit.unwrap_or(DEFAULT_PAGE_SIZE))
+            .await
+            .map_err(|e| e.to_string()),
+        None => Err("Database not initialized. Call init_database first.".to_string()),
+    }
+}
+
+/// Get the figures shown in the bike detail view
+#[tauri::command]
+pub async fn get_bike_statistics(
+    bike_id: String,
+    state: State<'_, AppState>,
+) -> Result<BikeStatistics, String> {
+    let bike = get_bike_by_id(bike_id.clone(), state.clone())
+        .await?
+        .ok_or_else(|| format!("Bike not found: {}", bike_id))?;
+
+    Ok(BikeStatistics::for_bike(
+        &bike,
+        state.maintenance_threshold,
+        chrono::Utc::now(),
+    ))
+}
+
+/// Start the background task releasing expired reservations every minute
+///
+/// Ticks before `init_database` are no-ops.
+pub fn spawn_reservation_sweeper(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(RESERVATION_SWEEP_INTERVAL);
+        loop {
+            interval.tick().await;
+
+            // Clone the pool handle so the lock isn't held across await
+            let db = app_handle
+                .state::<AppState>()
+                .db
+                .lock()
+                .ok()
+                .and_then(|guard| guard.clone());
+
+            if let Some(db) = db {
+                // A failed sweep is retried on the next tick
+                let _ = db.release_expired_reservations().await;
+            }
+        }
+    });
+}
+
+/// Parse a bike status sent by the frontend
+fn parse_bike_status(status: &str) -> Result<BikeStatus, String> {
+    BikeStatus::from_str(status).ok_or_else(|| format!("Unknown bike status: {}", status))
+}
+
 /// Generate mock fleet data for when database is not available
 fn generate_mock_fleet() -> Vec<Bike> {
     use chrono::Utc;
@@ -115,6 +602,10 @@ fn generate_mock_fleet() -> Vec<Bike> {
             last_maintenance: None,
             total_trips: (i as u32 * 17) % 200,
             total_distance_km: (i as f64 * 12.5) % 500.0,
+            reservation_expires_at: None,
+            maintenance_threshold_override: None,
+            deleted_at: None,
+            next_maintenance_at: None,
             created_at: now,
             updated_at: now,
         })
@@ -124,7 +615,7 @@ fn generate_mock_fleet() -> Vec<Bike> {
 /// Get fleet statistics
 #[tauri::command]
 pub async fn get_fleet_stats(state: State<'_, AppState>) -> Result<FleetStats, String> {
-    let bikes = get_fleet_data(state).await?;
+    let bikes = get_fleet_data(state, None, None).await?;
 
     let total = bikes.len() as u32;
     let available = bikes.iter().filter(|b| b.status == BikeStatus::Available).count() as u32;
@@ -132,6 +623,11 @@ pub async fn get_fleet_stats(state: State<'_, AppState>) -> Result<FleetStats, S
     let maintenance = bikes.iter().filter(|b| b.status == BikeStatus::Maintenance).count() as u32;
     let charging = bikes.iter().filter(|b| b.status == BikeStatus::Charging).count() as u32;
     let offline = bikes.iter().filter(|b| b.status == BikeStatus::Offline).count() as u32;
+    let reserved = bikes.iter().filter(|b| b.status == BikeStatus::Reserved).count() as u32;
+    let custom_threshold = bikes
+        .iter()
+        .filter(|b| b.maintenance_threshold_override.is_some())
+        .count() as u32;
 
     let avg_battery: f64 = bikes
         .iter()
@@ -147,7 +643,9 @@ pub async fn get_fleet_stats(state: State<'_, AppState>) -> Result<FleetStats, S
         bikes_in_maintenance: maintenance,
         bikes_charging: charging,
         bikes_offline: offline,
+        bikes_reserved: reserved,
         average_battery: avg_battery,
         total_trips_today: 42, // Mock value
+        bikes_with_custom_threshold: custom_threshold,
     })
 }
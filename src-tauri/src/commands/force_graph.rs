@@ -32,38 +32,68 @@
 //! - **ManyBody**: Repulsion between all nodes (prevents overlap)
 //! - **Collide**: Collision detection based on node radius
 //! - **Link**: Spring forces along edges (keeps connected nodes close)
+//!
+//! Strengths, radii and initial distances come from `ForceGraphConfig`;
+//! `get_force_graph_layout` and `update_node_position` accept one to
+//! override the defaults.
+//!
+//! # Pinned Nodes
+//! `update_node_position` stores the dragged node's position in the
+//! `force_graph_pins` table. Every later layout of that deliverer keeps
+//! pinned nodes fixed until `clear_node_pins` resets them. Retiring a bike
+//! drops its pins.
+//!
+//! # Issue Clusters
+//! With `ForceGraphClusterMode::ByIssueCategory`, issues are grouped under
+//! one `IssueCluster` node per category instead of surrounding the
+//! deliverer individually.
+//!
+//! # Animated Layouts
+//! `start_animated_layout` runs the same simulation on a background thread,
+//! ticking manually and emitting `force_graph_frame` events with intermediate
+//! states, so the frontend can show the graph settling.
+//!
+//! # Incremental Updates
+//! `get_force_graph_diff` returns only what changed since a previously
+//! fetched layout (see `force_graph_shared`).
+//!
+//! # Export
+//! `export_force_graph_svg` renders the default layout as an SVG document.
+//!
+//! # Fleet Overview
+//! `get_fleet_force_graph` lays out every deliverer together with the
+//! restaurants they deliver for (see `force_graph_shared::compute_fleet_layout`).
 
+use super::force_graph_shared::{self, ForceGraphCache, NodeInfo};
 use crate::database::DatabaseError;
 use crate::models::{
-    Bike, Delivery, ForceGraphData, ForceLink, ForceNode, ForceNodeData, ForceNodeType, Issue,
+    Bike, Delivery, DeliveryPriority, ForceGraphClusterMode, ForceGraphConfig, ForceGraphData,
+    ForceGraphDiff, ForceLink, ForceNode, ForceNodeData, ForceNodeType, Issue,
 };
 use crate::AppState;
-use fjadra::force::{Center, Collide, Link, ManyBody, Node, SimulationBuilder};
+use fjadra::force::{Center, Collide, ManyBody, Node, Simulation, SimulationBuilder};
+use std::collections::HashMap;
 use std::f64::consts::PI;
-use tauri::State;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager, State};
 
 // ============================================================================
 // Constants
 // ============================================================================
 
-/// Node radii for different types (affects collision detection and rendering)
-const DELIVERER_RADIUS: f64 = 40.0;
-const DELIVERY_RADIUS: f64 = 25.0;
-const ISSUE_RADIUS: f64 = 18.0;
+/// Event carrying intermediate layouts of an animated simulation
+pub const FORCE_GRAPH_FRAME_EVENT: &str = "force_graph_frame";
 
-/// Initial layout distances (starting positions before simulation)
-const DELIVERY_DISTANCE: f64 = 120.0;
-const ISSUE_DISTANCE: f64 = 60.0;
+// ============================================================================
+// Animation State
+// ============================================================================
 
-/// Force configuration
-///
-/// # Why these values?
-/// - CENTER_STRENGTH 0.05: Gentle pull to prevent drift without overwhelming other forces
-/// - REPULSION_STRENGTH -300: Strong enough to separate overlapping nodes
-/// - LINK_STRENGTH 0.7: Stored for ForceLink output (actual spring uses Fjädra defaults)
-const CENTER_STRENGTH: f64 = 0.05;
-const REPULSION_STRENGTH: f64 = -300.0;
-const LINK_STRENGTH: f64 = 0.7;
+/// Cancellation flags for running layout animations, keyed by animation ID
+#[derive(Default)]
+pub struct LayoutAnimations {
+    running: Mutex<HashMap<String, Arc<AtomicBool>>>,
+}
 
 // ============================================================================
 // Tauri Commands
@@ -84,10 +114,16 @@ const LINK_STRENGTH: f64 = 0.7;
 /// - Reduces ticks needed for stable layout
 /// - Deliveries arranged in circle around deliverer
 /// - Issues positioned near their linked delivery
+///
+/// # Arguments
+/// - `config`: Layout physics (optional, defaults to `ForceGraphConfig::default()`)
+/// - `cluster_mode`: Issue grouping (optional, defaults to `ForceGraphClusterMode::None`)
 #[tauri::command]
 pub fn get_force_graph_layout(
     state: State<'_, AppState>,
     bike_id: String,
+    config: Option<ForceGraphConfig>,
+    cluster_mode: Option<ForceGraphClusterMode>,
 ) -> Result<ForceGraphData, DatabaseError> {
     let db_guard = state.db.lock().unwrap();
     let db = db_guard
@@ -100,30 +136,44 @@ pub fn get_force_graph_layout(
         .ok_or_else(|| DatabaseError::InvalidData(format!("Bike not found: {}", bike_id)))?;
     let deliveries = db.get_deliveries_by_bike(&bike_id)?;
     let issues = db.get_issues_by_bike(&bike_id)?;
+    let pins = db.get_node_pins(&bike_id)?;
 
     // Build and compute the force graph using Fjädra
-    compute_force_layout(&bike, &deliveries, &issues, None)
+    compute_force_layout(
+        &bike,
+        &deliveries,
+        &issues,
+        &pins,
+        cluster_mode.unwrap_or_default(),
+        &config.unwrap_or_default(),
+    )
 }
 
 /// Update a node's position and recompute the layout
 ///
 /// # Use Case
 /// When user drags a node in the UI, this command:
-/// 1. Fixes the dragged node at its new position
-/// 2. Reruns simulation for other nodes
-/// 3. Returns updated layout
+/// 1. Pins the dragged node at its new position (persisted)
+/// 2. Reruns simulation for other nodes, keeping all pinned nodes fixed
+/// 3. Returns updated layout, also cached for `get_force_graph_diff`
 ///
 /// # Why recompute instead of just moving one node?
 /// - Force graphs are interconnected
 /// - Moving one node affects optimal positions of neighbors
 /// - Partial recompute maintains visual coherence
+///
+/// Pass the `cluster_mode` the graph is shown in, so the clusters survive the drag.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub fn update_node_position(
     state: State<'_, AppState>,
+    cache: State<'_, ForceGraphCache>,
     bike_id: String,
     node_id: String,
     x: f64,
     y: f64,
+    config: Option<ForceGraphConfig>,
+    cluster_mode: Option<ForceGraphClusterMode>,
 ) -> Result<ForceGraphData, DatabaseError> {
     let db_guard = state.db.lock().unwrap();
     let db = db_guard
@@ -136,8 +186,231 @@ pub fn update_node_position(
     let deliveries = db.get_deliveries_by_bike(&bike_id)?;
     let issues = db.get_issues_by_bike(&bike_id)?;
 
-    // Compute with fixed node position
-    compute_force_layout(&bike, &deliveries, &issues, Some((&node_id, x, y)))
+    db.save_node_pin(&bike_id, &node_id, x, y)?;
+    let pins = db.get_node_pins(&bike_id)?;
+
+    // Compute with pinned node positions
+    let layout = compute_force_layout(
+        &bike,
+        &deliveries,
+        &issues,
+        &pins,
+        cluster_mode.unwrap_or_default(),
+        &config.unwrap_or_default(),
+    )?;
+
+    // The client now shows this layout; its next diff starts from it
+    cache.insert(&bike_id, layout.clone());
+    Ok(layout)
+}
+
+/// Unpin every node of a deliverer's graph
+///
+/// The next layout is computed from scratch again.
+#[tauri::command]
+pub fn clear_node_pins(state: State<'_, AppState>, bike_id: String) -> Result<(), DatabaseError> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard
+        .as_ref()
+        .ok_or(DatabaseError::NotInitialized)?;
+
+    db.clear_node_pins(&bike_id)
+}
+
+/// Get the changes to a deliverer's layout since a previously fetched one
+///
+/// # Flow
+/// 1. Compute the current layout
+/// 2. Look up the layout with `previous_position_hash` in `ForceGraphCache`
+/// 3. Return the diff and cache the new layout
+///
+/// Pass the returned `position_hash` on the next call. An unknown hash
+/// returns every node as added.
+#[tauri::command]
+pub fn get_force_graph_diff(
+    state: State<'_, AppState>,
+    cache: State<'_, ForceGraphCache>,
+    bike_id: String,
+    previous_position_hash: u32,
+) -> Result<ForceGraphDiff, String> {
+    let layout =
+        get_force_graph_layout(state, bike_id.clone(), None, None).map_err(|e| e.to_string())?;
+
+    Ok(force_graph_shared::diff_against_cached(
+        &cache,
+        &bike_id,
+        previous_position_hash,
+        layout,
+    ))
+}
+
+/// Export a deliverer's force graph as an SVG document
+///
+/// Computes the default layout, then renders it with
+/// `force_graph_shared::render_svg`, scaled to fit `width` x `height`.
+///
+/// # Errors
+/// `InvalidData` for an unknown bike or a non-positive size
+#[tauri::command]
+pub fn export_force_graph_svg(
+    state: State<'_, AppState>,
+    bike_id: String,
+    width: f64,
+    height: f64,
+) -> Result<String, DatabaseError> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard
+        .as_ref()
+        .ok_or(DatabaseError::NotInitialized)?;
+
+    let bike = db
+        .get_bike_by_id(&bike_id)?
+        .ok_or_else(|| DatabaseError::InvalidData(format!("Bike not found: {}", bike_id)))?;
+    let deliveries = db.get_deliveries_by_bike(&bike_id)?;
+    let issues = db.get_issues_by_bike(&bike_id)?;
+    let pins = db.get_node_pins(&bike_id)?;
+
+    export_force_graph_svg_internal(&bike, &deliveries, &issues, &pins, width, height)
+}
+
+/// Export a deliverer's force graph as a GraphML document
+///
+/// For loading the delivery/issue graph into Gephi or Cytoscape. Computes
+/// the default layout, then renders it with `force_graph_shared::render_graphml`.
+///
+/// # Errors
+/// `InvalidData` for an unknown bike
+#[tauri::command]
+pub fn export_force_graph_graphml(
+    state: State<'_, AppState>,
+    bike_id: String,
+) -> Result<String, DatabaseError> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard
+        .as_ref()
+        .ok_or(DatabaseError::NotInitialized)?;
+
+    let bike = db
+        .get_bike_by_id(&bike_id)?
+        .ok_or_else(|| DatabaseError::InvalidData(format!("Bike not found: {}", bike_id)))?;
+    let deliveries = db.get_deliveries_by_bike(&bike_id)?;
+    let issues = db.get_issues_by_bike(&bike_id)?;
+    let pins = db.get_node_pins(&bike_id)?;
+
+    export_force_graph_graphml_internal(&bike, &deliveries, &issues, &pins)
+}
+
+/// Get the fleet-wide force graph of all deliverers and their restaurants
+///
+/// Deliverers that pick up at the same restaurants end up in one cluster.
+#[tauri::command]
+pub fn get_fleet_force_graph(state: State<'_, AppState>) -> Result<ForceGraphData, DatabaseError> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard
+        .as_ref()
+        .ok_or(DatabaseError::NotInitialized)?;
+
+    let bikes = db.get_all_bikes(None, None)?;
+    let deliveries = db.get_fleet_deliveries()?;
+
+    Ok(force_graph_shared::compute_fleet_layout(&bikes, &deliveries))
+}
+
+/// Start an animated layout for a deliverer (bike)
+///
+/// # Flow
+/// 1. Fetch bike, deliveries, and issues (while holding the DB lock)
+/// 2. Spawn a thread that ticks the simulation manually
+/// 3. Every `steps_per_emit` ticks, emit a `force_graph_frame` event
+/// 4. Stop once the simulation has cooled down (alpha < alpha_min),
+///    emitting the final layout, or when cancelled
+///
+/// # Returns
+/// Animation ID for `stop_animated_layout`
+#[tauri::command]
+pub fn start_animated_layout(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    bike_id: String,
+    steps_per_emit: u32,
+) -> Result<String, String> {
+    let (bike, deliveries, issues, pins) = {
+        let db_guard = state.db.lock().unwrap();
+        let db = db_guard
+            .as_ref()
+            .ok_or(DatabaseError::NotInitialized.to_string())?;
+
+        let bike = db
+            .get_bike_by_id(&bike_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Bike not found: {}", bike_id))?;
+        let deliveries = db.get_deliveries_by_bike(&bike_id).map_err(|e| e.to_string())?;
+        let issues = db.get_issues_by_bike(&bike_id).map_err(|e| e.to_string())?;
+        let pins = db.get_node_pins(&bike_id).map_err(|e| e.to_string())?;
+        (bike, deliveries, issues, pins)
+    };
+
+    let layout_id = uuid::Uuid::new_v4().to_string();
+    let cancelled = Arc::new(AtomicBool::new(false));
+    app_handle
+        .state::<LayoutAnimations>()
+        .running
+        .lock()
+        .unwrap()
+        .insert(layout_id.clone(), cancelled.clone());
+
+    let thread_id = layout_id.clone();
+    std::thread::spawn(move || {
+        // The simulation is built on this thread: force closures aren't Send
+        let config = ForceGraphConfig::default();
+        let mut layout = build_force_layout(
+            &bike,
+            &deliveries,
+            &issues,
+            &pins,
+            ForceGraphClusterMode::None,
+            &config,
+        );
+        let steps_per_emit = steps_per_emit.max(1);
+        let mut steps = 0;
+
+        while !layout.simulation.finished() && !cancelled.load(Ordering::Relaxed) {
+            layout.simulation.tick(1);
+            steps += 1;
+
+            if steps % steps_per_emit == 0 {
+                let _ = app_handle.emit(FORCE_GRAPH_FRAME_EVENT, layout.snapshot());
+            }
+        }
+
+        // Always deliver the settled layout, unless the frame was just sent
+        if !cancelled.load(Ordering::Relaxed) && steps % steps_per_emit != 0 {
+            let _ = app_handle.emit(FORCE_GRAPH_FRAME_EVENT, layout.snapshot());
+        }
+
+        app_handle
+            .state::<LayoutAnimations>()
+            .running
+            .lock()
+            .unwrap()
+            .remove(&thread_id);
+    });
+
+    Ok(layout_id)
+}
+
+/// Cancel a running animated layout
+///
+/// Unknown or already finished IDs are ignored.
+#[tauri::command]
+pub fn stop_animated_layout(
+    animations: State<'_, LayoutAnimations>,
+    layout_id: String,
+) -> Result<(), String> {
+    if let Some(cancelled) = animations.running.lock().unwrap().remove(&layout_id) {
+        cancelled.store(true, Ordering::Relaxed);
+    }
+    Ok(())
 }
 
 // ============================================================================
@@ -149,65 +422,163 @@ pub fn update_node_position(
 /// # Why exposed as pub?
 /// The secure_invoke handler in secure.rs needs to call this
 /// after fetching data from the database.
+///
+/// `pins` are the deliverer's pinned nodes as (node ID, x, y); after a drag
+/// they include the dragged node.
 pub fn get_force_graph_layout_internal(
     bike: &Bike,
     deliveries: &[Delivery],
     issues: &[Issue],
+    pins: &[(String, f64, f64)],
+    cluster_mode: ForceGraphClusterMode,
 ) -> Result<ForceGraphData, DatabaseError> {
-    compute_force_layout(bike, deliveries, issues, None)
+    compute_force_layout(
+        bike,
+        deliveries,
+        issues,
+        pins,
+        cluster_mode,
+        &ForceGraphConfig::default(),
+    )
 }
 
-/// Internal function to update node position (called by secure_invoke)
-pub fn update_node_position_internal(
+/// Internal function to export the layout as SVG (called by secure_invoke)
+pub fn export_force_graph_svg_internal(
     bike: &Bike,
     deliveries: &[Delivery],
     issues: &[Issue],
-    node_id: &str,
-    x: f64,
-    y: f64,
-) -> Result<ForceGraphData, DatabaseError> {
-    compute_force_layout(bike, deliveries, issues, Some((node_id, x, y)))
+    pins: &[(String, f64, f64)],
+    width: f64,
+    height: f64,
+) -> Result<String, DatabaseError> {
+    let layout = compute_force_layout(
+        bike,
+        deliveries,
+        issues,
+        pins,
+        ForceGraphClusterMode::None,
+        &ForceGraphConfig::default(),
+    )?;
+    force_graph_shared::render_svg(&layout, width, height).map_err(DatabaseError::InvalidData)
+}
+
+/// Internal function to export the layout as GraphML (called by secure_invoke)
+pub fn export_force_graph_graphml_internal(
+    bike: &Bike,
+    deliveries: &[Delivery],
+    issues: &[Issue],
+    pins: &[(String, f64, f64)],
+) -> Result<String, DatabaseError> {
+    let layout = compute_force_layout(
+        bike,
+        deliveries,
+        issues,
+        pins,
+        ForceGraphClusterMode::None,
+        &ForceGraphConfig::default(),
+    )?;
+    force_graph_shared::render_graphml(&layout).map_err(DatabaseError::InvalidData)
 }
 
 // ============================================================================
 // Layout Computation with Fjädra
 // ============================================================================
 
-/// Intermediate node data structure for building the graph
-struct NodeInfo {
-    id: String,
-    node_type: ForceNodeType,
-    label: String,
-    radius: f64,
-    data: ForceNodeData,
-    initial_x: f64,
-    initial_y: f64,
+/// A configured simulation together with the metadata of its nodes
+struct ForceLayout {
+    node_infos: Vec<NodeInfo>,
+    links: Vec<ForceLink>,
+    simulation: Simulation,
+}
+
+impl ForceLayout {
+    /// Build ForceGraphData from the current simulation positions
+    fn snapshot(&self) -> ForceGraphData {
+        let positions: Vec<[f64; 2]> = self.simulation.positions().collect();
+
+        let nodes: Vec<ForceNode> = self
+            .node_infos
+            .iter()
+            .cloned()
+            .enumerate()
+            .map(|(i, info)| {
+                let [x, y] = positions.get(i).copied().unwrap_or([info.initial_x, info.initial_y]);
+                ForceNode {
+                    id: info.id,
+                    node_type: info.node_type,
+                    label: info.label,
+                    x,
+                    y,
+                    radius: info.radius,
+                    data: info.data,
+                }
+            })
+            .collect();
+
+        let bounds = force_graph_shared::compute_bounds(&nodes);
+
+        ForceGraphData {
+            nodes,
+            links: self.links.clone(),
+            center_x: 0.0,
+            center_y: 0.0,
+            bounds,
+        }
+    }
 }
 
 /// Compute force layout using Fjädra simulation
 ///
 /// # Implementation
-/// 1. Build node metadata (id, type, label, data)
-/// 2. Compute initial positions (radial layout)
-/// 3. Create Fjädra Nodes with positions
-/// 4. Build link index pairs for spring forces
-/// 5. Configure and run simulation
-/// 6. Extract final positions and return ForceGraphData
+/// 1. Build node metadata, initial positions and forces (`build_force_layout`)
+/// 2. Run simulation to completion
+/// 3. Extract final positions and return ForceGraphData
 ///
 /// # Why Fjädra over geometric layout?
 /// - Produces more natural, organic layouts
 /// - Handles complex graph topologies better
 /// - Self-organizes to minimize edge crossings
 /// - Responds realistically to node dragging
+///
+/// # Errors
+/// `InvalidData` if `config` fails `ForceGraphConfig::validate`
 fn compute_force_layout(
     bike: &Bike,
     deliveries: &[Delivery],
     issues: &[Issue],
-    fixed_node: Option<(&str, f64, f64)>,
+    pins: &[(String, f64, f64)],
+    cluster_mode: ForceGraphClusterMode,
+    config: &ForceGraphConfig,
 ) -> Result<ForceGraphData, DatabaseError> {
+    config.validate().map_err(DatabaseError::InvalidData)?;
+    let mut layout = build_force_layout(bike, deliveries, issues, pins, cluster_mode, config);
+
+    // Run simulation to completion
+    // .step() runs until alpha drops below alpha_min
+    layout.simulation.step();
+
+    Ok(layout.snapshot())
+}
+
+/// Set up the Fjädra simulation for a deliverer's graph without running it
+///
+/// # Implementation
+/// 1. Build node metadata (id, type, label, data)
+/// 2. Compute initial positions (radial layout)
+/// 3. Create Fjädra Nodes with positions, fixing pinned nodes
+/// 4. Build link index pairs and rest distances for spring forces
+/// 5. Configure simulation forces
+fn build_force_layout(
+    bike: &Bike,
+    deliveries: &[Delivery],
+    issues: &[Issue],
+    pins: &[(String, f64, f64)],
+    cluster_mode: ForceGraphClusterMode,
+    config: &ForceGraphConfig,
+) -> ForceLayout {
     let mut node_infos: Vec<NodeInfo> = Vec::new();
     let mut links: Vec<ForceLink> = Vec::new();
-    let mut link_indices: Vec<(usize, usize)> = Vec::new();
+    let mut link_indices: Vec<(usize, usize, f64)> = Vec::new();
 
     // Track radii for collision detection
     let mut radii: Vec<f64> = Vec::new();
@@ -217,7 +588,7 @@ fn compute_force_layout(
         id: bike.id.clone(),
         node_type: ForceNodeType::Deliverer,
         label: bike.name.clone(),
-        radius: DELIVERER_RADIUS,
+        radius: config.deliverer_radius,
         data: ForceNodeData::Deliverer {
             name: bike.name.clone(),
             status: bike.status.clone(),
@@ -225,7 +596,7 @@ fn compute_force_layout(
         initial_x: 0.0,
         initial_y: 0.0,
     });
-    radii.push(DELIVERER_RADIUS);
+    radii.push(config.deliverer_radius);
 
     // 2. Create delivery nodes in a ring around center
     let delivery_count = deliveries.len();
@@ -235,35 +606,58 @@ fn compute_force_layout(
         } else {
             0.0
         };
-        let x = DELIVERY_DISTANCE * angle.cos();
-        let y = DELIVERY_DISTANCE * angle.sin();
+        let x = config.delivery_distance * angle.cos();
+        let y = config.delivery_distance * angle.sin();
+
+        let radius = if delivery.priority == DeliveryPriority::Urgent {
+            config.urgent_delivery_radius()
+        } else {
+            config.delivery_radius
+        };
 
         let delivery_index = node_infos.len();
         node_infos.push(NodeInfo {
             id: delivery.id.clone(),
             node_type: ForceNodeType::Delivery,
             label: delivery.customer_name.clone(),
-            radius: DELIVERY_RADIUS,
+            radius,
             data: ForceNodeData::Delivery {
                 status: delivery.status.clone(),
+                priority: delivery.priority,
                 customer: delivery.customer_name.clone(),
                 rating: delivery.rating,
             },
             initial_x: x,
             initial_y: y,
         });
-        radii.push(DELIVERY_RADIUS);
+        radii.push(radius);
 
         // Link: deliverer (0) -> delivery
         links.push(ForceLink {
             source: bike.id.clone(),
             target: delivery.id.clone(),
-            strength: LINK_STRENGTH,
+            strength: config.link_strength,
         });
-        link_indices.push((0, delivery_index));
+        link_indices.push((0, delivery_index, config.delivery_distance));
     }
 
     // 3. Create issue nodes
+    // In cluster mode every issue hangs off its category's cluster instead
+    let issues: &[Issue] = match cluster_mode {
+        ForceGraphClusterMode::None => issues,
+        ForceGraphClusterMode::ByIssueCategory => {
+            force_graph_shared::push_issue_clusters(
+                &bike.id,
+                issues,
+                config,
+                &mut node_infos,
+                &mut radii,
+                &mut links,
+                &mut link_indices,
+            );
+            &[]
+        }
+    };
     let standalone_issues: Vec<_> = issues.iter().filter(|i| i.delivery_id.is_none()).collect();
     let linked_issues: Vec<_> = issues.iter().filter(|i| i.delivery_id.is_some()).collect();
 
@@ -277,37 +671,38 @@ fn compute_force_layout(
             .enumerate()
             .find(|(_, n)| &n.id == delivery_id)
             .map(|(idx, n)| (idx, n.initial_x, n.initial_y))
-            .unwrap_or((1, DELIVERY_DISTANCE, 0.0));
+            .unwrap_or((1, config.delivery_distance, 0.0));
 
         // Offset from delivery position
         let angle_offset =
             (issues.iter().position(|i| i.id == issue.id).unwrap_or(0) as f64) * 0.8;
-        let x = delivery_x + ISSUE_DISTANCE * angle_offset.cos();
-        let y = delivery_y + ISSUE_DISTANCE * angle_offset.sin();
+        let x = delivery_x + config.issue_distance * angle_offset.cos();
+        let y = delivery_y + config.issue_distance * angle_offset.sin();
 
         let issue_index = node_infos.len();
         node_infos.push(NodeInfo {
             id: issue.id.clone(),
             node_type: ForceNodeType::Issue,
             label: issue.category.as_str().to_string(),
-            radius: ISSUE_RADIUS,
+            radius: config.issue_radius,
             data: ForceNodeData::Issue {
                 category: issue.category.clone(),
                 resolved: issue.resolved,
+                resolved_at: issue.resolved_at,
                 reporter: issue.reporter_type.clone(),
             },
             initial_x: x,
             initial_y: y,
         });
-        radii.push(ISSUE_RADIUS);
+        radii.push(config.issue_radius);
 
         // Link: delivery -> issue
         links.push(ForceLink {
             source: delivery_id.clone(),
             target: issue.id.clone(),
-            strength: LINK_STRENGTH * 0.8,
+            strength: config.link_strength * 0.8,
         });
-        link_indices.push((delivery_idx, issue_index));
+        link_indices.push((delivery_idx, issue_index, config.issue_distance));
     }
 
     // Position standalone issues in outer ring
@@ -318,144 +713,267 @@ fn compute_force_layout(
         } else {
             0.0
         };
-        let x = (DELIVERY_DISTANCE + ISSUE_DISTANCE) * angle.cos();
-        let y = (DELIVERY_DISTANCE + ISSUE_DISTANCE) * angle.sin();
+        let x = (config.delivery_distance + config.issue_distance) * angle.cos();
+        let y = (config.delivery_distance + config.issue_distance) * angle.sin();
 
         let issue_index = node_infos.len();
         node_infos.push(NodeInfo {
             id: issue.id.clone(),
             node_type: ForceNodeType::Issue,
             label: issue.category.as_str().to_string(),
-            radius: ISSUE_RADIUS,
+            radius: config.issue_radius,
             data: ForceNodeData::Issue {
                 category: issue.category.clone(),
                 resolved: issue.resolved,
+                resolved_at: issue.resolved_at,
                 reporter: issue.reporter_type.clone(),
             },
             initial_x: x,
             initial_y: y,
         });
-        radii.push(ISSUE_RADIUS);
+        radii.push(config.issue_radius);
 
         // Link: deliverer -> standalone issue
         links.push(ForceLink {
             source: bike.id.clone(),
             target: issue.id.clone(),
-            strength: LINK_STRENGTH * 0.5,
+            strength: config.link_strength * 0.5,
         });
-        link_indices.push((0, issue_index));
+        link_indices.push((0, issue_index, config.delivery_distance + config.issue_distance));
     }
 
     // 4. Create Fjädra nodes with initial positions
-    // Handle fixed node if specified (for drag operations)
-    let fixed_node_index = fixed_node.and_then(|(id, _, _)| {
-        node_infos.iter().position(|n| n.id == id)
-    });
-
+    // Pinned nodes (dragged by the user) stay where they were dropped
     let particles: Vec<Node> = node_infos
         .iter()
         .enumerate()
         .map(|(idx, info)| {
-            // Check if this is the fixed node
-            if let Some((fixed_id, fx, fy)) = fixed_node {
-                if info.id == fixed_id {
-                    return Node::default().fixed_position(fx, fy);
-                }
+            if let Some((_, px, py)) = pins.iter().find(|(id, _, _)| *id == info.id) {
+                return Node::default().fixed_position(*px, *py);
             }
-            // Also fix deliverer at center if not being dragged
-            if idx == 0 && fixed_node_index != Some(0) {
+            // Also fix deliverer at center if not pinned elsewhere
+            if idx == 0 {
                 return Node::default().fixed_position(0.0, 0.0);
             }
             Node::default().position(info.initial_x, info.initial_y)
         })
         .collect();
 
-    // 5. Build and run Fjädra simulation
+    // 5. Build Fjädra simulation
     //
     // Fjädra API notes:
     // - ManyBody.strength takes |node_idx, count| -> f64
-    // - Link uses default distance/strength (avoids closure lifetime issues)
+    // - Link takes per-link distance/strength, see `link_force`
     // - Collide.radius takes |node_idx| -> f64
     let radii_clone = radii.clone();
-    let mut simulation = SimulationBuilder::default()
+    let padding = config.collision_padding;
+    let repulsion_strength = config.repulsion_strength;
+    let simulation = SimulationBuilder::default()
         .build(particles)
-        .add_force("center", Center::new().strength(CENTER_STRENGTH))
+        .add_force("center", Center::new().strength(config.center_strength))
         .add_force(
             "charge",
-            ManyBody::new().strength(|_node_idx, _count| REPULSION_STRENGTH),
+            ManyBody::new().strength(move |_node_idx, _count| repulsion_strength),
         )
         .add_force(
             "collide",
             Collide::new()
-                .radius(move |i| radii_clone[i] + 5.0) // Add padding
+                .radius(move |i| radii_clone[i] + padding)
                 .iterations(2),
         )
         .add_force(
             "links",
-            // Use Link with defaults - the simulation will use sensible defaults
-            // for distance and strength based on link topology
-            Link::new(link_indices).iterations(3),
+            force_graph_shared::link_force(&links, link_indices),
         );
 
-    // Run simulation to completion
-    // .step() runs until alpha drops below alpha_min
-    simulation.step();
+    ForceLayout {
+        node_infos,
+        links,
+        simulation,
+    }
+}
 
-    // 6. Extract final positions and build output
-    let positions: Vec<[f64; 2]> = simulation.positions().collect();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{BikeStatus, DeliveryStatus, IssueCategory, IssueReporterType};
+    use chrono::Utc;
+
+    fn bike() -> Bike {
+        Bike {
+            id: "BIKE-0001".to_string(),
+            name: "Centraal".to_string(),
+            status: BikeStatus::InUse,
+            latitude: 52.3791,
+            longitude: 4.9003,
+            battery_level: Some(80),
+            last_maintenance: None,
+            total_trips: 0,
+            total_distance_km: 0.0,
+            reservation_expires_at: None,
+            maintenance_threshold_override: None,
+            deleted_at: None,
+            next_maintenance_at: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
 
-    let nodes: Vec<ForceNode> = node_infos
-        .into_iter()
-        .enumerate()
-        .map(|(i, info)| {
-            let [x, y] = positions.get(i).copied().unwrap_or([info.initial_x, info.initial_y]);
-            ForceNode {
-                id: info.id,
-                node_type: info.node_type,
-                label: info.label,
-                x,
-                y,
-                radius: info.radius,
-                data: info.data,
-            }
-        })
-        .collect();
+    fn delivery(id: &str) -> Delivery {
+        Delivery {
+            id: id.to_string(),
+            bike_id: "BIKE-0001".to_string(),
+            status: DeliveryStatus::Ongoing,
+            priority: DeliveryPriority::Normal,
+            customer_name: "P. de Vries".to_string(),
+            customer_address: "Damrak 1".to_string(),
+            restaurant_name: "Febo".to_string(),
+            restaurant_address: "Rokin 2".to_string(),
+            rating: None,
+            complaint: None,
+            created_at: Utc::now(),
+            completed_at: None,
+        }
+    }
 
-    let bounds = compute_bounds(&nodes);
+    #[test]
+    fn test_config_sets_radii_and_link_strength() {
+        let config = ForceGraphConfig {
+            deliverer_radius: 55.0,
+            delivery_radius: 10.0,
+            link_strength: 0.3,
+            ..ForceGraphConfig::default()
+        };
+        let deliveries = [delivery("DEL-0001"), delivery("DEL-0002")];
+
+        let graph = compute_force_layout(
+            &bike(),
+            &deliveries,
+            &[],
+            &[],
+            ForceGraphClusterMode::None,
+            &config,
+        )
+        .unwrap();
+
+        assert_eq!(graph.nodes[0].radius, 55.0);
+        assert!(graph.nodes[1..].iter().all(|node| node.radius == 10.0));
+        assert!(graph.links.iter().all(|link| link.strength == 0.3));
+
+        // A longer link distance pushes the deliveries further out
+        let spread = |delivery_distance: f64| {
+            let config = ForceGraphConfig {
+                delivery_distance,
+                ..config.clone()
+            };
+            let graph = compute_force_layout(
+                &bike(),
+                &deliveries,
+                &[],
+                &[],
+                ForceGraphClusterMode::None,
+                &config,
+            )
+            .unwrap();
+            graph.nodes[1..]
+                .iter()
+                .map(|node| node.x.hypot(node.y))
+                .fold(f64::INFINITY, f64::min)
+        };
+        assert!(spread(300.0) > spread(60.0) + 100.0);
+    }
 
-    Ok(ForceGraphData {
-        nodes,
-        links,
-        center_x: 0.0,
-        center_y: 0.0,
-        bounds,
-    })
-}
+    #[test]
+    fn test_zero_repulsion_keeps_nodes_finite() {
+        let config = ForceGraphConfig {
+            repulsion_strength: 0.0,
+            center_strength: 1.0,
+            ..ForceGraphConfig::default()
+        };
+        let deliveries = [delivery("DEL-0001"), delivery("DEL-0002")];
+
+        let graph = compute_force_layout(
+            &bike(),
+            &deliveries,
+            &[],
+            &[],
+            ForceGraphClusterMode::None,
+            &config,
+        )
+        .unwrap();
 
-/// Calculate bounding box of all nodes
-fn compute_bounds(nodes: &[ForceNode]) -> (f64, f64, f64, f64) {
-    if nodes.is_empty() {
-        return (0.0, 0.0, 0.0, 0.0);
+        assert!(graph.nodes.iter().all(|node| node.x.is_finite() && node.y.is_finite()));
     }
 
-    let mut min_x = f64::MAX;
-    let mut max_x = f64::MIN;
-    let mut min_y = f64::MAX;
-    let mut max_y = f64::MIN;
+    #[test]
+    fn test_invalid_config_rejected() {
+        let config = ForceGraphConfig {
+            issue_radius: -1.0,
+            ..ForceGraphConfig::default()
+        };
+
+        let result =
+            compute_force_layout(&bike(), &[], &[], &[], ForceGraphClusterMode::None, &config);
 
-    for node in nodes {
-        min_x = min_x.min(node.x - node.radius);
-        max_x = max_x.max(node.x + node.radius);
-        min_y = min_y.min(node.y - node.radius);
-        max_y = max_y.max(node.y + node.radius);
+        assert!(matches!(result, Err(DatabaseError::InvalidData(_))));
     }
 
-    // Add padding
-    let padding = 20.0;
-    (
-        min_x - padding,
-        max_x + padding,
-        min_y - padding,
-        max_y + padding,
-    )
+    #[test]
+    fn test_cluster_mode_groups_issues_by_category() {
+        let issue = |id: &str, category: IssueCategory, delivery_id: Option<&str>| Issue {
+            id: id.to_string(),
+            bike_id: "BIKE-0001".to_string(),
+            delivery_id: delivery_id.map(str::to_string),
+            reporter_type: IssueReporterType::Customer,
+            category,
+            description: "Late again".to_string(),
+            resolved: false,
+            resolved_at: None,
+            resolution_note: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        let deliveries = [delivery("DEL-0001")];
+        let issues = [
+            issue("ISS-0001", IssueCategory::Late, Some("DEL-0001")),
+            issue("ISS-0002", IssueCategory::Late, None),
+            issue("ISS-0003", IssueCategory::Late, None),
+            issue("ISS-0004", IssueCategory::Late, None),
+            issue("ISS-0005", IssueCategory::Rude, None),
+        ];
+        let config = ForceGraphConfig::default();
+
+        let graph = compute_force_layout(
+            &bike(),
+            &deliveries,
+            &issues,
+            &[],
+            ForceGraphClusterMode::ByIssueCategory,
+            &config,
+        )
+        .unwrap();
+
+        let late = graph.nodes.iter().find(|n| n.id == "cluster-late").unwrap();
+        assert_eq!(
+            late.data,
+            ForceNodeData::IssueCluster { category: IssueCategory::Late, count: 4 }
+        );
+        assert_eq!(late.radius, 2.0 * config.issue_radius);
+        // Every issue links to its cluster only; the delivery still links to the deliverer
+        for issue in &issues {
+            let sources: Vec<&str> = graph
+                .links
+                .iter()
+                .filter(|link| link.target == issue.id)
+                .map(|link| link.source.as_str())
+                .collect();
+            assert_eq!(sources.len(), 1);
+            assert!(sources[0].starts_with("cluster-"));
+        }
+        assert!(graph
+            .links
+            .iter()
+            .any(|link| link.source == "BIKE-0001" && link.target == "DEL-0001"));
+        assert_eq!(graph.nodes.len(), 1 + 1 + 2 + issues.len());
+    }
 }
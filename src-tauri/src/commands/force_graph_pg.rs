@@ -2,30 +2,27 @@
 //!
 //! Async versions of force graph commands for PostgreSQL backend.
 
+use super::force_graph_shared::{self, ForceGraphCache, NodeInfo};
 use crate::database_pg::DatabaseError;
 use crate::models::{
-    Bike, Delivery, ForceGraphData, ForceLink, ForceNode, ForceNodeData, ForceNodeType, Issue,
+    Bike, Delivery, DeliveryPriority, ForceGraphClusterMode, ForceGraphConfig, ForceGraphData,
+    ForceGraphDiff, ForceLink, ForceNode, ForceNodeData, ForceNodeType, Issue,
 };
 use crate::AppState;
-use fjadra::force::{Center, Collide, Link, ManyBody, Node, SimulationBuilder};
+use fjadra::force::{Center, Collide, ManyBody, Node, SimulationBuilder};
 use std::f64::consts::PI;
 use tauri::State;
 
-// Constants (same as SQLite version)
-const DELIVERER_RADIUS: f64 = 40.0;
-const DELIVERY_RADIUS: f64 = 25.0;
-const ISSUE_RADIUS: f64 = 18.0;
-const DELIVERY_DISTANCE: f64 = 120.0;
-const ISSUE_DISTANCE: f64 = 60.0;
-const CENTER_STRENGTH: f64 = 0.05;
-const REPULSION_STRENGTH: f64 = -300.0;
-const LINK_STRENGTH: f64 = 0.7;
-
 /// Get force graph layout for a specific deliverer (bike)
+///
+/// `config` overrides the default layout physics; `cluster_mode` groups
+/// issues by category.
 #[tauri::command]
 pub async fn get_force_graph_layout(
     state: State<'_, AppState>,
     bike_id: String,
+    config: Option<ForceGraphConfig>,
+    cluster_mode: Option<ForceGraphClusterMode>,
 ) -> Result<ForceGraphData, DatabaseError> {
     let db_guard = state.db.lock().unwrap();
     let db = db_guard.as_ref().ok_or(DatabaseError::NotInitialized)?;
@@ -37,19 +34,31 @@ pub async fn get_force_graph_layout(
         .ok_or_else(|| DatabaseError::InvalidData(format!("Bike not found: {}", bike_id)))?;
     let deliveries = db.get_deliveries_by_bike(&bike_id).await?;
     let issues = db.get_issues_by_bike(&bike_id).await?;
+    let pins = db.get_node_pins(&bike_id).await?;
 
     // Build and compute the force graph
-    compute_force_layout(&bike, &deliveries, &issues, None)
+    compute_force_layout(
+        &bike,
+        &deliveries,
+        &issues,
+        &pins,
+        cluster_mode.unwrap_or_default(),
+        &config.unwrap_or_default(),
+    )
 }
 
-/// Update a node's position and recompute the layout
+/// Pin a node at its dragged position and recompute the layout
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn update_node_position(
     state: State<'_, AppState>,
+    cache: State<'_, ForceGraphCache>,
     bike_id: String,
     node_id: String,
     x: f64,
     y: f64,
+    config: Option<ForceGraphConfig>,
+    cluster_mode: Option<ForceGraphClusterMode>,
 ) -> Result<ForceGraphData, DatabaseError> {
     let db_guard = state.db.lock().unwrap();
     let db = db_guard.as_ref().ok_or(DatabaseError::NotInitialized)?;
@@ -61,32 +70,114 @@ pub async fn update_node_position(
     let deliveries = db.get_deliveries_by_bike(&bike_id).await?;
     let issues = db.get_issues_by_bike(&bike_id).await?;
 
-    compute_force_layout(&bike, &deliveries, &issues, Some((&node_id, x, y)))
+    db.save_node_pin(&bike_id, &node_id, x, y).await?;
+    let pins = db.get_node_pins(&bike_id).await?;
+
+    let layout = compute_force_layout(
+        &bike,
+        &deliveries,
+        &issues,
+        &pins,
+        cluster_mode.unwrap_or_default(),
+        &config.unwrap_or_default(),
+    )?;
+
+    // The client now shows this layout; its next diff starts from it
+    cache.insert(&bike_id, layout.clone());
+    Ok(layout)
+}
+
+/// Unpin every node of a deliverer's graph
+#[tauri::command]
+pub async fn clear_node_pins(
+    state: State<'_, AppState>,
+    bike_id: String,
+) -> Result<(), DatabaseError> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or(DatabaseError::NotInitialized)?;
+
+    db.clear_node_pins(&bike_id).await
+}
+
+/// Export a deliverer's force graph as an SVG document
+///
+/// See `force_graph::export_force_graph_svg`.
+#[tauri::command]
+pub async fn export_force_graph_svg(
+    state: State<'_, AppState>,
+    bike_id: String,
+    width: f64,
+    height: f64,
+) -> Result<String, DatabaseError> {
+    let layout = get_force_graph_layout(state, bike_id, None, None).await?;
+    force_graph_shared::render_svg(&layout, width, height).map_err(DatabaseError::InvalidData)
+}
+
+/// Export a deliverer's force graph as a GraphML document
+///
+/// See `force_graph::export_force_graph_graphml`.
+#[tauri::command]
+pub async fn export_force_graph_graphml(
+    state: State<'_, AppState>,
+    bike_id: String,
+) -> Result<String, DatabaseError> {
+    let layout = get_force_graph_layout(state, bike_id, None, None).await?;
+    force_graph_shared::render_graphml(&layout).map_err(DatabaseError::InvalidData)
+}
+
+/// Get the fleet-wide force graph of all deliverers and their restaurants
+///
+/// See `force_graph::get_fleet_force_graph`.
+#[tauri::command]
+pub async fn get_fleet_force_graph(
+    state: State<'_, AppState>,
+) -> Result<ForceGraphData, DatabaseError> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or(DatabaseError::NotInitialized)?;
+
+    let bikes = db.get_all_bikes(None, None).await?;
+    let deliveries = db.get_fleet_deliveries().await?;
+
+    Ok(force_graph_shared::compute_fleet_layout(&bikes, &deliveries))
+}
+
+/// Get the changes to a deliverer's layout since a previously fetched one
+#[tauri::command]
+pub async fn get_force_graph_diff(
+    state: State<'_, AppState>,
+    cache: State<'_, ForceGraphCache>,
+    bike_id: String,
+    previous_position_hash: u32,
+) -> Result<ForceGraphDiff, String> {
+    let layout = get_force_graph_layout(state, bike_id.clone(), None, None)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(force_graph_shared::diff_against_cached(
+        &cache,
+        &bike_id,
+        previous_position_hash,
+        layout,
+    ))
 }
 
 // ============================================================================
 // Layout Computation (same algorithm as SQLite version)
 // ============================================================================
 
-struct NodeInfo {
-    id: String,
-    node_type: ForceNodeType,
-    label: String,
-    radius: f64,
-    data: ForceNodeData,
-    initial_x: f64,
-    initial_y: f64,
-}
-
 fn compute_force_layout(
     bike: &Bike,
     deliveries: &[Delivery],
     issues: &[Issue],
-    fixed_node: Option<(&str, f64, f64)>,
+    pins: &[(String, f64, f64)],
+    cluster_mode: ForceGraphClusterMode,
+    config: &ForceGraphConfig,
 ) -> Result<ForceGraphData, DatabaseError> {
+    config.validate().map_err(DatabaseError::InvalidData)?;
+
     let mut node_infos: Vec<NodeInfo> = Vec::new();
     let mut links: Vec<ForceLink> = Vec::new();
-    let mut link_indices: Vec<(usize, usize)> = Vec::new();
+    let mut link_indices: Vec<(usize, usize, f64)> = Vec::new();
     let mut radii: Vec<f64> = Vec::new();
 
     // 1. Create deliverer node at center
@@ -94,7 +185,7 @@ fn compute_force_layout(
         id: bike.id.clone(),
         node_type: ForceNodeType::Deliverer,
         label: bike.name.clone(),
-        radius: DELIVERER_RADIUS,
+        radius: config.deliverer_radius,
         data: ForceNodeData::Deliverer {
             name: bike.name.clone(),
             status: bike.status.clone(),
@@ -102,7 +193,7 @@ fn compute_force_layout(
         initial_x: 0.0,
         initial_y: 0.0,
     });
-    radii.push(DELIVERER_RADIUS);
+    radii.push(config.deliverer_radius);
 
     // 2. Create delivery nodes
     let delivery_count = deliveries.len();
@@ -112,34 +203,57 @@ fn compute_force_layout(
         } else {
             0.0
         };
-        let x = DELIVERY_DISTANCE * angle.cos();
-        let y = DELIVERY_DISTANCE * angle.sin();
+        let x = config.delivery_distance * angle.cos();
+        let y = config.delivery_distance * angle.sin();
+
+        let radius = if delivery.priority == DeliveryPriority::Urgent {
+            config.urgent_delivery_radius()
+        } else {
+            config.delivery_radius
+        };
 
         let delivery_index = node_infos.len();
         node_infos.push(NodeInfo {
             id: delivery.id.clone(),
             node_type: ForceNodeType::Delivery,
             label: delivery.customer_name.clone(),
-            radius: DELIVERY_RADIUS,
+            radius,
             data: ForceNodeData::Delivery {
                 status: delivery.status.clone(),
+                priority: delivery.priority,
                 customer: delivery.customer_name.clone(),
                 rating: delivery.rating,
             },
             initial_x: x,
             initial_y: y,
         });
-        radii.push(DELIVERY_RADIUS);
+        radii.push(radius);
 
         links.push(ForceLink {
             source: bike.id.clone(),
             target: delivery.id.clone(),
-            strength: LINK_STRENGTH,
+            strength: config.link_strength,
         });
-        link_indices.push((0, delivery_index));
+        link_indices.push((0, delivery_index, config.delivery_distance));
     }
 
     // 3. Create issue nodes
+    // In cluster mode every issue hangs off its category's cluster instead
+    let issues: &[Issue] = match cluster_mode {
+        ForceGraphClusterMode::None => issues,
+        ForceGraphClusterMode::ByIssueCategory => {
+            force_graph_shared::push_issue_clusters(
+                &bike.id,
+                issues,
+                config,
+                &mut node_infos,
+                &mut radii,
+                &mut links,
+                &mut link_indices,
+            );
+            &[]
+        }
+    };
     let standalone_issues: Vec<_> = issues.iter().filter(|i| i.delivery_id.is_none()).collect();
     let linked_issues: Vec<_> = issues.iter().filter(|i| i.delivery_id.is_some()).collect();
 
@@ -151,35 +265,36 @@ fn compute_force_layout(
             .enumerate()
             .find(|(_, n)| &n.id == delivery_id)
             .map(|(idx, n)| (idx, n.initial_x, n.initial_y))
-            .unwrap_or((1, DELIVERY_DISTANCE, 0.0));
+            .unwrap_or((1, config.delivery_distance, 0.0));
 
         let angle_offset =
             (issues.iter().position(|i| i.id == issue.id).unwrap_or(0) as f64) * 0.8;
-        let x = delivery_x + ISSUE_DISTANCE * angle_offset.cos();
-        let y = delivery_y + ISSUE_DISTANCE * angle_offset.sin();
+        let x = delivery_x + config.issue_distance * angle_offset.cos();
+        let y = delivery_y + config.issue_distance * angle_offset.sin();
 
         let issue_index = node_infos.len();
         node_infos.push(NodeInfo {
             id: issue.id.clone(),
             node_type: ForceNodeType::Issue,
             label: issue.category.as_str().to_string(),
-            radius: ISSUE_RADIUS,
+            radius: config.issue_radius,
             data: ForceNodeData::Issue {
                 category: issue.category.clone(),
                 resolved: issue.resolved,
+                resolved_at: issue.resolved_at,
                 reporter: issue.reporter_type.clone(),
             },
             initial_x: x,
             initial_y: y,
         });
-        radii.push(ISSUE_RADIUS);
+        radii.push(config.issue_radius);
 
         links.push(ForceLink {
             source: delivery_id.clone(),
             target: issue.id.clone(),
-            strength: LINK_STRENGTH * 0.8,
+            strength: config.link_strength * 0.8,
         });
-        link_indices.push((delivery_idx, issue_index));
+        link_indices.push((delivery_idx, issue_index, config.issue_distance));
     }
 
     let standalone_count = standalone_issues.len();
@@ -189,48 +304,43 @@ fn compute_force_layout(
         } else {
             0.0
         };
-        let x = (DELIVERY_DISTANCE + ISSUE_DISTANCE) * angle.cos();
-        let y = (DELIVERY_DISTANCE + ISSUE_DISTANCE) * angle.sin();
+        let x = (config.delivery_distance + config.issue_distance) * angle.cos();
+        let y = (config.delivery_distance + config.issue_distance) * angle.sin();
 
         let issue_index = node_infos.len();
         node_infos.push(NodeInfo {
             id: issue.id.clone(),
             node_type: ForceNodeType::Issue,
             label: issue.category.as_str().to_string(),
-            radius: ISSUE_RADIUS,
+            radius: config.issue_radius,
             data: ForceNodeData::Issue {
                 category: issue.category.clone(),
                 resolved: issue.resolved,
+                resolved_at: issue.resolved_at,
                 reporter: issue.reporter_type.clone(),
             },
             initial_x: x,
             initial_y: y,
         });
-        radii.push(ISSUE_RADIUS);
+        radii.push(config.issue_radius);
 
         links.push(ForceLink {
             source: bike.id.clone(),
             target: issue.id.clone(),
-            strength: LINK_STRENGTH * 0.5,
+            strength: config.link_strength * 0.5,
         });
-        link_indices.push((0, issue_index));
+        link_indices.push((0, issue_index, config.delivery_distance + config.issue_distance));
     }
 
-    // 4. Create Fjädra nodes
-    let fixed_node_index = fixed_node.and_then(|(id, _, _)| {
-        node_infos.iter().position(|n| n.id == id)
-    });
-
+    // 4. Create Fjädra nodes, keeping pinned ones fixed
     let particles: Vec<Node> = node_infos
         .iter()
         .enumerate()
         .map(|(idx, info)| {
-            if let Some((fixed_id, fx, fy)) = fixed_node {
-                if info.id == fixed_id {
-                    return Node::default().fixed_position(fx, fy);
-                }
+            if let Some((_, px, py)) = pins.iter().find(|(id, _, _)| *id == info.id) {
+                return Node::default().fixed_position(*px, *py);
             }
-            if idx == 0 && fixed_node_index != Some(0) {
+            if idx == 0 {
                 return Node::default().fixed_position(0.0, 0.0);
             }
             Node::default().position(info.initial_x, info.initial_y)
@@ -239,20 +349,22 @@ fn compute_force_layout(
 
     // 5. Build and run simulation
     let radii_clone = radii.clone();
+    let padding = config.collision_padding;
+    let repulsion_strength = config.repulsion_strength;
     let mut simulation = SimulationBuilder::default()
         .build(particles)
-        .add_force("center", Center::new().strength(CENTER_STRENGTH))
+        .add_force("center", Center::new().strength(config.center_strength))
         .add_force(
             "charge",
-            ManyBody::new().strength(|_node_idx, _count| REPULSION_STRENGTH),
+            ManyBody::new().strength(move |_node_idx, _count| repulsion_strength),
         )
         .add_force(
             "collide",
             Collide::new()
-                .radius(move |i| radii_clone[i] + 5.0)
+                .radius(move |i| radii_clone[i] + padding)
                 .iterations(2),
         )
-        .add_force("links", Link::new(link_indices).iterations(3));
+        .add_force("links", force_graph_shared::link_force(&links, link_indices));
 
     simulation.step();
 
@@ -276,7 +388,7 @@ fn compute_force_layout(
         })
         .collect();
 
-    let bounds = compute_bounds(&nodes);
+    let bounds = force_graph_shared::compute_bounds(&nodes);
 
     Ok(ForceGraphData {
         nodes,
@@ -286,29 +398,3 @@ fn compute_force_layout(
         bounds,
     })
 }
-
-fn compute_bounds(nodes: &[ForceNode]) -> (f64, f64, f64, f64) {
-    if nodes.is_empty() {
-        return (0.0, 0.0, 0.0, 0.0);
-    }
-
-    let mut min_x = f64::MAX;
-    let mut max_x = f64::MIN;
-    let mut min_y = f64::MAX;
-    let mut max_y = f64::MIN;
-
-    for node in nodes {
-        min_x = min_x.min(node.x - node.radius);
-        max_x = max_x.max(node.x + node.radius);
-        min_y = min_y.min(node.y - node.radius);
-        max_y = max_y.max(node.y + node.radius);
-    }
-
-    let padding = 20.0;
-    (
-        min_x - padding,
-        max_x + padding,
-        min_y - padding,
-        max_y + padding,
-    )
-}
@@ -0,0 +1,838 @@
+//! Force Graph Helpers Shared by Both Backends
+//!
+//! # Purpose
+//! Incremental updates for the force graph: `compute_diff` compares two
+//! layouts, and `ForceGraphCache` keeps recent layouts so that
+//! `get_force_graph_diff` can find the one the client is showing.
+//! `render_svg` turns a layout into a standalone SVG for export, and
+//! `render_graphml` into GraphML for Gephi or Cytoscape.
+//! `compute_fleet_layout` builds the fleet-wide deliverer/restaurant graph,
+//! `push_issue_clusters` the issue clusters of a deliverer's graph, and
+//! `link_force` the spring force for a graph's links.
+
+use crate::models::{
+    Bike, Delivery, ForceGraphConfig, ForceGraphData, ForceGraphDiff, ForceLink, ForceNode,
+    ForceNodeData, ForceNodeType, Issue, IssueCategory,
+};
+use fjadra::force::{Center, Collide, Link, ManyBody, Node, SimulationBuilder};
+use quick_xml::escape::escape;
+use quick_xml::events::{BytesDecl, BytesText, Event};
+use quick_xml::Writer;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::f64::consts::PI;
+use std::fmt::Write;
+use std::sync::Mutex;
+
+/// Position tolerance used by `get_force_graph_diff`, in pixels
+pub const DIFF_POSITION_TOLERANCE: f64 = 0.5;
+
+/// Layouts kept per bike; older ones are evicted first
+const MAX_CACHED_LAYOUTS_PER_BIKE: usize = 8;
+
+/// Label size in exported SVGs, in layout pixels
+const SVG_FONT_SIZE: f64 = 12.0;
+
+/// Weaker centering than a single deliverer's graph, so clusters can drift apart
+pub const FLEET_CENTER_STRENGTH: f64 = 0.03;
+
+/// Twice the default repulsion, so deliverers sharing no restaurant separate
+pub const FLEET_REPULSION_STRENGTH: f64 = -600.0;
+
+/// GraphML namespace and schema location
+const GRAPHML_NAMESPACE: &str = "http://graphml.graphdrawing.org/xmlns";
+const GRAPHML_SCHEMA_LOCATION: &str =
+    "http://graphml.graphdrawing.org/xmlns http://graphml.graphdrawing.org/xmlns/1.0/graphml.xsd";
+
+/// GraphML attribute keys as (key ID, element it applies to, type)
+const GRAPHML_KEYS: [(&str, &str, &str); 6] = [
+    ("label", "node", "string"),
+    ("type", "node", "string"),
+    ("x", "node", "double"),
+    ("y", "node", "double"),
+    ("radius", "node", "double"),
+    ("strength", "edge", "double"),
+];
+
+/// Prefix of restaurant node IDs (restaurants have no ID of their own)
+const RESTAURANT_NODE_PREFIX: &str = "restaurant:";
+
+/// Recently computed layouts, keyed by bike ID and position hash
+#[derive(Default)]
+pub struct ForceGraphCache {
+    layouts: Mutex<HashMap<String, VecDeque<(u32, ForceGraphData)>>>,
+}
+
+impl ForceGraphCache {
+    /// Cached layout of `bike_id` with the given `ForceGraphData::position_hash`
+    pub fn get(&self, bike_id: &str, position_hash: u32) -> Option<ForceGraphData> {
+        self.layouts
+            .lock()
+            .unwrap()
+            .get(bike_id)?
+            .iter()
+            .find(|(hash, _)| *hash == position_hash)
+            .map(|(_, layout)| layout.clone())
+    }
+
+    /// Remember a layout, evicting the oldest one of the bike when full
+    pub fn insert(&self, bike_id: &str, layout: ForceGraphData) {
+        let position_hash = layout.position_hash();
+        let mut layouts = self.layouts.lock().unwrap();
+        let entries = layouts.entry(bike_id.to_string()).or_default();
+
+        entries.retain(|(hash, _)| *hash != position_hash);
+        if entries.len() == MAX_CACHED_LAYOUTS_PER_BIKE {
+            entries.pop_front();
+        }
+        entries.push_back((position_hash, layout));
+    }
+}
+
+/// Diff `new` against the cached layout matching `previous_position_hash`
+/// and cache `new` for the next call
+///
+/// An unknown hash (e.g. evicted, or the first call) yields a diff with
+/// every node in `added_nodes`, so the client does a full render.
+pub fn diff_against_cached(
+    cache: &ForceGraphCache,
+    bike_id: &str,
+    previous_position_hash: u32,
+    new: ForceGraphData,
+) -> ForceGraphDiff {
+    let empty = ForceGraphData {
+        nodes: Vec::new(),
+        links: Vec::new(),
+        center_x: new.center_x,
+        center_y: new.center_y,
+        bounds: (0.0, 0.0, 0.0, 0.0),
+    };
+    let old = cache.get(bike_id, previous_position_hash).unwrap_or(empty);
+
+    let diff = compute_diff(&old, &new, DIFF_POSITION_TOLERANCE);
+    cache.insert(bike_id, new);
+    diff
+}
+
+/// Compare two layouts of the same graph
+///
+/// A node is unchanged when both coordinates moved by less than
+/// `position_tolerance` and all other fields are equal. Nodes are matched
+/// by ID; the result keeps the order of `new` (removed IDs: order of `old`).
+pub fn compute_diff(
+    old: &ForceGraphData,
+    new: &ForceGraphData,
+    position_tolerance: f64,
+) -> ForceGraphDiff {
+    let old_nodes: HashMap<&str, &ForceNode> =
+        old.nodes.iter().map(|node| (node.id.as_str(), node)).collect();
+
+    let mut changed_nodes = Vec::new();
+    let mut unchanged_node_ids = Vec::new();
+    let mut added_nodes = Vec::new();
+
+    for node in &new.nodes {
+        match old_nodes.get(node.id.as_str()) {
+            Some(old_node) if node_unchanged(old_node, node, position_tolerance) => {
+                unchanged_node_ids.push(node.id.clone())
+            }
+            Some(_) => changed_nodes.push(node.clone()),
+            None => added_nodes.push(node.clone()),
+        }
+    }
+
+    let new_ids: HashSet<&str> = new.nodes.iter().map(|node| node.id.as_str()).collect();
+    let removed_node_ids = old
+        .nodes
+        .iter()
+        .filter(|old_node| !new_ids.contains(old_node.id.as_str()))
+        .map(|old_node| old_node.id.clone())
+        .collect();
+
+    let (a, b) = (old.bounds, new.bounds);
+    let bounds_changed = [(a.0, b.0), (a.1, b.1), (a.2, b.2), (a.3, b.3)]
+        .iter()
+        .any(|(old, new)| (old - new).abs() >= position_tolerance);
+
+    ForceGraphDiff {
+        changed_nodes,
+        unchanged_node_ids,
+        added_nodes,
+        removed_node_ids,
+        bounds_changed,
+        new_bounds: bounds_changed.then_some(new.bounds),
+        position_hash: new.position_hash(),
+    }
+}
+
+fn node_unchanged(old: &ForceNode, new: &ForceNode, position_tolerance: f64) -> bool {
+    (old.x - new.x).abs() < position_tolerance
+        && (old.y - new.y).abs() < position_tolerance
+        && old.node_type == new.node_type
+        && old.label == new.label
+        && old.radius == new.radius
+        && old.data == new.data
+}
+
+/// Render a layout as a standalone SVG document
+///
+/// The `viewBox` is the layout's `bounds` (which already include a margin
+/// around the outermost nodes) and `width` x `height` is the viewport, so
+/// the graph is scaled to fit without distorting it. Links are drawn
+/// first so nodes and labels sit on top.
+///
+/// # Errors
+/// If `width` or `height` is not a positive number
+pub fn render_svg(graph: &ForceGraphData, width: f64, height: f64) -> Result<String, String> {
+    if !(width.is_finite() && width > 0.0 && height.is_finite() && height > 0.0) {
+        return Err(format!(
+            "SVG size must be positive, got {} x {}",
+            width, height
+        ));
+    }
+
+    let (min_x, max_x, min_y, max_y) = graph.bounds;
+    let positions: HashMap<&str, (f64, f64)> = graph
+        .nodes
+        .iter()
+        .map(|node| (node.id.as_str(), (node.x, node.y)))
+        .collect();
+
+    // Writing to a String cannot fail
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{:.0}" height="{:.0}" viewBox="{:.2} {:.2} {:.2} {:.2}">"#,
+        width,
+        height,
+        min_x,
+        min_y,
+        (max_x - min_x).max(1.0),
+        (max_y - min_y).max(1.0),
+    );
+
+    svg.push_str(r##"  <g class="links" stroke="#9E9E9E" stroke-width="1.5">"##);
+    svg.push('\n');
+    for link in &graph.links {
+        let (Some(source), Some(target)) = (
+            positions.get(link.source.as_str()),
+            positions.get(link.target.as_str()),
+        ) else {
+            continue;
+        };
+        let _ = writeln!(
+            svg,
+            r#"    <line x1="{:.2}" y1="{:.2}" x2="{:.2}" y2="{:.2}"/>"#,
+            source.0, source.1, target.0, target.1
+        );
+    }
+    svg.push_str("  </g>\n");
+
+    svg.push_str(r#"  <g class="nodes">"#);
+    svg.push('\n');
+    for node in &graph.nodes {
+        let _ = writeln!(
+            svg,
+            r#"    <circle cx="{:.2}" cy="{:.2}" r="{:.2}" fill="{}"/>"#,
+            node.x,
+            node.y,
+            node.radius,
+            node_color(&node.node_type)
+        );
+    }
+    svg.push_str("  </g>\n");
+
+    let _ = writeln!(
+        svg,
+        r#"  <g class="labels" font-family="sans-serif" font-size="{}" text-anchor="middle">"#,
+        SVG_FONT_SIZE
+    );
+    for node in &graph.nodes {
+        let _ = writeln!(
+            svg,
+            r#"    <text x="{:.2}" y="{:.2}">{}</text>"#,
+            node.x,
+            node.y + node.radius + SVG_FONT_SIZE,
+            escape(node.label.as_str())
+        );
+    }
+    svg.push_str("  </g>\n</svg>\n");
+
+    Ok(svg)
+}
+
+/// Render a layout as a GraphML document (UTF-8)
+///
+/// Every node is a `<node>` with its ID and `label`, `type`, `x`, `y` and
+/// `radius` data; every link an undirected `<edge>` with its `strength`.
+/// The `<key>` declarations come first, as the GraphML schema requires.
+///
+/// # Errors
+/// Only if writing the XML fails, which an in-memory buffer does not
+pub fn render_graphml(graph: &ForceGraphData) -> Result<String, String> {
+    let mut writer = Writer::new_with_indent(Vec::new(), b' ', 2);
+
+    writer
+        .write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))
+        .map_err(|e| e.to_string())?;
+    writer
+        .create_element("graphml")
+        .with_attributes([
+            ("xmlns", GRAPHML_NAMESPACE),
+            ("xmlns:xsi", "http://www.w3.org/2001/XMLSchema-instance"),
+            ("xsi:schemaLocation", GRAPHML_SCHEMA_LOCATION),
+        ])
+        .write_inner_content(|writer| {
+            for (id, domain, attr_type) in GRAPHML_KEYS {
+                writer
+                    .create_element("key")
+                    .with_attributes([
+                        ("id", id),
+                        ("for", domain),
+                        ("attr.name", id),
+                        ("attr.type", attr_type),
+                    ])
+                    .write_empty()?;
+            }
+
+            writer
+                .create_element("graph")
+                .with_attributes([("id", "G"), ("edgedefault", "undirected")])
+                .write_inner_content(|writer| {
+                    for node in &graph.nodes {
+                        let data = [
+                            ("label", node.label.clone()),
+                            ("type", node_type_name(&node.node_type).to_string()),
+                            ("x", node.x.to_string()),
+                            ("y", node.y.to_string()),
+                            ("radius", node.radius.to_string()),
+                        ];
+                        writer
+                            .create_element("node")
+                            .with_attribute(("id", node.id.as_str()))
+                            .write_inner_content(|writer| write_graphml_data(writer, &data))?;
+                    }
+                    for link in &graph.links {
+                        writer
+                            .create_element("edge")
+                            .with_attributes([
+                                ("source", link.source.as_str()),
+                                ("target", link.target.as_str()),
+                            ])
+                            .write_inner_content(|writer| {
+                                write_graphml_data(writer, &[("strength", link.strength.to_string())])
+                            })?;
+                    }
+                    Ok(())
+                })?;
+            Ok(())
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut xml = String::from_utf8(writer.into_inner()).map_err(|e| e.to_string())?;
+    xml.push('\n');
+    Ok(xml)
+}
+
+/// `<data key="...">value</data>` for each (key, value)
+fn write_graphml_data(
+    writer: &mut Writer<Vec<u8>>,
+    data: &[(&str, String)],
+) -> std::io::Result<()> {
+    for (key, value) in data {
+        writer
+            .create_element("data")
+            .with_attribute(("key", *key))
+            .write_text_content(BytesText::new(value))?;
+    }
+    Ok(())
+}
+
+/// Node type as serialized to the client
+fn node_type_name(node_type: &ForceNodeType) -> &'static str {
+    match node_type {
+        ForceNodeType::Deliverer => "deliverer",
+        ForceNodeType::Delivery => "delivery",
+        ForceNodeType::Issue => "issue",
+        ForceNodeType::Restaurant => "restaurant",
+        ForceNodeType::IssueCluster => "issueCluster",
+    }
+}
+
+/// Fill color of a node in exported SVGs (Material palette, as in the UI)
+fn node_color(node_type: &ForceNodeType) -> &'static str {
+    match node_type {
+        ForceNodeType::Deliverer => "#2196F3",
+        ForceNodeType::Delivery => "#4CAF50",
+        ForceNodeType::Issue => "#F44336",
+        ForceNodeType::Restaurant => "#FF9800",
+        ForceNodeType::IssueCluster => "#E57373",
+    }
+}
+
+/// Intermediate node data while a deliverer's graph is being built
+#[derive(Clone)]
+pub struct NodeInfo {
+    pub id: String,
+    pub node_type: ForceNodeType,
+    pub label: String,
+    pub radius: f64,
+    pub data: ForceNodeData,
+    pub initial_x: f64,
+    pub initial_y: f64,
+}
+
+/// Build the spring force for a graph's links
+///
+/// Each entry of `link_indices` is `(source, target, distance)` and matches
+/// the `ForceLink` at the same position, whose `strength` is used as is.
+pub fn link_force(links: &[ForceLink], link_indices: Vec<(usize, usize, f64)>) -> Link {
+    let strengths: Vec<f64> = links.iter().map(|link| link.strength).collect();
+    let distances: Vec<f64> = link_indices.iter().map(|&(_, _, distance)| distance).collect();
+    Link::new(link_indices.into_iter().map(|(source, target, _)| (source, target)))
+        .distance(move |_: &_, i: usize| distances[i])
+        .strength(move |_: &_, i: usize| strengths[i])
+        .iterations(3)
+}
+
+/// Add one `IssueCluster` node per issue category, with its issues around it
+///
+/// Used by `ForceGraphClusterMode::ByIssueCategory`. Clusters sit on the
+/// outer ring and link to the deliverer (node 0); each issue links to its
+/// cluster only, so the caller must not add the issues again.
+pub fn push_issue_clusters(
+    bike_id: &str,
+    issues: &[Issue],
+    config: &ForceGraphConfig,
+    node_infos: &mut Vec<NodeInfo>,
+    radii: &mut Vec<f64>,
+    links: &mut Vec<ForceLink>,
+    link_indices: &mut Vec<(usize, usize, f64)>,
+) {
+    let mut clusters: Vec<(&IssueCategory, Vec<&Issue>)> = Vec::new();
+    for issue in issues {
+        match clusters.iter_mut().find(|(category, _)| **category == issue.category) {
+            Some((_, members)) => members.push(issue),
+            None => clusters.push((&issue.category, vec![issue])),
+        }
+    }
+
+    // Clusters in the outer ring, their issues around them
+    let ring = config.delivery_distance + config.issue_distance;
+    let member_distance = config.issue_distance / 2.0;
+    let cluster_count = clusters.len();
+    for (i, (category, members)) in clusters.iter().enumerate() {
+        let angle = (i as f64 / cluster_count as f64) * 2.0 * PI + PI / 4.0;
+        let cluster_x = ring * angle.cos();
+        let cluster_y = ring * angle.sin();
+        let count = members.len() as u32;
+        let radius = (count as f64).sqrt() * config.issue_radius;
+
+        let cluster_id = format!("cluster-{}", category.as_str());
+        let cluster_index = node_infos.len();
+        node_infos.push(NodeInfo {
+            id: cluster_id.clone(),
+            node_type: ForceNodeType::IssueCluster,
+            label: category.as_str().to_string(),
+            radius,
+            data: ForceNodeData::IssueCluster {
+                category: (*category).clone(),
+                count,
+            },
+            initial_x: cluster_x,
+            initial_y: cluster_y,
+        });
+        radii.push(radius);
+
+        // Link: deliverer -> cluster
+        links.push(ForceLink {
+            source: bike_id.to_string(),
+            target: cluster_id.clone(),
+            strength: config.link_strength * 0.5,
+        });
+        link_indices.push((0, cluster_index, ring));
+
+        for (j, issue) in members.iter().enumerate() {
+            let member_angle = (j as f64 / members.len() as f64) * 2.0 * PI;
+            let issue_index = node_infos.len();
+            node_infos.push(NodeInfo {
+                id: issue.id.clone(),
+                node_type: ForceNodeType::Issue,
+                label: issue.category.as_str().to_string(),
+                radius: config.issue_radius,
+                data: ForceNodeData::Issue {
+                    category: issue.category.clone(),
+                    resolved: issue.resolved,
+                    resolved_at: issue.resolved_at,
+                    reporter: issue.reporter_type.clone(),
+                },
+                initial_x: cluster_x + member_distance * member_angle.cos(),
+                initial_y: cluster_y + member_distance * member_angle.sin(),
+            });
+            radii.push(config.issue_radius);
+
+            // Link: cluster -> issue
+            links.push(ForceLink {
+                source: cluster_id.clone(),
+                target: issue.id.clone(),
+                strength: config.link_strength * 0.8,
+            });
+            link_indices.push((cluster_index, issue_index, member_distance));
+        }
+    }
+}
+
+/// Compute the fleet-wide force graph: one node per deliverer and per restaurant
+///
+/// # Graph Structure
+/// Deliveries are grouped by `restaurant_name`; each deliverer is linked
+/// once to every restaurant it delivered for. Deliverers sharing
+/// restaurants are pulled together, the rest drift apart.
+///
+/// # Initial Positions
+/// Restaurants start on an inner ring, deliverers on an outer one. Unlike
+/// the single-deliverer graph no node is fixed at the center.
+pub fn compute_fleet_layout(bikes: &[Bike], deliveries: &[Delivery]) -> ForceGraphData {
+    let config = ForceGraphConfig::default();
+    let mut nodes: Vec<ForceNode> = Vec::with_capacity(bikes.len());
+    let mut links: Vec<ForceLink> = Vec::new();
+    let mut link_indices: Vec<(usize, usize, f64)> = Vec::new();
+
+    // 1. Deliverers on the outer ring
+    let deliverer_ring = config.delivery_distance * 2.0;
+    let mut deliverer_index: HashMap<&str, usize> = HashMap::new();
+    for (i, bike) in bikes.iter().enumerate() {
+        let angle = (i as f64 / bikes.len() as f64) * 2.0 * PI;
+        deliverer_index.insert(bike.id.as_str(), nodes.len());
+        nodes.push(ForceNode {
+            id: bike.id.clone(),
+            node_type: ForceNodeType::Deliverer,
+            label: bike.name.clone(),
+            x: deliverer_ring * angle.cos(),
+            y: deliverer_ring * angle.sin(),
+            radius: config.deliverer_radius,
+            data: ForceNodeData::Deliverer {
+                name: bike.name.clone(),
+                status: bike.status.clone(),
+            },
+        });
+    }
+
+    // 2. Restaurants on the inner ring, sorted by name for a stable layout
+    let mut delivery_counts: BTreeMap<&str, u32> = BTreeMap::new();
+    for delivery in deliveries {
+        *delivery_counts.entry(delivery.restaurant_name.as_str()).or_default() += 1;
+    }
+    let mut restaurant_index: HashMap<&str, usize> = HashMap::new();
+    for (i, (name, delivery_count)) in delivery_counts.iter().enumerate() {
+        let angle = (i as f64 / delivery_counts.len() as f64) * 2.0 * PI;
+        restaurant_index.insert(name, nodes.len());
+        nodes.push(ForceNode {
+            id: format!("{}{}", RESTAURANT_NODE_PREFIX, name),
+            node_type: ForceNodeType::Restaurant,
+            label: name.to_string(),
+            x: config.delivery_distance * angle.cos(),
+            y: config.delivery_distance * angle.sin(),
+            radius: config.delivery_radius,
+            data: ForceNodeData::Restaurant {
+                name: name.to_string(),
+                delivery_count: *delivery_count,
+            },
+        });
+    }
+
+    // 3. One link per deliverer/restaurant pair
+    let mut linked: HashSet<(usize, usize)> = HashSet::new();
+    for delivery in deliveries {
+        let Some(&source) = deliverer_index.get(delivery.bike_id.as_str()) else {
+            continue; // delivery of a deleted bike
+        };
+        let target = restaurant_index[delivery.restaurant_name.as_str()];
+        if linked.insert((source, target)) {
+            links.push(ForceLink {
+                source: nodes[source].id.clone(),
+                target: nodes[target].id.clone(),
+                strength: config.link_strength,
+            });
+            link_indices.push((source, target, config.delivery_distance));
+        }
+    }
+
+    // 4. Run the simulation
+    let radii: Vec<f64> = nodes.iter().map(|node| node.radius).collect();
+    let padding = config.collision_padding;
+    let particles: Vec<Node> = nodes
+        .iter()
+        .map(|node| Node::default().position(node.x, node.y))
+        .collect();
+    let mut simulation = SimulationBuilder::default()
+        .build(particles)
+        .add_force("center", Center::new().strength(FLEET_CENTER_STRENGTH))
+        .add_force(
+            "charge",
+            ManyBody::new().strength(|_node_idx, _count| FLEET_REPULSION_STRENGTH),
+        )
+        .add_force(
+            "collide",
+            Collide::new()
+                .radius(move |i| radii[i] + padding)
+                .iterations(2),
+        )
+        .add_force("links", link_force(&links, link_indices));
+    simulation.step();
+
+    for (node, [x, y]) in nodes.iter_mut().zip(simulation.positions()) {
+        node.x = x;
+        node.y = y;
+    }
+
+    let bounds = compute_bounds(&nodes);
+    ForceGraphData {
+        nodes,
+        links,
+        center_x: 0.0,
+        center_y: 0.0,
+        bounds,
+    }
+}
+
+/// Bounding box of all nodes, with a 20px margin
+pub(crate) fn compute_bounds(nodes: &[ForceNode]) -> (f64, f64, f64, f64) {
+    if nodes.is_empty() {
+        return (0.0, 0.0, 0.0, 0.0);
+    }
+
+    let padding = 20.0;
+    nodes.iter().fold(
+        (f64::MAX, f64::MIN, f64::MAX, f64::MIN),
+        |(min_x, max_x, min_y, max_y), node| {
+            (
+                min_x.min(node.x - node.radius - padding),
+                max_x.max(node.x + node.radius + padding),
+                min_y.min(node.y - node.radius - padding),
+                max_y.max(node.y + node.radius + padding),
+            )
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{BikeStatus, ForceLink, ForceNodeData};
+
+    fn node(id: &str, x: f64, y: f64) -> ForceNode {
+        ForceNode {
+            id: id.to_string(),
+            node_type: ForceNodeType::Deliverer,
+            label: id.to_string(),
+            x,
+            y,
+            radius: 40.0,
+            data: ForceNodeData::Deliverer {
+                name: id.to_string(),
+                status: BikeStatus::Available,
+            },
+        }
+    }
+
+    fn graph(nodes: Vec<ForceNode>) -> ForceGraphData {
+        ForceGraphData {
+            nodes,
+            links: Vec::new(),
+            center_x: 0.0,
+            center_y: 0.0,
+            bounds: (-100.0, 100.0, -100.0, 100.0),
+        }
+    }
+
+    #[test]
+    fn test_compute_diff_classifies_nodes() {
+        let old = graph(vec![node("a", 0.0, 0.0), node("b", 10.0, 10.0), node("c", 5.0, 5.0)]);
+        let new = graph(vec![node("a", 0.2, -0.3), node("b", 12.0, 10.0), node("d", 1.0, 1.0)]);
+
+        let diff = compute_diff(&old, &new, 0.5);
+
+        assert_eq!(diff.unchanged_node_ids, vec!["a"]);
+        assert_eq!(diff.changed_nodes.len(), 1);
+        assert_eq!(diff.changed_nodes[0].id, "b");
+        assert_eq!(diff.added_nodes.len(), 1);
+        assert_eq!(diff.added_nodes[0].id, "d");
+        assert_eq!(diff.removed_node_ids, vec!["c"]);
+        assert!(!diff.bounds_changed);
+        assert!(diff.new_bounds.is_none());
+        assert_eq!(diff.position_hash, new.position_hash());
+    }
+
+    #[test]
+    fn test_diff_against_cached_uses_previous_hash() {
+        let cache = ForceGraphCache::default();
+        let first = graph(vec![node("a", 0.0, 0.0)]);
+        let first_hash = first.position_hash();
+
+        // Unknown hash: everything is new
+        let diff = diff_against_cached(&cache, "BIKE-0001", 0, first.clone());
+        assert_eq!(diff.added_nodes.len(), 1);
+
+        let diff = diff_against_cached(&cache, "BIKE-0001", first_hash, first);
+        assert_eq!(diff.unchanged_node_ids, vec!["a"]);
+        assert!(diff.added_nodes.is_empty());
+    }
+
+    #[test]
+    fn test_render_svg_is_well_formed_xml() {
+        let mut issue = node("ISS-0001", 50.0, 50.0);
+        issue.node_type = ForceNodeType::Issue;
+        issue.label = "Late & cold <pizza>".to_string();
+        let mut layout = graph(vec![node("BIKE-0001", 0.0, 0.0), issue]);
+        layout.links.push(ForceLink {
+            source: "BIKE-0001".to_string(),
+            target: "ISS-0001".to_string(),
+            strength: 0.7,
+        });
+
+        let svg = render_svg(&layout, 800.0, 600.0).unwrap();
+
+        let doc = roxmltree::Document::parse(&svg).expect("well-formed XML");
+        let root = doc.root_element();
+        assert_eq!(root.tag_name().name(), "svg");
+        assert_eq!(root.attribute("viewBox"), Some("-100.00 -100.00 200.00 200.00"));
+
+        let count = |tag: &str| root.descendants().filter(|n| n.has_tag_name(tag)).count();
+        assert_eq!((count("circle"), count("line"), count("text")), (2, 1, 2));
+
+        let fills: Vec<&str> = root
+            .descendants()
+            .filter(|n| n.has_tag_name("circle"))
+            .filter_map(|n| n.attribute("fill"))
+            .collect();
+        assert_eq!(fills, vec!["#2196F3", "#F44336"]);
+
+        let labels: Vec<&str> = root
+            .descendants()
+            .filter(|n| n.has_tag_name("text"))
+            .filter_map(|n| n.text())
+            .collect();
+        assert_eq!(labels[1], "Late & cold <pizza>");
+    }
+
+    #[test]
+    fn test_render_svg_rejects_empty_viewport() {
+        assert!(render_svg(&graph(vec![]), 0.0, 600.0).is_err());
+        assert!(render_svg(&graph(vec![]), 800.0, f64::NAN).is_err());
+    }
+
+    #[test]
+    fn test_render_graphml_matches_reference() {
+        let mut issue = node("ISS-0001", 50.5, -25.0);
+        issue.node_type = ForceNodeType::Issue;
+        issue.label = "Late & cold <pizza>".to_string();
+        issue.radius = 20.0;
+        let mut layout = graph(vec![node("BIKE-0001", 0.0, 0.0), issue]);
+        layout.links.push(ForceLink {
+            source: "BIKE-0001".to_string(),
+            target: "ISS-0001".to_string(),
+            strength: 0.7,
+        });
+
+        let graphml = render_graphml(&layout).unwrap();
+
+        let reference = r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:schemaLocation="http://graphml.graphdrawing.org/xmlns http://graphml.graphdrawing.org/xmlns/1.0/graphml.xsd">
+  <key id="label" for="node" attr.name="label" attr.type="string"/>
+  <key id="type" for="node" attr.name="type" attr.type="string"/>
+  <key id="x" for="node" attr.name="x" attr.type="double"/>
+  <key id="y" for="node" attr.name="y" attr.type="double"/>
+  <key id="radius" for="node" attr.name="radius" attr.type="double"/>
+  <key id="strength" for="edge" attr.name="strength" attr.type="double"/>
+  <graph id="G" edgedefault="undirected">
+    <node id="BIKE-0001">
+      <data key="label">BIKE-0001</data>
+      <data key="type">deliverer</data>
+      <data key="x">0</data>
+      <data key="y">0</data>
+      <data key="radius">40</data>
+    </node>
+    <node id="ISS-0001">
+      <data key="label">Late &amp; cold &lt;pizza&gt;</data>
+      <data key="type">issue</data>
+      <data key="x">50.5</data>
+      <data key="y">-25</data>
+      <data key="radius">20</data>
+    </node>
+    <edge source="BIKE-0001" target="ISS-0001">
+      <data key="strength">0.7</data>
+    </edge>
+  </graph>
+</graphml>
+"#;
+        assert_eq!(graphml, reference);
+
+        let doc = roxmltree::Document::parse(&graphml).expect("well-formed XML");
+        let root = doc.root_element();
+        assert_eq!(root.tag_name().namespace(), Some(GRAPHML_NAMESPACE));
+        let label = root
+            .descendants()
+            .find(|n| n.has_tag_name("data") && n.text() == Some("Late & cold <pizza>"));
+        assert!(label.is_some());
+    }
+
+    #[test]
+    fn test_fleet_layout_shares_restaurant_nodes() {
+        let now = chrono::Utc::now();
+        let bike = |id: &str| Bike {
+            id: id.to_string(),
+            name: id.to_string(),
+            status: BikeStatus::InUse,
+            latitude: 52.3791,
+            longitude: 4.9003,
+            battery_level: Some(80),
+            last_maintenance: None,
+            total_trips: 0,
+            total_distance_km: 0.0,
+            reservation_expires_at: None,
+            maintenance_threshold_override: None,
+            deleted_at: None,
+            next_maintenance_at: None,
+            created_at: now,
+            updated_at: now,
+        };
+        let delivery = |id: &str, bike_id: &str, restaurant: &str| Delivery {
+            id: id.to_string(),
+            bike_id: bike_id.to_string(),
+            status: crate::models::DeliveryStatus::Completed,
+            priority: crate::models::DeliveryPriority::Normal,
+            customer_name: "P. de Vries".to_string(),
+            customer_address: "Damrak 1".to_string(),
+            restaurant_name: restaurant.to_string(),
+            restaurant_address: "Rokin 2".to_string(),
+            rating: None,
+            complaint: None,
+            created_at: now,
+            completed_at: None,
+        };
+        let bikes = [bike("BIKE-0001"), bike("BIKE-0002")];
+        let deliveries = [
+            delivery("DEL-0001", "BIKE-0001", "Febo"),
+            delivery("DEL-0002", "BIKE-0001", "Febo"),
+            delivery("DEL-0003", "BIKE-0002", "Febo"),
+            delivery("DEL-0004", "BIKE-0002", "Wok to Walk"),
+        ];
+
+        let graph = compute_fleet_layout(&bikes, &deliveries);
+
+        let restaurants: Vec<&ForceNode> = graph
+            .nodes
+            .iter()
+            .filter(|n| n.node_type == ForceNodeType::Restaurant)
+            .collect();
+        assert_eq!(graph.nodes.len(), 4);
+        assert_eq!(
+            restaurants[0].data,
+            ForceNodeData::Restaurant { name: "Febo".to_string(), delivery_count: 3 }
+        );
+        // BIKE-0001 delivered twice for Febo but is linked once
+        assert_eq!(graph.links.len(), 3);
+        assert!(graph.nodes.iter().all(|n| n.x.is_finite() && n.y.is_finite()));
+    }
+}
+
@@ -1,28 +1,198 @@
+use crate::license::{self, EncryptedLicenseStorage};
+use crate::models::PoolMetrics;
+use crate::AppState;
 use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
 
 /// Health check response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HealthStatus {
+    /// "healthy", or "degraded" while the license is invalid
     pub status: String,
     pub version: String,
     pub rust_version: String,
     pub tauri_version: String,
     pub timestamp: String,
+    /// Latest connection pool sample (PostgreSQL backend only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pool: Option<PoolMetrics>,
+    /// Always true in a returned status; see `health_check`
+    pub database_ok: bool,
+    /// The installed license (or the trial, if none is installed) is valid
+    pub license_valid: bool,
+    /// Idle pool connections (PostgreSQL backend only)
+    pub pool_available: Option<u32>,
+    /// Resident memory of the process (Linux only)
+    pub memory_usage_bytes: Option<u64>,
 }
 
-/// Health check command to verify the Rust backend is running
+/// Liveness probe: versions, database connectivity and license status
+///
+/// # Errors
+/// "503 Service Unavailable" when the database doesn't answer (or isn't
+/// initialized yet), so a supervisor polling this command can restart
+/// the process. An invalid license only marks the status "degraded".
 #[tauri::command]
-pub fn health_check() -> HealthStatus {
-    HealthStatus {
-        status: "healthy".to_string(),
+pub async fn health_check(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<HealthStatus, String> {
+    let database_ok = database_ok(&state).await;
+    let pool = latest_pool_metrics(&app_handle);
+
+    evaluate_health(
+        database_ok,
+        license_valid(&app_handle),
+        pool,
+        memory_usage_bytes(),
+    )
+}
+
+/// Build the status from the individual checks
+fn evaluate_health(
+    database_ok: bool,
+    license_valid: bool,
+    pool: Option<PoolMetrics>,
+    memory_usage_bytes: Option<u64>,
+) -> Result<HealthStatus, String> {
+    if !database_ok {
+        return Err("503 Service Unavailable: database is not reachable".to_string());
+    }
+
+    Ok(HealthStatus {
+        status: if license_valid { "healthy" } else { "degraded" }.to_string(),
         version: env!("CARGO_PKG_VERSION").to_string(),
         rust_version: rustc_version(),
         tauri_version: "1.8".to_string(),
         timestamp: chrono::Utc::now().to_rfc3339(),
-    }
+        pool_available: pool.as_ref().map(|p| p.available as u32),
+        pool,
+        database_ok,
+        license_valid,
+        memory_usage_bytes,
+    })
 }
 
 /// Get the Rust compiler version (simplified)
 fn rustc_version() -> String {
     "1.70+".to_string()
 }
+
+/// `SELECT 1` on the open connection
+#[cfg(feature = "sqlite")]
+async fn database_ok(state: &State<'_, AppState>) -> bool {
+    match state.db.lock() {
+        Ok(db_guard) => db_guard.as_ref().is_some_and(|db| db.ping().is_ok()),
+        Err(_) => false,
+    }
+}
+
+/// `Database::health_check` (a replica answering counts as reachable)
+#[cfg(feature = "postgres")]
+async fn database_ok(state: &State<'_, AppState>) -> bool {
+    // Clone the pool handle so the lock isn't held across the query
+    let db = match state.db.lock() {
+        Ok(db_guard) => db_guard.clone(),
+        Err(_) => return false,
+    };
+    match db {
+        Some(db) => db.health_check().await.is_ok(),
+        None => false,
+    }
+}
+
+/// Whether the stored license, or the trial without one, is valid
+fn license_valid(app_handle: &AppHandle) -> bool {
+    let Ok(app_data_dir) = app_handle.path().app_data_dir() else {
+        return false;
+    };
+    let fingerprint = license::machine_fingerprint(&app_data_dir);
+    let storage = EncryptedLicenseStorage::new(app_data_dir);
+
+    license::stored_license_status(&storage, &fingerprint).valid
+}
+
+/// Resident set size from `/proc/self/status`
+#[cfg(target_os = "linux")]
+fn memory_usage_bytes() -> Option<u64> {
+    parse_vm_rss(&std::fs::read_to_string("/proc/self/status").ok()?)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn memory_usage_bytes() -> Option<u64> {
+    None
+}
+
+/// `VmRSS:     12345 kB` line of a `/proc/<pid>/status` file, in bytes
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_vm_rss(status: &str) -> Option<u64> {
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kilobytes: u64 = line
+        .trim_start_matches("VmRSS:")
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse()
+        .ok()?;
+    Some(kilobytes * 1024)
+}
+
+/// Last sample of the pool metrics monitor, if it has taken one yet
+#[cfg(feature = "postgres")]
+fn latest_pool_metrics(app_handle: &AppHandle) -> Option<PoolMetrics> {
+    let monitor = app_handle.try_state::<super::database_pg::PoolMetricsMonitor>()?;
+    let latest = monitor.latest.lock().ok()?;
+    latest.clone()
+}
+
+/// SQLite has no connection pool
+#[cfg(feature = "sqlite")]
+fn latest_pool_metrics(_app_handle: &AppHandle) -> Option<PoolMetrics> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool_with_available(available: usize) -> PoolMetrics {
+        PoolMetrics {
+            pool_size: 4,
+            available,
+            waiting: 0,
+            max_size: 16,
+            total_acquired: 10,
+            total_recycled: 6,
+            replication_lag_bytes: None,
+        }
+    }
+
+    #[test]
+    fn test_unreachable_database_is_unavailable() {
+        let err = evaluate_health(false, true, None, Some(1024)).unwrap_err();
+
+        assert!(err.starts_with("503 Service Unavailable"));
+    }
+
+    #[test]
+    fn test_invalid_license_is_degraded() {
+        let status = evaluate_health(true, false, Some(pool_with_available(3)), None).unwrap();
+
+        assert_eq!(status.status, "degraded");
+        assert!(status.database_ok);
+        assert!(!status.license_valid);
+        assert_eq!(status.pool_available, Some(3));
+
+        let healthy = evaluate_health(true, true, None, None).unwrap();
+        assert_eq!(healthy.status, "healthy");
+        assert_eq!(healthy.pool_available, None);
+    }
+
+    #[test]
+    fn test_parse_vm_rss() {
+        let status = "Name:\tamsterdam-bike\nVmPeak:\t  204800 kB\nVmRSS:\t   51200 kB\nThreads:\t12\n";
+
+        assert_eq!(parse_vm_rss(status), Some(51200 * 1024));
+        assert_eq!(parse_vm_rss("Name:\tkthreadd\n"), None);
+    }
+}
@@ -15,7 +15,12 @@
 //! - Or directly to the deliverer (if standalone issue)
 
 use crate::database::DatabaseError;
-use crate::models::Issue;
+use crate::models::{
+    CreateIssueRequest, Issue, IssuePage, IssueSort, IssueStatistics, SortDirection,
+    DEFAULT_PAGE_SIZE,
+};
+use super::deliveries::parse_timestamp_arg;
+use crate::sync_queue::SyncOperation;
 use crate::AppState;
 use tauri::State;
 
@@ -25,16 +30,33 @@ use tauri::State;
 /// - `bike_id`: Filter by deliverer (optional)
 /// - `resolved`: Filter by resolution status (optional)
 /// - `category`: Filter by issue category (optional)
+/// - `from_ts`/`to_ts`: Only issues created in this RFC 3339 window,
+///   inclusive (optional)
+/// - `sort_by`: "created_at" (default), "category" or "resolved" (optional)
+/// - `sort_dir`: "asc" or "desc"; defaults to newest first for "created_at",
+///   ascending otherwise (optional)
+/// - `cursor`: `nextCursor` of the previous page (optional)
+/// - `limit`: Page size, 1-500 (default 50)
 ///
 /// # Returns
-/// Vec<Issue> - List of issues matching filters, sorted by created_at DESC
+/// IssuePage - One page of issues matching filters, in the requested order
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub fn get_issues(
     state: State<'_, AppState>,
     bike_id: Option<String>,
     resolved: Option<bool>,
     category: Option<String>,
-) -> Result<Vec<Issue>, DatabaseError> {
+    from_ts: Option<String>,
+    to_ts: Option<String>,
+    sort_by: Option<IssueSort>,
+    sort_dir: Option<SortDirection>,
+    cursor: Option<String>,
+    limit: Option<u32>,
+) -> Result<IssuePage, DatabaseError> {
+    let from = parse_timestamp_arg(from_ts.as_deref())?;
+    let to = parse_timestamp_arg(to_ts.as_deref())?;
+
     let db_guard = state.db.lock().unwrap();
     let db = db_guard
         .as_ref()
@@ -44,6 +66,12 @@ pub fn get_issues(
         bike_id.as_deref(),
         resolved,
         category.as_deref(),
+        from,
+        to,
+        sort_by,
+        sort_dir,
+        cursor.as_deref(),
+        limit.unwrap_or(DEFAULT_PAGE_SIZE),
     )
 }
 
@@ -61,6 +89,23 @@ pub fn get_issue_by_id(
     db.get_issue_by_id(&issue_id)
 }
 
+/// Get issue counts, breakdowns and resolution times for the dashboard
+///
+/// # Arguments
+/// - `bike_id`: Limit to one deliverer (optional, default the whole fleet)
+#[tauri::command]
+pub fn get_issue_statistics(
+    state: State<'_, AppState>,
+    bike_id: Option<String>,
+) -> Result<IssueStatistics, DatabaseError> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard
+        .as_ref()
+        .ok_or(DatabaseError::NotInitialized)?;
+
+    db.get_issue_statistics(bike_id.as_deref())
+}
+
 /// Get issues for a specific bike (for force graph)
 ///
 /// # Force Graph Usage
@@ -80,3 +125,124 @@ pub fn get_issues_for_bike(
 
     db.get_issues_by_bike(&bike_id)
 }
+
+/// Mark an issue as resolved
+///
+/// # Arguments
+/// - `issue_id`: Issue to resolve
+/// - `note`: How the issue was resolved (optional)
+///
+/// # Returns
+/// The updated issue; `resolved_at` records when it was resolved
+#[tauri::command]
+pub fn resolve_issue(
+    state: State<'_, AppState>,
+    issue_id: String,
+    note: Option<String>,
+) -> Result<Issue, DatabaseError> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard
+        .as_ref()
+        .ok_or(DatabaseError::NotInitialized)?;
+
+    db.queued_write(
+        || db.resolve_issue(&issue_id, note.as_deref()),
+        &SyncOperation::ResolveIssue {
+            issue_id: issue_id.clone(),
+            note: note.clone(),
+        },
+    )
+}
+
+/// Reopen a resolved issue
+///
+/// # Returns
+/// The updated issue, with `resolved_at` and `resolution_note` cleared
+#[tauri::command]
+pub fn reopen_issue(
+    state: State<'_, AppState>,
+    issue_id: String,
+) -> Result<Issue, DatabaseError> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard
+        .as_ref()
+        .ok_or(DatabaseError::NotInitialized)?;
+
+    db.reopen_issue(&issue_id)
+}
+
+/// Resolve several issues at once
+///
+/// # Arguments
+/// - `issue_ids`: 1-100 issue IDs; larger sets should be sent in pages
+///
+/// # Returns
+/// Number of issues resolved (unknown or already resolved IDs are skipped)
+#[tauri::command]
+pub fn batch_resolve_issues(
+    state: State<'_, AppState>,
+    issue_ids: Vec<String>,
+) -> Result<u32, DatabaseError> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard
+        .as_ref()
+        .ok_or(DatabaseError::NotInitialized)?;
+
+    let ids: Vec<&str> = issue_ids.iter().map(String::as_str).collect();
+    db.batch_resolve_issues(&ids)
+}
+
+/// Reopen several resolved issues at once
+///
+/// # Returns
+/// Number of issues reopened (unknown or unresolved IDs are skipped)
+#[tauri::command]
+pub fn batch_reopen_issues(
+    state: State<'_, AppState>,
+    issue_ids: Vec<String>,
+) -> Result<u32, DatabaseError> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard
+        .as_ref()
+        .ok_or(DatabaseError::NotInitialized)?;
+
+    let ids: Vec<&str> = issue_ids.iter().map(String::as_str).collect();
+    db.batch_reopen_issues(&ids)
+}
+
+/// Report a new issue
+///
+/// # Arguments
+/// - `request.deliveryId`: Delivery the issue is about, or null for a
+///   standalone issue on the deliverer
+/// - `request.category`: Suggested from the description when omitted
+///
+/// # Returns
+/// The stored issue; it appears in the bike's force graph on the next
+/// layout request
+#[tauri::command]
+pub fn create_issue(
+    state: State<'_, AppState>,
+    request: CreateIssueRequest,
+) -> Result<Issue, DatabaseError> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard
+        .as_ref()
+        .ok_or(DatabaseError::NotInitialized)?;
+
+    db.create_issue(&request)
+}
+
+/// Delete an issue
+#[tauri::command]
+pub fn delete_issue(
+    state: State<'_, AppState>,
+    issue_id: String,
+) -> Result<(), DatabaseError> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard
+        .as_ref()
+        .ok_or(DatabaseError::NotInitialized)?;
+
+    db.delete_issue(&issue_id)
+}
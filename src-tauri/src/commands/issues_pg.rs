@@ -3,22 +3,47 @@
 //! Async versions of issue commands for PostgreSQL backend.
 
 use crate::database_pg::DatabaseError;
-use crate::models::Issue;
+use crate::models::{
+    CreateIssueRequest, Issue, IssuePage, IssueSort, IssueStatistics, SortDirection,
+    DEFAULT_PAGE_SIZE,
+};
+use super::deliveries_pg::parse_timestamp_arg;
 use crate::AppState;
 use tauri::State;
 
-/// Get all issues with optional filtering
+/// Get one page of issues with optional filtering (default page size 50)
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn get_issues(
     state: State<'_, AppState>,
     bike_id: Option<String>,
     resolved: Option<bool>,
     category: Option<String>,
-) -> Result<Vec<Issue>, DatabaseError> {
+    from_ts: Option<String>,
+    to_ts: Option<String>,
+    sort_by: Option<IssueSort>,
+    sort_dir: Option<SortDirection>,
+    cursor: Option<String>,
+    limit: Option<u32>,
+) -> Result<IssuePage, DatabaseError> {
+    let from = parse_timestamp_arg(from_ts.as_deref())?;
+    let to = parse_timestamp_arg(to_ts.as_deref())?;
+
     let db_guard = state.db.lock().unwrap();
     let db = db_guard.as_ref().ok_or(DatabaseError::NotInitialized)?;
 
-    db.get_issues(bike_id.as_deref(), resolved, category.as_deref()).await
+    db.get_issues(
+        bike_id.as_deref(),
+        resolved,
+        category.as_deref(),
+        from,
+        to,
+        sort_by,
+        sort_dir,
+        cursor.as_deref(),
+        limit.unwrap_or(DEFAULT_PAGE_SIZE),
+    )
+    .await
 }
 
 /// Get a single issue by ID
@@ -33,6 +58,18 @@ pub async fn get_issue_by_id(
     db.get_issue_by_id(&issue_id).await
 }
 
+/// Get issue counts, breakdowns and resolution times for the dashboard
+#[tauri::command]
+pub async fn get_issue_statistics(
+    state: State<'_, AppState>,
+    bike_id: Option<String>,
+) -> Result<IssueStatistics, DatabaseError> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or(DatabaseError::NotInitialized)?;
+
+    db.get_issue_statistics(bike_id.as_deref()).await
+}
+
 /// Get issues for a specific bike (for force graph)
 #[tauri::command]
 pub async fn get_issues_for_bike(
@@ -44,3 +81,78 @@ pub async fn get_issues_for_bike(
 
     db.get_issues_by_bike(&bike_id).await
 }
+
+/// Mark an issue as resolved, with an optional resolution note
+#[tauri::command]
+pub async fn resolve_issue(
+    state: State<'_, AppState>,
+    issue_id: String,
+    note: Option<String>,
+) -> Result<Issue, DatabaseError> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or(DatabaseError::NotInitialized)?;
+
+    db.resolve_issue(&issue_id, note.as_deref()).await
+}
+
+/// Reopen a resolved issue, clearing its resolution time and note
+#[tauri::command]
+pub async fn reopen_issue(
+    state: State<'_, AppState>,
+    issue_id: String,
+) -> Result<Issue, DatabaseError> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or(DatabaseError::NotInitialized)?;
+
+    db.reopen_issue(&issue_id).await
+}
+
+/// Resolve several issues at once (1-100 IDs), returning how many changed
+#[tauri::command]
+pub async fn batch_resolve_issues(
+    state: State<'_, AppState>,
+    issue_ids: Vec<String>,
+) -> Result<u32, DatabaseError> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or(DatabaseError::NotInitialized)?;
+
+    let ids: Vec<&str> = issue_ids.iter().map(String::as_str).collect();
+    db.batch_resolve_issues(&ids).await
+}
+
+/// Reopen several resolved issues at once (1-100 IDs), returning how many changed
+#[tauri::command]
+pub async fn batch_reopen_issues(
+    state: State<'_, AppState>,
+    issue_ids: Vec<String>,
+) -> Result<u32, DatabaseError> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or(DatabaseError::NotInitialized)?;
+
+    let ids: Vec<&str> = issue_ids.iter().map(String::as_str).collect();
+    db.batch_reopen_issues(&ids).await
+}
+
+/// Report a new issue, standalone or linked to a delivery
+#[tauri::command]
+pub async fn create_issue(
+    state: State<'_, AppState>,
+    request: CreateIssueRequest,
+) -> Result<Issue, DatabaseError> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or(DatabaseError::NotInitialized)?;
+
+    db.create_issue(&request).await
+}
+
+/// Delete an issue
+#[tauri::command]
+pub async fn delete_issue(
+    state: State<'_, AppState>,
+    issue_id: String,
+) -> Result<(), DatabaseError> {
+    let db_guard = state.db.lock().unwrap();
+    let db = db_guard.as_ref().ok_or(DatabaseError::NotInitialized)?;
+
+    db.delete_issue(&issue_id).await
+}
@@ -1,6 +1,6 @@
 //! Tauri commands for license management
 
-use crate::license::{self, LicenseStatus, LicenseStorage};
+use crate::license::{self, EncryptedLicenseStorage, LicenseReport, LicenseStatus};
 use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Manager};
 
@@ -25,7 +25,8 @@ pub async fn activate_license(
         .app_data_dir()
         .map_err(|e| format!("Failed to get app data directory: {}", e))?;
 
-    let storage = LicenseStorage::new(app_data_dir);
+    let fingerprint = license::machine_fingerprint(&app_data_dir);
+    let storage = EncryptedLicenseStorage::new(app_data_dir);
 
     // Verify the license
     let status = license::get_license_status(&license_key);
@@ -33,7 +34,7 @@ pub async fn activate_license(
     if status.valid {
         // Store the license
         storage
-            .save(&license_key)
+            .save(&license_key, &fingerprint)
             .map_err(|e| format!("Failed to save license: {}", e))?;
 
         Ok(ActivateLicenseResponse {
@@ -56,7 +57,8 @@ pub async fn activate_license(
 
 /// Get current license status
 ///
-/// Loads the stored license (if any) and returns its status.
+/// Loads the stored license and returns its status; without one, the
+/// status of the implicit trial (`is_trial: true`).
 #[tauri::command]
 pub async fn get_license_status(app: AppHandle) -> Result<LicenseStatus, String> {
     let app_data_dir = app
@@ -64,26 +66,10 @@ pub async fn get_license_status(app: AppHandle) -> Result<LicenseStatus, String>
         .app_data_dir()
         .map_err(|e| format!("Failed to get app data directory: {}", e))?;
 
-    let storage = LicenseStorage::new(app_data_dir);
+    let fingerprint = license::machine_fingerprint(&app_data_dir);
+    let storage = EncryptedLicenseStorage::new(app_data_dir);
 
-    if !storage.exists() {
-        return Ok(LicenseStatus {
-            valid: false,
-            info: None,
-            error: Some("No license found".to_string()),
-            days_remaining: None,
-        });
-    }
-
-    match storage.load() {
-        Ok(license_key) => Ok(license::get_license_status(&license_key)),
-        Err(e) => Ok(LicenseStatus {
-            valid: false,
-            info: None,
-            error: Some(format!("Failed to load license: {}", e)),
-            days_remaining: None,
-        }),
-    }
+    Ok(license::stored_license_status(&storage, &fingerprint))
 }
 
 /// Deactivate (remove) the current license
@@ -94,7 +80,7 @@ pub async fn deactivate_license(app: AppHandle) -> Result<String, String> {
         .app_data_dir()
         .map_err(|e| format!("Failed to get app data directory: {}", e))?;
 
-    let storage = LicenseStorage::new(app_data_dir);
+    let storage = EncryptedLicenseStorage::new(app_data_dir);
 
     storage
         .remove()
@@ -105,26 +91,45 @@ pub async fn deactivate_license(app: AppHandle) -> Result<String, String> {
 
 /// Check if a feature is licensed
 ///
-/// Returns true if the current license includes the specified feature.
+/// Returns true if the current license (or the trial, if none is
+/// installed) includes the specified feature.
 #[tauri::command]
 pub async fn is_feature_licensed(app: AppHandle, feature: String) -> Result<bool, String> {
+    stored_license_has_feature(&app, &feature)
+}
+
+/// Whether the stored license (or the trial, if none is installed) includes `feature`
+pub(crate) fn stored_license_has_feature(app: &AppHandle, feature: &str) -> Result<bool, String> {
     let app_data_dir = app
         .path()
         .app_data_dir()
         .map_err(|e| format!("Failed to get app data directory: {}", e))?;
 
-    let storage = LicenseStorage::new(app_data_dir);
+    let fingerprint = license::machine_fingerprint(&app_data_dir);
+    let storage = EncryptedLicenseStorage::new(app_data_dir);
 
     if !storage.exists() {
-        return Ok(false);
+        let trial = storage.trial().map_err(|e| e.to_string())?;
+        return Ok(!trial.is_expired() && trial.has_feature(feature));
     }
 
-    match storage.load() {
-        Ok(license_key) => Ok(license::is_feature_licensed(&license_key, &feature)),
+    match storage.load(&fingerprint) {
+        Ok(license_key) => Ok(license::is_feature_licensed(&license_key, feature)),
         Err(_) => Ok(false),
     }
 }
 
+/// Reject a direct command whose feature the stored license lacks
+///
+/// Same message as the secure IPC path's `middleware::check_feature`.
+pub(crate) fn require_feature(app: &AppHandle, feature: &str) -> Result<(), String> {
+    if stored_license_has_feature(app, feature)? {
+        Ok(())
+    } else {
+        Err(format!("Feature not licensed: {}", feature))
+    }
+}
+
 /// Validate a license key without storing it
 ///
 /// Use this to check if a key is valid before activating.
@@ -132,3 +137,30 @@ pub async fn is_feature_licensed(app: AppHandle, feature: String) -> Result<bool
 pub async fn validate_license(license_key: String) -> Result<LicenseStatus, String> {
     Ok(license::get_license_status(&license_key))
 }
+
+/// Export a signed license report for compliance audits
+///
+/// # Errors
+/// Returns an error if no license is stored. An invalid or expired license
+/// still produces a report with `license_valid: false`.
+#[tauri::command]
+pub async fn export_license_report(app: AppHandle) -> Result<LicenseReport, String> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+
+    let fingerprint = license::machine_fingerprint(&app_data_dir);
+    let storage = EncryptedLicenseStorage::new(app_data_dir);
+
+    if !storage.exists() {
+        return Err("No license found".to_string());
+    }
+
+    let license_key = storage
+        .load(&fingerprint)
+        .map_err(|e| format!("Failed to load license: {}", e))?;
+
+    LicenseReport::generate(&license_key, fingerprint)
+        .map_err(|e| format!("Failed to generate license report: {}", e))
+}
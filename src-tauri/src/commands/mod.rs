@@ -1,5 +1,7 @@
 // SQLite commands (default)
 #[cfg(feature = "sqlite")]
+pub mod analytics;
+#[cfg(feature = "sqlite")]
 pub mod database;
 #[cfg(feature = "sqlite")]
 pub mod deliveries;
@@ -9,9 +11,13 @@ pub mod fleet;
 pub mod force_graph;
 #[cfg(feature = "sqlite")]
 pub mod issues;
+#[cfg(feature = "sqlite")]
+pub mod simulation;
 
 // PostgreSQL commands (for HA deployments)
 #[cfg(feature = "postgres")]
+pub mod analytics_pg;
+#[cfg(feature = "postgres")]
 pub mod database_pg;
 #[cfg(feature = "postgres")]
 pub mod deliveries_pg;
@@ -21,8 +27,12 @@ pub mod fleet_pg;
 pub mod force_graph_pg;
 #[cfg(feature = "postgres")]
 pub mod issues_pg;
+#[cfg(feature = "postgres")]
+pub mod simulation_pg;
 
 // Shared modules (both backends)
+pub mod audit;
+pub mod force_graph_shared;
 pub mod health;
 pub mod license;
 pub mod secure;
@@ -17,6 +17,12 @@
 //! Request: ChaCha20-Poly1305 encrypted bincode
 //! Response: ChaCha20-Poly1305 encrypted bincode
 //!
+//! # Batches
+//! `SecureCommand::Batch` carries several commands in one payload, so a
+//! polling loop pays for one encryption round trip instead of one per
+//! command. Each command in the batch still goes through the middleware
+//! chain on its own, and costs one rate limit token.
+//!
 //! # Session Initialization
 //! Before using secure_invoke:
 //! 1. Client calls `init_secure_session` with valid license
@@ -24,13 +30,31 @@
 //! 3. Server returns session nonce (client derives same key)
 //! 4. All subsequent calls use encrypted payloads
 
+use crate::audit::{AuditLog, AUDIT_LOG_FILE};
 use crate::crypto::{SecureCommand, SecureResponse, SessionCrypto};
 use crate::database::DatabaseError;
-use crate::models::ForceGraphData;
+use crate::license::{
+    EncryptedLicenseStorage, LicenseError, LicenseInfo, SeatRegistry, SeatStatus,
+    TRIAL_LICENSE_KEY,
+};
+use crate::middleware::{self, CommandMiddleware};
+use crate::rate_limiter::RateLimiter;
+use crate::sync_queue::SyncOperation;
+use crate::models::{
+    CreateDeliveryRequest, CreateIssueRequest, DeliverySort, DeliveryStatus,
+    ForceGraphClusterMode, ForceGraphData, IssueCategory, IssueReporterType, IssueSort,
+    SortDirection, DEFAULT_PAGE_SIZE,
+};
 use crate::AppState;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::sync::Mutex;
-use tauri::State;
+use std::sync::{Arc, Mutex};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager, State};
+use zeroize::Zeroizing;
+
+/// Most commands accepted in one `SecureCommand::Batch`
+const MAX_BATCH_COMMANDS: usize = 64;
 
 /// Session state holding the crypto context
 ///
@@ -40,6 +64,22 @@ use tauri::State;
 /// - Can be reset independently (e.g., on license change)
 pub struct SecureSessionState {
     pub crypto: Mutex<Option<SessionCrypto>>,
+
+    /// Id of the current session (hex session nonce) in `AppState::seats`
+    pub session_id: Mutex<Option<String>>,
+
+    /// License of the current session (shared with feature-check middleware)
+    pub license: Arc<Mutex<Option<LicenseInfo>>>,
+
+    /// Middleware chain run around every secure command, in registration order
+    pub middleware: Mutex<Vec<Arc<dyn CommandMiddleware>>>,
+
+    /// Audit trail of the current session (runs ahead of `middleware`)
+    pub audit: Mutex<Option<Arc<AuditLog>>>,
+
+    /// Caps `secure_invoke` calls, even before a session exists; see
+    /// `init_secure_session` for the limits
+    pub rate_limiter: Mutex<RateLimiter>,
 }
 
 /// Response from session initialization
@@ -50,6 +90,9 @@ pub struct SecureSessionInfo {
     /// Client uses this + license key to derive the same encryption key
     pub session_nonce_base64: String,
 
+    /// Pass to `close_session` to free this session's seat
+    pub session_id: String,
+
     /// Whether the session was successfully initialized
     pub initialized: bool,
 }
@@ -64,28 +107,80 @@ pub struct SecureSessionInfo {
 /// 5. Server returns session_nonce to client
 /// 6. Client derives same key locally
 ///
+/// # Seats
+/// Every session claims one of the license's seats, held until
+/// `close_session` or 30 minutes without a `secure_invoke`. Re-initializing
+/// ends the previous session first, so the same client never holds two.
+///
+/// # Trial mode
+/// While no license is installed, `license_key` may be `TRIAL_LICENSE_KEY`
+/// to open a session with the implicit trial license, which expires 30 days
+/// after the first run.
+///
+/// # Rate limit
+/// `secure_invoke` allows bursts of `rate_limit_tokens` commands (default
+/// 100), refilled at `rate_limit_refill` commands per second (default 20).
+///
 /// # Why derive key on both sides?
 /// - License key never sent over IPC after this call
 /// - Both sides compute same key from shared secret (license) + public nonce
 /// - Nonce ensures unique key per session
 #[tauri::command]
 pub fn init_secure_session(
-    _state: State<'_, AppState>,
+    app: AppHandle,
+    state: State<'_, AppState>,
     secure_state: State<'_, SecureSessionState>,
     license_key: String,
+    rate_limit_tokens: Option<f64>,
+    rate_limit_refill: Option<f64>,
 ) -> Result<SecureSessionInfo, String> {
+    let rate_limiter = session_rate_limiter(rate_limit_tokens, rate_limit_refill)?;
+
     // Validate license first
-    match crate::license::verify_license(&license_key) {
-        Ok(_license_info) => {
-            // License valid, create session
-            let session_nonce = SessionCrypto::generate_session_nonce();
+    let trial = if license_key == TRIAL_LICENSE_KEY { trial_license(&app)? } else { None };
+    let verified = match trial {
+        Some(trial) if trial.is_expired() => Err(LicenseError::Expired(trial.expires)),
+        Some(trial) => Ok(trial),
+        None => crate::license::verify_license_allowing_grace(&license_key),
+    };
 
-            let crypto = SessionCrypto::from_license(&license_key, &session_nonce)
-                .map_err(|e| e.to_string())?;
+    match verified {
+        Ok(license_info) => {
+            // License valid, end the previous session and create a new one
+            let previous = secure_state.session_id.lock().unwrap().take();
+            if previous.is_some() {
+                *secure_state.crypto.lock().unwrap() = None;
+            }
+            let (session_nonce, session_id) =
+                claim_session(&state.seats, &license_info, previous.as_deref())?;
+
+            let crypto = match SessionCrypto::from_license(&license_key, &session_nonce) {
+                Ok(crypto) => crypto,
+                Err(e) => {
+                    state.seats.release(&session_id);
+                    return Err(e.to_string());
+                }
+            };
+
+            // Audit every command of this session, signed with the license key
+            let app_data_dir = app
+                .path()
+                .app_data_dir()
+                .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+            std::fs::create_dir_all(&app_data_dir).map_err(|e| e.to_string())?;
+            let audit = AuditLog::new(
+                app_data_dir.join(AUDIT_LOG_FILE),
+                &license_key,
+                session_id.clone(),
+            );
 
             // Store crypto context
             let mut crypto_guard = secure_state.crypto.lock().unwrap();
             *crypto_guard = Some(crypto);
+            *secure_state.session_id.lock().unwrap() = Some(session_id.clone());
+            *secure_state.license.lock().unwrap() = Some(license_info);
+            *secure_state.audit.lock().unwrap() = Some(Arc::new(audit));
+            *secure_state.rate_limiter.lock().unwrap() = rate_limiter;
 
             // Return nonce (base64 encoded for JSON transport)
             let nonce_base64 = base64::Engine::encode(
@@ -95,6 +190,7 @@ pub fn init_secure_session(
 
             Ok(SecureSessionInfo {
                 session_nonce_base64: nonce_base64,
+                session_id,
                 initialized: true,
             })
         }
@@ -102,6 +198,167 @@ pub fn init_secure_session(
     }
 }
 
+/// Rate limiter for a new session, with defaults for omitted limits
+fn session_rate_limiter(tokens: Option<f64>, refill: Option<f64>) -> Result<RateLimiter, String> {
+    let tokens = tokens.unwrap_or(crate::rate_limiter::DEFAULT_MAX_TOKENS);
+    let refill = refill.unwrap_or(crate::rate_limiter::DEFAULT_REFILL_RATE);
+
+    if !(tokens >= 1.0 && tokens.is_finite()) {
+        return Err(format!("rate_limit_tokens must be at least 1, got {}", tokens));
+    }
+    if !(refill > 0.0 && refill.is_finite()) {
+        return Err(format!("rate_limit_refill must be positive, got {}", refill));
+    }
+
+    Ok(RateLimiter::new(tokens, refill))
+}
+
+/// The trial license, unless a license has been activated on this machine
+fn trial_license(app: &AppHandle) -> Result<Option<LicenseInfo>, String> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+
+    let storage = EncryptedLicenseStorage::new(app_data_dir);
+    if storage.exists() {
+        return Ok(None);
+    }
+    storage.trial().map(Some).map_err(|e| e.to_string())
+}
+
+/// Generate a session nonce and claim a seat for it
+///
+/// The seat of the `previous` session, if any, is freed first.
+///
+/// # Returns
+/// The nonce and the session id (the nonce in hex)
+fn claim_session(
+    seats: &SeatRegistry,
+    license_info: &LicenseInfo,
+    previous: Option<&str>,
+) -> Result<(Zeroizing<[u8; 16]>, String), String> {
+    if let Some(previous) = previous {
+        seats.release(previous);
+    }
+
+    let session_nonce = SessionCrypto::generate_session_nonce();
+    let session_id: String = session_nonce.iter().map(|b| format!("{:02x}", b)).collect();
+
+    seats
+        .claim(&session_id, license_info.seats.unwrap_or(u32::MAX))
+        .map_err(|e| e.to_string())?;
+
+    Ok((session_nonce, session_id))
+}
+
+/// End a secure session and free its seat
+///
+/// Closing the current session also drops its encryption key, so
+/// `secure_invoke` fails until `init_secure_session` is called again.
+/// Unknown or already expired sessions are ignored.
+#[tauri::command]
+pub fn close_session(
+    state: State<'_, AppState>,
+    secure_state: State<'_, SecureSessionState>,
+    session_id: String,
+) -> Result<(), String> {
+    state.seats.release(&session_id);
+
+    let mut current = secure_state.session_id.lock().unwrap();
+    if current.as_deref() == Some(session_id.as_str()) {
+        *current = None;
+        *secure_state.crypto.lock().unwrap() = None;
+        *secure_state.license.lock().unwrap() = None;
+        *secure_state.audit.lock().unwrap() = None;
+    }
+
+    Ok(())
+}
+
+/// Rotate the session key without re-initializing the session
+///
+/// # Flow
+/// 1. Server generates a new 16-byte nonce
+/// 2. Server derives a new key from the license key and that nonce
+/// 3. Server returns the nonce (base64); only then does the client derive
+///    the same key
+///
+/// The nonce never comes from the client, so a caller can't steer the
+/// session back to an earlier key.
+///
+/// # Atomicity
+/// The server swaps keys under the crypto lock, which `secure_invoke` holds
+/// for a whole request, so rotation happens between commands. Any error
+/// leaves the old key in place on both sides. A payload encrypted with the
+/// old key that arrives after rotation fails decryption with an error
+/// instead of being misread; the client resends it with the new key.
+#[tauri::command]
+pub fn rotate_session(secure_state: State<'_, SecureSessionState>) -> Result<String, String> {
+    let new_nonce = SessionCrypto::generate_session_nonce();
+
+    let mut crypto_guard = secure_state.crypto.lock().unwrap();
+    let crypto = crypto_guard
+        .as_mut()
+        .ok_or("Secure session not initialized. Call init_secure_session first.")?;
+    crypto.rotate(&new_nonce).map_err(|e| e.to_string())?;
+
+    Ok(base64::Engine::encode(&base64::engine::general_purpose::STANDARD, *new_nonce))
+}
+
+/// Get seat usage for the active license
+///
+/// # Returns
+/// SeatStatus { total, used, available }, or an error while no secure
+/// session is open. Licenses without `seats` report `u32::MAX` seats.
+#[tauri::command]
+pub fn get_seat_status(
+    state: State<'_, AppState>,
+    secure_state: State<'_, SecureSessionState>,
+) -> Result<SeatStatus, String> {
+    let total = secure_state
+        .license
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|license| license.seats.unwrap_or(u32::MAX))
+        .ok_or("Secure session not initialized. Call init_secure_session first.")?;
+    let used = state.seats.active_sessions().min(total);
+
+    Ok(SeatStatus {
+        total,
+        used,
+        available: total - used,
+    })
+}
+
+/// Add a middleware to the secure command chain
+///
+/// # Arguments
+/// - `middleware_name`: `logging`, `rate_limit`, `metrics` or `feature_check`
+///
+/// Middleware runs in registration order; `feature_check` checks against
+/// the license of whichever session is current when a command runs.
+/// Each middleware can be registered once.
+#[tauri::command]
+pub fn register_middleware(
+    secure_state: State<'_, SecureSessionState>,
+    middleware_name: String,
+) -> Result<(), String> {
+    let created = middleware::create_middleware(&middleware_name, &secure_state.license)?;
+
+    middleware::register(&mut secure_state.middleware.lock().unwrap(), created)
+}
+
+/// Get the command log and timings collected by the `logging` and
+/// `metrics` middleware
+#[tauri::command]
+pub fn get_middleware_report(
+    secure_state: State<'_, SecureSessionState>,
+) -> Result<middleware::MiddlewareReport, String> {
+    Ok(middleware::report(&secure_state.middleware.lock().unwrap()))
+}
+
 /// Secure invoke - single entry point for all encrypted commands
 ///
 /// # Arguments
@@ -118,6 +375,9 @@ pub fn secure_invoke(
     secure_state: State<'_, SecureSessionState>,
     encrypted_payload: Vec<u8>,
 ) -> Result<Vec<u8>, String> {
+    // Reject floods before spending any time on decryption
+    secure_state.rate_limiter.lock().unwrap().check(1.0)?;
+
     // Get crypto context
     let crypto_guard = secure_state.crypto.lock().unwrap();
     let crypto = crypto_guard
@@ -133,8 +393,30 @@ pub fn secure_invoke(
     let command: SecureCommand = bincode::deserialize(&decrypted)
         .map_err(|e| format!("Invalid command format: {}", e))?;
 
-    // Route and execute command
-    let response = execute_secure_command(&state, command);
+    // The first command of a batch was paid for above
+    if let SecureCommand::Batch { commands } = &command {
+        let extra = commands.len().saturating_sub(1);
+        secure_state.rate_limiter.lock().unwrap().check(extra as f64)?;
+    }
+
+    // Keep this session's seat from expiring
+    if let Some(session_id) = secure_state.session_id.lock().unwrap().as_deref() {
+        state.seats.heartbeat(session_id);
+    }
+
+    let license = secure_state
+        .license
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or("Secure session not initialized. Call init_secure_session first.")?;
+
+    // Route and execute command through the middleware chain
+    let mut chain = secure_state.middleware.lock().unwrap().clone();
+    if let Some(audit) = secure_state.audit.lock().unwrap().clone() {
+        chain.insert(0, audit);
+    }
+    let response = dispatch_secure_command(&state, &license, &chain, command);
 
     // Serialize response (bincode)
     let response_bytes = bincode::serialize(&response)
@@ -146,30 +428,188 @@ pub fn secure_invoke(
         .map_err(|e| format!("Response encryption failed: {}", e))
 }
 
+/// Measure the round-trip time of an encrypted ping, in milliseconds
+///
+/// Runs a `Ping` through the full `secure_invoke` path (encryption,
+/// middleware, dispatch) so the frontend can check that the session is
+/// still valid without sending a real command.
+#[tauri::command]
+pub fn measure_secure_roundtrip(
+    state: State<'_, AppState>,
+    secure_state: State<'_, SecureSessionState>,
+    sequence: u64,
+) -> Result<u64, String> {
+    let started = Instant::now();
+
+    let payload = bincode::serialize(&SecureCommand::Ping { sequence })
+        .map_err(|e| format!("Command serialization failed: {}", e))?;
+    let encrypted = {
+        let crypto_guard = secure_state.crypto.lock().unwrap();
+        let crypto = crypto_guard
+            .as_ref()
+            .ok_or("Secure session not initialized. Call init_secure_session first.")?;
+        crypto
+            .encrypt(&payload)
+            .map_err(|e| format!("Encryption failed: {}", e))?
+    };
+
+    let encrypted_response = secure_invoke(state, secure_state.clone(), encrypted)?;
+
+    let decrypted = {
+        let crypto_guard = secure_state.crypto.lock().unwrap();
+        let crypto = crypto_guard
+            .as_ref()
+            .ok_or("Secure session ended during ping")?;
+        crypto
+            .decrypt(&encrypted_response)
+            .map_err(|e| format!("Decryption failed: {}", e))?
+    };
+
+    match bincode::deserialize(&decrypted) {
+        Ok(SecureResponse::Pong { sequence: echoed, .. }) if echoed == sequence => {
+            Ok(started.elapsed().as_millis() as u64)
+        }
+        Ok(SecureResponse::Error(e)) => Err(e),
+        Ok(_) => Err("Unexpected response to ping".to_string()),
+        Err(e) => Err(format!("Invalid response format: {}", e)),
+    }
+}
+
+/// Run a command through the middleware chain, or each command of a batch
+///
+/// The batch itself skips the chain, so audit entries and metrics are
+/// recorded per command rather than for an opaque "Batch".
+fn dispatch_secure_command(
+    state: &State<'_, AppState>,
+    license: &LicenseInfo,
+    chain: &[Arc<dyn CommandMiddleware>],
+    command: SecureCommand,
+) -> SecureResponse {
+    let run = |command: SecureCommand| {
+        middleware::run_with_middleware(chain, command, |command| {
+            execute_secure_command(state, license, command)
+        })
+    };
+
+    match command {
+        SecureCommand::Batch { commands } => execute_batch(commands, run),
+        command => run(command),
+    }
+}
+
 /// Route and execute a secure command
-fn execute_secure_command(state: &State<'_, AppState>, command: SecureCommand) -> SecureResponse {
+///
+/// Commands needing a license feature (see `middleware::required_feature`)
+/// are rejected here when the session's license lacks it, so individual
+/// handlers don't repeat the check.
+fn execute_secure_command(
+    state: &State<'_, AppState>,
+    license: &LicenseInfo,
+    command: SecureCommand,
+) -> SecureResponse {
+    if let Err(e) = middleware::check_feature(license, &command) {
+        return SecureResponse::Error(e);
+    }
+
     match command {
-        SecureCommand::GetDeliveries { bike_id, status } => {
-            execute_get_deliveries(state, bike_id, status)
-        }
+        SecureCommand::GetDeliveries {
+            bike_id,
+            status,
+            from_ts,
+            to_ts,
+            sort_by,
+            sort_dir,
+            cursor,
+            limit,
+        } => execute_get_deliveries(
+            state, bike_id, status, from_ts, to_ts, sort_by, sort_dir, cursor, limit,
+        ),
         SecureCommand::GetDeliveryById { delivery_id } => {
             execute_get_delivery_by_id(state, delivery_id)
         }
+        SecureCommand::CreateDelivery {
+            bike_id,
+            customer_name,
+            customer_address,
+            restaurant_name,
+            restaurant_address,
+        } => execute_create_delivery(
+            state,
+            CreateDeliveryRequest {
+                bike_id,
+                customer_name,
+                customer_address,
+                restaurant_name,
+                restaurant_address,
+            },
+        ),
+        SecureCommand::UpdateDeliveryStatus {
+            delivery_id,
+            status,
+            rating,
+        } => execute_update_delivery_status(state, delivery_id, status, rating),
         SecureCommand::GetIssues {
             bike_id,
             resolved,
             category,
-        } => execute_get_issues(state, bike_id, resolved, category),
+            sort_by,
+            sort_dir,
+            cursor,
+            limit,
+        } => execute_get_issues(
+            state, bike_id, resolved, category, sort_by, sort_dir, cursor, limit,
+        ),
         SecureCommand::GetIssueById { issue_id } => execute_get_issue_by_id(state, issue_id),
+        SecureCommand::GetIssueStatistics { bike_id } => {
+            execute_get_issue_statistics(state, bike_id)
+        }
+        SecureCommand::CreateIssue {
+            bike_id,
+            delivery_id,
+            reporter_type,
+            category,
+            description,
+        } => execute_create_issue(state, bike_id, delivery_id, reporter_type, category, description),
+        SecureCommand::ResolveIssue { issue_id, note } => {
+            execute_resolve_issue(state, issue_id, note)
+        }
+        SecureCommand::ReopenIssue { issue_id } => execute_reopen_issue(state, issue_id),
+        SecureCommand::BatchResolveIssues { issue_ids } => {
+            execute_batch_resolve_issues(state, issue_ids)
+        }
+        SecureCommand::Search { query, limit } => execute_search(state, query, limit),
         SecureCommand::GetForceGraphLayout { bike_id } => {
-            execute_get_force_graph_layout(state, bike_id)
+            execute_get_force_graph_layout(state, bike_id, ForceGraphClusterMode::None)
+        }
+        SecureCommand::GetForceGraphLayoutClustered { bike_id, mode } => {
+            execute_get_force_graph_layout(state, bike_id, mode)
         }
         SecureCommand::UpdateNodePosition {
             bike_id,
             node_id,
             x,
             y,
-        } => execute_update_node_position(state, bike_id, node_id, x, y),
+            mode,
+        } => execute_update_node_position(state, bike_id, node_id, x, y, mode),
+        SecureCommand::ClearNodePins { bike_id } => execute_clear_node_pins(state, bike_id),
+        SecureCommand::ExportForceGraphSvg {
+            bike_id,
+            width,
+            height,
+        } => execute_export_force_graph_svg(state, bike_id, width, height),
+        SecureCommand::ExportForceGraphGraphml { bike_id } => {
+            execute_export_force_graph_graphml(state, bike_id)
+        }
+        SecureCommand::GetFleetForceGraph => execute_get_fleet_force_graph(state),
+        SecureCommand::ExportFleetCsv => execute_export_fleet_csv(state),
+        SecureCommand::ExportDeliveriesCsv { from, to } => {
+            execute_export_deliveries_csv(state, from, to)
+        }
+        SecureCommand::Ping { sequence } => execute_ping(sequence),
+        // Top-level batches are unpacked by `dispatch_secure_command`
+        SecureCommand::Batch { .. } => {
+            SecureResponse::Error("Batches cannot be nested".to_string())
+        }
     }
 }
 
@@ -177,15 +617,69 @@ fn execute_secure_command(state: &State<'_, AppState>, command: SecureCommand) -
 // Command Handlers
 // ============================================================================
 
+/// Parse the optional `sort_by`/`sort_dir` arguments of a listing command
+fn parse_sort<S>(
+    sort_by: Option<&str>,
+    sort_dir: Option<&str>,
+    parse_field: fn(&str) -> Option<S>,
+) -> Result<(Option<S>, Option<SortDirection>), String> {
+    let sort_by = sort_by
+        .map(|s| parse_field(s).ok_or_else(|| format!("Unknown sort field: {}", s)))
+        .transpose()?;
+    let sort_dir = sort_dir
+        .map(|s| SortDirection::from_str(s).ok_or_else(|| format!("Unknown sort direction: {}", s)))
+        .transpose()?;
+    Ok((sort_by, sort_dir))
+}
+
+/// Parse an optional RFC 3339 timestamp argument as UTC
+fn parse_timestamp(value: Option<&str>) -> Result<Option<DateTime<Utc>>, String> {
+    value
+        .map(|s| {
+            DateTime::parse_from_rfc3339(s)
+                .map(|at| at.with_timezone(&Utc))
+                .map_err(|e| format!("Invalid timestamp {}: {}", s, e))
+        })
+        .transpose()
+}
+
+#[allow(clippy::too_many_arguments)]
 fn execute_get_deliveries(
     state: &State<'_, AppState>,
     bike_id: Option<String>,
     status: Option<String>,
+    from_ts: Option<String>,
+    to_ts: Option<String>,
+    sort_by: Option<String>,
+    sort_dir: Option<String>,
+    cursor: Option<String>,
+    limit: Option<u32>,
 ) -> SecureResponse {
+    let sort = parse_sort(sort_by.as_deref(), sort_dir.as_deref(), DeliverySort::from_str);
+    let (sort_by, sort_dir) = match sort {
+        Ok(sort) => sort,
+        Err(e) => return SecureResponse::Error(e),
+    };
+    let range = parse_timestamp(from_ts.as_deref())
+        .and_then(|from| Ok((from, parse_timestamp(to_ts.as_deref())?)));
+    let (from, to) = match range {
+        Ok(range) => range,
+        Err(e) => return SecureResponse::Error(e),
+    };
+
     let db_guard = state.db.lock().unwrap();
     match db_guard.as_ref() {
-        Some(db) => match db.get_deliveries(bike_id.as_deref(), status.as_deref()) {
-            Ok(deliveries) => match bincode::serialize(&deliveries) {
+        Some(db) => match db.get_deliveries(
+            bike_id.as_deref(),
+            status.as_deref(),
+            from,
+            to,
+            sort_by,
+            sort_dir,
+            cursor.as_deref(),
+            limit.unwrap_or(DEFAULT_PAGE_SIZE),
+        ) {
+            Ok(page) => match bincode::serialize(&page) {
                 Ok(bytes) => SecureResponse::Success(bytes),
                 Err(e) => SecureResponse::Error(e.to_string()),
             },
@@ -212,16 +706,105 @@ fn execute_get_delivery_by_id(
     }
 }
 
+fn execute_create_delivery(
+    state: &State<'_, AppState>,
+    request: CreateDeliveryRequest,
+) -> SecureResponse {
+    let db_guard = state.db.lock().unwrap();
+    match db_guard.as_ref() {
+        Some(db) => match db.queued_write(
+            || db.create_delivery(&request),
+            &SyncOperation::CreateDelivery(request.clone()),
+        ) {
+            Ok(delivery) => match bincode::serialize(&delivery) {
+                Ok(bytes) => SecureResponse::Success(bytes),
+                Err(e) => SecureResponse::Error(e.to_string()),
+            },
+            Err(e) => SecureResponse::Error(e.to_string()),
+        },
+        None => SecureResponse::Error("Database not initialized".to_string()),
+    }
+}
+
+fn execute_update_delivery_status(
+    state: &State<'_, AppState>,
+    delivery_id: String,
+    status: String,
+    rating: Option<u8>,
+) -> SecureResponse {
+    let status = match DeliveryStatus::from_str(&status) {
+        Some(status) => status,
+        None => return SecureResponse::Error(format!("Unknown delivery status: {}", status)),
+    };
+
+    let db_guard = state.db.lock().unwrap();
+    match db_guard.as_ref() {
+        Some(db) => match db.queued_write(
+            || db.update_delivery_status(&delivery_id, status.clone(), rating),
+            &SyncOperation::UpdateDeliveryStatus {
+                delivery_id: delivery_id.clone(),
+                status: status.clone(),
+                rating,
+            },
+        ) {
+            Ok(delivery) => match bincode::serialize(&delivery) {
+                Ok(bytes) => SecureResponse::Success(bytes),
+                Err(e) => SecureResponse::Error(e.to_string()),
+            },
+            Err(e) => SecureResponse::Error(e.to_string()),
+        },
+        None => SecureResponse::Error("Database not initialized".to_string()),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 fn execute_get_issues(
     state: &State<'_, AppState>,
     bike_id: Option<String>,
     resolved: Option<bool>,
     category: Option<String>,
+    sort_by: Option<String>,
+    sort_dir: Option<String>,
+    cursor: Option<String>,
+    limit: Option<u32>,
+) -> SecureResponse {
+    let (sort_by, sort_dir) =
+        match parse_sort(sort_by.as_deref(), sort_dir.as_deref(), IssueSort::from_str) {
+            Ok(sort) => sort,
+            Err(e) => return SecureResponse::Error(e),
+        };
+
+    let db_guard = state.db.lock().unwrap();
+    match db_guard.as_ref() {
+        Some(db) => match db.get_issues(
+            bike_id.as_deref(),
+            resolved,
+            category.as_deref(),
+            None,
+            None,
+            sort_by,
+            sort_dir,
+            cursor.as_deref(),
+            limit.unwrap_or(DEFAULT_PAGE_SIZE),
+        ) {
+            Ok(page) => match bincode::serialize(&page) {
+                Ok(bytes) => SecureResponse::Success(bytes),
+                Err(e) => SecureResponse::Error(e.to_string()),
+            },
+            Err(e) => SecureResponse::Error(e.to_string()),
+        },
+        None => SecureResponse::Error("Database not initialized".to_string()),
+    }
+}
+
+fn execute_get_issue_statistics(
+    state: &State<'_, AppState>,
+    bike_id: Option<String>,
 ) -> SecureResponse {
     let db_guard = state.db.lock().unwrap();
     match db_guard.as_ref() {
-        Some(db) => match db.get_issues(bike_id.as_deref(), resolved, category.as_deref()) {
-            Ok(issues) => match bincode::serialize(&issues) {
+        Some(db) => match db.get_issue_statistics(bike_id.as_deref()) {
+            Ok(stats) => match bincode::serialize(&stats) {
                 Ok(bytes) => SecureResponse::Success(bytes),
                 Err(e) => SecureResponse::Error(e.to_string()),
             },
@@ -245,9 +828,124 @@ fn execute_get_issue_by_id(state: &State<'_, AppState>, issue_id: String) -> Sec
     }
 }
 
+fn execute_create_issue(
+    state: &State<'_, AppState>,
+    bike_id: String,
+    delivery_id: Option<String>,
+    reporter_type: String,
+    category: Option<String>,
+    description: String,
+) -> SecureResponse {
+    let reporter_type = match IssueReporterType::from_str(&reporter_type) {
+        Some(reporter_type) => reporter_type,
+        None => return SecureResponse::Error(format!("Unknown reporter type: {}", reporter_type)),
+    };
+    let category = match category.as_deref() {
+        Some(s) => match IssueCategory::from_str(s) {
+            Some(category) => Some(category),
+            None => return SecureResponse::Error(format!("Unknown issue category: {}", s)),
+        },
+        None => None,
+    };
+    let request = CreateIssueRequest {
+        bike_id,
+        delivery_id,
+        reporter_type,
+        category,
+        description,
+    };
+
+    let db_guard = state.db.lock().unwrap();
+    match db_guard.as_ref() {
+        Some(db) => match db.create_issue(&request) {
+            Ok(issue) => match bincode::serialize(&issue) {
+                Ok(bytes) => SecureResponse::Success(bytes),
+                Err(e) => SecureResponse::Error(e.to_string()),
+            },
+            Err(e) => SecureResponse::Error(e.to_string()),
+        },
+        None => SecureResponse::Error("Database not initialized".to_string()),
+    }
+}
+
+fn execute_resolve_issue(
+    state: &State<'_, AppState>,
+    issue_id: String,
+    note: Option<String>,
+) -> SecureResponse {
+    let db_guard = state.db.lock().unwrap();
+    match db_guard.as_ref() {
+        Some(db) => match db.queued_write(
+            || db.resolve_issue(&issue_id, note.as_deref()),
+            &SyncOperation::ResolveIssue {
+                issue_id: issue_id.clone(),
+                note: note.clone(),
+            },
+        ) {
+            Ok(issue) => match bincode::serialize(&issue) {
+                Ok(bytes) => SecureResponse::Success(bytes),
+                Err(e) => SecureResponse::Error(e.to_string()),
+            },
+            Err(e) => SecureResponse::Error(e.to_string()),
+        },
+        None => SecureResponse::Error("Database not initialized".to_string()),
+    }
+}
+
+fn execute_reopen_issue(state: &State<'_, AppState>, issue_id: String) -> SecureResponse {
+    let db_guard = state.db.lock().unwrap();
+    match db_guard.as_ref() {
+        Some(db) => match db.reopen_issue(&issue_id) {
+            Ok(issue) => match bincode::serialize(&issue) {
+                Ok(bytes) => SecureResponse::Success(bytes),
+                Err(e) => SecureResponse::Error(e.to_string()),
+            },
+            Err(e) => SecureResponse::Error(e.to_string()),
+        },
+        None => SecureResponse::Error("Database not initialized".to_string()),
+    }
+}
+
+fn execute_batch_resolve_issues(
+    state: &State<'_, AppState>,
+    issue_ids: Vec<String>,
+) -> SecureResponse {
+    let ids: Vec<&str> = issue_ids.iter().map(String::as_str).collect();
+    let db_guard = state.db.lock().unwrap();
+    match db_guard.as_ref() {
+        Some(db) => match db.batch_resolve_issues(&ids) {
+            Ok(count) => match bincode::serialize(&count) {
+                Ok(bytes) => SecureResponse::Success(bytes),
+                Err(e) => SecureResponse::Error(e.to_string()),
+            },
+            Err(e) => SecureResponse::Error(e.to_string()),
+        },
+        None => SecureResponse::Error("Database not initialized".to_string()),
+    }
+}
+
+fn execute_search(
+    state: &State<'_, AppState>,
+    query: String,
+    limit: Option<u32>,
+) -> SecureResponse {
+    let db_guard = state.db.lock().unwrap();
+    match db_guard.as_ref() {
+        Some(db) => match db.search(&query, limit.unwrap_or(DEFAULT_PAGE_SIZE)) {
+            Ok(results) => match bincode::serialize(&results) {
+                Ok(bytes) => SecureResponse::Success(bytes),
+                Err(e) => SecureResponse::Error(e.to_string()),
+            },
+            Err(e) => SecureResponse::Error(e.to_string()),
+        },
+        None => SecureResponse::Error("Database not initialized".to_string()),
+    }
+}
+
 fn execute_get_force_graph_layout(
     state: &State<'_, AppState>,
     bike_id: String,
+    cluster_mode: ForceGraphClusterMode,
 ) -> SecureResponse {
     // Note: This duplicates logic from force_graph.rs but with different error handling
     // In production, you'd want to refactor to share the core logic
@@ -262,10 +960,15 @@ fn execute_get_force_graph_layout(
                     })?;
                 let deliveries = db.get_deliveries_by_bike(&bike_id)?;
                 let issues = db.get_issues_by_bike(&bike_id)?;
+                let pins = db.get_node_pins(&bike_id)?;
 
                 // Use the force_graph module's logic
                 crate::commands::force_graph::get_force_graph_layout_internal(
-                    &bike, &deliveries, &issues,
+                    &bike,
+                    &deliveries,
+                    &issues,
+                    &pins,
+                    cluster_mode,
                 )
             })();
 
@@ -287,6 +990,7 @@ fn execute_update_node_position(
     node_id: String,
     x: f64,
     y: f64,
+    cluster_mode: ForceGraphClusterMode,
 ) -> SecureResponse {
     let db_guard = state.db.lock().unwrap();
     match db_guard.as_ref() {
@@ -300,11 +1004,125 @@ fn execute_update_node_position(
                 let deliveries = db.get_deliveries_by_bike(&bike_id)?;
                 let issues = db.get_issues_by_bike(&bike_id)?;
 
-                crate::commands::force_graph::update_node_position_internal(
-                    &bike, &deliveries, &issues, &node_id, x, y,
+                db.save_node_pin(&bike_id, &node_id, x, y)?;
+                let pins = db.get_node_pins(&bike_id)?;
+
+                crate::commands::force_graph::get_force_graph_layout_internal(
+                    &bike,
+                    &deliveries,
+                    &issues,
+                    &pins,
+                    cluster_mode,
+                )
+            })();
+
+            match result {
+                Ok(layout) => match bincode::serialize(&layout) {
+                    Ok(bytes) => SecureResponse::Success(bytes),
+                    Err(e) => SecureResponse::Error(e.to_string()),
+                },
+                Err(e) => SecureResponse::Error(e.to_string()),
+            }
+        }
+        None => SecureResponse::Error("Database not initialized".to_string()),
+    }
+}
+
+fn execute_clear_node_pins(state: &State<'_, AppState>, bike_id: String) -> SecureResponse {
+    let db_guard = state.db.lock().unwrap();
+    match db_guard.as_ref() {
+        Some(db) => match db.clear_node_pins(&bike_id) {
+            Ok(()) => SecureResponse::Success(Vec::new()),
+            Err(e) => SecureResponse::Error(e.to_string()),
+        },
+        None => SecureResponse::Error("Database not initialized".to_string()),
+    }
+}
+
+fn execute_export_force_graph_svg(
+    state: &State<'_, AppState>,
+    bike_id: String,
+    width: f64,
+    height: f64,
+) -> SecureResponse {
+    let db_guard = state.db.lock().unwrap();
+    match db_guard.as_ref() {
+        Some(db) => {
+            let result = (|| -> Result<String, DatabaseError> {
+                let bike = db
+                    .get_bike_by_id(&bike_id)?
+                    .ok_or_else(|| {
+                        DatabaseError::InvalidData(format!("Bike not found: {}", bike_id))
+                    })?;
+                let deliveries = db.get_deliveries_by_bike(&bike_id)?;
+                let issues = db.get_issues_by_bike(&bike_id)?;
+                let pins = db.get_node_pins(&bike_id)?;
+
+                crate::commands::force_graph::export_force_graph_svg_internal(
+                    &bike, &deliveries, &issues, &pins, width, height,
+                )
+            })();
+
+            match result {
+                Ok(svg) => match bincode::serialize(&svg) {
+                    Ok(bytes) => SecureResponse::Success(bytes),
+                    Err(e) => SecureResponse::Error(e.to_string()),
+                },
+                Err(e) => SecureResponse::Error(e.to_string()),
+            }
+        }
+        None => SecureResponse::Error("Database not initialized".to_string()),
+    }
+}
+
+fn execute_export_force_graph_graphml(
+    state: &State<'_, AppState>,
+    bike_id: String,
+) -> SecureResponse {
+    let db_guard = state.db.lock().unwrap();
+    match db_guard.as_ref() {
+        Some(db) => {
+            let result = (|| -> Result<String, DatabaseError> {
+                let bike = db
+                    .get_bike_by_id(&bike_id)?
+                    .ok_or_else(|| {
+                        DatabaseError::InvalidData(format!("Bike not found: {}", bike_id))
+                    })?;
+                let deliveries = db.get_deliveries_by_bike(&bike_id)?;
+                let issues = db.get_issues_by_bike(&bike_id)?;
+                let pins = db.get_node_pins(&bike_id)?;
+
+                crate::commands::force_graph::export_force_graph_graphml_internal(
+                    &bike, &deliveries, &issues, &pins,
                 )
             })();
 
+            match result {
+                Ok(graphml) => match bincode::serialize(&graphml) {
+                    Ok(bytes) => SecureResponse::Success(bytes),
+                    Err(e) => SecureResponse::Error(e.to_string()),
+                },
+                Err(e) => SecureResponse::Error(e.to_string()),
+            }
+        }
+        None => SecureResponse::Error("Database not initialized".to_string()),
+    }
+}
+
+fn execute_get_fleet_force_graph(state: &State<'_, AppState>) -> SecureResponse {
+    let db_guard = state.db.lock().unwrap();
+    match db_guard.as_ref() {
+        Some(db) => {
+            let result = (|| -> Result<ForceGraphData, DatabaseError> {
+                let bikes = db.get_all_bikes(None, None)?;
+                let deliveries = db.get_fleet_deliveries()?;
+
+                Ok(crate::commands::force_graph_shared::compute_fleet_layout(
+                    &bikes,
+                    &deliveries,
+                ))
+            })();
+
             match result {
                 Ok(layout) => match bincode::serialize(&layout) {
                     Ok(bytes) => SecureResponse::Success(bytes),
@@ -316,3 +1134,184 @@ fn execute_update_node_position(
         None => SecureResponse::Error("Database not initialized".to_string()),
     }
 }
+
+fn execute_export_fleet_csv(state: &State<'_, AppState>) -> SecureResponse {
+    let db_guard = state.db.lock().unwrap();
+    match db_guard.as_ref() {
+        Some(db) => {
+            let result = (|| -> Result<String, DatabaseError> {
+                let bikes = db.get_all_bikes(None, None)?;
+                crate::csv_export::fleet_csv(&bikes).map_err(DatabaseError::InvalidData)
+            })();
+
+            match result {
+                Ok(csv) => match bincode::serialize(&csv) {
+                    Ok(bytes) => SecureResponse::Success(bytes),
+                    Err(e) => SecureResponse::Error(e.to_string()),
+                },
+                Err(e) => SecureResponse::Error(e.to_string()),
+            }
+        }
+        None => SecureResponse::Error("Database not initialized".to_string()),
+    }
+}
+
+fn execute_export_deliveries_csv(
+    state: &State<'_, AppState>,
+    from: Option<String>,
+    to: Option<String>,
+) -> SecureResponse {
+    let range = parse_timestamp(from.as_deref())
+        .and_then(|from| Ok((from, parse_timestamp(to.as_deref())?)));
+    let (from, to) = match range {
+        Ok(range) => range,
+        Err(e) => return SecureResponse::Error(e),
+    };
+
+    let db_guard = state.db.lock().unwrap();
+    match db_guard.as_ref() {
+        Some(db) => {
+            let result = (|| -> Result<String, DatabaseError> {
+                let deliveries = db.get_deliveries_in_range(from, to)?;
+                crate::csv_export::deliveries_csv(&deliveries).map_err(DatabaseError::InvalidData)
+            })();
+
+            match result {
+                Ok(csv) => match bincode::serialize(&csv) {
+                    Ok(bytes) => SecureResponse::Success(bytes),
+                    Err(e) => SecureResponse::Error(e.to_string()),
+                },
+                Err(e) => SecureResponse::Error(e.to_string()),
+            }
+        }
+        None => SecureResponse::Error("Database not initialized".to_string()),
+    }
+}
+
+/// Run each command of a batch in order, collecting one response each
+///
+/// A failing command doesn't stop the batch; its slot holds the
+/// `SecureResponse::Error`.
+fn execute_batch(
+    commands: Vec<SecureCommand>,
+    run: impl FnMut(SecureCommand) -> SecureResponse,
+) -> SecureResponse {
+    if commands.len() > MAX_BATCH_COMMANDS {
+        return SecureResponse::Error(format!(
+            "Batch of {} commands exceeds the limit of {}",
+            commands.len(),
+            MAX_BATCH_COMMANDS
+        ));
+    }
+
+    SecureResponse::Batch {
+        responses: commands.into_iter().map(run).collect(),
+    }
+}
+
+fn execute_ping(sequence: u64) -> SecureResponse {
+    SecureResponse::Pong {
+        sequence,
+        server_time_ms: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::license::license_with;
+
+    #[test]
+    fn test_ping_echoes_sequence() {
+        match execute_ping(42) {
+            SecureResponse::Pong {
+                sequence,
+                server_time_ms,
+            } => {
+                assert_eq!(sequence, 42);
+                assert!(server_time_ms > 0);
+            }
+            other => panic!("Expected Pong, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_batch_returns_responses_in_order() {
+        let commands = vec![
+            SecureCommand::Ping { sequence: 1 },
+            SecureCommand::GetFleetForceGraph,
+            SecureCommand::Ping { sequence: 3 },
+        ];
+
+        let response = execute_batch(commands, |command| match command {
+            SecureCommand::Ping { sequence } => execute_ping(sequence),
+            other => SecureResponse::Error(format!("{} failed", other.name())),
+        });
+
+        match response {
+            SecureResponse::Batch { responses } => {
+                assert_eq!(responses.len(), 3);
+                assert!(matches!(responses[0], SecureResponse::Pong { sequence: 1, .. }));
+                assert!(
+                    matches!(&responses[1], SecureResponse::Error(e) if e == "GetFleetForceGraph failed")
+                );
+                assert!(matches!(responses[2], SecureResponse::Pong { sequence: 3, .. }));
+            }
+            other => panic!("Expected Batch, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_oversized_batch_is_rejected() {
+        let commands = vec![SecureCommand::Ping { sequence: 0 }; MAX_BATCH_COMMANDS + 1];
+
+        let response = execute_batch(commands, |_| panic!("no command should run"));
+
+        assert!(matches!(response, SecureResponse::Error(_)));
+    }
+
+    #[test]
+    fn test_two_seat_license_rejects_third_session() {
+        let seats = SeatRegistry::default();
+        let license = LicenseInfo {
+            seats: Some(2),
+            ..license_with(&[])
+        };
+
+        let (_, first) = claim_session(&seats, &license, None).unwrap();
+        claim_session(&seats, &license, None).unwrap();
+        let err = claim_session(&seats, &license, None).unwrap_err();
+        assert_eq!(err, "All 2 licensed seats are in use");
+
+        seats.release(&first);
+        claim_session(&seats, &license, None).unwrap();
+    }
+
+    #[test]
+    fn test_reinit_frees_the_previous_seat() {
+        let seats = SeatRegistry::default();
+        let license = LicenseInfo {
+            seats: Some(1),
+            ..license_with(&[])
+        };
+
+        let (_, first) = claim_session(&seats, &license, None).unwrap();
+        let (_, second) = claim_session(&seats, &license, Some(&first)).unwrap();
+        claim_session(&seats, &license, Some(&second)).unwrap();
+        assert_eq!(seats.active_sessions(), 1);
+    }
+
+    #[test]
+    fn test_session_rate_limiter_validates_limits() {
+        let mut limiter = session_rate_limiter(Some(2.0), None).unwrap();
+        assert!(limiter.check(1.0).is_ok());
+        assert!(limiter.check(1.0).is_ok());
+        assert!(limiter.check(1.0).is_err());
+
+        assert!(session_rate_limiter(Some(0.0), None).is_err());
+        assert!(session_rate_limiter(None, Some(-1.0)).is_err());
+    }
+}
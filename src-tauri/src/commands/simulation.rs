@@ -0,0 +1,38 @@
+//! Simulation Tauri Commands
+//!
+//! # Purpose
+//! Puts the fleet into preset states for QA and sales demos.
+
+use super::fleet::invalidate_fleet_data;
+use crate::models::{Bike, SimulationScenario};
+use crate::AppState;
+use tauri::State;
+
+/// Put the whole fleet into a preset scenario
+///
+/// # Arguments
+/// - `scenario`: `all_idle`, `all_delivering`, `critical_battery`,
+///   `post_maintenance_peak` or `maximum_coverage`
+///
+/// # Returns
+/// The updated fleet, for an immediate UI refresh
+#[tauri::command]
+pub fn apply_simulation_scenario(
+    scenario: String,
+    state: State<AppState>,
+) -> Result<Vec<Bike>, String> {
+    let scenario = SimulationScenario::from_str(&scenario)
+        .ok_or_else(|| format!("Unknown simulation scenario: {}", scenario))?;
+
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+
+    let fleet = match db_guard.as_ref() {
+        Some(db) => db
+            .apply_simulation_scenario(scenario)
+            .map_err(|e| e.to_string())?,
+        None => return Err("Database not initialized. Call init_database first.".to_string()),
+    };
+
+    invalidate_fleet_data(&state.cache);
+    Ok(fleet)
+}
@@ -0,0 +1,33 @@
+//! PostgreSQL Simulation Tauri Commands
+//!
+//! Async versions of simulation commands for PostgreSQL backend.
+
+use crate::models::{Bike, SimulationScenario};
+use crate::AppState;
+use tauri::State;
+
+/// Put the whole fleet into a preset scenario
+///
+/// # Arguments
+/// - `scenario`: `all_idle`, `all_delivering`, `critical_battery`,
+///   `post_maintenance_peak` or `maximum_coverage`
+///
+/// # Returns
+/// The updated fleet, for an immediate UI refresh
+#[tauri::command]
+pub async fn apply_simulation_scenario(
+    scenario: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<Bike>, String> {
+    let scenario = SimulationScenario::from_str(&scenario)
+        .ok_or_else(|| format!("Unknown simulation scenario: {}", scenario))?;
+
+    let db_guard = state.db.lock().map_err(|e| e.to_string())?;
+
+    match db_guard.as_ref() {
+        Some(db) => db
+            .apply_simulation_scenario(scenario).await
+            .map_err(|e| e.to_string()),
+        None => Err("Database not initialized. Call init_database first.".to_string()),
+    }
+}
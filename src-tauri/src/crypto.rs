@@ -17,21 +17,50 @@
 //! # Security Model
 //! - Session nonce generated at app startup (random 16 bytes)
 //! - Key derived using HKDF: license_key + session_nonce → encryption_key
-//! - Each message uses incrementing nonce (counter mode)
+//! - ChaCha20-Poly1305 (default): each message uses incrementing nonce (counter mode)
+//! - XChaCha20-Poly1305: each message uses a random 24-byte nonce
 //! - AEAD tag prevents tampering
+//! - Key material is zeroized when the session ends (`zeroize`)
+//!
+//! # Wire Format
+//! `[mode (1 byte)][nonce (12 or 24 bytes)][ciphertext + tag]`
+//!
+//! The nonce is 12 bytes for ChaCha20-Poly1305 and 24 bytes for
+//! XChaCha20-Poly1305. Both sides must agree on the cipher suite.
+//!
+//! The mode byte says whether the plaintext was compressed before encryption
+//! (`0x00` none, `0x01` LZ4, `0x02` Zstd). It is not authenticated, but the
+//! AEAD tag still covers the (compressed) payload, and a flipped mode byte
+//! only makes decompression fail.
 
 use chacha20poly1305::{
     aead::{Aead, KeyInit},
-    ChaCha20Poly1305, Nonce,
+    ChaCha20Poly1305, Nonce, XChaCha20Poly1305, XNonce,
 };
 use hkdf::Hkdf;
 use sha2::Sha256;
+use std::collections::{HashSet, VecDeque};
+use std::io::Read;
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
 use thiserror::Error;
+use zeroize::{Zeroize, Zeroizing};
 
 /// Nonce size for ChaCha20-Poly1305 (96 bits = 12 bytes)
 const NONCE_SIZE: usize = 12;
 
+/// Nonce size for XChaCha20-Poly1305 (192 bits = 24 bytes)
+const XNONCE_SIZE: usize = 24;
+
+/// First counter-nonce byte of messages encrypted by the client
+const CLIENT_DIRECTION: u8 = 0;
+
+/// First counter-nonce byte of messages encrypted by `encrypt`
+///
+/// Both sides count from zero under the same key; the direction byte keeps
+/// their nonces, and so their replay window entries, apart.
+const SERVER_DIRECTION: u8 = 1;
+
 /// Session nonce size for key derivation (128 bits = 16 bytes)
 const SESSION_NONCE_SIZE: usize = 16;
 
@@ -39,13 +68,39 @@ const SESSION_NONCE_SIZE: usize = 16;
 /// Changing this would produce different keys even with same inputs
 const HKDF_INFO: &[u8] = b"amsterdam-bike-fleet-ipc-v1";
 
+/// HKDF info string for XChaCha20-Poly1305 sessions
+/// Separate from `HKDF_INFO` so the two ciphers never share a key
+const HKDF_INFO_XCHACHA: &[u8] = b"amsterdam-bike-fleet-ipc-xchacha-v1";
+
+/// Poly1305 authentication tag size
+const TAG_SIZE: usize = 16;
+
+/// Upper bound for decompressed payloads
+/// Protects against decompression bombs from a malicious sender
+const MAX_DECOMPRESSED_SIZE: usize = 16 * 1024 * 1024;
+
+/// Zstd compression level (3 = zstd default, good speed/ratio balance)
+const ZSTD_LEVEL: i32 = 3;
+
+/// Nonces remembered for replay detection; older ones are forgotten
+const MAX_SEEN_NONCES: usize = 10_000;
+
 #[derive(Error, Debug)]
 pub enum CryptoError {
-    #[error("Encryption failed: {0}")]
-    EncryptionFailed(String),
+    #[error("EncryptionFailed while {operation}: {source}")]
+    EncryptionFailed {
+        operation: String,
+        source: chacha20poly1305::Error,
+    },
 
-    #[error("Decryption failed: {0}")]
-    DecryptionFailed(String),
+    #[error("DecryptionFailed while {operation}: {source}")]
+    DecryptionFailed {
+        operation: String,
+        source: chacha20poly1305::Error,
+    },
+
+    #[error("Invalid ciphertext: {0}")]
+    InvalidCiphertext(String),
 
     #[error("Key derivation failed: {0}")]
     KeyDerivationFailed(String),
@@ -55,6 +110,15 @@ pub enum CryptoError {
 
     #[error("Nonce counter overflow")]
     NonceOverflow,
+
+    #[error("Replay detected")]
+    ReplayDetected,
+
+    #[error("Session nonce was already used by an ended session")]
+    SessionNonceReused,
+
+    #[error("Compression failed: {0}")]
+    CompressionFailed(String),
 }
 
 impl serde::Serialize for CryptoError {
@@ -66,23 +130,217 @@ impl serde::Serialize for CryptoError {
     }
 }
 
+/// Payload compression applied before encryption
+///
+/// # Why compress?
+/// Force graph layouts with 50+ nodes exceed 20KB bincode-serialized, and
+/// animated layouts send one per frame. Compression must happen before
+/// encryption: ciphertext is indistinguishable from random and won't compress.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CompressionMode {
+    #[default]
+    None,
+    /// Fastest, moderate ratio
+    Lz4,
+    /// Better ratio, slower
+    Zstd,
+}
+
+impl CompressionMode {
+    /// Mode indicator byte on the wire
+    fn to_byte(self) -> u8 {
+        match self {
+            CompressionMode::None => 0x00,
+            CompressionMode::Lz4 => 0x01,
+            CompressionMode::Zstd => 0x02,
+        }
+    }
+
+    fn from_byte(byte: u8) -> Result<Self, CryptoError> {
+        match byte {
+            0x00 => Ok(CompressionMode::None),
+            0x01 => Ok(CompressionMode::Lz4),
+            0x02 => Ok(CompressionMode::Zstd),
+            other => Err(CryptoError::InvalidCiphertext(format!(
+                "Unknown compression mode: {:#04x}",
+                other
+            ))),
+        }
+    }
+
+    fn compress(self, data: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        match self {
+            CompressionMode::None => Ok(data.to_vec()),
+            CompressionMode::Lz4 => Ok(lz4_flex::compress_prepend_size(data)),
+            CompressionMode::Zstd => zstd::encode_all(data, ZSTD_LEVEL)
+                .map_err(|e| CryptoError::CompressionFailed(e.to_string())),
+        }
+    }
+
+    fn decompress(self, data: Vec<u8>) -> Result<Vec<u8>, CryptoError> {
+        match self {
+            CompressionMode::None => Ok(data),
+            CompressionMode::Lz4 => {
+                // The size prefix is attacker-controlled; check before allocating
+                let declared = data
+                    .get(..4)
+                    .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as usize)
+                    .ok_or_else(|| {
+                        CryptoError::CompressionFailed("Missing LZ4 size prefix".to_string())
+                    })?;
+                if declared > MAX_DECOMPRESSED_SIZE {
+                    return Err(CryptoError::CompressionFailed(
+                        "Decompressed payload too large".to_string(),
+                    ));
+                }
+                lz4_flex::decompress_size_prepended(&data)
+                    .map_err(|e| CryptoError::CompressionFailed(e.to_string()))
+            }
+            CompressionMode::Zstd => {
+                let decoder = zstd::stream::read::Decoder::new(data.as_slice())
+                    .map_err(|e| CryptoError::CompressionFailed(e.to_string()))?;
+                let mut output = Vec::new();
+                decoder
+                    .take(MAX_DECOMPRESSED_SIZE as u64 + 1)
+                    .read_to_end(&mut output)
+                    .map_err(|e| CryptoError::CompressionFailed(e.to_string()))?;
+                if output.len() > MAX_DECOMPRESSED_SIZE {
+                    return Err(CryptoError::CompressionFailed(
+                        "Decompressed payload too large".to_string(),
+                    ));
+                }
+                Ok(output)
+            }
+        }
+    }
+}
+
+/// AEAD cipher used for a session
+///
+/// # Why XChaCha20-Poly1305?
+/// Its 192-bit nonce is large enough to pick at random without collision
+/// risk, so no per-session counter is needed. ChaCha20-Poly1305 stays the
+/// default for compatibility with existing clients.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CipherSuite {
+    #[default]
+    ChaCha20Poly1305,
+    XChaCha20Poly1305,
+}
+
+impl CipherSuite {
+    /// Nonce length on the wire
+    fn nonce_size(self) -> usize {
+        match self {
+            CipherSuite::ChaCha20Poly1305 => NONCE_SIZE,
+            CipherSuite::XChaCha20Poly1305 => XNONCE_SIZE,
+        }
+    }
+
+    fn hkdf_info(self) -> &'static [u8] {
+        match self {
+            CipherSuite::ChaCha20Poly1305 => HKDF_INFO,
+            CipherSuite::XChaCha20Poly1305 => HKDF_INFO_XCHACHA,
+        }
+    }
+}
+
+/// Cipher instance plus the nonce state it needs
+enum SuiteCipher {
+    ChaCha20Poly1305 {
+        cipher: ChaCha20Poly1305,
+        /// Monotonically increasing nonce counter
+        /// Each encryption increments this to ensure unique nonces
+        nonce_counter: AtomicU64,
+    },
+    XChaCha20Poly1305 {
+        cipher: XChaCha20Poly1305,
+    },
+}
+
+/// Nonces of recently decrypted messages
+///
+/// The set answers "seen before?", the queue remembers insertion order so
+/// the oldest nonce can be evicted once `MAX_SEEN_NONCES` is reached.
+/// Holds message nonces (prefixed with their session nonce) and retired
+/// session nonces.
+#[derive(Default)]
+struct SeenNonces {
+    set: HashSet<Vec<u8>>,
+    order: VecDeque<Vec<u8>>,
+}
+
+impl SeenNonces {
+    /// Record `nonce`; false if it was already recorded
+    fn insert(&mut self, nonce: &[u8]) -> bool {
+        if !self.set.insert(nonce.to_vec()) {
+            return false;
+        }
+        self.order.push_back(nonce.to_vec());
+        if self.order.len() > MAX_SEEN_NONCES {
+            if let Some(oldest) = self.order.pop_front() {
+                self.set.remove(&oldest);
+            }
+        }
+        true
+    }
+}
+
+/// Session nonces of dropped sessions, which can't be used again
+///
+/// Without this, anyone holding the license key and a past session nonce
+/// could rebuild that session's key. Bounded like the replay window: the
+/// nonces are random, so an evicted one is as unlikely to come back as any
+/// other.
+fn retired_session_nonces() -> &'static Mutex<SeenNonces> {
+    static RETIRED: OnceLock<Mutex<SeenNonces>> = OnceLock::new();
+    RETIRED.get_or_init(|| Mutex::new(SeenNonces::default()))
+}
+
 /// Session-based encryption context
 ///
 /// # Why session-based?
 /// - Each app session gets a unique key
 /// - Derived from license key (ties encryption to valid license)
-/// - Nonce counter ensures unique nonces per message
+/// - Nonce counter (ChaCha) or random nonces (XChaCha) keep nonces unique
 ///
 /// # Thread Safety
 /// - AtomicU64 for nonce counter enables concurrent encryption
-/// - ChaCha20Poly1305 is internally immutable after creation
+/// - Both ciphers are internally immutable after creation
+///
+/// # Why keep the raw key?
+/// The cipher copies the key into its own `GenericArray`, which we can't
+/// reach to wipe. Keeping the derived bytes here lets `Drop` zeroize them
+/// instead of leaving them in freed heap memory.
+///
+/// The license key is kept (also wiped on drop) so that `rotate` can
+/// derive a new key without the caller supplying it again.
+///
+/// # Why retire the session nonce?
+/// Dropping the session retires its nonce, so the key can't be rebuilt
+/// later from the license key and a logged nonce.
 pub struct SessionCrypto {
-    /// The ChaCha20-Poly1305 cipher instance
-    cipher: ChaCha20Poly1305,
+    /// The AEAD cipher instance for the selected suite
+    cipher: SuiteCipher,
+
+    /// Derived 256-bit key, wiped on drop
+    key: [u8; 32],
+
+    /// Input key material for re-deriving the key on rotation
+    license_key: Zeroizing<String>,
 
-    /// Monotonically increasing nonce counter
-    /// Each encryption increments this to ensure unique nonces
-    nonce_counter: AtomicU64,
+    /// Salt of the current key, retired on drop
+    session_nonce: [u8; SESSION_NONCE_SIZE],
+
+    /// Nonces already accepted by `decrypt`, to reject replayed messages
+    ///
+    /// Entries are prefixed with the session nonce of the key they were
+    /// accepted under, so the window survives rotation even though the
+    /// nonce counter restarts.
+    seen_nonces: Mutex<SeenNonces>,
+
+    /// Compression applied to outgoing payloads
+    compression: CompressionMode,
 }
 
 impl SessionCrypto {
@@ -106,59 +364,181 @@ impl SessionCrypto {
         license_key: &str,
         session_nonce: &[u8; SESSION_NONCE_SIZE],
     ) -> Result<Self, CryptoError> {
-        // Input Key Material: the license key bytes
-        let ikm = license_key.as_bytes();
+        Self::new(CipherSuite::default(), license_key, session_nonce)
+    }
+
+    /// Create a session crypto context for a specific cipher suite
+    ///
+    /// Key derivation is the same as `from_license`, with a suite-specific
+    /// HKDF info string.
+    ///
+    /// # Errors
+    /// - `InvalidNonceLength` if `session_nonce` is not 16 bytes
+    /// - `SessionNonceReused` if a dropped session used `session_nonce`
+    pub fn new(
+        suite: CipherSuite,
+        license_key: &str,
+        session_nonce: &[u8],
+    ) -> Result<Self, CryptoError> {
+        let (cipher, key) = Self::derive_cipher(suite, license_key, session_nonce)?;
+
+        Ok(Self {
+            cipher,
+            key: *key,
+            license_key: Zeroizing::new(license_key.to_string()),
+            session_nonce: session_nonce
+                .try_into()
+                .map_err(|_| CryptoError::InvalidNonceLength)?,
+            seen_nonces: Mutex::new(SeenNonces::default()),
+            compression: CompressionMode::None,
+        })
+    }
+
+    /// Switch to a key derived from the same license and a new session nonce
+    ///
+    /// The nonce counter starts again at zero; the replay window is kept.
+    /// The old session nonce is retired like on drop, so no later session
+    /// or rotation can return to the old key. If derivation fails, the
+    /// session keeps its current key, so a failed rotation changes nothing.
+    ///
+    /// Messages encrypted with the old key, e.g. a request in flight while
+    /// rotating, no longer decrypt; `decrypt` rejects them with
+    /// `DecryptionFailed` like any other unauthenticated payload.
+    ///
+    /// # Errors
+    /// `SessionNonceReused` if `new_session_nonce` is the current nonce or
+    /// was retired
+    pub fn rotate(
+        &mut self,
+        new_session_nonce: &[u8; SESSION_NONCE_SIZE],
+    ) -> Result<(), CryptoError> {
+        if *new_session_nonce == self.session_nonce {
+            return Err(CryptoError::SessionNonceReused);
+        }
+        let (cipher, key) =
+            Self::derive_cipher(self.suite(), &self.license_key, new_session_nonce)?;
+
+        retired_session_nonces().lock().unwrap().insert(&self.session_nonce);
+        self.cipher = cipher;
+        self.key.zeroize();
+        self.key = *key;
+        self.session_nonce = *new_session_nonce;
+        Ok(())
+    }
+
+    /// Derive the session key and build the suite's cipher from it
+    fn derive_cipher(
+        suite: CipherSuite,
+        license_key: &str,
+        session_nonce: &[u8],
+    ) -> Result<(SuiteCipher, Zeroizing<[u8; 32]>), CryptoError> {
+        if session_nonce.len() != SESSION_NONCE_SIZE {
+            return Err(CryptoError::InvalidNonceLength);
+        }
+        if retired_session_nonces().lock().unwrap().set.contains(session_nonce) {
+            return Err(CryptoError::SessionNonceReused);
+        }
+
+        // Input Key Material: the license key bytes (wiped after derivation)
+        let ikm = Zeroizing::new(license_key.as_bytes().to_vec());
 
         // Salt: random session nonce
         let salt = session_nonce;
 
         // Create HKDF instance
-        let hk = Hkdf::<Sha256>::new(Some(salt), ikm);
+        let hk = Hkdf::<Sha256>::new(Some(salt), &ikm);
 
         // Expand to 256-bit key
-        let mut key = [0u8; 32];
-        hk.expand(HKDF_INFO, &mut key)
+        let mut key = Zeroizing::new([0u8; 32]);
+        hk.expand(suite.hkdf_info(), key.as_mut())
             .map_err(|e| CryptoError::KeyDerivationFailed(e.to_string()))?;
 
         // Create cipher from derived key
-        let cipher = ChaCha20Poly1305::new(&key.into());
+        let cipher = match suite {
+            CipherSuite::ChaCha20Poly1305 => SuiteCipher::ChaCha20Poly1305 {
+                cipher: ChaCha20Poly1305::new(&(*key).into()),
+                nonce_counter: AtomicU64::new(0),
+            },
+            CipherSuite::XChaCha20Poly1305 => SuiteCipher::XChaCha20Poly1305 {
+                cipher: XChaCha20Poly1305::new(&(*key).into()),
+            },
+        };
 
-        Ok(Self {
-            cipher,
-            nonce_counter: AtomicU64::new(0),
-        })
+        Ok((cipher, key))
+    }
+
+    /// Cipher suite of this session
+    pub fn suite(&self) -> CipherSuite {
+        match self.cipher {
+            SuiteCipher::ChaCha20Poly1305 { .. } => CipherSuite::ChaCha20Poly1305,
+            SuiteCipher::XChaCha20Poly1305 { .. } => CipherSuite::XChaCha20Poly1305,
+        }
+    }
+
+    /// Set the compression used by `encrypt`
+    ///
+    /// `decrypt` always follows the mode byte of the incoming message, so
+    /// both sides can switch modes independently.
+    pub fn set_compression(&mut self, mode: CompressionMode) {
+        self.compression = mode;
+    }
+
+    /// Compression used by `encrypt`
+    pub fn compression(&self) -> CompressionMode {
+        self.compression
     }
 
     /// Encrypt plaintext data
     ///
     /// # Returns
-    /// Ciphertext with format: [nonce (12 bytes)][encrypted data + tag]
+    /// Ciphertext with format: [mode (1 byte)][nonce (12 or 24 bytes)][encrypted data + tag]
     ///
     /// # Why prepend nonce?
     /// - Receiver needs nonce to decrypt
     /// - Nonce is not secret, just must be unique
     /// - Prepending is simpler than separate transmission
     pub fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, CryptoError> {
-        // Get next nonce value
-        let counter = self
-            .nonce_counter
-            .fetch_add(1, Ordering::SeqCst);
-
-        // Build 12-byte nonce from counter
-        // First 4 bytes: zeros (could be used for additional entropy)
-        // Last 8 bytes: counter value (little-endian)
-        let mut nonce_bytes = [0u8; NONCE_SIZE];
-        nonce_bytes[4..12].copy_from_slice(&counter.to_le_bytes());
-        let nonce = Nonce::from_slice(&nonce_bytes);
+        // Compress first; ciphertext doesn't compress
+        let payload = self.compression.compress(plaintext)?;
+
+        let (nonce_bytes, encrypted) = match &self.cipher {
+            SuiteCipher::ChaCha20Poly1305 {
+                cipher,
+                nonce_counter,
+            } => {
+                // Get next nonce value
+                let counter = nonce_counter.fetch_add(1, Ordering::SeqCst);
+
+                // Build 12-byte nonce from counter
+                // First byte: direction, next 3 bytes: zeros
+                // Last 8 bytes: counter value (little-endian)
+                let mut nonce_bytes = vec![0u8; NONCE_SIZE];
+                nonce_bytes[0] = SERVER_DIRECTION;
+                nonce_bytes[4..12].copy_from_slice(&counter.to_le_bytes());
+                let encrypted =
+                    cipher.encrypt(Nonce::from_slice(&nonce_bytes), payload.as_slice());
+                (nonce_bytes, encrypted)
+            }
+            SuiteCipher::XChaCha20Poly1305 { cipher } => {
+                // 192-bit random nonce: collisions are negligible
+                use rand::RngCore;
+                let mut nonce_bytes = vec![0u8; XNONCE_SIZE];
+                rand::thread_rng().fill_bytes(&mut nonce_bytes);
+                let encrypted =
+                    cipher.encrypt(XNonce::from_slice(&nonce_bytes), payload.as_slice());
+                (nonce_bytes, encrypted)
+            }
+        };
 
         // Encrypt with AEAD
-        let ciphertext = self
-            .cipher
-            .encrypt(nonce, plaintext)
-            .map_err(|e| CryptoError::EncryptionFailed(e.to_string()))?;
+        let ciphertext = encrypted.map_err(|source| CryptoError::EncryptionFailed {
+            operation: "encrypt".to_string(),
+            source,
+        })?;
 
-        // Prepend nonce to ciphertext
-        let mut result = Vec::with_capacity(NONCE_SIZE + ciphertext.len());
+        // Prepend mode byte and nonce to ciphertext
+        let mut result = Vec::with_capacity(1 + nonce_bytes.len() + ciphertext.len());
+        result.push(self.compression.to_byte());
         result.extend_from_slice(&nonce_bytes);
         result.extend_from_slice(&ciphertext);
 
@@ -168,31 +548,64 @@ impl SessionCrypto {
     /// Decrypt ciphertext data
     ///
     /// # Arguments
-    /// - `ciphertext`: Data with format [nonce (12 bytes)][encrypted + tag]
+    /// - `ciphertext`: Data with format [mode (1 byte)][nonce (12 or 24 bytes)][encrypted + tag]
     ///
     /// # Why AEAD?
     /// - Authentication tag ensures data wasn't tampered with
     /// - Decryption fails if tag doesn't match
     /// - Prevents chosen-ciphertext attacks
+    ///
+    /// # Replay Protection
+    /// A message whose nonce was already accepted (among the last 10,000)
+    /// fails with `ReplayDetected`. Nonces are recorded only after the tag
+    /// checks out, so forged messages can't block future nonces. Counter
+    /// nonces carry a direction byte, so the server's own messages (e.g. a
+    /// ping sent through `secure_invoke`) never block a client counter value.
     pub fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>, CryptoError> {
-        // Validate minimum length (nonce + at least tag)
-        if ciphertext.len() < NONCE_SIZE + 16 {
-            // 16 = Poly1305 tag size
-            return Err(CryptoError::DecryptionFailed(
+        let nonce_size = self.suite().nonce_size();
+
+        // Validate minimum length (mode + nonce + at least tag)
+        if ciphertext.len() < 1 + nonce_size + TAG_SIZE {
+            return Err(CryptoError::InvalidCiphertext(
                 "Ciphertext too short".to_string(),
             ));
         }
 
-        // Extract nonce from first 12 bytes
-        let nonce = Nonce::from_slice(&ciphertext[..NONCE_SIZE]);
+        // Mode byte tells us how to decompress
+        let mode = CompressionMode::from_byte(ciphertext[0])?;
+
+        // Nonce follows the mode byte, the rest is ciphertext + tag
+        let nonce_bytes = &ciphertext[1..1 + nonce_size];
+        let encrypted = &ciphertext[1 + nonce_size..];
+
+        if matches!(self.cipher, SuiteCipher::ChaCha20Poly1305 { .. })
+            && ![CLIENT_DIRECTION, SERVER_DIRECTION].contains(&nonce_bytes[0])
+        {
+            return Err(CryptoError::InvalidCiphertext(
+                "Unknown nonce direction".to_string(),
+            ));
+        }
 
         // Decrypt remaining bytes
-        let plaintext = self
-            .cipher
-            .decrypt(nonce, &ciphertext[NONCE_SIZE..])
-            .map_err(|e| CryptoError::DecryptionFailed(e.to_string()))?;
+        let payload = match &self.cipher {
+            SuiteCipher::ChaCha20Poly1305 { cipher, .. } => {
+                cipher.decrypt(Nonce::from_slice(nonce_bytes), encrypted)
+            }
+            SuiteCipher::XChaCha20Poly1305 { cipher } => {
+                cipher.decrypt(XNonce::from_slice(nonce_bytes), encrypted)
+            }
+        }
+        .map_err(|source| CryptoError::DecryptionFailed {
+            operation: "decrypt".to_string(),
+            source,
+        })?;
 
-        Ok(plaintext)
+        let seen = [self.session_nonce.as_slice(), nonce_bytes].concat();
+        if !self.seen_nonces.lock().unwrap().insert(&seen) {
+            return Err(CryptoError::ReplayDetected);
+        }
+
+        mode.decompress(payload)
     }
 
     /// Generate a random session nonce
@@ -200,18 +613,32 @@ impl SessionCrypto {
     /// # Why use rand?
     /// - Cryptographically secure random bytes
     /// - Each session needs unique nonce for key derivation
-    pub fn generate_session_nonce() -> [u8; SESSION_NONCE_SIZE] {
+    ///
+    /// Returned as `Zeroizing` since it is key derivation input.
+    pub fn generate_session_nonce() -> Zeroizing<[u8; SESSION_NONCE_SIZE]> {
         use rand::RngCore;
-        let mut nonce = [0u8; SESSION_NONCE_SIZE];
-        rand::thread_rng().fill_bytes(&mut nonce);
+        let mut nonce = Zeroizing::new([0u8; SESSION_NONCE_SIZE]);
+        rand::thread_rng().fill_bytes(nonce.as_mut());
         nonce
     }
 }
 
+impl Drop for SessionCrypto {
+    /// Wipe the derived key and retire the session nonce when the session ends
+    fn drop(&mut self) {
+        self.key.zeroize();
+        if let Ok(mut retired) = retired_session_nonces().lock() {
+            retired.insert(&self.session_nonce);
+        }
+        self.session_nonce.zeroize();
+    }
+}
+
 // ============================================================================
 // Secure Command Protocol
 // ============================================================================
 
+use crate::models::ForceGraphClusterMode;
 use serde::{Deserialize, Serialize};
 
 /// Commands that can be invoked through encrypted IPC
@@ -224,36 +651,159 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SecureCommand {
     // Delivery commands
+    /// `from_ts`/`to_ts` are RFC 3339; `sort_by` is a `DeliverySort`, e.g.
+    /// "rating"; `sort_dir` is "asc" or "desc"
     GetDeliveries {
         bike_id: Option<String>,
         status: Option<String>,
+        from_ts: Option<String>,
+        to_ts: Option<String>,
+        sort_by: Option<String>,
+        sort_dir: Option<String>,
+        cursor: Option<String>,
+        limit: Option<u32>,
     },
     GetDeliveryById {
         delivery_id: String,
     },
+    CreateDelivery {
+        bike_id: String,
+        customer_name: String,
+        customer_address: String,
+        restaurant_name: String,
+        restaurant_address: String,
+    },
+    UpdateDeliveryStatus {
+        delivery_id: String,
+        status: String,
+        rating: Option<u8>,
+    },
 
     // Issue commands
+    /// `sort_by` is an `IssueSort`, e.g. "category"; `sort_dir` is "asc" or "desc"
     GetIssues {
         bike_id: Option<String>,
         resolved: Option<bool>,
         category: Option<String>,
+        sort_by: Option<String>,
+        sort_dir: Option<String>,
+        cursor: Option<String>,
+        limit: Option<u32>,
     },
     GetIssueById {
         issue_id: String,
     },
+    /// `bike_id: None` covers the whole fleet
+    GetIssueStatistics {
+        bike_id: Option<String>,
+    },
+    CreateIssue {
+        bike_id: String,
+        delivery_id: Option<String>,
+        reporter_type: String,
+        category: Option<String>,
+        description: String,
+    },
+    ResolveIssue {
+        issue_id: String,
+        note: Option<String>,
+    },
+    ReopenIssue {
+        issue_id: String,
+    },
+    BatchResolveIssues {
+        issue_ids: Vec<String>,
+    },
+
+    // Search
+    Search {
+        query: String,
+        limit: Option<u32>,
+    },
 
     // Force graph commands
     GetForceGraphLayout {
         bike_id: String,
     },
+    GetForceGraphLayoutClustered {
+        bike_id: String,
+        mode: ForceGraphClusterMode,
+    },
+    /// `mode` is the clustering the graph is shown in
     UpdateNodePosition {
         bike_id: String,
         node_id: String,
         x: f64,
         y: f64,
+        mode: ForceGraphClusterMode,
+    },
+    /// Answered with an empty payload
+    ClearNodePins {
+        bike_id: String,
+    },
+    /// Answered with the SVG document as a bincode `String`
+    ExportForceGraphSvg {
+        bike_id: String,
+        width: f64,
+        height: f64,
+    },
+    /// Answered with the GraphML document as a bincode `String`
+    ExportForceGraphGraphml {
+        bike_id: String,
+    },
+    GetFleetForceGraph,
+
+    // Export commands: answered with the CSV document as a bincode `String`
+    ExportFleetCsv,
+    /// `from`/`to` are RFC 3339 bounds on the delivery creation time
+    ExportDeliveriesCsv {
+        from: Option<String>,
+        to: Option<String>,
+    },
+
+    // Session liveness: answered with `SecureResponse::Pong`
+    Ping {
+        sequence: u64,
+    },
+
+    /// Several commands in one encrypted payload, run in order and
+    /// answered with `SecureResponse::Batch`; batches can't be nested
+    Batch {
+        commands: Vec<SecureCommand>,
     },
 }
 
+impl SecureCommand {
+    /// Stable command name for logging and metrics
+    pub fn name(&self) -> &'static str {
+        match self {
+            SecureCommand::GetDeliveries { .. } => "GetDeliveries",
+            SecureCommand::GetDeliveryById { .. } => "GetDeliveryById",
+            SecureCommand::CreateDelivery { .. } => "CreateDelivery",
+            SecureCommand::UpdateDeliveryStatus { .. } => "UpdateDeliveryStatus",
+            SecureCommand::GetIssues { .. } => "GetIssues",
+            SecureCommand::GetIssueById { .. } => "GetIssueById",
+            SecureCommand::GetIssueStatistics { .. } => "GetIssueStatistics",
+            SecureCommand::CreateIssue { .. } => "CreateIssue",
+            SecureCommand::ResolveIssue { .. } => "ResolveIssue",
+            SecureCommand::ReopenIssue { .. } => "ReopenIssue",
+            SecureCommand::BatchResolveIssues { .. } => "BatchResolveIssues",
+            SecureCommand::Search { .. } => "Search",
+            SecureCommand::GetForceGraphLayout { .. } => "GetForceGraphLayout",
+            SecureCommand::GetForceGraphLayoutClustered { .. } => "GetForceGraphLayoutClustered",
+            SecureCommand::UpdateNodePosition { .. } => "UpdateNodePosition",
+            SecureCommand::ClearNodePins { .. } => "ClearNodePins",
+            SecureCommand::ExportForceGraphSvg { .. } => "ExportForceGraphSvg",
+            SecureCommand::ExportForceGraphGraphml { .. } => "ExportForceGraphGraphml",
+            SecureCommand::GetFleetForceGraph => "GetFleetForceGraph",
+            SecureCommand::ExportFleetCsv => "ExportFleetCsv",
+            SecureCommand::ExportDeliveriesCsv { .. } => "ExportDeliveriesCsv",
+            SecureCommand::Ping { .. } => "Ping",
+            SecureCommand::Batch { .. } => "Batch",
+        }
+    }
+}
+
 /// Response wrapper for secure commands
 ///
 /// # Why a wrapper?
@@ -263,6 +813,15 @@ pub enum SecureCommand {
 pub enum SecureResponse {
     Success(Vec<u8>), // Bincode-serialized payload
     Error(String),
+    /// Answer to `SecureCommand::Ping`, echoing its sequence number
+    Pong {
+        sequence: u64,
+        server_time_ms: u64,
+    },
+    /// Answer to `SecureCommand::Batch`, one response per command in order
+    Batch {
+        responses: Vec<SecureResponse>,
+    },
 }
 
 #[cfg(test)]
@@ -282,6 +841,28 @@ mod tests {
         assert_eq!(plaintext.as_slice(), decrypted.as_slice());
     }
 
+    #[test]
+    fn test_dropped_session_not_rederivable_from_license() {
+        let session_nonce = SessionCrypto::generate_session_nonce();
+        let crypto =
+            SessionCrypto::from_license("test-license-key", &session_nonce).unwrap();
+
+        let plaintext = b"Session payload";
+        let ciphertext = crypto.encrypt(plaintext).unwrap();
+        // While the session is alive, the client side derives the same key
+        let client = SessionCrypto::from_license("test-license-key", &session_nonce).unwrap();
+        assert_eq!(client.decrypt(&ciphertext).unwrap(), plaintext);
+        drop(client);
+        drop(crypto);
+
+        // Once dropped, the same license + nonce no longer yields a key
+        // that decrypts it (memory zeroing itself isn't observable)
+        let rederived = SessionCrypto::from_license("test-license-key", &session_nonce);
+        assert!(matches!(rederived, Err(CryptoError::SessionNonceReused)));
+        let ciphertext_decrypted = rederived.and_then(|crypto| crypto.decrypt(&ciphertext));
+        assert!(ciphertext_decrypted.is_err());
+    }
+
     #[test]
     fn test_different_sessions_different_keys() {
         let nonce1 = SessionCrypto::generate_session_nonce();
@@ -298,6 +879,132 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_rotate_invalidates_old_ciphertext() {
+        let session_nonce = SessionCrypto::generate_session_nonce();
+        let mut crypto =
+            SessionCrypto::from_license("test-license-key", &session_nonce).unwrap();
+        let before = crypto.encrypt(b"Sent before rotation").unwrap();
+
+        let new_nonce = SessionCrypto::generate_session_nonce();
+        crypto.rotate(&new_nonce).unwrap();
+
+        let err = crypto.decrypt(&before).unwrap_err();
+        assert!(matches!(err, CryptoError::DecryptionFailed { .. }));
+
+        // The client mirrors the rotation by deriving from the new nonce
+        let after = crypto.encrypt(b"Sent after rotation").unwrap();
+        let mirrored =
+            SessionCrypto::from_license("test-license-key", &new_nonce).unwrap();
+        assert_eq!(mirrored.decrypt(&after).unwrap(), b"Sent after rotation");
+        // Counter restarted: first message after rotation uses counter 0
+        let mut expected = [0u8; NONCE_SIZE];
+        expected[0] = SERVER_DIRECTION;
+        assert_eq!(&after[1..1 + NONCE_SIZE], &expected);
+    }
+
+    #[test]
+    fn test_rotate_never_returns_to_an_earlier_key() {
+        let first_nonce = SessionCrypto::generate_session_nonce();
+        let mut crypto =
+            SessionCrypto::from_license("test-license-key", &first_nonce).unwrap();
+        let client = SessionCrypto::from_license("test-license-key", &first_nonce).unwrap();
+        let request = client.encrypt(b"Sent before rotation").unwrap();
+        assert!(crypto.decrypt(&request).is_ok());
+
+        assert!(matches!(crypto.rotate(&first_nonce), Err(CryptoError::SessionNonceReused)));
+        let second_nonce = SessionCrypto::generate_session_nonce();
+        crypto.rotate(&second_nonce).unwrap();
+        assert!(matches!(crypto.rotate(&first_nonce), Err(CryptoError::SessionNonceReused)));
+        assert!(matches!(
+            SessionCrypto::from_license("test-license-key", &first_nonce),
+            Err(CryptoError::SessionNonceReused)
+        ));
+
+        // The first message under the new key reuses counter 0 without
+        // tripping the replay window, which survived the rotation
+        let client = SessionCrypto::from_license("test-license-key", &second_nonce).unwrap();
+        let request = client.encrypt(b"Sent after rotation").unwrap();
+        assert_eq!(crypto.decrypt(&request).unwrap(), b"Sent after rotation");
+        assert!(matches!(crypto.decrypt(&request), Err(CryptoError::ReplayDetected)));
+    }
+
+    #[test]
+    fn test_replayed_ciphertext_fails() {
+        let session_nonce = SessionCrypto::generate_session_nonce();
+        let crypto =
+            SessionCrypto::from_license("test-license-key", &session_nonce).unwrap();
+
+        let first = crypto.encrypt(b"Same message").unwrap();
+        let second = crypto.encrypt(b"Same message").unwrap();
+
+        assert_eq!(crypto.decrypt(&first).unwrap(), b"Same message");
+        assert_eq!(crypto.decrypt(&second).unwrap(), b"Same message");
+        assert!(matches!(crypto.decrypt(&first), Err(CryptoError::ReplayDetected)));
+    }
+
+    /// Encrypt like the client: counter nonce with the client direction byte
+    fn client_encrypt(crypto: &SessionCrypto, counter: u64, plaintext: &[u8]) -> Vec<u8> {
+        let SuiteCipher::ChaCha20Poly1305 { cipher, .. } = &crypto.cipher else {
+            panic!("client_encrypt needs a counter-nonce suite");
+        };
+        let mut nonce_bytes = [0u8; NONCE_SIZE];
+        nonce_bytes[0] = CLIENT_DIRECTION;
+        nonce_bytes[4..12].copy_from_slice(&counter.to_le_bytes());
+        let encrypted = cipher.encrypt(Nonce::from_slice(&nonce_bytes), plaintext).unwrap();
+
+        let mut message = vec![CompressionMode::None.to_byte()];
+        message.extend_from_slice(&nonce_bytes);
+        message.extend_from_slice(&encrypted);
+        message
+    }
+
+    #[test]
+    fn test_server_messages_do_not_block_client_counters() {
+        let session_nonce = SessionCrypto::generate_session_nonce();
+        let crypto =
+            SessionCrypto::from_license("test-license-key", &session_nonce).unwrap();
+        let client = SessionCrypto::from_license("test-license-key", &session_nonce).unwrap();
+
+        // A ping: the server encrypts the request and its response with
+        // counters 0 and 1, and decrypts both
+        let request = crypto.encrypt(b"Ping").unwrap();
+        assert_eq!(crypto.decrypt(&request).unwrap(), b"Ping");
+        let response = crypto.encrypt(b"Pong").unwrap();
+        assert_eq!(crypto.decrypt(&response).unwrap(), b"Pong");
+
+        // The client's own counters 0 and 1 are still accepted, once each
+        let first = client_encrypt(&client, 0, b"First");
+        let second = client_encrypt(&client, 1, b"Second");
+        assert_eq!(crypto.decrypt(&first).unwrap(), b"First");
+        assert_eq!(crypto.decrypt(&second).unwrap(), b"Second");
+        assert!(matches!(crypto.decrypt(&first), Err(CryptoError::ReplayDetected)));
+    }
+
+    #[test]
+    fn test_unknown_nonce_direction_fails() {
+        let session_nonce = SessionCrypto::generate_session_nonce();
+        let crypto =
+            SessionCrypto::from_license("test-license-key", &session_nonce).unwrap();
+
+        let mut message = crypto.encrypt(b"Hello").unwrap();
+        message[1] = 0xFF;
+
+        assert!(matches!(crypto.decrypt(&message), Err(CryptoError::InvalidCiphertext(_))));
+    }
+
+    #[test]
+    fn test_seen_nonces_evicts_oldest() {
+        let mut seen = SeenNonces::default();
+        for i in 0..=MAX_SEEN_NONCES as u64 {
+            assert!(seen.insert(&i.to_le_bytes()));
+        }
+
+        assert_eq!(seen.set.len(), MAX_SEEN_NONCES);
+        assert!(seen.insert(&0u64.to_le_bytes())); // Evicted, so accepted again
+        assert!(!seen.insert(&(MAX_SEEN_NONCES as u64).to_le_bytes()));
+    }
+
     #[test]
     fn test_tampered_ciphertext_fails() {
         let session_nonce = SessionCrypto::generate_session_nonce();
@@ -313,8 +1020,9 @@ mod tests {
         }
 
         // Decryption should fail due to authentication tag mismatch
-        let result = crypto.decrypt(&ciphertext);
-        assert!(result.is_err());
+        let err = crypto.decrypt(&ciphertext).unwrap_err();
+        assert!(matches!(err, CryptoError::DecryptionFailed { .. }));
+        assert!(err.to_string().starts_with("DecryptionFailed while decrypt"));
     }
 
     #[test]
@@ -338,6 +1046,90 @@ mod tests {
         assert_eq!(decrypted1, decrypted2);
     }
 
+    #[test]
+    fn test_cipher_suite_roundtrip() {
+        for (suite, nonce_size) in [
+            (CipherSuite::ChaCha20Poly1305, NONCE_SIZE),
+            (CipherSuite::XChaCha20Poly1305, XNONCE_SIZE),
+        ] {
+            let session_nonce = SessionCrypto::generate_session_nonce();
+            let crypto =
+                SessionCrypto::new(suite, "test-license-key", session_nonce.as_ref()).unwrap();
+            assert_eq!(crypto.suite(), suite);
+
+            let plaintext = b"Hello, encrypted world!";
+            let ciphertext = crypto.encrypt(plaintext).unwrap();
+
+            assert_eq!(ciphertext.len(), 1 + nonce_size + plaintext.len() + TAG_SIZE);
+            assert_ne!(crypto.encrypt(plaintext).unwrap(), ciphertext);
+            assert_eq!(crypto.decrypt(&ciphertext).unwrap(), plaintext);
+        }
+    }
+
+    #[test]
+    fn test_cipher_suites_cannot_decrypt_each_other() {
+        let session_nonce = SessionCrypto::generate_session_nonce();
+        let chacha = SessionCrypto::new(
+            CipherSuite::ChaCha20Poly1305,
+            "test-license-key",
+            session_nonce.as_ref(),
+        )
+        .unwrap();
+        let xchacha = SessionCrypto::new(
+            CipherSuite::XChaCha20Poly1305,
+            "test-license-key",
+            session_nonce.as_ref(),
+        )
+        .unwrap();
+
+        let plaintext = b"Cross-suite payload";
+        assert!(xchacha.decrypt(&chacha.encrypt(plaintext).unwrap()).is_err());
+        assert!(chacha.decrypt(&xchacha.encrypt(plaintext).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_new_rejects_bad_session_nonce() {
+        assert!(matches!(
+            SessionCrypto::new(CipherSuite::XChaCha20Poly1305, "test-license-key", &[0u8; 8]),
+            Err(CryptoError::InvalidNonceLength)
+        ));
+    }
+
+    #[test]
+    fn test_compression_roundtrip() {
+        let session_nonce = SessionCrypto::generate_session_nonce();
+        let mut crypto =
+            SessionCrypto::from_license("test-license-key", &session_nonce).unwrap();
+
+        // Repetitive payload, like a serialized force graph
+        let plaintext = b"node:BIKE-0001;x=12.5;y=-3.25;".repeat(1000);
+        let uncompressed_len = crypto.encrypt(&plaintext).unwrap().len();
+
+        for mode in [CompressionMode::Lz4, CompressionMode::Zstd] {
+            crypto.set_compression(mode);
+            let ciphertext = crypto.encrypt(&plaintext).unwrap();
+
+            assert_eq!(ciphertext[0], mode.to_byte());
+            assert!(ciphertext.len() < uncompressed_len);
+            assert_eq!(crypto.decrypt(&ciphertext).unwrap(), plaintext);
+        }
+    }
+
+    #[test]
+    fn test_unknown_compression_mode_fails() {
+        let session_nonce = SessionCrypto::generate_session_nonce();
+        let crypto =
+            SessionCrypto::from_license("test-license-key", &session_nonce).unwrap();
+
+        let mut ciphertext = crypto.encrypt(b"payload").unwrap();
+        ciphertext[0] = 0x7F;
+
+        assert!(matches!(
+            crypto.decrypt(&ciphertext),
+            Err(CryptoError::InvalidCiphertext(_))
+        ));
+    }
+
     #[test]
     fn test_bincode_command_serialization() {
         let cmd = SecureCommand::GetForceGraphLayout {
@@ -354,4 +1146,32 @@ mod tests {
             _ => panic!("Wrong variant"),
         }
     }
+
+    #[test]
+    fn test_batch_is_one_encrypted_payload() {
+        let session_nonce = SessionCrypto::generate_session_nonce();
+        let crypto =
+            SessionCrypto::from_license("test-license-key", &session_nonce).unwrap();
+        let batch = SecureCommand::Batch {
+            commands: vec![
+                SecureCommand::GetFleetForceGraph,
+                SecureCommand::GetForceGraphLayout {
+                    bike_id: "BIKE-0001".to_string(),
+                },
+                SecureCommand::Ping { sequence: 7 },
+            ],
+        };
+
+        let ciphertext = crypto.encrypt(&bincode::serialize(&batch).unwrap()).unwrap();
+        let decrypted: SecureCommand =
+            bincode::deserialize(&crypto.decrypt(&ciphertext).unwrap()).unwrap();
+
+        match decrypted {
+            SecureCommand::Batch { commands } => {
+                let names: Vec<_> = commands.iter().map(|c| c.name()).collect();
+                assert_eq!(names, ["GetFleetForceGraph", "GetForceGraphLayout", "Ping"]);
+            }
+            _ => panic!("Wrong variant"),
+        }
+    }
 }
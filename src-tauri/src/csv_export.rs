@@ -0,0 +1,163 @@
+//! Fleet and Delivery CSV Export
+//!
+//! # Purpose
+//! Turns bikes and deliveries into CSV documents that Excel and Power BI
+//! open directly, so both backends only have to load the rows.
+//!
+//! # Format
+//! A header row, then one record per row. Records end in `\r\n` (Excel
+//! expects it). Timestamps are RFC 3339; missing values are empty fields.
+
+use crate::models::{Bike, Delivery};
+
+const FLEET_COLUMNS: [&str; 9] = [
+    "id",
+    "name",
+    "status",
+    "latitude",
+    "longitude",
+    "battery_level",
+    "total_trips",
+    "total_distance_km",
+    "created_at",
+];
+
+const DELIVERY_COLUMNS: [&str; 12] = [
+    "id",
+    "bike_id",
+    "status",
+    "priority",
+    "customer_name",
+    "customer_address",
+    "restaurant_name",
+    "restaurant_address",
+    "rating",
+    "complaint",
+    "created_at",
+    "completed_at",
+];
+
+/// CSV of the given bikes, one row each
+pub fn fleet_csv(bikes: &[Bike]) -> Result<String, String> {
+    write_csv(&FLEET_COLUMNS, bikes.iter().map(|bike| {
+        vec![
+            bike.id.clone(),
+            bike.name.clone(),
+            bike.status.as_str().to_string(),
+            bike.latitude.to_string(),
+            bike.longitude.to_string(),
+            bike.battery_level.map(|b| b.to_string()).unwrap_or_default(),
+            bike.total_trips.to_string(),
+            bike.total_distance_km.to_string(),
+            bike.created_at.to_rfc3339(),
+        ]
+    }))
+}
+
+/// CSV of the given deliveries, one row each
+pub fn deliveries_csv(deliveries: &[Delivery]) -> Result<String, String> {
+    write_csv(&DELIVERY_COLUMNS, deliveries.iter().map(|delivery| {
+        vec![
+            delivery.id.clone(),
+            delivery.bike_id.clone(),
+            delivery.status.as_str().to_string(),
+            delivery.priority.as_str().to_string(),
+            delivery.customer_name.clone(),
+            delivery.customer_address.clone(),
+            delivery.restaurant_name.clone(),
+            delivery.restaurant_address.clone(),
+            delivery.rating.map(|r| r.to_string()).unwrap_or_default(),
+            delivery.complaint.clone().unwrap_or_default(),
+            delivery.created_at.to_rfc3339(),
+            delivery.completed_at.map(|at| at.to_rfc3339()).unwrap_or_default(),
+        ]
+    }))
+}
+
+fn write_csv(
+    columns: &[&str],
+    rows: impl Iterator<Item = Vec<String>>,
+) -> Result<String, String> {
+    let mut writer = csv::WriterBuilder::new()
+        .has_headers(true)
+        .terminator(csv::Terminator::CRLF)
+        .from_writer(Vec::new());
+
+    writer.write_record(columns).map_err(|e| e.to_string())?;
+    for row in rows {
+        writer.write_record(&row).map_err(|e| e.to_string())?;
+    }
+
+    let bytes = writer.into_inner().map_err(|e| e.to_string())?;
+    String::from_utf8(bytes).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{BikeStatus, DeliveryPriority, DeliveryStatus};
+    use chrono::Utc;
+
+    fn bike() -> Bike {
+        let now = Utc::now();
+        Bike {
+            id: "BIKE-0001".to_string(),
+            name: "Fiets, de Eerste".to_string(),
+            status: BikeStatus::Available,
+            latitude: 52.37,
+            longitude: 4.89,
+            battery_level: None,
+            last_maintenance: None,
+            total_trips: 12,
+            total_distance_km: 34.5,
+            reservation_expires_at: None,
+            maintenance_threshold_override: None,
+            deleted_at: None,
+            next_maintenance_at: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn test_fleet_csv_has_header_and_crlf_rows() {
+        let csv = fleet_csv(&[bike()]).unwrap();
+
+        let mut lines = csv.split("\r\n");
+        assert_eq!(
+            lines.next(),
+            Some("id,name,status,latitude,longitude,battery_level,total_trips,total_distance_km,created_at")
+        );
+        let row = lines.next().unwrap();
+        assert!(row.starts_with("BIKE-0001,\"Fiets, de Eerste\",available,52.37,4.89,,12,34.5,"));
+        assert_eq!(lines.next(), Some(""));
+        assert!(!csv.replace("\r\n", "").contains('\n'));
+    }
+
+    #[test]
+    fn test_deliveries_csv_has_header_and_crlf_rows() {
+        let delivery = Delivery {
+            id: "DEL-0001".to_string(),
+            bike_id: "BIKE-0001".to_string(),
+            status: DeliveryStatus::Upcoming,
+            priority: DeliveryPriority::Normal,
+            customer_name: "P. de Vries".to_string(),
+            customer_address: "Damrak 1".to_string(),
+            restaurant_name: "Febo".to_string(),
+            restaurant_address: "Rokin 2".to_string(),
+            rating: None,
+            complaint: None,
+            created_at: Utc::now(),
+            completed_at: None,
+        };
+
+        let csv = deliveries_csv(&[delivery]).unwrap();
+
+        assert!(csv.starts_with(
+            "id,bike_id,status,priority,customer_name,customer_address,restaurant_name,\
+             restaurant_address,rating,complaint,created_at,completed_at\r\n"
+        ));
+        assert!(csv.ends_with(",\r\n"));
+        assert_eq!(csv.matches("\r\n").count(), 2);
+    }
+}
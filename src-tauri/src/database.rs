@@ -1,21 +1,65 @@
+use crate::analytics;
+use crate::categorization::category_or_suggested;
+use crate::geo;
+use crate::migrations::MigrationRunner;
+use crate::simulation;
+use crate::slow_query_log::{SlowQueryLogger, SlowQueryRecord};
+use crate::sync_queue::{OfflineSyncQueue, SyncOperation};
 use crate::models::{
-    Bike, BikeStatus, DatabaseStats,
-    Delivery, DeliveryStatus,
-    Issue, IssueCategory, IssueReporterType,
+    AddBikeRequest, AuditLogEntry, AuditOp, Bike, BikeCorrelationPoint, BikeStatus,
+    BikesByStatusResult, CreateDeliveryRequest, CreateIssueRequest, DatabaseStats, Delivery,
+    DeliveryPage, DeliveryPriority, DeliveryStatus, FleetEvent, FleetEventType, FleetTimeSeries,
+    FlushResult, HeatmapCell,
+    HourlyUtilization, Issue, IssueCategory, IssueCategoryStats, IssuePage,
+    IssueRatingCorrelation, IssueReporterType, IssueStatistics,
+    Page, PageCursor, RestaurantStats, SearchResults, SeedConfig, SimulationScenario,
+    BikeSort, DeliverySort, IssueSort, SortDirection, TimeBucket, Waypoint, MAX_PAGE_SIZE,
 };
-use chrono::Utc;
-use rusqlite::{Connection, OptionalExtension, Result as SqliteResult};
-use std::path::PathBuf;
+use chrono::{DateTime, Utc};
+use rusqlite::{Connection, OpenFlags, OptionalExtension, Result as SqliteResult};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum DatabaseError {
-    #[error("SQLite error: {0}")]
-    Sqlite(#[from] rusqlite::Error),
+    #[error("QueryFailed while {operation}: {source}")]
+    QueryFailed {
+        operation: String,
+        source: rusqlite::Error,
+    },
     #[error("Database not initialized")]
     NotInitialized,
     #[error("Invalid data: {0}")]
     InvalidData(String),
+    #[error("Migration {0} failed: {1}")]
+    MigrationFailed(u32, String),
+    #[error("Database unavailable; the write was queued until the next flush_sync_queue")]
+    Queued,
+}
+
+impl DatabaseError {
+    /// Whether the failure may go away without changing the request:
+    /// the database was busy, locked or its storage briefly unavailable
+    pub fn is_transient(&self) -> bool {
+        use rusqlite::ErrorCode;
+
+        match self {
+            DatabaseError::QueryFailed {
+                source: rusqlite::Error::SqliteFailure(error, _),
+                ..
+            } => matches!(
+                error.code,
+                ErrorCode::DatabaseBusy
+                    | ErrorCode::DatabaseLocked
+                    | ErrorCode::SystemIoFailure
+                    | ErrorCode::CannotOpen
+                    | ErrorCode::DiskFull
+            ),
+            _ => false,
+        }
+    }
 }
 
 impl serde::Serialize for DatabaseError {
@@ -27,111 +71,205 @@ impl serde::Serialize for DatabaseError {
     }
 }
 
+/// Attach the failing operation to SQLite errors
+///
+/// # Why?
+/// A bare "no such column" reaching the frontend doesn't say which query
+/// broke; `QueryFailed while get_all_bikes: ...` does.
+pub(crate) trait QueryContext<T> {
+    fn context(self, operation: &str) -> Result<T, DatabaseError>;
+}
+
+impl<T> QueryContext<T> for Result<T, rusqlite::Error> {
+    fn context(self, operation: &str) -> Result<T, DatabaseError> {
+        self.map_err(|source| DatabaseError::QueryFailed {
+            operation: operation.to_string(),
+            source,
+        })
+    }
+}
+
+/// Connection tuning for SQLite
+///
+/// # Why WAL and a busy timeout?
+/// In WAL mode readers never block the writer, and a busy timeout makes a
+/// contended write retry for a while instead of failing with "database is
+/// locked" right away.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SqliteConfig {
+    /// Write-ahead logging instead of the rollback journal
+    pub wal_mode: bool,
+    /// How long a write waits for a lock before failing; 0 fails immediately
+    pub busy_timeout_ms: u32,
+    /// Log full table scans and statements slower than this (see
+    /// `slow_query_log`); None disables the checks
+    pub log_threshold_ms: Option<u32>,
+}
+
+impl SqliteConfig {
+    /// `log_threshold_ms` as a Duration
+    pub fn log_threshold(&self) -> Option<Duration> {
+        self.log_threshold_ms.map(|ms| Duration::from_millis(ms.into()))
+    }
+}
+
+impl Default for SqliteConfig {
+    fn default() -> Self {
+        Self {
+            wal_mode: true,
+            busy_timeout_ms: 5000,
+            log_threshold_ms: None,
+        }
+    }
+}
+
 /// Database wrapper for SQLite operations
 pub struct Database {
-    conn: Connection,
+    conn: SlowQueryLogger,
+    sqlite_config: SqliteConfig,
+    /// Where `with_queue_fallback` parks writes; see `attach_sync_queue`
+    sync_queue: Option<OfflineSyncQueue>,
 }
 
 impl Database {
     /// Initialize a new database connection
-    pub fn new(path: PathBuf) -> Result<Self, DatabaseError> {
-        let conn = Connection::open(&path)?;
-        let db = Database { conn };
+    pub fn new(path: PathBuf, sqlite_config: SqliteConfig) -> Result<Self, DatabaseError> {
+        let db = Self::open(&path, sqlite_config)?;
         db.initialize_schema()?;
         db.seed_mock_data()?;
         Ok(db)
     }
 
+    /// Initialize a new database connection, seeding an empty database
+    /// from `seed_config` instead of the Amsterdam demo data
+    pub fn with_seed_config(
+        path: PathBuf,
+        sqlite_config: SqliteConfig,
+        seed_config: SeedConfig,
+    ) -> Result<Self, DatabaseError> {
+        let db = Self::open(&path, sqlite_config)?;
+        db.initialize_schema()?;
+        db.seed_custom_data(seed_config)?;
+        Ok(db)
+    }
+
+    fn open(path: &Path, sqlite_config: SqliteConfig) -> Result<Self, DatabaseError> {
+        let conn = Connection::open(path).context("open_database")?;
+        let mut db = Database {
+            conn: SlowQueryLogger::new(conn, sqlite_config.log_threshold()),
+            sqlite_config,
+            sync_queue: None,
+        };
+        db.set_pragmas(sqlite_config)?;
+        Ok(db)
+    }
+
+    /// Connection tuning currently in effect
+    pub fn sqlite_config(&self) -> SqliteConfig {
+        self.sqlite_config
+    }
+
+    /// Full table scans and slow statements logged so far, newest first
+    pub fn slow_query_report(&self) -> Vec<SlowQueryRecord> {
+        self.conn.records()
+    }
+
+    /// Apply journal mode, busy timeout and slow query threshold to the
+    /// open connection
+    ///
+    /// # Returns
+    /// The resulting journal mode; in-memory databases stay `memory` even
+    /// when WAL is requested
+    pub fn set_pragmas(&mut self, sqlite_config: SqliteConfig) -> Result<String, DatabaseError> {
+        let journal_mode: String = self
+            .conn
+            .pragma_update_and_check(
+                None,
+                "journal_mode",
+                if sqlite_config.wal_mode { "WAL" } else { "DELETE" },
+                |row| row.get(0),
+            )
+            .context("set_pragmas")?;
+        self.conn
+            .busy_timeout(Duration::from_millis(sqlite_config.busy_timeout_ms.into()))
+            .context("set_pragmas")?;
+        self.conn.set_threshold(sqlite_config.log_threshold());
+
+        self.sqlite_config = sqlite_config;
+        Ok(journal_mode)
+    }
+
     /// Initialize the database schema
+    ///
+    /// Brings databases from before versioned migrations up to migration 1
+    /// first, then applies pending migrations (see `migrations.rs`).
     fn initialize_schema(&self) -> Result<(), DatabaseError> {
-        self.conn.execute_batch(
-            r#"
-            CREATE TABLE IF NOT EXISTS bikes (
-                id TEXT PRIMARY KEY,
-                name TEXT NOT NULL,
-                status TEXT NOT NULL DEFAULT 'available',
-                latitude REAL NOT NULL,
-                longitude REAL NOT NULL,
-                battery_level INTEGER,
-                last_maintenance TEXT,
-                total_trips INTEGER NOT NULL DEFAULT 0,
-                total_distance_km REAL NOT NULL DEFAULT 0.0,
-                created_at TEXT NOT NULL,
-                updated_at TEXT NOT NULL
-            );
+        // SQLite leaves foreign keys unenforced unless enabled per connection;
+        // the pragma is a no-op inside a transaction, so it can't be a migration
+        self.conn
+            .execute_batch("PRAGMA foreign_keys = ON;")
+            .context("initialize_schema")?;
 
-            CREATE TABLE IF NOT EXISTS trips (
-                id TEXT PRIMARY KEY,
-                bike_id TEXT NOT NULL,
-                start_time TEXT NOT NULL,
-                end_time TEXT,
-                start_latitude REAL NOT NULL,
-                start_longitude REAL NOT NULL,
-                end_latitude REAL,
-                end_longitude REAL,
-                distance_km REAL,
-                FOREIGN KEY (bike_id) REFERENCES bikes(id)
-            );
+        self.upgrade_legacy_schema()?;
+        MigrationRunner::default().run(&self.conn)?;
 
-            CREATE INDEX IF NOT EXISTS idx_bikes_status ON bikes(status);
-            CREATE INDEX IF NOT EXISTS idx_trips_bike_id ON trips(bike_id);
-
-            -- ================================================================
-            -- Deliveries table
-            -- ================================================================
-            -- Why this schema?
-            -- - bike_id links to the courier for force graph relationships
-            -- - status enables filtering (completed/ongoing/upcoming)
-            -- - rating/complaint only populated for completed deliveries
-            -- - Timestamps enable time-series analytics
-            CREATE TABLE IF NOT EXISTS deliveries (
-                id TEXT PRIMARY KEY,
-                bike_id TEXT NOT NULL,
-                status TEXT NOT NULL DEFAULT 'upcoming',
-                customer_name TEXT NOT NULL,
-                customer_address TEXT NOT NULL,
-                restaurant_name TEXT NOT NULL,
-                restaurant_address TEXT NOT NULL,
-                rating INTEGER,
-                complaint TEXT,
-                created_at TEXT NOT NULL,
-                completed_at TEXT,
-                FOREIGN KEY (bike_id) REFERENCES bikes(id)
-            );
+        Ok(())
+    }
 
-            -- ================================================================
-            -- Issues table
-            -- ================================================================
-            -- Why this schema?
-            -- - delivery_id is optional: issues can be standalone (bike problems)
-            -- - bike_id always present: every issue links to a deliverer
-            -- - This dual-linking enables force graph to show:
-            --   * Issues connected to specific deliveries
-            --   * Standalone issues connected directly to deliverer
-            CREATE TABLE IF NOT EXISTS issues (
-                id TEXT PRIMARY KEY,
-                delivery_id TEXT,
-                bike_id TEXT NOT NULL,
-                reporter_type TEXT NOT NULL,
-                category TEXT NOT NULL,
-                description TEXT NOT NULL,
-                resolved INTEGER NOT NULL DEFAULT 0,
-                created_at TEXT NOT NULL,
-                FOREIGN KEY (delivery_id) REFERENCES deliveries(id),
-                FOREIGN KEY (bike_id) REFERENCES bikes(id)
-            );
+    /// Add the columns that databases created before versioned migrations
+    /// may lack, so that migration 1 (all `IF NOT EXISTS`) matches them
+    fn upgrade_legacy_schema(&self) -> Result<(), DatabaseError> {
+        // Databases created before issues.updated_at existed
+        if self.is_missing_column("issues", "updated_at")? {
+            self.conn
+                .execute_batch(
+                    r#"
+                ALTER TABLE issues ADD COLUMN updated_at TEXT NOT NULL DEFAULT '';
+                UPDATE issues SET updated_at = created_at;
+                "#,
+                )
+                .context("upgrade_legacy_schema")?;
+        }
+
+        // Databases created before bike reservations existed
+        if self.is_missing_column("bikes", "reservation_expires_at")? {
+            self.conn
+                .execute_batch("ALTER TABLE bikes ADD COLUMN reservation_expires_at TEXT;")
+                .context("upgrade_legacy_schema")?;
+        }
+
+        // Databases created before per-bike maintenance thresholds existed
+        if self.is_missing_column("bikes", "maintenance_threshold_override")? {
+            self.conn
+                .execute_batch("ALTER TABLE bikes ADD COLUMN maintenance_threshold_override INTEGER;")
+                .context("upgrade_legacy_schema")?;
+        }
+
+        // Databases created before delivery priorities existed
+        if self.is_missing_column("deliveries", "priority")? {
+            self.conn
+                .execute_batch(
+                    "ALTER TABLE deliveries ADD COLUMN priority TEXT NOT NULL DEFAULT 'normal';",
+                )
+                .context("upgrade_legacy_schema")?;
+        }
 
-            -- Indexes for efficient querying
-            CREATE INDEX IF NOT EXISTS idx_deliveries_bike_id ON deliveries(bike_id);
-            CREATE INDEX IF NOT EXISTS idx_deliveries_status ON deliveries(status);
-            CREATE INDEX IF NOT EXISTS idx_issues_bike_id ON issues(bike_id);
-            CREATE INDEX IF NOT EXISTS idx_issues_delivery_id ON issues(delivery_id);
-            CREATE INDEX IF NOT EXISTS idx_issues_resolved ON issues(resolved);
-            "#,
-        )?;
         Ok(())
     }
 
+    /// Whether `table` exists but has no `column`
+    fn is_missing_column(&self, table: &str, column: &str) -> Result<bool, DatabaseError> {
+        self.conn
+            .query_row(
+                r#"SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1)
+                   AND NOT EXISTS (SELECT 1 FROM pragma_table_info(?1) WHERE name = ?2)"#,
+                [table, column],
+                |row| row.get(0),
+            )
+            .context("upgrade_legacy_schema")
+    }
+
     /// Seed the database with mock Amsterdam bike data
     ///
     /// # Why seed data?
@@ -139,60 +277,59 @@ impl Database {
     /// - Provides realistic Dutch names and Amsterdam addresses
     /// - Creates interconnected deliveries and issues for force graph demo
     fn seed_mock_data(&self) -> Result<(), DatabaseError> {
+        self.seed_custom_data(SeedConfig::amsterdam())
+    }
+
+    /// Seed an empty database from a locale-specific config
+    ///
+    /// Creates one bike per location, then 50 deliveries and 20 issues
+    /// spread across those bikes. Does nothing if bikes already exist.
+    pub fn seed_custom_data(&self, config: SeedConfig) -> Result<(), DatabaseError> {
+        config.validate().map_err(DatabaseError::InvalidData)?;
+
         // Check if we already have data
         let count: i64 = self
             .conn
-            .query_row("SELECT COUNT(*) FROM bikes", [], |row| row.get(0))?;
+            .query_row("SELECT COUNT(*) FROM bikes", [], |row| row.get(0))
+            .context("seed_custom_data")?;
 
         if count > 0 {
             return Ok(());
         }
 
-        // Amsterdam coordinates for various locations
-        let amsterdam_locations = vec![
-            ("Central Station", 52.3791, 4.9003),
-            ("Dam Square", 52.3731, 4.8932),
-            ("Vondelpark", 52.3579, 4.8686),
-            ("Rijksmuseum", 52.3600, 4.8852),
-            ("Anne Frank House", 52.3752, 4.8840),
-            ("Jordaan", 52.3747, 4.8797),
-            ("De Pijp", 52.3533, 4.8936),
-            ("Oost", 52.3614, 4.9366),
-            ("Noord", 52.3907, 4.9228),
-            ("Amstel", 52.3632, 4.9039),
-        ];
-
         let now = Utc::now();
         let now_str = now.to_rfc3339();
         let statuses = ["available", "available", "available", "in_use", "charging"];
 
-        for (i, (name, lat, lon)) in amsterdam_locations.iter().enumerate() {
+        for (i, (name, lat, lon)) in config.locations.iter().enumerate() {
             let id = format!("BIKE-{:04}", i + 1);
-            let bike_name = format!("Amsterdam {} Bike", name);
+            let bike_name = format!("{} Bike", name);
             let status = statuses[i % statuses.len()];
             let battery = 20 + (i * 8) % 80;
 
-            self.conn.execute(
-                r#"INSERT INTO bikes (id, name, status, latitude, longitude, battery_level,
+            self.conn
+                .execute(
+                    r#"INSERT INTO bikes (id, name, status, latitude, longitude, battery_level,
                    total_trips, total_distance_km, created_at, updated_at)
                    VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)"#,
-                rusqlite::params![
-                    id,
-                    bike_name,
-                    status,
-                    lat,
-                    lon,
-                    battery as i32,
-                    (i * 17) % 200,
-                    (i as f64 * 12.5) % 500.0,
-                    now_str,
-                    now_str
-                ],
-            )?;
+                    rusqlite::params![
+                        id,
+                        bike_name,
+                        status,
+                        lat,
+                        lon,
+                        battery as i32,
+                        (i * 17) % 200,
+                        (i as f64 * 12.5) % 500.0,
+                        now_str,
+                        now_str
+                    ],
+                )
+                .context("seed_custom_data")?;
         }
 
         // Seed deliveries and issues
-        self.seed_deliveries_and_issues()?;
+        self.seed_deliveries_and_issues(&config)?;
 
         Ok(())
     }
@@ -200,34 +337,18 @@ impl Database {
     /// Seed deliveries and issues for demonstration
     ///
     /// # Why separate method?
-    /// - Keeps seed_mock_data focused on bikes
+    /// - Keeps seed_custom_data focused on bikes
     /// - Deliveries/issues are dependent on bikes existing first
-    fn seed_deliveries_and_issues(&self) -> Result<(), DatabaseError> {
+    fn seed_deliveries_and_issues(&self, config: &SeedConfig) -> Result<(), DatabaseError> {
         let now = Utc::now();
+        let bike_count = config.locations.len();
+        let customer_names = &config.customer_names;
+        let restaurant_names = &config.restaurant_names;
+        let streets = &config.streets;
 
-        // Dutch customer names
-        let customer_names = [
-            "P. de Vries", "M. Jansen", "A. Bakker", "J. van Dijk", "S. Visser",
-            "L. Smit", "K. Mulder", "R. de Boer", "T. Bos", "E. van den Berg",
-            "H. Dekker", "F. Vermeer", "B. van Leeuwen", "N. Kok", "D. Peters",
-        ];
-
-        // Restaurant names
-        let restaurant_names = [
-            "De Pizzabakker", "Wok to Walk", "Febo", "New York Pizza", "Dominos",
-            "Thai Express", "Sushi Time", "Burger King", "McDonalds", "Subway",
-            "La Place", "Vapiano", "Bagels & Beans", "De Italiaan", "Ramen Ya",
-        ];
-
-        // Amsterdam streets
-        let streets = [
-            "Damrak", "Rokin", "Kalverstraat", "Leidsestraat", "Utrechtsestraat",
-            "Overtoom", "Kinkerstraat", "Ferdinand Bolstraat", "Javastraat", "Plantage",
-        ];
-
-        // Create 50 deliveries across 10 bikes
+        // Create 50 deliveries across all bikes
         for i in 0..50 {
-            let bike_id = format!("BIKE-{:04}", (i % 10) + 1);
+            let bike_id = format!("BIKE-{:04}", (i % bike_count) + 1);
             let delivery_id = format!("DEL-{:04}", i + 1);
 
             // Deterministic but varied status distribution
@@ -237,6 +358,13 @@ impl Database {
                 _ => "upcoming",
             };
 
+            // A few rush orders so the priority queue has something to sort
+            let priority = match i % 12 {
+                0 => "urgent",
+                1 | 7 => "high",
+                _ => "normal",
+            };
+
             // Only completed deliveries have ratings/complaints
             let rating: Option<i32> = if status == "completed" && i % 3 == 0 {
                 Some(((i % 5) + 1) as i32)
@@ -258,26 +386,29 @@ impl Database {
                 None
             };
 
-            self.conn.execute(
-                r#"INSERT INTO deliveries (
+            self.conn
+                .execute(
+                    r#"INSERT INTO deliveries (
                     id, bike_id, status, customer_name, customer_address,
                     restaurant_name, restaurant_address, rating, complaint,
-                    created_at, completed_at
-                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)"#,
-                rusqlite::params![
-                    delivery_id,
-                    bike_id,
-                    status,
-                    customer_names[i % customer_names.len()],
-                    format!("{} {}", streets[i % streets.len()], (i % 200) + 1),
-                    restaurant_names[i % restaurant_names.len()],
-                    format!("{} {}", streets[(i + 3) % streets.len()], (i % 150) + 1),
-                    rating,
-                    complaint,
-                    created_at.to_rfc3339(),
-                    completed_at
-                ],
-            )?;
+                    created_at, completed_at, priority
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)"#,
+                    rusqlite::params![
+                        delivery_id,
+                        bike_id,
+                        status,
+                        customer_names[i % customer_names.len()],
+                        format!("{} {}", streets[i % streets.len()], (i % 200) + 1),
+                        restaurant_names[i % restaurant_names.len()],
+                        format!("{} {}", streets[(i + 3) % streets.len()], (i % 150) + 1),
+                        rating,
+                        complaint,
+                        created_at.to_rfc3339(),
+                        completed_at,
+                        priority
+                    ],
+                )
+                .context("seed_deliveries_and_issues")?;
         }
 
         // Issue descriptions by category
@@ -295,7 +426,7 @@ impl Database {
         // Create 20 issues
         for i in 0..20 {
             let issue_id = format!("ISS-{:04}", i + 1);
-            let bike_id = format!("BIKE-{:04}", (i % 10) + 1);
+            let bike_id = format!("BIKE-{:04}", (i % bike_count) + 1);
 
             // 70% of issues linked to a delivery, 30% standalone
             let delivery_id: Option<String> = if i % 3 != 0 {
@@ -311,121 +442,241 @@ impl Database {
             let days_ago = (i as i64) % 14;
             let created_at = now - chrono::Duration::days(days_ago);
 
-            self.conn.execute(
-                r#"INSERT INTO issues (
+            // Resolved issues took 4-20 hours to resolve
+            let updated_at = if resolved {
+                (created_at + chrono::Duration::hours(4 + (i as i64 % 5) * 4)).min(now)
+            } else {
+                created_at
+            };
+
+            self.conn
+                .execute(
+                    r#"INSERT INTO issues (
                     id, delivery_id, bike_id, reporter_type, category,
-                    description, resolved, created_at
-                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)"#,
-                rusqlite::params![
-                    issue_id,
-                    delivery_id,
-                    bike_id,
-                    reporter_type,
-                    category,
-                    description,
-                    resolved as i32,
-                    created_at.to_rfc3339()
-                ],
-            )?;
+                    description, resolved, created_at, updated_at, resolved_at
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)"#,
+                    rusqlite::params![
+                        issue_id,
+                        delivery_id,
+                        bike_id,
+                        reporter_type,
+                        category,
+                        description,
+                        resolved as i32,
+                        created_at.to_rfc3339(),
+                        updated_at.to_rfc3339(),
+                        resolved.then(|| updated_at.to_rfc3339())
+                    ],
+                )
+                .context("seed_deliveries_and_issues")?;
         }
 
         Ok(())
     }
 
     /// Get all bikes from the database
-    pub fn get_all_bikes(&self) -> Result<Vec<Bike>, DatabaseError> {
-        let mut stmt = self.conn.prepare(
-            r#"SELECT id, name, status, latitude, longitude, battery_level,
-                      last_maintenance, total_trips, total_distance_km, created_at, updated_at
-               FROM bikes ORDER BY name"#,
-        )?;
+    pub fn get_all_bikes(
+        &self,
+        sort_by: Option<BikeSort>,
+        sort_dir: Option<SortDirection>,
+    ) -> Result<Vec<Bike>, DatabaseError> {
+        let sort_by = sort_by.unwrap_or_default();
+        let dir = sort_dir.unwrap_or_else(|| sort_by.default_direction()).as_sql();
+        let mut stmt = self
+            .conn
+            .prepare(&format!(
+                r#"SELECT id, name, status, latitude, longitude, battery_level,
+                      last_maintenance, total_trips, total_distance_km, created_at, updated_at,
+                      reservation_expires_at, maintenance_threshold_override, deleted_at,
+                      next_maintenance_at
+               FROM bikes WHERE deleted_at IS NULL ORDER BY {} {dir}, id {dir}"#,
+                bike_sort_column(sort_by)
+            ))
+            .context("get_all_bikes")?;
 
         let bikes = stmt
-            .query_map([], |row| {
-                let status_str: String = row.get(2)?;
-                let status =
-                    BikeStatus::from_str(&status_str).unwrap_or(BikeStatus::Offline);
-
-                Ok(Bike {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    status,
-                    latitude: row.get(3)?,
-                    longitude: row.get(4)?,
-                    battery_level: row.get::<_, Option<i32>>(5)?.map(|v| v as u8),
-                    last_maintenance: row
-                        .get::<_, Option<String>>(6)?
-                        .and_then(|s| chrono::DateTime::parse_from_rfc3339(&s).ok())
-                        .map(|dt| dt.with_timezone(&Utc)),
-                    total_trips: row.get::<_, i32>(7)? as u32,
-                    total_distance_km: row.get(8)?,
-                    created_at: chrono::DateTime::parse_from_rfc3339(&row.get::<_, String>(9)?)
-                        .map(|dt| dt.with_timezone(&Utc))
-                        .unwrap_or_else(|_| Utc::now()),
-                    updated_at: chrono::DateTime::parse_from_rfc3339(&row.get::<_, String>(10)?)
-                        .map(|dt| dt.with_timezone(&Utc))
-                        .unwrap_or_else(|_| Utc::now()),
-                })
-            })?
-            .collect::<SqliteResult<Vec<_>>>()?;
+            .query_map([], |row| self.map_bike_row(row))
+            .context("get_all_bikes")?
+            .collect::<SqliteResult<Vec<_>>>()
+            .context("get_all_bikes")?;
 
         Ok(bikes)
     }
 
     /// Get a bike by ID
     pub fn get_bike_by_id(&self, bike_id: &str) -> Result<Option<Bike>, DatabaseError> {
-        let mut stmt = self.conn.prepare(
-            r#"SELECT id, name, status, latitude, longitude, battery_level,
-                      last_maintenance, total_trips, total_distance_km, created_at, updated_at
-               FROM bikes WHERE id = ?1"#,
-        )?;
+        let mut stmt = self
+            .conn
+            .prepare(
+                r#"SELECT id, name, status, latitude, longitude, battery_level,
+                      last_maintenance, total_trips, total_distance_km, created_at, updated_at,
+                      reservation_expires_at, maintenance_threshold_override, deleted_at,
+                      next_maintenance_at
+               FROM bikes WHERE id = ?1 AND deleted_at IS NULL"#,
+            )
+            .context("get_bike_by_id")?;
 
         let bike = stmt
-            .query_row([bike_id], |row| {
-                let status_str: String = row.get(2)?;
-                let status =
-                    BikeStatus::from_str(&status_str).unwrap_or(BikeStatus::Offline);
-
-                Ok(Bike {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    status,
-                    latitude: row.get(3)?,
-                    longitude: row.get(4)?,
-                    battery_level: row.get::<_, Option<i32>>(5)?.map(|v| v as u8),
-                    last_maintenance: row
-                        .get::<_, Option<String>>(6)?
-                        .and_then(|s| chrono::DateTime::parse_from_rfc3339(&s).ok())
-                        .map(|dt| dt.with_timezone(&Utc)),
-                    total_trips: row.get::<_, i32>(7)? as u32,
-                    total_distance_km: row.get(8)?,
-                    created_at: chrono::DateTime::parse_from_rfc3339(&row.get::<_, String>(9)?)
-                        .map(|dt| dt.with_timezone(&Utc))
-                        .unwrap_or_else(|_| Utc::now()),
-                    updated_at: chrono::DateTime::parse_from_rfc3339(&row.get::<_, String>(10)?)
-                        .map(|dt| dt.with_timezone(&Utc))
-                        .unwrap_or_else(|_| Utc::now()),
-                })
-            })
-            .optional()?;
+            .query_row([bike_id], |row| self.map_bike_row(row))
+            .optional()
+            .context("get_bike_by_id")?;
 
         Ok(bike)
     }
 
+    /// Get all bikes with a given status, plus their count
+    ///
+    /// # Why a separate COUNT?
+    /// - Keeps `count` authoritative even if the list query is later paginated
+    /// - SQLite is in-process, so the extra query costs no round-trip
+    pub fn get_bikes_by_status(
+        &self,
+        status: &BikeStatus,
+    ) -> Result<BikesByStatusResult, DatabaseError> {
+        let count: u32 = self
+            .conn
+            .query_row(
+                "SELECT COUNT(*) FROM bikes WHERE status = ?1 AND deleted_at IS NULL",
+                [status.as_str()],
+                |row| row.get(0),
+            )
+            .context("get_bikes_by_status")?;
+
+        let mut stmt = self
+            .conn
+            .prepare(
+                r#"SELECT id, name, status, latitude, longitude, battery_level,
+                      last_maintenance, total_trips, total_distance_km, created_at, updated_at,
+                      reservation_expires_at, maintenance_threshold_override, deleted_at,
+                      next_maintenance_at
+               FROM bikes WHERE status = ?1 AND deleted_at IS NULL ORDER BY name"#,
+            )
+            .context("get_bikes_by_status")?;
+
+        let bikes = stmt
+            .query_map([status.as_str()], |row| self.map_bike_row(row))
+            .context("get_bikes_by_status")?
+            .collect::<SqliteResult<Vec<_>>>()
+            .context("get_bikes_by_status")?;
+
+        Ok(BikesByStatusResult {
+            bikes,
+            count,
+            status: status.clone(),
+        })
+    }
+
+    /// Get all bikes matching any of the given statuses
+    pub fn get_bikes_by_multiple_statuses(
+        &self,
+        statuses: &[BikeStatus],
+    ) -> Result<Vec<Bike>, DatabaseError> {
+        if statuses.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let placeholders = (1..=statuses.len())
+            .map(|i| format!("?{}", i))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let sql = format!(
+            r#"SELECT id, name, status, latitude, longitude, battery_level,
+                      last_maintenance, total_trips, total_distance_km, created_at, updated_at,
+                      reservation_expires_at, maintenance_threshold_override, deleted_at,
+                      next_maintenance_at
+               FROM bikes WHERE status IN ({}) AND deleted_at IS NULL ORDER BY name"#,
+            placeholders
+        );
+
+        let mut stmt = self
+            .conn
+            .prepare(&sql)
+            .context("get_bikes_by_multiple_statuses")?;
+
+        let bikes = stmt
+            .query_map(
+                rusqlite::params_from_iter(statuses.iter().map(|s| s.as_str())),
+                |row| self.map_bike_row(row),
+            )
+            .context("get_bikes_by_multiple_statuses")?
+            .collect::<SqliteResult<Vec<_>>>()
+            .context("get_bikes_by_multiple_statuses")?;
+
+        Ok(bikes)
+    }
+
+    /// Map a single SQLite row to Bike
+    fn map_bike_row(&self, row: &rusqlite::Row) -> rusqlite::Result<Bike> {
+        let status_str: String = row.get(2)?;
+        let status = BikeStatus::from_str(&status_str).unwrap_or(BikeStatus::Offline);
+
+        Ok(Bike {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            status,
+            latitude: row.get(3)?,
+            longitude: row.get(4)?,
+            battery_level: row.get::<_, Option<i32>>(5)?.map(|v| v as u8),
+            last_maintenance: row
+                .get::<_, Option<String>>(6)?
+                .and_then(|s| chrono::DateTime::parse_from_rfc3339(&s).ok())
+                .map(|dt| dt.with_timezone(&Utc)),
+            total_trips: row.get::<_, i32>(7)? as u32,
+            total_distance_km: row.get(8)?,
+            created_at: chrono::DateTime::parse_from_rfc3339(&row.get::<_, String>(9)?)
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now()),
+            updated_at: chrono::DateTime::parse_from_rfc3339(&row.get::<_, String>(10)?)
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now()),
+            reservation_expires_at: row
+                .get::<_, Option<String>>(11)?
+                .and_then(|s| chrono::DateTime::parse_from_rfc3339(&s).ok())
+                .map(|dt| dt.with_timezone(&Utc)),
+            maintenance_threshold_override: row.get::<_, Option<i64>>(12)?.map(|v| v as u32),
+            deleted_at: row
+                .get::<_, Option<String>>(13)?
+                .and_then(|s| chrono::DateTime::parse_from_rfc3339(&s).ok())
+                .map(|dt| dt.with_timezone(&Utc)),
+            next_maintenance_at: row
+                .get::<_, Option<String>>(14)?
+                .and_then(|s| chrono::DateTime::parse_from_rfc3339(&s).ok())
+                .map(|dt| dt.with_timezone(&Utc)),
+        })
+    }
+
     /// Add a new bike to the fleet
     pub fn add_bike(&self, name: &str, lat: f64, lon: f64, battery: Option<u8>) -> Result<Bike, DatabaseError> {
+        let tx = self.conn.unchecked_transaction().context("add_bike")?;
+        let bike = self.insert_bike(name, lat, lon, battery)?;
+        tx.commit().context("add_bike")?;
+        Ok(bike)
+    }
+
+    /// Insert and audit a bike, inside the caller's transaction
+    fn insert_bike(&self, name: &str, lat: f64, lon: f64, battery: Option<u8>) -> Result<Bike, DatabaseError> {
         let id = format!("BIKE-{}", uuid_v4_simple());
         let now = Utc::now();
         let now_str = now.to_rfc3339();
 
-        self.conn.execute(
-            r#"INSERT INTO bikes (id, name, status, latitude, longitude, battery_level,
+        self.conn
+            .execute(
+                r#"INSERT INTO bikes (id, name, status, latitude, longitude, battery_level,
                total_trips, total_distance_km, created_at, updated_at)
                VALUES (?1, ?2, 'available', ?3, ?4, ?5, 0, 0.0, ?6, ?7)"#,
-            rusqlite::params![id, name, lat, lon, battery.map(|b| b as i32), now_str, now_str],
-        )?;
+                rusqlite::params![
+                    id,
+                    name,
+                    lat,
+                    lon,
+                    battery.map(|b| b as i32),
+                    now_str,
+                    now_str
+                ],
+            )
+            .context("add_bike")?;
 
-        Ok(Bike {
+        let bike = Bike {
             id,
             name: name.to_string(),
             status: BikeStatus::Available,
@@ -435,12 +686,41 @@ impl Database {
             last_maintenance: None,
             total_trips: 0,
             total_distance_km: 0.0,
+            reservation_expires_at: None,
+            maintenance_threshold_override: None,
+            deleted_at: None,
+            next_maintenance_at: None,
             created_at: now,
             updated_at: now,
-        })
+        };
+        self.log_audit_event("bikes", &bike.id, AuditOp::Insert, None, Some(&audit_json(&bike)?))?;
+        self.append_event(FleetEventType::BikeAdded, &bike)?;
+        Ok(bike)
+    }
+
+    /// Add many bikes in one transaction
+    ///
+    /// Either all bikes are added or, on error, none.
+    ///
+    /// # Returns
+    /// The number of bikes added
+    pub fn import_bikes(&self, bikes: &[AddBikeRequest]) -> Result<u32, DatabaseError> {
+        let tx = self.conn.unchecked_transaction().context("import_bikes")?;
+
+        for bike in bikes {
+            self.insert_bike(&bike.name, bike.latitude, bike.longitude, bike.battery_level)?;
+        }
+
+        tx.commit().context("import_bikes")?;
+        Ok(bikes.len() as u32)
     }
 
     /// Update bike status
+    ///
+    /// # Errors
+    /// - `InvalidData` for `Reserved`: reservations need an expiry, so they go
+    ///   through `reserve_bike`
+    /// - `InvalidData` if the bike does not exist or is retired
     pub fn update_bike_status(
         &self,
         bike_id: &str,
@@ -449,271 +729,2569 @@ impl Database {
         lon: Option<f64>,
         battery: Option<u8>,
     ) -> Result<(), DatabaseError> {
+        if *status == BikeStatus::Reserved {
+            return Err(DatabaseError::InvalidData(
+                "Use reserve_bike to reserve a bike".to_string(),
+            ));
+        }
+
         let now = Utc::now().to_rfc3339();
+        let tx = self.conn.unchecked_transaction().context("update_bike_status")?;
+        let old = self.get_bike_by_id(bike_id)?;
 
         // Build update based on provided values
-        match (lat, lon, battery) {
+        let updated = match (lat, lon, battery) {
             (Some(lat_val), Some(lon_val), Some(bat_val)) => {
                 self.conn.execute(
-                    "UPDATE bikes SET status = ?1, updated_at = ?2, latitude = ?3, longitude = ?4, battery_level = ?5 WHERE id = ?6",
+                    "UPDATE bikes SET status = ?1, updated_at = ?2, latitude = ?3, longitude = ?4, battery_level = ?5, reservation_expires_at = NULL WHERE id = ?6 AND deleted_at IS NULL",
                     rusqlite::params![status.as_str(), now, lat_val, lon_val, bat_val as i32, bike_id],
-                )?;
+                ).context("update_bike_status")?
             }
             (Some(lat_val), Some(lon_val), None) => {
                 self.conn.execute(
-                    "UPDATE bikes SET status = ?1, updated_at = ?2, latitude = ?3, longitude = ?4 WHERE id = ?5",
+                    "UPDATE bikes SET status = ?1, updated_at = ?2, latitude = ?3, longitude = ?4, reservation_expires_at = NULL WHERE id = ?5 AND deleted_at IS NULL",
                     rusqlite::params![status.as_str(), now, lat_val, lon_val, bike_id],
-                )?;
+                ).context("update_bike_status")?
             }
             (None, None, Some(bat_val)) => {
                 self.conn.execute(
-                    "UPDATE bikes SET status = ?1, updated_at = ?2, battery_level = ?3 WHERE id = ?4",
+                    "UPDATE bikes SET status = ?1, updated_at = ?2, battery_level = ?3, reservation_expires_at = NULL WHERE id = ?4 AND deleted_at IS NULL",
                     rusqlite::params![status.as_str(), now, bat_val as i32, bike_id],
-                )?;
+                ).context("update_bike_status")?
             }
             _ => {
                 self.conn.execute(
-                    "UPDATE bikes SET status = ?1, updated_at = ?2 WHERE id = ?3",
+                    "UPDATE bikes SET status = ?1, updated_at = ?2, reservation_expires_at = NULL WHERE id = ?3 AND deleted_at IS NULL",
                     rusqlite::params![status.as_str(), now, bike_id],
-                )?;
+                ).context("update_bike_status")?
+            }
+        };
+
+        // Retired bikes are not updated either
+        if updated == 0 {
+            return Err(DatabaseError::InvalidData(format!("Bike not found: {}", bike_id)));
+        }
+
+        if let Some(old) = old {
+            if let Some(new) = self.audit_bike_update(&old)? {
+                self.append_event(FleetEventType::BikeStatusChanged, &new)?;
             }
         }
 
+        tx.commit().context("update_bike_status")?;
         Ok(())
     }
 
-    // ========================================================================
-    // Delivery Queries
-    // ========================================================================
-
-    /// Get all deliveries, optionally filtered by bike_id and/or status
+    /// Retire a bike from the fleet (soft delete)
     ///
-    /// # Why filtering at database level?
-    /// - More efficient than fetching all and filtering in Rust
-    /// - Reduces data transfer over IPC
-    /// - Enables pagination in the future
-    pub fn get_deliveries(
-        &self,
-        bike_id: Option<&str>,
-        status: Option<&str>,
-    ) -> Result<Vec<Delivery>, DatabaseError> {
-        let mut sql = String::from(
-            r#"SELECT id, bike_id, status, customer_name, customer_address,
-                      restaurant_name, restaurant_address, rating, complaint,
-                      created_at, completed_at
-               FROM deliveries WHERE 1=1"#,
-        );
+    /// The row is kept so that its deliveries, issues and trips stay
+    /// intact; the bike is set offline and hidden from all bike queries
+    /// except `get_retired_bikes`. Its force graph pins are deleted.
+    pub fn retire_bike(&self, bike_id: &str) -> Result<(), DatabaseError> {
+        let now = Utc::now();
+        let tx = self.conn.unchecked_transaction().context("retire_bike")?;
+        let old = self.get_bike_by_id(bike_id)?;
 
-        // Dynamic query building for optional filters
-        if bike_id.is_some() {
-            sql.push_str(" AND bike_id = ?1");
-        }
-        if status.is_some() {
-            sql.push_str(if bike_id.is_some() {
-                " AND status = ?2"
-            } else {
-                " AND status = ?1"
-            });
-        }
-        sql.push_str(" ORDER BY created_at DESC");
+        let updated = self
+            .conn
+            .execute(
+                "UPDATE bikes SET status = 'offline', deleted_at = ?1, updated_at = ?1, reservation_expires_at = NULL WHERE id = ?2 AND deleted_at IS NULL",
+                rusqlite::params![now.to_rfc3339(), bike_id],
+            )
+            .context("retire_bike")?;
 
-        let mut stmt = self.conn.prepare(&sql)?;
+        let old = match old {
+            Some(old) if updated > 0 => old,
+            _ => {
+                return Err(DatabaseError::InvalidData(format!(
+                    "Bike not found: {}",
+                    bike_id
+                )))
+            }
+        };
 
-        // Execute with appropriate params based on filters
-        let rows = match (bike_id, status) {
-            (Some(b), Some(s)) => stmt.query(rusqlite::params![b, s])?,
-            (Some(b), None) => stmt.query(rusqlite::params![b])?,
-            (None, Some(s)) => stmt.query(rusqlite::params![s])?,
-            (None, None) => stmt.query([])?,
+        self.log_audit_event("bikes", bike_id, AuditOp::Delete, Some(&audit_json(&old)?), None)?;
+        // A retired bike's graph is no longer shown, so its pins go with it
+        self.conn
+            .execute("DELETE FROM force_graph_pins WHERE bike_id = ?1", [bike_id])
+            .context("retire_bike")?;
+        let retired = Bike {
+            status: BikeStatus::Offline,
+            reservation_expires_at: None,
+            deleted_at: Some(now),
+            updated_at: now,
+            ..old
         };
+        self.append_event(FleetEventType::BikeRetired, &retired)?;
 
-        self.map_delivery_rows(rows)
+        tx.commit().context("retire_bike")?;
+        Ok(())
     }
 
-    /// Get a single delivery by ID
-    pub fn get_delivery_by_id(&self, delivery_id: &str) -> Result<Option<Delivery>, DatabaseError> {
-        let mut stmt = self.conn.prepare(
-            r#"SELECT id, bike_id, status, customer_name, customer_address,
-                      restaurant_name, restaurant_address, rating, complaint,
-                      created_at, completed_at
-               FROM deliveries WHERE id = ?1"#,
-        )?;
+    /// Get retired bikes, most recently retired first
+    pub fn get_retired_bikes(&self) -> Result<Vec<Bike>, DatabaseError> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                r#"SELECT id, name, status, latitude, longitude, battery_level,
+                      last_maintenance, total_trips, total_distance_km, created_at, updated_at,
+                      reservation_expires_at, maintenance_threshold_override, deleted_at,
+                      next_maintenance_at
+               FROM bikes WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC"#,
+            )
+            .context("get_retired_bikes")?;
 
-        let delivery = stmt
-            .query_row([delivery_id], |row| self.map_delivery_row(row))
-            .optional()?;
+        let bikes = stmt
+            .query_map([], |row| self.map_bike_row(row))
+            .context("get_retired_bikes")?
+            .collect::<SqliteResult<Vec<_>>>()
+            .context("get_retired_bikes")?;
 
-        Ok(delivery)
+        Ok(bikes)
     }
 
-    /// Get deliveries for a specific bike (for force graph)
+    // ========================================================================
+    // Simulation Scenarios
+    // ========================================================================
+
+    /// Put every bike into the state described by `scenario`
     ///
-    /// # Why a dedicated method?
-    /// - Force graph needs all deliveries for a single bike
-    /// - Simpler API than using get_deliveries with filter
-    pub fn get_deliveries_by_bike(&self, bike_id: &str) -> Result<Vec<Delivery>, DatabaseError> {
-        self.get_deliveries(Some(bike_id), None)
-    }
+    /// Runs in one transaction; active reservations are cleared.
+    ///
+    /// # Returns
+    /// The updated fleet
+    pub fn apply_simulation_scenario(
+        &self,
+        scenario: SimulationScenario,
+    ) -> Result<Vec<Bike>, DatabaseError> {
+        let mut bikes = self.get_all_bikes(None, None)?;
+        bikes.sort_by(|a, b| a.id.cmp(&b.id));
 
-    /// Map SQLite rows to Delivery structs
-    fn map_delivery_rows(&self, mut rows: rusqlite::Rows) -> Result<Vec<Delivery>, DatabaseError> {
-        let mut deliveries = Vec::new();
-        while let Some(row) = rows.next()? {
-            deliveries.push(self.map_delivery_row(row)?);
+        let today = Utc::now().date_naive().and_hms_opt(0, 0, 0).unwrap().and_utc();
+        let plan = simulation::plan_scenario(scenario, &bikes, today);
+        let now = Utc::now().to_rfc3339();
+
+        let tx = self
+            .conn
+            .unchecked_transaction()
+            .context("apply_simulation_scenario")?;
+
+        for (bike, target) in bikes.iter().zip(&plan) {
+            tx.execute(
+                r#"UPDATE bikes SET status = ?1,
+                          battery_level = COALESCE(?2, battery_level),
+                          latitude = COALESCE(?3, latitude),
+                          longitude = COALESCE(?4, longitude),
+                          last_maintenance = COALESCE(?5, last_maintenance),
+                          reservation_expires_at = NULL,
+                          updated_at = ?6
+                   WHERE id = ?7"#,
+                rusqlite::params![
+                    target.status.as_str(),
+                    target.battery_level.map(|b| b as i32),
+                    target.position.map(|(lat, _)| lat),
+                    target.position.map(|(_, lon)| lon),
+                    target.last_maintenance.map(|dt| dt.to_rfc3339()),
+                    now,
+                    bike.id,
+                ],
+            )
+            .context("apply_simulation_scenario")?;
+            if let Some(bike) = self.audit_bike_update(bike)? {
+                self.append_event(FleetEventType::BikeStatusChanged, &bike)?;
+            }
         }
-        Ok(deliveries)
+
+        tx.commit().context("apply_simulation_scenario")?;
+
+        self.get_all_bikes(None, None)
     }
 
-    /// Map a single SQLite row to Delivery
-    fn map_delivery_row(&self, row: &rusqlite::Row) -> rusqlite::Result<Delivery> {
-        let status_str: String = row.get(2)?;
-        let status = DeliveryStatus::from_str(&status_str).unwrap_or(DeliveryStatus::Upcoming);
+    // ========================================================================
+    // Reservations
+    // ========================================================================
 
-        Ok(Delivery {
-            id: row.get(0)?,
-            bike_id: row.get(1)?,
-            status,
-            customer_name: row.get(3)?,
-            customer_address: row.get(4)?,
-            restaurant_name: row.get(5)?,
-            restaurant_address: row.get(6)?,
-            rating: row.get::<_, Option<i32>>(7)?.map(|r| r as u8),
-            complaint: row.get(8)?,
-            created_at: row
-                .get::<_, String>(9)?
-                .parse::<chrono::DateTime<Utc>>()
-                .unwrap_or_else(|_| Utc::now()),
-            completed_at: row
-                .get::<_, Option<String>>(10)?
-                .and_then(|s| s.parse::<chrono::DateTime<Utc>>().ok()),
-        })
+    /// Reserve an available bike until `expires_at`
+    ///
+    /// # Errors
+    /// `InvalidData` if the expiry is in the past, or the bike is unknown,
+    /// retired or not available
+    pub fn reserve_bike(
+        &self,
+        bike_id: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), DatabaseError> {
+        if expires_at <= Utc::now() {
+            return Err(DatabaseError::InvalidData(
+                "Reservation expiry must be in the future".to_string(),
+            ));
+        }
+
+        let tx = self.conn.unchecked_transaction().context("reserve_bike")?;
+        let old = self.get_bike_by_id(bike_id)?;
+
+        // Conditional update: only an available bike can be reserved
+        let updated = self.conn.execute(
+            r#"UPDATE bikes SET status = 'reserved', reservation_expires_at = ?1, updated_at = ?2
+               WHERE id = ?3 AND status = 'available' AND deleted_at IS NULL"#,
+            rusqlite::params![expires_at.to_rfc3339(), Utc::now().to_rfc3339(), bike_id],
+        ).context("reserve_bike")?;
+
+        let old = match old {
+            Some(old) if updated > 0 => old,
+            Some(_) => {
+                return Err(DatabaseError::InvalidData(format!(
+                    "Bike not available for reservation: {}",
+                    bike_id
+                )))
+            }
+            None => return Err(DatabaseError::InvalidData(format!("Bike not found: {}", bike_id))),
+        };
+
+        self.audit_bike_update(&old)?;
+        self.append_bike_status_event(bike_id)?;
+
+        tx.commit().context("reserve_bike")?;
+        Ok(())
+    }
+
+    /// Cancel a reservation, making the bike available again
+    ///
+    /// # Errors
+    /// `InvalidData` if the bike is not reserved
+    pub fn cancel_reservation(&self, bike_id: &str) -> Result<(), DatabaseError> {
+        let tx = self.conn.unchecked_transaction().context("cancel_reservation")?;
+        let old = self.get_bike_by_id(bike_id)?;
+
+        let updated = self.conn.execute(
+            r#"UPDATE bikes SET status = 'available', reservation_expires_at = NULL, updated_at = ?1
+               WHERE id = ?2 AND status = 'reserved'"#,
+            rusqlite::params![Utc::now().to_rfc3339(), bike_id],
+        ).context("cancel_reservation")?;
+
+        let old = match old {
+            Some(old) if updated > 0 => old,
+            _ => {
+                return Err(DatabaseError::InvalidData(format!(
+                    "Bike is not reserved: {}",
+                    bike_id
+                )))
+            }
+        };
+
+        self.audit_bike_update(&old)?;
+        self.append_bike_status_event(bike_id)?;
+
+        tx.commit().context("cancel_reservation")?;
+        Ok(())
+    }
+
+    /// Make bikes whose reservation has expired available again
+    ///
+    /// # Returns
+    /// Number of released bikes
+    pub fn release_expired_reservations(&self) -> Result<u32, DatabaseError> {
+        let now = Utc::now().to_rfc3339();
+        let tx = self
+            .conn
+            .unchecked_transaction()
+            .context("release_expired_reservations")?;
+
+        // julianday() compares instants; RFC 3339 strings with varying
+        // fractional seconds don't sort correctly as text
+        let expired = r#"status = 'reserved'
+                 AND reservation_expires_at IS NOT NULL
+                 AND julianday(reservation_expires_at) <= julianday(?1)"#;
+
+        let mut stmt = self
+            .conn
+            .prepare(&format!(
+                r#"SELECT id, name, status, latitude, longitude, battery_level,
+                      last_maintenance, total_trips, total_distance_km, created_at, updated_at,
+                      reservation_expires_at, maintenance_threshold_override, deleted_at,
+                      next_maintenance_at
+               FROM bikes WHERE {}"#,
+                expired
+            ))
+            .context("release_expired_reservations")?;
+        let old = stmt
+            .query_map([&now], |row| self.map_bike_row(row))
+            .context("release_expired_reservations")?
+            .collect::<SqliteResult<Vec<_>>>()
+            .context("release_expired_reservations")?;
+
+        let released = self
+            .conn
+            .execute(
+                &format!(
+                    r#"UPDATE bikes SET status = 'available', reservation_expires_at = NULL, updated_at = ?1
+                       WHERE {}"#,
+                    expired
+                ),
+                [&now],
+            )
+            .context("release_expired_reservations")?;
+        for bike in &old {
+            if let Some(bike) = self.audit_bike_update(bike)? {
+                self.append_event(FleetEventType::BikeStatusChanged, &bike)?;
+            }
+        }
+
+        tx.commit().context("release_expired_reservations")?;
+        Ok(released as u32)
+    }
+
+    /// Get bikes whose battery is below `threshold` percent, lowest first
+    ///
+    /// Bikes without a battery reading are left out.
+    pub fn get_bikes_below_battery(&self, threshold: u8) -> Result<Vec<Bike>, DatabaseError> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                r#"SELECT id, name, status, latitude, longitude, battery_level,
+                      last_maintenance, total_trips, total_distance_km, created_at, updated_at,
+                      reservation_expires_at, maintenance_threshold_override, deleted_at,
+                      next_maintenance_at
+               FROM bikes
+               WHERE deleted_at IS NULL
+                 AND battery_level IS NOT NULL AND battery_level < ?1
+               ORDER BY battery_level ASC, name"#,
+            )
+            .context("get_bikes_below_battery")?;
+
+        let bikes = stmt
+            .query_map([threshold], |row| self.map_bike_row(row))
+            .context("get_bikes_below_battery")?
+            .collect::<SqliteResult<Vec<_>>>()
+            .context("get_bikes_below_battery")?;
+
+        Ok(bikes)
+    }
+
+    /// Get bikes whose last maintenance is at least their threshold ago
+    ///
+    /// Each bike's `maintenance_threshold_override` takes precedence over
+    /// `global_threshold_days`. Bikes never maintained are always due.
+    pub fn get_bikes_due_for_maintenance(
+        &self,
+        global_threshold_days: u32,
+    ) -> Result<Vec<Bike>, DatabaseError> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                r#"SELECT id, name, status, latitude, longitude, battery_level,
+                      last_maintenance, total_trips, total_distance_km, created_at, updated_at,
+                      reservation_expires_at, maintenance_threshold_override, deleted_at,
+                      next_maintenance_at
+               FROM bikes
+               WHERE deleted_at IS NULL
+                 AND (last_maintenance IS NULL
+                      OR julianday(?1) - julianday(last_maintenance)
+                         >= COALESCE(maintenance_threshold_override, ?2))
+               ORDER BY name"#,
+            )
+            .context("get_bikes_due_for_maintenance")?;
+
+        let bikes = stmt
+            .query_map(
+                rusqlite::params![Utc::now().to_rfc3339(), global_threshold_days],
+                |row| self.map_bike_row(row),
+            )
+            .context("get_bikes_due_for_maintenance")?
+            .collect::<SqliteResult<Vec<_>>>()
+            .context("get_bikes_due_for_maintenance")?;
+
+        Ok(bikes)
+    }
+
+    /// Set or clear (`None`) a bike's maintenance threshold in days
+    ///
+    /// # Errors
+    /// `InvalidData` if the threshold is 0 or the bike does not exist or is retired
+    pub fn set_bike_maintenance_threshold(
+        &self,
+        bike_id: &str,
+        threshold: Option<u32>,
+    ) -> Result<(), DatabaseError> {
+        validate_maintenance_threshold(threshold)?;
+
+        let tx = self
+            .conn
+            .unchecked_transaction()
+            .context("set_bike_maintenance_threshold")?;
+        let old = self
+            .get_bike_by_id(bike_id)?
+            .ok_or_else(|| DatabaseError::InvalidData(format!("Bike not found: {}", bike_id)))?;
+
+        self.conn
+            .execute(
+                "UPDATE bikes SET maintenance_threshold_override = ?1, updated_at = ?2 WHERE id = ?3 AND deleted_at IS NULL",
+                rusqlite::params![threshold, Utc::now().to_rfc3339(), bike_id],
+            )
+            .context("set_bike_maintenance_threshold")?;
+        self.audit_bike_update(&old)?;
+
+        tx.commit().context("set_bike_maintenance_threshold")?;
+        Ok(())
+    }
+
+    /// Schedule a bike's next maintenance, replacing any earlier schedule
+    ///
+    /// # Errors
+    /// `InvalidData` if the bike does not exist or is retired
+    pub fn schedule_maintenance(&self, bike_id: &str, at: DateTime<Utc>) -> Result<(), DatabaseError> {
+        let tx = self.conn.unchecked_transaction().context("schedule_maintenance")?;
+        let old = self
+            .get_bike_by_id(bike_id)?
+            .ok_or_else(|| DatabaseError::InvalidData(format!("Bike not found: {}", bike_id)))?;
+
+        self.conn
+            .execute(
+                r#"UPDATE bikes SET next_maintenance_at = ?1, updated_at = ?2
+                   WHERE id = ?3 AND deleted_at IS NULL"#,
+                rusqlite::params![at.to_rfc3339(), Utc::now().to_rfc3339(), bike_id],
+            )
+            .context("schedule_maintenance")?;
+        self.audit_bike_update(&old)?;
+
+        tx.commit().context("schedule_maintenance")?;
+        Ok(())
+    }
+
+    /// Get bikes whose scheduled maintenance is at or before `as_of`,
+    /// most overdue first
+    pub fn get_overdue_maintenance(&self, as_of: DateTime<Utc>) -> Result<Vec<Bike>, DatabaseError> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                r#"SELECT id, name, status, latitude, longitude, battery_level,
+                      last_maintenance, total_trips, total_distance_km, created_at, updated_at,
+                      reservation_expires_at, maintenance_threshold_override, deleted_at,
+                      next_maintenance_at
+               FROM bikes
+               WHERE deleted_at IS NULL
+                 AND next_maintenance_at IS NOT NULL
+                 AND julianday(next_maintenance_at) <= julianday(?1)
+               ORDER BY julianday(next_maintenance_at), name"#,
+            )
+            .context("get_overdue_maintenance")?;
+
+        let bikes = stmt
+            .query_map([as_of.to_rfc3339()], |row| self.map_bike_row(row))
+            .context("get_overdue_maintenance")?
+            .collect::<SqliteResult<Vec<_>>>()
+            .context("get_overdue_maintenance")?;
+
+        Ok(bikes)
+    }
+
+    /// Record a completed maintenance and schedule the next one
+    /// `next_due_in_days` after it
+    ///
+    /// `last_maintenance` and `next_maintenance_at` change together in one
+    /// transaction.
+    ///
+    /// # Errors
+    /// `InvalidData` if `next_due_in_days` is 0 or the bike does not exist
+    /// or is retired
+    pub fn record_maintenance_complete(
+        &self,
+        bike_id: &str,
+        performed_at: DateTime<Utc>,
+        next_due_in_days: u32,
+    ) -> Result<(), DatabaseError> {
+        if next_due_in_days == 0 {
+            return Err(DatabaseError::InvalidData(
+                "Next maintenance must be at least 1 day away".to_string(),
+            ));
+        }
+        let next_due = performed_at + chrono::Duration::days(next_due_in_days as i64);
+
+        let tx = self
+            .conn
+            .unchecked_transaction()
+            .context("record_maintenance_complete")?;
+        let old = self
+            .get_bike_by_id(bike_id)?
+            .ok_or_else(|| DatabaseError::InvalidData(format!("Bike not found: {}", bike_id)))?;
+
+        tx.execute(
+            r#"UPDATE bikes SET last_maintenance = ?1, next_maintenance_at = ?2, updated_at = ?3
+               WHERE id = ?4 AND deleted_at IS NULL"#,
+            rusqlite::params![
+                performed_at.to_rfc3339(),
+                next_due.to_rfc3339(),
+                Utc::now().to_rfc3339(),
+                bike_id,
+            ],
+        )
+        .context("record_maintenance_complete")?;
+        self.audit_bike_update(&old)?;
+
+        tx.commit().context("record_maintenance_complete")?;
+        Ok(())
     }
 
     // ========================================================================
-    // Issue Queries
+    // Trips
     // ========================================================================
 
-    /// Get all issues, optionally filtered
+    /// Start a trip for a bike at the given position
     ///
-    /// # Filter options
-    /// - bike_id: Issues for a specific deliverer
-    /// - resolved: Filter by resolution status
-    /// - category: Filter by issue category
-    pub fn get_issues(
+    /// # Returns
+    /// The new trip ID, to be passed to `end_trip`
+    pub fn start_trip(
         &self,
-        bike_id: Option<&str>,
-        resolved: Option<bool>,
-        category: Option<&str>,
-    ) -> Result<Vec<Issue>, DatabaseError> {
-        let mut sql = String::from(
-            r#"SELECT id, delivery_id, bike_id, reporter_type, category,
-                      description, resolved, created_at
-               FROM issues WHERE 1=1"#,
-        );
+        bike_id: &str,
+        start_lat: f64,
+        start_lon: f64,
+    ) -> Result<String, DatabaseError> {
+        if self.get_bike_by_id(bike_id)?.is_none() {
+            return Err(DatabaseError::InvalidData(format!("Bike not found: {}", bike_id)));
+        }
+
+        let id = format!("TRIP-{}", uuid_v4_simple());
+        self.conn
+            .execute(
+                r#"INSERT INTO trips (id, bike_id, start_time, start_latitude, start_longitude)
+                   VALUES (?1, ?2, ?3, ?4, ?5)"#,
+                rusqlite::params![id, bike_id, Utc::now().to_rfc3339(), start_lat, start_lon],
+            )
+            .context("start_trip")?;
+
+        Ok(id)
+    }
+
+    /// End an open trip at the given position
+    ///
+    /// The haversine distance from the start position is stored on the trip
+    /// and added to the bike's totals in the same transaction.
+    pub fn end_trip(&self, trip_id: &str, end_lat: f64, end_lon: f64) -> Result<(), DatabaseError> {
+        let tx = self.conn.unchecked_transaction().context("end_trip")?;
+
+        let trip: Option<(String, f64, f64)> = tx
+            .query_row(
+                r#"SELECT bike_id, start_latitude, start_longitude
+                   FROM trips WHERE id = ?1 AND end_time IS NULL"#,
+                [trip_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .optional()
+            .context("end_trip")?;
+        let (bike_id, start_lat, start_lon) = trip.ok_or_else(|| {
+            DatabaseError::InvalidData(format!("Open trip not found: {}", trip_id))
+        })?;
 
-        let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
-        let mut param_idx = 1;
+        let distance_km = geo::haversine_km(start_lat, start_lon, end_lat, end_lon);
+        let now = Utc::now().to_rfc3339();
+        let old = self.get_bike_by_id(&bike_id)?;
 
-        if let Some(b) = bike_id {
-            sql.push_str(&format!(" AND bike_id = ?{}", param_idx));
-            params.push(Box::new(b.to_string()));
-            param_idx += 1;
+        tx.execute(
+            r#"UPDATE trips SET end_time = ?1, end_latitude = ?2, end_longitude = ?3,
+                      distance_km = ?4
+               WHERE id = ?5"#,
+            rusqlite::params![now, end_lat, end_lon, distance_km, trip_id],
+        )
+        .context("end_trip")?;
+        tx.execute(
+            r#"UPDATE bikes SET total_trips = total_trips + 1,
+                      total_distance_km = total_distance_km + ?1,
+                      updated_at = ?2
+               WHERE id = ?3"#,
+            rusqlite::params![distance_km, now, bike_id],
+        )
+        .context("end_trip")?;
+        if let Some(old) = old {
+            self.audit_bike_update(&old)?;
         }
-        if let Some(r) = resolved {
-            sql.push_str(&format!(" AND resolved = ?{}", param_idx));
-            params.push(Box::new(r as i32));
-            param_idx += 1;
+
+        tx.commit().context("end_trip")?;
+        Ok(())
+    }
+
+    // ========================================================================
+    // Delivery Queries
+    // ========================================================================
+
+    /// Get one page of deliveries, optionally filtered by bike_id and/or status
+    ///
+    /// # Arguments
+    /// - `from`/`to`: Only deliveries created within this window (inclusive)
+    /// - `sort_by`: Sort field (default `CreatedAt`)
+    /// - `sort_dir`: Sort direction (default depends on the field)
+    /// - `cursor`: `next_cursor` of the previous page; None for the first page
+    /// - `limit`: Page size (1-500)
+    ///
+    /// # Why filtering at database level?
+    /// - More efficient than fetching all and filtering in Rust
+    /// - Reduces data transfer over IPC
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_deliveries(
+        &self,
+        bike_id: Option<&str>,
+        status: Option<&str>,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        sort_by: Option<DeliverySort>,
+        sort_dir: Option<SortDirection>,
+        cursor: Option<&str>,
+        limit: u32,
+    ) -> Result<DeliveryPage, DatabaseError> {
+        validate_page_limit(limit)?;
+        let after = cursor.map(decode_cursor).transpose()?;
+
+        validate_date_range(from, to)?;
+        let (filters, params) = delivery_filters(bike_id, status, from, to);
+        let param_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+        let total_count: u32 = self
+            .conn
+            .query_row(
+                &format!("SELECT COUNT(*) FROM deliveries WHERE 1=1{}", filters),
+                param_refs.as_slice(),
+                |row| row.get(0),
+            )
+            .context("get_deliveries")?;
+
+        let sort_by = sort_by.unwrap_or_default();
+        let sort_dir = sort_dir.unwrap_or_else(|| sort_by.default_direction());
+        let deliveries = self.query_deliveries(
+            delivery_filters(bike_id, status, from, to),
+            (sort_by, sort_dir),
+            after.as_ref(),
+            Some(limit + 1),
+        )?;
+
+        Ok(Page::from_rows(deliveries, limit, total_count, |delivery| PageCursor {
+            created_at: delivery.created_at,
+            id: delivery.id.clone(),
+        }))
+    }
+
+    /// Deliveries matching the filters after `after`, up to `limit` rows
+    fn query_deliveries(
+        &self,
+        (filters, mut params): SqlFilters,
+        (sort_by, sort_dir): (DeliverySort, SortDirection),
+        after: Option<&PageCursor>,
+        limit: Option<u32>,
+    ) -> Result<Vec<Delivery>, DatabaseError> {
+        let (column, null_fill) = delivery_sort_column(sort_by);
+        let mut sql = format!(
+            r#"SELECT id, bike_id, status, customer_name, customer_address,
+                      restaurant_name, restaurant_address, rating, complaint,
+                      created_at, completed_at, priority
+               FROM deliveries WHERE 1=1{}"#,
+            filters
+        );
+
+        if let Some(after) = after {
+            params.push(Box::new(after.created_at.to_rfc3339()));
+            params.push(Box::new(after.id.clone()));
+            sql.push_str(&keyset_after_cursor(
+                "deliveries",
+                column,
+                null_fill,
+                sort_dir,
+                params.len() - 1,
+                params.len(),
+            ));
         }
-        if let Some(c) = category {
-            sql.push_str(&format!(" AND category = ?{}", param_idx));
-            params.push(Box::new(c.to_string()));
+        sql.push_str(&keyset_order_by(column, null_fill.is_some(), sort_dir));
+        if let Some(limit) = limit {
+            sql.push_str(&format!(" LIMIT {}", limit));
         }
-        sql.push_str(" ORDER BY created_at DESC");
 
-        let mut stmt = self.conn.prepare(&sql)?;
-
-        // Convert params to references for execution
+        let mut stmt = self.conn.prepare(&sql).context("get_deliveries")?;
         let param_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
-        let rows = stmt.query(param_refs.as_slice())?;
+        let rows = stmt.query(param_refs.as_slice()).context("get_deliveries")?;
 
-        self.map_issue_rows(rows)
+        self.map_delivery_rows(rows)
     }
 
-    /// Get a single issue by ID
-    pub fn get_issue_by_id(&self, issue_id: &str) -> Result<Option<Issue>, DatabaseError> {
-        let mut stmt = self.conn.prepare(
-            r#"SELECT id, delivery_id, bike_id, reporter_type, category,
-                      description, resolved, created_at
-               FROM issues WHERE id = ?1"#,
-        )?;
+    /// Get a single delivery by ID
+    pub fn get_delivery_by_id(&self, delivery_id: &str) -> Result<Option<Delivery>, DatabaseError> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                r#"SELECT id, bike_id, status, customer_name, customer_address,
+                      restaurant_name, restaurant_address, rating, complaint,
+                      created_at, completed_at, priority
+               FROM deliveries WHERE id = ?1"#,
+            )
+            .context("get_delivery_by_id")?;
 
-        let issue = stmt
-            .query_row([issue_id], |row| self.map_issue_row(row))
-            .optional()?;
+        let delivery = stmt
+            .query_row([delivery_id], |row| self.map_delivery_row(row))
+            .optional()
+            .context("get_delivery_by_id")?;
 
-        Ok(issue)
+        Ok(delivery)
     }
 
-    /// Get issues for a specific bike (for force graph)
-    pub fn get_issues_by_bike(&self, bike_id: &str) -> Result<Vec<Issue>, DatabaseError> {
-        self.get_issues(Some(bike_id), None, None)
+    /// Get deliveries for a specific bike (for force graph)
+    ///
+    /// # Why a dedicated method?
+    /// - Force graph needs all deliveries for a single bike
+    /// - Simpler API than using get_deliveries with filter
+    pub fn get_deliveries_by_bike(&self, bike_id: &str) -> Result<Vec<Delivery>, DatabaseError> {
+        self.query_deliveries(
+            delivery_filters(Some(bike_id), None, None, None),
+            (DeliverySort::CreatedAt, SortDirection::Desc),
+            None,
+            None,
+        )
     }
 
-    /// Map SQLite rows to Issue structs
-    fn map_issue_rows(&self, mut rows: rusqlite::Rows) -> Result<Vec<Issue>, DatabaseError> {
-        let mut issues = Vec::new();
-        while let Some(row) = rows.next()? {
-            issues.push(self.map_issue_row(row)?);
+    /// Every delivery of a bike still in the fleet, newest first (for the fleet graph)
+    pub fn get_fleet_deliveries(&self) -> Result<Vec<Delivery>, DatabaseError> {
+        self.query_deliveries(
+            (" AND bike_id IN (SELECT id FROM bikes WHERE deleted_at IS NULL)".to_string(), Vec::new()),
+            (DeliverySort::CreatedAt, SortDirection::Desc),
+            None,
+            None,
+        )
+    }
+
+    /// Every delivery created within the optional window, oldest first (for export)
+    pub fn get_deliveries_in_range(
+        &self,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<Delivery>, DatabaseError> {
+        validate_date_range(from, to)?;
+        self.query_deliveries(
+            delivery_filters(None, None, from, to),
+            (DeliverySort::CreatedAt, SortDirection::Asc),
+            None,
+            None,
+        )
+    }
+
+    /// Map SQLite rows to Delivery structs
+    fn map_delivery_rows(&self, mut rows: rusqlite::Rows) -> Result<Vec<Delivery>, DatabaseError> {
+        let mut deliveries = Vec::new();
+        while let Some(row) = rows.next().context("map_delivery_rows")? {
+            deliveries.push(self.map_delivery_row(row).context("map_delivery_rows")?);
         }
-        Ok(issues)
+        Ok(deliveries)
     }
 
-    /// Map a single SQLite row to Issue
-    fn map_issue_row(&self, row: &rusqlite::Row) -> rusqlite::Result<Issue> {
-        let reporter_str: String = row.get(3)?;
-        let category_str: String = row.get(4)?;
-        let resolved: i32 = row.get(6)?;
+    /// Map a single SQLite row to Delivery
+    fn map_delivery_row(&self, row: &rusqlite::Row) -> rusqlite::Result<Delivery> {
+        let status_str: String = row.get(2)?;
+        let status = DeliveryStatus::from_str(&status_str).unwrap_or(DeliveryStatus::Upcoming);
+        let priority_str: String = row.get(11)?;
 
-        Ok(Issue {
+        Ok(Delivery {
             id: row.get(0)?,
-            delivery_id: row.get(1)?,
-            bike_id: row.get(2)?,
-            reporter_type: IssueReporterType::from_str(&reporter_str)
-                .unwrap_or(IssueReporterType::Customer),
-            category: IssueCategory::from_str(&category_str).unwrap_or(IssueCategory::Other),
-            description: row.get(5)?,
-            resolved: resolved != 0,
+            bike_id: row.get(1)?,
+            status,
+            priority: DeliveryPriority::from_str(&priority_str).unwrap_or_default(),
+            customer_name: row.get(3)?,
+            customer_address: row.get(4)?,
+            restaurant_name: row.get(5)?,
+            restaurant_address: row.get(6)?,
+            rating: row.get::<_, Option<i32>>(7)?.map(|r| r as u8),
+            complaint: row.get(8)?,
             created_at: row
-                .get::<_, String>(7)?
+                .get::<_, String>(9)?
                 .parse::<chrono::DateTime<Utc>>()
                 .unwrap_or_else(|_| Utc::now()),
+            completed_at: row
+                .get::<_, Option<String>>(10)?
+                .and_then(|s| s.parse::<chrono::DateTime<Utc>>().ok()),
         })
     }
 
-    // ========================================================================
-    // Statistics
-    // ========================================================================
-
-    /// Get database statistics
-    pub fn get_stats(&self) -> Result<DatabaseStats, DatabaseError> {
-        let total_bikes: u32 = self
-            .conn
-            .query_row("SELECT COUNT(*) FROM bikes", [], |row| row.get(0))?;
+    /// Create an upcoming delivery for a bike
+    ///
+    /// # Errors
+    /// `InvalidData` if a name or address is blank or the bike does not exist
+    pub fn create_delivery(&self, req: &CreateDeliveryRequest) -> Result<Delivery, DatabaseError> {
+        req.validate().map_err(DatabaseError::InvalidData)?;
+        if self.get_bike_by_id(&req.bike_id)?.is_none() {
+            return Err(DatabaseError::InvalidData(format!(
+                "Bike not found: {}",
+                req.bike_id
+            )));
+        }
 
-        let total_trips: u32 = self
-            .conn
-            .query_row("SELECT COALESCE(SUM(total_trips), 0) FROM bikes", [], |row| {
-                row.get(0)
-            })?;
+        let id = format!("DEL-{}", uuid_v4_simple());
+        let now = Utc::now();
+        let tx = self.conn.unchecked_transaction().context("create_delivery")?;
 
-        Ok(DatabaseStats {
-            total_bikes,
-            total_trips,
-            database_size_bytes: 0, // Would need file system access
-            last_sync: Some(Utc::now()),
-        })
-    }
-}
+        self.conn
+            .execute(
+                r#"INSERT INTO deliveries (
+                    id, bike_id, status, customer_name, customer_address,
+                    restaurant_name, restaurant_address, created_at, priority
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)"#,
+                rusqlite::params![
+                    id,
+                    req.bike_id,
+                    DeliveryStatus::Upcoming.as_str(),
+                    req.customer_name,
+                    req.customer_address,
+                    req.restaurant_name,
+                    req.restaurant_address,
+                    now.to_rfc3339(),
+                    DeliveryPriority::Normal.as_str(),
+                ],
+            )
+            .context("create_delivery")?;
+
+        let delivery = Delivery {
+            id,
+            bike_id: req.bike_id.clone(),
+            status: DeliveryStatus::Upcoming,
+            priority: DeliveryPriority::Normal,
+            customer_name: req.customer_name.clone(),
+            customer_address: req.customer_address.clone(),
+            restaurant_name: req.restaurant_name.clone(),
+            restaurant_address: req.restaurant_address.clone(),
+            rating: None,
+            complaint: None,
+            created_at: now,
+            completed_at: None,
+        };
+        self.log_audit_event(
+            "deliveries",
+            &delivery.id,
+            AuditOp::Insert,
+            None,
+            Some(&audit_json(&delivery)?),
+        )?;
+        self.append_event(FleetEventType::DeliveryCreated, &delivery)?;
+
+        tx.commit().context("create_delivery")?;
+        Ok(delivery)
+    }
+
+    /// Move a delivery to its next status
+    ///
+    /// Completing a delivery sets `completed_at` and, if given, records the
+    /// customer `rating` (1-5) in the same statement.
+    ///
+    /// # Errors
+    /// `InvalidData` if the delivery does not exist, the transition is not
+    /// `Upcoming → Ongoing` or `Ongoing → Completed`, or the rating is invalid
+    pub fn update_delivery_status(
+        &self,
+        delivery_id: &str,
+        new_status: DeliveryStatus,
+        rating: Option<u8>,
+    ) -> Result<Delivery, DatabaseError> {
+        let tx = self.conn.unchecked_transaction().context("update_delivery_status")?;
+        let mut delivery = self.get_delivery_by_id(delivery_id)?.ok_or_else(|| {
+            DatabaseError::InvalidData(format!("Delivery not found: {}", delivery_id))
+        })?;
+        validate_status_update(&delivery.status, &new_status, rating)?;
+
+        let completed_at = (new_status == DeliveryStatus::Completed).then(Utc::now);
+
+        // Compare-and-set on the old status, so a concurrent update can't
+        // be overwritten with a transition validated against stale data
+        let updated = self
+            .conn
+            .execute(
+                r#"UPDATE deliveries
+                   SET status = ?1,
+                       completed_at = COALESCE(?2, completed_at),
+                       rating = COALESCE(?3, rating)
+                   WHERE id = ?4 AND status = ?5"#,
+                rusqlite::params![
+                    new_status.as_str(),
+                    completed_at.map(|t| t.to_rfc3339()),
+                    rating.map(|r| r as i32),
+                    delivery_id,
+                    delivery.status.as_str(),
+                ],
+            )
+            .context("update_delivery_status")?;
+        if updated == 0 {
+            return Err(DatabaseError::InvalidData("Invalid status transition".to_string()));
+        }
+
+        let old = audit_json(&delivery)?;
+        delivery.status = new_status;
+        delivery.completed_at = completed_at.or(delivery.completed_at);
+        delivery.rating = rating.or(delivery.rating);
+        self.log_audit_event(
+            "deliveries",
+            delivery_id,
+            AuditOp::Update,
+            Some(&old),
+            Some(&audit_json(&delivery)?),
+        )?;
+        if delivery.status == DeliveryStatus::Completed {
+            self.append_event(FleetEventType::DeliveryCompleted, &delivery)?;
+        }
+
+        tx.commit().context("update_delivery_status")?;
+        Ok(delivery)
+    }
+
+    /// Delete a delivery
+    ///
+    /// With `cascade`, issues referencing the delivery are kept but become
+    /// standalone (`delivery_id = NULL`); without it, such issues block the
+    /// deletion.
+    ///
+    /// # Errors
+    /// `InvalidData` if the delivery does not exist or has issues and
+    /// `cascade` is false
+    pub fn delete_delivery(&self, delivery_id: &str, cascade: bool) -> Result<(), DatabaseError> {
+        let tx = self
+            .conn
+            .unchecked_transaction()
+            .context("delete_delivery")?;
+
+        let old = self.get_delivery_by_id(delivery_id)?.ok_or_else(|| {
+            DatabaseError::InvalidData(format!("Delivery not found: {}", delivery_id))
+        })?;
+        let linked_issues: Vec<Issue> = self
+            .get_issues_by_bike(&old.bike_id)?
+            .into_iter()
+            .filter(|issue| issue.delivery_id.as_deref() == Some(delivery_id))
+            .collect();
+
+        if !linked_issues.is_empty() {
+            if !cascade {
+                return Err(DatabaseError::InvalidData(
+                    "Cannot delete delivery with associated issues. Resolve them first."
+                        .to_string(),
+                ));
+            }
+            // SQLite can't add ON DELETE SET NULL to an existing table
+            tx.execute(
+                "UPDATE issues SET delivery_id = NULL, updated_at = ?1 WHERE delivery_id = ?2",
+                rusqlite::params![Utc::now().to_rfc3339(), delivery_id],
+            )
+            .context("delete_delivery")?;
+            for issue in &linked_issues {
+                self.audit_issue_update(issue)?;
+            }
+        }
+
+        tx.execute("DELETE FROM delivery_waypoints WHERE delivery_id = ?1", [delivery_id])
+            .context("delete_delivery")?;
+        tx.execute("DELETE FROM deliveries WHERE id = ?1", [delivery_id])
+            .context("delete_delivery")?;
+        self.log_audit_event(
+            "deliveries",
+            delivery_id,
+            AuditOp::Delete,
+            Some(&audit_json(&old)?),
+            None,
+        )?;
+
+        tx.commit().context("delete_delivery")?;
+        Ok(())
+    }
+
+    /// Append a GPS point to a delivery's route
+    ///
+    /// # Returns
+    /// ID of the new waypoint
+    ///
+    /// # Errors
+    /// `InvalidData` if the delivery does not exist or the coordinates are
+    /// out of range
+    pub fn add_waypoint(&self, delivery_id: &str, lat: f64, lon: f64) -> Result<String, DatabaseError> {
+        if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+            return Err(DatabaseError::InvalidData(format!(
+                "Invalid coordinates: {}, {}",
+                lat, lon
+            )));
+        }
+        if self.get_delivery_by_id(delivery_id)?.is_none() {
+            return Err(DatabaseError::InvalidData(format!(
+                "Delivery not found: {}",
+                delivery_id
+            )));
+        }
+
+        let id = format!("WPT-{}", uuid_v4_simple());
+        // The unique (delivery_id, sequence) index rejects a racing insert
+        // that computed the same sequence
+        self.conn
+            .execute(
+                r#"INSERT INTO delivery_waypoints (id, delivery_id, sequence, latitude, longitude, recorded_at)
+                   SELECT ?1, ?2, COALESCE(MAX(sequence), 0) + 1, ?3, ?4, ?5
+                   FROM delivery_waypoints WHERE delivery_id = ?2"#,
+                rusqlite::params![id, delivery_id, lat, lon, Utc::now().to_rfc3339()],
+            )
+            .context("add_waypoint")?;
+        Ok(id)
+    }
+
+    /// Waypoints of a delivery in the order they were recorded
+    pub fn get_waypoints(&self, delivery_id: &str) -> Result<Vec<Waypoint>, DatabaseError> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                r#"SELECT id, delivery_id, sequence, latitude, longitude, recorded_at
+                   FROM delivery_waypoints WHERE delivery_id = ?1
+                   ORDER BY sequence"#,
+            )
+            .context("get_waypoints")?;
+
+        let waypoints = stmt
+            .query_map([delivery_id], |row| {
+                Ok(Waypoint {
+                    id: row.get(0)?,
+                    delivery_id: row.get(1)?,
+                    sequence: row.get(2)?,
+                    latitude: row.get(3)?,
+                    longitude: row.get(4)?,
+                    recorded_at: row
+                        .get::<_, String>(5)?
+                        .parse::<chrono::DateTime<Utc>>()
+                        .unwrap_or_else(|_| Utc::now()),
+                })
+            })
+            .context("get_waypoints")?
+            .collect::<SqliteResult<Vec<_>>>()
+            .context("get_waypoints")?;
+        Ok(waypoints)
+    }
+
+    // ========================================================================
+    // Issue Queries
+    // ========================================================================
+
+    /// Get one page of issues, optionally filtered, newest first by default
+    ///
+    /// # Filter options
+    /// - bike_id: Issues for a specific deliverer
+    /// - resolved: Filter by resolution status
+    /// - category: Filter by issue category
+    ///
+    /// `from`, `to`, `sort_by`, `sort_dir`, `cursor` and `limit` work as in
+    /// `get_deliveries`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_issues(
+        &self,
+        bike_id: Option<&str>,
+        resolved: Option<bool>,
+        category: Option<&str>,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        sort_by: Option<IssueSort>,
+        sort_dir: Option<SortDirection>,
+        cursor: Option<&str>,
+        limit: u32,
+    ) -> Result<IssuePage, DatabaseError> {
+        validate_page_limit(limit)?;
+        let after = cursor.map(decode_cursor).transpose()?;
+
+        validate_date_range(from, to)?;
+        let (filters, params) = issue_filters(bike_id, resolved, category, from, to);
+        let param_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+        let total_count: u32 = self
+            .conn
+            .query_row(
+                &format!("SELECT COUNT(*) FROM issues WHERE 1=1{}", filters),
+                param_refs.as_slice(),
+                |row| row.get(0),
+            )
+            .context("get_issues")?;
+
+        let sort_by = sort_by.unwrap_or_default();
+        let sort_dir = sort_dir.unwrap_or_else(|| sort_by.default_direction());
+        let issues = self.query_issues(
+            issue_filters(bike_id, resolved, category, from, to),
+            (sort_by, sort_dir),
+            after.as_ref(),
+            Some(limit + 1),
+        )?;
+
+        Ok(Page::from_rows(issues, limit, total_count, |issue| PageCursor {
+            created_at: issue.created_at,
+            id: issue.id.clone(),
+        }))
+    }
+
+    /// Issues matching the filters after `after`, up to `limit` rows
+    fn query_issues(
+        &self,
+        (filters, mut params): SqlFilters,
+        (sort_by, sort_dir): (IssueSort, SortDirection),
+        after: Option<&PageCursor>,
+        limit: Option<u32>,
+    ) -> Result<Vec<Issue>, DatabaseError> {
+        let column = issue_sort_column(sort_by);
+        let mut sql = format!(
+            r#"SELECT id, delivery_id, bike_id, reporter_type, category,
+                      description, resolved, created_at, updated_at,
+                      resolved_at, resolution_note
+               FROM issues WHERE 1=1{}"#,
+            filters
+        );
+
+        if let Some(after) = after {
+            params.push(Box::new(after.created_at.to_rfc3339()));
+            params.push(Box::new(after.id.clone()));
+            sql.push_str(&keyset_after_cursor(
+                "issues",
+                column,
+                None,
+                sort_dir,
+                params.len() - 1,
+                params.len(),
+            ));
+        }
+        sql.push_str(&keyset_order_by(column, false, sort_dir));
+        if let Some(limit) = limit {
+            sql.push_str(&format!(" LIMIT {}", limit));
+        }
+
+        let mut stmt = self.conn.prepare(&sql).context("get_issues")?;
+
+        // Convert params to references for execution
+        let param_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+        let rows = stmt.query(param_refs.as_slice()).context("get_issues")?;
+
+        self.map_issue_rows(rows)
+    }
+
+    /// Get a single issue by ID
+    pub fn get_issue_by_id(&self, issue_id: &str) -> Result<Option<Issue>, DatabaseError> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                r#"SELECT id, delivery_id, bike_id, reporter_type, category,
+                      description, resolved, created_at, updated_at,
+                      resolved_at, resolution_note
+               FROM issues WHERE id = ?1"#,
+            )
+            .context("get_issue_by_id")?;
+
+        let issue = stmt
+            .query_row([issue_id], |row| self.map_issue_row(row))
+            .optional()
+            .context("get_issue_by_id")?;
+
+        Ok(issue)
+    }
+
+    /// Get issues for a specific bike (for force graph)
+    pub fn get_issues_by_bike(&self, bike_id: &str) -> Result<Vec<Issue>, DatabaseError> {
+        self.query_issues(
+            issue_filters(Some(bike_id), None, None, None, None),
+            (IssueSort::CreatedAt, SortDirection::Desc),
+            None,
+            None,
+        )
+    }
+
+    /// Map SQLite rows to Issue structs
+    fn map_issue_rows(&self, mut rows: rusqlite::Rows) -> Result<Vec<Issue>, DatabaseError> {
+        let mut issues = Vec::new();
+        while let Some(row) = rows.next().context("map_issue_rows")? {
+            issues.push(self.map_issue_row(row).context("map_issue_rows")?);
+        }
+        Ok(issues)
+    }
+
+    /// Map a single SQLite row to Issue
+    fn map_issue_row(&self, row: &rusqlite::Row) -> rusqlite::Result<Issue> {
+        let reporter_str: String = row.get(3)?;
+        let category_str: String = row.get(4)?;
+        let resolved: i32 = row.get(6)?;
+
+        Ok(Issue {
+            id: row.get(0)?,
+            delivery_id: row.get(1)?,
+            bike_id: row.get(2)?,
+            reporter_type: IssueReporterType::from_str(&reporter_str)
+                .unwrap_or(IssueReporterType::Customer),
+            category: IssueCategory::from_str(&category_str).unwrap_or(IssueCategory::Other),
+            description: row.get(5)?,
+            resolved: resolved != 0,
+            resolved_at: row
+                .get::<_, Option<String>>(9)?
+                .and_then(|s| s.parse::<chrono::DateTime<Utc>>().ok()),
+            resolution_note: row.get(10)?,
+            created_at: row
+                .get::<_, String>(7)?
+                .parse::<chrono::DateTime<Utc>>()
+                .unwrap_or_else(|_| Utc::now()),
+            updated_at: row
+                .get::<_, String>(8)?
+                .parse::<chrono::DateTime<Utc>>()
+                .unwrap_or_else(|_| Utc::now()),
+        })
+    }
+
+    /// Mark an issue as resolved, with an optional resolution note
+    ///
+    /// Stamps `resolved_at` and `updated_at`. Resolving an already resolved
+    /// issue keeps its original timestamp and note.
+    pub fn resolve_issue(&self, issue_id: &str, note: Option<&str>) -> Result<Issue, DatabaseError> {
+        let tx = self.conn.unchecked_transaction().context("resolve_issue")?;
+        let old = self.get_issue_by_id(issue_id)?;
+        let updated = self
+            .conn
+            .execute(
+                r#"UPDATE issues SET resolved = 1, resolved_at = ?1, updated_at = ?1,
+                                     resolution_note = ?2
+                   WHERE id = ?3 AND resolved = 0"#,
+                rusqlite::params![Utc::now().to_rfc3339(), note, issue_id],
+            )
+            .context("resolve_issue")?;
+
+        let old = old
+            .ok_or_else(|| DatabaseError::InvalidData(format!("Issue not found: {}", issue_id)))?;
+        let issue = if updated > 0 {
+            let issue = self.audit_issue_update(&old)?;
+            self.append_event(FleetEventType::IssueResolved, &issue)?;
+            issue
+        } else {
+            old
+        };
+
+        tx.commit().context("resolve_issue")?;
+        Ok(issue)
+    }
+
+    /// Reopen a resolved issue, clearing its resolution time and note
+    ///
+    /// Reopening an unresolved issue changes nothing.
+    pub fn reopen_issue(&self, issue_id: &str) -> Result<Issue, DatabaseError> {
+        let tx = self.conn.unchecked_transaction().context("reopen_issue")?;
+        let old = self
+            .get_issue_by_id(issue_id)?
+            .ok_or_else(|| DatabaseError::InvalidData(format!("Issue not found: {}", issue_id)))?;
+
+        let updated = self
+            .conn
+            .execute(
+                r#"UPDATE issues SET resolved = 0, resolved_at = NULL, resolution_note = NULL,
+                                     updated_at = ?1
+                   WHERE id = ?2 AND resolved = 1"#,
+                rusqlite::params![Utc::now().to_rfc3339(), issue_id],
+            )
+            .context("reopen_issue")?;
+        let issue = if updated > 0 { self.audit_issue_update(&old)? } else { old };
+
+        tx.commit().context("reopen_issue")?;
+        Ok(issue)
+    }
+
+    /// Resolve many issues at once (e.g. after a maintenance session)
+    ///
+    /// # Returns
+    /// Number of issues actually resolved; unknown or already resolved IDs
+    /// are skipped, not errors
+    ///
+    /// # Errors
+    /// `InvalidData` for an empty batch or more than 100 IDs
+    pub fn batch_resolve_issues(&self, issue_ids: &[&str]) -> Result<u32, DatabaseError> {
+        self.set_issues_resolved(issue_ids, true, "batch_resolve_issues")
+    }
+
+    /// Reopen many resolved issues at once
+    ///
+    /// Same counting and limits as `batch_resolve_issues`.
+    pub fn batch_reopen_issues(&self, issue_ids: &[&str]) -> Result<u32, DatabaseError> {
+        self.set_issues_resolved(issue_ids, false, "batch_reopen_issues")
+    }
+
+    /// Flip `resolved` for a batch of issues, counting only rows that changed
+    fn set_issues_resolved(
+        &self,
+        issue_ids: &[&str],
+        resolved: bool,
+        operation: &str,
+    ) -> Result<u32, DatabaseError> {
+        validate_issue_batch(issue_ids)?;
+
+        // ?1 = resolved flag, ?2 = timestamp, ?3.. = one placeholder per ID
+        let placeholders = (0..issue_ids.len())
+            .map(|i| format!("?{}", i + 3))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let sql = format!(
+            r#"UPDATE issues SET resolved = ?1, updated_at = ?2,
+                                 resolved_at = CASE WHEN ?1 THEN ?2 END,
+                                 resolution_note = NULL
+               WHERE resolved != ?1 AND id IN ({})"#,
+            placeholders
+        );
+
+        let now = Utc::now().to_rfc3339();
+        let mut params: Vec<&dyn rusqlite::ToSql> = vec![&resolved, &now];
+        params.extend(issue_ids.iter().map(|id| id as &dyn rusqlite::ToSql));
+
+        let tx = self.conn.unchecked_transaction().context(operation)?;
+
+        // The issues the update will change, each once, for the audit log
+        let mut changed: Vec<Issue> = Vec::new();
+        for id in issue_ids {
+            if let Some(issue) = self.get_issue_by_id(id)? {
+                if issue.resolved != resolved && !changed.iter().any(|c| c.id == issue.id) {
+                    changed.push(issue);
+                }
+            }
+        }
+
+        let updated = self
+            .conn
+            .execute(&sql, params.as_slice())
+            .context(operation)?;
+        for issue in &changed {
+            let issue = self.audit_issue_update(issue)?;
+            if resolved {
+                self.append_event(FleetEventType::IssueResolved, &issue)?;
+            }
+        }
+
+        tx.commit().context(operation)?;
+        Ok(updated as u32)
+    }
+
+    /// Report a new issue, standalone or linked to a delivery
+    ///
+    /// The description is trimmed; a missing category is suggested from it.
+    ///
+    /// # Errors
+    /// `InvalidData` if the description is blank, the bike does not exist,
+    /// or the delivery does not exist or belongs to another bike
+    pub fn create_issue(&self, req: &CreateIssueRequest) -> Result<Issue, DatabaseError> {
+        let description = req.description.trim();
+        if description.is_empty() {
+            return Err(DatabaseError::InvalidData(
+                "Issue description must not be empty".to_string(),
+            ));
+        }
+        if self.get_bike_by_id(&req.bike_id)?.is_none() {
+            return Err(DatabaseError::InvalidData(format!(
+                "Bike not found: {}",
+                req.bike_id
+            )));
+        }
+        if let Some(delivery_id) = &req.delivery_id {
+            match self.get_delivery_by_id(delivery_id)? {
+                None => {
+                    return Err(DatabaseError::InvalidData(format!(
+                        "Delivery not found: {}",
+                        delivery_id
+                    )))
+                }
+                Some(delivery) if delivery.bike_id != req.bike_id => {
+                    return Err(DatabaseError::InvalidData(format!(
+                        "Delivery {} belongs to {}, not {}",
+                        delivery_id, delivery.bike_id, req.bike_id
+                    )))
+                }
+                Some(_) => {}
+            }
+        }
+
+        let id = format!("ISS-{}", uuid_v4_simple());
+        let category = category_or_suggested(req.category.clone(), description);
+        let now = Utc::now();
+        let tx = self.conn.unchecked_transaction().context("create_issue")?;
+
+        self.conn
+            .execute(
+                r#"INSERT INTO issues (
+                    id, delivery_id, bike_id, reporter_type, category,
+                    description, resolved, created_at, updated_at
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, 0, ?7, ?7)"#,
+                rusqlite::params![
+                    id,
+                    req.delivery_id,
+                    req.bike_id,
+                    req.reporter_type.as_str(),
+                    category.as_str(),
+                    description,
+                    now.to_rfc3339(),
+                ],
+            )
+            .context("create_issue")?;
+
+        let issue = Issue {
+            id,
+            delivery_id: req.delivery_id.clone(),
+            bike_id: req.bike_id.clone(),
+            reporter_type: req.reporter_type.clone(),
+            category,
+            description: description.to_string(),
+            resolved: false,
+            resolved_at: None,
+            resolution_note: None,
+            created_at: now,
+            updated_at: now,
+        };
+        self.log_audit_event("issues", &issue.id, AuditOp::Insert, None, Some(&audit_json(&issue)?))?;
+        self.append_event(FleetEventType::IssueFiled, &issue)?;
+
+        tx.commit().context("create_issue")?;
+        Ok(issue)
+    }
+
+    /// Delete an issue
+    ///
+    /// # Errors
+    /// `InvalidData` if the issue does not exist
+    pub fn delete_issue(&self, issue_id: &str) -> Result<(), DatabaseError> {
+        let tx = self.conn.unchecked_transaction().context("delete_issue")?;
+        let old = self
+            .get_issue_by_id(issue_id)?
+            .ok_or_else(|| DatabaseError::InvalidData(format!("Issue not found: {}", issue_id)))?;
+
+        self.conn
+            .execute("DELETE FROM issues WHERE id = ?1", [issue_id])
+            .context("delete_issue")?;
+        self.log_audit_event("issues", issue_id, AuditOp::Delete, Some(&audit_json(&old)?), None)?;
+
+        tx.commit().context("delete_issue")?;
+        Ok(())
+    }
+
+    // ========================================================================
+    // Audit Log
+    // ========================================================================
+
+    /// Record a change to a row of `table` in the audit log
+    ///
+    /// `old` and `new` are the row as JSON before and after the change.
+    pub fn log_audit_event(
+        &self,
+        table: &str,
+        row_id: &str,
+        op: AuditOp,
+        old: Option<&str>,
+        new: Option<&str>,
+    ) -> Result<(), DatabaseError> {
+        self.conn
+            .execute(
+                r#"INSERT INTO audit_log (
+                    id, table_name, row_id, operation, old_value_json, new_value_json, occurred_at
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)"#,
+                rusqlite::params![
+                    format!("AUD-{}", uuid_v4_simple()),
+                    table,
+                    row_id,
+                    op.as_str(),
+                    old,
+                    new,
+                    Utc::now().to_rfc3339(),
+                ],
+            )
+            .context("log_audit_event")?;
+        Ok(())
+    }
+
+    /// Audit an update of `old`'s row
+    ///
+    /// # Returns
+    /// The bike as it is now; None if it is retired
+    fn audit_bike_update(&self, old: &Bike) -> Result<Option<Bike>, DatabaseError> {
+        let new = self.get_bike_by_id(&old.id)?;
+        if let Some(new) = &new {
+            self.log_audit_event(
+                "bikes",
+                &old.id,
+                AuditOp::Update,
+                Some(&audit_json(old)?),
+                Some(&audit_json(new)?),
+            )?;
+        }
+        Ok(new)
+    }
+
+    /// Audit an update of `old`'s row
+    ///
+    /// # Returns
+    /// The issue as it is now
+    fn audit_issue_update(&self, old: &Issue) -> Result<Issue, DatabaseError> {
+        let new = self
+            .get_issue_by_id(&old.id)?
+            .ok_or_else(|| DatabaseError::InvalidData(format!("Issue not found: {}", old.id)))?;
+        self.log_audit_event(
+            "issues",
+            &old.id,
+            AuditOp::Update,
+            Some(&audit_json(old)?),
+            Some(&audit_json(&new)?),
+        )?;
+        Ok(new)
+    }
+
+    /// Get audit log entries, oldest first
+    ///
+    /// Every filter is optional; `from` is inclusive, `to` exclusive.
+    pub fn get_audit_log(
+        &self,
+        table: Option<&str>,
+        row_id: Option<&str>,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<AuditLogEntry>, DatabaseError> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                r#"SELECT id, table_name, row_id, operation, old_value_json, new_value_json,
+                      occurred_at
+               FROM audit_log
+               WHERE (?1 IS NULL OR table_name = ?1)
+                 AND (?2 IS NULL OR row_id = ?2)
+                 AND (?3 IS NULL OR occurred_at >= ?3)
+                 AND (?4 IS NULL OR occurred_at < ?4)
+               ORDER BY occurred_at, id"#,
+            )
+            .context("get_audit_log")?;
+
+        let entries = stmt
+            .query_map(
+                rusqlite::params![
+                    table,
+                    row_id,
+                    from.map(|t| t.to_rfc3339()),
+                    to.map(|t| t.to_rfc3339()),
+                ],
+                |row| {
+                    let operation: String = row.get(3)?;
+                    let occurred_at: String = row.get(6)?;
+                    Ok(AuditLogEntry {
+                        id: row.get(0)?,
+                        table_name: row.get(1)?,
+                        row_id: row.get(2)?,
+                        operation: AuditOp::from_str(&operation).unwrap_or(AuditOp::Update),
+                        old_value_json: row.get(4)?,
+                        new_value_json: row.get(5)?,
+                        occurred_at: chrono::DateTime::parse_from_rfc3339(&occurred_at)
+                            .map(|dt| dt.with_timezone(&Utc))
+                            .unwrap_or_else(|_| Utc::now()),
+                    })
+                },
+            )
+            .context("get_audit_log")?
+            .collect::<SqliteResult<Vec<_>>>()
+            .context("get_audit_log")?;
+
+        Ok(entries)
+    }
+
+    // ========================================================================
+    // Fleet Events
+    // ========================================================================
+
+    /// Append an event to the fleet event log
+    ///
+    /// # Returns
+    /// ID of the event; later events always get higher IDs
+    pub fn append_event(
+        &self,
+        event_type: FleetEventType,
+        payload: &impl serde::Serialize,
+    ) -> Result<u64, DatabaseError> {
+        self.conn
+            .execute(
+                "INSERT INTO fleet_events (event_type, payload_json, occurred_at) VALUES (?1, ?2, ?3)",
+                rusqlite::params![event_type.as_str(), audit_json(payload)?, Utc::now().to_rfc3339()],
+            )
+            .context("append_event")?;
+        Ok(self.conn.last_insert_rowid() as u64)
+    }
+
+    /// Log the current state of a bike whose status just changed
+    fn append_bike_status_event(&self, bike_id: &str) -> Result<(), DatabaseError> {
+        if let Some(bike) = self.get_bike_by_id(bike_id)? {
+            self.append_event(FleetEventType::BikeStatusChanged, &bike)?;
+        }
+        Ok(())
+    }
+
+    /// Get up to `limit` events after `last_id`, oldest first
+    ///
+    /// Pass the ID of the last event seen, or 0 for the start of the log.
+    ///
+    /// # Errors
+    /// `InvalidData` if `limit` is 0 or above `MAX_PAGE_SIZE`
+    pub fn get_events_since(&self, last_id: u64, limit: u32) -> Result<Vec<FleetEvent>, DatabaseError> {
+        validate_page_limit(limit)?;
+
+        let mut stmt = self
+            .conn
+            .prepare(
+                r#"SELECT id, event_type, payload_json, occurred_at
+               FROM fleet_events WHERE id > ?1
+               ORDER BY id LIMIT ?2"#,
+            )
+            .context("get_events_since")?;
+
+        let events = stmt
+            .query_map(rusqlite::params![last_id as i64, limit], |row| {
+                let event_type: String = row.get(1)?;
+                let occurred_at: String = row.get(3)?;
+                Ok(FleetEvent {
+                    id: row.get::<_, i64>(0)? as u64,
+                    event_type: FleetEventType::from_str(&event_type)
+                        .unwrap_or(FleetEventType::BikeStatusChanged),
+                    payload_json: row.get(2)?,
+                    occurred_at: chrono::DateTime::parse_from_rfc3339(&occurred_at)
+                        .map(|dt| dt.with_timezone(&Utc))
+                        .unwrap_or_else(|_| Utc::now()),
+                })
+            })
+            .context("get_events_since")?
+            .collect::<SqliteResult<Vec<_>>>()
+            .context("get_events_since")?;
+
+        Ok(events)
+    }
+
+    // ========================================================================
+    // Force Graph Pins
+    // ========================================================================
+
+    /// Pin a node of a deliverer's force graph at `(x, y)`, replacing any
+    /// earlier pin of the same node
+    pub fn save_node_pin(
+        &self,
+        bike_id: &str,
+        node_id: &str,
+        x: f64,
+        y: f64,
+    ) -> Result<(), DatabaseError> {
+        self.conn
+            .execute(
+                r#"INSERT INTO force_graph_pins (bike_id, node_id, x, y)
+                   VALUES (?1, ?2, ?3, ?4)
+                   ON CONFLICT (bike_id, node_id) DO UPDATE SET x = excluded.x, y = excluded.y"#,
+                rusqlite::params![bike_id, node_id, x, y],
+            )
+            .context("save_node_pin")?;
+        Ok(())
+    }
+
+    /// Pinned nodes of a deliverer's force graph, as (node ID, x, y)
+    pub fn get_node_pins(&self, bike_id: &str) -> Result<Vec<(String, f64, f64)>, DatabaseError> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT node_id, x, y FROM force_graph_pins WHERE bike_id = ?1 ORDER BY node_id")
+            .context("get_node_pins")?;
+
+        let pins = stmt
+            .query_map([bike_id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .context("get_node_pins")?
+            .collect::<SqliteResult<Vec<_>>>()
+            .context("get_node_pins")?;
+
+        Ok(pins)
+    }
+
+    /// Unpin every node of a deliverer's force graph
+    pub fn clear_node_pins(&self, bike_id: &str) -> Result<(), DatabaseError> {
+        self.conn
+            .execute("DELETE FROM force_graph_pins WHERE bike_id = ?1", [bike_id])
+            .context("clear_node_pins")?;
+        Ok(())
+    }
+
+    // ========================================================================
+    // Search
+    // ========================================================================
+
+    /// Search bike names and delivery customer and restaurant fields
+    ///
+    /// Case-insensitive substring match; `%` and `_` in `query` match
+    /// themselves. At most `limit` bikes and `limit` deliveries are returned.
+    pub fn search(&self, query: &str, limit: u32) -> Result<SearchResults, DatabaseError> {
+        validate_page_limit(limit)?;
+        let query = escape_like(query)?;
+
+        let mut stmt = self
+            .conn
+            .prepare(
+                r#"SELECT id, name, status, latitude, longitude, battery_level,
+                      last_maintenance, total_trips, total_distance_km, created_at, updated_at,
+                      reservation_expires_at, maintenance_threshold_override, deleted_at,
+                      next_maintenance_at
+               FROM bikes
+               WHERE deleted_at IS NULL AND name LIKE '%' || ?1 || '%' ESCAPE '\'
+               ORDER BY name LIMIT ?2"#,
+            )
+            .context("search")?;
+        let bikes = stmt
+            .query_map(rusqlite::params![query, limit], |row| self.map_bike_row(row))
+            .context("search")?
+            .collect::<SqliteResult<Vec<_>>>()
+            .context("search")?;
+
+        let mut stmt = self
+            .conn
+            .prepare(
+                r#"SELECT id, bike_id, status, customer_name, customer_address,
+                      restaurant_name, restaurant_address, rating, complaint,
+                      created_at, completed_at, priority
+               FROM deliveries
+               WHERE customer_name LIKE '%' || ?1 || '%' ESCAPE '\'
+                  OR customer_address LIKE '%' || ?1 || '%' ESCAPE '\'
+                  OR restaurant_name LIKE '%' || ?1 || '%' ESCAPE '\'
+               ORDER BY created_at DESC, id DESC LIMIT ?2"#,
+            )
+            .context("search")?;
+        let rows = stmt.query(rusqlite::params![query, limit]).context("search")?;
+        let deliveries = self.map_delivery_rows(rows)?;
+
+        Ok(SearchResults { bikes, deliveries })
+    }
+
+    // ========================================================================
+    // Analytics
+    // ========================================================================
+
+    /// Correlate per-bike issue counts with average delivery ratings
+    ///
+    /// # Why per-bike subqueries?
+    /// - Every bike is returned, even with zero issues or no ratings
+    /// - Aggregation happens in SQLite, Pearson is computed in Rust
+    pub fn get_issue_rating_correlation(
+        &self,
+        days: u32,
+    ) -> Result<IssueRatingCorrelation, DatabaseError> {
+        let since = (Utc::now() - chrono::Duration::days(days as i64)).to_rfc3339();
+
+        let mut stmt = self
+            .conn
+            .prepare(
+                r#"SELECT b.id,
+                      (SELECT COUNT(*) FROM issues i
+                       WHERE i.bike_id = b.id AND i.created_at >= ?1),
+                      (SELECT AVG(d.rating) FROM deliveries d
+                       WHERE d.bike_id = b.id AND d.rating IS NOT NULL AND d.created_at >= ?1)
+               FROM bikes b ORDER BY b.id"#,
+            )
+            .context("get_issue_rating_correlation")?;
+
+        let by_bike = stmt
+            .query_map([&since], |row| {
+                Ok(BikeCorrelationPoint {
+                    bike_id: row.get(0)?,
+                    issue_count: row.get::<_, i64>(1)? as u32,
+                    average_rating: row.get(2)?,
+                })
+            })
+            .context("get_issue_rating_correlation")?
+            .collect::<SqliteResult<Vec<_>>>()
+            .context("get_issue_rating_correlation")?;
+
+        Ok(analytics::issue_rating_correlation(by_bike))
+    }
+
+    /// Issue categories ranked by frequency, with resolution figures
+    ///
+    /// # Arguments
+    /// - `limit`: Maximum number of categories returned
+    /// - `days`: Only count issues created in this window (None = all time)
+    ///
+    /// Resolution time is `updated_at - created_at` of resolved issues.
+    pub fn get_top_issue_categories(
+        &self,
+        limit: u32,
+        days: Option<u32>,
+    ) -> Result<Vec<IssueCategoryStats>, DatabaseError> {
+        let since = days.map(|d| (Utc::now() - chrono::Duration::days(d as i64)).to_rfc3339());
+
+        let mut stmt = self
+            .conn
+            .prepare(
+                r#"SELECT category,
+                      COUNT(*) AS count,
+                      SUM(CASE WHEN resolved = 1 THEN 1 ELSE 0 END) AS resolved_count,
+                      AVG(CASE WHEN resolved = 1
+                          THEN (julianday(updated_at) - julianday(created_at)) * 24 END)
+               FROM issues
+               WHERE ?1 IS NULL OR created_at >= ?1
+               GROUP BY category
+               ORDER BY count DESC, category
+               LIMIT ?2"#,
+            )
+            .context("get_top_issue_categories")?;
+
+        let stats = stmt
+            .query_map(rusqlite::params![since, limit], |row| {
+                let category_str: String = row.get(0)?;
+                let count: u32 = row.get(1)?;
+                let resolved_count: u32 = row.get(2)?;
+
+                Ok(IssueCategoryStats {
+                    category: IssueCategory::from_str(&category_str).unwrap_or(IssueCategory::Other),
+                    count,
+                    resolved_count,
+                    resolution_rate: resolved_count as f64 / count as f64,
+                    avg_resolution_time_hours: row.get(3)?,
+                })
+            })
+            .context("get_top_issue_categories")?
+            .collect::<SqliteResult<Vec<_>>>()
+            .context("get_top_issue_categories")?;
+
+        Ok(stats)
+    }
+
+    /// Issue counts, breakdowns and resolution times, of one bike or the fleet
+    pub fn get_issue_statistics(&self, bike_id: Option<&str>) -> Result<IssueStatistics, DatabaseError> {
+        let (total_issues, resolved_count, avg_resolution_days, oldest_unresolved_days) = self
+            .conn
+            .query_row(
+                r#"SELECT COUNT(*),
+                      COUNT(CASE WHEN resolved = 1 THEN 1 END),
+                      AVG(CASE WHEN resolved = 1
+                          THEN julianday(resolved_at) - julianday(created_at) END),
+                      MAX(CASE WHEN resolved = 0
+                          THEN julianday('now') - julianday(created_at) END)
+               FROM issues
+               WHERE ?1 IS NULL OR bike_id = ?1"#,
+                [bike_id],
+                |row| {
+                    Ok((
+                        row.get::<_, u32>(0)?,
+                        row.get::<_, u32>(1)?,
+                        row.get::<_, Option<f64>>(2)?,
+                        row.get::<_, Option<f64>>(3)?,
+                    ))
+                },
+            )
+            .context("get_issue_statistics")?;
+
+        let count_by = |column: &str| -> Result<HashMap<String, u32>, DatabaseError> {
+            let sql = format!(
+                "SELECT {0}, COUNT(*) FROM issues WHERE ?1 IS NULL OR bike_id = ?1 GROUP BY {0}",
+                column
+            );
+            let mut stmt = self.conn.prepare(&sql).context("get_issue_statistics")?;
+            let counts = stmt
+                .query_map([bike_id], |row| Ok((row.get(0)?, row.get(1)?)))
+                .context("get_issue_statistics")?
+                .collect::<SqliteResult<HashMap<_, _>>>()
+                .context("get_issue_statistics")?;
+            Ok(counts)
+        };
+
+        Ok(IssueStatistics {
+            total_issues,
+            resolved_count,
+            unresolved_count: total_issues - resolved_count,
+            resolution_rate_pct: if total_issues == 0 {
+                0.0
+            } else {
+                resolved_count as f64 / total_issues as f64 * 100.0
+            },
+            by_category: count_by("category")?,
+            by_reporter: count_by("reporter_type")?,
+            avg_resolution_days: avg_resolution_days.unwrap_or(0.0),
+            oldest_unresolved_days,
+        })
+    }
+
+    /// Delivery figures of one restaurant
+    ///
+    /// # Errors
+    /// `InvalidData` if the restaurant does not exist
+    pub fn get_restaurant_stats(&self, restaurant_id: &str) -> Result<RestaurantStats, DatabaseError> {
+        let sql = format!("{} WHERE r.id = ?1", RESTAURANT_STATS_SELECT);
+        self.conn
+            .query_row(&sql, [restaurant_id], |row| self.map_restaurant_stats_row(row))
+            .optional()
+            .context("get_restaurant_stats")?
+            .ok_or_else(|| {
+                DatabaseError::InvalidData(format!("Restaurant not found: {}", restaurant_id))
+            })
+    }
+
+    /// Restaurants with the most deliveries, best rated first on ties
+    pub fn get_top_restaurants(&self, limit: u32) -> Result<Vec<RestaurantStats>, DatabaseError> {
+        let sql = format!(
+            "{} ORDER BY r.total_deliveries DESC, r.avg_rating DESC NULLS LAST, r.name LIMIT ?1",
+            RESTAURANT_STATS_SELECT
+        );
+        let mut stmt = self.conn.prepare(&sql).context("get_top_restaurants")?;
+
+        let restaurants = stmt
+            .query_map([limit], |row| self.map_restaurant_stats_row(row))
+            .context("get_top_restaurants")?
+            .collect::<SqliteResult<Vec<_>>>()
+            .context("get_top_restaurants")?;
+
+        Ok(restaurants)
+    }
+
+    /// Map a row of `RESTAURANT_STATS_SELECT` to RestaurantStats
+    fn map_restaurant_stats_row(&self, row: &rusqlite::Row) -> rusqlite::Result<RestaurantStats> {
+        Ok(RestaurantStats {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            address: row.get(2)?,
+            total_deliveries: row.get(3)?,
+            completed_deliveries: row.get(4)?,
+            complaint_count: row.get(5)?,
+            avg_rating: row.get(6)?,
+        })
+    }
+
+    /// Position density grid for the map heatmap
+    ///
+    /// Combines current bike positions with the end positions of completed
+    /// trips (deliveries carry no coordinates), then bins them in Rust.
+    pub fn get_fleet_heatmap(&self, resolution_meters: f64) -> Result<Vec<HeatmapCell>, DatabaseError> {
+        if !resolution_meters.is_finite() || resolution_meters <= 0.0 {
+            return Err(DatabaseError::InvalidData(
+                "Heatmap resolution must be positive".to_string(),
+            ));
+        }
+
+        let mut stmt = self
+            .conn
+            .prepare(
+                r#"SELECT latitude, longitude FROM bikes WHERE deleted_at IS NULL
+               UNION ALL
+               SELECT end_latitude, end_longitude FROM trips
+               WHERE end_latitude IS NOT NULL AND end_longitude IS NOT NULL"#,
+            )
+            .context("get_fleet_heatmap")?;
+
+        let positions = stmt
+            .query_map([], |row| Ok((row.get::<_, f64>(0)?, row.get::<_, f64>(1)?)))
+            .context("get_fleet_heatmap")?
+            .collect::<SqliteResult<Vec<_>>>()
+            .context("get_fleet_heatmap")?;
+
+        Ok(analytics::heatmap_cells(&positions, resolution_meters))
+    }
+
+    /// Fleet activity per `bucket` from `from` (inclusive) to `to` (exclusive)
+    ///
+    /// Buckets without any activity are included with zero values.
+    pub fn get_fleet_time_series(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        bucket: TimeBucket,
+    ) -> Result<Vec<FleetTimeSeries>, DatabaseError> {
+        analytics::validate_time_series_range(from, to, bucket)
+            .map_err(DatabaseError::InvalidData)?;
+
+        let start = |column: &str| time_bucket_start(bucket, column);
+        let in_range = |column: &str| {
+            format!("julianday({column}) >= julianday(?1) AND julianday({column}) < julianday(?2)")
+        };
+        let sql = format!(
+            r#"SELECT bucket, SUM(created), SUM(completed), AVG(rating),
+                      SUM(opened), SUM(resolved), SUM(distance)
+               FROM (
+                   SELECT {} AS bucket, 1 AS created, 0 AS completed, NULL AS rating,
+                          0 AS opened, 0 AS resolved, 0.0 AS distance
+                   FROM deliveries WHERE {}
+                   UNION ALL
+                   SELECT {}, 0, 1, rating, 0, 0, 0.0 FROM deliveries WHERE {}
+                   UNION ALL
+                   SELECT {}, 0, 0, NULL, 1, 0, 0.0 FROM issues WHERE {}
+                   UNION ALL
+                   SELECT {}, 0, 0, NULL, 0, 1, 0.0 FROM issues WHERE {}
+                   UNION ALL
+                   SELECT {}, 0, 0, NULL, 0, 0, distance_km FROM trips
+                   WHERE {} AND distance_km IS NOT NULL
+               )
+               GROUP BY bucket"#,
+            start("created_at"),
+            in_range("created_at"),
+            start("completed_at"),
+            in_range("completed_at"),
+            start("created_at"),
+            in_range("created_at"),
+            start("resolved_at"),
+            in_range("resolved_at"),
+            start("end_time"),
+            in_range("end_time"),
+        );
+
+        let mut stmt = self.conn.prepare(&sql).context("get_fleet_time_series")?;
+        let rows = stmt
+            .query_map([from.to_rfc3339(), to.to_rfc3339()], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, u32>(1)?,
+                    row.get::<_, u32>(2)?,
+                    row.get::<_, Option<f64>>(3)?,
+                    row.get::<_, u32>(4)?,
+                    row.get::<_, u32>(5)?,
+                    row.get::<_, f64>(6)?,
+                ))
+            })
+            .context("get_fleet_time_series")?
+            .collect::<SqliteResult<Vec<_>>>()
+            .context("get_fleet_time_series")?;
+
+        let measured = rows
+            .into_iter()
+            .map(|(start, total, completed, rating, opened, resolved, distance)| {
+                let period_start = DateTime::parse_from_rfc3339(&start)
+                    .map_err(|e| {
+                        DatabaseError::InvalidData(format!("Invalid bucket {}: {}", start, e))
+                    })?
+                    .with_timezone(&Utc);
+                Ok(FleetTimeSeries {
+                    period_start,
+                    period_end: period_start + bucket.duration(),
+                    total_deliveries: total,
+                    completed_deliveries: completed,
+                    average_rating: rating.unwrap_or(0.0),
+                    issues_opened: opened,
+                    issues_resolved: resolved,
+                    distance_km: distance,
+                })
+            })
+            .collect::<Result<Vec<_>, DatabaseError>>()?;
+
+        Ok(analytics::fill_time_series(from, to, bucket, measured))
+    }
+
+    /// Active and completed deliveries per hour of the day (UTC), for
+    /// deliveries created from `from` (inclusive) to `to` (exclusive)
+    ///
+    /// Always returns all 24 hours, starting at midnight.
+    pub fn get_hourly_utilization(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<HourlyUtilization>, DatabaseError> {
+        if from >= to {
+            return Err(DatabaseError::InvalidData(
+                "Utilization start must be before its end".to_string(),
+            ));
+        }
+
+        let fleet_size: u32 = self
+            .conn
+            .query_row("SELECT COUNT(*) FROM bikes WHERE deleted_at IS NULL", [], |row| {
+                row.get(0)
+            })
+            .context("get_hourly_utilization")?;
+
+        let mut stmt = self
+            .conn
+            .prepare(
+                r#"SELECT CAST(strftime('%H', created_at) AS INTEGER) AS hour,
+                          SUM(status = 'ongoing'),
+                          SUM(status = 'completed'),
+                          COUNT(DISTINCT CASE WHEN status IN ('ongoing', 'completed')
+                                              THEN bike_id END)
+                   FROM deliveries
+                   WHERE julianday(created_at) >= julianday(?1)
+                     AND julianday(created_at) < julianday(?2)
+                   GROUP BY hour"#,
+            )
+            .context("get_hourly_utilization")?;
+        let measured = stmt
+            .query_map([from.to_rfc3339(), to.to_rfc3339()], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+            })
+            .context("get_hourly_utilization")?
+            .collect::<SqliteResult<Vec<_>>>()
+            .context("get_hourly_utilization")?;
+
+        Ok(analytics::hourly_utilization(&measured, fleet_size))
+    }
+
+    // ========================================================================
+    // Statistics
+    // ========================================================================
+
+    /// Get database statistics
+    pub fn get_stats(&self) -> Result<DatabaseStats, DatabaseError> {
+        let total_bikes: u32 = self
+            .conn
+            .query_row(
+                "SELECT COUNT(*) FROM bikes WHERE deleted_at IS NULL",
+                [],
+                |row| row.get(0),
+            )
+            .context("get_stats")?;
+
+        let total_trips: u32 = self
+            .conn
+            .query_row(
+                "SELECT COALESCE(SUM(total_trips), 0) FROM bikes",
+                [],
+                |row| row.get(0),
+            )
+            .context("get_stats")?;
+
+        Ok(DatabaseStats {
+            total_bikes,
+            total_trips,
+            database_size_bytes: 0, // Would need file system access
+            last_sync: Some(Utc::now()),
+        })
+    }
+
+    // ========================================================================
+    // Offline sync queue
+    // ========================================================================
+
+    /// Open the queue file used by `with_queue_fallback` and `flush_sync_queue`
+    pub fn attach_sync_queue(&mut self, path: &Path) -> Result<(), DatabaseError> {
+        self.sync_queue = Some(OfflineSyncQueue::open(path)?);
+        Ok(())
+    }
+
+    /// Run a write, queueing it for `flush_sync_queue` if the database is
+    /// temporarily unavailable
+    ///
+    /// While earlier writes are still queued, the write is queued behind
+    /// them without running, so replaying them can't overwrite it.
+    ///
+    /// # Arguments
+    /// - `op`: The write itself
+    /// - `serialized_op`: The same write as `SyncOperation` JSON, for replay
+    ///
+    /// # Errors
+    /// `InvalidData` if `serialized_op` isn't a `SyncOperation`. Errors of
+    /// `op` that aren't transient, or any error without an attached queue,
+    /// are returned as they are.
+    pub fn with_queue_fallback<F>(&self, op: F, serialized_op: &str) -> Result<(), DatabaseError>
+    where
+        F: FnOnce() -> Result<(), DatabaseError>,
+    {
+        let operation: SyncOperation = serde_json::from_str(serialized_op)
+            .map_err(|e| DatabaseError::InvalidData(format!("Invalid sync operation: {}", e)))?;
+
+        if let Some(queue) = &self.sync_queue {
+            if queue.has_replayable()? {
+                return queue.push(&operation);
+            }
+        }
+
+        match (op(), &self.sync_queue) {
+            (Err(e), Some(queue)) if e.is_transient() => queue.push(&operation),
+            (result, _) => result,
+        }
+    }
+
+    /// Run a write through `with_queue_fallback`, keeping its result
+    ///
+    /// # Errors
+    /// `Queued` if the write was queued: it has no result until it is
+    /// replayed. Other errors are those of `with_queue_fallback`.
+    pub fn queued_write<T, F>(&self, op: F, operation: &SyncOperation) -> Result<T, DatabaseError>
+    where
+        F: FnOnce() -> Result<T, DatabaseError>,
+    {
+        let serialized_op = serde_json::to_string(operation)
+            .map_err(|e| DatabaseError::InvalidData(format!("Unserializable operation: {}", e)))?;
+
+        let mut output = None;
+        self.with_queue_fallback(
+            || {
+                output = Some(op()?);
+                Ok(())
+            },
+            &serialized_op,
+        )?;
+
+        output.ok_or(DatabaseError::Queued)
+    }
+
+    /// Replay queued writes in the order they were queued
+    ///
+    /// Applied writes leave the queue. The first failed one stays, with its
+    /// retry count incremented, and the writes after it wait for the next
+    /// flush, so they are never applied before it. Stuck writes (see
+    /// `MAX_SYNC_RETRIES`) are skipped and reported in `stuck`. Nothing to
+    /// do without an attached queue.
+    pub fn flush_sync_queue(&self) -> Result<FlushResult, DatabaseError> {
+        let mut result = FlushResult::default();
+        let Some(queue) = &self.sync_queue else {
+            return Ok(result);
+        };
+
+        for write in queue.pending()? {
+            if write.is_stuck() {
+                continue;
+            }
+
+            let applied = serde_json::from_str::<SyncOperation>(&write.payload_json)
+                .map_err(|e| DatabaseError::InvalidData(format!("Invalid sync operation: {}", e)))
+                .and_then(|operation| operation.apply(self));
+
+            match applied {
+                Ok(()) => {
+                    queue.remove(write.id)?;
+                    result.applied += 1;
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        operation = %write.operation,
+                        id = write.id,
+                        queued_at = %write.created_at.to_rfc3339(),
+                        retry = write.retries + 1,
+                        error = %e,
+                        "queued write failed"
+                    );
+                    queue.record_failure(write.id)?;
+                    result.failed += 1;
+                    break;
+                }
+            }
+        }
+
+        result.stuck = queue.pending()?.iter().filter(|write| write.is_stuck()).count() as u32;
+        Ok(result)
+    }
+
+    // ========================================================================
+    // Backup
+    // ========================================================================
+
+    /// Copy the live database to `backup_path` using SQLite's online backup API
+    ///
+    /// # Why the backup API instead of a file copy?
+    /// - Produces a consistent snapshot even while the database is in use
+    /// - Copies in small page batches, pausing between steps so other
+    ///   readers/writers are not locked out for the whole backup
+    pub fn create_backup(&self, backup_path: &Path) -> Result<(), DatabaseError> {
+        let mut dst = Connection::open(backup_path).context("create_backup")?;
+        let backup =
+            rusqlite::backup::Backup::new(&self.conn, &mut dst).context("create_backup")?;
+        backup
+            .run_to_completion(BACKUP_PAGES_PER_STEP, BACKUP_STEP_PAUSE, None)
+            .context("create_backup")?;
+        Ok(())
+    }
+
+    /// Run `SELECT 1` to check the connection still answers
+    pub fn ping(&self) -> Result<(), DatabaseError> {
+        self.conn
+            .query_row("SELECT 1", [], |row| row.get::<_, i64>(0))
+            .context("ping")?;
+        Ok(())
+    }
+
+    /// Run `PRAGMA integrity_check` on the open database
+    ///
+    /// # Returns
+    /// The problems SQLite found; empty when the database is intact
+    pub fn integrity_check(&self) -> Result<Vec<String>, DatabaseError> {
+        integrity_problems(&self.conn).context("integrity_check")
+    }
+
+    /// `integrity_check` of a database file, opened read-only
+    ///
+    /// Files that are not SQLite databases fail with an error.
+    pub fn check_file_integrity(path: &Path) -> Result<Vec<String>, DatabaseError> {
+        let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .context("check_file_integrity")?;
+        integrity_problems(&conn).context("check_file_integrity")
+    }
+}
+
+/// Rows of `PRAGMA integrity_check` other than the single "ok" of a healthy database
+fn integrity_problems(conn: &Connection) -> SqliteResult<Vec<String>> {
+    let mut stmt = conn.prepare("PRAGMA integrity_check")?;
+    let rows = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<SqliteResult<Vec<_>>>()?;
+    Ok(rows.into_iter().filter(|row| row != "ok").collect())
+}
+
+/// Pages copied per backup step (SQLite default page size is 4 KiB)
+const BACKUP_PAGES_PER_STEP: std::os::raw::c_int = 100;
+
+/// Pause between backup steps, lets other connections acquire the lock
+const BACKUP_STEP_PAUSE: Duration = Duration::from_millis(10);
+
+/// Largest batch accepted by batch issue updates; larger sets should be paged
+const MAX_ISSUE_BATCH: usize = 100;
+
+/// Reject a zero-day maintenance threshold
+fn validate_maintenance_threshold(threshold: Option<u32>) -> Result<(), DatabaseError> {
+    if threshold == Some(0) {
+        return Err(DatabaseError::InvalidData(
+            "Maintenance threshold must be at least 1 day".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Reject empty or oversized issue batches
+fn validate_issue_batch(issue_ids: &[&str]) -> Result<(), DatabaseError> {
+    if issue_ids.is_empty() {
+        return Err(DatabaseError::InvalidData("No issue IDs given".to_string()));
+    }
+    if issue_ids.len() > MAX_ISSUE_BATCH {
+        return Err(DatabaseError::InvalidData(format!(
+            "Too many issue IDs: {} (max {})",
+            issue_ids.len(),
+            MAX_ISSUE_BATCH
+        )));
+    }
+    Ok(())
+}
+
+/// Columns of `RestaurantStats`; callers append the WHERE/ORDER BY
+const RESTAURANT_STATS_SELECT: &str = r#"SELECT r.id, r.name, r.address, r.total_deliveries,
+       (SELECT COUNT(*) FROM deliveries d WHERE d.restaurant_id = r.id AND d.status = 'completed'),
+       (SELECT COUNT(*) FROM deliveries d WHERE d.restaurant_id = r.id AND d.complaint IS NOT NULL),
+       r.avg_rating
+FROM restaurants r"#;
+
+/// Sort key putting urgent deliveries first
+const PRIORITY_RANK: &str = "CASE priority WHEN 'urgent' THEN 0 WHEN 'high' THEN 1 ELSE 2 END";
+
+/// Serialize a row for the audit log
+fn audit_json<T: serde::Serialize>(row: &T) -> Result<String, DatabaseError> {
+    serde_json::to_string(row).map_err(|e| DatabaseError::InvalidData(e.to_string()))
+}
+
+/// Escape a search query for `LIKE ... ESCAPE '\'`
+fn escape_like(query: &str) -> Result<String, DatabaseError> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Err(DatabaseError::InvalidData("Search query cannot be empty".to_string()));
+    }
+    Ok(query
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_"))
+}
+
+/// SQL expression for the start of the `bucket` containing `column`
+///
+/// Yields RFC 3339 text in UTC, e.g. `2024-01-08T00:00:00Z`.
+fn time_bucket_start(bucket: TimeBucket, column: &str) -> String {
+    match bucket {
+        TimeBucket::Hour => format!("strftime('%Y-%m-%dT%H:00:00Z', {column})"),
+        TimeBucket::Day => format!("strftime('%Y-%m-%dT00:00:00Z', {column})"),
+        // 'weekday 0' moves forward to Sunday (or stays), so -6 days is Monday
+        TimeBucket::Week => {
+            format!("strftime('%Y-%m-%dT00:00:00Z', {column}, 'weekday 0', '-6 days')")
+        }
+    }
+}
+
+/// Sort column of a delivery listing
+///
+/// Nullable columns also return the value standing in for NULL when a row is
+/// compared against the cursor.
+fn delivery_sort_column(sort_by: DeliverySort) -> (&'static str, Option<&'static str>) {
+    match sort_by {
+        DeliverySort::CreatedAt => ("created_at", None),
+        DeliverySort::CompletedAt => ("completed_at", Some("''")),
+        DeliverySort::Status => ("status", None),
+        DeliverySort::Rating => ("rating", Some("0")),
+        DeliverySort::Priority => (PRIORITY_RANK, None),
+    }
+}
+
+/// Sort column of an issue listing
+fn issue_sort_column(sort_by: IssueSort) -> &'static str {
+    match sort_by {
+        IssueSort::CreatedAt => "created_at",
+        IssueSort::Category => "category",
+        IssueSort::Resolved => "resolved",
+    }
+}
+
+/// Sort column of a bike listing
+fn bike_sort_column(sort_by: BikeSort) -> &'static str {
+    match sort_by {
+        BikeSort::Name => "name",
+        BikeSort::Battery => "battery_level",
+        BikeSort::TotalTrips => "total_trips",
+        BikeSort::UpdatedAt => "updated_at",
+    }
+}
+
+/// ORDER BY clause for a keyset-paginated listing sorted on `column`
+///
+/// Timestamps are RFC 3339 strings, so text ordering is chronological. NULLs
+/// of a nullable column come last in either direction. `created_at` and `id`
+/// break ties; the final `id` makes the order total, which keyset pagination
+/// relies on.
+fn keyset_order_by(column: &str, nullable: bool, dir: SortDirection) -> String {
+    let d = dir.as_sql();
+    if column == "created_at" {
+        return format!(" ORDER BY created_at {d}, id {d}");
+    }
+    let nulls_last = if nullable {
+        format!("({column} IS NULL), ")
+    } else {
+        String::new()
+    };
+    format!(" ORDER BY {nulls_last}{column} {d}, created_at {d}, id {d}")
+}
+
+/// Keyset condition for the rows of `table` after the cursor
+///
+/// `?{c}` and `?{i}` bind the cursor's `created_at` and `id`. Other sort keys
+/// are read from the cursor's row; if that row has been deleted, sorts on
+/// columns other than `created_at` end the listing. `null_fill` stands in for
+/// NULL in a nullable column, where all NULL rows tie.
+fn keyset_after_cursor(
+    table: &str,
+    column: &str,
+    null_fill: Option<&str>,
+    dir: SortDirection,
+    c: usize,
+    i: usize,
+) -> String {
+    let op = match dir {
+        SortDirection::Asc => ">",
+        SortDirection::Desc => "<",
+    };
+    if column == "created_at" {
+        return format!(" AND (created_at, id) {op} (?{c}, ?{i})");
+    }
+    let cursor_value = |expr: &str| format!("(SELECT {expr} FROM {table} WHERE id = ?{i})");
+
+    match null_fill {
+        None => format!(
+            " AND ({column}, created_at, id) {op} ({}, ?{c}, ?{i})",
+            cursor_value(column)
+        ),
+        Some(fill) => {
+            let is_null = format!("({column} IS NULL)");
+            let value = format!("COALESCE({column}, {fill})");
+            format!(
+                " AND ({is_null} > {n} OR ({is_null} = {n} AND ({value}, created_at, id) {op} ({v}, ?{c}, ?{i})))",
+                n = cursor_value(&is_null),
+                v = cursor_value(&value)
+            )
+        }
+    }
+}
+
+/// WHERE fragment (appended to `WHERE 1=1`) and its parameters
+type SqlFilters = (String, Vec<Box<dyn rusqlite::ToSql>>);
+
+/// WHERE fragment and parameters for the delivery filters
+fn delivery_filters(
+    bike_id: Option<&str>,
+    status: Option<&str>,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> SqlFilters {
+    let mut sql = String::new();
+    let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+    if let Some(b) = bike_id {
+        params.push(Box::new(b.to_string()));
+        sql.push_str(&format!(" AND bike_id = ?{}", params.len()));
+    }
+    if let Some(s) = status {
+        params.push(Box::new(s.to_string()));
+        sql.push_str(&format!(" AND status = ?{}", params.len()));
+    }
+    push_created_range(&mut sql, &mut params, from, to);
+
+    (sql, params)
+}
+
+/// WHERE fragment and parameters for the issue filters
+fn issue_filters(
+    bike_id: Option<&str>,
+    resolved: Option<bool>,
+    category: Option<&str>,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> SqlFilters {
+    let mut sql = String::new();
+    let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+    if let Some(b) = bike_id {
+        params.push(Box::new(b.to_string()));
+        sql.push_str(&format!(" AND bike_id = ?{}", params.len()));
+    }
+    if let Some(r) = resolved {
+        params.push(Box::new(r as i32));
+        sql.push_str(&format!(" AND resolved = ?{}", params.len()));
+    }
+    if let Some(c) = category {
+        params.push(Box::new(c.to_string()));
+        sql.push_str(&format!(" AND category = ?{}", params.len()));
+    }
+    push_created_range(&mut sql, &mut params, from, to);
+
+    (sql, params)
+}
+
+/// Append an inclusive `created_at` window to a filter fragment
+///
+/// Timestamps are stored as RFC 3339 strings, so text comparison is
+/// chronological.
+fn push_created_range(
+    sql: &mut String,
+    params: &mut Vec<Box<dyn rusqlite::ToSql>>,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) {
+    if let Some(from) = from {
+        params.push(Box::new(from.to_rfc3339()));
+        sql.push_str(&format!(" AND created_at >= ?{}", params.len()));
+    }
+    if let Some(to) = to {
+        params.push(Box::new(to.to_rfc3339()));
+        sql.push_str(&format!(" AND created_at <= ?{}", params.len()));
+    }
+}
+
+/// Reject a window that ends before it starts
+fn validate_date_range(
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> Result<(), DatabaseError> {
+    if let (Some(from), Some(to)) = (from, to) {
+        if from > to {
+            return Err(DatabaseError::InvalidData(
+                "Date range start must not be after its end".to_string(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Reject page sizes outside 1..=MAX_PAGE_SIZE
+fn validate_page_limit(limit: u32) -> Result<(), DatabaseError> {
+    if limit == 0 || limit > MAX_PAGE_SIZE {
+        return Err(DatabaseError::InvalidData(format!(
+            "Page limit must be between 1 and {}",
+            MAX_PAGE_SIZE
+        )));
+    }
+    Ok(())
+}
+
+fn decode_cursor(cursor: &str) -> Result<PageCursor, DatabaseError> {
+    PageCursor::decode(cursor).ok_or_else(|| DatabaseError::InvalidData("Invalid cursor".to_string()))
+}
+
+/// Check a delivery status change and its optional rating
+///
+/// A rating (1-5) may only accompany the transition to `Completed`.
+fn validate_status_update(
+    current: &DeliveryStatus,
+    new_status: &DeliveryStatus,
+    rating: Option<u8>,
+) -> Result<(), DatabaseError> {
+    if !current.can_transition_to(new_status) {
+        return Err(DatabaseError::InvalidData("Invalid status transition".to_string()));
+    }
+    match rating {
+        Some(_) if *new_status != DeliveryStatus::Completed => Err(DatabaseError::InvalidData(
+            "A rating can only be recorded when completing a delivery".to_string(),
+        )),
+        Some(r) if !(1..=5).contains(&r) => Err(DatabaseError::InvalidData(
+            "Rating must be between 1 and 5".to_string(),
+        )),
+        _ => Ok(()),
+    }
+}
 
 /// Generate a simple UUID-like string (not cryptographically secure, for demo purposes)
 fn uuid_v4_simple() -> String {
@@ -721,6 +3299,1388 @@ fn uuid_v4_simple() -> String {
     let now = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .unwrap()
-        .as_nanos();
-    format!("{:x}", now)
+        .as_nanos();
+    format!("{:x}", now)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::force_graph::get_force_graph_layout_internal;
+    use crate::models::{
+        BatteryAlertSummary, ForceGraphClusterMode, ForceGraphData, DEFAULT_LOW_BATTERY_THRESHOLD,
+    };
+    use crate::sync_queue::MAX_SYNC_RETRIES;
+
+    /// Fresh database seeded with the demo data
+    fn test_db(name: &str) -> Database {
+        let path = std::env::temp_dir().join(format!("{}_{}.db", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        Database::new(path, SqliteConfig::default()).unwrap()
+    }
+
+    /// An issue linked to a delivery, as (issue ID, delivery ID)
+    fn linked_issue(db: &Database) -> (String, String) {
+        db.get_issues(None, None, None, None, None, None, None, None, MAX_PAGE_SIZE)
+            .unwrap()
+            .items
+            .into_iter()
+            .find_map(|issue| Some((issue.id, issue.delivery_id?)))
+            .expect("seed data has issues linked to deliveries")
+    }
+
+    #[test]
+    fn test_wal_mode_enabled_by_default() {
+        let db = test_db("wal_mode");
+
+        let journal_mode: String = db
+            .conn
+            .query_row("PRAGMA journal_mode", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(journal_mode, "wal");
+    }
+
+    #[test]
+    fn test_backup_passes_integrity_check() {
+        let db = test_db("integrity_source");
+        let backup = std::env::temp_dir().join(format!("integrity_backup_{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&backup);
+
+        db.create_backup(&backup).unwrap();
+
+        assert!(db.integrity_check().unwrap().is_empty());
+        assert!(Database::check_file_integrity(&backup).unwrap().is_empty());
+
+        let not_a_database = backup.with_extension("txt");
+        std::fs::write(&not_a_database, "name,latitude\n").unwrap();
+        assert!(Database::check_file_integrity(&not_a_database).is_err());
+    }
+
+    #[test]
+    fn test_set_pragmas_switches_journal_mode() {
+        let mut db = test_db("set_pragmas");
+        let config = SqliteConfig {
+            wal_mode: false,
+            busy_timeout_ms: 250,
+            log_threshold_ms: Some(100),
+        };
+
+        assert_eq!(db.set_pragmas(config).unwrap(), "delete");
+        assert_eq!(db.sqlite_config(), config);
+    }
+
+    #[test]
+    fn test_retire_bike_hides_it_but_keeps_history() {
+        let db = test_db("retire_bike");
+        let deliveries = db.get_deliveries_by_bike("BIKE-0001").unwrap().len();
+
+        db.retire_bike("BIKE-0001").unwrap();
+
+        assert!(db.get_bike_by_id("BIKE-0001").unwrap().is_none());
+        assert_eq!(db.get_all_bikes(None, None).unwrap().len(), 9);
+        let retired = db.get_retired_bikes().unwrap();
+        assert_eq!(retired.len(), 1);
+        assert_eq!(retired[0].status, BikeStatus::Offline);
+        assert!(retired[0].deleted_at.is_some());
+        assert_eq!(db.get_deliveries_by_bike("BIKE-0001").unwrap().len(), deliveries);
+
+        let again = db.retire_bike("BIKE-0001");
+        assert!(matches!(again, Err(DatabaseError::InvalidData(_))));
+
+        // Retired bikes can't be changed through the other updates
+        let status = db.update_bike_status("BIKE-0001", &BikeStatus::Available, None, None, None);
+        assert!(matches!(status, Err(DatabaseError::InvalidData(_))));
+        let reserve = db.reserve_bike("BIKE-0001", Utc::now() + chrono::Duration::hours(1));
+        assert!(matches!(reserve, Err(DatabaseError::InvalidData(_))));
+        let threshold = db.set_bike_maintenance_threshold("BIKE-0001", Some(7));
+        assert!(matches!(threshold, Err(DatabaseError::InvalidData(_))));
+        assert_eq!(db.get_retired_bikes().unwrap()[0].status, BikeStatus::Offline);
+    }
+
+    #[test]
+    fn test_retire_bike_is_audited_as_delete() {
+        let db = test_db("audit_retire");
+
+        db.retire_bike("BIKE-0001").unwrap();
+
+        let entries = db.get_audit_log(Some("bikes"), Some("BIKE-0001"), None, None).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].operation, AuditOp::Delete);
+        assert!(entries[0].old_value_json.as_deref().unwrap().contains("BIKE-0001"));
+        assert!(entries[0].new_value_json.is_none());
+    }
+
+    #[test]
+    fn test_every_mutation_is_audited() {
+        let db = test_db("audit_mutations");
+        let audited = |table: &str, row_id: &str| {
+            db.get_audit_log(Some(table), Some(row_id), None, None)
+                .unwrap()
+                .into_iter()
+                .map(|entry| entry.operation)
+                .collect::<Vec<_>>()
+        };
+
+        let bike = db.add_bike("Audited", 52.37, 4.89, None).unwrap();
+        db.reserve_bike(&bike.id, Utc::now() + chrono::Duration::minutes(15)).unwrap();
+        db.cancel_reservation(&bike.id).unwrap();
+        db.set_bike_maintenance_threshold(&bike.id, Some(30)).unwrap();
+        // A failed mutation leaves no audit row behind
+        assert!(db.cancel_reservation(&bike.id).is_err());
+        assert_eq!(
+            audited("bikes", &bike.id),
+            [AuditOp::Insert, AuditOp::Update, AuditOp::Update, AuditOp::Update]
+        );
+
+        let delivery_id = db
+            .create_delivery(&CreateDeliveryRequest {
+                bike_id: "BIKE-0001".to_string(),
+                customer_name: "P. de Vries".to_string(),
+                customer_address: "Damrak 1".to_string(),
+                restaurant_name: "Febo".to_string(),
+                restaurant_address: "Rokin 2".to_string(),
+            })
+            .unwrap()
+            .id;
+        let issue_id = db.create_issue(&issue_request("BIKE-0001", Some(&delivery_id))).unwrap().id;
+        assert_eq!(db.batch_resolve_issues(&[&issue_id, &issue_id]).unwrap(), 1);
+        db.reopen_issue(&issue_id).unwrap();
+        db.delete_delivery(&delivery_id, true).unwrap();
+        db.delete_issue(&issue_id).unwrap();
+        assert_eq!(
+            audited("issues", &issue_id),
+            [AuditOp::Insert, AuditOp::Update, AuditOp::Update, AuditOp::Update, AuditOp::Delete]
+        );
+        assert_eq!(audited("deliveries", &delivery_id), [AuditOp::Insert, AuditOp::Delete]);
+    }
+
+    #[test]
+    fn test_events_since_returns_each_event_once() {
+        let db = test_db("fleet_events");
+        let start = db.get_events_since(0, MAX_PAGE_SIZE).unwrap().last().map_or(0, |e| e.id);
+
+        let bike = db.add_bike("Events", 52.37, 4.89, None).unwrap();
+        db.update_bike_status(&bike.id, &BikeStatus::Charging, None, None, None).unwrap();
+        db.retire_bike(&bike.id).unwrap();
+
+        let events = db.get_events_since(start, MAX_PAGE_SIZE).unwrap();
+        let types: Vec<FleetEventType> = events.iter().map(|e| e.event_type).collect();
+        assert_eq!(
+            types,
+            vec![
+                FleetEventType::BikeAdded,
+                FleetEventType::BikeStatusChanged,
+                FleetEventType::BikeRetired,
+            ]
+        );
+        assert!(events.windows(2).all(|w| w[0].id < w[1].id));
+        assert!(events[1].payload_json.contains("\"charging\""));
+
+        let first = db.get_events_since(start, 1).unwrap();
+        assert_eq!(first.len(), 1);
+        let rest = db.get_events_since(first[0].id, MAX_PAGE_SIZE).unwrap();
+        assert_eq!(rest.len(), 2);
+        assert!(db.get_events_since(events[2].id, MAX_PAGE_SIZE).unwrap().is_empty());
+        assert!(db.get_events_since(0, 0).is_err());
+    }
+
+    /// Events appended since `start`, by type
+    fn event_types_since(db: &Database, start: u64) -> Vec<FleetEventType> {
+        db.get_events_since(start, MAX_PAGE_SIZE)
+            .unwrap()
+            .into_iter()
+            .map(|e| e.event_type)
+            .collect()
+    }
+
+    /// ID of the latest event, to read only what follows
+    fn last_event_id(db: &Database) -> u64 {
+        let mut last = 0;
+        loop {
+            match db.get_events_since(last, MAX_PAGE_SIZE).unwrap().last() {
+                Some(event) => last = event.id,
+                None => return last,
+            }
+        }
+    }
+
+    #[test]
+    fn test_batch_resolve_appends_issue_resolved_events() {
+        let db = test_db("batch_resolve_events");
+        let open: Vec<String> = db
+            .get_issues(None, Some(false), None, None, None, None, None, None, 2)
+            .unwrap()
+            .items
+            .into_iter()
+            .map(|issue| issue.id)
+            .collect();
+        let batch: Vec<&str> = open.iter().map(String::as_str).collect();
+        let start = last_event_id(&db);
+
+        db.batch_resolve_issues(&batch).unwrap();
+        db.batch_reopen_issues(&batch).unwrap();
+
+        assert_eq!(
+            event_types_since(&db, start),
+            vec![FleetEventType::IssueResolved, FleetEventType::IssueResolved]
+        );
+    }
+
+    #[test]
+    fn test_released_reservations_append_status_events() {
+        let db = test_db("reservation_expiry_events");
+        let bike = db.add_bike("Expiring", 52.37, 4.89, None).unwrap();
+        db.reserve_bike(&bike.id, Utc::now() + chrono::Duration::minutes(1)).unwrap();
+        let expired = (Utc::now() - chrono::Duration::minutes(1)).to_rfc3339();
+        db.conn
+            .execute(
+                "UPDATE bikes SET reservation_expires_at = ?1 WHERE id = ?2",
+                rusqlite::params![expired, bike.id],
+            )
+            .unwrap();
+        let start = last_event_id(&db);
+
+        db.release_expired_reservations().unwrap();
+
+        let events = db.get_events_since(start, MAX_PAGE_SIZE).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_type, FleetEventType::BikeStatusChanged);
+        assert!(events[0].payload_json.contains("\"available\""));
+    }
+
+    #[test]
+    fn test_simulation_scenario_appends_a_status_event_per_bike() {
+        let db = test_db("scenario_events");
+        let start = last_event_id(&db);
+
+        let bikes = db.apply_simulation_scenario(SimulationScenario::AllIdle).unwrap();
+
+        let types = event_types_since(&db, start);
+        assert_eq!(types.len(), bikes.len());
+        assert!(types.iter().all(|t| *t == FleetEventType::BikeStatusChanged));
+    }
+
+    #[test]
+    fn test_fleet_time_series_counts_each_days_activity() {
+        use chrono::TimeZone;
+
+        let db = test_db("time_series");
+        let at = |day: u32, hour: u32| Utc.with_ymd_and_hms(2024, 3, day, hour, 0, 0).unwrap();
+
+        // Activity on fixed days, long before the seed data
+        let trip_id = db.start_trip("BIKE-0001", 52.3791, 4.9003).unwrap();
+        db.end_trip(&trip_id, 52.3731, 4.8932).unwrap();
+        db.conn
+            .execute(
+                "UPDATE trips SET start_time = ?1, end_time = ?2 WHERE id = ?3",
+                rusqlite::params![at(14, 9).to_rfc3339(), at(14, 10).to_rfc3339(), trip_id],
+            )
+            .unwrap();
+        let delivery = db
+            .create_delivery(&CreateDeliveryRequest {
+                bike_id: "BIKE-0001".to_string(),
+                customer_name: "P. de Vries".to_string(),
+                customer_address: "Damrak 1".to_string(),
+                restaurant_name: "Febo".to_string(),
+                restaurant_address: "Rokin 2".to_string(),
+            })
+            .unwrap();
+        db.conn
+            .execute(
+                r#"UPDATE deliveries SET status = 'completed', rating = 4,
+                                         created_at = ?1, completed_at = ?2
+                   WHERE id = ?3"#,
+                rusqlite::params![at(12, 18).to_rfc3339(), at(13, 8).to_rfc3339(), delivery.id],
+            )
+            .unwrap();
+
+        let series = db.get_fleet_time_series(at(12, 0), at(15, 0), TimeBucket::Day).unwrap();
+
+        assert_eq!(series.len(), 3);
+        assert!(series.windows(2).all(|w| w[0].period_end == w[1].period_start));
+        assert_eq!(series[0].period_start, at(12, 0));
+        assert_eq!((series[0].total_deliveries, series[0].completed_deliveries), (1, 0));
+        assert_eq!((series[1].total_deliveries, series[1].completed_deliveries), (0, 1));
+        assert_eq!(series[1].average_rating, 4.0);
+        assert_eq!(series[1].distance_km, 0.0);
+        assert!((series[2].distance_km - 0.82).abs() < 0.05);
+        assert_eq!(series[2].total_deliveries, 0);
+    }
+
+    #[test]
+    fn test_hourly_utilization_covers_every_hour() {
+        let db = test_db("hourly_utilization");
+        let now = Utc::now();
+        let from = now - chrono::Duration::days(3650);
+        let to = now + chrono::Duration::seconds(1);
+
+        let hours = db.get_hourly_utilization(from, to).unwrap();
+
+        assert_eq!(hours.len(), 24);
+        assert!(hours.iter().enumerate().all(|(i, h)| h.hour as usize == i));
+        assert!(hours.iter().all(|h| (0.0..=1.0).contains(&h.utilization_ratio)));
+        let expected: u32 = db
+            .conn
+            .query_row(
+                "SELECT COUNT(*) FROM deliveries WHERE status IN ('ongoing', 'completed')",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        let counted: u32 = hours
+            .iter()
+            .map(|h| h.active_deliveries + h.completed_deliveries)
+            .sum();
+        assert_eq!(counted, expected);
+        assert!(db.get_hourly_utilization(to, from).is_err());
+    }
+
+    #[test]
+    fn test_search_bikes_and_deliveries() {
+        let db = test_db("search");
+
+        let results = db.search("vondel", 10).unwrap();
+        assert_eq!(results.bikes.len(), 1);
+        assert_eq!(results.bikes[0].name, "Amsterdam Vondelpark Bike");
+
+        let results = db.search("FEBO", 2).unwrap();
+        assert_eq!(results.deliveries.len(), 2);
+        assert!(results.deliveries.iter().all(|d| d.restaurant_name == "Febo"));
+
+        // Wildcards match literally
+        let results = db.search("%", 10).unwrap();
+        assert!(results.bikes.is_empty() && results.deliveries.is_empty());
+        assert!(db.search("  ", 10).is_err());
+    }
+
+    #[test]
+    fn test_import_bikes() {
+        let db = test_db("import_bikes");
+        let csv = "name,latitude,longitude,battery_level\n\
+                   Noord 1,52.3907,4.9228,90\n\
+                   Noord 2,52.3910,4.9230,\n\
+                   Utrecht,52.0907,5.1214,50\n";
+        let parsed = crate::bike_import::parse_bike_csv(csv).unwrap();
+        assert_eq!(parsed.errors.len(), 1);
+
+        assert_eq!(db.import_bikes(&parsed.bikes).unwrap(), 2);
+        let bikes = db.get_all_bikes(None, None).unwrap();
+        assert_eq!(bikes.len(), 12);
+        assert!(bikes.iter().any(|b| b.name == "Noord 2" && b.battery_level.is_none()));
+    }
+
+    #[test]
+    fn test_low_battery_bikes_lowest_first() {
+        let db = test_db("low_battery");
+        db.add_bike("Warning", 52.37, 4.89, Some(15)).unwrap();
+        db.add_bike("Critical", 52.37, 4.89, Some(5)).unwrap();
+        db.add_bike("No Reading", 52.37, 4.89, None).unwrap();
+
+        let bikes = db.get_bikes_below_battery(DEFAULT_LOW_BATTERY_THRESHOLD).unwrap();
+        let names: Vec<&str> = bikes.iter().map(|b| b.name.as_str()).collect();
+        assert_eq!(names, vec!["Critical", "Warning"]);
+
+        let summary = BatteryAlertSummary::from_bikes(bikes);
+        assert_eq!(summary.critical_count, 1);
+        assert_eq!(summary.warning_count, 1);
+    }
+
+    #[test]
+    fn test_overdue_maintenance_most_overdue_first() {
+        let db = test_db("overdue_maintenance");
+        let now = Utc::now();
+        let soon = db.add_bike("Soon", 52.37, 4.89, None).unwrap();
+        let late = db.add_bike("Late", 52.37, 4.89, None).unwrap();
+        let future = db.add_bike("Future", 52.37, 4.89, None).unwrap();
+        assert!(db.get_overdue_maintenance(now).unwrap().is_empty());
+
+        db.schedule_maintenance(&soon.id, now - chrono::Duration::hours(1)).unwrap();
+        db.schedule_maintenance(&late.id, now - chrono::Duration::days(3)).unwrap();
+        db.schedule_maintenance(&future.id, now + chrono::Duration::days(3)).unwrap();
+
+        let overdue = db.get_overdue_maintenance(now).unwrap();
+        let names: Vec<&str> = overdue.iter().map(|b| b.name.as_str()).collect();
+        assert_eq!(names, vec!["Late", "Soon"]);
+
+        db.record_maintenance_complete(&late.id, now, 30).unwrap();
+        let late = db.get_bike_by_id(&late.id).unwrap().unwrap();
+        assert_eq!(late.last_maintenance, Some(now));
+        assert_eq!(late.next_maintenance_at, Some(now + chrono::Duration::days(30)));
+        let names: Vec<String> =
+            db.get_overdue_maintenance(now).unwrap().into_iter().map(|b| b.name).collect();
+        assert_eq!(names, vec!["Soon"]);
+
+        assert!(matches!(
+            db.schedule_maintenance("BIKE-9999", now),
+            Err(DatabaseError::InvalidData(_))
+        ));
+        assert!(matches!(
+            db.record_maintenance_complete(&soon.id, now, 0),
+            Err(DatabaseError::InvalidData(_))
+        ));
+    }
+
+    #[test]
+    fn test_bikes_due_for_maintenance_honour_overrides() {
+        let db = test_db("maintenance_due");
+        let now = Utc::now();
+        let maintained = |name: &str, days_ago: i64| {
+            let bike = db.add_bike(name, 52.37, 4.89, None).unwrap();
+            db.record_maintenance_complete(&bike.id, now - chrono::Duration::days(days_ago), 30)
+                .unwrap();
+            bike.id
+        };
+        maintained("Recent", 10);
+        let strict = maintained("Strict", 10);
+        let lenient = maintained("Lenient", 40);
+        maintained("Old", 40);
+        db.add_bike("Never", 52.37, 4.89, None).unwrap();
+
+        db.set_bike_maintenance_threshold(&strict, Some(7)).unwrap();
+        db.set_bike_maintenance_threshold(&lenient, Some(60)).unwrap();
+        let due_names = |db: &Database| -> Vec<String> {
+            db.get_bikes_due_for_maintenance(30)
+                .unwrap()
+                .into_iter()
+                .map(|bike| bike.name)
+                .filter(|name| {
+                    ["Recent", "Strict", "Lenient", "Old", "Never"].contains(&name.as_str())
+                })
+                .collect()
+        };
+        assert_eq!(due_names(&db), vec!["Never", "Old", "Strict"]);
+
+        // Clearing the override falls back to the global threshold
+        db.set_bike_maintenance_threshold(&lenient, None).unwrap();
+        assert_eq!(due_names(&db), vec!["Lenient", "Never", "Old", "Strict"]);
+        let lenient = db.get_bike_by_id(&lenient).unwrap().unwrap();
+        assert_eq!(lenient.maintenance_threshold_override, None);
+
+        assert!(matches!(
+            db.set_bike_maintenance_threshold(&strict, Some(0)),
+            Err(DatabaseError::InvalidData(_))
+        ));
+        assert!(matches!(
+            db.set_bike_maintenance_threshold("BIKE-9999", Some(7)),
+            Err(DatabaseError::InvalidData(_))
+        ));
+    }
+
+    #[test]
+    fn test_reserve_and_cancel_bike() {
+        let db = test_db("reservations");
+        let bike = db.add_bike("Reservable", 52.37, 4.89, None).unwrap();
+        let expires_at = Utc::now() + chrono::Duration::minutes(15);
+
+        db.reserve_bike(&bike.id, expires_at).unwrap();
+        let reserved = db.get_bike_by_id(&bike.id).unwrap().unwrap();
+        assert_eq!(reserved.status, BikeStatus::Reserved);
+        assert!(reserved.reservation_expires_at.is_some());
+        assert!(matches!(
+            db.reserve_bike(&bike.id, expires_at),
+            Err(DatabaseError::InvalidData(_))
+        ));
+
+        db.cancel_reservation(&bike.id).unwrap();
+        let cancelled = db.get_bike_by_id(&bike.id).unwrap().unwrap();
+        assert_eq!(cancelled.status, BikeStatus::Available);
+        assert!(cancelled.reservation_expires_at.is_none());
+        assert!(matches!(db.cancel_reservation(&bike.id), Err(DatabaseError::InvalidData(_))));
+
+        let past = Utc::now() - chrono::Duration::minutes(1);
+        assert!(matches!(db.reserve_bike(&bike.id, past), Err(DatabaseError::InvalidData(_))));
+    }
+
+    #[test]
+    fn test_update_bike_status_rejects_reserved() {
+        let db = test_db("reserved_status");
+        let bike = db.add_bike("No Expiry", 52.37, 4.89, None).unwrap();
+
+        let result = db.update_bike_status(&bike.id, &BikeStatus::Reserved, None, None, None);
+
+        assert!(matches!(result, Err(DatabaseError::InvalidData(_))));
+        let bike = db.get_bike_by_id(&bike.id).unwrap().unwrap();
+        assert_eq!(bike.status, BikeStatus::Available);
+    }
+
+    #[test]
+    fn test_expired_reservations_are_released() {
+        let db = test_db("reservation_expiry");
+        let expiring = db.add_bike("Expiring", 52.37, 4.89, None).unwrap();
+        let held = db.add_bike("Held", 52.37, 4.89, None).unwrap();
+        db.reserve_bike(&expiring.id, Utc::now() + chrono::Duration::minutes(1)).unwrap();
+        db.reserve_bike(&held.id, Utc::now() + chrono::Duration::hours(1)).unwrap();
+        // reserve_bike refuses past expiries, so backdate one directly
+        let expired = (Utc::now() - chrono::Duration::minutes(1)).to_rfc3339();
+        db.conn
+            .execute(
+                "UPDATE bikes SET reservation_expires_at = ?1 WHERE id = ?2",
+                rusqlite::params![expired, expiring.id],
+            )
+            .unwrap();
+
+        assert_eq!(db.release_expired_reservations().unwrap(), 1);
+
+        let released = db.get_bike_by_id(&expiring.id).unwrap().unwrap();
+        assert_eq!(released.status, BikeStatus::Available);
+        assert!(released.reservation_expires_at.is_none());
+        let held = db.get_bike_by_id(&held.id).unwrap().unwrap();
+        assert_eq!(held.status, BikeStatus::Reserved);
+        assert_eq!(db.release_expired_reservations().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_trip_updates_bike_totals() {
+        let db = test_db("trips");
+        let before = db.get_bike_by_id("BIKE-0001").unwrap().unwrap();
+
+        let trip_id = db.start_trip("BIKE-0001", 52.3791, 4.9003).unwrap();
+        db.end_trip(&trip_id, 52.3731, 4.8932).unwrap();
+
+        let after = db.get_bike_by_id("BIKE-0001").unwrap().unwrap();
+        assert_eq!(after.total_trips, before.total_trips + 1);
+        let added = after.total_distance_km - before.total_distance_km;
+        assert!((added - 0.82).abs() < 0.05, "added {}", added);
+
+        // A trip can only be ended once
+        let again = db.end_trip(&trip_id, 52.37, 4.89);
+        assert!(matches!(again, Err(DatabaseError::InvalidData(_))));
+        assert!(db.start_trip("BIKE-9999", 52.37, 4.89).is_err());
+    }
+
+    #[test]
+    fn test_create_delivery() {
+        let db = test_db("create_delivery");
+        let mut request = CreateDeliveryRequest {
+            bike_id: "BIKE-0001".to_string(),
+            customer_name: "P. de Vries".to_string(),
+            customer_address: "Damrak 1".to_string(),
+            restaurant_name: "Febo".to_string(),
+            restaurant_address: "Rokin 2".to_string(),
+        };
+
+        let created = db.create_delivery(&request).unwrap();
+        assert_eq!(created.status, DeliveryStatus::Upcoming);
+        let stored = db.get_delivery_by_id(&created.id).unwrap().expect("delivery stored");
+        assert_eq!(stored.bike_id, "BIKE-0001");
+        assert_eq!(stored.restaurant_name, "Febo");
+
+        request.bike_id = "BIKE-9999".to_string();
+        let err = db.create_delivery(&request).unwrap_err();
+        assert!(matches!(err, DatabaseError::InvalidData(_)));
+    }
+
+    #[test]
+    fn test_create_delivery_updates_restaurant_stats() {
+        let db = test_db("restaurant_stats");
+        let top = db.get_top_restaurants(1).unwrap().remove(0);
+        assert!(top.total_deliveries > 0);
+        let mut request = CreateDeliveryRequest {
+            bike_id: "BIKE-0001".to_string(),
+            customer_name: "P. de Vries".to_string(),
+            customer_address: "Damrak 1".to_string(),
+            restaurant_name: top.name.clone(),
+            restaurant_address: "Rokin 2".to_string(),
+        };
+
+        db.create_delivery(&request).unwrap();
+        let stats = db.get_restaurant_stats(&top.id).unwrap();
+        assert_eq!(stats.total_deliveries, top.total_deliveries + 1);
+
+        // A restaurant seen for the first time is created with its delivery
+        request.restaurant_name = "Snackbar Zuid".to_string();
+        let created = db.create_delivery(&request).unwrap();
+        let restaurant_id: String = db
+            .conn
+            .query_row(
+                "SELECT restaurant_id FROM deliveries WHERE id = ?1",
+                [&created.id],
+                |row| row.get(0),
+            )
+            .unwrap();
+        db.update_delivery_status(&created.id, DeliveryStatus::Ongoing, None).unwrap();
+        db.update_delivery_status(&created.id, DeliveryStatus::Completed, Some(4)).unwrap();
+
+        let snackbar = db.get_restaurant_stats(&restaurant_id).unwrap();
+        assert_eq!(snackbar.name, "Snackbar Zuid");
+        assert_eq!(snackbar.address.as_deref(), Some("Rokin 2"));
+        assert_eq!(snackbar.total_deliveries, 1);
+        assert_eq!(snackbar.completed_deliveries, 1);
+        assert_eq!(snackbar.avg_rating, Some(4.0));
+        assert!(db.get_restaurant_stats("REST-missing").is_err());
+    }
+
+    #[test]
+    fn test_busy_writes_are_queued_and_flushed() {
+        let mut db = test_db("sync_queue");
+        let queue_path =
+            std::env::temp_dir().join(format!("sync_queue_{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&queue_path);
+        db.attach_sync_queue(&queue_path).unwrap();
+        let busy = || {
+            Err(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_BUSY),
+                None,
+            ))
+            .context("retire_bike")
+        };
+
+        let retire = r#"{"operation": "retire_bike", "payload": {"bike_id": "BIKE-0001"}}"#;
+
+        // Invalid data would fail the same way on replay, so it isn't queued
+        let invalid = || Err(DatabaseError::InvalidData("Bike not found".to_string()));
+        assert!(db.with_queue_fallback(invalid, retire).is_err());
+        assert!(db.with_queue_fallback(|| Ok(()), "not an operation").is_err());
+
+        db.with_queue_fallback(busy, retire).unwrap();
+        let missing = r#"{"operation": "retire_bike", "payload": {"bike_id": "BIKE-9999"}}"#;
+        db.with_queue_fallback(busy, missing).unwrap();
+        assert!(db.get_retired_bikes().unwrap().is_empty());
+
+        let flushed = db.flush_sync_queue().unwrap();
+
+        assert_eq!(flushed, FlushResult { applied: 1, failed: 1, stuck: 0 });
+        assert_eq!(db.get_retired_bikes().unwrap()[0].id, "BIKE-0001");
+        let remaining = db.sync_queue.as_ref().unwrap().pending().unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].retries, 1);
+
+        // The missing bike never turns up: the write gets stuck but is kept
+        for _ in 1..MAX_SYNC_RETRIES - 1 {
+            db.flush_sync_queue().unwrap();
+        }
+        let flushed = db.flush_sync_queue().unwrap();
+        assert_eq!(flushed, FlushResult { applied: 0, failed: 1, stuck: 1 });
+        let flushed = db.flush_sync_queue().unwrap();
+        assert_eq!(flushed, FlushResult { applied: 0, failed: 0, stuck: 1 });
+        let remaining = db.sync_queue.as_ref().unwrap().pending().unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].retries, MAX_SYNC_RETRIES);
+    }
+
+    #[test]
+    fn test_writes_wait_behind_queued_writes() {
+        let mut db = test_db("sync_queue_order");
+        let queue_path =
+            std::env::temp_dir().join(format!("sync_queue_order_{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&queue_path);
+        db.attach_sync_queue(&queue_path).unwrap();
+        let status_update = |status: BikeStatus| SyncOperation::UpdateBikeStatus {
+            bike_id: "BIKE-0001".to_string(),
+            status,
+            latitude: None,
+            longitude: None,
+            battery_level: None,
+        };
+        let busy = || {
+            Err(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_BUSY),
+                None,
+            ))
+            .context("update_bike_status")
+        };
+
+        let older = status_update(BikeStatus::Maintenance);
+        assert!(matches!(db.queued_write::<(), _>(busy, &older), Err(DatabaseError::Queued)));
+
+        // The database is back, but the newer write must not overtake the older
+        let newer = status_update(BikeStatus::Charging);
+        let result = db.queued_write(
+            || db.update_bike_status("BIKE-0001", &BikeStatus::Charging, None, None, None),
+            &newer,
+        );
+        assert!(matches!(result, Err(DatabaseError::Queued)));
+
+        let flushed = db.flush_sync_queue().unwrap();
+        assert_eq!(flushed, FlushResult { applied: 2, failed: 0, stuck: 0 });
+        let bike = db.get_bike_by_id("BIKE-0001").unwrap().unwrap();
+        assert_eq!(bike.status, BikeStatus::Charging);
+
+        // With the queue empty again, writes run directly
+        let direct = status_update(BikeStatus::Available);
+        db.queued_write(
+            || db.update_bike_status("BIKE-0001", &BikeStatus::Available, None, None, None),
+            &direct,
+        )
+        .unwrap();
+        assert!(db.sync_queue.as_ref().unwrap().pending().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_failed_replay_holds_back_later_writes() {
+        let mut db = test_db("sync_queue_hold");
+        let queue_path =
+            std::env::temp_dir().join(format!("sync_queue_hold_{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&queue_path);
+        db.attach_sync_queue(&queue_path).unwrap();
+        let busy = || {
+            Err(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_BUSY),
+                None,
+            ))
+            .context("retire_bike")
+        };
+
+        let missing = r#"{"operation": "retire_bike", "payload": {"bike_id": "BIKE-9999"}}"#;
+        db.with_queue_fallback(busy, missing).unwrap();
+        let retire = r#"{"operation": "retire_bike", "payload": {"bike_id": "BIKE-0001"}}"#;
+        db.with_queue_fallback(|| panic!("must wait behind the queue"), retire).unwrap();
+
+        let flushed = db.flush_sync_queue().unwrap();
+        assert_eq!(flushed, FlushResult { applied: 0, failed: 1, stuck: 0 });
+        assert!(db.get_retired_bikes().unwrap().is_empty());
+
+        // Once the failing write is stuck, the writes behind it go through
+        for _ in 1..MAX_SYNC_RETRIES {
+            db.flush_sync_queue().unwrap();
+        }
+        let flushed = db.flush_sync_queue().unwrap();
+        assert_eq!(flushed, FlushResult { applied: 1, failed: 0, stuck: 1 });
+        assert_eq!(db.get_retired_bikes().unwrap()[0].id, "BIKE-0001");
+    }
+
+    #[test]
+    fn test_queued_write_returns_the_result_or_queued() {
+        let mut db = test_db("queued_write");
+        let queue_path =
+            std::env::temp_dir().join(format!("queued_write_{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&queue_path);
+        db.attach_sync_queue(&queue_path).unwrap();
+        let retire = SyncOperation::RetireBike {
+            bike_id: "BIKE-0002".to_string(),
+        };
+
+        assert_eq!(db.queued_write(|| Ok(7), &retire).unwrap(), 7);
+        assert!(db.sync_queue.as_ref().unwrap().pending().unwrap().is_empty());
+
+        let busy = || {
+            Err(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_BUSY),
+                None,
+            ))
+            .context("retire_bike")
+        };
+        assert!(matches!(
+            db.queued_write::<(), _>(busy, &retire),
+            Err(DatabaseError::Queued)
+        ));
+        let pending = db.sync_queue.as_ref().unwrap().pending().unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].operation, "retire_bike");
+    }
+
+    #[test]
+    fn test_update_delivery_status_only_moves_forward() {
+        let db = test_db("update_delivery_status");
+        let upcoming = db
+            .query_deliveries(
+                delivery_filters(None, Some("upcoming"), None, None),
+                (DeliverySort::CreatedAt, SortDirection::Desc),
+                None,
+                Some(1),
+            )
+            .unwrap()
+            .remove(0);
+
+        let skip = db.update_delivery_status(&upcoming.id, DeliveryStatus::Completed, None);
+        assert!(matches!(skip, Err(DatabaseError::InvalidData(_))));
+        let early_rating = db.update_delivery_status(&upcoming.id, DeliveryStatus::Ongoing, Some(4));
+        assert!(matches!(early_rating, Err(DatabaseError::InvalidData(_))));
+
+        db.update_delivery_status(&upcoming.id, DeliveryStatus::Ongoing, None).unwrap();
+        let completed = db
+            .update_delivery_status(&upcoming.id, DeliveryStatus::Completed, Some(4))
+            .unwrap();
+        assert!(completed.completed_at.is_some());
+
+        let stored = db.get_delivery_by_id(&upcoming.id).unwrap().unwrap();
+        assert_eq!(stored.status, DeliveryStatus::Completed);
+        assert_eq!(stored.rating, Some(4));
+        assert!(stored.completed_at.is_some());
+
+        let back = db.update_delivery_status(&upcoming.id, DeliveryStatus::Ongoing, None);
+        assert!(matches!(back, Err(DatabaseError::InvalidData(_))));
+    }
+
+    #[test]
+    fn test_delete_delivery_with_issues_requires_cascade() {
+        let db = test_db("delete_delivery_no_cascade");
+        let (_, delivery_id) = linked_issue(&db);
+
+        let err = db.delete_delivery(&delivery_id, false).unwrap_err();
+        assert!(matches!(err, DatabaseError::InvalidData(_)));
+        assert!(db.get_delivery_by_id(&delivery_id).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_delete_delivery_cascade_detaches_issues() {
+        let db = test_db("delete_delivery_cascade");
+        let (issue_id, delivery_id) = linked_issue(&db);
+
+        db.delete_delivery(&delivery_id, true).unwrap();
+
+        assert!(db.get_delivery_by_id(&delivery_id).unwrap().is_none());
+        let issue = db.get_issue_by_id(&issue_id).unwrap().expect("issue kept");
+        assert_eq!(issue.delivery_id, None);
+    }
+
+    #[test]
+    fn test_waypoints_keep_insertion_order() {
+        let db = test_db("waypoints");
+        let (_, delivery_id) = linked_issue(&db);
+
+        let points = [(52.3791, 4.9003), (52.3731, 4.8932), (52.3579, 4.8686)];
+        let ids: Vec<String> = points
+            .iter()
+            .map(|&(lat, lon)| db.add_waypoint(&delivery_id, lat, lon).unwrap())
+            .collect();
+
+        let waypoints = db.get_waypoints(&delivery_id).unwrap();
+        assert_eq!(waypoints.iter().map(|w| w.id.clone()).collect::<Vec<_>>(), ids);
+        assert_eq!(waypoints.iter().map(|w| w.sequence).collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!((waypoints[2].latitude, waypoints[2].longitude), points[2]);
+
+        assert!(matches!(
+            db.add_waypoint("DEL-missing", 52.37, 4.89),
+            Err(DatabaseError::InvalidData(_))
+        ));
+        assert!(matches!(
+            db.add_waypoint(&delivery_id, 91.0, 4.89),
+            Err(DatabaseError::InvalidData(_))
+        ));
+
+        db.delete_delivery(&delivery_id, true).unwrap();
+        assert!(db.get_waypoints(&delivery_id).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_delivery_pages_cover_every_sort_order() {
+        let db = test_db("delivery_pages");
+
+        let sorts = [
+            DeliverySort::CreatedAt,
+            DeliverySort::CompletedAt,
+            DeliverySort::Status,
+            DeliverySort::Rating,
+            DeliverySort::Priority,
+        ];
+        for sort in sorts.into_iter().flat_map(|sort_by| {
+            [SortDirection::Asc, SortDirection::Desc].map(|sort_dir| (sort_by, sort_dir))
+        }) {
+            let expected: Vec<String> = db
+                .query_deliveries(delivery_filters(None, None, None, None), sort, None, None)
+                .unwrap()
+                .into_iter()
+                .map(|delivery| delivery.id)
+                .collect();
+
+            let mut paged = Vec::new();
+            let mut cursor = None;
+            loop {
+                let page = db
+                    .get_deliveries(
+                        None,
+                        None,
+                        None,
+                        None,
+                        Some(sort.0),
+                        Some(sort.1),
+                        cursor.as_deref(),
+                        7,
+                    )
+                    .unwrap();
+                assert_eq!(page.total_count, 50);
+                paged.extend(page.items.into_iter().map(|delivery| delivery.id));
+                cursor = page.next_cursor;
+                if cursor.is_none() {
+                    break;
+                }
+            }
+
+            assert_eq!(paged, expected, "{:?}", sort);
+        }
+    }
+
+    #[test]
+    fn test_deliveries_ordered_by_each_sort() {
+        let db = test_db("delivery_sorts");
+        db.conn
+            .execute_batch(
+                "UPDATE deliveries SET priority = 'urgent' WHERE rowid % 7 = 0;
+                 UPDATE deliveries SET priority = 'high' WHERE rowid % 7 = 3;",
+            )
+            .unwrap();
+
+        // Sort key of a delivery: (missing, number, text); missing keys go last
+        let key = |sort: DeliverySort, d: &Delivery| -> (bool, i64, String) {
+            match sort {
+                DeliverySort::CreatedAt => (false, d.created_at.timestamp_micros(), String::new()),
+                DeliverySort::CompletedAt => {
+                    d.completed_at.map_or((true, 0, String::new()), |at| {
+                        (false, at.timestamp_micros(), String::new())
+                    })
+                }
+                DeliverySort::Status => (false, 0, d.status.as_str().to_string()),
+                DeliverySort::Rating => d.rating.map_or((true, 0, String::new()), |r| {
+                    (false, r as i64, String::new())
+                }),
+                DeliverySort::Priority => {
+                    let rank = match d.priority {
+                        DeliveryPriority::Urgent => 0,
+                        DeliveryPriority::High => 1,
+                        DeliveryPriority::Normal => 2,
+                    };
+                    (false, rank, String::new())
+                }
+            }
+        };
+
+        for sort in [
+            DeliverySort::CreatedAt,
+            DeliverySort::CompletedAt,
+            DeliverySort::Status,
+            DeliverySort::Rating,
+            DeliverySort::Priority,
+        ] {
+            for dir in [SortDirection::Asc, SortDirection::Desc] {
+                let deliveries = db
+                    .get_deliveries(
+                        None,
+                        None,
+                        None,
+                        None,
+                        Some(sort),
+                        Some(dir),
+                        None,
+                        MAX_PAGE_SIZE,
+                    )
+                    .unwrap()
+                    .items;
+                assert_eq!(deliveries.len(), 50);
+
+                for pair in deliveries.windows(2) {
+                    let (a, b) = (key(sort, &pair[0]), key(sort, &pair[1]));
+                    let ordered = match (a.0, b.0) {
+                        (false, true) | (true, true) => true,
+                        (true, false) => false,
+                        (false, false) if dir == SortDirection::Asc => (a.1, &a.2) <= (b.1, &b.2),
+                        (false, false) => (a.1, &a.2) >= (b.1, &b.2),
+                    };
+                    assert!(
+                        ordered,
+                        "{:?} {:?}: {} before {}",
+                        sort, dir, pair[0].id, pair[1].id
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_deliveries_sorted_by_rating_desc_start_with_highest() {
+        let db = test_db("delivery_rating_sort");
+
+        let page = db
+            .get_deliveries(
+                None,
+                None,
+                None,
+                None,
+                Some(DeliverySort::Rating),
+                Some(SortDirection::Desc),
+                None,
+                MAX_PAGE_SIZE,
+            )
+            .unwrap();
+        let ratings: Vec<Option<u8>> = page.items.iter().map(|delivery| delivery.rating).collect();
+
+        let best = ratings.iter().flatten().max().copied();
+        assert!(best.is_some());
+        assert_eq!(ratings[0], best);
+        let rated = ratings.iter().take_while(|rating| rating.is_some()).count();
+        assert!(ratings[..rated].windows(2).all(|pair| pair[0] >= pair[1]));
+        assert!(ratings[rated..].iter().all(|rating| rating.is_none()));
+    }
+
+    #[test]
+    fn test_delivery_date_range_excludes_older_deliveries() {
+        let db = test_db("delivery_date_range");
+        let delivery = db
+            .create_delivery(&CreateDeliveryRequest {
+                bike_id: "BIKE-0001".to_string(),
+                customer_name: "P. de Vries".to_string(),
+                customer_address: "Damrak 1".to_string(),
+                restaurant_name: "Febo".to_string(),
+                restaurant_address: "Rokin 2".to_string(),
+            })
+            .unwrap();
+        let now = Utc::now();
+        db.conn
+            .execute(
+                "UPDATE deliveries SET created_at = ?1 WHERE id = ?2",
+                rusqlite::params![(now - chrono::Duration::days(10)).to_rfc3339(), delivery.id],
+            )
+            .unwrap();
+
+        let ids_since = |days: i64| -> Vec<String> {
+            db.get_deliveries(
+                Some("BIKE-0001"),
+                None,
+                Some(now - chrono::Duration::days(days)),
+                Some(now),
+                None,
+                None,
+                None,
+                MAX_PAGE_SIZE,
+            )
+            .unwrap()
+            .items
+            .into_iter()
+            .map(|delivery| delivery.id)
+            .collect()
+        };
+        assert!(!ids_since(7).contains(&delivery.id));
+        assert!(ids_since(11).contains(&delivery.id));
+
+        let yesterday = now - chrono::Duration::days(1);
+        let inverted =
+            db.get_deliveries(None, None, Some(now), Some(yesterday), None, None, None, 10);
+        assert!(matches!(inverted, Err(DatabaseError::InvalidData(_))));
+    }
+
+    #[test]
+    fn test_fleet_deliveries_match_the_per_bike_lists() {
+        let db = test_db("fleet_deliveries");
+        let retired = db
+            .get_all_bikes(None, None)
+            .unwrap()
+            .into_iter()
+            .find(|bike| !db.get_deliveries_by_bike(&bike.id).unwrap().is_empty())
+            .unwrap();
+        db.retire_bike(&retired.id).unwrap();
+
+        let mut per_bike: Vec<String> = Vec::new();
+        for bike in db.get_all_bikes(None, None).unwrap() {
+            per_bike.extend(db.get_deliveries_by_bike(&bike.id).unwrap().into_iter().map(|d| d.id));
+        }
+        let mut fleet: Vec<String> =
+            db.get_fleet_deliveries().unwrap().into_iter().map(|d| d.id).collect();
+        per_bike.sort();
+        fleet.sort();
+        assert!(!fleet.is_empty());
+        assert_eq!(fleet, per_bike);
+
+        let retired_deliveries = db.get_deliveries_by_bike(&retired.id).unwrap();
+        assert!(retired_deliveries.iter().all(|d| !fleet.contains(&d.id)));
+    }
+
+    #[test]
+    fn test_issue_pages_are_stable_under_inserts() {
+        let db = test_db("issue_pages");
+
+        let first = db.get_issues(None, None, None, None, None, None, None, None, 5).unwrap();
+        assert_eq!(first.items.len(), 5);
+        assert_eq!(first.total_count, 20);
+
+        // A newer issue sorts before the cursor and must not shift page 2
+        let now = Utc::now().to_rfc3339();
+        db.conn
+            .execute(
+                r#"INSERT INTO issues (id, bike_id, reporter_type, category, description,
+                                      resolved, created_at, updated_at)
+                   VALUES ('ISS-NEW', 'BIKE-0001', 'customer', 'late', 'New', 0, ?1, ?1)"#,
+                [&now],
+            )
+            .unwrap();
+
+        let second = db
+            .get_issues(
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                first.next_cursor.as_deref(),
+                5,
+            )
+            .unwrap();
+        let all = db
+            .query_issues(
+                issue_filters(None, None, None, None, None),
+                (IssueSort::CreatedAt, SortDirection::Desc),
+                None,
+                None,
+            )
+            .unwrap();
+        let expected: Vec<&str> = all[6..11].iter().map(|issue| issue.id.as_str()).collect();
+        let actual: Vec<&str> = second.items.iter().map(|issue| issue.id.as_str()).collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_invalid_page_requests_rejected() {
+        let db = test_db("invalid_pages");
+
+        assert!(db.get_issues(None, None, None, None, None, None, None, None, 0).is_err());
+        assert!(db.get_issues(None, None, None, None, None, None, None, None, MAX_PAGE_SIZE + 1).is_err());
+        assert!(db.get_issues(None, None, None, None, None, None, None, Some("not-a-cursor"), 10).is_err());
+    }
+
+    #[test]
+    fn test_resolve_and_reopen_issue() {
+        let db = test_db("resolve_reopen_issue");
+        let open = db
+            .get_issues(None, Some(false), None, None, None, None, None, None, 1)
+            .unwrap()
+            .items
+            .remove(0);
+
+        let resolved = db.resolve_issue(&open.id, Some("Replaced tire")).unwrap();
+        assert!(resolved.resolved);
+        assert!(resolved.resolved_at.is_some());
+        assert_eq!(resolved.resolution_note.as_deref(), Some("Replaced tire"));
+
+        // Resolving again keeps the original resolution
+        let again = db.resolve_issue(&open.id, Some("Other note")).unwrap();
+        assert_eq!(again.resolved_at, resolved.resolved_at);
+        assert_eq!(again.resolution_note.as_deref(), Some("Replaced tire"));
+
+        let reopened = db.reopen_issue(&open.id).unwrap();
+        assert!(!reopened.resolved);
+        assert_eq!(reopened.resolved_at, None);
+        assert_eq!(reopened.resolution_note, None);
+
+        assert!(matches!(
+            db.reopen_issue("ISS-9999"),
+            Err(DatabaseError::InvalidData(_))
+        ));
+    }
+
+    #[test]
+    fn test_batch_resolve_and_reopen_issues() {
+        let db = test_db("batch_resolve_issues");
+        let open: Vec<String> = db
+            .get_issues(None, Some(false), None, None, None, None, None, None, 3)
+            .unwrap()
+            .items
+            .into_iter()
+            .map(|issue| issue.id)
+            .collect();
+        let already_resolved = db
+            .get_issues(None, Some(true), None, None, None, None, None, None, 1)
+            .unwrap()
+            .items
+            .remove(0);
+        let mut batch: Vec<&str> = open.iter().map(String::as_str).collect();
+        batch.extend([already_resolved.id.as_str(), "ISS-9999"]);
+
+        // Unknown and already resolved IDs are skipped, not counted
+        assert_eq!(db.batch_resolve_issues(&batch).unwrap(), 3);
+        for id in &open {
+            let issue = db.get_issue_by_id(id).unwrap().unwrap();
+            assert!(issue.resolved);
+            assert!(issue.resolved_at.is_some());
+        }
+        let untouched = db.get_issue_by_id(&already_resolved.id).unwrap().unwrap();
+        assert_eq!(untouched.resolved_at, already_resolved.resolved_at);
+        assert_eq!(db.batch_resolve_issues(&batch).unwrap(), 0);
+
+        assert_eq!(db.batch_reopen_issues(&batch).unwrap(), 4);
+        for id in open.iter().chain([&already_resolved.id]) {
+            let issue = db.get_issue_by_id(id).unwrap().unwrap();
+            assert!(!issue.resolved);
+            assert_eq!(issue.resolved_at, None);
+        }
+        assert_eq!(db.batch_reopen_issues(&batch).unwrap(), 0);
+
+        assert!(matches!(db.batch_resolve_issues(&[]), Err(DatabaseError::InvalidData(_))));
+        let too_many = vec!["ISS-9999"; 101];
+        assert!(matches!(
+            db.batch_reopen_issues(&too_many),
+            Err(DatabaseError::InvalidData(_))
+        ));
+    }
+
+    #[test]
+    fn test_seeded_resolved_issues_have_resolved_at() {
+        let db = test_db("seeded_resolved_at");
+
+        let issues = db
+            .get_issues(None, None, None, None, None, None, None, None, MAX_PAGE_SIZE)
+            .unwrap()
+            .items;
+        assert!(issues.iter().any(|issue| issue.resolved));
+        for issue in issues {
+            assert_eq!(issue.resolved, issue.resolved_at.is_some(), "{}", issue.id);
+        }
+    }
+
+    #[test]
+    fn test_delete_issue() {
+        let db = test_db("delete_issue");
+        let (issue_id, _) = linked_issue(&db);
+
+        db.delete_issue(&issue_id).unwrap();
+
+        assert!(db.get_issue_by_id(&issue_id).unwrap().is_none());
+        assert!(matches!(
+            db.delete_issue(&issue_id),
+            Err(DatabaseError::InvalidData(_))
+        ));
+    }
+
+    /// Force graph of `bike_id` as `get_force_graph_layout` computes it
+    fn force_graph(db: &Database, bike_id: &str) -> ForceGraphData {
+        let bike = db.get_bike_by_id(bike_id).unwrap().unwrap();
+        let deliveries = db.get_deliveries_by_bike(bike_id).unwrap();
+        let issues = db.get_issues_by_bike(bike_id).unwrap();
+        let pins = db.get_node_pins(bike_id).unwrap();
+        get_force_graph_layout_internal(
+            &bike,
+            &deliveries,
+            &issues,
+            &pins,
+            ForceGraphClusterMode::None,
+        )
+        .unwrap()
+    }
+
+    fn issue_request(bike_id: &str, delivery_id: Option<&str>) -> CreateIssueRequest {
+        CreateIssueRequest {
+            bike_id: bike_id.to_string(),
+            delivery_id: delivery_id.map(str::to_string),
+            reporter_type: IssueReporterType::Customer,
+            category: None,
+            description: "  Flat tire during delivery ".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_issue_statistics_for_known_issues() {
+        let db = test_db("issue_statistics");
+        let bike = db.add_bike("Statistiek", 52.37, 4.89, Some(80)).unwrap();
+
+        let reported = [
+            (IssueCategory::Late, IssueReporterType::Customer),
+            (IssueCategory::Late, IssueReporterType::Customer),
+            (IssueCategory::Damaged, IssueReporterType::Restaurant),
+            (IssueCategory::BikeProblem, IssueReporterType::Deliverer),
+        ];
+        let mut ids = Vec::new();
+        for (category, reporter_type) in reported {
+            let mut request = issue_request(&bike.id, None);
+            request.category = Some(category);
+            request.reporter_type = reporter_type;
+            ids.push(db.create_issue(&request).unwrap().id);
+        }
+        for id in &ids[..3] {
+            db.resolve_issue(id, None).unwrap();
+        }
+
+        let stats = db.get_issue_statistics(Some(&bike.id)).unwrap();
+        assert_eq!(stats.total_issues, 4);
+        assert_eq!((stats.resolved_count, stats.unresolved_count), (3, 1));
+        assert!((stats.resolution_rate_pct - 75.0).abs() < 1e-9);
+        assert_eq!(
+            stats.by_category,
+            HashMap::from([
+                ("late".to_string(), 2),
+                ("damaged".to_string(), 1),
+                ("bike_problem".to_string(), 1),
+            ])
+        );
+        assert_eq!(stats.by_reporter.get("customer"), Some(&2));
+        assert_eq!(stats.by_reporter.values().sum::<u32>(), 4);
+        // Everything was reported and resolved just now
+        assert!(stats.avg_resolution_days >= 0.0 && stats.avg_resolution_days < 0.01);
+        assert!(stats.oldest_unresolved_days.unwrap() < 0.01);
+
+        let fleet = db.get_issue_statistics(None).unwrap();
+        assert!(fleet.total_issues > stats.total_issues);
+        assert_eq!(fleet.by_category.values().sum::<u32>(), fleet.total_issues);
+
+        let nobody = db.get_issue_statistics(Some("BIKE-9999")).unwrap();
+        assert_eq!((nobody.total_issues, nobody.resolution_rate_pct), (0, 0.0));
+        assert_eq!(nobody.oldest_unresolved_days, None);
+    }
+
+    #[test]
+    fn test_create_standalone_issue() {
+        let db = test_db("create_standalone_issue");
+
+        let issue = db.create_issue(&issue_request("BIKE-0001", None)).unwrap();
+        assert_eq!(issue.description, "Flat tire during delivery");
+        assert_eq!(issue.category, IssueCategory::BikeProblem);
+
+        let graph = force_graph(&db, "BIKE-0001");
+        assert!(graph.nodes.iter().any(|node| node.id == issue.id));
+        assert!(graph
+            .links
+            .iter()
+            .any(|link| link.source == "BIKE-0001" && link.target == issue.id));
+    }
+
+    #[test]
+    fn test_create_delivery_linked_issue() {
+        let db = test_db("create_linked_issue");
+        let delivery = db.get_deliveries_by_bike("BIKE-0001").unwrap().remove(0);
+
+        let mut request = issue_request("BIKE-0001", Some(&delivery.id));
+        request.category = Some(IssueCategory::Late);
+        let issue = db.create_issue(&request).unwrap();
+        assert_eq!(issue.category, IssueCategory::Late);
+
+        let graph = force_graph(&db, "BIKE-0001");
+        assert!(graph
+            .links
+            .iter()
+            .any(|link| link.source == delivery.id && link.target == issue.id));
+    }
+
+    #[test]
+    fn test_create_issue_rejects_invalid_requests() {
+        let db = test_db("create_issue_invalid");
+        let other_bike_delivery = db.get_deliveries_by_bike("BIKE-0002").unwrap().remove(0);
+
+        let mut blank = issue_request("BIKE-0001", None);
+        blank.description = "   ".to_string();
+        for request in [
+            blank,
+            issue_request("BIKE-9999", None),
+            issue_request("BIKE-0001", Some("DEL-9999")),
+            issue_request("BIKE-0001", Some(&other_bike_delivery.id)),
+        ] {
+            assert!(matches!(
+                db.create_issue(&request),
+                Err(DatabaseError::InvalidData(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_node_pins_round_trip_and_fix_layout() {
+        let db = test_db("node_pins");
+        let delivery = db.get_deliveries_by_bike("BIKE-0001").unwrap().remove(0);
+
+        db.save_node_pin("BIKE-0001", &delivery.id, 10.0, 20.0).unwrap();
+        db.save_node_pin("BIKE-0001", &delivery.id, 150.0, -75.0).unwrap();
+        db.save_node_pin("BIKE-0002", "DEL-9999", 1.0, 1.0).unwrap();
+
+        assert_eq!(
+            db.get_node_pins("BIKE-0001").unwrap(),
+            vec![(delivery.id.clone(), 150.0, -75.0)]
+        );
+        let graph = force_graph(&db, "BIKE-0001");
+        let node = graph.nodes.iter().find(|n| n.id == delivery.id).unwrap();
+        assert_eq!((node.x, node.y), (150.0, -75.0));
+
+        db.clear_node_pins("BIKE-0001").unwrap();
+        assert!(db.get_node_pins("BIKE-0001").unwrap().is_empty());
+        assert_eq!(db.get_node_pins("BIKE-0002").unwrap().len(), 1);
+
+        db.retire_bike("BIKE-0002").unwrap();
+        assert!(db.get_node_pins("BIKE-0002").unwrap().is_empty());
+    }
 }
@@ -16,24 +16,49 @@
 //
 // The host should point to HAProxy VIP for automatic failover.
 
+use crate::analytics;
+use crate::categorization::category_or_suggested;
+use crate::geo;
+use crate::migrations::MigrationRunner;
+pub use crate::pg_config::{DatabaseConfig, SslMode};
+use crate::simulation;
 use crate::models::{
-    Bike, BikeStatus, DatabaseStats, Delivery, DeliveryStatus, Issue, IssueCategory,
-    IssueReporterType,
+    AddBikeRequest, AuditLogEntry, AuditOp, Bike, BikeCorrelationPoint, BikeStatus,
+    BikeStatusEvent, BikesByStatusResult, CreateDeliveryRequest, CreateIssueRequest,
+    DatabaseStats, Delivery, DeliveryPage, DeliveryPriority, DeliveryStatus, FleetEvent,
+    FleetEventType, FleetTimeSeries,
+    HeatmapCell, HourlyUtilization, Issue, IssueCategory, IssueCategoryStats, IssuePage,
+    IndexScanStats, IssueRatingCorrelation, IssueReporterType, IssueStatistics,
+    Page, PageCursor, PoolMetrics, RestaurantStats, SearchResults, SeedConfig, SimulationScenario,
+    BikeSort, DeliverySort, IssueSort, SortDirection, TableScanStats, TableStats, TimeBucket,
+    Waypoint, MAX_PAGE_SIZE,
 };
 use chrono::{DateTime, Utc};
-use deadpool_postgres::{Config, ManagerConfig, Pool, RecyclingMethod, Runtime};
+use deadpool_postgres::{
+    Config, GenericClient, Hook, ManagerConfig, Pool, RecyclingMethod, Runtime, Status,
+};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 use thiserror::Error;
 use tokio_postgres::types::ToSql;
 use tokio_postgres::NoTls;
 
 #[derive(Error, Debug)]
 pub enum DatabaseError {
-    #[error("PostgreSQL error: {0}")]
-    Postgres(#[from] tokio_postgres::Error),
-
-    #[error("Pool error: {0}")]
-    Pool(#[from] deadpool_postgres::PoolError),
+    #[error("QueryFailed while {operation}: {source}")]
+    QueryFailed {
+        operation: String,
+        source: tokio_postgres::Error,
+    },
+
+    #[error("PoolUnavailable while {operation}: {source}")]
+    PoolUnavailable {
+        operation: String,
+        source: deadpool_postgres::PoolError,
+    },
 
     #[error("Database not initialized")]
     NotInitialized,
@@ -43,6 +68,12 @@ pub enum DatabaseError {
 
     #[error("Configuration error: {0}")]
     Config(String),
+
+    #[error("TLS error: {0}")]
+    TlsError(String),
+
+    #[error("Migration {0} failed: {1}")]
+    MigrationFailed(u32, String),
 }
 
 impl serde::Serialize for DatabaseError {
@@ -54,27 +85,28 @@ impl serde::Serialize for DatabaseError {
     }
 }
 
-/// Database configuration for PostgreSQL
-#[derive(Debug, Clone)]
-pub struct DatabaseConfig {
-    pub host: String,
-    pub port: u16,
-    pub user: String,
-    pub password: String,
-    pub dbname: String,
-    pub pool_size: usize,
+/// Attach the failing operation to PostgreSQL and pool errors
+///
+/// Same idea as the SQLite backend: the frontend sees which query broke.
+pub(crate) trait QueryContext<T> {
+    fn context(self, operation: &str) -> Result<T, DatabaseError>;
 }
 
-impl Default for DatabaseConfig {
-    fn default() -> Self {
-        Self {
-            host: "localhost".to_string(),
-            port: 5432,
-            user: "fleet_app".to_string(),
-            password: String::new(),
-            dbname: "bike_fleet".to_string(),
-            pool_size: 16,
-        }
+impl<T> QueryContext<T> for Result<T, tokio_postgres::Error> {
+    fn context(self, operation: &str) -> Result<T, DatabaseError> {
+        self.map_err(|source| DatabaseError::QueryFailed {
+            operation: operation.to_string(),
+            source,
+        })
+    }
+}
+
+impl<T> QueryContext<T> for Result<T, deadpool_postgres::PoolError> {
+    fn context(self, operation: &str) -> Result<T, DatabaseError> {
+        self.map_err(|source| DatabaseError::PoolUnavailable {
+            operation: operation.to_string(),
+            source,
+        })
     }
 }
 
@@ -88,6 +120,12 @@ impl DatabaseConfig {
     /// - PG_PASSWORD (required)
     /// - PG_DATABASE (default: bike_fleet)
     /// - PG_POOL_SIZE (default: 16)
+    /// - PG_WARMUP_TIMEOUT_SECONDS (default: 30)
+    /// - PG_SSL_MODE: disable | require | verify-ca | verify-full (default: disable)
+    /// - PG_TLS: `true` sets `tls`, turning PG_SSL_MODE=disable into require
+    /// - PG_SSL_CA_CERT: CA certificate PEM path (required for verify-ca/verify-full)
+    ///
+    /// Any mode other than `disable` needs the `postgres-tls` feature.
     pub fn from_env() -> Result<Self, DatabaseError> {
         Ok(Self {
             host: std::env::var("PG_HOST").unwrap_or_else(|_| "localhost".to_string()),
@@ -103,10 +141,138 @@ impl DatabaseConfig {
                 .unwrap_or_else(|_| "16".to_string())
                 .parse()
                 .unwrap_or(16),
+            warmup_timeout_seconds: std::env::var("PG_WARMUP_TIMEOUT_SECONDS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .unwrap_or(30),
+            tls: std::env::var("PG_TLS").is_ok_and(|v| v.eq_ignore_ascii_case("true")),
+            ssl_mode: match std::env::var("PG_SSL_MODE") {
+                Ok(mode) => SslMode::from_str(&mode).ok_or_else(|| {
+                    DatabaseError::Config(format!("Unknown PG_SSL_MODE: {}", mode))
+                })?,
+                Err(_) => SslMode::Disable,
+            },
+            ssl_ca_cert_path: std::env::var_os("PG_SSL_CA_CERT").map(PathBuf::from),
+            seed_config: SeedConfig::amsterdam(),
         })
     }
 }
 
+/// Build the rustls connector for a TLS `ssl_mode`
+///
+/// # Why skip the host name outside `VerifyFull`?
+/// Patroni members are usually reached through HAProxy or by IP, so their
+/// certificates rarely name the address we dial. `Require`/`VerifyCa` still
+/// reject certificates that don't chain to a trusted CA.
+#[cfg(feature = "postgres-tls")]
+fn tls_connector(
+    ssl_mode: SslMode,
+    ca_cert_path: Option<&std::path::Path>,
+) -> Result<tokio_postgres_rustls::MakeRustlsConnect, DatabaseError> {
+    let mut roots = rustls::RootCertStore::empty();
+
+    match ca_cert_path {
+        Some(path) => {
+            let pem = std::fs::read(path).map_err(|e| {
+                DatabaseError::TlsError(format!("Failed to read CA certificate {}: {}", path.display(), e))
+            })?;
+            for cert in rustls_pemfile::certs(&mut pem.as_slice()) {
+                let cert = cert.map_err(|e| DatabaseError::TlsError(format!("Invalid CA certificate: {}", e)))?;
+                roots
+                    .add(cert)
+                    .map_err(|e| DatabaseError::TlsError(format!("Invalid CA certificate: {}", e)))?;
+            }
+            if roots.is_empty() {
+                return Err(DatabaseError::TlsError(format!(
+                    "No certificates found in {}",
+                    path.display()
+                )));
+            }
+        }
+        None if ssl_mode == SslMode::Require => {
+            roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+        }
+        None => {
+            return Err(DatabaseError::Config(
+                "PG_SSL_CA_CERT is required for verify-ca and verify-full".to_string(),
+            ))
+        }
+    }
+
+    // Explicit provider: the process-wide default is ambiguous if another
+    // dependency enables a second rustls backend
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let builder = rustls::ClientConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .map_err(|e| DatabaseError::TlsError(e.to_string()))?;
+
+    let tls_config = if ssl_mode == SslMode::VerifyFull {
+        builder.with_root_certificates(roots).with_no_client_auth()
+    } else {
+        let verifier =
+            rustls::client::WebPkiServerVerifier::builder_with_provider(Arc::new(roots), provider)
+                .build()
+                .map_err(|e| DatabaseError::TlsError(e.to_string()))?;
+        builder
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(ChainOnlyVerifier(verifier)))
+            .with_no_client_auth()
+    };
+
+    Ok(tokio_postgres_rustls::MakeRustlsConnect::new(tls_config))
+}
+
+/// Verifies the certificate chain but accepts any server name
+#[cfg(feature = "postgres-tls")]
+#[derive(Debug)]
+struct ChainOnlyVerifier(Arc<rustls::client::WebPkiServerVerifier>);
+
+#[cfg(feature = "postgres-tls")]
+impl rustls::client::danger::ServerCertVerifier for ChainOnlyVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &rustls::pki_types::CertificateDer<'_>,
+        intermediates: &[rustls::pki_types::CertificateDer<'_>],
+        server_name: &rustls::pki_types::ServerName<'_>,
+        ocsp_response: &[u8],
+        now: rustls::pki_types::UnixTime,
+    ) -> Result<rustls::client::danger::ServerCertVerified, rustls::Error> {
+        use rustls::CertificateError;
+
+        match self
+            .0
+            .verify_server_cert(end_entity, intermediates, server_name, ocsp_response, now)
+        {
+            Err(rustls::Error::InvalidCertificate(
+                CertificateError::NotValidForName | CertificateError::NotValidForNameContext { .. },
+            )) => Ok(rustls::client::danger::ServerCertVerified::assertion()),
+            other => other,
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &rustls::pki_types::CertificateDer<'_>,
+        dss: &rustls::DigitallySignedStruct,
+    ) -> Result<rustls::client::danger::HandshakeSignatureValid, rustls::Error> {
+        self.0.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &rustls::pki_types::CertificateDer<'_>,
+        dss: &rustls::DigitallySignedStruct,
+    ) -> Result<rustls::client::danger::HandshakeSignatureValid, rustls::Error> {
+        self.0.verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<rustls::SignatureScheme> {
+        self.0.supported_verify_schemes()
+    }
+}
+
 /// PostgreSQL database wrapper with connection pooling
 ///
 /// # Why connection pooling?
@@ -116,6 +282,18 @@ impl DatabaseConfig {
 /// - Works transparently with HAProxy failover
 pub struct Database {
     pool: Pool,
+    counters: Arc<PoolCounters>,
+}
+
+/// Connections handed out by the pool since startup
+///
+/// deadpool only reports the current pool state, so the totals are counted
+/// by hooks: every checkout either creates a connection or recycles an
+/// idle one.
+#[derive(Debug, Default)]
+struct PoolCounters {
+    acquired: AtomicU64,
+    recycled: AtomicU64,
 }
 
 impl Database {
@@ -127,6 +305,7 @@ impl Database {
     /// # Returns
     /// A new Database instance with an active connection pool
     pub async fn new(config: DatabaseConfig) -> Result<Self, DatabaseError> {
+        let ssl_mode = config.effective_ssl_mode();
         let mut cfg = Config::new();
         cfg.host = Some(config.host);
         cfg.port = Some(config.port);
@@ -137,158 +316,113 @@ impl Database {
             recycling_method: RecyclingMethod::Fast,
         });
 
-        let pool = cfg
-            .builder(NoTls)?
+        let builder = match ssl_mode {
+            SslMode::Disable => cfg.builder(NoTls),
+            #[cfg(feature = "postgres-tls")]
+            mode => cfg.builder(tls_connector(mode, config.ssl_ca_cert_path.as_deref())?),
+            #[cfg(not(feature = "postgres-tls"))]
+            mode => {
+                return Err(DatabaseError::TlsError(format!(
+                    "SSL mode {:?} requires a build with the postgres-tls feature",
+                    mode
+                )))
+            }
+        };
+
+        let counters = Arc::new(PoolCounters::default());
+        let (on_create, on_recycle) = (counters.clone(), counters.clone());
+        let pool = builder
+            .map_err(|e| DatabaseError::Config(e.to_string()))?
+            .post_create(Hook::sync_fn(move |_, _| {
+                on_create.acquired.fetch_add(1, Ordering::Relaxed);
+                Ok(())
+            }))
+            .post_recycle(Hook::sync_fn(move |_, _| {
+                on_recycle.acquired.fetch_add(1, Ordering::Relaxed);
+                on_recycle.recycled.fetch_add(1, Ordering::Relaxed);
+                Ok(())
+            }))
             .max_size(config.pool_size)
             .runtime(Runtime::Tokio1)
             .build()
             .map_err(|e| DatabaseError::Config(e.to_string()))?;
 
-        let db = Database { pool };
+        let db = Database { pool, counters };
 
         // Initialize schema
         db.initialize_schema().await?;
 
+        // Seed demo data if empty (Amsterdam unless configured otherwise)
+        db.seed_custom_data(config.seed_config).await?;
+
+        // Pre-open half the pool. A failed or timed out warmup is ignored:
+        // it only costs first-request latency, connections still open lazily
+        let warmup_timeout = Duration::from_secs(config.warmup_timeout_seconds);
+        let _ = tokio::time::timeout(warmup_timeout, db.warmup(config.pool_size / 2)).await;
+
         Ok(db)
     }
 
-    /// Initialize the database schema
+    /// Pre-establish pool connections to avoid cold-start latency
     ///
-    /// # Why idempotent schema creation?
-    /// - Safe to run on every startup
-    /// - Uses IF NOT EXISTS for all objects
-    /// - Allows rolling deployments without manual migrations
-    async fn initialize_schema(&self) -> Result<(), DatabaseError> {
-        let client = self.pool.get().await?;
-
-        client
-            .batch_execute(
-                r#"
-            -- Enable UUID extension for better primary keys
-            CREATE EXTENSION IF NOT EXISTS "uuid-ossp";
-
-            -- Bikes table
-            CREATE TABLE IF NOT EXISTS bikes (
-                id TEXT PRIMARY KEY,
-                name TEXT NOT NULL,
-                status TEXT NOT NULL DEFAULT 'available',
-                latitude DOUBLE PRECISION NOT NULL,
-                longitude DOUBLE PRECISION NOT NULL,
-                battery_level INTEGER,
-                last_maintenance TIMESTAMPTZ,
-                total_trips INTEGER NOT NULL DEFAULT 0,
-                total_distance_km DOUBLE PRECISION NOT NULL DEFAULT 0.0,
-                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
-                updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
-            );
-
-            -- Trips table
-            CREATE TABLE IF NOT EXISTS trips (
-                id TEXT PRIMARY KEY,
-                bike_id TEXT NOT NULL REFERENCES bikes(id),
-                start_time TIMESTAMPTZ NOT NULL,
-                end_time TIMESTAMPTZ,
-                start_latitude DOUBLE PRECISION NOT NULL,
-                start_longitude DOUBLE PRECISION NOT NULL,
-                end_latitude DOUBLE PRECISION,
-                end_longitude DOUBLE PRECISION,
-                distance_km DOUBLE PRECISION
-            );
-
-            -- Deliveries table
-            CREATE TABLE IF NOT EXISTS deliveries (
-                id TEXT PRIMARY KEY,
-                bike_id TEXT NOT NULL REFERENCES bikes(id),
-                status TEXT NOT NULL DEFAULT 'upcoming',
-                customer_name TEXT NOT NULL,
-                customer_address TEXT NOT NULL,
-                restaurant_name TEXT NOT NULL,
-                restaurant_address TEXT NOT NULL,
-                rating INTEGER CHECK (rating >= 1 AND rating <= 5),
-                complaint TEXT,
-                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
-                completed_at TIMESTAMPTZ
-            );
+    /// # Why concurrently?
+    /// deadpool only opens a new connection when none is idle. Holding all
+    /// `min_connections` at once forces that many to exist; dropping them
+    /// returns them to the pool as idle connections.
+    pub async fn warmup(&self, min_connections: usize) -> Result<(), DatabaseError> {
+        // Asking for more than the pool holds would wait forever
+        let count = min_connections.min(self.pool.status().max_size);
+
+        let checks = (0..count).map(|_| async {
+            let client = self.pool.get().await.context("warmup")?;
+            client.execute("SELECT 1", &[]).await.context("warmup")?;
+            Ok::<_, DatabaseError>(client)
+        });
 
-            -- Issues table
-            CREATE TABLE IF NOT EXISTS issues (
-                id TEXT PRIMARY KEY,
-                delivery_id TEXT REFERENCES deliveries(id),
-                bike_id TEXT NOT NULL REFERENCES bikes(id),
-                reporter_type TEXT NOT NULL,
-                category TEXT NOT NULL,
-                description TEXT NOT NULL,
-                resolved BOOLEAN NOT NULL DEFAULT FALSE,
-                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
-            );
+        // Keep every client until all are established, then release together
+        let clients = futures::future::join_all(checks).await;
+        clients.into_iter().collect::<Result<Vec<_>, _>>()?;
 
-            -- Indexes for performance
-            CREATE INDEX IF NOT EXISTS idx_bikes_status ON bikes(status);
-            CREATE INDEX IF NOT EXISTS idx_trips_bike_id ON trips(bike_id);
-            CREATE INDEX IF NOT EXISTS idx_deliveries_bike_id ON deliveries(bike_id);
-            CREATE INDEX IF NOT EXISTS idx_deliveries_status ON deliveries(status);
-            CREATE INDEX IF NOT EXISTS idx_issues_bike_id ON issues(bike_id);
-            CREATE INDEX IF NOT EXISTS idx_issues_delivery_id ON issues(delivery_id);
-            CREATE INDEX IF NOT EXISTS idx_issues_resolved ON issues(resolved);
-
-            -- Function to update updated_at timestamp
-            CREATE OR REPLACE FUNCTION update_updated_at_column()
-            RETURNS TRIGGER AS $$
-            BEGIN
-                NEW.updated_at = NOW();
-                RETURN NEW;
-            END;
-            $$ language 'plpgsql';
-
-            -- Trigger for bikes table
-            DROP TRIGGER IF EXISTS update_bikes_updated_at ON bikes;
-            CREATE TRIGGER update_bikes_updated_at
-                BEFORE UPDATE ON bikes
-                FOR EACH ROW
-                EXECUTE FUNCTION update_updated_at_column();
-            "#,
-            )
-            .await?;
+        Ok(())
+    }
 
-        // Seed mock data if empty
-        self.seed_mock_data().await?;
+    /// Initialize the database schema by applying pending migrations
+    ///
+    /// # Why versioned migrations?
+    /// - Safe to run on every startup: applied versions are skipped
+    /// - Can alter existing tables, which IF NOT EXISTS can't
+    /// - Instances starting together serialize on an advisory lock
+    async fn initialize_schema(&self) -> Result<(), DatabaseError> {
+        let mut client = self.pool.get().await.context("initialize_schema")?;
+        MigrationRunner::default().run(&mut client).await?;
 
         Ok(())
     }
 
-    /// Seed the database with mock Amsterdam bike data
-    async fn seed_mock_data(&self) -> Result<(), DatabaseError> {
-        let client = self.pool.get().await?;
+    /// Seed an empty database from a locale-specific config
+    ///
+    /// Does nothing if bikes already exist.
+    pub async fn seed_custom_data(&self, config: SeedConfig) -> Result<(), DatabaseError> {
+        config.validate().map_err(DatabaseError::InvalidData)?;
+
+        let client = self.pool.get().await.context("seed_custom_data")?;
 
         // Check if we already have data
         let row = client
             .query_one("SELECT COUNT(*)::INTEGER as count FROM bikes", &[])
-            .await?;
+            .await
+            .context("seed_custom_data")?;
         let count: i32 = row.get("count");
 
         if count > 0 {
             return Ok(());
         }
 
-        // Amsterdam coordinates for various locations
-        let amsterdam_locations = vec![
-            ("Central Station", 52.3791, 4.9003),
-            ("Dam Square", 52.3731, 4.8932),
-            ("Vondelpark", 52.3579, 4.8686),
-            ("Rijksmuseum", 52.3600, 4.8852),
-            ("Anne Frank House", 52.3752, 4.8840),
-            ("Jordaan", 52.3747, 4.8797),
-            ("De Pijp", 52.3533, 4.8936),
-            ("Oost", 52.3614, 4.9366),
-            ("Noord", 52.3907, 4.9228),
-            ("Amstel", 52.3632, 4.9039),
-        ];
-
         let statuses = ["available", "available", "available", "in_use", "charging"];
 
-        for (i, (name, lat, lon)) in amsterdam_locations.iter().enumerate() {
+        for (i, (name, lat, lon)) in config.locations.iter().enumerate() {
             let id = format!("BIKE-{:04}", i + 1);
-            let bike_name = format!("Amsterdam {} Bike", name);
+            let bike_name = format!("{} Bike", name);
             let status = statuses[i % statuses.len()];
             let battery = (20 + (i * 8) % 80) as i32;
 
@@ -307,40 +441,31 @@ impl Database {
                         &((i as f64 * 12.5) % 500.0),
                     ],
                 )
-                .await?;
+                .await.context("seed_custom_data")?;
         }
 
         // Seed deliveries and issues
-        self.seed_deliveries_and_issues().await?;
+        self.seed_deliveries_and_issues(&config).await?;
 
         Ok(())
     }
 
     /// Seed deliveries and issues for demonstration
-    async fn seed_deliveries_and_issues(&self) -> Result<(), DatabaseError> {
-        let client = self.pool.get().await?;
+    async fn seed_deliveries_and_issues(&self, config: &SeedConfig) -> Result<(), DatabaseError> {
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("seed_deliveries_and_issues")?;
         let now = Utc::now();
-
-        let customer_names = [
-            "P. de Vries", "M. Jansen", "A. Bakker", "J. van Dijk", "S. Visser",
-            "L. Smit", "K. Mulder", "R. de Boer", "T. Bos", "E. van den Berg",
-            "H. Dekker", "F. Vermeer", "B. van Leeuwen", "N. Kok", "D. Peters",
-        ];
-
-        let restaurant_names = [
-            "De Pizzabakker", "Wok to Walk", "Febo", "New York Pizza", "Dominos",
-            "Thai Express", "Sushi Time", "Burger King", "McDonalds", "Subway",
-            "La Place", "Vapiano", "Bagels & Beans", "De Italiaan", "Ramen Ya",
-        ];
-
-        let streets = [
-            "Damrak", "Rokin", "Kalverstraat", "Leidsestraat", "Utrechtsestraat",
-            "Overtoom", "Kinkerstraat", "Ferdinand Bolstraat", "Javastraat", "Plantage",
-        ];
+        let bike_count = config.locations.len();
+        let customer_names = &config.customer_names;
+        let restaurant_names = &config.restaurant_names;
+        let streets = &config.streets;
 
         // Create 50 deliveries
         for i in 0..50 {
-            let bike_id = format!("BIKE-{:04}", (i % 10) + 1);
+            let bike_id = format!("BIKE-{:04}", (i % bike_count) + 1);
             let delivery_id = format!("DEL-{:04}", i + 1);
 
             let status = match i % 10 {
@@ -349,6 +474,12 @@ impl Database {
                 _ => "upcoming",
             };
 
+            let priority = match i % 12 {
+                0 => "urgent",
+                1 | 7 => "high",
+                _ => "normal",
+            };
+
             let rating: Option<i32> = if status == "completed" && i % 3 == 0 {
                 Some(((i % 5) + 1) as i32)
             } else {
@@ -372,8 +503,9 @@ impl Database {
             client
                 .execute(
                     r#"INSERT INTO deliveries (id, bike_id, status, customer_name, customer_address,
-                       restaurant_name, restaurant_address, rating, complaint, created_at, completed_at)
-                       VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)"#,
+                       restaurant_name, restaurant_address, rating, complaint, created_at, completed_at,
+                       priority)
+                       VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)"#,
                     &[
                         &delivery_id,
                         &bike_id,
@@ -386,9 +518,10 @@ impl Database {
                         &complaint,
                         &created_at,
                         &completed_at,
+                        &priority,
                     ],
                 )
-                .await?;
+                .await.context("seed_deliveries_and_issues")?;
         }
 
         let issue_descriptions: [(&str, &str); 6] = [
@@ -405,7 +538,7 @@ impl Database {
         // Create 20 issues
         for i in 0..20 {
             let issue_id = format!("ISS-{:04}", i + 1);
-            let bike_id = format!("BIKE-{:04}", (i % 10) + 1);
+            let bike_id = format!("BIKE-{:04}", (i % bike_count) + 1);
 
             let delivery_id: Option<String> = if i % 3 != 0 {
                 Some(format!("DEL-{:04}", (i % 50) + 1))
@@ -420,11 +553,18 @@ impl Database {
             let days_ago = (i as i64) % 14;
             let created_at = now - chrono::Duration::days(days_ago);
 
+            // Resolved issues took 4-20 hours to resolve
+            let updated_at = if resolved {
+                (created_at + chrono::Duration::hours(4 + (i as i64 % 5) * 4)).min(now)
+            } else {
+                created_at
+            };
+
             client
                 .execute(
                     r#"INSERT INTO issues (id, delivery_id, bike_id, reporter_type, category,
-                       description, resolved, created_at)
-                       VALUES ($1, $2, $3, $4, $5, $6, $7, $8)"#,
+                       description, resolved, created_at, updated_at, resolved_at)
+                       VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)"#,
                     &[
                         &issue_id,
                         &delivery_id,
@@ -434,9 +574,12 @@ impl Database {
                         &description,
                         &resolved,
                         &created_at,
+                        &updated_at,
+                        &resolved.then_some(updated_at),
                     ],
                 )
-                .await?;
+                .await
+                .context("seed_deliveries_and_issues")?;
         }
 
         Ok(())
@@ -447,38 +590,164 @@ impl Database {
     // ========================================================================
 
     /// Get all bikes from the database
-    pub async fn get_all_bikes(&self) -> Result<Vec<Bike>, DatabaseError> {
-        let client = self.pool.get().await?;
+    pub async fn get_all_bikes(
+        &self,
+        sort_by: Option<BikeSort>,
+        sort_dir: Option<SortDirection>,
+    ) -> Result<Vec<Bike>, DatabaseError> {
+        let client = self.pool.get().await.context("get_all_bikes")?;
+        let sort_by = sort_by.unwrap_or_default();
+        let dir = sort_dir.unwrap_or_else(|| sort_by.default_direction()).as_sql();
 
         let rows = client
             .query(
-                r#"SELECT id, name, status, latitude, longitude, battery_level,
-                          last_maintenance, total_trips, total_distance_km, created_at, updated_at
-                   FROM bikes ORDER BY name"#,
+                &format!(
+                    r#"SELECT id, name, status, latitude, longitude, battery_level,
+                          last_maintenance, total_trips, total_distance_km, created_at, updated_at,
+                          reservation_expires_at, maintenance_threshold_override, deleted_at,
+                          next_maintenance_at
+                   FROM bikes WHERE deleted_at IS NULL ORDER BY {} {dir}, id {dir}"#,
+                    bike_sort_column(sort_by)
+                ),
                 &[],
             )
-            .await?;
+            .await
+            .context("get_all_bikes")?;
 
         let bikes = rows.iter().map(|row| self.map_bike_row(row)).collect();
         Ok(bikes)
     }
 
+    /// Get bikes in the fleet that are not offline, by name
+    ///
+    /// The `status != 'offline'` condition matches the partial index
+    /// `idx_bikes_active`.
+    pub async fn get_active_bikes(&self) -> Result<Vec<Bike>, DatabaseError> {
+        let client = self.pool.get().await.context("get_active_bikes")?;
+
+        let rows = client
+            .query(
+                r#"SELECT id, name, status, latitude, longitude, battery_level,
+                          last_maintenance, total_trips, total_distance_km, created_at, updated_at,
+                          reservation_expires_at, maintenance_threshold_override, deleted_at,
+                          next_maintenance_at
+                   FROM bikes WHERE deleted_at IS NULL AND status != 'offline'
+                   ORDER BY name"#,
+                &[],
+            )
+            .await
+            .context("get_active_bikes")?;
+
+        Ok(rows.iter().map(|row| self.map_bike_row(row)).collect())
+    }
+
     /// Get a bike by ID
     pub async fn get_bike_by_id(&self, bike_id: &str) -> Result<Option<Bike>, DatabaseError> {
-        let client = self.pool.get().await?;
+        let client = self.pool.get().await.context("get_bike_by_id")?;
+        self.fetch_bike(&client, bike_id).await
+    }
 
+    /// `get_bike_by_id` on `client`, e.g. inside a transaction
+    async fn fetch_bike(
+        &self,
+        client: &impl GenericClient,
+        bike_id: &str,
+    ) -> Result<Option<Bike>, DatabaseError> {
         let row = client
             .query_opt(
                 r#"SELECT id, name, status, latitude, longitude, battery_level,
-                          last_maintenance, total_trips, total_distance_km, created_at, updated_at
-                   FROM bikes WHERE id = $1"#,
+                          last_maintenance, total_trips, total_distance_km, created_at, updated_at,
+                          reservation_expires_at, maintenance_threshold_override, deleted_at,
+                          next_maintenance_at
+                   FROM bikes WHERE id = $1 AND deleted_at IS NULL"#,
                 &[&bike_id],
             )
-            .await?;
+            .await
+            .context("get_bike_by_id")?;
 
         Ok(row.map(|r| self.map_bike_row(&r)))
     }
 
+    /// Get all bikes with a given status, plus their count
+    ///
+    /// # Why a CTE?
+    /// - Count and rows come back in a single round-trip to the pool
+    /// - An empty result still carries the (zero) count via the LEFT JOIN
+    pub async fn get_bikes_by_status(
+        &self,
+        status: &BikeStatus,
+    ) -> Result<BikesByStatusResult, DatabaseError> {
+        let client = self.pool.get().await.context("get_bikes_by_status")?;
+
+        let rows = client
+            .query(
+                r#"WITH counts AS (
+                       SELECT COUNT(*) AS total FROM bikes
+                       WHERE status = $1 AND deleted_at IS NULL
+                   )
+                   SELECT counts.total, b.id, b.name, b.status, b.latitude, b.longitude,
+                          b.battery_level, b.last_maintenance, b.total_trips,
+                          b.total_distance_km, b.created_at, b.updated_at,
+                          b.reservation_expires_at, b.maintenance_threshold_override, b.deleted_at,
+                          b.next_maintenance_at
+                   FROM counts
+                   LEFT JOIN bikes b ON b.status = $1 AND b.deleted_at IS NULL
+                   ORDER BY b.name"#,
+                &[&status.as_str()],
+            )
+            .await
+            .context("get_bikes_by_status")?;
+
+        let count = rows
+            .first()
+            .map(|row| row.get::<_, i64>("total") as u32)
+            .unwrap_or(0);
+        let bikes = rows
+            .iter()
+            .filter(|row| row.get::<_, Option<String>>("id").is_some())
+            .map(|row| self.map_bike_row(row))
+            .collect();
+
+        Ok(BikesByStatusResult {
+            bikes,
+            count,
+            status: status.clone(),
+        })
+    }
+
+    /// Get all bikes matching any of the given statuses
+    pub async fn get_bikes_by_multiple_statuses(
+        &self,
+        statuses: &[BikeStatus],
+    ) -> Result<Vec<Bike>, DatabaseError> {
+        if statuses.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("get_bikes_by_multiple_statuses")?;
+        let statuses: Vec<&str> = statuses.iter().map(|s| s.as_str()).collect();
+
+        let rows = client
+            .query(
+                r#"SELECT id, name, status, latitude, longitude, battery_level,
+                          last_maintenance, total_trips, total_distance_km, created_at, updated_at,
+                          reservation_expires_at, maintenance_threshold_override, deleted_at,
+                          next_maintenance_at
+                   FROM bikes
+                   WHERE status IN (SELECT unnest($1::text[])) AND deleted_at IS NULL
+                   ORDER BY name"#,
+                &[&statuses],
+            )
+            .await
+            .context("get_bikes_by_multiple_statuses")?;
+
+        Ok(rows.iter().map(|row| self.map_bike_row(row)).collect())
+    }
+
     /// Add a new bike to the fleet
     pub async fn add_bike(
         &self,
@@ -487,7 +756,22 @@ impl Database {
         lon: f64,
         battery: Option<u8>,
     ) -> Result<Bike, DatabaseError> {
-        let client = self.pool.get().await?;
+        let mut client = self.pool.get().await.context("add_bike")?;
+        let tx = client.transaction().await.context("add_bike")?;
+        let bike = self.insert_bike(&tx, name, lat, lon, battery).await?;
+        tx.commit().await.context("add_bike")?;
+        Ok(bike)
+    }
+
+    /// Insert and audit a bike through `client`, e.g. inside a transaction
+    async fn insert_bike(
+        &self,
+        client: &impl GenericClient,
+        name: &str,
+        lat: f64,
+        lon: f64,
+        battery: Option<u8>,
+    ) -> Result<Bike, DatabaseError> {
         let id = format!("BIKE-{}", uuid_v4_simple());
         let now = Utc::now();
 
@@ -506,9 +790,10 @@ impl Database {
                     &now,
                 ],
             )
-            .await?;
+            .await
+            .context("add_bike")?;
 
-        Ok(Bike {
+        let bike = Bike {
             id,
             name: name.to_string(),
             status: BikeStatus::Available,
@@ -518,12 +803,51 @@ impl Database {
             last_maintenance: None,
             total_trips: 0,
             total_distance_km: 0.0,
+            reservation_expires_at: None,
+            maintenance_threshold_override: None,
+            deleted_at: None,
+            next_maintenance_at: None,
             created_at: now,
             updated_at: now,
-        })
+        };
+        self.log_audit_event(
+            client,
+            "bikes",
+            &bike.id,
+            AuditOp::Insert,
+            None,
+            Some(&audit_json(&bike)?),
+        )
+        .await?;
+        self.append_event(client, FleetEventType::BikeAdded, &bike).await?;
+        Ok(bike)
+    }
+
+    /// Add many bikes in one transaction
+    ///
+    /// Either all bikes are added or, on error, none.
+    ///
+    /// # Returns
+    /// The number of bikes added
+    pub async fn import_bikes(&self, bikes: &[AddBikeRequest]) -> Result<u32, DatabaseError> {
+        let mut client = self.pool.get().await.context("import_bikes")?;
+        let tx = client.transaction().await.context("import_bikes")?;
+
+        for bike in bikes {
+            self.insert_bike(&tx, &bike.name, bike.latitude, bike.longitude, bike.battery_level)
+                .await?;
+        }
+
+        tx.commit().await.context("import_bikes")?;
+        Ok(bikes.len() as u32)
     }
 
     /// Update bike status
+    ///
+    /// # Errors
+    /// - `InvalidData` for `Reserved`: reservations need an expiry, so they go
+    ///   through `reserve_bike`
+    /// - `InvalidData` if the bike does not exist or is retired
     pub async fn update_bike_status(
         &self,
         bike_id: &str,
@@ -532,147 +856,819 @@ impl Database {
         lon: Option<f64>,
         battery: Option<u8>,
     ) -> Result<(), DatabaseError> {
-        let client = self.pool.get().await?;
+        if *status == BikeStatus::Reserved {
+            return Err(DatabaseError::InvalidData(
+                "Use reserve_bike to reserve a bike".to_string(),
+            ));
+        }
+
+        let mut client = self.pool.get().await.context("update_bike_status")?;
+        let tx = client.transaction().await.context("update_bike_status")?;
+        let old = self.fetch_bike(&tx, bike_id).await?;
 
         // PostgreSQL handles the updated_at via trigger
-        match (lat, lon, battery) {
+        let updated = match (lat, lon, battery) {
             (Some(lat_val), Some(lon_val), Some(bat_val)) => {
-                client
+                tx
                     .execute(
-                        "UPDATE bikes SET status = $1, latitude = $2, longitude = $3, battery_level = $4 WHERE id = $5",
+                        "UPDATE bikes SET status = $1, latitude = $2, longitude = $3, battery_level = $4, reservation_expires_at = NULL WHERE id = $5 AND deleted_at IS NULL",
                         &[&status.as_str(), &lat_val, &lon_val, &(bat_val as i32), &bike_id],
                     )
-                    .await?;
+                    .await.context("update_bike_status")?
             }
             (Some(lat_val), Some(lon_val), None) => {
-                client
+                tx
                     .execute(
-                        "UPDATE bikes SET status = $1, latitude = $2, longitude = $3 WHERE id = $4",
+                        "UPDATE bikes SET status = $1, latitude = $2, longitude = $3, reservation_expires_at = NULL WHERE id = $4 AND deleted_at IS NULL",
                         &[&status.as_str(), &lat_val, &lon_val, &bike_id],
                     )
-                    .await?;
+                    .await.context("update_bike_status")?
             }
             (None, None, Some(bat_val)) => {
-                client
+                tx
                     .execute(
-                        "UPDATE bikes SET status = $1, battery_level = $2 WHERE id = $3",
+                        "UPDATE bikes SET status = $1, battery_level = $2, reservation_expires_at = NULL WHERE id = $3 AND deleted_at IS NULL",
                         &[&status.as_str(), &(bat_val as i32), &bike_id],
                     )
-                    .await?;
+                    .await.context("update_bike_status")?
             }
             _ => {
-                client
+                tx
                     .execute(
-                        "UPDATE bikes SET status = $1 WHERE id = $2",
+                        "UPDATE bikes SET status = $1, reservation_expires_at = NULL WHERE id = $2 AND deleted_at IS NULL",
                         &[&status.as_str(), &bike_id],
                     )
-                    .await?;
+                    .await
+                    .context("update_bike_status")?
+            }
+        };
+
+        // Retired bikes are not updated either
+        if updated == 0 {
+            return Err(DatabaseError::InvalidData(format!("Bike not found: {}", bike_id)));
+        }
+
+        if let Some(old) = old {
+            if let Some(new) = self.audit_bike_update(&tx, &old).await? {
+                self.append_event(&tx, FleetEventType::BikeStatusChanged, &new).await?;
             }
         }
 
+        tx.commit().await.context("update_bike_status")?;
         Ok(())
     }
 
-    fn map_bike_row(&self, row: &tokio_postgres::Row) -> Bike {
-        let status_str: String = row.get("status");
-        let status = BikeStatus::from_str(&status_str).unwrap_or(BikeStatus::Offline);
-        let battery_level: Option<i32> = row.get("battery_level");
+    /// Retire a bike from the fleet (soft delete)
+    ///
+    /// The row is kept so that its deliveries, issues and trips stay
+    /// intact; the bike is set offline and hidden from all bike queries
+    /// except `get_retired_bikes`. Its force graph pins are deleted.
+    pub async fn retire_bike(&self, bike_id: &str) -> Result<(), DatabaseError> {
+        let mut client = self.pool.get().await.context("retire_bike")?;
+        let tx = client.transaction().await.context("retire_bike")?;
+        let old = self.fetch_bike(&tx, bike_id).await?;
 
-        Bike {
-            id: row.get("id"),
-            name: row.get("name"),
-            status,
-            latitude: row.get("latitude"),
-            longitude: row.get("longitude"),
-            battery_level: battery_level.map(|v| v as u8),
-            last_maintenance: row.get("last_maintenance"),
-            total_trips: row.get::<_, i32>("total_trips") as u32,
-            total_distance_km: row.get("total_distance_km"),
-            created_at: row.get("created_at"),
-            updated_at: row.get("updated_at"),
-        }
+        // PostgreSQL handles the updated_at via trigger
+        let updated = tx
+            .execute(
+                "UPDATE bikes SET status = 'offline', deleted_at = NOW(), reservation_expires_at = NULL WHERE id = $1 AND deleted_at IS NULL",
+                &[&bike_id],
+            )
+            .await
+            .context("retire_bike")?;
+
+        let old = match old {
+            Some(old) if updated > 0 => old,
+            _ => {
+                return Err(DatabaseError::InvalidData(format!(
+                    "Bike not found: {}",
+                    bike_id
+                )))
+            }
+        };
+
+        self.log_audit_event(&tx, "bikes", bike_id, AuditOp::Delete, Some(&audit_json(&old)?), None)
+            .await?;
+        // A retired bike's graph is no longer shown, so its pins go with it
+        tx.execute("DELETE FROM force_graph_pins WHERE bike_id = $1", &[&bike_id])
+            .await
+            .context("retire_bike")?;
+        let now = Utc::now();
+        let retired = Bike {
+            status: BikeStatus::Offline,
+            reservation_expires_at: None,
+            deleted_at: Some(now),
+            updated_at: now,
+            ..old
+        };
+        self.append_event(&tx, FleetEventType::BikeRetired, &retired).await?;
+
+        tx.commit().await.context("retire_bike")?;
+        Ok(())
+    }
+
+    /// Get retired bikes, most recently retired first
+    pub async fn get_retired_bikes(&self) -> Result<Vec<Bike>, DatabaseError> {
+        let client = self.pool.get().await.context("get_retired_bikes")?;
+
+        let rows = client
+            .query(
+                r#"SELECT id, name, status, latitude, longitude, battery_level,
+                          last_maintenance, total_trips, total_distance_km, created_at, updated_at,
+                          reservation_expires_at, maintenance_threshold_override, deleted_at,
+                          next_maintenance_at
+                   FROM bikes WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC"#,
+                &[],
+            )
+            .await
+            .context("get_retired_bikes")?;
+
+        Ok(rows.iter().map(|row| self.map_bike_row(row)).collect())
     }
 
     // ========================================================================
-    // Delivery Queries
+    // Simulation Scenarios
     // ========================================================================
 
-    /// Get all deliveries, optionally filtered by bike_id and/or status
-    pub async fn get_deliveries(
+    /// Put every bike into the state described by `scenario`
+    ///
+    /// Runs in one transaction; active reservations are cleared.
+    ///
+    /// # Returns
+    /// The updated fleet
+    pub async fn apply_simulation_scenario(
         &self,
-        bike_id: Option<&str>,
-        status: Option<&str>,
-    ) -> Result<Vec<Delivery>, DatabaseError> {
-        let client = self.pool.get().await?;
+        scenario: SimulationScenario,
+    ) -> Result<Vec<Bike>, DatabaseError> {
+        let mut bikes = self.get_all_bikes(None, None).await?;
+        bikes.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let today = Utc::now().date_naive().and_hms_opt(0, 0, 0).unwrap().and_utc();
+        let plan = simulation::plan_scenario(scenario, &bikes, today);
+
+        let mut client = self.pool.get().await.context("apply_simulation_scenario")?;
+        let tx = client
+            .transaction()
+            .await
+            .context("apply_simulation_scenario")?;
+
+        // updated_at is maintained by trigger
+        for (bike, target) in bikes.iter().zip(&plan) {
+            tx.execute(
+                r#"UPDATE bikes SET status = $1,
+                          battery_level = COALESCE($2, battery_level),
+                          latitude = COALESCE($3, latitude),
+                          longitude = COALESCE($4, longitude),
+                          last_maintenance = COALESCE($5, last_maintenance),
+                          reservation_expires_at = NULL
+                   WHERE id = $6"#,
+                &[
+                    &target.status.as_str(),
+                    &target.battery_level.map(|b| b as i32),
+                    &target.position.map(|(lat, _)| lat),
+                    &target.position.map(|(_, lon)| lon),
+                    &target.last_maintenance,
+                    &bike.id,
+                ],
+            )
+            .await
+            .context("apply_simulation_scenario")?;
+            if let Some(bike) = self.audit_bike_update(&tx, bike).await? {
+                self.append_event(&tx, FleetEventType::BikeStatusChanged, &bike).await?;
+            }
+        }
 
-        // Build dynamic query
-        let mut sql = String::from(
-            r#"SELECT id, bike_id, status, customer_name, customer_address,
-                      restaurant_name, restaurant_address, rating, complaint,
-                      created_at, completed_at
-               FROM deliveries WHERE true"#,
-        );
+        tx.commit().await.context("apply_simulation_scenario")?;
 
-        let mut params: Vec<&(dyn ToSql + Sync)> = Vec::new();
-        let mut param_idx = 1;
+        self.get_all_bikes(None, None).await
+    }
 
-        // Temporary variables to extend lifetime
-        let bike_id_str: String;
-        let status_str: String;
+    // ========================================================================
+    // Reservations
+    // ========================================================================
 
-        if let Some(b) = bike_id {
-            sql.push_str(&format!(" AND bike_id = ${}", param_idx));
-            bike_id_str = b.to_string();
-            params.push(&bike_id_str);
-            param_idx += 1;
-        }
-        if let Some(s) = status {
-            sql.push_str(&format!(" AND status = ${}", param_idx));
-            status_str = s.to_string();
-            params.push(&status_str);
+    /// Reserve an available bike until `expires_at`
+    ///
+    /// # Errors
+    /// `InvalidData` if the expiry is in the past, or the bike is unknown,
+    /// retired or not available
+    pub async fn reserve_bike(
+        &self,
+        bike_id: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), DatabaseError> {
+        if expires_at <= Utc::now() {
+            return Err(DatabaseError::InvalidData(
+                "Reservation expiry must be in the future".to_string(),
+            ));
         }
-        sql.push_str(" ORDER BY created_at DESC");
 
-        let rows = client.query(&sql, &params).await?;
+        let mut client = self.pool.get().await.context("reserve_bike")?;
+        let tx = client.transaction().await.context("reserve_bike")?;
+        let old = self.fetch_bike(&tx, bike_id).await?;
 
-        let deliveries = rows.iter().map(|row| self.map_delivery_row(row)).collect();
-        Ok(deliveries)
-    }
+        // Conditional update: only an available bike can be reserved
+        let updated = tx
+            .execute(
+                r#"UPDATE bikes SET status = 'reserved', reservation_expires_at = $1
+                   WHERE id = $2 AND status = 'available' AND deleted_at IS NULL"#,
+                &[&expires_at, &bike_id],
+            )
+            .await
+            .context("reserve_bike")?;
+
+        let old = match old {
+            Some(old) if updated > 0 => old,
+            Some(_) => {
+                return Err(DatabaseError::InvalidData(format!(
+                    "Bike not available for reservation: {}",
+                    bike_id
+                )))
+            }
+            None => return Err(DatabaseError::InvalidData(format!("Bike not found: {}", bike_id))),
+        };
 
-    /// Get a single delivery by ID
-    pub async fn get_delivery_by_id(
-        &self,
-        delivery_id: &str,
-    ) -> Result<Option<Delivery>, DatabaseError> {
-        let client = self.pool.get().await?;
+        self.audit_bike_update(&tx, &old).await?;
+        self.append_bike_status_event(&tx, bike_id).await?;
 
-        let row = client
-            .query_opt(
-                r#"SELECT id, bike_id, status, customer_name, customer_address,
-                          restaurant_name, restaurant_address, rating, complaint,
-                          created_at, completed_at
-                   FROM deliveries WHERE id = $1"#,
-                &[&delivery_id],
+        tx.commit().await.context("reserve_bike")?;
+        Ok(())
+    }
+
+    /// Cancel a reservation, making the bike available again
+    ///
+    /// # Errors
+    /// `InvalidData` if the bike is not reserved
+    pub async fn cancel_reservation(&self, bike_id: &str) -> Result<(), DatabaseError> {
+        let mut client = self.pool.get().await.context("cancel_reservation")?;
+        let tx = client.transaction().await.context("cancel_reservation")?;
+        let old = self.fetch_bike(&tx, bike_id).await?;
+
+        let updated = tx
+            .execute(
+                r#"UPDATE bikes SET status = 'available', reservation_expires_at = NULL
+                   WHERE id = $1 AND status = 'reserved'"#,
+                &[&bike_id],
             )
-            .await?;
+            .await
+            .context("cancel_reservation")?;
 
-        Ok(row.map(|r| self.map_delivery_row(&r)))
-    }
+        let old = match old {
+            Some(old) if updated > 0 => old,
+            _ => {
+                return Err(DatabaseError::InvalidData(format!(
+                    "Bike is not reserved: {}",
+                    bike_id
+                )))
+            }
+        };
 
-    /// Get deliveries for a specific bike (for force graph)
-    pub async fn get_deliveries_by_bike(&self, bike_id: &str) -> Result<Vec<Delivery>, DatabaseError> {
-        self.get_deliveries(Some(bike_id), None).await
+        self.audit_bike_update(&tx, &old).await?;
+        self.append_bike_status_event(&tx, bike_id).await?;
+
+        tx.commit().await.context("cancel_reservation")?;
+        Ok(())
     }
 
-    fn map_delivery_row(&self, row: &tokio_postgres::Row) -> Delivery {
-        let status_str: String = row.get("status");
-        let status = DeliveryStatus::from_str(&status_str).unwrap_or(DeliveryStatus::Upcoming);
-        let rating: Option<i32> = row.get("rating");
+    /// Make bikes whose reservation has expired available again
+    ///
+    /// # Returns
+    /// Number of released bikes
+    pub async fn release_expired_reservations(&self) -> Result<u32, DatabaseError> {
+        let mut client = self
+            .pool
+            .get()
+            .await
+            .context("release_expired_reservations")?;
+        let tx = client
+            .transaction()
+            .await
+            .context("release_expired_reservations")?;
+
+        // Row locks keep the audited rows and the released rows the same
+        let rows = tx
+            .query(
+                r#"SELECT id, name, status, latitude, longitude, battery_level,
+                          last_maintenance, total_trips, total_distance_km, created_at, updated_at,
+                          reservation_expires_at, maintenance_threshold_override, deleted_at,
+                          next_maintenance_at
+                   FROM bikes
+                   WHERE status = 'reserved' AND reservation_expires_at <= NOW()
+                   FOR UPDATE"#,
+                &[],
+            )
+            .await
+            .context("release_expired_reservations")?;
+        let expired: Vec<Bike> = rows.iter().map(|row| self.map_bike_row(row)).collect();
+        let ids: Vec<&str> = expired.iter().map(|bike| bike.id.as_str()).collect();
+
+        tx.execute(
+            r#"UPDATE bikes SET status = 'available', reservation_expires_at = NULL
+               WHERE id IN (SELECT unnest($1::text[]))"#,
+            &[&ids],
+        )
+        .await
+        .context("release_expired_reservations")?;
+        for bike in &expired {
+            if let Some(bike) = self.audit_bike_update(&tx, bike).await? {
+                self.append_event(&tx, FleetEventType::BikeStatusChanged, &bike).await?;
+            }
+        }
 
-        Delivery {
+        tx.commit().await.context("release_expired_reservations")?;
+        Ok(expired.len() as u32)
+    }
+
+    /// Get bikes whose battery is below `threshold` percent, lowest first
+    ///
+    /// Bikes without a battery reading are left out.
+    pub async fn get_bikes_below_battery(&self, threshold: u8) -> Result<Vec<Bike>, DatabaseError> {
+        let client = self.pool.get().await.context("get_bikes_below_battery")?;
+
+        let rows = client
+            .query(
+                r#"SELECT id, name, status, latitude, longitude, battery_level,
+                          last_maintenance, total_trips, total_distance_km, created_at, updated_at,
+                          reservation_expires_at, maintenance_threshold_override, deleted_at,
+                          next_maintenance_at
+                   FROM bikes
+                   WHERE deleted_at IS NULL
+                     AND battery_level IS NOT NULL AND battery_level < $1
+                   ORDER BY battery_level ASC, name"#,
+                &[&(threshold as i32)],
+            )
+            .await
+            .context("get_bikes_below_battery")?;
+
+        Ok(rows.iter().map(|row| self.map_bike_row(row)).collect())
+    }
+
+    /// Get bikes whose last maintenance is at least their threshold ago
+    ///
+    /// Each bike's `maintenance_threshold_override` takes precedence over
+    /// `global_threshold_days`. Bikes never maintained are always due.
+    pub async fn get_bikes_due_for_maintenance(
+        &self,
+        global_threshold_days: u32,
+    ) -> Result<Vec<Bike>, DatabaseError> {
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("get_bikes_due_for_maintenance")?;
+
+        let rows = client
+            .query(
+                r#"SELECT id, name, status, latitude, longitude, battery_level,
+                          last_maintenance, total_trips, total_distance_km, created_at, updated_at,
+                          reservation_expires_at, maintenance_threshold_override, deleted_at,
+                          next_maintenance_at
+                   FROM bikes
+                   WHERE deleted_at IS NULL
+                     AND (last_maintenance IS NULL
+                          OR last_maintenance <= NOW() - make_interval(
+                                 days => COALESCE(maintenance_threshold_override, $1)))
+                   ORDER BY name"#,
+                &[&(global_threshold_days as i32)],
+            )
+            .await
+            .context("get_bikes_due_for_maintenance")?;
+
+        Ok(rows.iter().map(|row| self.map_bike_row(row)).collect())
+    }
+
+    /// Set or clear (`None`) a bike's maintenance threshold in days
+    ///
+    /// # Errors
+    /// `InvalidData` if the threshold is 0 or the bike does not exist or is retired
+    pub async fn set_bike_maintenance_threshold(
+        &self,
+        bike_id: &str,
+        threshold: Option<u32>,
+    ) -> Result<(), DatabaseError> {
+        validate_maintenance_threshold(threshold)?;
+        let mut client = self
+            .pool
+            .get()
+            .await
+            .context("set_bike_maintenance_threshold")?;
+        let tx = client
+            .transaction()
+            .await
+            .context("set_bike_maintenance_threshold")?;
+        let old = self
+            .fetch_bike(&tx, bike_id)
+            .await?
+            .ok_or_else(|| DatabaseError::InvalidData(format!("Bike not found: {}", bike_id)))?;
+
+        tx.execute(
+            "UPDATE bikes SET maintenance_threshold_override = $1 WHERE id = $2 AND deleted_at IS NULL",
+            &[&threshold.map(|t| t as i32), &bike_id],
+        )
+        .await
+        .context("set_bike_maintenance_threshold")?;
+        self.audit_bike_update(&tx, &old).await?;
+
+        tx.commit().await.context("set_bike_maintenance_threshold")?;
+        Ok(())
+    }
+
+    /// Schedule a bike's next maintenance, replacing any earlier schedule
+    ///
+    /// # Errors
+    /// `InvalidData` if the bike does not exist or is retired
+    pub async fn schedule_maintenance(
+        &self,
+        bike_id: &str,
+        at: DateTime<Utc>,
+    ) -> Result<(), DatabaseError> {
+        let mut client = self.pool.get().await.context("schedule_maintenance")?;
+        let tx = client.transaction().await.context("schedule_maintenance")?;
+        let old = self
+            .fetch_bike(&tx, bike_id)
+            .await?
+            .ok_or_else(|| DatabaseError::InvalidData(format!("Bike not found: {}", bike_id)))?;
+
+        tx.execute(
+            r#"UPDATE bikes SET next_maintenance_at = $1
+               WHERE id = $2 AND deleted_at IS NULL"#,
+            &[&at, &bike_id],
+        )
+        .await
+        .context("schedule_maintenance")?;
+        self.audit_bike_update(&tx, &old).await?;
+
+        tx.commit().await.context("schedule_maintenance")?;
+        Ok(())
+    }
+
+    /// Get bikes whose scheduled maintenance is at or before `as_of`,
+    /// most overdue first
+    pub async fn get_overdue_maintenance(
+        &self,
+        as_of: DateTime<Utc>,
+    ) -> Result<Vec<Bike>, DatabaseError> {
+        let client = self.pool.get().await.context("get_overdue_maintenance")?;
+
+        let rows = client
+            .query(
+                r#"SELECT id, name, status, latitude, longitude, battery_level,
+                          last_maintenance, total_trips, total_distance_km, created_at, updated_at,
+                          reservation_expires_at, maintenance_threshold_override, deleted_at,
+                          next_maintenance_at
+                   FROM bikes
+                   WHERE deleted_at IS NULL
+                     AND next_maintenance_at IS NOT NULL AND next_maintenance_at <= $1
+                   ORDER BY next_maintenance_at, name"#,
+                &[&as_of],
+            )
+            .await
+            .context("get_overdue_maintenance")?;
+
+        Ok(rows.iter().map(|row| self.map_bike_row(row)).collect())
+    }
+
+    /// Record a completed maintenance and schedule the next one
+    /// `next_due_in_days` after it
+    ///
+    /// `last_maintenance` and `next_maintenance_at` change together in one
+    /// transaction.
+    ///
+    /// # Errors
+    /// `InvalidData` if `next_due_in_days` is 0 or the bike does not exist
+    /// or is retired
+    pub async fn record_maintenance_complete(
+        &self,
+        bike_id: &str,
+        performed_at: DateTime<Utc>,
+        next_due_in_days: u32,
+    ) -> Result<(), DatabaseError> {
+        if next_due_in_days == 0 {
+            return Err(DatabaseError::InvalidData(
+                "Next maintenance must be at least 1 day away".to_string(),
+            ));
+        }
+        let next_due = performed_at + chrono::Duration::days(next_due_in_days as i64);
+
+        let mut client = self.pool.get().await.context("record_maintenance_complete")?;
+        let tx = client.transaction().await.context("record_maintenance_complete")?;
+        let old = self
+            .fetch_bike(&tx, bike_id)
+            .await?
+            .ok_or_else(|| DatabaseError::InvalidData(format!("Bike not found: {}", bike_id)))?;
+
+        tx.execute(
+            r#"UPDATE bikes SET last_maintenance = $1, next_maintenance_at = $2
+               WHERE id = $3 AND deleted_at IS NULL"#,
+            &[&performed_at, &next_due, &bike_id],
+        )
+        .await
+        .context("record_maintenance_complete")?;
+        self.audit_bike_update(&tx, &old).await?;
+
+        tx.commit().await.context("record_maintenance_complete")?;
+        Ok(())
+    }
+
+    fn map_bike_row(&self, row: &tokio_postgres::Row) -> Bike {
+        let status_str: String = row.get("status");
+        let status = BikeStatus::from_str(&status_str).unwrap_or(BikeStatus::Offline);
+        let battery_level: Option<i32> = row.get("battery_level");
+
+        Bike {
+            id: row.get("id"),
+            name: row.get("name"),
+            status,
+            latitude: row.get("latitude"),
+            longitude: row.get("longitude"),
+            battery_level: battery_level.map(|v| v as u8),
+            last_maintenance: row.get("last_maintenance"),
+            total_trips: row.get::<_, i32>("total_trips") as u32,
+            total_distance_km: row.get("total_distance_km"),
+            reservation_expires_at: row.get("reservation_expires_at"),
+            maintenance_threshold_override: row
+                .get::<_, Option<i32>>("maintenance_threshold_override")
+                .map(|v| v as u32),
+            deleted_at: row.get("deleted_at"),
+            next_maintenance_at: row.get("next_maintenance_at"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+        }
+    }
+
+    // ========================================================================
+    // Trips
+    // ========================================================================
+
+    /// Start a trip for a bike at the given position
+    ///
+    /// # Returns
+    /// The new trip ID, to be passed to `end_trip`
+    pub async fn start_trip(
+        &self,
+        bike_id: &str,
+        start_lat: f64,
+        start_lon: f64,
+    ) -> Result<String, DatabaseError> {
+        if self.get_bike_by_id(bike_id).await?.is_none() {
+            return Err(DatabaseError::InvalidData(format!("Bike not found: {}", bike_id)));
+        }
+
+        let client = self.pool.get().await.context("start_trip")?;
+        let id = format!("TRIP-{}", uuid_v4_simple());
+        client
+            .execute(
+                r#"INSERT INTO trips (id, bike_id, start_time, start_latitude, start_longitude)
+                   VALUES ($1, $2, NOW(), $3, $4)"#,
+                &[&id, &bike_id, &start_lat, &start_lon],
+            )
+            .await
+            .context("start_trip")?;
+
+        Ok(id)
+    }
+
+    /// End an open trip at the given position
+    ///
+    /// The haversine distance from the start position is stored on the trip
+    /// and added to the bike's totals in the same transaction.
+    pub async fn end_trip(
+        &self,
+        trip_id: &str,
+        end_lat: f64,
+        end_lon: f64,
+    ) -> Result<(), DatabaseError> {
+        let mut client = self.pool.get().await.context("end_trip")?;
+        let tx = client.transaction().await.context("end_trip")?;
+
+        // Row lock so that two concurrent end_trip calls can't both count the trip
+        let row = tx
+            .query_opt(
+                r#"SELECT bike_id, start_latitude, start_longitude
+                   FROM trips WHERE id = $1 AND end_time IS NULL
+                   FOR UPDATE"#,
+                &[&trip_id],
+            )
+            .await
+            .context("end_trip")?
+            .ok_or_else(|| {
+                DatabaseError::InvalidData(format!("Open trip not found: {}", trip_id))
+            })?;
+        let bike_id: String = row.get("bike_id");
+
+        let distance_km = geo::haversine_km(
+            row.get("start_latitude"),
+            row.get("start_longitude"),
+            end_lat,
+            end_lon,
+        );
+        let old = self.fetch_bike(&tx, &bike_id).await?;
+
+        tx.execute(
+            r#"UPDATE trips SET end_time = NOW(), end_latitude = $1, end_longitude = $2,
+                      distance_km = $3
+               WHERE id = $4"#,
+            &[&end_lat, &end_lon, &distance_km, &trip_id],
+        )
+        .await
+        .context("end_trip")?;
+        // updated_at is maintained by trigger
+        tx.execute(
+            r#"UPDATE bikes SET total_trips = total_trips + 1,
+                      total_distance_km = total_distance_km + $1
+               WHERE id = $2"#,
+            &[&distance_km, &bike_id],
+        )
+        .await
+        .context("end_trip")?;
+        if let Some(old) = old {
+            self.audit_bike_update(&tx, &old).await?;
+        }
+
+        tx.commit().await.context("end_trip")?;
+        Ok(())
+    }
+
+    // ========================================================================
+    // Delivery Queries
+    // ========================================================================
+
+    /// Get one page of deliveries, optionally filtered by bike_id and/or status
+    ///
+    /// `from`/`to` bound `created_at` (inclusive), `sort_by`/`sort_dir` pick
+    /// the order (newest first by default), `cursor` is the `next_cursor` of
+    /// the previous page; `limit` is 1-500.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn get_deliveries(
+        &self,
+        bike_id: Option<&str>,
+        status: Option<&str>,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        sort_by: Option<DeliverySort>,
+        sort_dir: Option<SortDirection>,
+        cursor: Option<&str>,
+        limit: u32,
+    ) -> Result<DeliveryPage, DatabaseError> {
+        validate_page_limit(limit)?;
+        let after = cursor.map(decode_cursor).transpose()?;
+
+        let client = self.pool.get().await.context("get_deliveries")?;
+        validate_date_range(from, to)?;
+        let (filters, params) = delivery_filters(bike_id, status, from, to);
+        let param_refs: Vec<&(dyn ToSql + Sync)> =
+            params.iter().map(|p| p.as_ref() as &(dyn ToSql + Sync)).collect();
+        let row = client
+            .query_one(
+                &format!("SELECT COUNT(*)::INTEGER FROM deliveries WHERE true{}", filters),
+                &param_refs,
+            )
+            .await
+            .context("get_deliveries")?;
+        let total_count = row.get::<_, i32>(0) as u32;
+
+        let sort_by = sort_by.unwrap_or_default();
+        let sort_dir = sort_dir.unwrap_or_else(|| sort_by.default_direction());
+        let deliveries = self
+            .query_deliveries(
+                delivery_filters(bike_id, status, from, to),
+                (sort_by, sort_dir),
+                after.as_ref(),
+                Some(limit + 1),
+            )
+            .await?;
+
+        Ok(Page::from_rows(deliveries, limit, total_count, |delivery| PageCursor {
+            created_at: delivery.created_at,
+            id: delivery.id.clone(),
+        }))
+    }
+
+    /// Deliveries matching the filters after `after`, up to `limit` rows
+    async fn query_deliveries(
+        &self,
+        (filters, mut params): SqlFilters,
+        (sort_by, sort_dir): (DeliverySort, SortDirection),
+        after: Option<&PageCursor>,
+        limit: Option<u32>,
+    ) -> Result<Vec<Delivery>, DatabaseError> {
+        let (column, null_fill) = delivery_sort_column(sort_by);
+        let client = self.pool.get().await.context("get_deliveries")?;
+
+        let mut sql = format!(
+            r#"SELECT id, bike_id, status, customer_name, customer_address,
+                      restaurant_name, restaurant_address, rating, complaint,
+                      created_at, completed_at, priority
+               FROM deliveries WHERE true{}"#,
+            filters
+        );
+
+        if let Some(after) = after {
+            params.push(Box::new(after.created_at));
+            params.push(Box::new(after.id.clone()));
+            sql.push_str(&keyset_after_cursor(
+                "deliveries",
+                column,
+                null_fill,
+                sort_dir,
+                params.len() - 1,
+                params.len(),
+            ));
+        }
+        sql.push_str(&keyset_order_by(column, null_fill.is_some(), sort_dir));
+        if let Some(limit) = limit {
+            sql.push_str(&format!(" LIMIT {}", limit));
+        }
+
+        let param_refs: Vec<&(dyn ToSql + Sync)> =
+            params.iter().map(|p| p.as_ref() as &(dyn ToSql + Sync)).collect();
+        let rows = client
+            .query(&sql, &param_refs)
+            .await
+            .context("get_deliveries")?;
+
+        let deliveries = rows.iter().map(|row| self.map_delivery_row(row)).collect();
+        Ok(deliveries)
+    }
+
+    /// Get a single delivery by ID
+    pub async fn get_delivery_by_id(
+        &self,
+        delivery_id: &str,
+    ) -> Result<Option<Delivery>, DatabaseError> {
+        let client = self.pool.get().await.context("get_delivery_by_id")?;
+        self.fetch_delivery(&client, delivery_id).await
+    }
+
+    /// `get_delivery_by_id` on `client`, e.g. inside a transaction
+    async fn fetch_delivery(
+        &self,
+        client: &impl GenericClient,
+        delivery_id: &str,
+    ) -> Result<Option<Delivery>, DatabaseError> {
+        let row = client
+            .query_opt(
+                r#"SELECT id, bike_id, status, customer_name, customer_address,
+                          restaurant_name, restaurant_address, rating, complaint,
+                          created_at, completed_at, priority
+                   FROM deliveries WHERE id = $1"#,
+                &[&delivery_id],
+            )
+            .await
+            .context("get_delivery_by_id")?;
+
+        Ok(row.map(|r| self.map_delivery_row(&r)))
+    }
+
+    /// Get deliveries for a specific bike (for force graph)
+    pub async fn get_deliveries_by_bike(&self, bike_id: &str) -> Result<Vec<Delivery>, DatabaseError> {
+        self.query_deliveries(
+            delivery_filters(Some(bike_id), None, None, None),
+            (DeliverySort::CreatedAt, SortDirection::Desc),
+            None,
+            None,
+        )
+        .await
+    }
+
+    /// Every delivery of a bike still in the fleet, newest first (for the fleet graph)
+    pub async fn get_fleet_deliveries(&self) -> Result<Vec<Delivery>, DatabaseError> {
+        self.query_deliveries(
+            (" AND bike_id IN (SELECT id FROM bikes WHERE deleted_at IS NULL)".to_string(), Vec::new()),
+            (DeliverySort::CreatedAt, SortDirection::Desc),
+            None,
+            None,
+        )
+        .await
+    }
+
+    /// Every delivery created within the optional window, oldest first (for export)
+    pub async fn get_deliveries_in_range(
+        &self,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<Delivery>, DatabaseError> {
+        validate_date_range(from, to)?;
+        self.query_deliveries(
+            delivery_filters(None, None, from, to),
+            (DeliverySort::CreatedAt, SortDirection::Asc),
+            None,
+            None,
+        )
+        .await
+    }
+
+    fn map_delivery_row(&self, row: &tokio_postgres::Row) -> Delivery {
+        let status_str: String = row.get("status");
+        let status = DeliveryStatus::from_str(&status_str).unwrap_or(DeliveryStatus::Upcoming);
+        let rating: Option<i32> = row.get("rating");
+        let priority_str: String = row.get("priority");
+
+        Delivery {
             id: row.get("id"),
             bike_id: row.get("bike_id"),
             status,
+            priority: DeliveryPriority::from_str(&priority_str).unwrap_or_default(),
             customer_name: row.get("customer_name"),
             customer_address: row.get("customer_address"),
             restaurant_name: row.get("restaurant_name"),
@@ -684,88 +1680,1375 @@ impl Database {
         }
     }
 
+    /// Create an upcoming delivery for a bike
+    ///
+    /// # Errors
+    /// `InvalidData` if a name or address is blank or the bike does not exist
+    pub async fn create_delivery(
+        &self,
+        req: &CreateDeliveryRequest,
+    ) -> Result<Delivery, DatabaseError> {
+        req.validate().map_err(DatabaseError::InvalidData)?;
+        if self.get_bike_by_id(&req.bike_id).await?.is_none() {
+            return Err(DatabaseError::InvalidData(format!(
+                "Bike not found: {}",
+                req.bike_id
+            )));
+        }
+
+        let mut client = self.pool.get().await.context("create_delivery")?;
+        let tx = client.transaction().await.context("create_delivery")?;
+        let id = format!("DEL-{}", uuid_v4_simple());
+        let now = Utc::now();
+
+        tx.execute(
+            r#"INSERT INTO deliveries (id, bike_id, status, customer_name, customer_address,
+               restaurant_name, restaurant_address, created_at, priority)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)"#,
+            &[
+                &id,
+                &req.bike_id,
+                &DeliveryStatus::Upcoming.as_str(),
+                &req.customer_name,
+                &req.customer_address,
+                &req.restaurant_name,
+                &req.restaurant_address,
+                &now,
+                &DeliveryPriority::Normal.as_str(),
+            ],
+        )
+        .await
+        .context("create_delivery")?;
+
+        let delivery = Delivery {
+            id,
+            bike_id: req.bike_id.clone(),
+            status: DeliveryStatus::Upcoming,
+            priority: DeliveryPriority::Normal,
+            customer_name: req.customer_name.clone(),
+            customer_address: req.customer_address.clone(),
+            restaurant_name: req.restaurant_name.clone(),
+            restaurant_address: req.restaurant_address.clone(),
+            rating: None,
+            complaint: None,
+            created_at: now,
+            completed_at: None,
+        };
+        self.log_audit_event(
+            &tx,
+            "deliveries",
+            &delivery.id,
+            AuditOp::Insert,
+            None,
+            Some(&audit_json(&delivery)?),
+        )
+        .await?;
+        self.append_event(&tx, FleetEventType::DeliveryCreated, &delivery).await?;
+
+        tx.commit().await.context("create_delivery")?;
+        Ok(delivery)
+    }
+
+    /// Move a delivery to its next status
+    ///
+    /// Completing a delivery sets `completed_at` and, if given, records the
+    /// customer `rating` (1-5) in the same statement.
+    ///
+    /// # Errors
+    /// `InvalidData` if the delivery does not exist, the transition is not
+    /// `Upcoming → Ongoing` or `Ongoing → Completed`, or the rating is invalid
+    pub async fn update_delivery_status(
+        &self,
+        delivery_id: &str,
+        new_status: DeliveryStatus,
+        rating: Option<u8>,
+    ) -> Result<Delivery, DatabaseError> {
+        let mut client = self.pool.get().await.context("update_delivery_status")?;
+        let tx = client.transaction().await.context("update_delivery_status")?;
+        let mut delivery = self.fetch_delivery(&tx, delivery_id).await?.ok_or_else(|| {
+            DatabaseError::InvalidData(format!("Delivery not found: {}", delivery_id))
+        })?;
+        validate_status_update(&delivery.status, &new_status, rating)?;
+
+        let completed_at = (new_status == DeliveryStatus::Completed).then(Utc::now);
+
+        // Compare-and-set on the old status, so a concurrent update can't
+        // be overwritten with a transition validated against stale data
+        let updated = tx
+            .execute(
+                r#"UPDATE deliveries
+                   SET status = $1,
+                       completed_at = COALESCE($2, completed_at),
+                       rating = COALESCE($3, rating)
+                   WHERE id = $4 AND status = $5"#,
+                &[
+                    &new_status.as_str(),
+                    &completed_at,
+                    &rating.map(|r| r as i32),
+                    &delivery_id,
+                    &delivery.status.as_str(),
+                ],
+            )
+            .await
+            .context("update_delivery_status")?;
+        if updated == 0 {
+            return Err(DatabaseError::InvalidData("Invalid status transition".to_string()));
+        }
+
+        let old = audit_json(&delivery)?;
+        delivery.status = new_status;
+        delivery.completed_at = completed_at.or(delivery.completed_at);
+        delivery.rating = rating.or(delivery.rating);
+        self.log_audit_event(
+            &tx,
+            "deliveries",
+            delivery_id,
+            AuditOp::Update,
+            Some(&old),
+            Some(&audit_json(&delivery)?),
+        )
+        .await?;
+        if delivery.status == DeliveryStatus::Completed {
+            self.append_event(&tx, FleetEventType::DeliveryCompleted, &delivery).await?;
+        }
+
+        tx.commit().await.context("update_delivery_status")?;
+        Ok(delivery)
+    }
+
+    /// Delete a delivery
+    ///
+    /// With `cascade`, the `ON DELETE SET NULL` foreign key turns referencing
+    /// issues into standalone issues; without it, such issues block the
+    /// deletion.
+    ///
+    /// # Errors
+    /// `InvalidData` if the delivery does not exist or has issues and
+    /// `cascade` is false
+    pub async fn delete_delivery(
+        &self,
+        delivery_id: &str,
+        cascade: bool,
+    ) -> Result<(), DatabaseError> {
+        let mut client = self.pool.get().await.context("delete_delivery")?;
+        let tx = client.transaction().await.context("delete_delivery")?;
+
+        let old = self.fetch_delivery(&tx, delivery_id).await?.ok_or_else(|| {
+            DatabaseError::InvalidData(format!("Delivery not found: {}", delivery_id))
+        })?;
+        let rows = tx
+            .query(
+                r#"SELECT id, delivery_id, bike_id, reporter_type, category,
+                          description, resolved, created_at, updated_at,
+                          resolved_at, resolution_note
+                   FROM issues WHERE delivery_id = $1"#,
+                &[&delivery_id],
+            )
+            .await
+            .context("delete_delivery")?;
+        let linked_issues: Vec<Issue> = rows.iter().map(|row| self.map_issue_row(row)).collect();
+        if !cascade && !linked_issues.is_empty() {
+            return Err(DatabaseError::InvalidData(
+                "Cannot delete delivery with associated issues. Resolve them first.".to_string(),
+            ));
+        }
+
+        tx.execute("DELETE FROM delivery_waypoints WHERE delivery_id = $1", &[&delivery_id])
+            .await
+            .context("delete_delivery")?;
+        tx.execute("DELETE FROM deliveries WHERE id = $1", &[&delivery_id])
+            .await
+            .context("delete_delivery")?;
+        for issue in &linked_issues {
+            self.audit_issue_update(&tx, issue).await?;
+        }
+        self.log_audit_event(
+            &tx,
+            "deliveries",
+            delivery_id,
+            AuditOp::Delete,
+            Some(&audit_json(&old)?),
+            None,
+        )
+        .await?;
+
+        tx.commit().await.context("delete_delivery")?;
+        Ok(())
+    }
+
+    /// Append a GPS point to a delivery's route
+    ///
+    /// # Returns
+    /// ID of the new waypoint
+    ///
+    /// # Errors
+    /// `InvalidData` if the delivery does not exist or the coordinates are
+    /// out of range
+    pub async fn add_waypoint(
+        &self,
+        delivery_id: &str,
+        lat: f64,
+        lon: f64,
+    ) -> Result<String, DatabaseError> {
+        if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+            return Err(DatabaseError::InvalidData(format!(
+                "Invalid coordinates: {}, {}",
+                lat, lon
+            )));
+        }
+        if self.get_delivery_by_id(delivery_id).await?.is_none() {
+            return Err(DatabaseError::InvalidData(format!(
+                "Delivery not found: {}",
+                delivery_id
+            )));
+        }
+
+        let client = self.pool.get().await.context("add_waypoint")?;
+        let id = format!("WPT-{}", uuid_v4_simple());
+        // The unique (delivery_id, sequence) index rejects a racing insert
+        // that computed the same sequence
+        client
+            .execute(
+                r#"INSERT INTO delivery_waypoints (id, delivery_id, sequence, latitude, longitude, recorded_at)
+                   SELECT $1, $2, COALESCE(MAX(sequence), 0) + 1, $3, $4, NOW()
+                   FROM delivery_waypoints WHERE delivery_id = $2"#,
+                &[&id, &delivery_id, &lat, &lon],
+            )
+            .await
+            .context("add_waypoint")?;
+        Ok(id)
+    }
+
+    /// Waypoints of a delivery in the order they were recorded
+    pub async fn get_waypoints(&self, delivery_id: &str) -> Result<Vec<Waypoint>, DatabaseError> {
+        let client = self.pool.get().await.context("get_waypoints")?;
+
+        let rows = client
+            .query(
+                r#"SELECT id, delivery_id, sequence, latitude, longitude, recorded_at
+                   FROM delivery_waypoints WHERE delivery_id = $1
+                   ORDER BY sequence"#,
+                &[&delivery_id],
+            )
+            .await
+            .context("get_waypoints")?;
+
+        Ok(rows
+            .iter()
+            .map(|row| Waypoint {
+                id: row.get(0),
+                delivery_id: row.get(1),
+                sequence: row.get::<_, i32>(2) as u32,
+                latitude: row.get(3),
+                longitude: row.get(4),
+                recorded_at: row.get(5),
+            })
+            .collect())
+    }
+
+    // ========================================================================
+    // Issue Queries
+    // ========================================================================
+
+    /// Get one page of issues, optionally filtered, newest first by default
+    ///
+    /// `from`, `to`, `sort_by`, `sort_dir`, `cursor` and `limit` work as in
+    /// `get_deliveries`.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn get_issues(
+        &self,
+        bike_id: Option<&str>,
+        resolved: Option<bool>,
+        category: Option<&str>,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        sort_by: Option<IssueSort>,
+        sort_dir: Option<SortDirection>,
+        cursor: Option<&str>,
+        limit: u32,
+    ) -> Result<IssuePage, DatabaseError> {
+        validate_page_limit(limit)?;
+        let after = cursor.map(decode_cursor).transpose()?;
+
+        let client = self.pool.get().await.context("get_issues")?;
+        validate_date_range(from, to)?;
+        let (filters, params) = issue_filters(bike_id, resolved, category, from, to);
+        let param_refs: Vec<&(dyn ToSql + Sync)> =
+            params.iter().map(|p| p.as_ref() as &(dyn ToSql + Sync)).collect();
+        let row = client
+            .query_one(
+                &format!("SELECT COUNT(*)::INTEGER FROM issues WHERE true{}", filters),
+                &param_refs,
+            )
+            .await
+            .context("get_issues")?;
+        let total_count = row.get::<_, i32>(0) as u32;
+
+        let sort_by = sort_by.unwrap_or_default();
+        let sort_dir = sort_dir.unwrap_or_else(|| sort_by.default_direction());
+        let issues = self
+            .query_issues(
+                issue_filters(bike_id, resolved, category, from, to),
+                (sort_by, sort_dir),
+                after.as_ref(),
+                Some(limit + 1),
+            )
+            .await?;
+
+        Ok(Page::from_rows(issues, limit, total_count, |issue| PageCursor {
+            created_at: issue.created_at,
+            id: issue.id.clone(),
+        }))
+    }
+
+    /// Issues matching the filters after `after`, up to `limit` rows
+    async fn query_issues(
+        &self,
+        (filters, mut params): SqlFilters,
+        (sort_by, sort_dir): (IssueSort, SortDirection),
+        after: Option<&PageCursor>,
+        limit: Option<u32>,
+    ) -> Result<Vec<Issue>, DatabaseError> {
+        let client = self.pool.get().await.context("get_issues")?;
+        let column = issue_sort_column(sort_by);
+
+        let mut sql = format!(
+            r#"SELECT id, delivery_id, bike_id, reporter_type, category,
+                      description, resolved, created_at, updated_at,
+                      resolved_at, resolution_note
+               FROM issues WHERE true{}"#,
+            filters
+        );
+
+        if let Some(after) = after {
+            params.push(Box::new(after.created_at));
+            params.push(Box::new(after.id.clone()));
+            sql.push_str(&keyset_after_cursor(
+                "issues",
+                column,
+                None,
+                sort_dir,
+                params.len() - 1,
+                params.len(),
+            ));
+        }
+        sql.push_str(&keyset_order_by(column, false, sort_dir));
+        if let Some(limit) = limit {
+            sql.push_str(&format!(" LIMIT {}", limit));
+        }
+
+        let param_refs: Vec<&(dyn ToSql + Sync)> =
+            params.iter().map(|p| p.as_ref() as &(dyn ToSql + Sync)).collect();
+        let rows = client
+            .query(&sql, &param_refs)
+            .await
+            .context("get_issues")?;
+
+        let issues = rows.iter().map(|row| self.map_issue_row(row)).collect();
+        Ok(issues)
+    }
+
+    /// Get a single issue by ID
+    pub async fn get_issue_by_id(&self, issue_id: &str) -> Result<Option<Issue>, DatabaseError> {
+        let client = self.pool.get().await.context("get_issue_by_id")?;
+        self.fetch_issue(&client, issue_id).await
+    }
+
+    /// `get_issue_by_id` on `client`, e.g. inside a transaction
+    async fn fetch_issue(
+        &self,
+        client: &impl GenericClient,
+        issue_id: &str,
+    ) -> Result<Option<Issue>, DatabaseError> {
+        let row = client
+            .query_opt(
+                r#"SELECT id, delivery_id, bike_id, reporter_type, category,
+                          description, resolved, created_at, updated_at,
+                          resolved_at, resolution_note
+                   FROM issues WHERE id = $1"#,
+                &[&issue_id],
+            )
+            .await
+            .context("get_issue_by_id")?;
+
+        Ok(row.map(|r| self.map_issue_row(&r)))
+    }
+
+    /// Get issues for a specific bike (for force graph)
+    pub async fn get_issues_by_bike(&self, bike_id: &str) -> Result<Vec<Issue>, DatabaseError> {
+        self.query_issues(
+            issue_filters(Some(bike_id), None, None, None, None),
+            (IssueSort::CreatedAt, SortDirection::Desc),
+            None,
+            None,
+        )
+        .await
+    }
+
+    fn map_issue_row(&self, row: &tokio_postgres::Row) -> Issue {
+        let reporter_str: String = row.get("reporter_type");
+        let category_str: String = row.get("category");
+
+        Issue {
+            id: row.get("id"),
+            delivery_id: row.get("delivery_id"),
+            bike_id: row.get("bike_id"),
+            reporter_type: IssueReporterType::from_str(&reporter_str)
+                .unwrap_or(IssueReporterType::Customer),
+            category: IssueCategory::from_str(&category_str).unwrap_or(IssueCategory::Other),
+            description: row.get("description"),
+            resolved: row.get("resolved"),
+            resolved_at: row.get("resolved_at"),
+            resolution_note: row.get("resolution_note"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+        }
+    }
+
+    /// Mark an issue as resolved, with an optional resolution note
+    ///
+    /// Stamps `resolved_at` and `updated_at`. Resolving an already resolved
+    /// issue keeps its original timestamp and note.
+    pub async fn resolve_issue(
+        &self,
+        issue_id: &str,
+        note: Option<&str>,
+    ) -> Result<Issue, DatabaseError> {
+        let mut client = self.pool.get().await.context("resolve_issue")?;
+        let tx = client.transaction().await.context("resolve_issue")?;
+        let old = self
+            .fetch_issue(&tx, issue_id)
+            .await?
+            .ok_or_else(|| DatabaseError::InvalidData(format!("Issue not found: {}", issue_id)))?;
+
+        let updated = tx
+            .execute(
+                r#"UPDATE issues SET resolved = TRUE, resolved_at = NOW(), updated_at = NOW(),
+                                     resolution_note = $1
+                   WHERE id = $2 AND NOT resolved"#,
+                &[&note, &issue_id],
+            )
+            .await.context("resolve_issue")?;
+
+        let issue = if updated > 0 {
+            let issue = self.audit_issue_update(&tx, &old).await?;
+            self.append_event(&tx, FleetEventType::IssueResolved, &issue).await?;
+            issue
+        } else {
+            old
+        };
+
+        tx.commit().await.context("resolve_issue")?;
+        Ok(issue)
+    }
+
+    /// Reopen a resolved issue, clearing its resolution time and note
+    ///
+    /// Reopening an unresolved issue changes nothing.
+    pub async fn reopen_issue(&self, issue_id: &str) -> Result<Issue, DatabaseError> {
+        let mut client = self.pool.get().await.context("reopen_issue")?;
+        let tx = client.transaction().await.context("reopen_issue")?;
+        let old = self
+            .fetch_issue(&tx, issue_id)
+            .await?
+            .ok_or_else(|| DatabaseError::InvalidData(format!("Issue not found: {}", issue_id)))?;
+
+        let updated = tx
+            .execute(
+                r#"UPDATE issues SET resolved = FALSE, resolved_at = NULL, resolution_note = NULL,
+                                     updated_at = NOW()
+                   WHERE id = $1 AND resolved"#,
+                &[&issue_id],
+            )
+            .await
+            .context("reopen_issue")?;
+        let issue = if updated > 0 {
+            self.audit_issue_update(&tx, &old).await?
+        } else {
+            old
+        };
+
+        tx.commit().await.context("reopen_issue")?;
+        Ok(issue)
+    }
+
+    /// Resolve many issues at once, returning how many were actually resolved
+    ///
+    /// Unknown or already resolved IDs are skipped. Batches must hold 1-100 IDs.
+    pub async fn batch_resolve_issues(&self, issue_ids: &[&str]) -> Result<u32, DatabaseError> {
+        self.set_issues_resolved(issue_ids, true, "batch_resolve_issues").await
+    }
+
+    /// Reopen many resolved issues at once, returning how many were reopened
+    pub async fn batch_reopen_issues(&self, issue_ids: &[&str]) -> Result<u32, DatabaseError> {
+        self.set_issues_resolved(issue_ids, false, "batch_reopen_issues").await
+    }
+
+    /// Flip `resolved` for a batch of issues, counting only rows that changed
+    async fn set_issues_resolved(
+        &self,
+        issue_ids: &[&str],
+        resolved: bool,
+        operation: &str,
+    ) -> Result<u32, DatabaseError> {
+        validate_issue_batch(issue_ids)?;
+
+        let mut client = self.pool.get().await.context(operation)?;
+        let tx = client.transaction().await.context(operation)?;
+
+        // Row locks keep the audited rows and the updated rows the same
+        let rows = tx
+            .query(
+                r#"SELECT id, delivery_id, bike_id, reporter_type, category,
+                          description, resolved, created_at, updated_at,
+                          resolved_at, resolution_note
+                   FROM issues
+                   WHERE resolved <> $1 AND id IN (SELECT unnest($2::text[]))
+                   FOR UPDATE"#,
+                &[&resolved, &issue_ids],
+            )
+            .await
+            .context(operation)?;
+        let changed: Vec<Issue> = rows.iter().map(|row| self.map_issue_row(row)).collect();
+
+        let updated = tx
+            .execute(
+                r#"UPDATE issues SET resolved = $1, updated_at = NOW(),
+                                     resolved_at = CASE WHEN $1 THEN NOW() END,
+                                     resolution_note = NULL
+                   WHERE resolved <> $1 AND id IN (SELECT unnest($2::text[]))"#,
+                &[&resolved, &issue_ids],
+            )
+            .await
+            .context(operation)?;
+        for issue in &changed {
+            let issue = self.audit_issue_update(&tx, issue).await?;
+            if resolved {
+                self.append_event(&tx, FleetEventType::IssueResolved, &issue).await?;
+            }
+        }
+
+        tx.commit().await.context(operation)?;
+        Ok(updated as u32)
+    }
+
+    /// Report a new issue, standalone or linked to a delivery
+    ///
+    /// The description is trimmed; a missing category is suggested from it.
+    ///
+    /// # Errors
+    /// `InvalidData` if the description is blank, the bike does not exist,
+    /// or the delivery does not exist or belongs to another bike
+    pub async fn create_issue(&self, req: &CreateIssueRequest) -> Result<Issue, DatabaseError> {
+        let description = req.description.trim();
+        if description.is_empty() {
+            return Err(DatabaseError::InvalidData(
+                "Issue description must not be empty".to_string(),
+            ));
+        }
+        if self.get_bike_by_id(&req.bike_id).await?.is_none() {
+            return Err(DatabaseError::InvalidData(format!(
+                "Bike not found: {}",
+                req.bike_id
+            )));
+        }
+        if let Some(delivery_id) = &req.delivery_id {
+            match self.get_delivery_by_id(delivery_id).await? {
+                None => {
+                    return Err(DatabaseError::InvalidData(format!(
+                        "Delivery not found: {}",
+                        delivery_id
+                    )))
+                }
+                Some(delivery) if delivery.bike_id != req.bike_id => {
+                    return Err(DatabaseError::InvalidData(format!(
+                        "Delivery {} belongs to {}, not {}",
+                        delivery_id, delivery.bike_id, req.bike_id
+                    )))
+                }
+                Some(_) => {}
+            }
+        }
+
+        let id = format!("ISS-{}", uuid_v4_simple());
+        let category = category_or_suggested(req.category.clone(), description);
+        let now = Utc::now();
+
+        let mut client = self.pool.get().await.context("create_issue")?;
+        let tx = client.transaction().await.context("create_issue")?;
+        tx.execute(
+            r#"INSERT INTO issues (id, delivery_id, bike_id, reporter_type, category,
+               description, resolved, created_at, updated_at)
+               VALUES ($1, $2, $3, $4, $5, $6, FALSE, $7, $7)"#,
+            &[
+                &id,
+                &req.delivery_id,
+                &req.bike_id,
+                &req.reporter_type.as_str(),
+                &category.as_str(),
+                &description,
+                &now,
+            ],
+        )
+        .await
+        .context("create_issue")?;
+
+        let issue = Issue {
+            id,
+            delivery_id: req.delivery_id.clone(),
+            bike_id: req.bike_id.clone(),
+            reporter_type: req.reporter_type.clone(),
+            category,
+            description: description.to_string(),
+            resolved: false,
+            resolved_at: None,
+            resolution_note: None,
+            created_at: now,
+            updated_at: now,
+        };
+        self.log_audit_event(
+            &tx,
+            "issues",
+            &issue.id,
+            AuditOp::Insert,
+            None,
+            Some(&audit_json(&issue)?),
+        )
+        .await?;
+        self.append_event(&tx, FleetEventType::IssueFiled, &issue).await?;
+
+        tx.commit().await.context("create_issue")?;
+        Ok(issue)
+    }
+
+    /// Delete an issue
+    ///
+    /// # Errors
+    /// `InvalidData` if the issue does not exist
+    pub async fn delete_issue(&self, issue_id: &str) -> Result<(), DatabaseError> {
+        let mut client = self.pool.get().await.context("delete_issue")?;
+        let tx = client.transaction().await.context("delete_issue")?;
+        let old = self
+            .fetch_issue(&tx, issue_id)
+            .await?
+            .ok_or_else(|| DatabaseError::InvalidData(format!("Issue not found: {}", issue_id)))?;
+
+        tx.execute("DELETE FROM issues WHERE id = $1", &[&issue_id])
+            .await
+            .context("delete_issue")?;
+        self.log_audit_event(
+            &tx,
+            "issues",
+            issue_id,
+            AuditOp::Delete,
+            Some(&audit_json(&old)?),
+            None,
+        )
+        .await?;
+
+        tx.commit().await.context("delete_issue")?;
+        Ok(())
+    }
+
+    // ========================================================================
+    // Audit Log
+    // ========================================================================
+
+    /// Record a change to a row of `table` in the audit log
+    ///
+    /// `old` and `new` are the row as JSON before and after the change.
+    /// Pass the transaction that made the change, so both commit together.
+    pub async fn log_audit_event(
+        &self,
+        client: &impl GenericClient,
+        table: &str,
+        row_id: &str,
+        op: AuditOp,
+        old: Option<&str>,
+        new: Option<&str>,
+    ) -> Result<(), DatabaseError> {
+        client
+            .execute(
+                r#"INSERT INTO audit_log (id, table_name, row_id, operation, old_value_json,
+                   new_value_json, occurred_at)
+                   VALUES ($1, $2, $3, $4, $5, $6, NOW())"#,
+                &[
+                    &format!("AUD-{}", uuid_v4_simple()),
+                    &table,
+                    &row_id,
+                    &op.as_str(),
+                    &old,
+                    &new,
+                ],
+            )
+            .await
+            .context("log_audit_event")?;
+        Ok(())
+    }
+
+    /// Audit an update of `old`'s row
+    ///
+    /// # Returns
+    /// The bike as it is now; None if it is retired
+    async fn audit_bike_update(
+        &self,
+        client: &impl GenericClient,
+        old: &Bike,
+    ) -> Result<Option<Bike>, DatabaseError> {
+        let new = self.fetch_bike(client, &old.id).await?;
+        if let Some(new) = &new {
+            self.log_audit_event(
+                client,
+                "bikes",
+                &old.id,
+                AuditOp::Update,
+                Some(&audit_json(old)?),
+                Some(&audit_json(new)?),
+            )
+            .await?;
+        }
+        Ok(new)
+    }
+
+    /// Audit an update of `old`'s row
+    ///
+    /// # Returns
+    /// The issue as it is now
+    async fn audit_issue_update(
+        &self,
+        client: &impl GenericClient,
+        old: &Issue,
+    ) -> Result<Issue, DatabaseError> {
+        let new = self
+            .fetch_issue(client, &old.id)
+            .await?
+            .ok_or_else(|| DatabaseError::InvalidData(format!("Issue not found: {}", old.id)))?;
+        self.log_audit_event(
+            client,
+            "issues",
+            &old.id,
+            AuditOp::Update,
+            Some(&audit_json(old)?),
+            Some(&audit_json(&new)?),
+        )
+        .await?;
+        Ok(new)
+    }
+
+    /// Get audit log entries, oldest first
+    ///
+    /// Every filter is optional; `from` is inclusive, `to` exclusive.
+    pub async fn get_audit_log(
+        &self,
+        table: Option<&str>,
+        row_id: Option<&str>,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<AuditLogEntry>, DatabaseError> {
+        let client = self.pool.get().await.context("get_audit_log")?;
+
+        let rows = client
+            .query(
+                r#"SELECT id, table_name, row_id, operation, old_value_json, new_value_json,
+                          occurred_at
+                   FROM audit_log
+                   WHERE ($1::text IS NULL OR table_name = $1)
+                     AND ($2::text IS NULL OR row_id = $2)
+                     AND ($3::timestamptz IS NULL OR occurred_at >= $3)
+                     AND ($4::timestamptz IS NULL OR occurred_at < $4)
+                   ORDER BY occurred_at, id"#,
+                &[&table, &row_id, &from, &to],
+            )
+            .await
+            .context("get_audit_log")?;
+
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let operation: String = row.get("operation");
+                AuditLogEntry {
+                    id: row.get("id"),
+                    table_name: row.get("table_name"),
+                    row_id: row.get("row_id"),
+                    operation: AuditOp::from_str(&operation).unwrap_or(AuditOp::Update),
+                    old_value_json: row.get("old_value_json"),
+                    new_value_json: row.get("new_value_json"),
+                    occurred_at: row.get("occurred_at"),
+                }
+            })
+            .collect())
+    }
+
+    // ========================================================================
+    // Fleet Events
+    // ========================================================================
+
+    /// Append an event to the fleet event log
+    ///
+    /// Pass the transaction that made the change, so both commit together.
+    ///
+    /// # Returns
+    /// ID of the event; later events always get higher IDs
+    pub async fn append_event(
+        &self,
+        client: &impl GenericClient,
+        event_type: FleetEventType,
+        payload: &(impl serde::Serialize + Sync),
+    ) -> Result<u64, DatabaseError> {
+        let row = client
+            .query_one(
+                "INSERT INTO fleet_events (event_type, payload_json, occurred_at) VALUES ($1, $2, NOW()) RETURNING id",
+                &[&event_type.as_str(), &audit_json(payload)?],
+            )
+            .await
+            .context("append_event")?;
+        Ok(row.get::<_, i64>("id") as u64)
+    }
+
+    /// Log the current state of a bike whose status just changed
+    async fn append_bike_status_event(
+        &self,
+        client: &impl GenericClient,
+        bike_id: &str,
+    ) -> Result<(), DatabaseError> {
+        if let Some(bike) = self.fetch_bike(client, bike_id).await? {
+            self.append_event(client, FleetEventType::BikeStatusChanged, &bike).await?;
+        }
+        Ok(())
+    }
+
+    /// Get up to `limit` events after `last_id`, oldest first
+    ///
+    /// Pass the ID of the last event seen, or 0 for the start of the log.
+    ///
+    /// # Errors
+    /// `InvalidData` if `limit` is 0 or above `MAX_PAGE_SIZE`
+    pub async fn get_events_since(
+        &self,
+        last_id: u64,
+        limit: u32,
+    ) -> Result<Vec<FleetEvent>, DatabaseError> {
+        validate_page_limit(limit)?;
+
+        let client = self.pool.get().await.context("get_events_since")?;
+        let rows = client
+            .query(
+                r#"SELECT id, event_type, payload_json, occurred_at
+                   FROM fleet_events WHERE id > $1
+                   ORDER BY id LIMIT $2"#,
+                &[&(last_id as i64), &(limit as i64)],
+            )
+            .await
+            .context("get_events_since")?;
+
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let event_type: String = row.get("event_type");
+                FleetEvent {
+                    id: row.get::<_, i64>("id") as u64,
+                    event_type: FleetEventType::from_str(&event_type)
+                        .unwrap_or(FleetEventType::BikeStatusChanged),
+                    payload_json: row.get("payload_json"),
+                    occurred_at: row.get("occurred_at"),
+                }
+            })
+            .collect())
+    }
+
+    // ========================================================================
+    // Force Graph Pins
+    // ========================================================================
+
+    /// Pin a node of a deliverer's force graph at `(x, y)`, replacing any
+    /// earlier pin of the same node
+    pub async fn save_node_pin(
+        &self,
+        bike_id: &str,
+        node_id: &str,
+        x: f64,
+        y: f64,
+    ) -> Result<(), DatabaseError> {
+        let client = self.pool.get().await.context("save_node_pin")?;
+
+        client
+            .execute(
+                r#"INSERT INTO force_graph_pins (bike_id, node_id, x, y)
+                   VALUES ($1, $2, $3, $4)
+                   ON CONFLICT (bike_id, node_id) DO UPDATE SET x = EXCLUDED.x, y = EXCLUDED.y"#,
+                &[&bike_id, &node_id, &x, &y],
+            )
+            .await
+            .context("save_node_pin")?;
+        Ok(())
+    }
+
+    /// Pinned nodes of a deliverer's force graph, as (node ID, x, y)
+    pub async fn get_node_pins(
+        &self,
+        bike_id: &str,
+    ) -> Result<Vec<(String, f64, f64)>, DatabaseError> {
+        let client = self.pool.get().await.context("get_node_pins")?;
+
+        let rows = client
+            .query(
+                "SELECT node_id, x, y FROM force_graph_pins WHERE bike_id = $1 ORDER BY node_id",
+                &[&bike_id],
+            )
+            .await
+            .context("get_node_pins")?;
+
+        Ok(rows
+            .iter()
+            .map(|row| (row.get("node_id"), row.get("x"), row.get("y")))
+            .collect())
+    }
+
+    /// Unpin every node of a deliverer's force graph
+    pub async fn clear_node_pins(&self, bike_id: &str) -> Result<(), DatabaseError> {
+        let client = self.pool.get().await.context("clear_node_pins")?;
+
+        client
+            .execute("DELETE FROM force_graph_pins WHERE bike_id = $1", &[&bike_id])
+            .await
+            .context("clear_node_pins")?;
+        Ok(())
+    }
+
     // ========================================================================
-    // Issue Queries
+    // Search
     // ========================================================================
 
-    /// Get all issues, optionally filtered
-    pub async fn get_issues(
+    /// Search bike names and delivery customer and restaurant fields
+    ///
+    /// Case-insensitive substring match; `%` and `_` in `query` match
+    /// themselves. At most `limit` bikes and `limit` deliveries are returned.
+    pub async fn search(&self, query: &str, limit: u32) -> Result<SearchResults, DatabaseError> {
+        validate_page_limit(limit)?;
+        let query = escape_like(query)?;
+        let client = self.pool.get().await.context("search")?;
+
+        let rows = client
+            .query(
+                r#"SELECT id, name, status, latitude, longitude, battery_level,
+                          last_maintenance, total_trips, total_distance_km, created_at, updated_at,
+                          reservation_expires_at, maintenance_threshold_override, deleted_at,
+                          next_maintenance_at
+                   FROM bikes
+                   WHERE deleted_at IS NULL AND name ILIKE '%' || $1 || '%' ESCAPE '\'
+                   ORDER BY name LIMIT $2"#,
+                &[&query, &(limit as i64)],
+            )
+            .await
+            .context("search")?;
+        let bikes = rows.iter().map(|row| self.map_bike_row(row)).collect();
+
+        let rows = client
+            .query(
+                r#"SELECT id, bike_id, status, customer_name, customer_address,
+                          restaurant_name, restaurant_address, rating, complaint,
+                          created_at, completed_at, priority
+                   FROM deliveries
+                   WHERE customer_name ILIKE '%' || $1 || '%' ESCAPE '\'
+                      OR customer_address ILIKE '%' || $1 || '%' ESCAPE '\'
+                      OR restaurant_name ILIKE '%' || $1 || '%' ESCAPE '\'
+                   ORDER BY created_at DESC, id DESC LIMIT $2"#,
+                &[&query, &(limit as i64)],
+            )
+            .await
+            .context("search")?;
+        let deliveries = rows.iter().map(|row| self.map_delivery_row(row)).collect();
+
+        Ok(SearchResults { bikes, deliveries })
+    }
+
+    // ========================================================================
+    // Analytics
+    // ========================================================================
+
+    /// Correlate per-bike issue counts with average delivery ratings
+    ///
+    /// # Why a CTE?
+    /// - Issue counts and rating averages are aggregated independently,
+    ///   avoiding the row multiplication of a direct double join
+    /// - Pearson itself is computed in Rust (shared with SQLite backend)
+    pub async fn get_issue_rating_correlation(
         &self,
-        bike_id: Option<&str>,
-        resolved: Option<bool>,
-        category: Option<&str>,
-    ) -> Result<Vec<Issue>, DatabaseError> {
-        let client = self.pool.get().await?;
+        days: u32,
+    ) -> Result<IssueRatingCorrelation, DatabaseError> {
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("get_issue_rating_correlation")?;
 
-        let mut sql = String::from(
-            r#"SELECT id, delivery_id, bike_id, reporter_type, category,
-                      description, resolved, created_at
-               FROM issues WHERE true"#,
-        );
+        let rows = client
+            .query(
+                r#"WITH issue_counts AS (
+                       SELECT bike_id, COUNT(*)::INTEGER AS issue_count
+                       FROM issues
+                       WHERE created_at >= NOW() - make_interval(days => $1)
+                       GROUP BY bike_id
+                   ),
+                   rating_averages AS (
+                       SELECT bike_id, AVG(rating)::DOUBLE PRECISION AS average_rating
+                       FROM deliveries
+                       WHERE rating IS NOT NULL
+                         AND created_at >= NOW() - make_interval(days => $1)
+                       GROUP BY bike_id
+                   )
+                   SELECT b.id AS bike_id,
+                          COALESCE(ic.issue_count, 0) AS issue_count,
+                          ra.average_rating
+                   FROM bikes b
+                   LEFT JOIN issue_counts ic ON ic.bike_id = b.id
+                   LEFT JOIN rating_averages ra ON ra.bike_id = b.id
+                   ORDER BY b.id"#,
+                &[&(days as i32)],
+            )
+            .await
+            .context("get_issue_rating_correlation")?;
+
+        let by_bike = rows
+            .iter()
+            .map(|row| BikeCorrelationPoint {
+                bike_id: row.get("bike_id"),
+                issue_count: row.get::<_, i32>("issue_count") as u32,
+                average_rating: row.get("average_rating"),
+            })
+            .collect();
+
+        Ok(analytics::issue_rating_correlation(by_bike))
+    }
+
+    /// Issue categories ranked by frequency, with resolution figures
+    ///
+    /// Resolution time is `updated_at - created_at` of resolved issues.
+    pub async fn get_top_issue_categories(
+        &self,
+        limit: u32,
+        days: Option<u32>,
+    ) -> Result<Vec<IssueCategoryStats>, DatabaseError> {
+        let client = self.pool.get().await.context("get_top_issue_categories")?;
 
-        let mut params: Vec<Box<dyn ToSql + Sync + Send>> = Vec::new();
-        let mut param_idx = 1;
+        let rows = client
+            .query(
+                r#"SELECT category,
+                          COUNT(*)::INTEGER AS count,
+                          SUM(CASE WHEN resolved THEN 1 ELSE 0 END)::INTEGER AS resolved_count,
+                          (AVG(EXTRACT(EPOCH FROM (updated_at - created_at)) / 3600)
+                              FILTER (WHERE resolved))::DOUBLE PRECISION AS avg_resolution_time_hours
+                   FROM issues
+                   WHERE $2::INTEGER IS NULL
+                      OR created_at >= NOW() - make_interval(days => $2::INTEGER)
+                   GROUP BY category
+                   ORDER BY count DESC, category
+                   LIMIT $1"#,
+                &[&(limit as i64), &days.map(|d| d as i32)],
+            )
+            .await.context("get_top_issue_categories")?;
+
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let category_str: String = row.get("category");
+                let count = row.get::<_, i32>("count") as u32;
+                let resolved_count = row.get::<_, i32>("resolved_count") as u32;
+
+                IssueCategoryStats {
+                    category: IssueCategory::from_str(&category_str).unwrap_or(IssueCategory::Other),
+                    count,
+                    resolved_count,
+                    resolution_rate: resolved_count as f64 / count as f64,
+                    avg_resolution_time_hours: row.get("avg_resolution_time_hours"),
+                }
+            })
+            .collect())
+    }
 
-        if let Some(b) = bike_id {
-            sql.push_str(&format!(" AND bike_id = ${}", param_idx));
-            params.push(Box::new(b.to_string()));
-            param_idx += 1;
-        }
-        if let Some(r) = resolved {
-            sql.push_str(&format!(" AND resolved = ${}", param_idx));
-            params.push(Box::new(r));
-            param_idx += 1;
-        }
-        if let Some(c) = category {
-            sql.push_str(&format!(" AND category = ${}", param_idx));
-            params.push(Box::new(c.to_string()));
+    /// Issue counts, breakdowns and resolution times, of one bike or the fleet
+    pub async fn get_issue_statistics(
+        &self,
+        bike_id: Option<&str>,
+    ) -> Result<IssueStatistics, DatabaseError> {
+        let client = self.pool.get().await.context("get_issue_statistics")?;
+
+        let totals = client
+            .query_one(
+                r#"SELECT COUNT(*)::INTEGER AS total_issues,
+                          (COUNT(*) FILTER (WHERE resolved))::INTEGER AS resolved_count,
+                          (AVG(EXTRACT(EPOCH FROM (resolved_at - created_at)) / 86400)
+                              FILTER (WHERE resolved))::DOUBLE PRECISION AS avg_resolution_days,
+                          (MAX(EXTRACT(EPOCH FROM (NOW() - created_at)) / 86400)
+                              FILTER (WHERE NOT resolved))::DOUBLE PRECISION AS oldest_unresolved_days
+                   FROM issues
+                   WHERE $1::TEXT IS NULL OR bike_id = $1"#,
+                &[&bike_id],
+            )
+            .await
+            .context("get_issue_statistics")?;
+
+        let mut by_category = HashMap::new();
+        let mut by_reporter = HashMap::new();
+        for (column, counts) in [("category", &mut by_category), ("reporter_type", &mut by_reporter)] {
+            let sql = format!(
+                "SELECT {0} AS key, COUNT(*)::INTEGER AS count FROM issues
+                 WHERE $1::TEXT IS NULL OR bike_id = $1 GROUP BY {0}",
+                column
+            );
+            let rows = client
+                .query(&sql, &[&bike_id])
+                .await
+                .context("get_issue_statistics")?;
+            counts.extend(
+                rows.iter()
+                    .map(|row| (row.get::<_, String>("key"), row.get::<_, i32>("count") as u32)),
+            );
         }
-        sql.push_str(" ORDER BY created_at DESC");
 
-        let param_refs: Vec<&(dyn ToSql + Sync)> =
-            params.iter().map(|p| p.as_ref() as &(dyn ToSql + Sync)).collect();
-        let rows = client.query(&sql, &param_refs).await?;
+        let total_issues = totals.get::<_, i32>("total_issues") as u32;
+        let resolved_count = totals.get::<_, i32>("resolved_count") as u32;
 
-        let issues = rows.iter().map(|row| self.map_issue_row(row)).collect();
-        Ok(issues)
+        Ok(IssueStatistics {
+            total_issues,
+            resolved_count,
+            unresolved_count: total_issues - resolved_count,
+            resolution_rate_pct: if total_issues == 0 {
+                0.0
+            } else {
+                resolved_count as f64 / total_issues as f64 * 100.0
+            },
+            by_category,
+            by_reporter,
+            avg_resolution_days: totals
+                .get::<_, Option<f64>>("avg_resolution_days")
+                .unwrap_or(0.0),
+            oldest_unresolved_days: totals.get("oldest_unresolved_days"),
+        })
     }
 
-    /// Get a single issue by ID
-    pub async fn get_issue_by_id(&self, issue_id: &str) -> Result<Option<Issue>, DatabaseError> {
-        let client = self.pool.get().await?;
+    /// Delivery figures of one restaurant
+    ///
+    /// # Errors
+    /// `InvalidData` if the restaurant does not exist
+    pub async fn get_restaurant_stats(
+        &self,
+        restaurant_id: &str,
+    ) -> Result<RestaurantStats, DatabaseError> {
+        let client = self.pool.get().await.context("get_restaurant_stats")?;
 
+        let sql = format!("{} WHERE r.id = $1", RESTAURANT_STATS_SELECT);
         let row = client
-            .query_opt(
-                r#"SELECT id, delivery_id, bike_id, reporter_type, category,
-                          description, resolved, created_at
-                   FROM issues WHERE id = $1"#,
-                &[&issue_id],
-            )
-            .await?;
+            .query_opt(&sql, &[&restaurant_id])
+            .await
+            .context("get_restaurant_stats")?
+            .ok_or_else(|| {
+                DatabaseError::InvalidData(format!("Restaurant not found: {}", restaurant_id))
+            })?;
+
+        Ok(self.map_restaurant_stats_row(&row))
+    }
 
-        Ok(row.map(|r| self.map_issue_row(&r)))
+    /// Restaurants with the most deliveries, best rated first on ties
+    pub async fn get_top_restaurants(
+        &self,
+        limit: u32,
+    ) -> Result<Vec<RestaurantStats>, DatabaseError> {
+        let client = self.pool.get().await.context("get_top_restaurants")?;
+
+        let sql = format!(
+            "{} ORDER BY r.total_deliveries DESC, r.avg_rating DESC NULLS LAST, r.name LIMIT $1",
+            RESTAURANT_STATS_SELECT
+        );
+        let rows = client
+            .query(&sql, &[&(limit as i64)])
+            .await
+            .context("get_top_restaurants")?;
+
+        Ok(rows.iter().map(|row| self.map_restaurant_stats_row(row)).collect())
     }
 
-    /// Get issues for a specific bike (for force graph)
-    pub async fn get_issues_by_bike(&self, bike_id: &str) -> Result<Vec<Issue>, DatabaseError> {
-        self.get_issues(Some(bike_id), None, None).await
+    fn map_restaurant_stats_row(&self, row: &tokio_postgres::Row) -> RestaurantStats {
+        RestaurantStats {
+            id: row.get("id"),
+            name: row.get("name"),
+            address: row.get("address"),
+            total_deliveries: row.get::<_, i32>("total_deliveries") as u32,
+            completed_deliveries: row.get::<_, i32>("completed_deliveries") as u32,
+            complaint_count: row.get::<_, i32>("complaint_count") as u32,
+            avg_rating: row.get("avg_rating"),
+        }
     }
 
-    fn map_issue_row(&self, row: &tokio_postgres::Row) -> Issue {
-        let reporter_str: String = row.get("reporter_type");
-        let category_str: String = row.get("category");
+    /// Position density grid for the map heatmap
+    ///
+    /// Bins current bike positions plus completed trip end positions
+    /// server-side, so only aggregated cells cross the network.
+    pub async fn get_fleet_heatmap(
+        &self,
+        resolution_meters: f64,
+    ) -> Result<Vec<HeatmapCell>, DatabaseError> {
+        if !resolution_meters.is_finite() || resolution_meters <= 0.0 {
+            return Err(DatabaseError::InvalidData(
+                "Heatmap resolution must be positive".to_string(),
+            ));
+        }
 
-        Issue {
-            id: row.get("id"),
-            delivery_id: row.get("delivery_id"),
-            bike_id: row.get("bike_id"),
-            reporter_type: IssueReporterType::from_str(&reporter_str)
-                .unwrap_or(IssueReporterType::Customer),
-            category: IssueCategory::from_str(&category_str).unwrap_or(IssueCategory::Other),
-            description: row.get("description"),
-            resolved: row.get("resolved"),
-            created_at: row.get("created_at"),
+        let bin = analytics::heatmap_bin_degrees(resolution_meters);
+        let client = self.pool.get().await.context("get_fleet_heatmap")?;
+
+        let rows = client
+            .query(
+                r#"WITH positions AS (
+                       SELECT latitude, longitude FROM bikes WHERE deleted_at IS NULL
+                       UNION ALL
+                       SELECT end_latitude, end_longitude FROM trips
+                       WHERE end_latitude IS NOT NULL AND end_longitude IS NOT NULL
+                   )
+                   SELECT ROUND(latitude / $1) * $1 AS lat_bin,
+                          ROUND(longitude / $1) * $1 AS lon_bin,
+                          COUNT(*) AS weight
+                   FROM positions
+                   GROUP BY lat_bin, lon_bin
+                   ORDER BY weight DESC, lat_bin, lon_bin"#,
+                &[&bin],
+            )
+            .await
+            .context("get_fleet_heatmap")?;
+
+        Ok(rows
+            .iter()
+            .map(|row| HeatmapCell {
+                center_lat: row.get("lat_bin"),
+                center_lon: row.get("lon_bin"),
+                weight: row.get::<_, i64>("weight") as f64,
+            })
+            .collect())
+    }
+
+    /// Fleet activity per `bucket` from `from` (inclusive) to `to` (exclusive)
+    ///
+    /// Buckets without any activity are included with zero values.
+    pub async fn get_fleet_time_series(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        bucket: TimeBucket,
+    ) -> Result<Vec<FleetTimeSeries>, DatabaseError> {
+        analytics::validate_time_series_range(from, to, bucket)
+            .map_err(DatabaseError::InvalidData)?;
+        let client = self.pool.get().await.context("get_fleet_time_series")?;
+
+        // Truncate in UTC; date_trunc on timestamptz would use the session time zone
+        let rows = client
+            .query(
+                r#"SELECT bucket,
+                          SUM(created)::BIGINT AS created,
+                          SUM(completed)::BIGINT AS completed,
+                          AVG(rating)::FLOAT8 AS rating,
+                          SUM(opened)::BIGINT AS opened,
+                          SUM(resolved)::BIGINT AS resolved,
+                          SUM(distance)::FLOAT8 AS distance
+                   FROM (
+                       SELECT date_trunc($3, created_at AT TIME ZONE 'UTC') AT TIME ZONE 'UTC' AS bucket,
+                              1 AS created, 0 AS completed, NULL::INTEGER AS rating,
+                              0 AS opened, 0 AS resolved, 0.0::FLOAT8 AS distance
+                       FROM deliveries WHERE created_at >= $1 AND created_at < $2
+                       UNION ALL
+                       SELECT date_trunc($3, completed_at AT TIME ZONE 'UTC') AT TIME ZONE 'UTC',
+                              0, 1, rating, 0, 0, 0.0
+                       FROM deliveries WHERE completed_at >= $1 AND completed_at < $2
+                       UNION ALL
+                       SELECT date_trunc($3, created_at AT TIME ZONE 'UTC') AT TIME ZONE 'UTC',
+                              0, 0, NULL, 1, 0, 0.0
+                       FROM issues WHERE created_at >= $1 AND created_at < $2
+                       UNION ALL
+                       SELECT date_trunc($3, resolved_at AT TIME ZONE 'UTC') AT TIME ZONE 'UTC',
+                              0, 0, NULL, 0, 1, 0.0
+                       FROM issues WHERE resolved_at >= $1 AND resolved_at < $2
+                       UNION ALL
+                       SELECT date_trunc($3, end_time AT TIME ZONE 'UTC') AT TIME ZONE 'UTC',
+                              0, 0, NULL, 0, 0, distance_km
+                       FROM trips
+                       WHERE end_time >= $1 AND end_time < $2 AND distance_km IS NOT NULL
+                   ) events
+                   GROUP BY bucket"#,
+                &[&from, &to, &bucket.as_str()],
+            )
+            .await
+            .context("get_fleet_time_series")?;
+
+        let measured = rows
+            .iter()
+            .map(|row| {
+                let period_start: DateTime<Utc> = row.get("bucket");
+                FleetTimeSeries {
+                    period_start,
+                    period_end: period_start + bucket.duration(),
+                    total_deliveries: row.get::<_, i64>("created") as u32,
+                    completed_deliveries: row.get::<_, i64>("completed") as u32,
+                    average_rating: row.get::<_, Option<f64>>("rating").unwrap_or(0.0),
+                    issues_opened: row.get::<_, i64>("opened") as u32,
+                    issues_resolved: row.get::<_, i64>("resolved") as u32,
+                    distance_km: row.get("distance"),
+                }
+            })
+            .collect();
+
+        Ok(analytics::fill_time_series(from, to, bucket, measured))
+    }
+
+    /// Active and completed deliveries per hour of the day (UTC), for
+    /// deliveries created from `from` (inclusive) to `to` (exclusive)
+    ///
+    /// Always returns all 24 hours, starting at midnight.
+    pub async fn get_hourly_utilization(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<HourlyUtilization>, DatabaseError> {
+        if from >= to {
+            return Err(DatabaseError::InvalidData(
+                "Utilization start must be before its end".to_string(),
+            ));
         }
+        let client = self.pool.get().await.context("get_hourly_utilization")?;
+
+        let fleet_size: i64 = client
+            .query_one("SELECT COUNT(*) FROM bikes WHERE deleted_at IS NULL", &[])
+            .await
+            .context("get_hourly_utilization")?
+            .get(0);
+
+        // Hour in UTC; EXTRACT on timestamptz would use the session time zone
+        let rows = client
+            .query(
+                r#"SELECT EXTRACT(HOUR FROM created_at AT TIME ZONE 'UTC')::INT AS hour,
+                          COUNT(*) FILTER (WHERE status = 'ongoing') AS active,
+                          COUNT(*) FILTER (WHERE status = 'completed') AS completed,
+                          COUNT(DISTINCT bike_id)
+                              FILTER (WHERE status IN ('ongoing', 'completed')) AS busy_bikes
+                   FROM deliveries
+                   WHERE created_at >= $1 AND created_at < $2
+                   GROUP BY hour"#,
+                &[&from, &to],
+            )
+            .await
+            .context("get_hourly_utilization")?;
+
+        let measured: Vec<(u8, u32, u32, u32)> = rows
+            .iter()
+            .map(|row| {
+                (
+                    row.get::<_, i32>("hour") as u8,
+                    row.get::<_, i64>("active") as u32,
+                    row.get::<_, i64>("completed") as u32,
+                    row.get::<_, i64>("busy_bikes") as u32,
+                )
+            })
+            .collect();
+
+        Ok(analytics::hourly_utilization(&measured, fleet_size as u32))
     }
 
     // ========================================================================
@@ -774,25 +3057,25 @@ impl Database {
 
     /// Get database statistics
     pub async fn get_stats(&self) -> Result<DatabaseStats, DatabaseError> {
-        let client = self.pool.get().await?;
+        let client = self.pool.get().await.context("get_stats")?;
 
         let total_bikes: i64 = client
-            .query_one("SELECT COUNT(*) FROM bikes", &[])
-            .await?
+            .query_one("SELECT COUNT(*) FROM bikes WHERE deleted_at IS NULL", &[])
+            .await
+            .context("get_stats")?
             .get(0);
 
         let total_trips: i64 = client
             .query_one("SELECT COALESCE(SUM(total_trips), 0) FROM bikes", &[])
-            .await?
+            .await
+            .context("get_stats")?
             .get(0);
 
         // Get database size (PostgreSQL specific)
         let db_size: i64 = client
-            .query_one(
-                "SELECT pg_database_size(current_database())",
-                &[],
-            )
-            .await?
+            .query_one("SELECT pg_database_size(current_database())", &[])
+            .await
+            .context("get_stats")?
             .get(0);
 
         Ok(DatabaseStats {
@@ -814,12 +3097,13 @@ impl Database {
     /// - Ok(false) if connected to replica (read-only)
     /// - Err if connection failed
     pub async fn health_check(&self) -> Result<bool, DatabaseError> {
-        let client = self.pool.get().await?;
+        let client = self.pool.get().await.context("health_check")?;
 
         // Check if we're on primary or replica
         let row = client
             .query_one("SELECT pg_is_in_recovery()", &[])
-            .await?;
+            .await
+            .context("health_check")?;
         let is_replica: bool = row.get(0);
 
         Ok(!is_replica) // Returns true if primary (not in recovery)
@@ -830,7 +3114,7 @@ impl Database {
     /// # Returns
     /// Replication lag in bytes, or None if not applicable
     pub async fn get_replication_lag(&self) -> Result<Option<i64>, DatabaseError> {
-        let client = self.pool.get().await?;
+        let client = self.pool.get().await.context("get_replication_lag")?;
 
         let row = client
             .query_opt(
@@ -839,10 +3123,353 @@ impl Database {
                    LIMIT 1"#,
                 &[],
             )
-            .await?;
+            .await
+            .context("get_replication_lag")?;
 
         Ok(row.map(|r| r.get("lag")))
     }
+
+    /// Current connection pool state, plus replication lag
+    ///
+    /// Never fails: the metrics matter most when the database is
+    /// unreachable, so an unavailable lag is reported as `None`.
+    pub async fn pool_metrics(&self) -> PoolMetrics {
+        let replication_lag_bytes = self.get_replication_lag().await.ok().flatten();
+        pool_metrics(self.pool.status(), &self.counters, replication_lag_bytes)
+    }
+
+    /// Sequential and index scan counts per table and per index
+    ///
+    /// Counts run from the last `pg_stat_reset()`, so compare two calls to
+    /// see what a workload does.
+    pub async fn table_stats(&self) -> Result<TableStats, DatabaseError> {
+        let client = self.pool.get().await.context("table_stats")?;
+
+        let tables = client
+            .query(
+                r#"SELECT relname, seq_scan, COALESCE(idx_scan, 0) AS idx_scan,
+                          CASE WHEN seq_scan + COALESCE(idx_scan, 0) > 0
+                               THEN COALESCE(idx_scan, 0)::float8 / (seq_scan + COALESCE(idx_scan, 0))
+                          END AS index_hit_rate,
+                          n_live_tup
+                   FROM pg_stat_user_tables
+                   ORDER BY seq_scan + COALESCE(idx_scan, 0) DESC, relname"#,
+                &[],
+            )
+            .await
+            .context("table_stats")?
+            .iter()
+            .map(|row| TableScanStats {
+                table_name: row.get("relname"),
+                seq_scan: row.get("seq_scan"),
+                idx_scan: row.get("idx_scan"),
+                index_hit_rate: row.get("index_hit_rate"),
+                live_rows: row.get("n_live_tup"),
+            })
+            .collect();
+
+        let indexes = client
+            .query(
+                r#"SELECT relname, indexrelname, idx_scan
+                   FROM pg_stat_user_indexes
+                   ORDER BY idx_scan DESC, relname, indexrelname"#,
+                &[],
+            )
+            .await
+            .context("table_stats")?
+            .iter()
+            .map(|row| IndexScanStats {
+                table_name: row.get("relname"),
+                index_name: row.get("indexrelname"),
+                idx_scan: row.get("idx_scan"),
+            })
+            .collect();
+
+        Ok(TableStats { tables, indexes })
+    }
+}
+
+fn pool_metrics(
+    status: Status,
+    counters: &PoolCounters,
+    replication_lag_bytes: Option<i64>,
+) -> PoolMetrics {
+    PoolMetrics {
+        pool_size: status.size,
+        available: status.available,
+        waiting: status.waiting,
+        max_size: status.max_size,
+        total_acquired: counters.acquired.load(Ordering::Relaxed),
+        total_recycled: counters.recycled.load(Ordering::Relaxed),
+        replication_lag_bytes,
+    }
+}
+
+/// Largest batch accepted by batch issue updates; larger sets should be paged
+const MAX_ISSUE_BATCH: usize = 100;
+
+/// Reject a zero-day maintenance threshold
+fn validate_maintenance_threshold(threshold: Option<u32>) -> Result<(), DatabaseError> {
+    if threshold == Some(0) {
+        return Err(DatabaseError::InvalidData(
+            "Maintenance threshold must be at least 1 day".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Reject empty or oversized issue batches
+fn validate_issue_batch(issue_ids: &[&str]) -> Result<(), DatabaseError> {
+    if issue_ids.is_empty() {
+        return Err(DatabaseError::InvalidData("No issue IDs given".to_string()));
+    }
+    if issue_ids.len() > MAX_ISSUE_BATCH {
+        return Err(DatabaseError::InvalidData(format!(
+            "Too many issue IDs: {} (max {})",
+            issue_ids.len(),
+            MAX_ISSUE_BATCH
+        )));
+    }
+    Ok(())
+}
+
+/// Columns of `RestaurantStats`; callers append the WHERE/ORDER BY
+const RESTAURANT_STATS_SELECT: &str = r#"SELECT r.id, r.name, r.address, r.total_deliveries,
+       (SELECT COUNT(*)::INTEGER FROM deliveries d
+        WHERE d.restaurant_id = r.id AND d.status = 'completed') AS completed_deliveries,
+       (SELECT COUNT(*)::INTEGER FROM deliveries d
+        WHERE d.restaurant_id = r.id AND d.complaint IS NOT NULL) AS complaint_count,
+       r.avg_rating
+FROM restaurants r"#;
+
+/// Sort key putting urgent deliveries first
+const PRIORITY_RANK: &str = "CASE priority WHEN 'urgent' THEN 0 WHEN 'high' THEN 1 ELSE 2 END";
+
+/// Serialize a row for the audit log
+fn audit_json<T: serde::Serialize>(row: &T) -> Result<String, DatabaseError> {
+    serde_json::to_string(row).map_err(|e| DatabaseError::InvalidData(e.to_string()))
+}
+
+/// Escape a search query for `ILIKE ... ESCAPE '\'`
+fn escape_like(query: &str) -> Result<String, DatabaseError> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Err(DatabaseError::InvalidData("Search query cannot be empty".to_string()));
+    }
+    Ok(query
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_"))
+}
+
+/// Sort column of a delivery listing
+///
+/// Nullable columns also return the value standing in for NULL when a row is
+/// compared against the cursor.
+fn delivery_sort_column(sort_by: DeliverySort) -> (&'static str, Option<&'static str>) {
+    match sort_by {
+        DeliverySort::CreatedAt => ("created_at", None),
+        DeliverySort::CompletedAt => ("completed_at", Some("'-infinity'")),
+        DeliverySort::Status => ("status", None),
+        DeliverySort::Rating => ("rating", Some("0")),
+        DeliverySort::Priority => (PRIORITY_RANK, None),
+    }
+}
+
+/// Sort column of an issue listing
+fn issue_sort_column(sort_by: IssueSort) -> &'static str {
+    match sort_by {
+        IssueSort::CreatedAt => "created_at",
+        IssueSort::Category => "category",
+        IssueSort::Resolved => "resolved",
+    }
+}
+
+/// Sort column of a bike listing
+fn bike_sort_column(sort_by: BikeSort) -> &'static str {
+    match sort_by {
+        BikeSort::Name => "name",
+        BikeSort::Battery => "battery_level",
+        BikeSort::TotalTrips => "total_trips",
+        BikeSort::UpdatedAt => "updated_at",
+    }
+}
+
+/// ORDER BY clause for a keyset-paginated listing sorted on `column`
+///
+/// NULLs of a nullable column come last in either direction. `created_at`
+/// and `id` break ties; the final `id` makes the order total, which keyset
+/// pagination relies on.
+fn keyset_order_by(column: &str, nullable: bool, dir: SortDirection) -> String {
+    let d = dir.as_sql();
+    if column == "created_at" {
+        return format!(" ORDER BY created_at {d}, id {d}");
+    }
+    let nulls_last = if nullable { " NULLS LAST" } else { "" };
+    format!(" ORDER BY {column} {d}{nulls_last}, created_at {d}, id {d}")
+}
+
+/// Keyset condition for the rows of `table` after the cursor
+///
+/// `${c}` and `${i}` bind the cursor's `created_at` and `id`. Other sort keys
+/// are read from the cursor's row; if that row has been deleted, sorts on
+/// columns other than `created_at` end the listing. `null_fill` stands in for
+/// NULL in a nullable column, where all NULL rows tie.
+fn keyset_after_cursor(
+    table: &str,
+    column: &str,
+    null_fill: Option<&str>,
+    dir: SortDirection,
+    c: usize,
+    i: usize,
+) -> String {
+    let op = match dir {
+        SortDirection::Asc => ">",
+        SortDirection::Desc => "<",
+    };
+    if column == "created_at" {
+        return format!(" AND (created_at, id) {op} (${c}, ${i})");
+    }
+    let cursor_value = |expr: &str| format!("(SELECT {expr} FROM {table} WHERE id = ${i})");
+
+    match null_fill {
+        None => format!(
+            " AND ({column}, created_at, id) {op} ({}, ${c}, ${i})",
+            cursor_value(column)
+        ),
+        Some(fill) => {
+            let is_null = format!("({column} IS NULL)");
+            let value = format!("COALESCE({column}, {fill})");
+            format!(
+                " AND ({is_null} > {n} OR ({is_null} = {n} AND ({value}, created_at, id) {op} ({v}, ${c}, ${i})))",
+                n = cursor_value(&is_null),
+                v = cursor_value(&value)
+            )
+        }
+    }
+}
+
+/// WHERE fragment (appended to `WHERE true`) and its parameters
+type SqlFilters = (String, Vec<Box<dyn ToSql + Sync + Send>>);
+
+/// WHERE fragment and parameters for the delivery filters
+fn delivery_filters(
+    bike_id: Option<&str>,
+    status: Option<&str>,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> SqlFilters {
+    let mut sql = String::new();
+    let mut params: Vec<Box<dyn ToSql + Sync + Send>> = Vec::new();
+
+    if let Some(b) = bike_id {
+        params.push(Box::new(b.to_string()));
+        sql.push_str(&format!(" AND bike_id = ${}", params.len()));
+    }
+    if let Some(s) = status {
+        params.push(Box::new(s.to_string()));
+        sql.push_str(&format!(" AND status = ${}", params.len()));
+    }
+    push_created_range(&mut sql, &mut params, from, to);
+
+    (sql, params)
+}
+
+/// WHERE fragment and parameters for the issue filters
+fn issue_filters(
+    bike_id: Option<&str>,
+    resolved: Option<bool>,
+    category: Option<&str>,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> SqlFilters {
+    let mut sql = String::new();
+    let mut params: Vec<Box<dyn ToSql + Sync + Send>> = Vec::new();
+
+    if let Some(b) = bike_id {
+        params.push(Box::new(b.to_string()));
+        sql.push_str(&format!(" AND bike_id = ${}", params.len()));
+    }
+    if let Some(r) = resolved {
+        params.push(Box::new(r));
+        sql.push_str(&format!(" AND resolved = ${}", params.len()));
+    }
+    if let Some(c) = category {
+        params.push(Box::new(c.to_string()));
+        sql.push_str(&format!(" AND category = ${}", params.len()));
+    }
+    push_created_range(&mut sql, &mut params, from, to);
+
+    (sql, params)
+}
+
+/// Append an inclusive `created_at` window to a filter fragment
+fn push_created_range(
+    sql: &mut String,
+    params: &mut Vec<Box<dyn ToSql + Sync + Send>>,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) {
+    if let Some(from) = from {
+        params.push(Box::new(from));
+        sql.push_str(&format!(" AND created_at >= ${}", params.len()));
+    }
+    if let Some(to) = to {
+        params.push(Box::new(to));
+        sql.push_str(&format!(" AND created_at <= ${}", params.len()));
+    }
+}
+
+/// Reject a window that ends before it starts
+fn validate_date_range(
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> Result<(), DatabaseError> {
+    if let (Some(from), Some(to)) = (from, to) {
+        if from > to {
+            return Err(DatabaseError::InvalidData(
+                "Date range start must not be after its end".to_string(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Reject page sizes outside 1..=MAX_PAGE_SIZE
+fn validate_page_limit(limit: u32) -> Result<(), DatabaseError> {
+    if limit == 0 || limit > MAX_PAGE_SIZE {
+        return Err(DatabaseError::InvalidData(format!(
+            "Page limit must be between 1 and {}",
+            MAX_PAGE_SIZE
+        )));
+    }
+    Ok(())
+}
+
+fn decode_cursor(cursor: &str) -> Result<PageCursor, DatabaseError> {
+    PageCursor::decode(cursor).ok_or_else(|| DatabaseError::InvalidData("Invalid cursor".to_string()))
+}
+
+/// Check a delivery status change and its optional rating
+///
+/// A rating (1-5) may only accompany the transition to `Completed`.
+fn validate_status_update(
+    current: &DeliveryStatus,
+    new_status: &DeliveryStatus,
+    rating: Option<u8>,
+) -> Result<(), DatabaseError> {
+    if !current.can_transition_to(new_status) {
+        return Err(DatabaseError::InvalidData("Invalid status transition".to_string()));
+    }
+    match rating {
+        Some(_) if *new_status != DeliveryStatus::Completed => Err(DatabaseError::InvalidData(
+            "A rating can only be recorded when completing a delivery".to_string(),
+        )),
+        Some(r) if !(1..=5).contains(&r) => Err(DatabaseError::InvalidData(
+            "Rating must be between 1 and 5".to_string(),
+        )),
+        _ => Ok(()),
+    }
 }
 
 /// Generate a simple UUID-like string
@@ -872,3 +3499,146 @@ pub async fn create_shared_database(config: DatabaseConfig) -> Result<SharedData
     let db = Database::new(config).await?;
     Ok(Arc::new(db))
 }
+
+// ============================================================================
+// Real-time bike status events (LISTEN/NOTIFY)
+// ============================================================================
+
+/// Channel notified by the `bikes` status trigger (migration 7)
+pub const BIKE_STATUS_CHANNEL: &str = "bike_status_changes";
+
+/// Receives the bike status changes made through any connection
+///
+/// # Why a dedicated connection?
+/// A LISTEN belongs to its session. On a pooled client it would end when
+/// the client is recycled, and its notifications would go to whichever
+/// caller borrowed the client next.
+pub struct EventSubscriber {
+    /// Never read, but dropping it would close the connection
+    _client: tokio_postgres::Client,
+    notifications: tokio::sync::mpsc::UnboundedReceiver<tokio_postgres::Notification>,
+}
+
+impl EventSubscriber {
+    /// Open a connection and LISTEN on `BIKE_STATUS_CHANNEL`
+    pub async fn connect(config: &DatabaseConfig) -> Result<Self, DatabaseError> {
+        let mut pg_config = tokio_postgres::Config::new();
+        pg_config
+            .host(&config.host)
+            .port(config.port)
+            .user(&config.user)
+            .password(&config.password)
+            .dbname(&config.dbname);
+
+        let (sender, notifications) = tokio::sync::mpsc::unbounded_channel();
+        let client = match config.effective_ssl_mode() {
+            SslMode::Disable => {
+                let (client, connection) =
+                    pg_config.connect(NoTls).await.context("subscribe_bike_events")?;
+                tokio::spawn(forward_notifications(connection, sender));
+                client
+            }
+            #[cfg(feature = "postgres-tls")]
+            mode => {
+                let tls = tls_connector(mode, config.ssl_ca_cert_path.as_deref())?;
+                let (client, connection) =
+                    pg_config.connect(tls).await.context("subscribe_bike_events")?;
+                tokio::spawn(forward_notifications(connection, sender));
+                client
+            }
+            #[cfg(not(feature = "postgres-tls"))]
+            mode => {
+                return Err(DatabaseError::TlsError(format!(
+                    "SSL mode {:?} requires a build with the postgres-tls feature",
+                    mode
+                )))
+            }
+        };
+
+        client
+            .batch_execute(&format!("LISTEN {}", BIKE_STATUS_CHANNEL))
+            .await
+            .context("subscribe_bike_events")?;
+
+        Ok(Self {
+            _client: client,
+            notifications,
+        })
+    }
+
+    /// Wait for the next status change
+    ///
+    /// Notifications with an unreadable payload are skipped.
+    ///
+    /// # Returns
+    /// `None` once the connection has closed
+    pub async fn next_event(&mut self) -> Option<BikeStatusEvent> {
+        while let Some(notification) = self.notifications.recv().await {
+            if let Some(event) = BikeStatusEvent::from_notification(notification.payload()) {
+                return Some(event);
+            }
+        }
+        None
+    }
+}
+
+/// Drive `connection`, passing on its notifications until it closes or
+/// the subscriber is dropped
+async fn forward_notifications<S, T>(
+    mut connection: tokio_postgres::Connection<S, T>,
+    sender: tokio::sync::mpsc::UnboundedSender<tokio_postgres::Notification>,
+) where
+    S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
+    T: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
+{
+    use futures::StreamExt;
+
+    let mut messages = futures::stream::poll_fn(move |cx| connection.poll_message(cx));
+    while let Some(message) = messages.next().await {
+        match message {
+            Ok(tokio_postgres::AsyncMessage::Notification(notification)) => {
+                if sender.send(notification).is_err() {
+                    break;
+                }
+            }
+            Ok(_) => {}
+            Err(error) => {
+                tracing::warn!(%error, "bike status event connection failed");
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pool_metrics_from_status() {
+        let status = Status {
+            max_size: 16,
+            size: 8,
+            available: 3,
+            waiting: 2,
+        };
+        let counters = PoolCounters::default();
+        counters.acquired.store(40, Ordering::Relaxed);
+        counters.recycled.store(32, Ordering::Relaxed);
+
+        let metrics = pool_metrics(status, &counters, Some(1024));
+
+        assert_eq!(
+            metrics,
+            PoolMetrics {
+                pool_size: 8,
+                available: 3,
+                waiting: 2,
+                max_size: 16,
+                total_acquired: 40,
+                total_recycled: 32,
+                replication_lag_bytes: Some(1024),
+            }
+        );
+    }
+}
@@ -0,0 +1,42 @@
+//! Geographic Helpers
+//!
+//! # Purpose
+//! Distance math shared by the SQLite and PostgreSQL backends. Mirrors
+//! the functions in `wasm-lib`, which the backend cannot depend on.
+
+/// Mean Earth radius in kilometers
+pub const EARTH_RADIUS_KM: f64 = 6371.0;
+
+/// Great-circle distance between two coordinates in kilometers
+///
+/// Uses the haversine formula; accurate to ~0.5% since it assumes a
+/// spherical Earth, which is plenty for city-scale trips.
+pub fn haversine_km(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let lat1_rad = lat1.to_radians();
+    let lat2_rad = lat2.to_radians();
+    let delta_lat = (lat2 - lat1).to_radians();
+    let delta_lon = (lon2 - lon1).to_radians();
+
+    let a = (delta_lat / 2.0).sin().powi(2)
+        + lat1_rad.cos() * lat2_rad.cos() * (delta_lon / 2.0).sin().powi(2);
+    let c = 2.0 * a.sqrt().atan2((1.0 - a).sqrt());
+
+    EARTH_RADIUS_KM * c
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_haversine_same_point_is_zero() {
+        assert_eq!(haversine_km(52.3791, 4.9003, 52.3791, 4.9003), 0.0);
+    }
+
+    #[test]
+    fn test_haversine_centraal_to_dam() {
+        // Amsterdam Centraal to Dam Square, roughly 0.8 km apart
+        let distance = haversine_km(52.3791, 4.9003, 52.3731, 4.8932);
+        assert!((distance - 0.82).abs() < 0.05, "got {}", distance);
+    }
+}
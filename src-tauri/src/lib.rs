@@ -23,19 +23,38 @@
 //! - Session keys derived from license key (HKDF)
 //! - No algorithms exposed to browser
 
+mod analytics;
+pub mod audit;
+mod bike_import;
+pub mod cache;
+pub mod categorization;
 mod commands;
 pub mod crypto;
+mod csv_export;
+mod geo;
 pub mod license;
+pub mod middleware;
+mod migrations;
 mod models;
+pub mod rate_limiter;
+mod simulation;
+#[cfg(feature = "sqlite")]
+mod slow_query_log;
+#[cfg(feature = "sqlite")]
+mod sync_queue;
 
 // Database backend selection via feature flags
 #[cfg(feature = "sqlite")]
 mod database;
 #[cfg(feature = "sqlite")]
-pub use database::Database;
+pub use database::{Database, SqliteConfig};
 
 #[cfg(feature = "postgres")]
 mod database_pg;
+#[cfg(any(feature = "postgres", feature = "pg-migration"))]
+mod pg_config;
+#[cfg(feature = "pg-migration")]
+mod migration_tool;
 #[cfg(feature = "postgres")]
 pub use database_pg::{Database, DatabaseConfig, SharedDatabase};
 
@@ -46,16 +65,37 @@ use std::sync::Mutex;
 // Application State
 // ============================================================================
 
+/// Days between maintenances for bikes without their own threshold
+const DEFAULT_MAINTENANCE_THRESHOLD_DAYS: u32 = 90;
+
+/// Distinct queries kept in the SQLite backend's query cache
+#[cfg(feature = "sqlite")]
+const QUERY_CACHE_MAX_ENTRIES: usize = 32;
+
+/// How long a cached query result is served before the database is asked again
+#[cfg(feature = "sqlite")]
+const QUERY_CACHE_TTL: std::time::Duration = std::time::Duration::from_secs(5);
+
 /// Application state for SQLite backend (synchronous)
 #[cfg(feature = "sqlite")]
 pub struct AppState {
     pub db: Mutex<Option<database::Database>>,
+    /// Fleet-wide maintenance threshold in days
+    pub maintenance_threshold: u32,
+    /// Secure sessions holding a license seat
+    pub seats: license::SeatRegistry,
+    /// Results of hot read queries; see `commands::fleet::get_fleet_data`
+    pub cache: Mutex<cache::QueryCache<String, cache::CachedPayload>>,
 }
 
 /// Application state for PostgreSQL backend (async with connection pool)
 #[cfg(feature = "postgres")]
 pub struct AppState {
     pub db: Mutex<Option<database_pg::SharedDatabase>>,
+    /// Fleet-wide maintenance threshold in days
+    pub maintenance_threshold: u32,
+    /// Secure sessions holding a license seat
+    pub seats: license::SeatRegistry,
 }
 
 // ============================================================================
@@ -67,19 +107,49 @@ pub struct AppState {
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
+        .plugin(tauri_plugin_dialog::init())
         // Core application state
         .manage(AppState {
             db: Mutex::new(None),
+            maintenance_threshold: DEFAULT_MAINTENANCE_THRESHOLD_DAYS,
+            seats: license::SeatRegistry::default(),
+            cache: Mutex::new(cache::QueryCache::new(QUERY_CACHE_MAX_ENTRIES, QUERY_CACHE_TTL)),
         })
+        // Running force graph animations (cancellation flags)
+        .manage(commands::force_graph::LayoutAnimations::default())
+        .manage(commands::force_graph_shared::ForceGraphCache::default())
+        // Directories picked for database backup/restore
+        .manage(commands::database::BackupDirectories::default())
         // Secure session state (holds encryption context)
         .manage(SecureSessionState {
             crypto: Mutex::new(None),
+            session_id: Mutex::new(None),
+            license: std::sync::Arc::new(Mutex::new(None)),
+            middleware: Mutex::new(Vec::new()),
+            audit: Mutex::new(None),
+            rate_limiter: Mutex::new(rate_limiter::RateLimiter::default()),
+        })
+        .setup(|app| {
+            commands::fleet::spawn_reservation_sweeper(app.handle().clone());
+            Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             // Database initialization
             commands::database::init_database,
             commands::database::get_database_stats,
             commands::database::is_database_initialized,
+            commands::database::seed_custom_data,
+            commands::database::create_database_backup,
+            commands::database::list_database_backups,
+            commands::database::restore_from_backup,
+            commands::database::choose_backup_directory,
+            commands::database::backup_database,
+            commands::database::restore_database,
+            commands::database::flush_sync_queue,
+            #[cfg(feature = "pg-migration")]
+            commands::database::run_migration,
+            commands::database::set_sqlite_pragmas,
+            commands::database::get_slow_query_report,
 
             // Health check
             commands::health::health_check,
@@ -90,31 +160,92 @@ pub fn run() {
             commands::license::deactivate_license,
             commands::license::is_feature_licensed,
             commands::license::validate_license,
+            commands::license::export_license_report,
+
+            // Audit trail export
+            commands::audit::export_audit_trail_csv,
+            commands::audit::export_audit_trail_json,
 
             // Fleet data (legacy - direct commands)
             commands::fleet::get_fleet_data,
             commands::fleet::get_bike_by_id,
             commands::fleet::add_bike,
+            commands::fleet::import_bikes_from_csv,
             commands::fleet::update_bike_status,
+            commands::fleet::retire_bike,
+            commands::fleet::get_retired_bikes,
+            commands::fleet::start_trip,
+            commands::fleet::end_trip,
             commands::fleet::get_fleet_stats,
+            commands::fleet::reserve_bike,
+            commands::fleet::cancel_reservation,
+            commands::fleet::set_bike_maintenance_threshold,
+            commands::fleet::get_bikes_due_for_maintenance,
+            commands::fleet::get_overdue_maintenance,
+            commands::fleet::get_low_battery_bikes,
+            commands::fleet::get_fleet_time_series,
+            commands::fleet::get_hourly_utilization,
+            commands::fleet::get_restaurant_stats,
+            commands::fleet::get_top_restaurants,
+            commands::fleet::export_fleet_csv,
+            commands::fleet::get_audit_log,
+            commands::fleet::poll_events,
+            commands::fleet::search,
+            commands::fleet::get_bike_statistics,
+            commands::fleet::get_bikes_by_status,
+            commands::fleet::get_bikes_by_multiple_statuses,
 
             // Delivery commands (direct, for development)
             commands::deliveries::get_deliveries,
             commands::deliveries::get_delivery_by_id,
             commands::deliveries::get_deliveries_for_bike,
+            commands::deliveries::create_delivery,
+            commands::deliveries::update_delivery_status,
+            commands::deliveries::delete_delivery,
+            commands::deliveries::record_waypoint,
+            commands::deliveries::get_delivery_route,
+            commands::deliveries::export_deliveries_csv,
 
             // Issue commands (direct, for development)
             commands::issues::get_issues,
             commands::issues::get_issue_by_id,
+            commands::issues::get_issue_statistics,
             commands::issues::get_issues_for_bike,
+            commands::issues::resolve_issue,
+            commands::issues::reopen_issue,
+            commands::issues::batch_resolve_issues,
+            commands::issues::batch_reopen_issues,
+            commands::issues::create_issue,
+            commands::issues::delete_issue,
+
+            // Analytics commands
+            commands::analytics::get_issue_rating_correlation,
+            commands::analytics::get_fleet_heatmap,
+            commands::analytics::get_top_issue_categories,
+
+            // Simulation scenarios (QA and demos)
+            commands::simulation::apply_simulation_scenario,
 
             // Force graph commands (direct, for development)
             commands::force_graph::get_force_graph_layout,
             commands::force_graph::update_node_position,
+            commands::force_graph::start_animated_layout,
+            commands::force_graph::stop_animated_layout,
+            commands::force_graph::get_force_graph_diff,
+            commands::force_graph::export_force_graph_svg,
+            commands::force_graph::export_force_graph_graphml,
+            commands::force_graph::get_fleet_force_graph,
+            commands::force_graph::clear_node_pins,
 
             // Secure IPC (encrypted commands - production use)
             commands::secure::init_secure_session,
+            commands::secure::rotate_session,
+            commands::secure::close_session,
             commands::secure::secure_invoke,
+            commands::secure::measure_secure_roundtrip,
+            commands::secure::get_seat_status,
+            commands::secure::register_middleware,
+            commands::secure::get_middleware_report,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -133,17 +264,38 @@ pub fn run() {
         // Core application state (will be initialized by init_database command)
         .manage(AppState {
             db: Mutex::new(None),
+            maintenance_threshold: DEFAULT_MAINTENANCE_THRESHOLD_DAYS,
+            seats: license::SeatRegistry::default(),
         })
+        .manage(commands::force_graph_shared::ForceGraphCache::default())
+        // Real-time bike status listener (started by subscribe_bike_events)
+        .manage(commands::database_pg::BikeEventSubscription::default())
+        .manage(commands::database_pg::PoolMetricsMonitor::default())
         // Secure session state (holds encryption context)
         .manage(SecureSessionState {
             crypto: Mutex::new(None),
+            session_id: Mutex::new(None),
+            license: std::sync::Arc::new(Mutex::new(None)),
+            middleware: Mutex::new(Vec::new()),
+            audit: Mutex::new(None),
+            rate_limiter: Mutex::new(rate_limiter::RateLimiter::default()),
+        })
+        .setup(|app| {
+            commands::fleet_pg::spawn_reservation_sweeper(app.handle().clone());
+            commands::database_pg::spawn_pool_metrics_monitor(app.handle().clone());
+            Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             // Database initialization (PostgreSQL version)
             commands::database_pg::init_database,
             commands::database_pg::get_database_stats,
             commands::database_pg::is_database_initialized,
+            commands::database_pg::seed_custom_data,
             commands::database_pg::database_health_check,
+            commands::database_pg::warm_up_database_pool,
+            commands::database_pg::subscribe_bike_events,
+            commands::database_pg::get_pool_metrics,
+            commands::database_pg::analyze_table_stats,
 
             // Health check
             commands::health::health_check,
@@ -154,31 +306,90 @@ pub fn run() {
             commands::license::deactivate_license,
             commands::license::is_feature_licensed,
             commands::license::validate_license,
+            commands::license::export_license_report,
+
+            // Audit trail export
+            commands::audit::export_audit_trail_csv,
+            commands::audit::export_audit_trail_json,
 
             // Fleet data (PostgreSQL async versions)
             commands::fleet_pg::get_fleet_data,
             commands::fleet_pg::get_bike_by_id,
             commands::fleet_pg::add_bike,
+            commands::fleet_pg::import_bikes_from_csv,
             commands::fleet_pg::update_bike_status,
+            commands::fleet_pg::retire_bike,
+            commands::fleet_pg::get_retired_bikes,
+            commands::fleet_pg::start_trip,
+            commands::fleet_pg::end_trip,
             commands::fleet_pg::get_fleet_stats,
+            commands::fleet_pg::reserve_bike,
+            commands::fleet_pg::cancel_reservation,
+            commands::fleet_pg::set_bike_maintenance_threshold,
+            commands::fleet_pg::get_bikes_due_for_maintenance,
+            commands::fleet_pg::get_overdue_maintenance,
+            commands::fleet_pg::get_low_battery_bikes,
+            commands::fleet_pg::get_fleet_time_series,
+            commands::fleet_pg::get_hourly_utilization,
+            commands::fleet_pg::get_restaurant_stats,
+            commands::fleet_pg::get_top_restaurants,
+            commands::fleet_pg::export_fleet_csv,
+            commands::fleet_pg::get_audit_log,
+            commands::fleet_pg::poll_events,
+            commands::fleet_pg::search,
+            commands::fleet_pg::get_bike_statistics,
+            commands::fleet_pg::get_bikes_by_status,
+            commands::fleet_pg::get_bikes_by_multiple_statuses,
 
             // Delivery commands (PostgreSQL async versions)
             commands::deliveries_pg::get_deliveries,
             commands::deliveries_pg::get_delivery_by_id,
             commands::deliveries_pg::get_deliveries_for_bike,
+            commands::deliveries_pg::create_delivery,
+            commands::deliveries_pg::update_delivery_status,
+            commands::deliveries_pg::delete_delivery,
+            commands::deliveries_pg::record_waypoint,
+            commands::deliveries_pg::get_delivery_route,
+            commands::deliveries_pg::export_deliveries_csv,
 
             // Issue commands (PostgreSQL async versions)
             commands::issues_pg::get_issues,
             commands::issues_pg::get_issue_by_id,
+            commands::issues_pg::get_issue_statistics,
             commands::issues_pg::get_issues_for_bike,
+            commands::issues_pg::resolve_issue,
+            commands::issues_pg::reopen_issue,
+            commands::issues_pg::batch_resolve_issues,
+            commands::issues_pg::batch_reopen_issues,
+            commands::issues_pg::create_issue,
+            commands::issues_pg::delete_issue,
+
+            // Analytics commands (PostgreSQL async versions)
+            commands::analytics_pg::get_issue_rating_correlation,
+            commands::analytics_pg::get_fleet_heatmap,
+            commands::analytics_pg::get_top_issue_categories,
+
+            // Simulation scenarios (PostgreSQL async versions)
+            commands::simulation_pg::apply_simulation_scenario,
 
             // Force graph commands (PostgreSQL async versions)
             commands::force_graph_pg::get_force_graph_layout,
             commands::force_graph_pg::update_node_position,
+            commands::force_graph_pg::get_force_graph_diff,
+            commands::force_graph_pg::export_force_graph_svg,
+            commands::force_graph_pg::export_force_graph_graphml,
+            commands::force_graph_pg::get_fleet_force_graph,
+            commands::force_graph_pg::clear_node_pins,
 
             // Secure IPC (encrypted commands - production use)
             commands::secure::init_secure_session,
+            commands::secure::rotate_session,
+            commands::secure::close_session,
             commands::secure::secure_invoke,
+            commands::secure::measure_secure_roundtrip,
+            commands::secure::get_seat_status,
+            commands::secure::register_middleware,
+            commands::secure::get_middleware_report,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
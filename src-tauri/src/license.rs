@@ -1,16 +1,39 @@
-//! License verification module using Ed25519 signatures
+//! License verification module using Ed25519 or RSA-4096 signatures
 //!
 //! License keys are cryptographically signed payloads that can be verified offline.
 //! The private key is kept secret (in the license generator tool).
 //! Only the public key is embedded in this binary.
+//!
+//! # Key formats
+//! - v1: `base64(payload_json + ed25519_signature)`
+//! - v2: `base64(algorithm_byte + payload_json + signature)`, where the
+//!   byte is a `SignatureAlgorithm` (0x01 Ed25519, 0x02 RSA-4096 PSS)
+//!
+//! A v1 key starts with the payload's `{`, so both formats verify.
 
-use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use base64::{
+    engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD},
+    Engine,
+};
+use chacha20poly1305::{
+    aead::{Aead, KeyInit},
+    ChaCha20Poly1305, Nonce,
+};
 use chrono::{DateTime, Utc};
 use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use rsa::pkcs8::DecodePublicKey;
+use rsa::traits::PublicKeyParts;
+use rsa::RsaPublicKey;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 use thiserror::Error;
+use zeroize::Zeroizing;
 
 /// The Ed25519 public key for license verification (32 bytes, base64 encoded)
 /// IMPORTANT: This is the PUBLIC key - safe to embed in the binary.
@@ -19,19 +42,70 @@ use thiserror::Error;
 /// Generated with: cargo run --bin license-generator -- --generate-keys
 const PUBLIC_KEY_BASE64: &str = "9-olA_QuQjwR-cPw9ZmN_QnFSdJCUf4iTBhXXsNqbI0";
 
+/// The RSA-4096 public key for `SignatureAlgorithm::Rsa4096` licenses
+/// (SubjectPublicKeyInfo DER, URL-safe base64)
+///
+/// Generated with: cargo run --bin license-generator -- --generate-keys --algorithm rsa
+const RSA_PUBLIC_KEY_BASE64: &str = "REPLACE_WITH_YOUR_RSA_PUBLIC_KEY_BASE64_HERE";
+
 /// License key prefix for easy identification
 const LICENSE_PREFIX: &str = "ABF-";
 
+/// Product identifier licenses must be issued for
+const PRODUCT_ID: &str = "amsterdam-bike-fleet";
+
+/// HKDF info string for the license file key
+const STORAGE_HKDF_INFO: &[u8] = b"license-storage-v1";
+
+/// First bytes of a license file written by `LicenseStorage::save_encrypted`
+const ENCRYPTED_LICENSE_MAGIC: [u8; 4] = [0xAB, 0xF0, 0x01, 0x00];
+
+/// HKDF info string of the JSON license files written before the magic header
+const JSON_STORAGE_HKDF_INFO: &[u8] = b"amsterdam-bike-fleet-license-storage-v1";
+
+/// Version of the JSON license files written before the magic header
+const STORAGE_VERSION: u32 = 1;
+
+/// ChaCha20-Poly1305 nonce size (96 bits = 12 bytes)
+const STORAGE_NONCE_SIZE: usize = 12;
+
+/// Written next to the license file once it has been saved encrypted
+const LICENSE_MIGRATED_MARKER: &str = "license.migrated";
+
+/// Customer name marking the implicit trial license
+const TRIAL_CUSTOMER: &str = "TRIAL";
+
+/// Length of the trial, counted from the first run
+const TRIAL_DAYS: i64 = 30;
+
+/// Holds the first run's timestamp (RFC 3339), next to the license file
+const TRIAL_STARTED_FILE: &str = "trial.started";
+
+/// Features available without a license
+const TRIAL_FEATURES: [&str; 1] = ["basic"];
+
+/// License key a client passes to `init_secure_session` in trial mode
+pub const TRIAL_LICENSE_KEY: &str = "TRIAL";
+
+/// Sessions without a heartbeat for this long no longer hold a seat
+const SEAT_IDLE_TIMEOUT_MINUTES: i64 = 30;
+
 #[derive(Error, Debug)]
 pub enum LicenseError {
     #[error("Invalid license key format")]
     InvalidFormat,
 
-    #[error("Invalid base64 encoding: {0}")]
-    Base64Error(#[from] base64::DecodeError),
+    #[error("Base64Error while {operation}: {source}")]
+    Base64Error {
+        operation: String,
+        source: base64::DecodeError,
+    },
 
-    #[error("Invalid JSON payload: {0}")]
-    JsonError(#[from] serde_json::Error),
+    #[error("JsonError while {operation}: {source}")]
+    JsonError {
+        operation: String,
+        source: serde_json::Error,
+    },
 
     #[error("Invalid signature")]
     InvalidSignature,
@@ -39,20 +113,175 @@ pub enum LicenseError {
     #[error("License has expired (expired: {0})")]
     Expired(String),
 
+    /// Not fatal: the license expired but still works for this many days
+    #[error("License has expired; grace period ends in {0} days")]
+    InGracePeriod(i64),
+
     #[error("License is for a different product: {0}")]
     WrongProduct(String),
 
     #[error("Feature not included in license: {0}")]
     FeatureNotLicensed(String),
 
+    #[error("All {0} licensed seats are in use")]
+    SeatLimitExceeded(u32),
+
     #[error("Public key not configured")]
     PublicKeyNotConfigured,
 
     #[error("Invalid public key: {0}")]
     InvalidPublicKey(String),
 
-    #[error("License file error: {0}")]
-    FileError(String),
+    #[error("FileError while {operation}: {source}")]
+    FileError {
+        operation: String,
+        source: std::io::Error,
+    },
+}
+
+impl LicenseError {
+    /// Whether the license must be rejected (everything but `InGracePeriod`)
+    pub fn is_fatal(&self) -> bool {
+        !matches!(self, LicenseError::InGracePeriod(_))
+    }
+}
+
+/// Algorithm that signed a license, named by the first byte of a v2 key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureAlgorithm {
+    /// 64-byte signature; also every v1 key
+    Ed25519,
+    /// PSS padding with SHA-256, for customers whose policy requires RSA
+    Rsa4096,
+}
+
+impl SignatureAlgorithm {
+    pub fn version_byte(self) -> u8 {
+        match self {
+            SignatureAlgorithm::Ed25519 => 0x01,
+            SignatureAlgorithm::Rsa4096 => 0x02,
+        }
+    }
+
+    pub fn from_version_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0x01 => Some(SignatureAlgorithm::Ed25519),
+            0x02 => Some(SignatureAlgorithm::Rsa4096),
+            _ => None,
+        }
+    }
+}
+
+/// Checks license signatures made with one public key
+pub trait LicenseVerifier: Send + Sync {
+    /// `InvalidSignature` unless `sig` is a valid signature of `payload`
+    fn verify(&self, payload: &[u8], sig: &[u8]) -> Result<(), LicenseError>;
+
+    /// Length of the signature at the end of a license key
+    fn signature_len(&self) -> usize;
+}
+
+/// Ed25519 license verifier
+pub struct Ed25519Verifier {
+    key: VerifyingKey,
+}
+
+impl Ed25519Verifier {
+    /// Parse a URL-safe base64 public key (32 bytes)
+    pub fn from_base64(public_key_base64: &str) -> Result<Self, LicenseError> {
+        let public_key_bytes = URL_SAFE_NO_PAD
+            .decode(public_key_base64)
+            .map_err(|e| LicenseError::InvalidPublicKey(e.to_string()))?;
+
+        if public_key_bytes.len() != 32 {
+            return Err(LicenseError::InvalidPublicKey(format!(
+                "Expected 32 bytes, got {}",
+                public_key_bytes.len()
+            )));
+        }
+
+        let key = VerifyingKey::from_bytes(
+            public_key_bytes
+                .as_slice()
+                .try_into()
+                .map_err(|_| LicenseError::InvalidPublicKey("Invalid key bytes".to_string()))?,
+        )
+        .map_err(|e| LicenseError::InvalidPublicKey(e.to_string()))?;
+
+        Ok(Self { key })
+    }
+}
+
+impl LicenseVerifier for Ed25519Verifier {
+    fn verify(&self, payload: &[u8], sig: &[u8]) -> Result<(), LicenseError> {
+        let signature =
+            Signature::from_slice(sig).map_err(|_| LicenseError::InvalidSignature)?;
+        self.key
+            .verify(payload, &signature)
+            .map_err(|_| LicenseError::InvalidSignature)
+    }
+
+    fn signature_len(&self) -> usize {
+        ed25519_dalek::SIGNATURE_LENGTH
+    }
+}
+
+/// RSA-PSS (SHA-256) license verifier
+pub struct RsaPssVerifier {
+    key: rsa::pss::VerifyingKey<Sha256>,
+    signature_len: usize,
+}
+
+impl RsaPssVerifier {
+    /// Parse a URL-safe base64 SubjectPublicKeyInfo DER public key
+    pub fn from_base64(public_key_base64: &str) -> Result<Self, LicenseError> {
+        let der = URL_SAFE_NO_PAD
+            .decode(public_key_base64)
+            .map_err(|e| LicenseError::InvalidPublicKey(e.to_string()))?;
+        let key = RsaPublicKey::from_public_key_der(&der)
+            .map_err(|e| LicenseError::InvalidPublicKey(e.to_string()))?;
+
+        Ok(Self {
+            // Signatures are as long as the modulus: 512 bytes for RSA-4096
+            signature_len: key.size(),
+            key: rsa::pss::VerifyingKey::new(key),
+        })
+    }
+}
+
+impl LicenseVerifier for RsaPssVerifier {
+    fn verify(&self, payload: &[u8], sig: &[u8]) -> Result<(), LicenseError> {
+        let signature =
+            rsa::pss::Signature::try_from(sig).map_err(|_| LicenseError::InvalidSignature)?;
+        self.key
+            .verify(payload, &signature)
+            .map_err(|_| LicenseError::InvalidSignature)
+    }
+
+    fn signature_len(&self) -> usize {
+        self.signature_len
+    }
+}
+
+/// Verifier for the public key of `algorithm` embedded in this binary
+fn embedded_verifier(
+    algorithm: SignatureAlgorithm,
+) -> Result<Box<dyn LicenseVerifier>, LicenseError> {
+    match algorithm {
+        SignatureAlgorithm::Ed25519 => {
+            // Check placeholder hasn't been replaced
+            if PUBLIC_KEY_BASE64 == "REPLACE_WITH_YOUR_PUBLIC_KEY_BASE64_HERE" {
+                return Err(LicenseError::PublicKeyNotConfigured);
+            }
+            Ok(Box::new(Ed25519Verifier::from_base64(PUBLIC_KEY_BASE64)?))
+        }
+        SignatureAlgorithm::Rsa4096 => {
+            if RSA_PUBLIC_KEY_BASE64 == "REPLACE_WITH_YOUR_RSA_PUBLIC_KEY_BASE64_HERE" {
+                return Err(LicenseError::PublicKeyNotConfigured);
+            }
+            Ok(Box::new(RsaPssVerifier::from_base64(RSA_PUBLIC_KEY_BASE64)?))
+        }
+    }
 }
 
 /// Information embedded in a license key
@@ -83,6 +312,10 @@ pub struct LicenseInfo {
     #[serde(default)]
     pub issued: Option<String>,
 
+    /// Days the license keeps working after `expires` (optional)
+    #[serde(default)]
+    pub grace_period_days: Option<u32>,
+
     /// License version (for future format changes)
     #[serde(default = "default_version")]
     pub version: u32,
@@ -92,27 +325,62 @@ fn default_version() -> u32 {
     1
 }
 
+/// A license valid until 2099 with exactly `features`, for tests
+#[cfg(test)]
+pub(crate) fn license_with(features: &[&str]) -> LicenseInfo {
+    LicenseInfo {
+        customer: "test@example.com".to_string(),
+        company: None,
+        product: PRODUCT_ID.to_string(),
+        expires: "2099-12-31".to_string(),
+        features: features.iter().map(|f| f.to_string()).collect(),
+        seats: None,
+        issued: None,
+        grace_period_days: None,
+        version: default_version(),
+    }
+}
+
 impl LicenseInfo {
-    /// Check if the license has expired
+    /// Implicit license while none is installed: basic features only,
+    /// expiring 30 days after `started`. Never saved to disk; see
+    /// `EncryptedLicenseStorage::trial` for the persisted first run.
+    pub fn trial(started: DateTime<Utc>) -> LicenseInfo {
+        LicenseInfo {
+            customer: TRIAL_CUSTOMER.to_string(),
+            company: None,
+            product: PRODUCT_ID.to_string(),
+            expires: (started + chrono::Duration::days(TRIAL_DAYS)).to_rfc3339(),
+            features: TRIAL_FEATURES.iter().map(|f| f.to_string()).collect(),
+            seats: None,
+            issued: Some(started.format("%Y-%m-%d").to_string()),
+            grace_period_days: None,
+            version: default_version(),
+        }
+    }
+
+    /// Whether this is the implicit trial license
+    pub fn is_trial(&self) -> bool {
+        self.customer == TRIAL_CUSTOMER
+    }
+
+    /// Check if the license has expired, including any grace period
     pub fn is_expired(&self) -> bool {
-        match DateTime::parse_from_rfc3339(&self.expires) {
-            Ok(expires) => Utc::now() > expires,
-            Err(_) => {
-                // Try parsing as date only (YYYY-MM-DD)
-                match chrono::NaiveDate::parse_from_str(&self.expires, "%Y-%m-%d") {
-                    Ok(date) => {
-                        let expires = date
-                            .and_hms_opt(23, 59, 59)
-                            .unwrap()
-                            .and_utc();
-                        Utc::now() > expires
-                    }
-                    Err(_) => true, // Invalid date format = expired
-                }
-            }
+        match self.expires_at() {
+            Some(expires) => Utc::now() > expires + self.grace_period(),
+            None => true, // Invalid date format = expired
         }
     }
 
+    /// Days left in the grace period, if the license is past `expires`
+    /// but still inside it
+    pub fn grace_days_remaining(&self) -> Option<i64> {
+        let expires = self.expires_at()?;
+        let now = Utc::now();
+        let grace_ends = expires + self.grace_period();
+        (now > expires && now <= grace_ends).then(|| (grace_ends - now).num_days())
+    }
+
     /// Check if a feature is licensed
     pub fn has_feature(&self, feature: &str) -> bool {
         self.features.iter().any(|f| f == feature || f == "*")
@@ -120,16 +388,27 @@ impl LicenseInfo {
 
     /// Get days until expiration (negative if expired)
     pub fn days_until_expiry(&self) -> i64 {
-        let expires = match DateTime::parse_from_rfc3339(&self.expires) {
-            Ok(dt) => dt.with_timezone(&Utc),
-            Err(_) => {
-                match chrono::NaiveDate::parse_from_str(&self.expires, "%Y-%m-%d") {
-                    Ok(date) => date.and_hms_opt(23, 59, 59).unwrap().and_utc(),
-                    Err(_) => return -9999,
-                }
-            }
-        };
-        (expires - Utc::now()).num_days()
+        match self.expires_at() {
+            Some(expires) => (expires - Utc::now()).num_days(),
+            None => -9999,
+        }
+    }
+
+    /// Expiry instant: RFC 3339, or the end of the day for YYYY-MM-DD
+    fn expires_at(&self) -> Option<DateTime<Utc>> {
+        match DateTime::parse_from_rfc3339(&self.expires) {
+            Ok(dt) => Some(dt.with_timezone(&Utc)),
+            Err(_) => Some(
+                chrono::NaiveDate::parse_from_str(&self.expires, "%Y-%m-%d")
+                    .ok()?
+                    .and_hms_opt(23, 59, 59)?
+                    .and_utc(),
+            ),
+        }
+    }
+
+    fn grace_period(&self) -> chrono::Duration {
+        chrono::Duration::days(self.grace_period_days.unwrap_or(0).into())
     }
 }
 
@@ -140,37 +419,100 @@ pub struct LicenseStatus {
     pub info: Option<LicenseInfo>,
     pub error: Option<String>,
     pub days_remaining: Option<i64>,
+    /// Expired but still inside `grace_period_days`; show a renewal warning
+    pub in_grace_period: bool,
+    pub grace_days_remaining: Option<i64>,
+    /// No license installed; `info` is `LicenseInfo::trial()`
+    pub is_trial: bool,
 }
 
-/// Verify a license key and extract its information
+/// Seat usage summary returned to the frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeatStatus {
+    pub total: u32,
+    pub used: u32,
+    pub available: u32,
+}
+
+/// Active sessions of the license, for enforcing `LicenseInfo::seats`
 ///
-/// License key format: ABF-<base64(payload_json + signature_64bytes)>
-pub fn verify_license(license_key: &str) -> Result<LicenseInfo, LicenseError> {
-    // Check placeholder hasn't been replaced
-    if PUBLIC_KEY_BASE64 == "REPLACE_WITH_YOUR_PUBLIC_KEY_BASE64_HERE" {
-        return Err(LicenseError::PublicKeyNotConfigured);
+/// Maps session id -> last activity. A session holds a seat from `claim`
+/// until `release`, or until it has been idle for 30 minutes, so a crashed
+/// client cannot keep a seat forever. Cloning shares the same registry.
+#[derive(Debug, Clone, Default)]
+pub struct SeatRegistry {
+    sessions: Arc<Mutex<HashMap<String, DateTime<Utc>>>>,
+}
+
+impl SeatRegistry {
+    /// Claim a seat for a session (claiming again just refreshes it)
+    ///
+    /// # Errors
+    /// `SeatLimitExceeded` if `seats_allowed` other sessions are active
+    pub fn claim(&self, session_id: &str, seats_allowed: u32) -> Result<(), LicenseError> {
+        self.claim_at(session_id, seats_allowed, Utc::now())
     }
 
-    // Parse the public key
-    let public_key_bytes = URL_SAFE_NO_PAD
-        .decode(PUBLIC_KEY_BASE64)
-        .map_err(|e| LicenseError::InvalidPublicKey(e.to_string()))?;
+    /// Free the seat of a session
+    pub fn release(&self, session_id: &str) {
+        self.sessions.lock().unwrap().remove(session_id);
+    }
+
+    /// Mark a session as active; unknown sessions are ignored
+    pub fn heartbeat(&self, session_id: &str) {
+        if let Some(last_active) = self.sessions.lock().unwrap().get_mut(session_id) {
+            *last_active = Utc::now();
+        }
+    }
 
-    if public_key_bytes.len() != 32 {
-        return Err(LicenseError::InvalidPublicKey(format!(
-            "Expected 32 bytes, got {}",
-            public_key_bytes.len()
-        )));
+    /// Number of sessions currently holding a seat
+    pub fn active_sessions(&self) -> u32 {
+        let mut sessions = self.sessions.lock().unwrap();
+        Self::drop_idle(&mut sessions, Utc::now());
+        sessions.len() as u32
     }
 
-    let public_key = VerifyingKey::from_bytes(
-        public_key_bytes
-            .as_slice()
-            .try_into()
-            .map_err(|_| LicenseError::InvalidPublicKey("Invalid key bytes".to_string()))?,
-    )
-    .map_err(|e| LicenseError::InvalidPublicKey(e.to_string()))?;
+    fn claim_at(
+        &self,
+        session_id: &str,
+        seats_allowed: u32,
+        now: DateTime<Utc>,
+    ) -> Result<(), LicenseError> {
+        let mut sessions = self.sessions.lock().unwrap();
+        Self::drop_idle(&mut sessions, now);
 
+        if !sessions.contains_key(session_id) && sessions.len() as u32 >= seats_allowed {
+            return Err(LicenseError::SeatLimitExceeded(seats_allowed));
+        }
+        sessions.insert(session_id.to_string(), now);
+        Ok(())
+    }
+
+    fn drop_idle(sessions: &mut HashMap<String, DateTime<Utc>>, now: DateTime<Utc>) {
+        let cutoff = now - chrono::Duration::minutes(SEAT_IDLE_TIMEOUT_MINUTES);
+        sessions.retain(|_, last_active| *last_active > cutoff);
+    }
+}
+
+/// Verify a license key and extract its information
+///
+/// License key format: ABF-<base64([algorithm_byte] + payload_json + signature)>,
+/// see the module docs
+///
+/// # Errors
+/// `InGracePeriod` for a license that is past its expiry date but inside
+/// its grace period. That error is not fatal (see `LicenseError::is_fatal`);
+/// use `verify_license_allowing_grace` to get the info in that case.
+pub fn verify_license(license_key: &str) -> Result<LicenseInfo, LicenseError> {
+    let info = verify_license_allowing_grace(license_key)?;
+    match info.grace_days_remaining() {
+        Some(days) => Err(LicenseError::InGracePeriod(days)),
+        None => Ok(info),
+    }
+}
+
+/// Like `verify_license`, but accepts licenses inside their grace period
+pub fn verify_license_allowing_grace(license_key: &str) -> Result<LicenseInfo, LicenseError> {
     // Remove prefix and validate format
     let key_data = license_key
         .strip_prefix(LICENSE_PREFIX)
@@ -180,40 +522,58 @@ pub fn verify_license(license_key: &str) -> Result<LicenseInfo, LicenseError> {
     let key_clean: String = key_data.chars().filter(|c| *c != '-').collect();
 
     // Decode base64
-    let decoded = URL_SAFE_NO_PAD.decode(&key_clean)?;
+    let decoded = URL_SAFE_NO_PAD
+        .decode(&key_clean)
+        .map_err(|source| LicenseError::Base64Error {
+            operation: "decode_license_key".to_string(),
+            source,
+        })?;
+
+    verify_decoded_license(&decoded, embedded_verifier)
+}
+
+/// Check the signature, product and expiry of a base64-decoded license key
+/// against the public keys from `verifier_for`
+fn verify_decoded_license(
+    decoded: &[u8],
+    verifier_for: impl Fn(SignatureAlgorithm) -> Result<Box<dyn LicenseVerifier>, LicenseError>,
+) -> Result<LicenseInfo, LicenseError> {
+    // v2 keys start with the algorithm byte, v1 keys with the payload's `{`
+    let (algorithm, signed) = match decoded.split_first() {
+        Some((&byte, rest)) if byte != b'{' => (
+            SignatureAlgorithm::from_version_byte(byte).ok_or(LicenseError::InvalidFormat)?,
+            rest,
+        ),
+        _ => (SignatureAlgorithm::Ed25519, decoded),
+    };
+    let verifier = verifier_for(algorithm)?;
 
-    // Must have at least 64 bytes for signature + some payload
-    if decoded.len() < 65 {
+    // Must have the signature + some payload
+    if signed.len() <= verifier.signature_len() {
         return Err(LicenseError::InvalidFormat);
     }
 
     // Split into payload and signature
-    // Format: [payload_bytes...][signature_64_bytes]
-    let signature_start = decoded.len() - 64;
-    let payload_bytes = &decoded[..signature_start];
-    let signature_bytes = &decoded[signature_start..];
-
-    // Parse signature
-    let signature = Signature::from_bytes(
-        signature_bytes
-            .try_into()
-            .map_err(|_| LicenseError::InvalidSignature)?,
-    );
+    // Format: [payload_bytes...][signature_bytes]
+    let (payload_bytes, signature_bytes) =
+        signed.split_at(signed.len() - verifier.signature_len());
 
     // Verify signature
-    public_key
-        .verify(payload_bytes, &signature)
-        .map_err(|_| LicenseError::InvalidSignature)?;
+    verifier.verify(payload_bytes, signature_bytes)?;
 
     // Parse JSON payload
-    let info: LicenseInfo = serde_json::from_slice(payload_bytes)?;
+    let info: LicenseInfo =
+        serde_json::from_slice(payload_bytes).map_err(|source| LicenseError::JsonError {
+            operation: "parse_license_payload".to_string(),
+            source,
+        })?;
 
     // Validate product
-    if info.product != "amsterdam-bike-fleet" && info.product != "*" {
+    if info.product != PRODUCT_ID && info.product != "*" {
         return Err(LicenseError::WrongProduct(info.product.clone()));
     }
 
-    // Check expiration
+    // Check expiration (past the grace period, if any)
     if info.is_expired() {
         return Err(LicenseError::Expired(info.expires.clone()));
     }
@@ -223,14 +583,19 @@ pub fn verify_license(license_key: &str) -> Result<LicenseInfo, LicenseError> {
 
 /// Get the status of a license key (for UI display)
 pub fn get_license_status(license_key: &str) -> LicenseStatus {
-    match verify_license(license_key) {
+    match verify_license_allowing_grace(license_key) {
         Ok(info) => {
             let days = info.days_until_expiry();
+            let grace_days_remaining = info.grace_days_remaining();
             LicenseStatus {
                 valid: true,
                 info: Some(info),
-                error: None,
+                error: grace_days_remaining
+                    .map(|days| LicenseError::InGracePeriod(days).to_string()),
                 days_remaining: Some(days),
+                in_grace_period: grace_days_remaining.is_some(),
+                grace_days_remaining,
+                is_trial: false,
             }
         }
         Err(e) => LicenseStatus {
@@ -238,19 +603,186 @@ pub fn get_license_status(license_key: &str) -> LicenseStatus {
             info: None,
             error: Some(e.to_string()),
             days_remaining: None,
+            in_grace_period: false,
+            grace_days_remaining: None,
+            is_trial: false,
+        },
+    }
+}
+
+/// Status of the installed license, or of the trial if there is none
+pub fn stored_license_status(
+    storage: &EncryptedLicenseStorage,
+    machine_fingerprint: &str,
+) -> LicenseStatus {
+    if !storage.exists() {
+        return match storage.trial() {
+            Ok(trial) => LicenseStatus {
+                valid: !trial.is_expired(),
+                days_remaining: Some(trial.days_until_expiry()),
+                error: trial.is_expired().then(|| "Trial has expired".to_string()),
+                info: Some(trial),
+                in_grace_period: false,
+                grace_days_remaining: None,
+                is_trial: true,
+            },
+            Err(e) => LicenseStatus {
+                valid: false,
+                info: None,
+                error: Some(format!("Failed to load trial: {}", e)),
+                days_remaining: None,
+                in_grace_period: false,
+                grace_days_remaining: None,
+                is_trial: true,
+            },
+        };
+    }
+
+    match storage.load(machine_fingerprint) {
+        Ok(license_key) => get_license_status(&license_key),
+        Err(e) => LicenseStatus {
+            valid: false,
+            info: None,
+            error: Some(format!("Failed to load license: {}", e)),
+            days_remaining: None,
+            in_grace_period: false,
+            grace_days_remaining: None,
+            is_trial: false,
         },
     }
 }
 
 /// Check if a specific feature is licensed
 pub fn is_feature_licensed(license_key: &str, feature: &str) -> bool {
-    match verify_license(license_key) {
+    match verify_license_allowing_grace(license_key) {
         Ok(info) => info.has_feature(feature),
         Err(_) => false,
     }
 }
 
+/// License compliance report for enterprise IT audits
+///
+/// # Why signed?
+/// `report_signature` is an HMAC-SHA256 over the other fields, keyed with
+/// the license key bytes. Anyone holding the license key can recompute it
+/// (see `verify_signature`), so the compliance team can tell the report
+/// came from software holding that license and wasn't edited afterwards.
+///
+/// # Signed payload
+/// The report serialized as compact JSON without `report_signature`, keys
+/// in alphabetical order. The signature is lowercase hex.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LicenseReport {
+    pub license_valid: bool,
+    pub customer: Option<String>,
+    pub company: Option<String>,
+    pub product: String,
+    pub expires: Option<String>,
+    pub days_remaining: Option<i64>,
+    pub features: Vec<String>,
+    pub seats: Option<u32>,
+    pub machine_fingerprint: String,
+    pub report_generated_at: String,
+    pub report_signature: String,
+}
+
+impl LicenseReport {
+    /// Build and sign a report for a license key
+    ///
+    /// Invalid or expired keys still produce a report (with
+    /// `license_valid: false`), so the audit trail shows the failure.
+    pub fn generate(license_key: &str, machine_fingerprint: String) -> Result<Self, LicenseError> {
+        let verified = verify_license_allowing_grace(license_key);
+        let info = verified.as_ref().ok();
+
+        let mut report = LicenseReport {
+            license_valid: info.is_some(),
+            customer: info.map(|i| i.customer.clone()),
+            company: info.and_then(|i| i.company.clone()),
+            product: info.map_or_else(|| PRODUCT_ID.to_string(), |i| i.product.clone()),
+            expires: info.map(|i| i.expires.clone()),
+            days_remaining: info.map(|i| i.days_until_expiry()),
+            features: info.map(|i| i.features.clone()).unwrap_or_default(),
+            seats: info.and_then(|i| i.seats),
+            machine_fingerprint,
+            report_generated_at: Utc::now().to_rfc3339(),
+            report_signature: String::new(),
+        };
+        report.report_signature = report.compute_signature(license_key)?;
+
+        Ok(report)
+    }
+
+    /// Check `report_signature` against the license key
+    pub fn verify_signature(&self, license_key: &str) -> bool {
+        self.compute_signature(license_key)
+            .map(|signature| signature == self.report_signature)
+            .unwrap_or(false)
+    }
+
+    /// HMAC-SHA256 over every field except `report_signature`
+    fn compute_signature(&self, license_key: &str) -> Result<String, LicenseError> {
+        let to_json_error = |source| LicenseError::JsonError {
+            operation: "serialize_license_report".to_string(),
+            source,
+        };
+
+        // BTreeMap pins the key order regardless of serde_json features
+        let fields: BTreeMap<String, serde_json::Value> =
+            match serde_json::to_value(self).map_err(to_json_error)? {
+                serde_json::Value::Object(fields) => fields
+                    .into_iter()
+                    .filter(|(key, _)| key != "report_signature")
+                    .collect(),
+                _ => BTreeMap::new(),
+            };
+        let payload = serde_json::to_vec(&fields).map_err(to_json_error)?;
+
+        let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(license_key.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(&payload);
+
+        Ok(to_hex(&mac.finalize().into_bytes()))
+    }
+}
+
+/// Stable identifier for this installation (not a hardware ID)
+///
+/// SHA-256 of the host name, OS, architecture and app data directory, so a
+/// report can be tied to the machine it was generated on without exposing
+/// those details.
+pub fn machine_fingerprint(app_data_dir: &Path) -> String {
+    let hostname = fs::read_to_string("/etc/hostname")
+        .ok()
+        .or_else(|| std::env::var("COMPUTERNAME").ok())
+        .or_else(|| std::env::var("HOSTNAME").ok())
+        .unwrap_or_default();
+
+    let mut hasher = Sha256::new();
+    for part in [
+        hostname.trim(),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        &app_data_dir.to_string_lossy(),
+    ] {
+        hasher.update(part.as_bytes());
+        hasher.update([0u8]);
+    }
+
+    to_hex(&hasher.finalize())
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
 /// License storage manager - handles persisting license to disk
+///
+/// # Secure storage
+/// With the `secure-storage` feature, `save` and `load` go through
+/// `save_encrypted` / `load_encrypted` keyed with the hardware machine ID,
+/// so a copied `license.key` is useless on another computer. Plaintext
+/// files from before the feature was enabled still load.
 pub struct LicenseStorage {
     storage_path: PathBuf,
 }
@@ -264,27 +796,73 @@ impl LicenseStorage {
 
     /// Save license key to disk
     pub fn save(&self, license_key: &str) -> Result<(), LicenseError> {
-        fs::create_dir_all(self.storage_path.parent().unwrap())
-            .map_err(|e| LicenseError::FileError(e.to_string()))?;
-
-        fs::write(&self.storage_path, license_key)
-            .map_err(|e| LicenseError::FileError(e.to_string()))?;
+        #[cfg(feature = "secure-storage")]
+        return self.save_encrypted(license_key, &machine_id()?);
 
-        Ok(())
+        #[cfg(not(feature = "secure-storage"))]
+        self.write(license_key.as_bytes())
     }
 
     /// Load license key from disk
     pub fn load(&self) -> Result<String, LicenseError> {
-        fs::read_to_string(&self.storage_path)
-            .map(|s| s.trim().to_string())
-            .map_err(|e| LicenseError::FileError(e.to_string()))
+        #[cfg(feature = "secure-storage")]
+        return self.load_encrypted(&machine_id()?);
+
+        #[cfg(not(feature = "secure-storage"))]
+        plaintext_license(self.read()?)
+    }
+
+    /// Save the license key encrypted with a key derived from `machine_id`
+    ///
+    /// File layout: `ENCRYPTED_LICENSE_MAGIC`, 12-byte nonce, then the
+    /// ChaCha20-Poly1305 ciphertext + tag.
+    pub fn save_encrypted(&self, license_key: &str, machine_id: &[u8]) -> Result<(), LicenseError> {
+        self.write(&EncryptedLicenseStorage::seal(license_key, machine_id)?)
+    }
+
+    /// Load a license key written by `save_encrypted`
+    ///
+    /// Files without the magic header are read as plaintext (migration
+    /// path for licenses saved before encryption was enabled).
+    ///
+    /// # Errors
+    /// `FileError` ("License file is not valid on this machine") when the
+    /// file was encrypted with a different machine ID
+    pub fn load_encrypted(&self, machine_id: &[u8]) -> Result<String, LicenseError> {
+        let contents = self.read()?;
+        match EncryptedLicenseStorage::open(&contents, machine_id)? {
+            Some(license_key) => Ok(license_key),
+            None => plaintext_license(contents),
+        }
+    }
+
+    fn read(&self) -> Result<Vec<u8>, LicenseError> {
+        fs::read(&self.storage_path).map_err(|source| LicenseError::FileError {
+            operation: "load_license".to_string(),
+            source,
+        })
+    }
+
+    fn write(&self, contents: &[u8]) -> Result<(), LicenseError> {
+        fs::create_dir_all(self.storage_path.parent().unwrap())
+            .map_err(|source| LicenseError::FileError {
+                operation: "create_license_dir".to_string(),
+                source,
+            })?;
+
+        fs::write(&self.storage_path, contents).map_err(|source| LicenseError::FileError {
+            operation: "save_license".to_string(),
+            source,
+        })
     }
 
     /// Remove stored license
     pub fn remove(&self) -> Result<(), LicenseError> {
         if self.storage_path.exists() {
-            fs::remove_file(&self.storage_path)
-                .map_err(|e| LicenseError::FileError(e.to_string()))?;
+            fs::remove_file(&self.storage_path).map_err(|source| LicenseError::FileError {
+                operation: "remove_license".to_string(),
+                source,
+            })?;
         }
         Ok(())
     }
@@ -295,22 +873,275 @@ impl LicenseStorage {
     }
 }
 
+/// On-disk format of license files encrypted before the magic header
+#[derive(Debug, Serialize, Deserialize)]
+struct EncryptedLicenseFile {
+    version: u32,
+    /// Base64, 12 bytes
+    nonce: String,
+    /// Base64 ChaCha20-Poly1305 ciphertext + tag
+    ciphertext: String,
+}
+
+/// License storage encrypted at rest with a machine-derived key
+///
+/// # Why?
+/// A plaintext `license.key` can simply be copied to another machine.
+/// Encrypting it with a key derived from `machine_fingerprint` makes the
+/// file useless elsewhere. This is defense in depth, not DRM: the Ed25519
+/// signature is still what makes a license valid.
+///
+/// # Secure storage
+/// With the `secure-storage` feature, the key is derived from the hardware
+/// machine ID instead, which a copied app data directory or a renamed host
+/// can't reproduce. The file format is the same either way: the one
+/// `LicenseStorage::save_encrypted` writes, sealed by `seal` below.
+///
+/// # Legacy files
+/// A plaintext file written by `LicenseStorage`, or a JSON file from before
+/// the magic header, loads once and is re-saved right away. Every `save`
+/// leaves a marker next to the file; once it exists, plaintext files are
+/// rejected, so a key copied in as plaintext doesn't bypass the machine
+/// binding.
+pub struct EncryptedLicenseStorage {
+    storage: LicenseStorage,
+    migrated_marker: PathBuf,
+    trial_started: PathBuf,
+}
+
+impl EncryptedLicenseStorage {
+    pub fn new(app_data_dir: PathBuf) -> Self {
+        Self {
+            migrated_marker: app_data_dir.join(LICENSE_MIGRATED_MARKER),
+            trial_started: app_data_dir.join(TRIAL_STARTED_FILE),
+            storage: LicenseStorage::new(app_data_dir),
+        }
+    }
+
+    /// Encrypt and save the license key
+    pub fn save(&self, license_key: &str, machine_fingerprint: &str) -> Result<(), LicenseError> {
+        self.save_for_machine(license_key, &machine_secret(machine_fingerprint)?)
+    }
+
+    /// `save` with the key derived from `machine_secret`
+    fn save_for_machine(&self, license_key: &str, machine_secret: &[u8]) -> Result<(), LicenseError> {
+        self.storage.save_encrypted(license_key, machine_secret)?;
+        fs::write(&self.migrated_marker, b"").map_err(|source| LicenseError::FileError {
+            operation: "save_license".to_string(),
+            source,
+        })
+    }
+
+    /// Load and decrypt the license key
+    ///
+    /// A legacy plaintext or JSON file is re-saved encrypted before returning.
+    ///
+    /// # Errors
+    /// - `FileError` ("License file is not valid on this machine") when the
+    ///   file was encrypted with a different fingerprint
+    /// - `FileError` ("License file is not encrypted") for a plaintext file
+    ///   once a license has been saved encrypted
+    pub fn load(&self, machine_fingerprint: &str) -> Result<String, LicenseError> {
+        self.load_for_machine(&machine_secret(machine_fingerprint)?)
+    }
+
+    /// `load` with the key derived from `machine_secret`
+    fn load_for_machine(&self, machine_secret: &[u8]) -> Result<String, LicenseError> {
+        let contents = self.storage.read()?;
+        if let Some(license_key) = Self::open(&contents, machine_secret)? {
+            return Ok(license_key);
+        }
+
+        // Written before the magic header: a JSON file or a plaintext license
+        let contents = plaintext_license(contents)?;
+        let license_key = match serde_json::from_str::<EncryptedLicenseFile>(&contents) {
+            Ok(file) => Self::open_json(&file, machine_secret)?,
+            Err(_) if self.migrated_marker.exists() => {
+                return Err(invalid_license_file("License file is not encrypted".to_string()));
+            }
+            Err(_) => contents,
+        };
+        self.save_for_machine(&license_key, machine_secret)?;
+        Ok(license_key)
+    }
+
+    /// Encrypt the license key into the `ENCRYPTED_LICENSE_MAGIC` format
+    fn seal(license_key: &str, machine_secret: &[u8]) -> Result<Vec<u8>, LicenseError> {
+        use rand::RngCore;
+
+        let mut nonce = [0u8; STORAGE_NONCE_SIZE];
+        rand::thread_rng().fill_bytes(&mut nonce);
+
+        let ciphertext = storage_cipher(machine_secret, STORAGE_HKDF_INFO)
+            .encrypt(Nonce::from_slice(&nonce), license_key.as_bytes())
+            .map_err(|e| LicenseError::FileError {
+                operation: "encrypt_license".to_string(),
+                source: std::io::Error::other(e),
+            })?;
+
+        let mut contents = ENCRYPTED_LICENSE_MAGIC.to_vec();
+        contents.extend_from_slice(&nonce);
+        contents.extend_from_slice(&ciphertext);
+        Ok(contents)
+    }
+
+    /// Decrypt a file written by `seal`; `None` without the magic header
+    fn open(contents: &[u8], machine_secret: &[u8]) -> Result<Option<String>, LicenseError> {
+        let Some(encrypted) = contents.strip_prefix(&ENCRYPTED_LICENSE_MAGIC) else {
+            return Ok(None);
+        };
+        if encrypted.len() < STORAGE_NONCE_SIZE {
+            return Err(invalid_license_file("License file is truncated".to_string()));
+        }
+
+        let (nonce, ciphertext) = encrypted.split_at(STORAGE_NONCE_SIZE);
+        let plaintext = storage_cipher(machine_secret, STORAGE_HKDF_INFO)
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| {
+                invalid_license_file("License file is not valid on this machine".to_string())
+            })?;
+
+        String::from_utf8(plaintext)
+            .map(Some)
+            .map_err(|_| invalid_license_file("License file is not valid UTF-8".to_string()))
+    }
+
+    /// Decrypt a JSON file from before the magic header
+    fn open_json(file: &EncryptedLicenseFile, machine_secret: &[u8]) -> Result<String, LicenseError> {
+        if file.version != STORAGE_VERSION {
+            return Err(invalid_license_file(format!(
+                "Unsupported license file version: {}",
+                file.version
+            )));
+        }
+
+        let nonce = STANDARD
+            .decode(&file.nonce)
+            .ok()
+            .filter(|nonce| nonce.len() == STORAGE_NONCE_SIZE)
+            .ok_or_else(|| invalid_license_file("Invalid license file nonce".to_string()))?;
+        let ciphertext = STANDARD
+            .decode(&file.ciphertext)
+            .map_err(|source| LicenseError::Base64Error {
+                operation: "decode_license_file".to_string(),
+                source,
+            })?;
+
+        let plaintext = storage_cipher(machine_secret, JSON_STORAGE_HKDF_INFO)
+            .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+            .map_err(|_| {
+                invalid_license_file("License file is not valid on this machine".to_string())
+            })?;
+
+        String::from_utf8(plaintext)
+            .map_err(|_| invalid_license_file("License file is not valid UTF-8".to_string()))
+    }
+
+    /// Remove stored license
+    pub fn remove(&self) -> Result<(), LicenseError> {
+        self.storage.remove()
+    }
+
+    /// Check if a license is stored
+    pub fn exists(&self) -> bool {
+        self.storage.exists()
+    }
+
+    /// The trial license, running from the first run on this machine
+    ///
+    /// The first call records the current time; later calls, also after a
+    /// restart, derive the expiry from that record.
+    ///
+    /// # Errors
+    /// `FileError` if the record can't be written, or exists but can't be
+    /// read; an unreadable record never restarts the trial
+    pub fn trial(&self) -> Result<LicenseInfo, LicenseError> {
+        let started = match fs::read_to_string(&self.trial_started) {
+            Ok(contents) => DateTime::parse_from_rfc3339(contents.trim())
+                .map(|started| started.with_timezone(&Utc))
+                .map_err(|_| invalid_license_file("Trial start is not a timestamp".to_string()))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                let now = Utc::now();
+                fs::create_dir_all(self.trial_started.parent().unwrap()).map_err(|source| {
+                    LicenseError::FileError {
+                        operation: "create_license_dir".to_string(),
+                        source,
+                    }
+                })?;
+                fs::write(&self.trial_started, now.to_rfc3339()).map_err(|source| {
+                    LicenseError::FileError {
+                        operation: "save_trial_start".to_string(),
+                        source,
+                    }
+                })?;
+                now
+            }
+            Err(source) => {
+                return Err(LicenseError::FileError {
+                    operation: "load_trial_start".to_string(),
+                    source,
+                })
+            }
+        };
+
+        Ok(LicenseInfo::trial(started))
+    }
+}
+
+/// Cipher keyed by HKDF-SHA256 over a machine identifier
+fn storage_cipher(machine_secret: &[u8], info: &[u8]) -> ChaCha20Poly1305 {
+    let hk = Hkdf::<Sha256>::new(None, machine_secret);
+    let mut key = Zeroizing::new([0u8; 32]);
+    hk.expand(info, key.as_mut())
+        .expect("32 bytes is a valid HKDF-SHA256 output length");
+    ChaCha20Poly1305::new(&(*key).into())
+}
+
+/// Machine identifier the license file key is derived from
+///
+/// The hardware machine ID with `secure-storage`, else the fingerprint.
+#[cfg(feature = "secure-storage")]
+fn machine_secret(_machine_fingerprint: &str) -> Result<Vec<u8>, LicenseError> {
+    machine_id()
+}
+
+#[cfg(not(feature = "secure-storage"))]
+fn machine_secret(machine_fingerprint: &str) -> Result<Vec<u8>, LicenseError> {
+    Ok(machine_fingerprint.as_bytes().to_vec())
+}
+
+/// Hardware machine ID (e.g. /etc/machine-id, the Windows MachineGuid)
+#[cfg(feature = "secure-storage")]
+fn machine_id() -> Result<Vec<u8>, LicenseError> {
+    machine_uid::get()
+        .map(String::into_bytes)
+        .map_err(|e| LicenseError::FileError {
+            operation: "read_machine_id".to_string(),
+            source: std::io::Error::other(e.to_string()),
+        })
+}
+
+/// A license file read as plaintext, trimmed
+fn plaintext_license(contents: Vec<u8>) -> Result<String, LicenseError> {
+    String::from_utf8(contents)
+        .map(|s| s.trim().to_string())
+        .map_err(|_| invalid_license_file("License file is not valid UTF-8".to_string()))
+}
+
+fn invalid_license_file(message: String) -> LicenseError {
+    LicenseError::FileError {
+        operation: "decrypt_license".to_string(),
+        source: std::io::Error::new(std::io::ErrorKind::InvalidData, message),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_license_info_expiry() {
-        let info = LicenseInfo {
-            customer: "test@example.com".to_string(),
-            company: None,
-            product: "amsterdam-bike-fleet".to_string(),
-            expires: "2099-12-31".to_string(),
-            features: vec!["premium".to_string()],
-            seats: None,
-            issued: None,
-            version: 1,
-        };
+        let info = license_with(&["premium"]);
 
         assert!(!info.is_expired());
         assert!(info.has_feature("premium"));
@@ -320,34 +1151,312 @@ mod tests {
     #[test]
     fn test_expired_license() {
         let info = LicenseInfo {
-            customer: "test@example.com".to_string(),
-            company: None,
-            product: "amsterdam-bike-fleet".to_string(),
             expires: "2020-01-01".to_string(),
-            features: vec![],
-            seats: None,
-            issued: None,
-            version: 1,
+            ..license_with(&[])
         };
 
         assert!(info.is_expired());
     }
 
+    fn license_expired_days_ago(days: i64, grace_period_days: Option<u32>) -> LicenseInfo {
+        let expires = Utc::now().date_naive() - chrono::Duration::days(days);
+        LicenseInfo {
+            expires: expires.format("%Y-%m-%d").to_string(),
+            grace_period_days,
+            ..license_with(&[])
+        }
+    }
+
+    #[test]
+    fn test_expired_yesterday_within_grace_is_valid() {
+        let info = license_expired_days_ago(1, Some(7));
+
+        assert!(!info.is_expired());
+        let days = info.grace_days_remaining().unwrap();
+        assert!((5..=6).contains(&days), "got {}", days);
+        assert!(!LicenseError::InGracePeriod(days).is_fatal());
+
+        // Without a grace period the same license is expired
+        assert!(license_expired_days_ago(1, None).is_expired());
+    }
+
+    #[test]
+    fn test_expired_past_grace_is_invalid() {
+        let info = license_expired_days_ago(8, Some(7));
+
+        assert!(info.is_expired());
+        assert_eq!(info.grace_days_remaining(), None);
+    }
+
+    #[test]
+    fn test_idle_sessions_free_their_seat() {
+        let registry = SeatRegistry::default();
+        let long_ago = Utc::now() - chrono::Duration::minutes(SEAT_IDLE_TIMEOUT_MINUTES + 1);
+
+        registry.claim_at("stale", 1, long_ago).unwrap();
+        registry.claim("fresh", 1).unwrap();
+
+        assert_eq!(registry.active_sessions(), 1);
+    }
+
+    #[test]
+    fn test_trial_blocks_export() {
+        let trial = LicenseInfo::trial(Utc::now());
+
+        assert!(trial.is_trial());
+        assert!(trial.has_feature("basic"));
+        assert!(!trial.has_feature("export"));
+    }
+
+    #[test]
+    fn test_trial_valid_for_30_days() {
+        let trial = LicenseInfo::trial(Utc::now());
+        assert!(!trial.is_expired());
+        assert!((29..=30).contains(&trial.days_until_expiry()));
+
+        let trial = LicenseInfo::trial(Utc::now() - chrono::Duration::days(10));
+        assert!((19..=20).contains(&trial.days_until_expiry()));
+
+        let trial = LicenseInfo::trial(Utc::now() - chrono::Duration::days(31));
+        assert!(trial.is_expired());
+    }
+
+    #[test]
+    fn test_trial_runs_from_the_first_run() {
+        let dir = std::env::temp_dir().join(format!("abf-trial-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let storage = EncryptedLicenseStorage::new(dir.clone());
+
+        let status = stored_license_status(&storage, "test-machine");
+        assert!(status.valid);
+        assert!(status.is_trial);
+        let first = status.info.unwrap();
+        assert!(first.is_trial());
+        // Only the start is recorded, never a license
+        assert!(!storage.exists());
+        assert_eq!(storage.trial().unwrap().expires, first.expires);
+
+        let started = Utc::now() - chrono::Duration::days(31);
+        fs::write(dir.join(TRIAL_STARTED_FILE), started.to_rfc3339()).unwrap();
+        let status = stored_license_status(&storage, "test-machine");
+        assert!(!status.valid);
+        assert!(status.is_trial);
+
+        fs::write(dir.join(TRIAL_STARTED_FILE), "yesterday").unwrap();
+        assert!(storage.trial().is_err());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
     #[test]
     fn test_wildcard_feature() {
-        let info = LicenseInfo {
-            customer: "test@example.com".to_string(),
-            company: None,
-            product: "amsterdam-bike-fleet".to_string(),
-            expires: "2099-12-31".to_string(),
-            features: vec!["*".to_string()],
-            seats: None,
-            issued: None,
-            version: 1,
-        };
+        let info = license_with(&["*"]);
 
         assert!(info.has_feature("anything"));
         assert!(info.has_feature("premium"));
         assert!(info.has_feature("enterprise"));
     }
+
+    #[test]
+    fn test_license_report_signature() {
+        let report = LicenseReport::generate("ABF-not-a-real-key", "test-machine".to_string())
+            .unwrap();
+
+        assert!(!report.license_valid);
+        assert_eq!(report.product, PRODUCT_ID);
+        assert_eq!(report.report_signature.len(), 64);
+        assert!(report.verify_signature("ABF-not-a-real-key"));
+        assert!(!report.verify_signature("ABF-some-other-key"));
+
+        let mut tampered = report.clone();
+        tampered.license_valid = true;
+        assert!(!tampered.verify_signature("ABF-not-a-real-key"));
+    }
+
+    #[test]
+    fn test_encrypted_storage_is_machine_bound() {
+        let dir = std::env::temp_dir().join(format!("abf-license-test-{}", std::process::id()));
+        let storage = EncryptedLicenseStorage::new(dir.clone());
+        let machine_a = b"4c4c4544-0042-3510-8052-b4c04f4e4d32";
+        let machine_b = b"00000000-0000-0000-0000-000000000000";
+
+        storage.save_for_machine("ABF-test-license", machine_a).unwrap();
+        let on_disk = fs::read(dir.join("license.key")).unwrap();
+        assert!(!String::from_utf8_lossy(&on_disk).contains("ABF-test-license"));
+        assert_eq!(storage.load_for_machine(machine_a).unwrap(), "ABF-test-license");
+
+        let err = storage.load_for_machine(machine_b).unwrap_err();
+        assert!(err
+            .to_string()
+            .ends_with("License file is not valid on this machine"));
+
+        // Once encrypted, a plaintext file dropped in its place is refused
+        LicenseStorage::new(dir.clone()).save("ABF-copied").unwrap();
+        let err = storage.load_for_machine(machine_a).unwrap_err();
+        assert!(err.to_string().ends_with("License file is not encrypted"));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_plaintext_license_migrated_once() {
+        let dir = std::env::temp_dir().join(format!("abf-migrate-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let storage = EncryptedLicenseStorage::new(dir.clone());
+        LicenseStorage::new(dir.clone()).save("ABF-legacy").unwrap();
+
+        assert_eq!(storage.load("machine-a").unwrap(), "ABF-legacy");
+
+        // Re-saved encrypted on first load
+        let on_disk = fs::read(dir.join("license.key")).unwrap();
+        assert!(!String::from_utf8_lossy(&on_disk).contains("ABF-legacy"));
+        assert_eq!(storage.load("machine-a").unwrap(), "ABF-legacy");
+
+        LicenseStorage::new(dir.clone()).save("ABF-legacy").unwrap();
+        assert!(storage.load("machine-a").is_err());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_machine_encrypted_storage() {
+        let dir = std::env::temp_dir().join(format!("abf-machine-test-{}", std::process::id()));
+        let storage = LicenseStorage::new(dir.clone());
+        let machine_a = b"4c4c4544-0042-3510-8052-b4c04f4e4d32";
+        let machine_b = b"00000000-0000-0000-0000-000000000000";
+
+        storage.save_encrypted("ABF-test-license", machine_a).unwrap();
+        let on_disk = fs::read(dir.join("license.key")).unwrap();
+        assert_eq!(on_disk[..4], ENCRYPTED_LICENSE_MAGIC);
+        assert_eq!(storage.load_encrypted(machine_a).unwrap(), "ABF-test-license");
+
+        let err = storage.load_encrypted(machine_b).unwrap_err();
+        assert!(err
+            .to_string()
+            .ends_with("License file is not valid on this machine"));
+
+        // Files without the header load as plaintext
+        fs::write(dir.join("license.key"), "ABF-legacy\n").unwrap();
+        assert_eq!(storage.load_encrypted(machine_b).unwrap(), "ABF-legacy");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_json_license_file_resaved_with_header() {
+        let dir = std::env::temp_dir().join(format!("abf-json-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let storage = EncryptedLicenseStorage::new(dir.clone());
+        let machine = b"4c4c4544-0042-3510-8052-b4c04f4e4d32";
+
+        let nonce = [7u8; STORAGE_NONCE_SIZE];
+        let ciphertext = storage_cipher(machine, JSON_STORAGE_HKDF_INFO)
+            .encrypt(Nonce::from_slice(&nonce), b"ABF-json".as_slice())
+            .unwrap();
+        let file = EncryptedLicenseFile {
+            version: STORAGE_VERSION,
+            nonce: STANDARD.encode(nonce),
+            ciphertext: STANDARD.encode(ciphertext),
+        };
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("license.key"), serde_json::to_string(&file).unwrap()).unwrap();
+        fs::write(dir.join(LICENSE_MIGRATED_MARKER), b"").unwrap();
+
+        assert_eq!(storage.load_for_machine(machine).unwrap(), "ABF-json");
+        let on_disk = fs::read(dir.join("license.key")).unwrap();
+        assert_eq!(on_disk[..4], ENCRYPTED_LICENSE_MAGIC);
+        assert_eq!(storage.load_for_machine(machine).unwrap(), "ABF-json");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    const TEST_PAYLOAD: &str = r#"{"customer":"test@example.com","product":"amsterdam-bike-fleet","expires":"2099-12-31","features":["export"],"version":2}"#;
+
+    /// Decoded license key: `[version_byte] + payload + signature`
+    fn license_bytes(version_byte: Option<u8>, payload: &[u8], signature: &[u8]) -> Vec<u8> {
+        let mut bytes: Vec<u8> = version_byte.into_iter().collect();
+        bytes.extend_from_slice(payload);
+        bytes.extend_from_slice(signature);
+        bytes
+    }
+
+    #[test]
+    fn test_ed25519_v1_and_v2_keys_verify() {
+        use ed25519_dalek::{Signer, SigningKey};
+
+        let signing_key = SigningKey::generate(&mut rand::rngs::OsRng);
+        let public_key = URL_SAFE_NO_PAD.encode(signing_key.verifying_key().to_bytes());
+        let verifier_for = |algorithm| match algorithm {
+            SignatureAlgorithm::Ed25519 => Ed25519Verifier::from_base64(&public_key)
+                .map(|v| Box::new(v) as Box<dyn LicenseVerifier>),
+            SignatureAlgorithm::Rsa4096 => Err(LicenseError::PublicKeyNotConfigured),
+        };
+        let signature = signing_key.sign(TEST_PAYLOAD.as_bytes()).to_bytes();
+
+        // v1: no algorithm byte
+        let v1 = license_bytes(None, TEST_PAYLOAD.as_bytes(), &signature);
+        let info = verify_decoded_license(&v1, verifier_for).unwrap();
+        assert_eq!(info.customer, "test@example.com");
+
+        let v2 = license_bytes(Some(0x01), TEST_PAYLOAD.as_bytes(), &signature);
+        assert_eq!(verify_decoded_license(&v2, verifier_for).unwrap().version, 2);
+
+        let unknown = license_bytes(Some(0x7F), TEST_PAYLOAD.as_bytes(), &signature);
+        assert!(matches!(
+            verify_decoded_license(&unknown, verifier_for),
+            Err(LicenseError::InvalidFormat)
+        ));
+    }
+
+    #[test]
+    fn test_rsa_pss_key_verifies() {
+        use rsa::pkcs8::EncodePublicKey;
+        use rsa::signature::{RandomizedSigner, SignatureEncoding};
+
+        // Smaller than production keys to keep the test fast; the format
+        // only depends on the key's signature length
+        let private_key = rsa::RsaPrivateKey::new(&mut rand::rngs::OsRng, 2048).unwrap();
+        let public_key = URL_SAFE_NO_PAD.encode(
+            private_key
+                .to_public_key()
+                .to_public_key_der()
+                .unwrap()
+                .as_bytes(),
+        );
+        let verifier_for = |algorithm| match algorithm {
+            SignatureAlgorithm::Rsa4096 => RsaPssVerifier::from_base64(&public_key)
+                .map(|v| Box::new(v) as Box<dyn LicenseVerifier>),
+            SignatureAlgorithm::Ed25519 => Err(LicenseError::PublicKeyNotConfigured),
+        };
+        let signature = rsa::pss::BlindedSigningKey::<Sha256>::new(private_key)
+            .sign_with_rng(&mut rand::rngs::OsRng, TEST_PAYLOAD.as_bytes())
+            .to_vec();
+        assert_eq!(signature.len(), 256);
+
+        let key = license_bytes(Some(0x02), TEST_PAYLOAD.as_bytes(), &signature);
+        let info = verify_decoded_license(&key, verifier_for).unwrap();
+        assert!(info.has_feature("export"));
+
+        let tampered_payload = TEST_PAYLOAD.replace("export", "report");
+        let tampered = license_bytes(Some(0x02), tampered_payload.as_bytes(), &signature);
+        assert!(matches!(
+            verify_decoded_license(&tampered, verifier_for),
+            Err(LicenseError::InvalidSignature)
+        ));
+
+        // Ed25519 byte on an RSA-signed key
+        let mislabeled = license_bytes(Some(0x01), TEST_PAYLOAD.as_bytes(), &signature);
+        assert!(verify_decoded_license(&mislabeled, verifier_for).is_err());
+    }
+
+    #[test]
+    fn test_error_includes_operation() {
+        let err = verify_license("ABF-not!base64").unwrap_err();
+        assert!(matches!(err, LicenseError::Base64Error { .. }));
+        assert!(err.to_string().starts_with("Base64Error while decode_license_key: "));
+
+        let storage = LicenseStorage::new(std::env::temp_dir().join("abf-missing-license-dir"));
+        let err = storage.load().unwrap_err();
+        assert!(err.to_string().starts_with("FileError while load_license: "));
+    }
 }
@@ -0,0 +1,500 @@
+//! Secure Command Middleware
+//!
+//! # Purpose
+//! Pluggable pre/post processing around `secure_invoke` dispatch.
+//! Cross-cutting concerns (logging, rate limiting, feature checks, metrics)
+//! implement `CommandMiddleware` instead of being added to the dispatch match.
+//!
+//! # Ordering
+//! - `before` runs in registration order; the first error rejects the command
+//! - `after` runs in reverse order, only for middleware whose `before` ran
+//!
+//! Like nested function calls: the first registered middleware wraps all others.
+
+use crate::crypto::{SecureCommand, SecureResponse};
+use crate::license::LicenseInfo;
+use crate::rate_limiter::RateLimiter;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// Maximum number of entries kept by `LoggingMiddleware`
+const MAX_LOG_ENTRIES: usize = 1000;
+
+/// Hook invoked around every secure command
+pub trait CommandMiddleware: Send + Sync {
+    /// Name used by `register_middleware`; a chain holds each name once
+    fn name(&self) -> &'static str;
+
+    /// Add what this middleware has collected to `report`
+    fn collect(&self, _report: &mut MiddlewareReport) {}
+
+    /// Called before dispatch; returning an error rejects the command
+    fn before(&self, cmd: &SecureCommand) -> Result<(), String>;
+
+    /// Called after dispatch (or after a later middleware rejected the command)
+    fn after(&self, cmd: &SecureCommand, response: &SecureResponse);
+}
+
+/// Run `dispatch` wrapped by the given middleware chain
+///
+/// If a middleware rejects the command, `dispatch` is skipped and the
+/// rejection is returned as `SecureResponse::Error`.
+pub fn run_with_middleware<F>(
+    middleware: &[Arc<dyn CommandMiddleware>],
+    command: SecureCommand,
+    dispatch: F,
+) -> SecureResponse
+where
+    F: FnOnce(SecureCommand) -> SecureResponse,
+{
+    let mut entered = 0;
+    let mut rejection = None;
+
+    for m in middleware {
+        match m.before(&command) {
+            Ok(()) => entered += 1,
+            Err(e) => {
+                rejection = Some(e);
+                break;
+            }
+        }
+    }
+
+    let response = match rejection {
+        Some(e) => SecureResponse::Error(e),
+        None => dispatch(command.clone()),
+    };
+
+    for m in middleware[..entered].iter().rev() {
+        m.after(&command, &response);
+    }
+
+    response
+}
+
+/// Create a middleware by name (used by the `register_middleware` command)
+///
+/// # Names
+/// - `logging`, `rate_limit`, `metrics`
+/// - `feature_check` (checks against `license` as it is when a command runs)
+pub fn create_middleware(
+    name: &str,
+    license: &Arc<Mutex<Option<LicenseInfo>>>,
+) -> Result<Arc<dyn CommandMiddleware>, String> {
+    match name {
+        "logging" => Ok(Arc::new(LoggingMiddleware::new())),
+        "rate_limit" => Ok(Arc::new(RateLimitMiddleware::default())),
+        "metrics" => Ok(Arc::new(MetricsMiddleware::new())),
+        "feature_check" => Ok(Arc::new(FeatureCheckMiddleware::new(license.clone()))),
+        other => Err(format!("Unknown middleware: {}", other)),
+    }
+}
+
+/// Add `created` to `chain` unless a middleware of the same name is there
+pub fn register(
+    chain: &mut Vec<Arc<dyn CommandMiddleware>>,
+    created: Arc<dyn CommandMiddleware>,
+) -> Result<(), String> {
+    if chain.iter().any(|m| m.name() == created.name()) {
+        return Err(format!("Middleware already registered: {}", created.name()));
+    }
+    chain.push(created);
+    Ok(())
+}
+
+/// What the registered middleware has collected (`get_middleware_report`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MiddlewareReport {
+    /// From `logging`, oldest first
+    pub log: Vec<CommandLogEntry>,
+    /// From `metrics`, per command name
+    pub metrics: HashMap<String, CommandMetrics>,
+}
+
+/// Collect the data of every middleware in `chain`
+pub fn report(chain: &[Arc<dyn CommandMiddleware>]) -> MiddlewareReport {
+    let mut report = MiddlewareReport::default();
+    for m in chain {
+        m.collect(&mut report);
+    }
+    report
+}
+
+// ============================================================================
+// Logging
+// ============================================================================
+
+/// One executed secure command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandLogEntry {
+    pub command: String,
+    pub success: bool,
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// Appends every command outcome to an in-memory audit log
+///
+/// Only the command name is recorded, never its arguments, so the log
+/// doesn't leak business data that the encrypted channel protects.
+#[derive(Debug, Default)]
+pub struct LoggingMiddleware {
+    entries: Mutex<VecDeque<CommandLogEntry>>,
+}
+
+impl LoggingMiddleware {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Logged entries, oldest first
+    pub fn entries(&self) -> Vec<CommandLogEntry> {
+        self.entries.lock().unwrap().iter().cloned().collect()
+    }
+}
+
+impl CommandMiddleware for LoggingMiddleware {
+    fn name(&self) -> &'static str {
+        "logging"
+    }
+
+    fn collect(&self, report: &mut MiddlewareReport) {
+        report.log.extend(self.entries());
+    }
+
+    fn before(&self, _cmd: &SecureCommand) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn after(&self, cmd: &SecureCommand, response: &SecureResponse) {
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() == MAX_LOG_ENTRIES {
+            entries.pop_front();
+        }
+        entries.push_back(CommandLogEntry {
+            command: cmd.name().to_string(),
+            success: !matches!(response, SecureResponse::Error(_)),
+            occurred_at: Utc::now(),
+        });
+    }
+}
+
+// ============================================================================
+// Rate Limiting
+// ============================================================================
+
+/// Rejects commands once the token bucket is empty
+#[derive(Debug)]
+pub struct RateLimitMiddleware {
+    limiter: Mutex<RateLimiter>,
+}
+
+impl RateLimitMiddleware {
+    /// Burst size and refill rate (tokens/second); each command costs 1 token
+    pub fn new(max_tokens: f64, refill_rate: f64) -> Self {
+        Self {
+            limiter: Mutex::new(RateLimiter::new(max_tokens, refill_rate)),
+        }
+    }
+}
+
+impl Default for RateLimitMiddleware {
+    /// 100 command burst, 20 commands/second sustained
+    fn default() -> Self {
+        Self {
+            limiter: Mutex::new(RateLimiter::default()),
+        }
+    }
+}
+
+impl CommandMiddleware for RateLimitMiddleware {
+    fn name(&self) -> &'static str {
+        "rate_limit"
+    }
+
+    fn before(&self, _cmd: &SecureCommand) -> Result<(), String> {
+        self.limiter.lock().unwrap().check(1.0)
+    }
+
+    fn after(&self, _cmd: &SecureCommand, _response: &SecureResponse) {}
+}
+
+// ============================================================================
+// Feature Checks
+// ============================================================================
+
+/// License feature needed to run a command, if any
+pub fn required_feature(cmd: &SecureCommand) -> Option<&'static str> {
+    match cmd {
+        SecureCommand::GetForceGraphLayout { .. }
+        | SecureCommand::GetForceGraphLayoutClustered { .. }
+        | SecureCommand::UpdateNodePosition { .. }
+        | SecureCommand::ClearNodePins { .. }
+        | SecureCommand::ExportForceGraphSvg { .. }
+        | SecureCommand::ExportForceGraphGraphml { .. }
+        | SecureCommand::GetFleetForceGraph => Some("force_graph"),
+        SecureCommand::ExportFleetCsv | SecureCommand::ExportDeliveriesCsv { .. } => {
+            Some("export")
+        }
+        _ => None,
+    }
+}
+
+/// Check that `license` includes the feature `cmd` needs
+///
+/// Commands without a required feature (basic reads and writes) always pass.
+pub fn check_feature(license: &LicenseInfo, cmd: &SecureCommand) -> Result<(), String> {
+    match required_feature(cmd) {
+        Some(feature) if !license.has_feature(feature) => {
+            Err(format!("Feature not licensed: {}", feature))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Rejects commands whose feature is not in the active license
+///
+/// `execute_secure_command` applies the same check to every command; this
+/// middleware only makes a rejection visible to middleware registered after it.
+/// The license is read for every command, so an upgrade applies at once.
+#[derive(Debug)]
+pub struct FeatureCheckMiddleware {
+    license: Arc<Mutex<Option<LicenseInfo>>>,
+}
+
+impl FeatureCheckMiddleware {
+    pub fn new(license: Arc<Mutex<Option<LicenseInfo>>>) -> Self {
+        Self { license }
+    }
+}
+
+impl CommandMiddleware for FeatureCheckMiddleware {
+    fn name(&self) -> &'static str {
+        "feature_check"
+    }
+
+    fn before(&self, cmd: &SecureCommand) -> Result<(), String> {
+        match self.license.lock().unwrap().as_ref() {
+            Some(license) => check_feature(license, cmd),
+            None => Err("Secure session not initialized. Call init_secure_session first.".to_string()),
+        }
+    }
+
+    fn after(&self, _cmd: &SecureCommand, _response: &SecureResponse) {}
+}
+
+// ============================================================================
+// Metrics
+// ============================================================================
+
+/// Aggregated timings for one command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandMetrics {
+    pub calls: u64,
+    pub errors: u64,
+    pub total_duration_ms: f64,
+}
+
+/// Records call counts and durations per command
+///
+/// # Why a single start slot?
+/// `secure_invoke` holds the session crypto lock for the whole call,
+/// so commands never overlap.
+#[derive(Debug, Default)]
+pub struct MetricsMiddleware {
+    started: Mutex<Option<Instant>>,
+    metrics: Mutex<HashMap<&'static str, CommandMetrics>>,
+}
+
+impl MetricsMiddleware {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Metrics per command name
+    pub fn snapshot(&self) -> HashMap<String, CommandMetrics> {
+        self.metrics
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(name, metrics)| (name.to_string(), metrics.clone()))
+            .collect()
+    }
+}
+
+impl CommandMiddleware for MetricsMiddleware {
+    fn name(&self) -> &'static str {
+        "metrics"
+    }
+
+    fn collect(&self, report: &mut MiddlewareReport) {
+        report.metrics.extend(self.snapshot());
+    }
+
+    fn before(&self, _cmd: &SecureCommand) -> Result<(), String> {
+        *self.started.lock().unwrap() = Some(Instant::now());
+        Ok(())
+    }
+
+    fn after(&self, cmd: &SecureCommand, response: &SecureResponse) {
+        let elapsed_ms = self
+            .started
+            .lock()
+            .unwrap()
+            .take()
+            .map(|start| start.elapsed().as_secs_f64() * 1000.0)
+            .unwrap_or(0.0);
+
+        let mut metrics = self.metrics.lock().unwrap();
+        let entry = metrics.entry(cmd.name()).or_default();
+        entry.calls += 1;
+        entry.total_duration_ms += elapsed_ms;
+        if matches!(response, SecureResponse::Error(_)) {
+            entry.errors += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::license::license_with;
+
+    /// Records hook calls into a shared trace
+    struct TraceMiddleware {
+        name: &'static str,
+        reject: bool,
+        trace: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl CommandMiddleware for TraceMiddleware {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn before(&self, _cmd: &SecureCommand) -> Result<(), String> {
+            self.trace.lock().unwrap().push(format!("before:{}", self.name));
+            if self.reject {
+                Err(format!("rejected by {}", self.name))
+            } else {
+                Ok(())
+            }
+        }
+
+        fn after(&self, _cmd: &SecureCommand, _response: &SecureResponse) {
+            self.trace.lock().unwrap().push(format!("after:{}", self.name));
+        }
+    }
+
+    fn trace(name: &'static str, reject: bool, log: &Arc<Mutex<Vec<String>>>) -> Arc<dyn CommandMiddleware> {
+        Arc::new(TraceMiddleware { name, reject, trace: log.clone() })
+    }
+
+    fn command() -> SecureCommand {
+        SecureCommand::GetIssueById { issue_id: "ISS-0001".to_string() }
+    }
+
+    #[test]
+    fn test_middleware_order() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let chain = vec![trace("a", false, &log), trace("b", false, &log)];
+
+        let response = run_with_middleware(&chain, command(), |_| SecureResponse::Success(vec![]));
+
+        assert!(matches!(response, SecureResponse::Success(_)));
+        assert_eq!(*log.lock().unwrap(), vec!["before:a", "before:b", "after:b", "after:a"]);
+    }
+
+    #[test]
+    fn test_rejection_skips_dispatch() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let chain = vec![
+            trace("a", false, &log),
+            trace("b", true, &log),
+            trace("c", false, &log),
+        ];
+
+        let response = run_with_middleware(&chain, command(), |_| panic!("should not dispatch"));
+
+        assert!(matches!(response, SecureResponse::Error(ref e) if e == "rejected by b"));
+        assert_eq!(*log.lock().unwrap(), vec!["before:a", "before:b", "after:a"]);
+    }
+
+    #[test]
+    fn test_feature_check_and_logging() {
+        let license = license_with(&["premium"]);
+        let session_license = Arc::new(Mutex::new(Some(license)));
+        let mut chain = Vec::new();
+        for name in ["logging", "feature_check", "metrics"] {
+            register(&mut chain, create_middleware(name, &session_license).unwrap()).unwrap();
+        }
+        let duplicate = create_middleware("logging", &session_license).unwrap();
+        assert!(register(&mut chain, duplicate).is_err());
+        assert_eq!(chain.len(), 3);
+
+        let graph = SecureCommand::GetForceGraphLayout { bike_id: "BIKE-0001".to_string() };
+        let response = run_with_middleware(&chain, graph.clone(), |_| SecureResponse::Success(vec![]));
+        assert!(matches!(response, SecureResponse::Error(_)));
+
+        run_with_middleware(&chain, command(), |_| SecureResponse::Success(vec![]));
+
+        // An upgrade is seen by the middleware already registered
+        session_license.lock().unwrap().as_mut().unwrap().features.push("force_graph".to_string());
+        let response = run_with_middleware(&chain, graph, |_| SecureResponse::Success(vec![]));
+        assert!(matches!(response, SecureResponse::Success(_)));
+
+        let report = report(&chain);
+        assert_eq!(report.log.len(), 3);
+        assert_eq!(report.log[0].command, "GetForceGraphLayout");
+        assert!(!report.log[0].success);
+        assert!(report.log[1].success);
+        assert!(report.log[2].success);
+        assert_eq!(report.metrics["GetForceGraphLayout"].calls, 1);
+        assert_eq!(report.metrics["GetIssueById"].calls, 1);
+    }
+
+    #[test]
+    fn test_trial_license_gates_force_graph() {
+        let trial = LicenseInfo::trial(Utc::now());
+        let premium = license_with(&["*"]);
+        let deliveries = SecureCommand::GetDeliveries {
+            bike_id: None,
+            status: None,
+            from_ts: None,
+            to_ts: None,
+            sort_by: None,
+            sort_dir: None,
+            cursor: None,
+            limit: None,
+        };
+        let graph = SecureCommand::GetForceGraphLayout { bike_id: "BIKE-0001".to_string() };
+
+        assert!(check_feature(&trial, &deliveries).is_ok());
+        assert_eq!(
+            check_feature(&trial, &graph),
+            Err("Feature not licensed: force_graph".to_string())
+        );
+        assert!(check_feature(&premium, &deliveries).is_ok());
+        assert!(check_feature(&premium, &graph).is_ok());
+    }
+
+    #[test]
+    fn test_csv_exports_require_export_feature() {
+        let trial = LicenseInfo::trial(Utc::now());
+        let exporter = license_with(&["basic", "export"]);
+        let deliveries = SecureCommand::ExportDeliveriesCsv { from: None, to: None };
+
+        for cmd in [SecureCommand::ExportFleetCsv, deliveries] {
+            assert_eq!(
+                check_feature(&trial, &cmd),
+                Err("Feature not licensed: export".to_string())
+            );
+            assert!(check_feature(&exporter, &cmd).is_ok());
+        }
+    }
+}
@@ -0,0 +1,383 @@
+//! SQLite to PostgreSQL Migration
+//!
+//! # Purpose
+//! Customers moving from the desktop app to an on-premise HA deployment
+//! take their data with them. `migrate_sqlite_to_postgres` copies bikes,
+//! trips, deliveries, issues and the audit log from a SQLite file to a
+//! PostgreSQL server.
+//!
+//! # How
+//! - The SQLite file is opened read-only and read in one transaction, so
+//!   the app can keep writing to it without tearing the copy
+//! - Each table is streamed into a temporary table with `COPY`, then
+//!   inserted with `ON CONFLICT DO NOTHING`: rows whose ID the server
+//!   already has are skipped, so running the migration twice is harmless
+//! - All writes happen in one PostgreSQL transaction
+//!
+//! # Limits
+//! - The server must already have the schema (start the server app once).
+//!   A server behind the SQLite file's schema version is rejected.
+//! - Plain connections only (`SslMode::Disable`); this build has no TLS
+//! - Other tables (waypoints, fleet events, force graph pins, ...) are not
+//!   copied
+//! - SQLite columns the server lacks are dropped, with a warning
+
+use crate::pg_config::{DatabaseConfig, SslMode};
+use futures::SinkExt;
+use rusqlite::types::ValueRef;
+use rusqlite::{Connection, OpenFlags};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+use tokio_postgres::{NoTls, Transaction};
+
+/// Tables copied, parents first so foreign keys hold
+const MIGRATED_TABLES: [&str; 5] = ["bikes", "trips", "deliveries", "issues", "audit_log"];
+
+#[derive(Error, Debug)]
+pub enum MigrationError {
+    #[error("SQLite error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+
+    #[error("PostgreSQL error: {0}")]
+    Postgres(#[from] tokio_postgres::Error),
+
+    #[error("Configuration error: {0}")]
+    Config(String),
+
+    #[error(
+        "PostgreSQL schema is at version {postgres_version}, the SQLite file at \
+         {sqlite_version}; start the server app once to update it"
+    )]
+    SchemaBehind {
+        sqlite_version: u32,
+        postgres_version: u32,
+    },
+}
+
+/// Outcome of a migration
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct MigrationReport {
+    pub bikes_migrated: u32,
+    pub deliveries_migrated: u32,
+    pub issues_migrated: u32,
+    /// Rows of any table the server already had
+    pub skipped: u32,
+    /// Data that could not be copied, e.g. columns the server lacks
+    pub warnings: Vec<String>,
+}
+
+/// Rows of one SQLite table, as text for `COPY`; `None` is NULL
+#[derive(Debug)]
+struct TableDump {
+    table: &'static str,
+    columns: Vec<String>,
+    rows: Vec<Vec<Option<String>>>,
+}
+
+/// Everything read from the SQLite file
+#[derive(Debug)]
+struct SqliteSnapshot {
+    schema_version: u32,
+    tables: Vec<TableDump>,
+}
+
+/// Copy the fleet in `sqlite_path` to the PostgreSQL server of `pg_config`
+///
+/// # Errors
+/// - `Config` for TLS modes
+/// - `SchemaBehind` if the server has not applied the SQLite file's migrations
+/// - `Sqlite`/`Postgres` if reading or writing fails; nothing is written then
+pub async fn migrate_sqlite_to_postgres(
+    sqlite_path: PathBuf,
+    pg_config: DatabaseConfig,
+) -> Result<MigrationReport, MigrationError> {
+    if pg_config.effective_ssl_mode() != SslMode::Disable {
+        return Err(MigrationError::Config(
+            "Migration only supports PG_SSL_MODE=disable".to_string(),
+        ));
+    }
+
+    let snapshot = read_sqlite(&sqlite_path)?;
+
+    let (mut client, connection) = tokio_postgres::Config::new()
+        .host(&pg_config.host)
+        .port(pg_config.port)
+        .user(&pg_config.user)
+        .password(&pg_config.password)
+        .dbname(&pg_config.dbname)
+        .connect(NoTls)
+        .await?;
+    tokio::spawn(async move {
+        if let Err(error) = connection.await {
+            tracing::warn!(%error, "migration connection closed");
+        }
+    });
+
+    let tx = client.transaction().await?;
+
+    let postgres_version = postgres_schema_version(&tx).await?;
+    if postgres_version < snapshot.schema_version {
+        return Err(MigrationError::SchemaBehind {
+            sqlite_version: snapshot.schema_version,
+            postgres_version,
+        });
+    }
+
+    let mut report = MigrationReport::default();
+    for dump in &snapshot.tables {
+        let migrated = write_table(&tx, dump, &mut report).await?;
+        match dump.table {
+            "bikes" => report.bikes_migrated = migrated,
+            "deliveries" => report.deliveries_migrated = migrated,
+            "issues" => report.issues_migrated = migrated,
+            _ => {}
+        }
+    }
+
+    tx.commit().await?;
+    Ok(report)
+}
+
+// ============================================================================
+// SQLite side
+// ============================================================================
+
+/// Read the migrated tables of a SQLite file without writing to it
+fn read_sqlite(path: &Path) -> Result<SqliteSnapshot, MigrationError> {
+    let conn = Connection::open_with_flags(
+        path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )?;
+    // One read transaction: every table from the same moment
+    let tx = conn.unchecked_transaction()?;
+
+    let schema_version = tx.query_row("SELECT COALESCE(MAX(version), 0) FROM migrations", [], |row| {
+        row.get(0)
+    })?;
+    let tables = MIGRATED_TABLES
+        .iter()
+        .map(|table| dump_table(&tx, table))
+        .collect::<Result<_, _>>()?;
+
+    Ok(SqliteSnapshot { schema_version, tables })
+}
+
+fn dump_table(conn: &Connection, table: &'static str) -> Result<TableDump, MigrationError> {
+    let mut stmt = conn.prepare(&format!("SELECT * FROM {} ORDER BY rowid", table))?;
+    let columns: Vec<String> = stmt.column_names().into_iter().map(String::from).collect();
+
+    let mut rows = Vec::new();
+    let mut query = stmt.query([])?;
+    while let Some(row) = query.next()? {
+        let values = (0..columns.len())
+            .map(|i| row.get_ref(i).map(copy_text))
+            .collect::<Result<_, _>>()?;
+        rows.push(values);
+    }
+
+    Ok(TableDump { table, columns, rows })
+}
+
+/// A SQLite value in the text form PostgreSQL parses on input
+fn copy_text(value: ValueRef<'_>) -> Option<String> {
+    match value {
+        ValueRef::Null => None,
+        ValueRef::Integer(i) => Some(i.to_string()),
+        ValueRef::Real(f) => Some(f.to_string()),
+        ValueRef::Text(text) => Some(String::from_utf8_lossy(text).into_owned()),
+        // bytea hex format
+        ValueRef::Blob(bytes) => Some(
+            std::iter::once("\\x".to_string())
+                .chain(bytes.iter().map(|byte| format!("{:02x}", byte)))
+                .collect(),
+        ),
+    }
+}
+
+/// CSV for `COPY ... (FORMAT csv)` with the columns at `keep`
+///
+/// Values are always quoted, so an empty string stays distinct from NULL
+/// (an unquoted empty field).
+fn copy_csv(rows: &[Vec<Option<String>>], keep: &[usize]) -> Vec<u8> {
+    let mut csv = String::new();
+    for row in rows {
+        for (n, &i) in keep.iter().enumerate() {
+            if n > 0 {
+                csv.push(',');
+            }
+            if let Some(value) = &row[i] {
+                csv.push('"');
+                csv.push_str(&value.replace('"', "\"\""));
+                csv.push('"');
+            }
+        }
+        csv.push('\n');
+    }
+    csv.into_bytes()
+}
+
+// ============================================================================
+// PostgreSQL side
+// ============================================================================
+
+/// Highest applied migration; 0 for a server that never ran the app
+async fn postgres_schema_version(tx: &Transaction<'_>) -> Result<u32, MigrationError> {
+    let has_migrations: bool = tx
+        .query_one("SELECT to_regclass('migrations') IS NOT NULL", &[])
+        .await?
+        .get(0);
+    if !has_migrations {
+        return Ok(0);
+    }
+
+    let version: i32 = tx
+        .query_one("SELECT COALESCE(MAX(version), 0) FROM migrations", &[])
+        .await?
+        .get(0);
+    Ok(version as u32)
+}
+
+/// Copy one table, skipping rows the server already has
+///
+/// # Returns
+/// Rows inserted
+async fn write_table(
+    tx: &Transaction<'_>,
+    dump: &TableDump,
+    report: &mut MigrationReport,
+) -> Result<u32, MigrationError> {
+    let server_columns: HashSet<String> = tx
+        .query(
+            "SELECT column_name::text FROM information_schema.columns
+             WHERE table_schema = current_schema() AND table_name = $1",
+            &[&dump.table],
+        )
+        .await?
+        .iter()
+        .map(|row| row.get(0))
+        .collect();
+
+    let mut keep = Vec::new();
+    for (i, column) in dump.columns.iter().enumerate() {
+        if server_columns.contains(column) {
+            keep.push(i);
+        } else {
+            report.warnings.push(format!(
+                "{}.{} does not exist on the server and was not migrated",
+                dump.table, column
+            ));
+        }
+    }
+    let columns = keep
+        .iter()
+        .map(|&i| format!("\"{}\"", dump.columns[i].replace('"', "\"\"")))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let staging = format!("migrate_{}", dump.table);
+    tx.batch_execute(&format!(
+        "CREATE TEMP TABLE {} (LIKE {} INCLUDING DEFAULTS) ON COMMIT DROP",
+        staging, dump.table
+    ))
+    .await?;
+
+    let sink = tx
+        .copy_in(&format!("COPY {} ({}) FROM STDIN WITH (FORMAT csv)", staging, columns))
+        .await?;
+    let mut sink = std::pin::pin!(sink);
+    sink.send(std::io::Cursor::new(copy_csv(&dump.rows, &keep))).await?;
+    sink.as_mut().finish().await?;
+
+    let inserted = tx
+        .execute(
+            &format!(
+                "INSERT INTO {} ({}) SELECT {} FROM {} ON CONFLICT DO NOTHING",
+                dump.table, columns, columns, staging
+            ),
+            &[],
+        )
+        .await? as u32;
+
+    report.skipped += dump.rows.len() as u32 - inserted;
+    Ok(inserted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{Database, SqliteConfig};
+    use crate::models::{CreateIssueRequest, IssueCategory, IssueReporterType};
+
+    fn test_db(name: &str) -> (Database, PathBuf) {
+        let path = std::env::temp_dir().join(format!("{}_{}.db", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        (Database::new(path.clone(), SqliteConfig::default()).unwrap(), path)
+    }
+
+    fn row_count(path: &Path, table: &str) -> usize {
+        Connection::open(path)
+            .unwrap()
+            .query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get::<_, i64>(0))
+            .unwrap() as usize
+    }
+
+    #[test]
+    fn test_snapshot_row_counts_match_the_database() {
+        let (db, path) = test_db("migration_snapshot");
+        db.add_bike("Migrated", 52.37, 4.89, Some(80)).unwrap();
+        let bike_id = db.get_all_bikes(None, None).unwrap()[0].id.clone();
+        db.create_issue(&CreateIssueRequest {
+            bike_id,
+            delivery_id: None,
+            reporter_type: IssueReporterType::Deliverer,
+            category: Some(IssueCategory::Damaged),
+            description: "Flat tyre".to_string(),
+        })
+        .unwrap();
+
+        let snapshot = read_sqlite(&path).unwrap();
+
+        assert!(snapshot.schema_version > 0);
+        let tables: Vec<&str> = snapshot.tables.iter().map(|dump| dump.table).collect();
+        assert_eq!(tables, MIGRATED_TABLES);
+        for dump in &snapshot.tables {
+            assert_eq!(dump.rows.len(), row_count(&path, dump.table), "{}", dump.table);
+            assert!(dump.rows.iter().all(|row| row.len() == dump.columns.len()));
+        }
+        let bikes = &snapshot.tables[0];
+        let name = bikes.columns.iter().position(|column| column == "name").unwrap();
+        assert!(bikes.rows.iter().any(|row| row[name].as_deref() == Some("Migrated")));
+    }
+
+    #[test]
+    fn test_missing_file_is_not_created() {
+        let path = std::env::temp_dir().join(format!("migration_missing_{}.db", std::process::id()));
+
+        assert!(matches!(read_sqlite(&path), Err(MigrationError::Sqlite(_))));
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_copy_csv_keeps_null_and_empty_apart() {
+        let rows = vec![
+            vec![Some("BIKE-0001".to_string()), None, Some(String::new())],
+            vec![Some("say \"hi\"".to_string()), Some("85".to_string()), None],
+        ];
+
+        let csv = String::from_utf8(copy_csv(&rows, &[0, 1, 2])).unwrap();
+        assert_eq!(csv, "\"BIKE-0001\",,\"\"\n\"say \"\"hi\"\"\",\"85\",\n");
+        let csv = String::from_utf8(copy_csv(&rows, &[2, 0])).unwrap();
+        assert_eq!(csv, "\"\",\"BIKE-0001\"\n,\"say \"\"hi\"\"\"\n");
+    }
+
+    #[test]
+    fn test_copy_text() {
+        assert_eq!(copy_text(ValueRef::Null), None);
+        assert_eq!(copy_text(ValueRef::Integer(85)).as_deref(), Some("85"));
+        assert_eq!(copy_text(ValueRef::Real(52.3791)).as_deref(), Some("52.3791"));
+        assert_eq!(copy_text(ValueRef::Text(b"available")).as_deref(), Some("available"));
+        assert_eq!(copy_text(ValueRef::Blob(&[0x0f, 0xa0])).as_deref(), Some("\\x0fa0"));
+    }
+}
@@ -0,0 +1,805 @@
+//! Versioned Schema Migrations
+//!
+//! # Purpose
+//! `CREATE TABLE IF NOT EXISTS` can't change a table that already exists, so
+//! schema changes reach deployed databases as numbered migrations. The
+//! `migrations` table records the versions a database has applied; on
+//! startup `MigrationRunner` applies the missing ones in version order, each
+//! in its own transaction.
+//!
+//! # Adding a Migration
+//! Append a `Migration` with the next version to `MIGRATIONS` of both
+//! backends. Never edit a migration that has shipped; fix it with a new one.
+//!
+//! Version 1 is the schema at the time migrations were introduced. It only
+//! uses `IF NOT EXISTS`, so databases created before then adopt it as-is.
+
+#[cfg(feature = "sqlite")]
+use crate::database::{DatabaseError, QueryContext};
+#[cfg(feature = "postgres")]
+use crate::database_pg::{DatabaseError, QueryContext};
+use std::collections::HashSet;
+
+/// A single schema change
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    /// Unique version; migrations apply in ascending order
+    pub version: u32,
+    /// SQL executed as one batch
+    pub up: &'static str,
+}
+
+/// Applies the migrations a database hasn't seen yet
+pub struct MigrationRunner {
+    /// Sorted by version
+    migrations: Vec<Migration>,
+}
+
+impl MigrationRunner {
+    /// # Panics
+    /// If two migrations share a version
+    pub fn new(mut migrations: Vec<Migration>) -> Self {
+        migrations.sort_by_key(|migration| migration.version);
+        assert!(
+            migrations.windows(2).all(|pair| pair[0].version != pair[1].version),
+            "duplicate migration version"
+        );
+        Self { migrations }
+    }
+
+    /// Migrations not in `applied`, in version order
+    fn pending<'a>(&'a self, applied: &'a HashSet<u32>) -> impl Iterator<Item = &'a Migration> {
+        self.migrations
+            .iter()
+            .filter(move |migration| !applied.contains(&migration.version))
+    }
+}
+
+impl Default for MigrationRunner {
+    /// The migrations of the compiled backend
+    fn default() -> Self {
+        Self::new(MIGRATIONS.to_vec())
+    }
+}
+
+// ============================================================================
+// SQLite
+// ============================================================================
+
+#[cfg(feature = "sqlite")]
+impl MigrationRunner {
+    /// Apply every pending migration
+    ///
+    /// # Returns
+    /// Versions applied by this call, in order
+    ///
+    /// # Errors
+    /// `MigrationFailed` if a migration fails; it is rolled back and later
+    /// migrations are not attempted
+    pub fn run(&self, conn: &rusqlite::Connection) -> Result<Vec<u32>, DatabaseError> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS migrations (
+                version INTEGER PRIMARY KEY,
+                applied_at TEXT NOT NULL
+            );",
+        )
+        .context("run_migrations")?;
+
+        let applied: HashSet<u32> = conn
+            .prepare("SELECT version FROM migrations")
+            .and_then(|mut stmt| stmt.query_map([], |row| row.get(0))?.collect())
+            .context("run_migrations")?;
+
+        let mut newly_applied = Vec::new();
+        for migration in self.pending(&applied) {
+            let tx = conn.unchecked_transaction().context("run_migrations")?;
+
+            // Dropping `tx` on error rolls the migration back
+            tx.execute_batch(migration.up)
+                .and_then(|()| {
+                    tx.execute(
+                        "INSERT INTO migrations (version, applied_at) VALUES (?1, ?2)",
+                        rusqlite::params![migration.version, chrono::Utc::now().to_rfc3339()],
+                    )
+                })
+                .map_err(|e| DatabaseError::MigrationFailed(migration.version, e.to_string()))?;
+
+            tx.commit().context("run_migrations")?;
+            newly_applied.push(migration.version);
+        }
+
+        Ok(newly_applied)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+const MIGRATIONS: &[Migration] = &[Migration {
+    version: 1,
+    up: r#"
+        CREATE TABLE IF NOT EXISTS bikes (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            status TEXT NOT NULL DEFAULT 'available',
+            latitude REAL NOT NULL,
+            longitude REAL NOT NULL,
+            battery_level INTEGER,
+            last_maintenance TEXT,
+            total_trips INTEGER NOT NULL DEFAULT 0,
+            total_distance_km REAL NOT NULL DEFAULT 0.0,
+            reservation_expires_at TEXT,
+            maintenance_threshold_override INTEGER,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS trips (
+            id TEXT PRIMARY KEY,
+            bike_id TEXT NOT NULL,
+            start_time TEXT NOT NULL,
+            end_time TEXT,
+            start_latitude REAL NOT NULL,
+            start_longitude REAL NOT NULL,
+            end_latitude REAL,
+            end_longitude REAL,
+            distance_km REAL,
+            FOREIGN KEY (bike_id) REFERENCES bikes(id)
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_bikes_status ON bikes(status);
+        CREATE INDEX IF NOT EXISTS idx_trips_bike_id ON trips(bike_id);
+
+        -- ================================================================
+        -- Deliveries table
+        -- ================================================================
+        -- Why this schema?
+        -- - bike_id links to the courier for force graph relationships
+        -- - status enables filtering (completed/ongoing/upcoming)
+        -- - rating/complaint only populated for completed deliveries
+        -- - Timestamps enable time-series analytics
+        CREATE TABLE IF NOT EXISTS deliveries (
+            id TEXT PRIMARY KEY,
+            bike_id TEXT NOT NULL,
+            status TEXT NOT NULL DEFAULT 'upcoming',
+            priority TEXT NOT NULL DEFAULT 'normal',
+            customer_name TEXT NOT NULL,
+            customer_address TEXT NOT NULL,
+            restaurant_name TEXT NOT NULL,
+            restaurant_address TEXT NOT NULL,
+            rating INTEGER,
+            complaint TEXT,
+            created_at TEXT NOT NULL,
+            completed_at TEXT,
+            FOREIGN KEY (bike_id) REFERENCES bikes(id)
+        );
+
+        -- ================================================================
+        -- Issues table
+        -- ================================================================
+        -- Why this schema?
+        -- - delivery_id is optional: issues can be standalone (bike problems)
+        -- - bike_id always present: every issue links to a deliverer
+        -- - This dual-linking enables force graph to show:
+        --   * Issues connected to specific deliveries
+        --   * Standalone issues connected directly to deliverer
+        CREATE TABLE IF NOT EXISTS issues (
+            id TEXT PRIMARY KEY,
+            delivery_id TEXT,
+            bike_id TEXT NOT NULL,
+            reporter_type TEXT NOT NULL,
+            category TEXT NOT NULL,
+            description TEXT NOT NULL,
+            resolved INTEGER NOT NULL DEFAULT 0,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+            FOREIGN KEY (delivery_id) REFERENCES deliveries(id),
+            FOREIGN KEY (bike_id) REFERENCES bikes(id)
+        );
+
+        -- Indexes for efficient querying
+        CREATE INDEX IF NOT EXISTS idx_deliveries_bike_id ON deliveries(bike_id);
+        CREATE INDEX IF NOT EXISTS idx_deliveries_status ON deliveries(status);
+        CREATE INDEX IF NOT EXISTS idx_issues_bike_id ON issues(bike_id);
+        CREATE INDEX IF NOT EXISTS idx_issues_delivery_id ON issues(delivery_id);
+        CREATE INDEX IF NOT EXISTS idx_issues_resolved ON issues(resolved);
+    "#,
+}, Migration {
+    // Issue resolution details; already resolved issues were resolved
+    // when they were last updated
+    version: 2,
+    up: r#"
+        ALTER TABLE issues ADD COLUMN resolved_at TEXT;
+        ALTER TABLE issues ADD COLUMN resolution_note TEXT;
+        UPDATE issues SET resolved_at = updated_at WHERE resolved = 1;
+    "#,
+}, Migration {
+    // Soft delete for bikes; retired bikes keep their history
+    version: 3,
+    up: r#"
+        ALTER TABLE bikes ADD COLUMN deleted_at TEXT;
+    "#,
+}, Migration {
+    // Audit trail of changes to bikes, deliveries and issues
+    version: 4,
+    up: r#"
+        CREATE TABLE IF NOT EXISTS audit_log (
+            id TEXT PRIMARY KEY,
+            table_name TEXT NOT NULL,
+            row_id TEXT NOT NULL,
+            operation TEXT NOT NULL,
+            old_value_json TEXT,
+            new_value_json TEXT,
+            occurred_at TEXT NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_audit_log_row ON audit_log(table_name, row_id);
+        CREATE INDEX IF NOT EXISTS idx_audit_log_occurred_at ON audit_log(occurred_at);
+    "#,
+}, Migration {
+    // Force graph nodes the user dragged into place
+    version: 5,
+    up: r#"
+        CREATE TABLE IF NOT EXISTS force_graph_pins (
+            bike_id TEXT NOT NULL,
+            node_id TEXT NOT NULL,
+            x REAL NOT NULL,
+            y REAL NOT NULL,
+            PRIMARY KEY (bike_id, node_id)
+        );
+    "#,
+}, Migration {
+    // Restaurants normalized out of deliveries. Triggers link new
+    // deliveries to their restaurant and keep its counters current.
+    version: 6,
+    up: r#"
+        CREATE TABLE IF NOT EXISTS restaurants (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL UNIQUE,
+            address TEXT,
+            total_deliveries INTEGER NOT NULL DEFAULT 0,
+            avg_rating REAL
+        );
+
+        -- Existing restaurants keep the address of their latest delivery
+        INSERT INTO restaurants (id, name, address, total_deliveries, avg_rating)
+        SELECT 'REST-' || lower(hex(randomblob(8))),
+               d.restaurant_name,
+               (SELECT latest.restaurant_address FROM deliveries latest
+                WHERE latest.restaurant_name = d.restaurant_name
+                ORDER BY latest.created_at DESC LIMIT 1),
+               COUNT(*),
+               AVG(d.rating)
+        FROM deliveries d
+        GROUP BY d.restaurant_name;
+
+        ALTER TABLE deliveries ADD COLUMN restaurant_id TEXT REFERENCES restaurants(id);
+        UPDATE deliveries
+        SET restaurant_id = (SELECT id FROM restaurants WHERE name = deliveries.restaurant_name);
+        CREATE INDEX IF NOT EXISTS idx_deliveries_restaurant_id ON deliveries(restaurant_id);
+
+        CREATE TRIGGER IF NOT EXISTS deliveries_restaurant_insert
+        AFTER INSERT ON deliveries
+        BEGIN
+            INSERT OR IGNORE INTO restaurants (id, name, address)
+            VALUES ('REST-' || lower(hex(randomblob(8))), NEW.restaurant_name, NEW.restaurant_address);
+            UPDATE deliveries
+            SET restaurant_id = (SELECT id FROM restaurants WHERE name = NEW.restaurant_name)
+            WHERE id = NEW.id;
+            UPDATE restaurants
+            SET total_deliveries = total_deliveries + 1,
+                avg_rating = (SELECT AVG(rating) FROM deliveries WHERE restaurant_id = restaurants.id)
+            WHERE name = NEW.restaurant_name;
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS deliveries_restaurant_rating
+        AFTER UPDATE OF rating ON deliveries
+        BEGIN
+            UPDATE restaurants
+            SET avg_rating = (SELECT AVG(rating) FROM deliveries WHERE restaurant_id = restaurants.id)
+            WHERE id = NEW.restaurant_id;
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS deliveries_restaurant_delete
+        AFTER DELETE ON deliveries
+        BEGIN
+            UPDATE restaurants
+            SET total_deliveries = total_deliveries - 1,
+                avg_rating = (SELECT AVG(rating) FROM deliveries WHERE restaurant_id = restaurants.id)
+            WHERE id = OLD.restaurant_id;
+        END;
+    "#,
+}, Migration {
+    // GPS trail of a delivery, in the order the points were recorded.
+    // Version 8 to stay in step with PostgreSQL's numbering.
+    version: 8,
+    up: r#"
+        CREATE TABLE IF NOT EXISTS delivery_waypoints (
+            id TEXT PRIMARY KEY,
+            delivery_id TEXT NOT NULL REFERENCES deliveries(id),
+            sequence INTEGER NOT NULL,
+            latitude REAL,
+            longitude REAL,
+            recorded_at TEXT
+        );
+
+        CREATE UNIQUE INDEX IF NOT EXISTS idx_delivery_waypoints_sequence
+            ON delivery_waypoints(delivery_id, sequence);
+    "#,
+}, Migration {
+    // Scheduled maintenance, alongside the last one performed
+    version: 9,
+    up: r#"
+        ALTER TABLE bikes ADD COLUMN next_maintenance_at TEXT;
+    "#,
+}, Migration {
+    // Append-only log of fleet changes, polled by ID (`get_events_since`).
+    // SQLite has no version 10; the partial indexes are PostgreSQL only.
+    version: 11,
+    up: r#"
+        CREATE TABLE IF NOT EXISTS fleet_events (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            event_type TEXT NOT NULL,
+            payload_json TEXT NOT NULL,
+            occurred_at TEXT NOT NULL
+        );
+    "#,
+}];
+
+// ============================================================================
+// PostgreSQL
+// ============================================================================
+
+/// Advisory lock held while migrating, so that instances starting at the
+/// same time against one cluster don't apply a migration twice
+#[cfg(feature = "postgres")]
+const MIGRATION_LOCK_ID: i64 = 0x6162_665f_6d69_6772;
+
+#[cfg(feature = "postgres")]
+impl MigrationRunner {
+    /// Apply every pending migration
+    ///
+    /// # Returns
+    /// Versions applied by this call, in order
+    ///
+    /// # Errors
+    /// `MigrationFailed` if a migration fails; it is rolled back and later
+    /// migrations are not attempted
+    pub async fn run(&self, client: &mut tokio_postgres::Client) -> Result<Vec<u32>, DatabaseError> {
+        client
+            .batch_execute(
+                "CREATE TABLE IF NOT EXISTS migrations (
+                    version INTEGER PRIMARY KEY,
+                    applied_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+                );",
+            )
+            .await
+            .context("run_migrations")?;
+
+        let applied: HashSet<u32> = client
+            .query("SELECT version FROM migrations", &[])
+            .await
+            .context("run_migrations")?
+            .iter()
+            .map(|row| row.get::<_, i32>(0) as u32)
+            .collect();
+
+        let mut newly_applied = Vec::new();
+        for migration in self.pending(&applied) {
+            let version = migration.version as i32;
+            let tx = client.transaction().await.context("run_migrations")?;
+
+            tx.execute("SELECT pg_advisory_xact_lock($1)", &[&MIGRATION_LOCK_ID])
+                .await
+                .context("run_migrations")?;
+            let already_applied: bool = tx
+                .query_one(
+                    "SELECT EXISTS (SELECT 1 FROM migrations WHERE version = $1)",
+                    &[&version],
+                )
+                .await
+                .context("run_migrations")?
+                .get(0);
+            if already_applied {
+                // Another instance got there first
+                continue;
+            }
+
+            // Dropping `tx` on error rolls the migration back
+            let result = match tx.batch_execute(migration.up).await {
+                Ok(()) => tx
+                    .execute("INSERT INTO migrations (version) VALUES ($1)", &[&version])
+                    .await
+                    .map(|_| ()),
+                Err(e) => Err(e),
+            };
+            result.map_err(|e| DatabaseError::MigrationFailed(migration.version, e.to_string()))?;
+
+            tx.commit().await.context("run_migrations")?;
+            newly_applied.push(migration.version);
+        }
+
+        Ok(newly_applied)
+    }
+}
+
+/// Partial indexes over bikes that are not offline (`get_active_bikes`)
+/// and bikes out on a ride
+///
+/// Kept apart so the SQLite tests can check it re-runs cleanly; both
+/// backends accept partial indexes.
+#[cfg(any(feature = "postgres", test))]
+const ACTIVE_BIKE_INDEXES: &str = r#"
+        CREATE INDEX IF NOT EXISTS idx_bikes_active ON bikes(status) WHERE status != 'offline';
+        CREATE INDEX IF NOT EXISTS idx_bikes_in_use ON bikes(id, latitude, longitude)
+            WHERE status = 'in_use';
+    "#;
+
+#[cfg(feature = "postgres")]
+const MIGRATIONS: &[Migration] = &[Migration {
+    version: 1,
+    up: r#"
+        -- Enable UUID extension for better primary keys
+        CREATE EXTENSION IF NOT EXISTS "uuid-ossp";
+
+        -- Bikes table
+        CREATE TABLE IF NOT EXISTS bikes (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            status TEXT NOT NULL DEFAULT 'available',
+            latitude DOUBLE PRECISION NOT NULL,
+            longitude DOUBLE PRECISION NOT NULL,
+            battery_level INTEGER,
+            last_maintenance TIMESTAMPTZ,
+            total_trips INTEGER NOT NULL DEFAULT 0,
+            total_distance_km DOUBLE PRECISION NOT NULL DEFAULT 0.0,
+            reservation_expires_at TIMESTAMPTZ,
+            maintenance_threshold_override INTEGER,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+        );
+
+        -- Trips table
+        CREATE TABLE IF NOT EXISTS trips (
+            id TEXT PRIMARY KEY,
+            bike_id TEXT NOT NULL REFERENCES bikes(id),
+            start_time TIMESTAMPTZ NOT NULL,
+            end_time TIMESTAMPTZ,
+            start_latitude DOUBLE PRECISION NOT NULL,
+            start_longitude DOUBLE PRECISION NOT NULL,
+            end_latitude DOUBLE PRECISION,
+            end_longitude DOUBLE PRECISION,
+            distance_km DOUBLE PRECISION
+        );
+
+        -- Deliveries table
+        CREATE TABLE IF NOT EXISTS deliveries (
+            id TEXT PRIMARY KEY,
+            bike_id TEXT NOT NULL REFERENCES bikes(id),
+            status TEXT NOT NULL DEFAULT 'upcoming',
+            priority TEXT NOT NULL DEFAULT 'normal',
+            customer_name TEXT NOT NULL,
+            customer_address TEXT NOT NULL,
+            restaurant_name TEXT NOT NULL,
+            restaurant_address TEXT NOT NULL,
+            rating INTEGER CHECK (rating >= 1 AND rating <= 5),
+            complaint TEXT,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            completed_at TIMESTAMPTZ
+        );
+
+        -- Issues table
+        CREATE TABLE IF NOT EXISTS issues (
+            id TEXT PRIMARY KEY,
+            delivery_id TEXT REFERENCES deliveries(id) ON DELETE SET NULL,
+            bike_id TEXT NOT NULL REFERENCES bikes(id),
+            reporter_type TEXT NOT NULL,
+            category TEXT NOT NULL,
+            description TEXT NOT NULL,
+            resolved BOOLEAN NOT NULL DEFAULT FALSE,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+        );
+
+        -- Databases created before issues.updated_at existed
+        ALTER TABLE issues ADD COLUMN IF NOT EXISTS updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+
+        -- Databases created before bike reservations existed
+        ALTER TABLE bikes ADD COLUMN IF NOT EXISTS reservation_expires_at TIMESTAMPTZ;
+
+        -- Databases created before per-bike maintenance thresholds existed
+        ALTER TABLE bikes ADD COLUMN IF NOT EXISTS maintenance_threshold_override INTEGER;
+
+        -- Databases created before deliveries could be deleted: deleting a
+        -- delivery turns its issues into standalone issues
+        ALTER TABLE issues
+            DROP CONSTRAINT IF EXISTS issues_delivery_id_fkey,
+            ADD CONSTRAINT issues_delivery_id_fkey
+                FOREIGN KEY (delivery_id) REFERENCES deliveries(id) ON DELETE SET NULL;
+
+        -- Databases created before delivery priorities existed
+        ALTER TABLE deliveries ADD COLUMN IF NOT EXISTS priority TEXT NOT NULL DEFAULT 'normal';
+
+        -- Indexes for performance
+        CREATE INDEX IF NOT EXISTS idx_bikes_status ON bikes(status);
+        CREATE INDEX IF NOT EXISTS idx_trips_bike_id ON trips(bike_id);
+        CREATE INDEX IF NOT EXISTS idx_deliveries_bike_id ON deliveries(bike_id);
+        CREATE INDEX IF NOT EXISTS idx_deliveries_status ON deliveries(status);
+        CREATE INDEX IF NOT EXISTS idx_issues_bike_id ON issues(bike_id);
+        CREATE INDEX IF NOT EXISTS idx_issues_delivery_id ON issues(delivery_id);
+        CREATE INDEX IF NOT EXISTS idx_issues_resolved ON issues(resolved);
+
+        -- Function to update updated_at timestamp
+        CREATE OR REPLACE FUNCTION update_updated_at_column()
+        RETURNS TRIGGER AS $$
+        BEGIN
+            NEW.updated_at = NOW();
+            RETURN NEW;
+        END;
+        $$ language 'plpgsql';
+
+        -- Trigger for bikes table
+        DROP TRIGGER IF EXISTS update_bikes_updated_at ON bikes;
+        CREATE TRIGGER update_bikes_updated_at
+            BEFORE UPDATE ON bikes
+            FOR EACH ROW
+            EXECUTE FUNCTION update_updated_at_column();
+    "#,
+}, Migration {
+    // Issue resolution details; already resolved issues were resolved
+    // when they were last updated
+    version: 2,
+    up: r#"
+        ALTER TABLE issues
+            ADD COLUMN IF NOT EXISTS resolved_at TIMESTAMPTZ,
+            ADD COLUMN IF NOT EXISTS resolution_note TEXT;
+        UPDATE issues SET resolved_at = updated_at WHERE resolved AND resolved_at IS NULL;
+    "#,
+}, Migration {
+    // Soft delete for bikes; retired bikes keep their history
+    version: 3,
+    up: r#"
+        ALTER TABLE bikes ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+    "#,
+}, Migration {
+    // Audit trail of changes to bikes, deliveries and issues
+    version: 4,
+    up: r#"
+        CREATE TABLE IF NOT EXISTS audit_log (
+            id TEXT PRIMARY KEY,
+            table_name TEXT NOT NULL,
+            row_id TEXT NOT NULL,
+            operation TEXT NOT NULL,
+            old_value_json TEXT,
+            new_value_json TEXT,
+            occurred_at TIMESTAMPTZ NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_audit_log_row ON audit_log(table_name, row_id);
+        CREATE INDEX IF NOT EXISTS idx_audit_log_occurred_at ON audit_log(occurred_at);
+    "#,
+}, Migration {
+    // Force graph nodes the user dragged into place
+    version: 5,
+    up: r#"
+        CREATE TABLE IF NOT EXISTS force_graph_pins (
+            bike_id TEXT NOT NULL,
+            node_id TEXT NOT NULL,
+            x DOUBLE PRECISION NOT NULL,
+            y DOUBLE PRECISION NOT NULL,
+            PRIMARY KEY (bike_id, node_id)
+        );
+    "#,
+}, Migration {
+    // Restaurants normalized out of deliveries. Triggers link new
+    // deliveries to their restaurant and keep its counters current.
+    version: 6,
+    up: r#"
+        CREATE TABLE IF NOT EXISTS restaurants (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL UNIQUE,
+            address TEXT,
+            total_deliveries INTEGER NOT NULL DEFAULT 0,
+            avg_rating DOUBLE PRECISION
+        );
+
+        -- Existing restaurants keep the address of their latest delivery
+        INSERT INTO restaurants (id, name, address, total_deliveries, avg_rating)
+        SELECT 'REST-' || substr(md5(random()::text || restaurant_name), 1, 16),
+               restaurant_name,
+               (array_agg(restaurant_address ORDER BY created_at DESC))[1],
+               COUNT(*),
+               AVG(rating)
+        FROM deliveries
+        GROUP BY restaurant_name
+        ON CONFLICT (name) DO NOTHING;
+
+        ALTER TABLE deliveries
+            ADD COLUMN IF NOT EXISTS restaurant_id TEXT REFERENCES restaurants(id);
+        UPDATE deliveries d SET restaurant_id = r.id
+        FROM restaurants r
+        WHERE r.name = d.restaurant_name AND d.restaurant_id IS NULL;
+        CREATE INDEX IF NOT EXISTS idx_deliveries_restaurant_id ON deliveries(restaurant_id);
+
+        -- Link a new delivery to its restaurant, creating it on first sight
+        CREATE OR REPLACE FUNCTION assign_delivery_restaurant()
+        RETURNS TRIGGER AS $$
+        BEGIN
+            INSERT INTO restaurants (id, name, address)
+            VALUES ('REST-' || substr(md5(random()::text || NEW.restaurant_name), 1, 16),
+                    NEW.restaurant_name, NEW.restaurant_address)
+            ON CONFLICT (name) DO NOTHING;
+            SELECT id INTO NEW.restaurant_id FROM restaurants WHERE name = NEW.restaurant_name;
+            RETURN NEW;
+        END;
+        $$ language 'plpgsql';
+
+        -- Keep total_deliveries and avg_rating in step with deliveries
+        CREATE OR REPLACE FUNCTION refresh_restaurant_stats()
+        RETURNS TRIGGER AS $$
+        DECLARE
+            target TEXT := CASE WHEN TG_OP = 'DELETE' THEN OLD.restaurant_id ELSE NEW.restaurant_id END;
+        BEGIN
+            UPDATE restaurants
+            SET total_deliveries = total_deliveries
+                    + CASE TG_OP WHEN 'INSERT' THEN 1 WHEN 'DELETE' THEN -1 ELSE 0 END,
+                avg_rating = (SELECT AVG(rating) FROM deliveries WHERE restaurant_id = target)
+            WHERE id = target;
+            RETURN NULL;
+        END;
+        $$ language 'plpgsql';
+
+        DROP TRIGGER IF EXISTS assign_delivery_restaurant ON deliveries;
+        CREATE TRIGGER assign_delivery_restaurant
+            BEFORE INSERT ON deliveries
+            FOR EACH ROW
+            EXECUTE FUNCTION assign_delivery_restaurant();
+
+        DROP TRIGGER IF EXISTS refresh_restaurant_stats ON deliveries;
+        CREATE TRIGGER refresh_restaurant_stats
+            AFTER INSERT OR DELETE OR UPDATE OF rating ON deliveries
+            FOR EACH ROW
+            EXECUTE FUNCTION refresh_restaurant_stats();
+    "#,
+}, Migration {
+    // Announce bike status changes to listening connections
+    // (`EventSubscriber`). PostgreSQL only: SQLite has no version 7.
+    version: 7,
+    up: r#"
+        CREATE OR REPLACE FUNCTION notify_bike_status_change()
+        RETURNS TRIGGER AS $$
+        BEGIN
+            PERFORM pg_notify(
+                'bike_status_changes',
+                json_build_object('bike_id', NEW.id, 'status', NEW.status)::text
+            );
+            RETURN NULL;
+        END;
+        $$ language 'plpgsql';
+
+        DROP TRIGGER IF EXISTS notify_bike_status_change ON bikes;
+        CREATE TRIGGER notify_bike_status_change
+            AFTER UPDATE ON bikes
+            FOR EACH ROW
+            WHEN (OLD.status IS DISTINCT FROM NEW.status)
+            EXECUTE FUNCTION notify_bike_status_change();
+    "#,
+}, Migration {
+    // GPS trail of a delivery, in the order the points were recorded
+    version: 8,
+    up: r#"
+        CREATE TABLE IF NOT EXISTS delivery_waypoints (
+            id TEXT PRIMARY KEY,
+            delivery_id TEXT NOT NULL REFERENCES deliveries(id),
+            sequence INTEGER NOT NULL,
+            latitude DOUBLE PRECISION,
+            longitude DOUBLE PRECISION,
+            recorded_at TIMESTAMPTZ
+        );
+
+        CREATE UNIQUE INDEX IF NOT EXISTS idx_delivery_waypoints_sequence
+            ON delivery_waypoints(delivery_id, sequence);
+    "#,
+}, Migration {
+    // Scheduled maintenance, alongside the last one performed
+    version: 9,
+    up: r#"
+        ALTER TABLE bikes ADD COLUMN IF NOT EXISTS next_maintenance_at TIMESTAMPTZ;
+        CREATE INDEX IF NOT EXISTS idx_bikes_next_maintenance_at ON bikes(next_maintenance_at);
+    "#,
+}, Migration {
+    // Partial indexes for the bikes most queries are about
+    version: 10,
+    up: ACTIVE_BIKE_INDEXES,
+}, Migration {
+    // Append-only log of fleet changes, polled by ID (`get_events_since`)
+    version: 11,
+    up: r#"
+        CREATE TABLE IF NOT EXISTS fleet_events (
+            id BIGSERIAL PRIMARY KEY,
+            event_type TEXT NOT NULL,
+            payload_json TEXT NOT NULL,
+            occurred_at TIMESTAMPTZ NOT NULL
+        );
+    "#,
+}];
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+    use rusqlite::Connection;
+
+    fn applied_versions(conn: &Connection) -> Vec<u32> {
+        conn.prepare("SELECT version FROM migrations ORDER BY version")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_run_applies_pending_migrations_in_order_once() {
+        let conn = Connection::open_in_memory().unwrap();
+        let runner = MigrationRunner::new(vec![
+            Migration { version: 2, up: "ALTER TABLE t ADD COLUMN b INTEGER;" },
+            Migration { version: 1, up: "CREATE TABLE t (a INTEGER);" },
+        ]);
+
+        assert_eq!(runner.run(&conn).unwrap(), vec![1, 2]);
+        assert_eq!(runner.run(&conn).unwrap(), Vec::<u32>::new());
+        assert_eq!(applied_versions(&conn), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_failed_migration_rolls_back() {
+        let conn = Connection::open_in_memory().unwrap();
+        let runner = MigrationRunner::new(vec![
+            Migration { version: 1, up: "CREATE TABLE t (a INTEGER);" },
+            Migration { version: 2, up: "CREATE TABLE u (a INTEGER); INSERT INTO missing VALUES (1);" },
+            Migration { version: 3, up: "CREATE TABLE v (a INTEGER);" },
+        ]);
+
+        let err = runner.run(&conn).unwrap_err();
+        assert!(matches!(err, DatabaseError::MigrationFailed(2, _)));
+        assert_eq!(applied_versions(&conn), vec![1]);
+
+        let tables: Vec<String> = conn
+            .prepare("SELECT name FROM sqlite_master WHERE type = 'table' AND name IN ('u', 'v')")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert!(tables.is_empty());
+    }
+
+    #[test]
+    fn test_schema_migrations_apply_to_empty_database() {
+        let conn = Connection::open_in_memory().unwrap();
+        MigrationRunner::default().run(&conn).unwrap();
+
+        let expected: Vec<u32> = MIGRATIONS.iter().map(|migration| migration.version).collect();
+        assert_eq!(applied_versions(&conn), expected);
+    }
+
+    #[test]
+    fn test_active_bike_indexes_are_idempotent() {
+        let conn = Connection::open_in_memory().unwrap();
+        MigrationRunner::default().run(&conn).unwrap();
+
+        conn.execute_batch(ACTIVE_BIKE_INDEXES).unwrap();
+        conn.execute_batch(ACTIVE_BIKE_INDEXES).unwrap();
+
+        let indexes: Vec<String> = conn
+            .prepare(
+                "SELECT name FROM sqlite_master
+                 WHERE type = 'index' AND name IN ('idx_bikes_active', 'idx_bikes_in_use')
+                 ORDER BY name",
+            )
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(indexes, vec!["idx_bikes_active", "idx_bikes_in_use"]);
+    }
+}
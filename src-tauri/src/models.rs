@@ -1,5 +1,7 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Represents a bike in the Amsterdam fleet
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -13,6 +15,16 @@ pub struct Bike {
     pub last_maintenance: Option<DateTime<Utc>>,
     pub total_trips: u32,
     pub total_distance_km: f64,
+    /// When a `Reserved` bike becomes available again
+    pub reservation_expires_at: Option<DateTime<Utc>>,
+    /// Days between maintenances for this bike; `None` uses the fleet setting
+    pub maintenance_threshold_override: Option<u32>,
+    /// When the bike was retired (stolen, scrapped); `None` while in the fleet
+    #[serde(default)]
+    pub deleted_at: Option<DateTime<Utc>>,
+    /// When the next maintenance is scheduled; `None` if none is
+    #[serde(default)]
+    pub next_maintenance_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -26,6 +38,8 @@ pub enum BikeStatus {
     Maintenance,
     Charging,
     Offline,
+    /// Pre-booked for a future timeslot, not yet in use
+    Reserved,
 }
 
 impl BikeStatus {
@@ -36,6 +50,7 @@ impl BikeStatus {
             BikeStatus::Maintenance => "maintenance",
             BikeStatus::Charging => "charging",
             BikeStatus::Offline => "offline",
+            BikeStatus::Reserved => "reserved",
         }
     }
 
@@ -46,6 +61,44 @@ impl BikeStatus {
             "maintenance" => Some(BikeStatus::Maintenance),
             "charging" => Some(BikeStatus::Charging),
             "offline" => Some(BikeStatus::Offline),
+            "reserved" => Some(BikeStatus::Reserved),
+            _ => None,
+        }
+    }
+}
+
+/// Bikes with a single status, plus how many there are
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BikesByStatusResult {
+    pub bikes: Vec<Bike>,
+    pub count: u32,
+    pub status: BikeStatus,
+}
+
+/// Preset fleet state for testing and demos
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SimulationScenario {
+    /// Every bike available with a full battery
+    AllIdle,
+    /// Every bike out on a delivery
+    AllDelivering,
+    /// 80% available on 5–15% battery, 20% in maintenance
+    CriticalBattery,
+    /// Every bike freshly serviced: available, full battery, maintained today
+    PostMaintenancePeak,
+    /// Every bike available, spread evenly over the city
+    MaximumCoverage,
+}
+
+impl SimulationScenario {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "all_idle" => Some(SimulationScenario::AllIdle),
+            "all_delivering" => Some(SimulationScenario::AllDelivering),
+            "critical_battery" => Some(SimulationScenario::CriticalBattery),
+            "post_maintenance_peak" => Some(SimulationScenario::PostMaintenancePeak),
+            "maximum_coverage" => Some(SimulationScenario::MaximumCoverage),
             _ => None,
         }
     }
@@ -60,8 +113,83 @@ pub struct FleetStats {
     pub bikes_in_maintenance: u32,
     pub bikes_charging: u32,
     pub bikes_offline: u32,
+    pub bikes_reserved: u32,
     pub average_battery: f64,
     pub total_trips_today: u32,
+    /// Bikes with a `maintenance_threshold_override`
+    pub bikes_with_custom_threshold: u32,
+}
+
+/// Per-bike figures for the bike detail view
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BikeStatistics {
+    pub bike_id: String,
+    pub total_trips: u32,
+    pub total_distance_km: f64,
+    /// `None` if the bike has never been maintained
+    pub days_since_maintenance: Option<u32>,
+    /// Override if set, otherwise the fleet-wide threshold (days)
+    pub effective_maintenance_threshold: u32,
+    pub maintenance_due: bool,
+}
+
+impl BikeStatistics {
+    /// Statistics of `bike`, given the fleet-wide maintenance threshold
+    pub fn for_bike(bike: &Bike, global_threshold_days: u32, now: DateTime<Utc>) -> Self {
+        let effective_maintenance_threshold = bike
+            .maintenance_threshold_override
+            .unwrap_or(global_threshold_days);
+        let days_since_maintenance = bike
+            .last_maintenance
+            .map(|at| (now - at).num_days().max(0) as u32);
+
+        BikeStatistics {
+            bike_id: bike.id.clone(),
+            total_trips: bike.total_trips,
+            total_distance_km: bike.total_distance_km,
+            days_since_maintenance,
+            effective_maintenance_threshold,
+            // Same rule as Database::get_bikes_due_for_maintenance
+            maintenance_due: !matches!(
+                days_since_maintenance,
+                Some(days) if days < effective_maintenance_threshold
+            ),
+        }
+    }
+}
+
+/// `get_low_battery_bikes` threshold used when none is given (percent)
+pub const DEFAULT_LOW_BATTERY_THRESHOLD: u8 = 20;
+
+/// Bikes below this battery level (percent) are critical, the rest warnings
+pub const CRITICAL_BATTERY_LEVEL: u8 = 10;
+
+/// Low-battery bikes with counts for the alert badge
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatteryAlertSummary {
+    /// Bikes below `CRITICAL_BATTERY_LEVEL`
+    pub critical_count: u32,
+    pub warning_count: u32,
+    /// Lowest battery first
+    pub bikes: Vec<Bike>,
+}
+
+impl BatteryAlertSummary {
+    /// Summary of `bikes`, all of which are assumed to be below the threshold
+    pub fn from_bikes(bikes: Vec<Bike>) -> Self {
+        let critical_count = bikes
+            .iter()
+            .filter(|bike| {
+                matches!(bike.battery_level, Some(level) if level < CRITICAL_BATTERY_LEVEL)
+            })
+            .count() as u32;
+
+        BatteryAlertSummary {
+            critical_count,
+            warning_count: bikes.len() as u32 - critical_count,
+            bikes,
+        }
+    }
 }
 
 /// Database statistics
@@ -73,6 +201,157 @@ pub struct DatabaseStats {
     pub last_sync: Option<DateTime<Utc>>,
 }
 
+/// Connection pool state of the PostgreSQL backend
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PoolMetrics {
+    /// Open connections, idle or in use
+    pub pool_size: usize,
+    /// Idle connections ready to be handed out
+    pub available: usize,
+    /// Callers waiting for a connection
+    pub waiting: usize,
+    pub max_size: usize,
+    /// Connections handed out since startup
+    pub total_acquired: u64,
+    /// Of those, idle connections that were reused
+    pub total_recycled: u64,
+    /// Bytes the first replica is behind; None without streaming replicas
+    /// or when the lag couldn't be queried
+    pub replication_lag_bytes: Option<i64>,
+}
+
+/// Scan counts of the PostgreSQL backend's tables and indexes, from
+/// `pg_stat_user_tables` and `pg_stat_user_indexes`
+#[cfg_attr(not(feature = "postgres"), allow(dead_code))]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TableStats {
+    /// Busiest tables first
+    pub tables: Vec<TableScanStats>,
+    /// Most used indexes first; an index with no scans is never chosen
+    pub indexes: Vec<IndexScanStats>,
+}
+
+/// How a table has been read since the statistics were last reset
+#[cfg_attr(not(feature = "postgres"), allow(dead_code))]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TableScanStats {
+    pub table_name: String,
+    pub seq_scan: i64,
+    pub idx_scan: i64,
+    /// Share of scans that used an index (0.0-1.0); None before any scan
+    pub index_hit_rate: Option<f64>,
+    pub live_rows: i64,
+}
+
+/// How often one index has been scanned
+#[cfg_attr(not(feature = "postgres"), allow(dead_code))]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IndexScanStats {
+    pub table_name: String,
+    pub index_name: String,
+    pub idx_scan: i64,
+}
+
+/// Outcome of replaying the offline sync queue
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct FlushResult {
+    /// Writes applied and removed from the queue
+    pub applied: u32,
+    /// Writes that failed again; they stay queued with one more retry
+    pub failed: u32,
+    /// Writes past `MAX_SYNC_RETRIES`, kept in the queue but no longer
+    /// replayed
+    pub stuck: u32,
+}
+
+/// Metadata for a database backup file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupInfo {
+    pub path: String,
+    pub size_bytes: u64,
+    pub created_at: String, // RFC 3339, from file modification time
+}
+
+/// Locale-specific demo data used to seed an empty database
+///
+/// # Why configurable?
+/// Evaluations in other cities (London, Berlin) look more convincing with
+/// local place, street and customer names than with Amsterdam data.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeedConfig {
+    /// (min longitude, max longitude, min latitude, max latitude)
+    pub city_bounds: (f64, f64, f64, f64),
+    /// (name, latitude, longitude); one bike is seeded per location
+    pub locations: Vec<(String, f64, f64)>,
+    pub customer_names: Vec<String>,
+    pub restaurant_names: Vec<String>,
+    pub streets: Vec<String>,
+}
+
+impl SeedConfig {
+    /// The built-in Amsterdam demo data
+    pub fn amsterdam() -> Self {
+        let strings = |values: &[&str]| values.iter().map(|v| v.to_string()).collect();
+
+        Self {
+            city_bounds: (4.7, 5.1, 52.2, 52.5),
+            locations: [
+                ("Amsterdam Central Station", 52.3791, 4.9003),
+                ("Amsterdam Dam Square", 52.3731, 4.8932),
+                ("Amsterdam Vondelpark", 52.3579, 4.8686),
+                ("Amsterdam Rijksmuseum", 52.3600, 4.8852),
+                ("Amsterdam Anne Frank House", 52.3752, 4.8840),
+                ("Amsterdam Jordaan", 52.3747, 4.8797),
+                ("Amsterdam De Pijp", 52.3533, 4.8936),
+                ("Amsterdam Oost", 52.3614, 4.9366),
+                ("Amsterdam Noord", 52.3907, 4.9228),
+                ("Amsterdam Amstel", 52.3632, 4.9039),
+            ]
+            .iter()
+            .map(|(name, lat, lon)| (name.to_string(), *lat, *lon))
+            .collect(),
+            customer_names: strings(&[
+                "P. de Vries", "M. Jansen", "A. Bakker", "J. van Dijk", "S. Visser",
+                "L. Smit", "K. Mulder", "R. de Boer", "T. Bos", "E. van den Berg",
+                "H. Dekker", "F. Vermeer", "B. van Leeuwen", "N. Kok", "D. Peters",
+            ]),
+            restaurant_names: strings(&[
+                "De Pizzabakker", "Wok to Walk", "Febo", "New York Pizza", "Dominos",
+                "Thai Express", "Sushi Time", "Burger King", "McDonalds", "Subway",
+                "La Place", "Vapiano", "Bagels & Beans", "De Italiaan", "Ramen Ya",
+            ]),
+            streets: strings(&[
+                "Damrak", "Rokin", "Kalverstraat", "Leidsestraat", "Utrechtsestraat",
+                "Overtoom", "Kinkerstraat", "Ferdinand Bolstraat", "Javastraat", "Plantage",
+            ]),
+        }
+    }
+
+    /// Check the config can produce a sensible dataset
+    ///
+    /// # Rules
+    /// - At least 2 locations, all inside `city_bounds`
+    /// - Name and street lists must not be empty
+    pub fn validate(&self) -> Result<(), String> {
+        if self.locations.len() < 2 {
+            return Err("Seed config needs at least 2 locations".to_string());
+        }
+
+        let (min_lon, max_lon, min_lat, max_lat) = self.city_bounds;
+        for (name, lat, lon) in &self.locations {
+            if *lon < min_lon || *lon > max_lon || *lat < min_lat || *lat > max_lat {
+                return Err(format!("Location '{}' is outside city_bounds", name));
+            }
+        }
+
+        if self.customer_names.is_empty() || self.restaurant_names.is_empty() || self.streets.is_empty() {
+            return Err("Seed config needs customer names, restaurant names and streets".to_string());
+        }
+
+        Ok(())
+    }
+}
+
 /// Request to add a new bike
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AddBikeRequest {
@@ -82,6 +361,22 @@ pub struct AddBikeRequest {
     pub battery_level: Option<u8>,
 }
 
+/// Outcome of a bulk bike import
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportResult {
+    pub imported: u32,
+    /// Rows rejected by validation, one entry each in `errors`
+    pub skipped: u32,
+    pub errors: Vec<String>,
+}
+
+/// Matches of `search`, each list capped at the requested limit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchResults {
+    pub bikes: Vec<Bike>,
+    pub deliveries: Vec<Delivery>,
+}
+
 /// Request to update bike status
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpdateBikeStatusRequest {
@@ -122,6 +417,166 @@ impl DeliveryStatus {
             _ => None,
         }
     }
+
+    /// Deliveries only move forward, one step at a time:
+    /// `Upcoming → Ongoing → Completed`
+    pub fn can_transition_to(&self, next: &DeliveryStatus) -> bool {
+        matches!(
+            (self, next),
+            (DeliveryStatus::Upcoming, DeliveryStatus::Ongoing)
+                | (DeliveryStatus::Ongoing, DeliveryStatus::Completed)
+        )
+    }
+}
+
+/// How urgently a delivery should be dispatched
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DeliveryPriority {
+    #[default]
+    Normal,
+    High,
+    Urgent,
+}
+
+impl DeliveryPriority {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeliveryPriority::Normal => "normal",
+            DeliveryPriority::High => "high",
+            DeliveryPriority::Urgent => "urgent",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "normal" => Some(DeliveryPriority::Normal),
+            "high" => Some(DeliveryPriority::High),
+            "urgent" => Some(DeliveryPriority::Urgent),
+            _ => None,
+        }
+    }
+}
+
+/// Direction of a sorted listing
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SortDirection {
+    Asc,
+    Desc,
+}
+
+impl SortDirection {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "asc" => Some(SortDirection::Asc),
+            "desc" => Some(SortDirection::Desc),
+            _ => None,
+        }
+    }
+
+    /// SQL keyword for ORDER BY
+    pub fn as_sql(self) -> &'static str {
+        match self {
+            SortDirection::Asc => "ASC",
+            SortDirection::Desc => "DESC",
+        }
+    }
+}
+
+/// Sort field for bike listings
+///
+/// # Why enums instead of a raw ORDER BY string?
+/// - The value comes from the frontend; only known columns reach SQL
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum BikeSort {
+    /// Alphabetical (default)
+    #[default]
+    Name,
+    Battery,
+    TotalTrips,
+    UpdatedAt,
+}
+
+impl BikeSort {
+    /// Direction used when none is given
+    pub fn default_direction(self) -> SortDirection {
+        match self {
+            BikeSort::Name => SortDirection::Asc,
+            BikeSort::Battery | BikeSort::TotalTrips | BikeSort::UpdatedAt => SortDirection::Desc,
+        }
+    }
+}
+
+/// Sort field for delivery listings
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliverySort {
+    /// Newest first by default
+    #[default]
+    CreatedAt,
+    /// Not yet completed deliveries come last in either direction
+    CompletedAt,
+    Status,
+    /// Unrated deliveries come last in either direction
+    Rating,
+    /// Urgent, then high, then normal when ascending
+    Priority,
+}
+
+impl DeliverySort {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "created_at" => Some(DeliverySort::CreatedAt),
+            "completed_at" => Some(DeliverySort::CompletedAt),
+            "status" => Some(DeliverySort::Status),
+            "rating" => Some(DeliverySort::Rating),
+            "priority" => Some(DeliverySort::Priority),
+            _ => None,
+        }
+    }
+
+    /// Direction used when none is given
+    pub fn default_direction(self) -> SortDirection {
+        match self {
+            DeliverySort::CreatedAt | DeliverySort::Rating => SortDirection::Desc,
+            DeliverySort::CompletedAt | DeliverySort::Status | DeliverySort::Priority => {
+                SortDirection::Asc
+            }
+        }
+    }
+}
+
+/// Sort field for issue listings
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueSort {
+    /// Newest first by default
+    #[default]
+    CreatedAt,
+    Category,
+    /// Open issues first when ascending
+    Resolved,
+}
+
+impl IssueSort {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "created_at" => Some(IssueSort::CreatedAt),
+            "category" => Some(IssueSort::Category),
+            "resolved" => Some(IssueSort::Resolved),
+            _ => None,
+        }
+    }
+
+    /// Direction used when none is given
+    pub fn default_direction(self) -> SortDirection {
+        match self {
+            IssueSort::CreatedAt => SortDirection::Desc,
+            IssueSort::Category | IssueSort::Resolved => SortDirection::Asc,
+        }
+    }
 }
 
 /// Represents a delivery in the fleet system
@@ -136,6 +591,8 @@ pub struct Delivery {
     pub id: String,
     pub bike_id: String,
     pub status: DeliveryStatus,
+    #[serde(default)]
+    pub priority: DeliveryPriority,
     pub customer_name: String,
     pub customer_address: String,
     pub restaurant_name: String,
@@ -146,6 +603,48 @@ pub struct Delivery {
     pub completed_at: Option<DateTime<Utc>>,
 }
 
+/// Request to create a new delivery
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateDeliveryRequest {
+    pub bike_id: String,
+    pub customer_name: String,
+    pub customer_address: String,
+    pub restaurant_name: String,
+    pub restaurant_address: String,
+}
+
+impl CreateDeliveryRequest {
+    /// Check that no name or address is blank
+    pub fn validate(&self) -> Result<(), String> {
+        let fields = [
+            ("customer_name", &self.customer_name),
+            ("customer_address", &self.customer_address),
+            ("restaurant_name", &self.restaurant_name),
+            ("restaurant_address", &self.restaurant_address),
+        ];
+        match fields.iter().find(|(_, value)| value.trim().is_empty()) {
+            Some((field, _)) => Err(format!("{} must not be empty", field)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// A GPS point on a delivery's route
+///
+/// `sequence` starts at 1 and counts up in the order points were recorded,
+/// so a route is the waypoints sorted by `sequence`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Waypoint {
+    pub id: String,
+    pub delivery_id: String,
+    pub sequence: u32,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub recorded_at: DateTime<Utc>,
+}
+
 // ============================================================================
 // Issue Models
 // ============================================================================
@@ -179,7 +678,7 @@ impl IssueReporterType {
 }
 
 /// Issue category for classification
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 #[serde(rename_all = "snake_case")]
 pub enum IssueCategory {
     Late,
@@ -233,7 +732,97 @@ pub struct Issue {
     pub category: IssueCategory,
     pub description: String,
     pub resolved: bool,
+    pub resolved_at: Option<DateTime<Utc>>, // null while unresolved
+    pub resolution_note: Option<String>,
     pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>, // Stamped when `resolved` changes
+}
+
+/// Request to report a new issue
+///
+/// Without a `category`, one is suggested from the description
+/// (see `categorization::auto_categorize_issue`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateIssueRequest {
+    pub bike_id: String,
+    pub delivery_id: Option<String>, // None = standalone issue
+    pub reporter_type: IssueReporterType,
+    #[serde(default)]
+    pub category: Option<IssueCategory>,
+    pub description: String,
+}
+
+// ============================================================================
+// Pagination
+// ============================================================================
+
+/// Page size used when a list command is called without `limit`
+pub const DEFAULT_PAGE_SIZE: u32 = 50;
+
+/// Largest accepted `limit`
+pub const MAX_PAGE_SIZE: u32 = 500;
+
+/// One page of a keyset-paginated list
+///
+/// # Why keyset instead of OFFSET?
+/// - Pages stay stable when rows are inserted while the user is paging
+/// - The database seeks to the cursor instead of counting off skipped rows
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<String>, // Pass back as `cursor`; None on the last page
+    pub total_count: u32,            // Rows matching the filters, across all pages
+}
+
+pub type DeliveryPage = Page<Delivery>;
+pub type IssuePage = Page<Issue>;
+
+impl<T> Page<T> {
+    /// Build a page from up to `limit + 1` rows; the extra row only tells
+    /// that another page follows and is dropped
+    pub fn from_rows(
+        mut rows: Vec<T>,
+        limit: u32,
+        total_count: u32,
+        cursor_of: impl Fn(&T) -> PageCursor,
+    ) -> Self {
+        let next_cursor = if rows.len() > limit as usize {
+            rows.truncate(limit as usize);
+            rows.last().map(|last| cursor_of(last).encode())
+        } else {
+            None
+        };
+
+        Page {
+            items: rows,
+            next_cursor,
+            total_count,
+        }
+    }
+}
+
+/// `(created_at, id)` of the last row of a page
+///
+/// Handed to clients as opaque URL-safe base64, so its shape can change.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PageCursor {
+    pub created_at: DateTime<Utc>,
+    pub id: String,
+}
+
+impl PageCursor {
+    pub fn encode(&self) -> String {
+        let json = serde_json::to_vec(&(self.created_at, &self.id)).unwrap();
+        URL_SAFE_NO_PAD.encode(json)
+    }
+
+    pub fn decode(cursor: &str) -> Option<Self> {
+        let json = URL_SAFE_NO_PAD.decode(cursor).ok()?;
+        let (created_at, id) = serde_json::from_slice(&json).ok()?;
+        Some(PageCursor { created_at, id })
+    }
 }
 
 // ============================================================================
@@ -242,16 +831,21 @@ pub struct Issue {
 
 /// Node types in the force-directed graph
 ///
-/// # Why three types?
+/// # Why these types?
 /// - Deliverer: Center of the graph (the courier/bike)
 /// - Delivery: Primary connections to deliverer
 /// - Issue: Secondary connections (to delivery or directly to deliverer)
+/// - Restaurant: Only in the fleet-wide graph, shared by deliverers
+/// - IssueCluster: Groups issues of one category (`ForceGraphClusterMode::ByIssueCategory`)
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum ForceNodeType {
     Deliverer,
     Delivery,
     Issue,
+    Restaurant,
+    #[serde(rename = "issueCluster")]
+    IssueCluster,
 }
 
 /// Type-specific data payload for force graph nodes
@@ -260,7 +854,7 @@ pub enum ForceNodeType {
 /// - Each node type carries different data
 /// - Rust enum with variants provides type safety
 /// - Serializes to discriminated union in TypeScript
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "type", rename_all = "camelCase")]
 pub enum ForceNodeData {
     Deliverer {
@@ -269,14 +863,24 @@ pub enum ForceNodeData {
     },
     Delivery {
         status: DeliveryStatus,
+        priority: DeliveryPriority,
         customer: String,
         rating: Option<u8>,
     },
     Issue {
         category: IssueCategory,
         resolved: bool,
+        resolved_at: Option<DateTime<Utc>>,
         reporter: IssueReporterType,
     },
+    Restaurant {
+        name: String,
+        delivery_count: u32,
+    },
+    IssueCluster {
+        category: IssueCategory,
+        count: u32,
+    },
 }
 
 /// A node in the force-directed graph
@@ -297,6 +901,27 @@ pub struct ForceNode {
     pub data: ForceNodeData, // Type-specific payload
 }
 
+impl ForceNode {
+    /// 32-bit FNV-1a hash of the node's ID and position
+    ///
+    /// Used to identify layouts cheaply (see `ForceGraphData::position_hash`).
+    pub fn hash(&self) -> u32 {
+        let mut hash = FNV_OFFSET_BASIS;
+        for byte in self
+            .id
+            .bytes()
+            .chain(self.x.to_bits().to_le_bytes())
+            .chain(self.y.to_bits().to_le_bytes())
+        {
+            hash = (hash ^ byte as u32).wrapping_mul(FNV_PRIME);
+        }
+        hash
+    }
+}
+
+const FNV_OFFSET_BASIS: u32 = 0x811c_9dc5;
+const FNV_PRIME: u32 = 0x0100_0193;
+
 /// A link/edge in the force-directed graph
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -320,3 +945,473 @@ pub struct ForceGraphData {
     pub center_y: f64,
     pub bounds: (f64, f64, f64, f64), // (min_x, max_x, min_y, max_y)
 }
+
+impl ForceGraphData {
+    /// Combined `ForceNode::hash` of all nodes, in order
+    pub fn position_hash(&self) -> u32 {
+        self.nodes.iter().fold(FNV_OFFSET_BASIS, |hash, node| {
+            (hash ^ node.hash()).wrapping_mul(FNV_PRIME)
+        })
+    }
+}
+
+/// Changes between two layouts of the same force graph
+///
+/// # Why?
+/// Dragging a node usually moves only a few neighbours noticeably; the
+/// client re-renders just `changed_nodes` and `added_nodes`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForceGraphDiff {
+    pub changed_nodes: Vec<ForceNode>,
+    pub unchanged_node_ids: Vec<String>,
+    pub added_nodes: Vec<ForceNode>,
+    pub removed_node_ids: Vec<String>,
+    pub bounds_changed: bool,
+    pub new_bounds: Option<(f64, f64, f64, f64)>,
+    /// `ForceGraphData::position_hash` of the new layout, for the next diff
+    pub position_hash: u32,
+}
+
+/// Physics and geometry of the force graph layout
+///
+/// Omitted fields take their default, so callers can tweak a single
+/// parameter. Distances and radii are in layout pixels.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ForceGraphConfig {
+    /// Many-body strength; negative values push nodes apart
+    pub repulsion_strength: f64,
+    /// Pull toward the center, 0.0-1.0
+    pub center_strength: f64,
+    /// Reported on `ForceLink`s (the spring force uses Fjädra's defaults)
+    pub link_strength: f64,
+    /// Extra space kept between colliding nodes
+    pub collision_padding: f64,
+    pub deliverer_radius: f64,
+    pub delivery_radius: f64,
+    pub issue_radius: f64,
+    /// Initial distance of deliveries from the deliverer
+    pub delivery_distance: f64,
+    /// Initial distance of issues from their delivery
+    pub issue_distance: f64,
+}
+
+impl Default for ForceGraphConfig {
+    /// # Why these values?
+    /// - center_strength 0.05: Gentle pull to prevent drift without overwhelming other forces
+    /// - repulsion_strength -300: Strong enough to separate overlapping nodes
+    fn default() -> Self {
+        Self {
+            repulsion_strength: -300.0,
+            center_strength: 0.05,
+            link_strength: 0.7,
+            collision_padding: 5.0,
+            deliverer_radius: 40.0,
+            delivery_radius: 25.0,
+            issue_radius: 18.0,
+            delivery_distance: 120.0,
+            issue_distance: 60.0,
+        }
+    }
+}
+
+/// Size of an urgent delivery node relative to a regular one
+///
+/// With the default radii this is 32px: clearly larger than a delivery
+/// (25px), still smaller than the deliverer (40px) it surrounds.
+const URGENT_DELIVERY_RADIUS_SCALE: f64 = 1.28;
+
+impl ForceGraphConfig {
+    /// Urgent deliveries are drawn larger so they stand out around the deliverer
+    pub fn urgent_delivery_radius(&self) -> f64 {
+        self.delivery_radius * URGENT_DELIVERY_RADIUS_SCALE
+    }
+
+    /// Check the config can produce a layout
+    ///
+    /// # Rules
+    /// - All values finite
+    /// - Radii and distances positive, padding and link strength not negative
+    /// - center_strength within 0.0-1.0
+    pub fn validate(&self) -> Result<(), String> {
+        let values = [
+            self.repulsion_strength,
+            self.center_strength,
+            self.link_strength,
+            self.collision_padding,
+            self.deliverer_radius,
+            self.delivery_radius,
+            self.issue_radius,
+            self.delivery_distance,
+            self.issue_distance,
+        ];
+        if values.iter().any(|v| !v.is_finite()) {
+            return Err("Force graph config values must be finite".to_string());
+        }
+
+        let sizes = [
+            self.deliverer_radius,
+            self.delivery_radius,
+            self.issue_radius,
+            self.delivery_distance,
+            self.issue_distance,
+        ];
+        if sizes.iter().any(|v| *v <= 0.0) {
+            return Err("Force graph radii and distances must be positive".to_string());
+        }
+        if self.collision_padding < 0.0 || self.link_strength < 0.0 {
+            return Err("Collision padding and link strength must not be negative".to_string());
+        }
+        if !(0.0..=1.0).contains(&self.center_strength) {
+            return Err("Center strength must be between 0.0 and 1.0".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+/// How issues are arranged in a deliverer's force graph
+///
+/// # Why cluster?
+/// - Deliverers with many issues get a cluttered outer ring
+/// - One node per category keeps the graph readable while the
+///   individual issues stay reachable as its children
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ForceGraphClusterMode {
+    /// Every issue hangs off its delivery or the deliverer (default)
+    #[default]
+    None,
+    /// Issues hang off one `IssueCluster` node per category
+    ByIssueCategory,
+}
+
+// ============================================================================
+// Analytics Models
+// ============================================================================
+
+/// Per-bike data point used for issue/rating correlation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BikeCorrelationPoint {
+    pub bike_id: String,
+    pub issue_count: u32,
+    pub average_rating: Option<f64>, // None = no rated deliveries in window
+}
+
+/// Correlation between issue frequency and delivery ratings
+///
+/// # Why NaN for small samples?
+/// - Pearson is meaningless with fewer than 3 points
+/// - serde_json serializes NaN as `null`, so the UI can show "not enough data"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IssueRatingCorrelation {
+    pub pearson_coefficient: f64,
+    pub sample_size: u32, // Bikes with both an issue count and an average rating
+    pub by_bike: Vec<BikeCorrelationPoint>,
+}
+
+/// One cell of the fleet position heatmap
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeatmapCell {
+    pub center_lat: f64,
+    pub center_lon: f64,
+    pub weight: f64, // Number of positions that fell into this cell
+}
+
+/// Frequency and resolution figures for one issue category
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IssueCategoryStats {
+    pub category: IssueCategory,
+    pub count: u32,
+    pub resolved_count: u32,
+    pub resolution_rate: f64, // resolved_count / count (0.0-1.0)
+    pub avg_resolution_time_hours: Option<f64>, // None = nothing resolved yet
+}
+
+/// Issue health of the fleet or of one bike, for the dashboard
+///
+/// Resolution time is `resolved_at - created_at`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IssueStatistics {
+    pub total_issues: u32,
+    pub resolved_count: u32,
+    pub unresolved_count: u32,
+    pub resolution_rate_pct: f64, // 0.0 without issues
+    pub by_category: HashMap<String, u32>, // Categories without issues are omitted
+    pub by_reporter: HashMap<String, u32>,
+    pub avg_resolution_days: f64, // 0.0 if nothing is resolved
+    pub oldest_unresolved_days: Option<f64>, // None if everything is resolved
+}
+
+/// Delivery volume and customer satisfaction of one restaurant
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestaurantStats {
+    pub id: String,
+    pub name: String,
+    pub address: Option<String>,
+    pub total_deliveries: u32,
+    pub completed_deliveries: u32,
+    pub complaint_count: u32,
+    pub avg_rating: Option<f64>, // None = no rated deliveries yet
+}
+
+/// Bucket width of a fleet time series
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeBucket {
+    Hour,
+    Day,
+    /// ISO weeks, starting Monday 00:00 UTC
+    Week,
+}
+
+impl TimeBucket {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TimeBucket::Hour => "hour",
+            TimeBucket::Day => "day",
+            TimeBucket::Week => "week",
+        }
+    }
+
+    pub fn duration(&self) -> chrono::Duration {
+        match self {
+            TimeBucket::Hour => chrono::Duration::hours(1),
+            TimeBucket::Day => chrono::Duration::days(1),
+            TimeBucket::Week => chrono::Duration::weeks(1),
+        }
+    }
+
+    /// Start of the bucket containing `at`
+    pub fn start_of(&self, at: DateTime<Utc>) -> DateTime<Utc> {
+        use chrono::{Datelike, Timelike};
+
+        let midnight = at.date_naive().and_hms_opt(0, 0, 0).unwrap().and_utc();
+        match self {
+            TimeBucket::Hour => midnight + chrono::Duration::hours(at.hour() as i64),
+            TimeBucket::Day => midnight,
+            TimeBucket::Week => {
+                midnight - chrono::Duration::days(at.weekday().num_days_from_monday() as i64)
+            }
+        }
+    }
+}
+
+/// Fleet activity within one time bucket
+///
+/// Each event is counted in the bucket it happened in: deliveries by
+/// creation and completion time, issues by creation and resolution time,
+/// distance by trip end time.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FleetTimeSeries {
+    pub period_start: DateTime<Utc>,
+    pub period_end: DateTime<Utc>, // Exclusive
+    pub total_deliveries: u32,
+    pub completed_deliveries: u32,
+    pub average_rating: f64, // Of deliveries completed in the bucket; 0.0 if none rated
+    pub issues_opened: u32,
+    pub issues_resolved: u32,
+    pub distance_km: f64,
+}
+
+/// Delivery load in one hour of the day (UTC), summed over all days of a range
+///
+/// Deliveries count towards the hour they were created in.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct HourlyUtilization {
+    pub hour: u8, // 0-23
+    pub active_deliveries: u32,
+    pub completed_deliveries: u32,
+    /// Share of the fleet (0.0-1.0) with an active or completed delivery in this hour
+    pub utilization_ratio: f64,
+}
+
+/// Kind of change recorded in the audit log
+///
+/// Retiring a bike is recorded as a `Delete`, even though the row is only
+/// soft-deleted.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditOp {
+    Insert,
+    Update,
+    Delete,
+}
+
+impl AuditOp {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditOp::Insert => "insert",
+            AuditOp::Update => "update",
+            AuditOp::Delete => "delete",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "insert" => Some(AuditOp::Insert),
+            "update" => Some(AuditOp::Update),
+            "delete" => Some(AuditOp::Delete),
+            _ => None,
+        }
+    }
+}
+
+/// One row of the `audit_log` table
+///
+/// Not to be confused with `audit::AuditEntry`, the signed trail of secure
+/// commands; this records data changes, with the row before and after as JSON.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditLogEntry {
+    pub id: String,
+    pub table_name: String,
+    pub row_id: String,
+    pub operation: AuditOp,
+    pub old_value_json: Option<String>, // None for inserts
+    pub new_value_json: Option<String>, // None for deletes
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// Kind of change in the append-only `fleet_events` log
+///
+/// Logged by `add_bike` (and so `import_bikes`), `update_bike_status`,
+/// `reserve_bike`, `cancel_reservation`, `retire_bike`, `create_delivery`,
+/// `update_delivery_status` (on completion), `create_issue` and
+/// `resolve_issue`. Bulk changes (scenarios, expired reservations, batch
+/// issue updates) are not logged.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FleetEventType {
+    BikeAdded,
+    BikeStatusChanged,
+    BikeRetired,
+    DeliveryCreated,
+    DeliveryCompleted,
+    IssueFiled,
+    IssueResolved,
+}
+
+impl FleetEventType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FleetEventType::BikeAdded => "bike_added",
+            FleetEventType::BikeStatusChanged => "bike_status_changed",
+            FleetEventType::BikeRetired => "bike_retired",
+            FleetEventType::DeliveryCreated => "delivery_created",
+            FleetEventType::DeliveryCompleted => "delivery_completed",
+            FleetEventType::IssueFiled => "issue_filed",
+            FleetEventType::IssueResolved => "issue_resolved",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "bike_added" => Some(FleetEventType::BikeAdded),
+            "bike_status_changed" => Some(FleetEventType::BikeStatusChanged),
+            "bike_retired" => Some(FleetEventType::BikeRetired),
+            "delivery_created" => Some(FleetEventType::DeliveryCreated),
+            "delivery_completed" => Some(FleetEventType::DeliveryCompleted),
+            "issue_filed" => Some(FleetEventType::IssueFiled),
+            "issue_resolved" => Some(FleetEventType::IssueResolved),
+            _ => None,
+        }
+    }
+}
+
+/// One row of the `fleet_events` table
+///
+/// IDs only grow, so a client polling with the last ID it has seen gets
+/// each event exactly once.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FleetEvent {
+    pub id: u64,
+    pub event_type: FleetEventType,
+    /// The bike, delivery or issue after the change
+    pub payload_json: String,
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// A bike's status changed, as announced on the PostgreSQL
+/// `bike_status_changes` channel
+///
+/// Only the PostgreSQL backend emits these; SQLite has no second writer.
+#[cfg_attr(not(feature = "postgres"), allow(dead_code))]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BikeStatusEvent {
+    pub bike_id: String,
+    pub status: BikeStatus,
+}
+
+#[cfg_attr(not(feature = "postgres"), allow(dead_code))]
+impl BikeStatusEvent {
+    /// Parse a notification payload, `{"bike_id": "BIKE-0001", "status": "in_use"}`
+    ///
+    /// `None` if the payload is malformed or names an unknown status.
+    pub fn from_notification(payload: &str) -> Option<Self> {
+        // Statuses arrive as stored in the database, not in serde's spelling
+        #[derive(Deserialize)]
+        struct Payload {
+            bike_id: String,
+            status: String,
+        }
+
+        let payload: Payload = serde_json::from_str(payload).ok()?;
+        Some(Self {
+            status: BikeStatus::from_str(&payload.status)?,
+            bike_id: payload.bike_id,
+        })
+    }
+}
+
+/// Payload of the `bike-status-changed` event the SQLite backend emits
+/// after `update_bike_status` succeeds
+#[cfg_attr(not(feature = "sqlite"), allow(dead_code))]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UpdateBikeStatusEvent {
+    pub bike_id: String,
+    pub new_status: BikeStatus,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Payload of the `bike-retired` event the SQLite backend emits after
+/// `retire_bike` succeeds
+#[cfg_attr(not(feature = "sqlite"), allow(dead_code))]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BikeRetiredEvent {
+    pub bike_id: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bike_status_event_from_notification() {
+        let event =
+            BikeStatusEvent::from_notification(r#"{"bike_id" : "BIKE-0007", "status" : "in_use"}"#);
+        assert_eq!(
+            event,
+            Some(BikeStatusEvent {
+                bike_id: "BIKE-0007".to_string(),
+                status: BikeStatus::InUse,
+            })
+        );
+
+        assert_eq!(BikeStatusEvent::from_notification(r#"{"bike_id": "BIKE-0007"}"#), None);
+        assert_eq!(
+            BikeStatusEvent::from_notification(r#"{"bike_id": "BIKE-0007", "status": "flying"}"#),
+            None
+        );
+        assert_eq!(BikeStatusEvent::from_notification("not json"), None);
+    }
+}
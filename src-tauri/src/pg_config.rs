@@ -0,0 +1,90 @@
+//! PostgreSQL Connection Settings
+//!
+//! # Why a separate module?
+//! The settings are needed without the PostgreSQL backend itself:
+//! `migration_tool` connects to a server from a SQLite build. Loading
+//! them from the environment stays with the backend, in `database_pg`.
+
+use crate::models::SeedConfig;
+use std::path::PathBuf;
+
+/// Database configuration for PostgreSQL
+///
+/// The migration tool only uses the connection fields.
+#[derive(Debug, Clone)]
+#[cfg_attr(not(feature = "postgres"), allow(dead_code))]
+pub struct DatabaseConfig {
+    pub host: String,
+    pub port: u16,
+    pub user: String,
+    pub password: String,
+    pub dbname: String,
+    pub pool_size: usize,
+    /// Upper bound for the startup pool warmup; slower warmups are abandoned
+    pub warmup_timeout_seconds: u64,
+    /// Require TLS: upgrades `SslMode::Disable` to `Require`, never
+    /// downgrades a stricter mode (see `effective_ssl_mode`)
+    pub tls: bool,
+    /// Transport security for every pool connection
+    pub ssl_mode: SslMode,
+    /// PEM file with the CA certificate(s) the server chain must lead to
+    pub ssl_ca_cert_path: Option<PathBuf>,
+    /// Demo data for an empty database (defaults to Amsterdam)
+    pub seed_config: SeedConfig,
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        Self {
+            host: "localhost".to_string(),
+            port: 5432,
+            user: "fleet_app".to_string(),
+            password: String::new(),
+            dbname: "bike_fleet".to_string(),
+            pool_size: 16,
+            warmup_timeout_seconds: 30,
+            tls: false,
+            ssl_mode: SslMode::Disable,
+            ssl_ca_cert_path: None,
+            seed_config: SeedConfig::amsterdam(),
+        }
+    }
+}
+
+impl DatabaseConfig {
+    /// `ssl_mode` with `tls` applied; what connections actually use
+    pub fn effective_ssl_mode(&self) -> SslMode {
+        match self.ssl_mode {
+            SslMode::Disable if self.tls => SslMode::Require,
+            mode => mode,
+        }
+    }
+}
+
+/// Transport security for PostgreSQL connections (mirrors libpq's `sslmode`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(not(feature = "postgres"), allow(dead_code))]
+pub enum SslMode {
+    /// Plain TCP; only for local development
+    #[default]
+    Disable,
+    /// TLS with a trusted chain; falls back to the webpki roots without a CA file
+    Require,
+    /// TLS with a chain leading to the configured CA; the host name is not checked
+    VerifyCa,
+    /// Like `VerifyCa`, and the certificate must match the server host name
+    VerifyFull,
+}
+
+impl SslMode {
+    #[cfg_attr(not(feature = "postgres"), allow(dead_code))]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "disable" => Some(SslMode::Disable),
+            "require" => Some(SslMode::Require),
+            "verify-ca" => Some(SslMode::VerifyCa),
+            "verify-full" => Some(SslMode::VerifyFull),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,198 @@
+//! Fleet Simulation Scenarios
+//!
+//! # Purpose
+//! Computes the per-bike target state of a `SimulationScenario`, shared by
+//! the SQLite and PostgreSQL backends. The database layer only applies it.
+//!
+//! # Idempotency
+//! Targets depend only on the bike's position in ID order and on the
+//! fleet's bounding box (which `MaximumCoverage` preserves), so applying a
+//! scenario twice yields the same fleet.
+
+use crate::models::{Bike, BikeStatus, SimulationScenario};
+use chrono::{DateTime, Utc};
+
+/// `CriticalBattery` sends one bike in this many to maintenance: roughly
+/// the share a fleet this drained would have pulled from service
+const CRITICAL_BATTERY_MAINTENANCE_EVERY: usize = 5;
+
+/// Target state of one bike; `None` fields are left unchanged
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScenarioBikeState {
+    pub status: BikeStatus,
+    pub battery_level: Option<u8>,
+    pub position: Option<(f64, f64)>,
+    pub last_maintenance: Option<DateTime<Utc>>,
+}
+
+/// Compute the target state for each bike (same order as `bikes`)
+///
+/// `bikes` must be sorted by ID; `today` is the maintenance date set by
+/// `PostMaintenancePeak`.
+pub fn plan_scenario(
+    scenario: SimulationScenario,
+    bikes: &[Bike],
+    today: DateTime<Utc>,
+) -> Vec<ScenarioBikeState> {
+    let grid = coverage_grid(bikes);
+
+    (0..bikes.len())
+        .map(|i| match scenario {
+            SimulationScenario::AllIdle => ScenarioBikeState {
+                status: BikeStatus::Available,
+                battery_level: Some(100),
+                position: None,
+                last_maintenance: None,
+            },
+            SimulationScenario::AllDelivering => ScenarioBikeState {
+                status: BikeStatus::InUse,
+                battery_level: None,
+                position: None,
+                last_maintenance: None,
+            },
+            // Every 5th bike (the last of each group of 5) is in
+            // maintenance; batteries cycle through 5..=15
+            SimulationScenario::CriticalBattery => ScenarioBikeState {
+                status: if i % CRITICAL_BATTERY_MAINTENANCE_EVERY
+                    == CRITICAL_BATTERY_MAINTENANCE_EVERY - 1
+                {
+                    BikeStatus::Maintenance
+                } else {
+                    BikeStatus::Available
+                },
+                battery_level: Some(5 + (i * 7 % 11) as u8),
+                position: None,
+                last_maintenance: None,
+            },
+            SimulationScenario::PostMaintenancePeak => ScenarioBikeState {
+                status: BikeStatus::Available,
+                battery_level: Some(100),
+                position: None,
+                last_maintenance: Some(today),
+            },
+            SimulationScenario::MaximumCoverage => ScenarioBikeState {
+                status: BikeStatus::Available,
+                battery_level: None,
+                position: grid.as_ref().map(|grid| grid.point(i)),
+                last_maintenance: None,
+            },
+        })
+        .collect()
+}
+
+/// Evenly spaced grid spanning the fleet's bounding box
+struct CoverageGrid {
+    min_lat: f64,
+    max_lat: f64,
+    min_lon: f64,
+    max_lon: f64,
+    rows: usize,
+    columns: usize,
+}
+
+impl CoverageGrid {
+    fn point(&self, index: usize) -> (f64, f64) {
+        let row = index / self.columns;
+        let column = index % self.columns;
+        (
+            lerp(self.min_lat, self.max_lat, row, self.rows),
+            lerp(self.min_lon, self.max_lon, column, self.columns),
+        )
+    }
+}
+
+/// `step`-th of `count` evenly spaced points from `min` to `max`
+///
+/// Written so the end points are exactly `min` and `max`.
+fn lerp(min: f64, max: f64, step: usize, count: usize) -> f64 {
+    if count < 2 {
+        return min;
+    }
+    let t = step as f64 / (count - 1) as f64;
+    min * (1.0 - t) + max * t
+}
+
+/// Grid whose outermost points sit on the bounding box edges
+///
+/// # Why edges, not cell centers?
+/// The bounding box of the result equals the input's, which keeps
+/// `MaximumCoverage` idempotent.
+fn coverage_grid(bikes: &[Bike]) -> Option<CoverageGrid> {
+    if bikes.is_empty() {
+        return None;
+    }
+
+    let (mut min_lat, mut max_lat) = (f64::MAX, f64::MIN);
+    let (mut min_lon, mut max_lon) = (f64::MAX, f64::MIN);
+    for bike in bikes {
+        min_lat = min_lat.min(bike.latitude);
+        max_lat = max_lat.max(bike.latitude);
+        min_lon = min_lon.min(bike.longitude);
+        max_lon = max_lon.max(bike.longitude);
+    }
+
+    let columns = (bikes.len() as f64).sqrt().ceil() as usize;
+    let rows = bikes.len().div_ceil(columns);
+
+    Some(CoverageGrid {
+        min_lat,
+        max_lat,
+        min_lon,
+        max_lon,
+        rows,
+        columns,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bike(id: usize, lat: f64, lon: f64) -> Bike {
+        Bike {
+            id: format!("BIKE-{:04}", id),
+            name: format!("Bike {}", id),
+            status: BikeStatus::Offline,
+            latitude: lat,
+            longitude: lon,
+            battery_level: Some(50),
+            last_maintenance: None,
+            total_trips: 0,
+            total_distance_km: 0.0,
+            reservation_expires_at: None,
+            maintenance_threshold_override: None,
+            deleted_at: None,
+            next_maintenance_at: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_critical_battery_split() {
+        let bikes: Vec<Bike> = (0..10).map(|i| bike(i, 52.37, 4.9)).collect();
+        let plan = plan_scenario(SimulationScenario::CriticalBattery, &bikes, Utc::now());
+
+        let maintenance = plan.iter().filter(|s| s.status == BikeStatus::Maintenance).count();
+        assert_eq!(maintenance, 2);
+        assert!(plan
+            .iter()
+            .all(|s| matches!(s.battery_level, Some(5..=15))));
+    }
+
+    #[test]
+    fn test_maximum_coverage_is_idempotent() {
+        let mut bikes: Vec<Bike> = (0..7)
+            .map(|i| bike(i, 52.35 + i as f64 * 0.001, 4.85 + (i * i) as f64 * 0.002))
+            .collect();
+        let now = Utc::now();
+
+        let first = plan_scenario(SimulationScenario::MaximumCoverage, &bikes, now);
+        for (bike, state) in bikes.iter_mut().zip(&first) {
+            (bike.latitude, bike.longitude) = state.position.unwrap();
+        }
+        let second = plan_scenario(SimulationScenario::MaximumCoverage, &bikes, now);
+
+        assert_eq!(first, second);
+    }
+}
@@ -0,0 +1,278 @@
+//! Slow Query Log (SQLite backend)
+//!
+//! # Purpose
+//! Catches missing indexes during development without external tooling.
+//! `SlowQueryLogger` wraps the database connection; while a threshold is
+//! set (`SqliteConfig::log_threshold_ms`), it checks the plan of every
+//! parameterized statement and times the statements it runs itself.
+//!
+//! # What gets logged
+//! - A plan with a full table scan (`SCAN bikes`, not `SEARCH bikes USING
+//!   INDEX ...`), once per distinct SQL text
+//! - An `execute` or `query_row` that takes longer than the threshold
+//!
+//! Each incident goes to `tracing::warn!`, and the latest
+//! `MAX_SLOW_QUERY_RECORDS` are kept for `get_slow_query_report`.
+//!
+//! # Limits
+//! Statements from `prepare` are checked for scans but not timed, since
+//! they run after the logger hands them out. Statements run through a
+//! transaction go to the connection directly and are not checked at all.
+
+use chrono::{DateTime, Utc};
+use rusqlite::{Connection, Params, Row, Statement};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::ops::Deref;
+use std::time::{Duration, Instant};
+
+/// Incidents kept for `get_slow_query_report`; older ones are dropped
+pub const MAX_SLOW_QUERY_RECORDS: usize = 50;
+
+/// A statement that scanned a whole table or ran too long
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SlowQueryRecord {
+    pub sql: String,
+    /// `detail` of each `EXPLAIN QUERY PLAN` row; empty for statements
+    /// without parameters, which are not explained
+    pub plan: Vec<String>,
+    pub full_scan: bool,
+    /// None when the plan alone was flagged, before the statement ran
+    pub duration_ms: Option<f64>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// Connection wrapper that reports full scans and slow statements
+///
+/// Derefs to the `Connection`, so anything not instrumented here works
+/// as before.
+pub struct SlowQueryLogger {
+    conn: Connection,
+    threshold: Option<Duration>,
+    /// Plans of the SQL seen so far, so each statement is explained once
+    plans: RefCell<HashMap<String, Vec<String>>>,
+    /// Newest first
+    records: RefCell<VecDeque<SlowQueryRecord>>,
+}
+
+impl SlowQueryLogger {
+    /// Wrap `conn`; `threshold: None` disables all checks
+    pub fn new(conn: Connection, threshold: Option<Duration>) -> Self {
+        Self {
+            conn,
+            threshold,
+            plans: RefCell::new(HashMap::new()),
+            records: RefCell::new(VecDeque::new()),
+        }
+    }
+
+    /// Change the threshold; `None` disables all checks
+    pub fn set_threshold(&mut self, threshold: Option<Duration>) {
+        self.threshold = threshold;
+    }
+
+    /// Logged incidents, newest first
+    pub fn records(&self) -> Vec<SlowQueryRecord> {
+        self.records.borrow().iter().cloned().collect()
+    }
+
+    /// `Connection::prepare`, after checking the statement's plan
+    pub fn prepare(&self, sql: &str) -> rusqlite::Result<Statement<'_>> {
+        if self.threshold.is_some() {
+            self.check_plan(sql);
+        }
+        self.conn.prepare(sql)
+    }
+
+    /// `Connection::execute`, with plan check and timing
+    pub fn execute<P: Params>(&self, sql: &str, params: P) -> rusqlite::Result<usize> {
+        self.timed(sql, || self.conn.execute(sql, params))
+    }
+
+    /// `Connection::query_row`, with plan check and timing
+    pub fn query_row<T, P, F>(&self, sql: &str, params: P, f: F) -> rusqlite::Result<T>
+    where
+        P: Params,
+        F: FnOnce(&Row<'_>) -> rusqlite::Result<T>,
+    {
+        self.timed(sql, || self.conn.query_row(sql, params, f))
+    }
+
+    fn timed<T>(
+        &self,
+        sql: &str,
+        run: impl FnOnce() -> rusqlite::Result<T>,
+    ) -> rusqlite::Result<T> {
+        let Some(threshold) = self.threshold else {
+            return run();
+        };
+
+        let plan = self.check_plan(sql);
+        let started = Instant::now();
+        let result = run();
+        let elapsed = started.elapsed();
+
+        if elapsed > threshold {
+            let full_scan = plan.iter().any(|step| is_full_scan(step));
+            self.record(sql, plan, full_scan, Some(elapsed));
+        }
+        result
+    }
+
+    /// Plan of `sql`, explaining and checking it the first time it's seen
+    ///
+    /// Statements without parameters are not explained: they are fixed
+    /// queries like whole-table counts, which scan by design.
+    fn check_plan(&self, sql: &str) -> Vec<String> {
+        if !sql.contains('?') {
+            return Vec::new();
+        }
+        if let Some(plan) = self.plans.borrow().get(sql) {
+            return plan.clone();
+        }
+
+        // A statement that can't be explained fails when it runs instead
+        let plan = explain_query_plan(&self.conn, sql).unwrap_or_default();
+        self.plans.borrow_mut().insert(sql.to_string(), plan.clone());
+
+        if plan.iter().any(|step| is_full_scan(step)) {
+            self.record(sql, plan.clone(), true, None);
+        }
+        plan
+    }
+
+    fn record(&self, sql: &str, plan: Vec<String>, full_scan: bool, elapsed: Option<Duration>) {
+        let duration_ms = elapsed.map(|d| d.as_secs_f64() * 1000.0);
+        tracing::warn!(
+            sql,
+            ?plan,
+            full_scan,
+            ?duration_ms,
+            "slow SQLite query"
+        );
+
+        let mut records = self.records.borrow_mut();
+        records.push_front(SlowQueryRecord {
+            sql: sql.to_string(),
+            plan,
+            full_scan,
+            duration_ms,
+            recorded_at: Utc::now(),
+        });
+        records.truncate(MAX_SLOW_QUERY_RECORDS);
+    }
+}
+
+impl Deref for SlowQueryLogger {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        &self.conn
+    }
+}
+
+/// `detail` column of `EXPLAIN QUERY PLAN <sql>`, in plan order
+fn explain_query_plan(conn: &Connection, sql: &str) -> rusqlite::Result<Vec<String>> {
+    let mut stmt = conn.prepare(&format!("EXPLAIN QUERY PLAN {}", sql))?;
+    // The plan doesn't depend on the values, so the parameters stay unbound
+    let mut rows = stmt.raw_query();
+    let mut plan = Vec::new();
+    while let Some(row) = rows.next()? {
+        plan.push(row.get("detail")?);
+    }
+    Ok(plan)
+}
+
+/// Whether a plan step reads a whole table
+///
+/// SQLite before 3.36 says `SCAN TABLE bikes`, later versions `SCAN bikes`.
+/// Scans of an index (`USING COVERING INDEX`) and of constant rows don't count.
+fn is_full_scan(step: &str) -> bool {
+    step.starts_with("SCAN ") && !step.contains("INDEX") && !step.contains("CONSTANT ROW")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn logger(threshold: Option<Duration>) -> SlowQueryLogger {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE bikes (id TEXT PRIMARY KEY, name TEXT NOT NULL, status TEXT NOT NULL);
+             CREATE INDEX idx_bikes_status ON bikes(status);
+             INSERT INTO bikes VALUES ('BIKE-0001', 'Centraal', 'available');",
+        )
+        .unwrap();
+        SlowQueryLogger::new(conn, threshold)
+    }
+
+    #[test]
+    fn test_full_scan_is_logged_once() {
+        let log = logger(Some(Duration::from_secs(3600)));
+
+        let by_name = "SELECT id FROM bikes WHERE name = ?1";
+        for _ in 0..2 {
+            let id: String = log.query_row(by_name, ["Centraal"], |row| row.get(0)).unwrap();
+            assert_eq!(id, "BIKE-0001");
+        }
+        let mut stmt = log.prepare("SELECT id FROM bikes WHERE status = ?1").unwrap();
+        assert_eq!(stmt.query_map(["available"], |row| row.get::<_, String>(0)).unwrap().count(), 1);
+
+        let records = log.records();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].sql, by_name);
+        assert!(records[0].full_scan);
+        assert_eq!(records[0].duration_ms, None);
+        assert!(records[0].plan.iter().any(|step| step.starts_with("SCAN")));
+    }
+
+    #[test]
+    fn test_statements_over_threshold_are_logged() {
+        let log = logger(Some(Duration::ZERO));
+
+        log.execute("UPDATE bikes SET status = ?1 WHERE id = ?2", ["in_use", "BIKE-0001"])
+            .unwrap();
+
+        let records = log.records();
+        assert_eq!(records.len(), 1);
+        assert!(!records[0].full_scan);
+        assert!(records[0].duration_ms.is_some());
+    }
+
+    #[test]
+    fn test_no_threshold_logs_nothing() {
+        let log = logger(None);
+
+        log.query_row("SELECT id FROM bikes WHERE name = ?1", ["Centraal"], |row| {
+            row.get::<_, String>(0)
+        })
+        .unwrap();
+
+        assert!(log.records().is_empty());
+    }
+
+    #[test]
+    fn test_only_the_latest_records_are_kept() {
+        let log = logger(Some(Duration::ZERO));
+
+        for i in 0..MAX_SLOW_QUERY_RECORDS + 5 {
+            let sql = format!("SELECT id FROM bikes WHERE id = ?1 AND {} = {}", i, i);
+            let _ = log.query_row(&sql, ["BIKE-0001"], |row| row.get::<_, String>(0));
+        }
+
+        let records = log.records();
+        assert_eq!(records.len(), MAX_SLOW_QUERY_RECORDS);
+        assert!(records[0].sql.contains(&format!("{0} = {0}", MAX_SLOW_QUERY_RECORDS + 4)));
+    }
+
+    #[test]
+    fn test_is_full_scan() {
+        assert!(is_full_scan("SCAN bikes"));
+        assert!(is_full_scan("SCAN TABLE bikes"));
+        assert!(!is_full_scan("SEARCH bikes USING INDEX idx_bikes_status (status=?)"));
+        assert!(!is_full_scan("SCAN bikes USING COVERING INDEX idx_bikes_status"));
+        assert!(!is_full_scan("SCAN CONSTANT ROW"));
+    }
+}
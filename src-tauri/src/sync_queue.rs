@@ -0,0 +1,208 @@
+//! Offline Sync Queue (SQLite backend)
+//!
+//! # Purpose
+//! When the database file sits on a network drive, or the disk is briefly
+//! unavailable, writes fail immediately. `Database::with_queue_fallback`
+//! parks such writes in this queue instead, and `flush_sync_queue` replays
+//! them once the database is reachable again.
+//!
+//! # Why a separate file?
+//! A write that can't reach the database can't be queued inside it either.
+//! The queue lives in its own SQLite file next to the database.
+//!
+//! # What gets queued
+//! Only failures that may go away on their own (busy, locked, I/O, full
+//! disk); invalid data is rejected right away. Each entry is a
+//! `SyncOperation` as JSON, replayed in insertion order. While writes are
+//! waiting, new writes queue up behind them, so a replay never overwrites
+//! newer data; a flush stops at the first failure for the same reason.
+//! A failed replay
+//! keeps the entry and counts a retry; after `MAX_SYNC_RETRIES` it is
+//! stuck: kept for inspection, but no longer replayed.
+
+use crate::database::{Database, DatabaseError, QueryContext};
+use crate::models::{BikeStatus, CreateDeliveryRequest, DeliveryStatus};
+use chrono::{DateTime, Utc};
+use rusqlite::{Connection, Result as SqliteResult};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Queue file name, next to the database file
+pub const SYNC_QUEUE_FILE: &str = "sync_queue.db";
+
+/// Failed replays after which a write is stuck and no longer replayed
+pub const MAX_SYNC_RETRIES: u32 = 5;
+
+/// A write that can be queued and replayed later
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "operation", content = "payload", rename_all = "snake_case")]
+pub enum SyncOperation {
+    UpdateBikeStatus {
+        bike_id: String,
+        status: BikeStatus,
+        latitude: Option<f64>,
+        longitude: Option<f64>,
+        battery_level: Option<u8>,
+    },
+    RetireBike { bike_id: String },
+    CreateDelivery(CreateDeliveryRequest),
+    UpdateDeliveryStatus {
+        delivery_id: String,
+        status: DeliveryStatus,
+        rating: Option<u8>,
+    },
+    ResolveIssue { issue_id: String, note: Option<String> },
+}
+
+impl SyncOperation {
+    /// Name stored in the queue's `operation` column
+    pub fn name(&self) -> &'static str {
+        match self {
+            SyncOperation::UpdateBikeStatus { .. } => "update_bike_status",
+            SyncOperation::RetireBike { .. } => "retire_bike",
+            SyncOperation::CreateDelivery(_) => "create_delivery",
+            SyncOperation::UpdateDeliveryStatus { .. } => "update_delivery_status",
+            SyncOperation::ResolveIssue { .. } => "resolve_issue",
+        }
+    }
+
+    /// Run the write against `db`
+    pub fn apply(&self, db: &Database) -> Result<(), DatabaseError> {
+        match self {
+            SyncOperation::UpdateBikeStatus {
+                bike_id,
+                status,
+                latitude,
+                longitude,
+                battery_level,
+            } => db.update_bike_status(bike_id, status, *latitude, *longitude, *battery_level),
+            SyncOperation::RetireBike { bike_id } => db.retire_bike(bike_id),
+            SyncOperation::CreateDelivery(request) => db.create_delivery(request).map(|_| ()),
+            SyncOperation::UpdateDeliveryStatus {
+                delivery_id,
+                status,
+                rating,
+            } => db
+                .update_delivery_status(delivery_id, status.clone(), *rating)
+                .map(|_| ()),
+            SyncOperation::ResolveIssue { issue_id, note } => {
+                db.resolve_issue(issue_id, note.as_deref()).map(|_| ())
+            }
+        }
+    }
+}
+
+/// One pending write
+#[derive(Debug, Clone)]
+pub struct QueuedWrite {
+    pub id: i64,
+    pub operation: String,
+    /// The `SyncOperation` as JSON
+    pub payload_json: String,
+    pub created_at: DateTime<Utc>,
+    /// Failed replays so far
+    pub retries: u32,
+}
+
+impl QueuedWrite {
+    /// Whether the write failed too often to be replayed again
+    pub fn is_stuck(&self) -> bool {
+        self.retries >= MAX_SYNC_RETRIES
+    }
+}
+
+/// Writes waiting for the database, persisted in their own SQLite file
+pub struct OfflineSyncQueue {
+    conn: Connection,
+}
+
+impl OfflineSyncQueue {
+    /// Open (or create) the queue file
+    pub fn open(path: &Path) -> Result<Self, DatabaseError> {
+        let conn = Connection::open(path).context("open_sync_queue")?;
+        conn.execute_batch(
+            r#"CREATE TABLE IF NOT EXISTS sync_queue (
+                id INTEGER PRIMARY KEY,
+                operation TEXT NOT NULL,
+                payload_json TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                retries INTEGER NOT NULL DEFAULT 0
+            );"#,
+        )
+        .context("open_sync_queue")?;
+
+        Ok(Self { conn })
+    }
+
+    /// Append a write to the end of the queue
+    pub fn push(&self, operation: &SyncOperation) -> Result<(), DatabaseError> {
+        let payload_json = serde_json::to_string(operation)
+            .map_err(|e| DatabaseError::InvalidData(format!("Unserializable operation: {}", e)))?;
+
+        self.conn
+            .execute(
+                "INSERT INTO sync_queue (operation, payload_json, created_at) VALUES (?1, ?2, ?3)",
+                rusqlite::params![operation.name(), payload_json, Utc::now().to_rfc3339()],
+            )
+            .context("push_sync_queue")?;
+
+        Ok(())
+    }
+
+    /// Every pending write, oldest first
+    pub fn pending(&self) -> Result<Vec<QueuedWrite>, DatabaseError> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                r#"SELECT id, operation, payload_json, created_at, retries
+                   FROM sync_queue ORDER BY id"#,
+            )
+            .context("pending_sync_queue")?;
+
+        let writes = stmt
+            .query_map([], |row| {
+                let created_at: String = row.get(3)?;
+                Ok(QueuedWrite {
+                    id: row.get(0)?,
+                    operation: row.get(1)?,
+                    payload_json: row.get(2)?,
+                    created_at: DateTime::parse_from_rfc3339(&created_at)
+                        .map(|t| t.with_timezone(&Utc))
+                        .unwrap_or_else(|_| Utc::now()),
+                    retries: row.get(4)?,
+                })
+            })
+            .context("pending_sync_queue")?
+            .collect::<SqliteResult<Vec<_>>>()
+            .context("pending_sync_queue")?;
+
+        Ok(writes)
+    }
+
+    /// Whether any write is waiting to be replayed; stuck ones don't count
+    pub fn has_replayable(&self) -> Result<bool, DatabaseError> {
+        self.conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM sync_queue WHERE retries < ?1)",
+                [MAX_SYNC_RETRIES],
+                |row| row.get(0),
+            )
+            .context("has_replayable_sync_queue")
+    }
+
+    /// Drop a write that has been applied
+    pub fn remove(&self, id: i64) -> Result<(), DatabaseError> {
+        self.conn
+            .execute("DELETE FROM sync_queue WHERE id = ?1", [id])
+            .context("remove_sync_queue")?;
+        Ok(())
+    }
+
+    /// Count a failed replay; the write stays queued
+    pub fn record_failure(&self, id: i64) -> Result<(), DatabaseError> {
+        self.conn
+            .execute("UPDATE sync_queue SET retries = retries + 1 WHERE id = ?1", [id])
+            .context("record_sync_queue_failure")?;
+        Ok(())
+    }
+}
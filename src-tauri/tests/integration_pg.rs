@@ -0,0 +1,75 @@
+//! Integration tests for the PostgreSQL backend
+//!
+//! Each test starts a throwaway PostgreSQL container with `testcontainers`,
+//! so Docker must be running. The backends are mutually exclusive:
+//!
+//! ```bash
+//! cargo test --no-default-features --features postgres --test integration_pg
+//! ```
+#![cfg(feature = "postgres")]
+
+use amsterdam_bike_fleet_lib::{Database, DatabaseConfig};
+use testcontainers::clients::Cli;
+use testcontainers::images::postgres::Postgres;
+
+/// Connect to a container started from `Postgres::default()`
+///
+/// The image trusts local connections as `postgres`, so no password is set.
+async fn connect(port: u16) -> Database {
+    let config = DatabaseConfig {
+        host: "127.0.0.1".to_string(),
+        port,
+        user: "postgres".to_string(),
+        password: String::new(),
+        dbname: "postgres".to_string(),
+        pool_size: 4,
+        ..DatabaseConfig::default()
+    };
+
+    Database::new(config).await.expect("connect to test database")
+}
+
+#[cfg(feature = "postgres")]
+#[tokio::test]
+async fn test_new_database_is_seeded() {
+    let docker = Cli::default();
+    let container = docker.run(Postgres::default());
+    let db = connect(container.get_host_port_ipv4(5432)).await;
+
+    let bikes = db.get_all_bikes(None, None).await.unwrap();
+    assert_eq!(bikes.len(), 10);
+
+    let deliveries = db
+        .get_deliveries(None, None, None, None, None, None, None, 100)
+        .await
+        .unwrap();
+    assert_eq!(deliveries.total_count, 50);
+    assert_eq!(deliveries.items.len(), 50);
+    assert!(deliveries.next_cursor.is_none());
+}
+
+#[cfg(feature = "postgres")]
+#[tokio::test]
+async fn test_add_bike() {
+    let docker = Cli::default();
+    let container = docker.run(Postgres::default());
+    let db = connect(container.get_host_port_ipv4(5432)).await;
+
+    let bike = db.add_bike("Test Bike", 52.37, 4.89, Some(80)).await.unwrap();
+
+    let bikes = db.get_all_bikes(None, None).await.unwrap();
+    assert_eq!(bikes.len(), 11);
+    let stored = bikes.iter().find(|b| b.id == bike.id).expect("new bike listed");
+    assert_eq!(stored.name, "Test Bike");
+    assert_eq!(stored.battery_level, Some(80));
+}
+
+#[cfg(feature = "postgres")]
+#[tokio::test]
+async fn test_health_check_on_primary() {
+    let docker = Cli::default();
+    let container = docker.run(Postgres::default());
+    let db = connect(container.get_host_port_ipv4(5432)).await;
+
+    assert!(db.health_check().await.unwrap());
+}
@@ -0,0 +1,72 @@
+//! Integration tests for concurrent seat allocation
+//!
+//! Simulates several secure sessions competing for a limited number of
+//! license seats.
+
+use amsterdam_bike_fleet_lib::license::SeatRegistry;
+use std::sync::{Arc, Barrier};
+use std::thread;
+
+#[test]
+fn test_seats_limited_to_license_count() {
+    let seats = SeatRegistry::default();
+
+    seats.claim("first", 2).expect("first seat");
+    seats.claim("second", 2).expect("second seat");
+
+    let err = seats.claim("third", 2).unwrap_err();
+    assert_eq!(err.to_string(), "All 2 licensed seats are in use");
+    assert_eq!(seats.active_sessions(), 2);
+
+    // Claiming a held seat again only refreshes it
+    seats.claim("first", 2).expect("re-claim");
+    assert_eq!(seats.active_sessions(), 2);
+
+    // Ending a session frees its seat
+    seats.release("first");
+    assert_eq!(seats.active_sessions(), 1);
+    assert!(seats.claim("third", 2).is_ok());
+}
+
+#[test]
+fn test_concurrent_sessions_never_exceed_seats() {
+    const SESSIONS: usize = 8;
+    let seats = SeatRegistry::default();
+    let barrier = Arc::new(Barrier::new(SESSIONS));
+
+    let handles: Vec<_> = (0..SESSIONS)
+        .map(|i| {
+            let seats = seats.clone();
+            let barrier = Arc::clone(&barrier);
+            thread::spawn(move || {
+                let session_id = format!("session-{}", i);
+                let granted = seats.claim(&session_id, 3).is_ok();
+                // Hold the seat until every session has tried to claim one
+                barrier.wait();
+                if granted {
+                    seats.release(&session_id);
+                }
+                granted
+            })
+        })
+        .collect();
+
+    let granted = handles
+        .into_iter()
+        .map(|h| h.join().unwrap())
+        .filter(|granted| *granted)
+        .count();
+
+    assert_eq!(granted, 3);
+    assert_eq!(seats.active_sessions(), 0, "all seats released after sessions end");
+}
+
+#[test]
+fn test_unlimited_when_license_has_no_seats() {
+    let seats = SeatRegistry::default();
+
+    for i in 0..100 {
+        seats.claim(&format!("session-{}", i), u32::MAX).unwrap();
+    }
+    assert_eq!(seats.active_sessions(), 100);
+}
@@ -136,6 +136,24 @@ export class DelivererGraphPageComponent implements OnInit {
           { label: 'Reporter', value: this.selectedNode.data.reporter || '' },
           { label: 'Resolved', value: this.selectedNode.data.resolved ? 'Yes' : 'No' }
         );
+        if (this.selectedNode.data.resolved_at) {
+          details.push({
+            label: 'Resolved At',
+            value: new Date(this.selectedNode.data.resolved_at).toLocaleString()
+          });
+        }
+        break;
+      case 'restaurant':
+        details.push(
+          { label: 'Name', value: this.selectedNode.data.name || '' },
+          { label: 'Deliveries', value: this.selectedNode.data.delivery_count?.toString() || '0' }
+        );
+        break;
+      case 'issueCluster':
+        details.push(
+          { label: 'Category', value: this.selectedNode.data.category || '' },
+          { label: 'Issues', value: this.selectedNode.data.count?.toString() || '0' }
+        );
         break;
     }
 
@@ -217,6 +217,10 @@ export class ForceGraphComponent implements OnInit, OnChanges, OnDestroy {
         return this.getDeliveryColor(node);
       case 'issue':
         return this.getIssueColor(node);
+      case 'restaurant':
+        return '#FF9800'; // Orange for restaurant
+      case 'issueCluster':
+        return '#E57373'; // Light red for issue cluster
       default:
         return '#9E9E9E';
     }
@@ -382,6 +386,10 @@ export class ForceGraphComponent implements OnInit, OnChanges, OnDestroy {
         return `${node.data.category} issue - ${
           node.data.resolved ? 'resolved' : 'open'
         }`;
+      case 'restaurant':
+        return `${node.data.name} (${node.data.delivery_count} deliveries)`;
+      case 'issueCluster':
+        return `${node.data.count} ${node.data.category} issues`;
       default:
         return node.label;
     }
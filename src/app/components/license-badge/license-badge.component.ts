@@ -109,7 +109,7 @@ export class LicenseBadgeComponent {
     // Valid license
     if (status.valid && status.info) {
       // Check if it's a trial (has 'trial' feature or is expiring soon)
-      const isTrial = status.info.features.includes('trial') || isExpiringSoon;
+      const isTrial = status.is_trial || status.info.features.includes('trial') || isExpiringSoon;
 
       if (isTrial) {
         const daysText = status.days_remaining !== null
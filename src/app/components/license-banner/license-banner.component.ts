@@ -26,9 +26,10 @@ export class LicenseBannerComponent {
     this.bannerState$ = combineLatest([
       this.licenseService.isExpired$,
       this.licenseService.isExpiringSoon$,
-      this.licenseService.daysRemaining$
+      this.licenseService.daysRemaining$,
+      this.licenseService.graceDaysRemaining$
     ]).pipe(
-      map(([isExpired, isExpiringSoon, daysRemaining]) => {
+      map(([isExpired, isExpiringSoon, daysRemaining, graceDaysRemaining]) => {
         if (isExpired) {
           return {
             type: 'error' as BannerType,
@@ -37,6 +38,15 @@ export class LicenseBannerComponent {
           };
         }
 
+        if (graceDaysRemaining !== null) {
+          const dayText = graceDaysRemaining === 1 ? 'day' : 'days';
+          return {
+            type: 'warning' as BannerType,
+            daysRemaining: graceDaysRemaining,
+            message: `Your license has expired. It keeps working for ${graceDaysRemaining} more ${dayText}; renew now to avoid service interruption.`
+          };
+        }
+
         if (isExpiringSoon && daysRemaining !== null) {
           const dayText = daysRemaining === 1 ? 'day' : 'days';
           return {
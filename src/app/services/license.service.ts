@@ -64,10 +64,18 @@ export class LicenseService {
   readonly isExpired$ = this._status.pipe(
     map(s => {
       const days = s?.days_remaining;
-      return days !== null && days !== undefined && days <= 0;
+      return !s?.in_grace_period && days !== null && days !== undefined && days <= 0;
     })
   );
 
+  readonly inGracePeriod$ = this._status.pipe(
+    map(s => s?.in_grace_period ?? false)
+  );
+
+  readonly graceDaysRemaining$ = this._status.pipe(
+    map(s => s?.grace_days_remaining ?? null)
+  );
+
   constructor(private tauri: TauriService) {
     // Auto-check license on service init if in Tauri
     this.checkLicense();
@@ -123,7 +131,10 @@ export class LicenseService {
         valid: false,
         info: null,
         error: 'Running in browser mode (no license check)',
-        days_remaining: null
+        days_remaining: null,
+        in_grace_period: false,
+        grace_days_remaining: null,
+        is_trial: false
       };
       this._status.next(browserStatus);
       return browserStatus;
@@ -202,7 +213,10 @@ export class LicenseService {
         valid: false,
         info: null,
         error: 'License deactivated',
-        days_remaining: null
+        days_remaining: null,
+        in_grace_period: false,
+        grace_days_remaining: null,
+        is_trial: false
       });
     } catch (err) {
       const errorMsg = err instanceof Error ? err.message : String(err);
@@ -4,7 +4,9 @@ import { invoke } from '@tauri-apps/api/core';
 /**
  * Bike status enum matching Rust backend
  */
-export type BikeStatus = 'available' | 'in_use' | 'maintenance' | 'charging' | 'offline';
+export type BikeStatus = 'available' | 'in_use' | 'maintenance' | 'charging' | 'offline' | 'reserved';
+export type BikeSort = 'name' | 'battery' | 'total_trips' | 'updated_at';
+export type SortDirection = 'asc' | 'desc';
 
 /**
  * Bike model matching Rust backend
@@ -19,10 +21,33 @@ export interface Bike {
   last_maintenance: string | null;
   total_trips: number;
   total_distance_km: number;
+  reservation_expires_at: string | null;
+  maintenance_threshold_override: number | null; // Days; null = fleet setting
+  deleted_at?: string | null; // Set once the bike is retired
+  next_maintenance_at?: string | null; // Scheduled maintenance, if any
   created_at: string;
   updated_at: string;
 }
 
+/**
+ * Preset fleet states for QA and demos
+ */
+export type SimulationScenario =
+  | 'all_idle'
+  | 'all_delivering'
+  | 'critical_battery'
+  | 'post_maintenance_peak'
+  | 'maximum_coverage';
+
+/**
+ * Bikes with a single status, plus their count
+ */
+export interface BikesByStatusResult {
+  bikes: Bike[];
+  count: number;
+  status: BikeStatus;
+}
+
 /**
  * Fleet statistics model
  */
@@ -33,8 +58,132 @@ export interface FleetStats {
   bikes_in_maintenance: number;
   bikes_charging: number;
   bikes_offline: number;
+  bikes_reserved: number;
   average_battery: number;
   total_trips_today: number;
+  bikes_with_custom_threshold: number;
+}
+
+/**
+ * Per-bike figures for the bike detail view
+ */
+export interface BikeStatistics {
+  bike_id: string;
+  total_trips: number;
+  total_distance_km: number;
+  days_since_maintenance: number | null;
+  effective_maintenance_threshold: number; // Days
+  maintenance_due: boolean;
+}
+
+/**
+ * Outcome of a bulk CSV bike import
+ */
+export interface ImportResult {
+  imported: number;
+  skipped: number;
+  errors: string[]; // "Line N: reason" per skipped row
+}
+
+/**
+ * Outcome of copying a SQLite database to a PostgreSQL server
+ */
+export interface MigrationReport {
+  bikes_migrated: number;
+  deliveries_migrated: number;
+  issues_migrated: number;
+  skipped: number; // Rows of any table the server already had
+  warnings: string[];
+}
+
+/**
+ * Low-battery bikes with badge counts (critical: below 10%)
+ */
+export interface BatteryAlertSummary {
+  critical_count: number;
+  warning_count: number;
+  bikes: Bike[]; // Lowest battery first
+}
+
+/**
+ * Search matches, each list capped at the requested limit
+ */
+export interface SearchResults {
+  bikes: Bike[];
+  deliveries: Delivery[];
+}
+
+export type AuditOp = 'insert' | 'update' | 'delete';
+
+/**
+ * One recorded change to a bike, delivery or issue row
+ */
+export interface AuditLogEntry {
+  id: string;
+  table_name: string;
+  row_id: string;
+  operation: AuditOp;
+  old_value_json: string | null; // null for inserts
+  new_value_json: string | null; // null for deletes
+  occurred_at: string;
+}
+
+export type FleetEventType =
+  | 'bike_added'
+  | 'bike_status_changed'
+  | 'bike_retired'
+  | 'delivery_created'
+  | 'delivery_completed'
+  | 'issue_filed'
+  | 'issue_resolved';
+
+/**
+ * One entry of the append-only fleet event log
+ */
+export interface FleetEvent {
+  id: number; // Increases with every event; pass the last one seen to pollEvents
+  event_type: FleetEventType;
+  payload_json: string; // The bike, delivery or issue after the change
+  occurred_at: string;
+}
+
+export type TimeBucket = 'hour' | 'day' | 'week';
+
+/**
+ * Fleet activity within one time bucket (dashboard charts)
+ */
+export interface FleetTimeSeries {
+  periodStart: string;
+  periodEnd: string; // Exclusive
+  totalDeliveries: number;
+  completedDeliveries: number;
+  averageRating: number; // 0 if nothing rated in the bucket
+  issuesOpened: number;
+  issuesResolved: number;
+  distanceKm: number;
+}
+
+/**
+ * Delivery load in one hour of the day (UTC), summed over a date range
+ */
+export interface HourlyUtilization {
+  hour: number; // 0-23
+  activeDeliveries: number;
+  completedDeliveries: number;
+  utilizationRatio: number; // Share of the fleet (0-1) busy in this hour
+}
+
+/**
+ * Delivery volume and customer satisfaction of one restaurant
+ */
+export interface RestaurantStats {
+  id: string;
+  name: string;
+  address: string | null;
+  totalDeliveries: number;
+  completedDeliveries: number;
+  complaintCount: number;
+  avgRating: number | null; // null = no rated deliveries yet
 }
 
 /**
@@ -47,6 +196,29 @@ export interface DatabaseStats {
   last_sync: string | null;
 }
 
+/**
+ * SQLite connection tuning (SQLite backend only)
+ */
+export interface SqliteConfig {
+  walMode?: boolean;
+  busyTimeoutMs?: number;
+  /** Log full table scans and statements slower than this; null disables */
+  logThresholdMs?: number | null;
+}
+
+/**
+ * A statement flagged by the SQLite slow query log
+ */
+export interface SlowQueryRecord {
+  sql: string;
+  /** EXPLAIN QUERY PLAN details; empty for statements without parameters */
+  plan: string[];
+  fullScan: boolean;
+  /** null when only the plan was flagged */
+  durationMs: number | null;
+  recordedAt: string;
+}
+
 /**
  * Health check response
  */
@@ -56,6 +228,24 @@ export interface HealthStatus {
   rust_version: string;
   tauri_version: string;
   timestamp: string;
+  pool?: PoolMetrics; // PostgreSQL backend only
+  database_ok: boolean;
+  license_valid: boolean;
+  pool_available: number | null; // PostgreSQL backend only
+  memory_usage_bytes: number | null; // Linux only
+}
+
+/**
+ * Connection pool state of the PostgreSQL backend
+ */
+export interface PoolMetrics {
+  pool_size: number;
+  available: number;
+  waiting: number;
+  max_size: number;
+  total_acquired: number;
+  total_recycled: number;
+  replication_lag_bytes: number | null;
 }
 
 /**
@@ -90,6 +280,7 @@ export interface LicenseInfo {
   features: string[];
   seats?: number;
   issued?: string;
+  grace_period_days?: number;
   version: number;
 }
 
@@ -101,6 +292,28 @@ export interface LicenseStatus {
   info: LicenseInfo | null;
   error: string | null;
   days_remaining: number | null;
+  /** Expired but still valid; `error` then holds the warning text */
+  in_grace_period: boolean;
+  grace_days_remaining: number | null;
+  /** No license installed; `info` is the implicit 30-day trial */
+  is_trial: boolean;
+}
+
+/**
+ * Signed license compliance report
+ */
+export interface LicenseReport {
+  license_valid: boolean;
+  customer: string | null;
+  company: string | null;
+  product: string;
+  expires: string | null;
+  days_remaining: number | null;
+  features: string[];
+  seats: number | null;
+  machine_fingerprint: string;
+  report_generated_at: string;
+  report_signature: string;
 }
 
 /**
@@ -223,6 +436,8 @@ export class TauriService {
 
   /**
    * Check the health of the Rust backend
+   *
+   * Rejects with "503 Service Unavailable: ..." when the database doesn't answer.
    */
   async healthCheck(): Promise<HealthStatus> {
     return this.invokeCommand<HealthStatus>('health_check');
@@ -254,15 +469,64 @@ export class TauriService {
     return this.invokeCommand<boolean>('is_database_initialized');
   }
 
+  /**
+   * Change SQLite journal mode, busy timeout and slow query threshold
+   * until the app restarts
+   * Omitted fields take their defaults (WAL, 5000 ms, no slow query log)
+   *
+   * @returns The resulting journal mode
+   */
+  async setSqlitePragmas(config: SqliteConfig): Promise<string> {
+    return this.invokeCommand<string>('set_sqlite_pragmas', { config });
+  }
+
+  /**
+   * Get the latest full table scans and slow statements, newest first
+   * (SQLite only)
+   */
+  async getSlowQueryReport(): Promise<SlowQueryRecord[]> {
+    return this.invokeCommand<SlowQueryRecord[]>('get_slow_query_report');
+  }
+
+  /**
+   * Copy bikes, trips, deliveries, issues and the audit log to a PostgreSQL
+   * server; rows it already has are skipped
+   * Copies this app's database unless `sqlitePath` is given
+   * (SQLite builds with the pg-migration feature only)
+   */
+  async runMigration(
+    pgHost: string,
+    pgUser: string,
+    pgPassword: string,
+    pgDbname: string,
+    pgPort?: number,
+    sqlitePath?: string
+  ): Promise<MigrationReport> {
+    return this.invokeCommand<MigrationReport>('run_migration', {
+      sqlitePath,
+      pgHost,
+      pgPort,
+      pgUser,
+      pgPassword,
+      pgDbname,
+    });
+  }
+
   // ============================================
   // Fleet Commands
   // ============================================
 
   /**
-   * Get all bikes in the fleet
+   * Get all bikes in the fleet, by name unless a sort is given
    */
-  async getFleetData(): Promise<Bike[]> {
-    return this.invokeCommand<Bike[]>('get_fleet_data');
+  async getFleetData(options?: {
+    sortBy?: BikeSort;
+    sortDir?: SortDirection;
+  }): Promise<Bike[]> {
+    return this.invokeCommand<Bike[]>('get_fleet_data', {
+      sortBy: options?.sortBy ?? null,
+      sortDir: options?.sortDir ?? null
+    });
   }
 
   /**
@@ -272,6 +536,122 @@ export class TauriService {
     return this.invokeCommand<Bike | null>('get_bike_by_id', { bikeId });
   }
 
+  /**
+   * Get all bikes with a given status, plus their count
+   */
+  async getBikesByStatus(status: BikeStatus): Promise<BikesByStatusResult> {
+    return this.invokeCommand<BikesByStatusResult>('get_bikes_by_status', { status });
+  }
+
+  /**
+   * Get all bikes matching any of the given statuses
+   */
+  async getBikesByMultipleStatuses(statuses: BikeStatus[]): Promise<Bike[]> {
+    return this.invokeCommand<Bike[]>('get_bikes_by_multiple_statuses', { statuses });
+  }
+
+  /**
+   * Set a bike's maintenance threshold in days (null restores the fleet setting)
+   */
+  async setBikeMaintenanceThreshold(bikeId: string, threshold: number | null): Promise<void> {
+    return this.invokeCommand<void>('set_bike_maintenance_threshold', { bikeId, threshold });
+  }
+
+  /**
+   * Get bikes due for maintenance under their effective threshold
+   */
+  async getBikesDueForMaintenance(): Promise<Bike[]> {
+    return this.invokeCommand<Bike[]>('get_bikes_due_for_maintenance');
+  }
+
+  /**
+   * Get bikes whose scheduled maintenance date has passed, most overdue first
+   */
+  async getOverdueMaintenance(): Promise<Bike[]> {
+    return this.invokeCommand<Bike[]>('get_overdue_maintenance');
+  }
+
+  /**
+   * Get bikes below a battery percentage (default 20), lowest first
+   */
+  async getLowBatteryBikes(threshold?: number): Promise<BatteryAlertSummary> {
+    return this.invokeCommand<BatteryAlertSummary>('get_low_battery_bikes', { threshold });
+  }
+
+  /**
+   * Get fleet activity per bucket between two RFC 3339 timestamps (`to` exclusive)
+   * Empty buckets are included with zero values
+   */
+  async getFleetTimeSeries(from: string, to: string, bucket: TimeBucket): Promise<FleetTimeSeries[]> {
+    return this.invokeCommand<FleetTimeSeries[]>('get_fleet_time_series', { from, to, bucket });
+  }
+
+  /**
+   * Get delivery load per hour of the day (UTC), all 24 hours from midnight
+   * `from`/`to` are RFC 3339; `to` is exclusive
+   */
+  async getHourlyUtilization(from: string, to: string): Promise<HourlyUtilization[]> {
+    return this.invokeCommand<HourlyUtilization[]>('get_hourly_utilization', { from, to });
+  }
+
+  /**
+   * Get delivery figures of one restaurant
+   */
+  async getRestaurantStats(restaurantId: string): Promise<RestaurantStats> {
+    return this.invokeCommand<RestaurantStats>('get_restaurant_stats', { restaurantId });
+  }
+
+  /**
+   * Get the restaurants with the most deliveries, best rated first on ties
+   */
+  async getTopRestaurants(limit: number): Promise<RestaurantStats[]> {
+    return this.invokeCommand<RestaurantStats[]>('get_top_restaurants', { limit });
+  }
+
+  /**
+   * Export the fleet (retired bikes excluded) as a CSV document
+   * Requires the "export" license feature
+   */
+  async exportFleetCsv(): Promise<string> {
+    return this.invokeCommand<string>('export_fleet_csv');
+  }
+
+  /**
+   * Get recorded changes to bikes, deliveries and issues, oldest first
+   * All filters are optional; `from`/`to` are RFC 3339 and `to` is exclusive
+   */
+  async getAuditLog(
+    table?: string,
+    rowId?: string,
+    from?: string,
+    to?: string
+  ): Promise<AuditLogEntry[]> {
+    return this.invokeCommand<AuditLogEntry[]>('get_audit_log', { table, rowId, from, to });
+  }
+
+  /**
+   * Get fleet events logged after `lastId` (0 for the whole log), oldest first
+   * Returns at most `limit` (default 50) events
+   */
+  async pollEvents(lastId: number, limit?: number): Promise<FleetEvent[]> {
+    return this.invokeCommand<FleetEvent[]>('poll_events', { lastId, limit });
+  }
+
+  /**
+   * Search bike names and delivery customer/restaurant fields (case-insensitive)
+   * Returns at most `limit` (default 50) bikes and deliveries each
+   */
+  async search(query: string, limit?: number): Promise<SearchResults> {
+    return this.invokeCommand<SearchResults>('search', { query, limit });
+  }
+
+  /**
+   * Get the figures shown in the bike detail view
+   */
+  async getBikeStatistics(bikeId: string): Promise<BikeStatistics> {
+    return this.invokeCommand<BikeStatistics>('get_bike_statistics', { bikeId });
+  }
+
   /**
    * Add a new bike to the fleet
    */
@@ -279,6 +659,14 @@ export class TauriService {
     return this.invokeCommand<Bike>('add_bike', { request });
   }
 
+  /**
+   * Import bikes from CSV (header: name,latitude,longitude,battery_level)
+   * Invalid rows are skipped and reported in `errors`
+   */
+  async importBikesFromCsv(csvContent: string): Promise<ImportResult> {
+    return this.invokeCommand<ImportResult>('import_bikes_from_csv', { csvContent });
+  }
+
   /**
    * Update the status of a bike
    */
@@ -286,6 +674,42 @@ export class TauriService {
     return this.invokeCommand<void>('update_bike_status', { request });
   }
 
+  /**
+   * Retire a bike; it is hidden from the fleet but its history is kept
+   */
+  async retireBike(bikeId: string): Promise<void> {
+    return this.invokeCommand<void>('retire_bike', { bikeId });
+  }
+
+  /**
+   * Get retired bikes, most recently retired first
+   */
+  async getRetiredBikes(): Promise<Bike[]> {
+    return this.invokeCommand<Bike[]>('get_retired_bikes');
+  }
+
+  /**
+   * Start a trip for a bike; returns the trip ID for endTrip
+   */
+  async startTrip(bikeId: string, startLat: number, startLon: number): Promise<string> {
+    return this.invokeCommand<string>('start_trip', { bikeId, startLat, startLon });
+  }
+
+  /**
+   * End a trip; its distance is added to the bike's totals
+   */
+  async endTrip(tripId: string, endLat: number, endLon: number): Promise<void> {
+    return this.invokeCommand<void>('end_trip', { tripId, endLat, endLon });
+  }
+
+  /**
+   * Put the whole fleet into a preset scenario
+   * Returns the updated fleet
+   */
+  async applySimulationScenario(scenario: SimulationScenario): Promise<Bike[]> {
+    return this.invokeCommand<Bike[]>('apply_simulation_scenario', { scenario });
+  }
+
   /**
    * Get fleet statistics
    */
@@ -335,6 +759,41 @@ export class TauriService {
     return this.invokeCommand<LicenseStatus>('validate_license', { licenseKey });
   }
 
+  /**
+   * Export a signed license report for compliance audits
+   * Fails if no license is stored
+   */
+  async exportLicenseReport(): Promise<LicenseReport> {
+    return this.invokeCommand<LicenseReport>('export_license_report');
+  }
+
+  /**
+   * Export the secure command audit trail as CSV
+   * The output path must be inside the home directory or the app's exports folder
+   * Returns the number of exported rows
+   */
+  async exportAuditTrailCsv(outputPath: string, fromIso8601?: string, toIso8601?: string): Promise<number> {
+    return this.invokeCommand<number>('export_audit_trail_csv', { fromIso8601, toIso8601, outputPath });
+  }
+
+  /**
+   * Export the secure command audit trail as JSON
+   * With integrityCheck, entries failing HMAC verification get `"tampered": true`
+   */
+  async exportAuditTrailJson(
+    outputPath: string,
+    fromIso8601?: string,
+    toIso8601?: string,
+    integrityCheck?: boolean
+  ): Promise<number> {
+    return this.invokeCommand<number>('export_audit_trail_json', {
+      fromIso8601,
+      toIso8601,
+      outputPath,
+      integrityCheck,
+    });
+  }
+
   // ============================================
   // Encrypted IPC (Secure Session)
   // ============================================
@@ -345,6 +804,7 @@ export class TauriService {
 
   private sessionNonce: Uint8Array | null = null;
   private sessionKey: CryptoKey | null = null;
+  private sessionId: string | null = null;
 
   /**
    * Initialize a secure session with encrypted IPC
@@ -355,10 +815,21 @@ export class TauriService {
    * 3. Backend returns nonce (base64 encoded)
    * 4. Client derives same encryption key from license + nonce
    * 5. All subsequent secure_invoke calls use encrypted payloads
+   *
+   * Without an installed license, pass TRIAL_LICENSE_KEY.
+   *
+   * secure_invoke is rate limited to bursts of `rateLimitTokens` commands
+   * (default 100), refilled at `rateLimitRefill` per second (default 20).
    */
-  async initSecureSession(licenseKey: string): Promise<boolean> {
+  async initSecureSession(
+    licenseKey: string,
+    rateLimitTokens?: number,
+    rateLimitRefill?: number
+  ): Promise<boolean> {
     const response = await this.invokeCommand<SecureSessionInfo>('init_secure_session', {
-      licenseKey
+      licenseKey,
+      rateLimitTokens,
+      rateLimitRefill
     });
 
     if (!response.initialized) {
@@ -367,6 +838,7 @@ export class TauriService {
 
     // Decode session nonce from base64
     this.sessionNonce = this.base64ToUint8Array(response.sessionNonceBase64);
+    this.sessionId = response.sessionId;
 
     // Derive session key using SubtleCrypto (matches Rust HKDF)
     await this.deriveSessionKey(licenseKey);
@@ -374,6 +846,37 @@ export class TauriService {
     return true;
   }
 
+  /**
+   * End the secure session and free its license seat
+   *
+   * Sessions that are never closed keep their seat until they have been
+   * idle for 30 minutes.
+   */
+  async closeSecureSession(): Promise<void> {
+    if (!this.sessionId) {
+      return;
+    }
+
+    await this.invokeCommand<void>('close_session', { sessionId: this.sessionId });
+    this.sessionId = null;
+    this.sessionNonce = null;
+    this.sessionKey = null;
+  }
+
+  /**
+   * Rotate the session key without re-initializing the session
+   *
+   * The backend picks the new nonce and switches keys first; the local key
+   * only changes once it returns the nonce, so a failed rotation leaves both sides on the old key.
+   * Requests in flight during rotation fail decryption and must be resent.
+   */
+  async rotateSecureSession(licenseKey: string): Promise<void> {
+    const newNonce = await this.invokeCommand<string>('rotate_session');
+
+    this.sessionNonce = this.base64ToUint8Array(newNonce);
+    await this.deriveSessionKey(licenseKey);
+  }
+
   /**
    * Derive session encryption key from license + nonce
    *
@@ -426,6 +929,16 @@ export class TauriService {
     return this.sessionKey !== null && this.sessionNonce !== null;
   }
 
+  /**
+   * Ping the backend through the encrypted channel
+   *
+   * Fails if the secure session is no longer valid; otherwise returns the
+   * round-trip time in milliseconds. Pings are not written to the audit trail.
+   */
+  async measureSecureRoundtrip(sequence: number): Promise<number> {
+    return this.invokeCommand<number>('measure_secure_roundtrip', { sequence });
+  }
+
   /**
    * Invoke a secure (encrypted) command
    *
@@ -460,12 +973,29 @@ export class TauriService {
   // ============================================
 
   /**
-   * Get deliveries with optional filtering
+   * Get one page of deliveries with optional filtering
+   * `fromTs`/`toTs` (ISO 8601) limit results to deliveries created in that window
+   * Pass the previous page's `nextCursor` as `cursor` to continue (page size 1-500, default 50)
    */
-  async getDeliveries(options?: { bikeId?: string; status?: string }): Promise<Delivery[]> {
-    return this.invokeCommand<Delivery[]>('get_deliveries', {
+  async getDeliveries(options?: {
+    bikeId?: string;
+    status?: string;
+    fromTs?: string;
+    toTs?: string;
+    sortBy?: DeliverySort;
+    sortDir?: SortDirection;
+    cursor?: string;
+    limit?: number;
+  }): Promise<Page<Delivery>> {
+    return this.invokeCommand<Page<Delivery>>('get_deliveries', {
       bikeId: options?.bikeId ?? null,
-      status: options?.status ?? null
+      status: options?.status ?? null,
+      fromTs: options?.fromTs ?? null,
+      toTs: options?.toTs ?? null,
+      sortBy: options?.sortBy ?? null,
+      sortDir: options?.sortDir ?? null,
+      cursor: options?.cursor ?? null,
+      limit: options?.limit ?? null
     });
   }
 
@@ -483,22 +1013,91 @@ export class TauriService {
     return this.invokeCommand<Delivery[]>('get_deliveries_for_bike', { bikeId });
   }
 
+  /**
+   * Create a new upcoming delivery; returns it with its generated ID
+   */
+  async createDelivery(request: CreateDeliveryRequest): Promise<Delivery> {
+    return this.invokeCommand<Delivery>('create_delivery', { request });
+  }
+
+  /**
+   * Move a delivery to its next status (upcoming → ongoing → completed)
+   * A customer `rating` (1-5) can only be given when completing
+   */
+  async updateDeliveryStatus(deliveryId: string, status: DeliveryStatus, rating?: number): Promise<Delivery> {
+    return this.invokeCommand<Delivery>('update_delivery_status', {
+      deliveryId,
+      status,
+      rating: rating ?? null
+    });
+  }
+
+  /**
+   * Delete a delivery
+   *
+   * Fails if issues reference the delivery, unless `cascade` is set, in
+   * which case those issues become standalone.
+   */
+  async deleteDelivery(deliveryId: string, cascade = false): Promise<void> {
+    return this.invokeCommand<void>('delete_delivery', { deliveryId, cascade });
+  }
+
+  /**
+   * Append a GPS point to a delivery's route
+   * Returns the ID of the new waypoint
+   */
+  async recordWaypoint(deliveryId: string, lat: number, lon: number): Promise<string> {
+    return this.invokeCommand<string>('record_waypoint', { deliveryId, lat, lon });
+  }
+
+  /**
+   * Get the recorded route of a delivery, oldest waypoint first
+   */
+  async getDeliveryRoute(deliveryId: string): Promise<Waypoint[]> {
+    return this.invokeCommand<Waypoint[]>('get_delivery_route', { deliveryId });
+  }
+
+  /**
+   * Export deliveries as a CSV document, oldest first
+   * `from`/`to` (RFC 3339) limit the export to deliveries created in that window;
+   * requires the "export" license feature
+   */
+  async exportDeliveriesCsv(from?: string, to?: string): Promise<string> {
+    return this.invokeCommand<string>('export_deliveries_csv', {
+      from: from ?? null,
+      to: to ?? null
+    });
+  }
+
   // ============================================
   // Issue Commands
   // ============================================
 
   /**
-   * Get issues with optional filtering
+   * Get one page of issues with optional filtering, newest first unless a sort is given
+   * Pass the previous page's `nextCursor` as `cursor` to continue (page size 1-500, default 50)
    */
   async getIssues(options?: {
     bikeId?: string;
     resolved?: boolean;
     category?: string;
-  }): Promise<Issue[]> {
-    return this.invokeCommand<Issue[]>('get_issues', {
+    fromTs?: string;
+    toTs?: string;
+    sortBy?: IssueSort;
+    sortDir?: SortDirection;
+    cursor?: string;
+    limit?: number;
+  }): Promise<Page<Issue>> {
+    return this.invokeCommand<Page<Issue>>('get_issues', {
       bikeId: options?.bikeId ?? null,
       resolved: options?.resolved ?? null,
-      category: options?.category ?? null
+      category: options?.category ?? null,
+      fromTs: options?.fromTs ?? null,
+      toTs: options?.toTs ?? null,
+      sortBy: options?.sortBy ?? null,
+      sortDir: options?.sortDir ?? null,
+      cursor: options?.cursor ?? null,
+      limit: options?.limit ?? null
     });
   }
 
@@ -509,6 +1108,16 @@ export class TauriService {
     return this.invokeCommand<Issue | null>('get_issue_by_id', { issueId });
   }
 
+  /**
+   * Get issue counts, breakdowns and resolution times, for one bike or
+   * (without `bikeId`) the whole fleet
+   */
+  async getIssueStatistics(bikeId?: string): Promise<IssueStatistics> {
+    return this.invokeCommand<IssueStatistics>('get_issue_statistics', {
+      bikeId: bikeId ?? null
+    });
+  }
+
   /**
    * Get issues for a specific bike (for force graph)
    */
@@ -516,6 +1125,34 @@ export class TauriService {
     return this.invokeCommand<Issue[]>('get_issues_for_bike', { bikeId });
   }
 
+  /**
+   * Mark an issue as resolved, with an optional resolution note
+   */
+  async resolveIssue(issueId: string, note?: string): Promise<Issue> {
+    return this.invokeCommand<Issue>('resolve_issue', { issueId, note: note ?? null });
+  }
+
+  /**
+   * Reopen a resolved issue, clearing its resolution time and note
+   */
+  async reopenIssue(issueId: string): Promise<Issue> {
+    return this.invokeCommand<Issue>('reopen_issue', { issueId });
+  }
+
+  /**
+   * Report a new issue; without a category, one is suggested from the description
+   */
+  async createIssue(request: CreateIssueRequest): Promise<Issue> {
+    return this.invokeCommand<Issue>('create_issue', { request });
+  }
+
+  /**
+   * Delete an issue
+   */
+  async deleteIssue(issueId: string): Promise<void> {
+    return this.invokeCommand<void>('delete_issue', { issueId });
+  }
+
   // ============================================
   // Force Graph Commands
   // ============================================
@@ -524,32 +1161,89 @@ export class TauriService {
    * Get force graph layout for a deliverer
    *
    * Returns pre-computed node positions from Fjädra simulation
-   * running in the Rust backend.
+   * running in the Rust backend. With `clusterMode: 'by_issue_category'`
+   * issues are grouped under one node per category.
    */
-  async getForceGraphLayout(bikeId: string): Promise<ForceGraphData> {
-    return this.invokeCommand<ForceGraphData>('get_force_graph_layout', { bikeId });
+  async getForceGraphLayout(
+    bikeId: string,
+    config?: Partial<ForceGraphConfig>,
+    clusterMode?: ForceGraphClusterMode
+  ): Promise<ForceGraphData> {
+    return this.invokeCommand<ForceGraphData>('get_force_graph_layout', {
+      bikeId,
+      config: config ?? null,
+      clusterMode: clusterMode ?? null
+    });
   }
 
   /**
    * Update a node's position and get recomputed layout
    *
    * Called when user drags a node in the UI.
-   * Backend recomputes positions with the moved node fixed.
+   * Backend pins the moved node (persisted across layouts) and recomputes
+   * positions around it. Pass the `clusterMode` the graph is shown in.
    */
   async updateNodePosition(
     bikeId: string,
     nodeId: string,
     x: number,
-    y: number
+    y: number,
+    config?: Partial<ForceGraphConfig>,
+    clusterMode?: ForceGraphClusterMode
   ): Promise<ForceGraphData> {
     return this.invokeCommand<ForceGraphData>('update_node_position', {
       bikeId,
       nodeId,
       x,
-      y
+      y,
+      config: config ?? null,
+      clusterMode: clusterMode ?? null
     });
   }
 
+  /**
+   * Unpin every node of a deliverer's graph dragged via `updateNodePosition`
+   */
+  async clearNodePins(bikeId: string): Promise<void> {
+    return this.invokeCommand<void>('clear_node_pins', { bikeId });
+  }
+
+  /**
+   * Get only the nodes that changed since a previously fetched layout
+   *
+   * Pass 0 (or any unknown hash) to receive every node as added.
+   */
+  async getForceGraphDiff(bikeId: string, previousPositionHash: number): Promise<ForceGraphDiff> {
+    return this.invokeCommand<ForceGraphDiff>('get_force_graph_diff', {
+      bikeId,
+      previousPositionHash
+    });
+  }
+
+  /**
+   * Export a deliverer's force graph as an SVG document, scaled to fit
+   * `width` x `height`
+   */
+  async exportForceGraphSvg(bikeId: string, width: number, height: number): Promise<string> {
+    return this.invokeCommand<string>('export_force_graph_svg', { bikeId, width, height });
+  }
+
+  /**
+   * Export a deliverer's force graph as a GraphML document, for Gephi or
+   * Cytoscape
+   */
+  async exportForceGraphGraphml(bikeId: string): Promise<string> {
+    return this.invokeCommand<string>('export_force_graph_graphml', { bikeId });
+  }
+
+  /**
+   * Get the fleet-wide graph of all deliverers and the restaurants they
+   * deliver for
+   */
+  async getFleetForceGraph(): Promise<ForceGraphData> {
+    return this.invokeCommand<ForceGraphData>('get_fleet_force_graph');
+  }
+
   // ============================================
   // Utilities
   // ============================================
@@ -562,6 +1256,10 @@ export class TauriService {
     }
     return bytes;
   }
+
+  private uint8ArrayToBase64(bytes: Uint8Array): string {
+    return btoa(String.fromCharCode(...bytes));
+  }
 }
 
 // ============================================
@@ -573,9 +1271,17 @@ export class TauriService {
  */
 export interface SecureSessionInfo {
   sessionNonceBase64: string;
+  /** Holds one license seat until `close_session` */
+  sessionId: string;
   initialized: boolean;
 }
 
+/**
+ * License key for initSecureSession while no license is installed
+ * (must match TRIAL_LICENSE_KEY in license.rs)
+ */
+export const TRIAL_LICENSE_KEY = 'TRIAL';
+
 /**
  * Secure command wrapper
  * Used to bundle command type and args for encrypted invoke
@@ -590,11 +1296,14 @@ export interface SecureCommand {
 // ============================================
 
 export type DeliveryStatus = 'completed' | 'ongoing' | 'upcoming';
+export type DeliveryPriority = 'normal' | 'high' | 'urgent';
+export type DeliverySort = 'created_at' | 'completed_at' | 'status' | 'rating' | 'priority';
 
 export interface Delivery {
   id: string;
   bikeId: string;
   status: DeliveryStatus;
+  priority: DeliveryPriority;
   customerName: string;
   customerAddress: string;
   restaurantName: string;
@@ -605,12 +1314,30 @@ export interface Delivery {
   completedAt: string | null;
 }
 
+export interface Waypoint {
+  id: string;
+  deliveryId: string;
+  sequence: number; // 1-based, in recording order
+  latitude: number;
+  longitude: number;
+  recordedAt: string;
+}
+
+export interface CreateDeliveryRequest {
+  bikeId: string;
+  customerName: string;
+  customerAddress: string;
+  restaurantName: string;
+  restaurantAddress: string;
+}
+
 // ============================================
 // Issue Types (matching Rust models)
 // ============================================
 
 export type IssueReporterType = 'customer' | 'deliverer' | 'restaurant';
 export type IssueCategory = 'late' | 'damaged' | 'wrong_order' | 'rude' | 'bike_problem' | 'other';
+export type IssueSort = 'created_at' | 'category' | 'resolved';
 
 export interface Issue {
   id: string;
@@ -620,25 +1347,72 @@ export interface Issue {
   category: IssueCategory;
   description: string;
   resolved: boolean;
+  resolvedAt: string | null;
+  resolutionNote: string | null;
   createdAt: string;
 }
 
+export interface CreateIssueRequest {
+  bikeId: string;
+  /** Omit for a standalone issue on the deliverer */
+  deliveryId?: string | null;
+  reporterType: IssueReporterType;
+  /** Suggested from the description when omitted */
+  category?: IssueCategory | null;
+  description: string;
+}
+
+/**
+ * Issue health of the fleet or of one bike
+ */
+export interface IssueStatistics {
+  totalIssues: number;
+  resolvedCount: number;
+  unresolvedCount: number;
+  /** 0-100; 0 without issues */
+  resolutionRatePct: number;
+  /** Keyed by category; categories without issues are omitted */
+  byCategory: Partial<Record<IssueCategory, number>>;
+  byReporter: Partial<Record<IssueReporterType, number>>;
+  /** 0 if nothing is resolved */
+  avgResolutionDays: number;
+  /** null if every issue is resolved */
+  oldestUnresolvedDays: number | null;
+}
+
+/**
+ * One page of a paginated list
+ */
+export interface Page<T> {
+  items: T[];
+  /** Opaque; pass back as `cursor` for the next page, null on the last page */
+  nextCursor: string | null;
+  /** Items matching the filters across all pages */
+  totalCount: number;
+}
+
 // ============================================
 // Force Graph Types (matching Rust models)
 // ============================================
 
-export type ForceNodeType = 'deliverer' | 'delivery' | 'issue';
+export type ForceNodeType = 'deliverer' | 'delivery' | 'issue' | 'restaurant' | 'issueCluster';
+
+export type ForceGraphClusterMode = 'none' | 'by_issue_category';
 
 export interface ForceNodeData {
-  type: 'deliverer' | 'delivery' | 'issue';
+  type: 'deliverer' | 'delivery' | 'issue' | 'restaurant' | 'issueCluster';
   // Type-specific fields
-  name?: string;           // deliverer
+  name?: string;           // deliverer, restaurant
   status?: BikeStatus | DeliveryStatus;
+  priority?: DeliveryPriority; // delivery
   customer?: string;       // delivery
   rating?: number | null;  // delivery
-  category?: IssueCategory; // issue
+  category?: IssueCategory; // issue, issueCluster
   resolved?: boolean;      // issue
+  resolved_at?: string | null; // issue
   reporter?: IssueReporterType; // issue
+  delivery_count?: number; // restaurant
+  count?: number;          // issueCluster
 }
 
 export interface ForceNode {
@@ -664,3 +1438,28 @@ export interface ForceGraphData {
   centerY: number;
   bounds: [number, number, number, number]; // [minX, maxX, minY, maxY]
 }
+
+/**
+ * Force graph layout physics; omitted fields take the backend defaults
+ */
+export interface ForceGraphConfig {
+  repulsionStrength: number;  // default -300
+  centerStrength: number;     // 0.0-1.0, default 0.05
+  linkStrength: number;       // default 0.7
+  collisionPadding: number;   // default 5
+  delivererRadius: number;    // default 40
+  deliveryRadius: number;     // default 25
+  issueRadius: number;        // default 18
+  deliveryDistance: number;   // default 120
+  issueDistance: number;      // default 60
+}
+
+export interface ForceGraphDiff {
+  changedNodes: ForceNode[];
+  unchangedNodeIds: string[];
+  addedNodes: ForceNode[];
+  removedNodeIds: string[];
+  boundsChanged: boolean;
+  newBounds: [number, number, number, number] | null;
+  positionHash: number; // Pass as previousPositionHash on the next call
+}
@@ -0,0 +1,47 @@
+// Regenerates the "API" section of README.md from the `///` doc comments
+// on `#[wasm_bindgen(js_name = ...)]` functions in src/lib.rs.
+//
+// Usage: npm run docs
+
+import { readFileSync, writeFileSync } from 'node:fs';
+import { dirname, join } from 'node:path';
+import { fileURLToPath } from 'node:url';
+
+const root = join(dirname(fileURLToPath(import.meta.url)), '..');
+const readmePath = join(root, 'README.md');
+
+const START = '<!-- API:START -->';
+const END = '<!-- API:END -->';
+
+const lines = readFileSync(join(root, 'src', 'lib.rs'), 'utf8').split('\n');
+const sections = [];
+
+lines.forEach((line, index) => {
+  const match = line.match(/^#\[wasm_bindgen\(js_name = (\w+)\)\]/);
+  if (!match) return;
+
+  // Contiguous `///` block directly above the attribute
+  const doc = [];
+  for (let i = index - 1; i >= 0 && lines[i].startsWith('///'); i--) {
+    doc.unshift(lines[i].replace(/^\/\/\/ ?/, ''));
+  }
+
+  const body = doc
+    .map((text) => text.replace(/^# (.+)$/, '**$1**\n'))
+    .join('\n')
+    .trim();
+
+  sections.push(`### \`${match[1]}\`\n\n${body || '_Undocumented._'}`);
+});
+
+const readme = readFileSync(readmePath, 'utf8');
+const start = readme.indexOf(START);
+const end = readme.indexOf(END);
+if (start === -1 || end === -1) {
+  throw new Error(`README.md must contain ${START} and ${END}`);
+}
+
+const generated = `${START}\n<!-- Generated by \`npm run docs\`; edit the doc comments in src/lib.rs instead. -->\n\n${sections.join('\n\n')}\n\n`;
+writeFileSync(readmePath, readme.slice(0, start) + generated + readme.slice(end));
+
+console.log(`Documented ${sections.length} functions in README.md`);
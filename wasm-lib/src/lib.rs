@@ -3,7 +3,10 @@
 //! This module provides protected client-side algorithms for fleet management,
 //! including statistics calculation, data validation, and geographic computations.
 
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
 use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
 use wasm_bindgen::prelude::*;
 
 // Initialize panic hook for better error messages in development
@@ -13,6 +16,50 @@ pub fn init() {
     console_error_panic_hook::set_once();
 }
 
+/// Serializer matching what `JSON.parse` would produce on the JS side
+///
+/// The default `serde_wasm_bindgen` serializer turns `None` into `undefined`
+/// (the key vanishes under `JSON.stringify`) and maps into JS `Map`s, while
+/// the TypeScript models expect `null` and plain objects.
+const JS_SERIALIZER: serde_wasm_bindgen::Serializer = serde_wasm_bindgen::Serializer::json_compatible();
+
+/// Convert a result to a JS value using `JS_SERIALIZER`
+fn to_js_value<T: Serialize + ?Sized>(value: &T) -> Result<JsValue, serde_wasm_bindgen::Error> {
+    value.serialize(&JS_SERIALIZER)
+}
+
+// ============================================================================
+// Deterministic Test Mode
+// ============================================================================
+
+thread_local! {
+    /// Seeded RNG used instead of timestamp formulas when deterministic mode is on
+    static DETERMINISTIC_RNG: RefCell<Option<SmallRng>> = const { RefCell::new(None) };
+}
+
+/// Enable deterministic mode with the given seed.
+///
+/// While set, `simulateBikeMovement`, `transitionBikeStatus`,
+/// `calculateBikeSpeed`, `simulationTick` and `clusterBikePositions` draw
+/// their random values from a seeded RNG instead of timestamps or
+/// caller-supplied values, so a sequence of calls is fully reproducible (e.g. seed 42, 100 ticks,
+/// compare against a golden output).
+#[wasm_bindgen(js_name = setDeterministicSeed)]
+pub fn set_deterministic_seed(seed: u64) {
+    DETERMINISTIC_RNG.with(|rng| *rng.borrow_mut() = Some(SmallRng::seed_from_u64(seed)));
+}
+
+/// Disable deterministic mode, restoring timestamp-based behavior
+#[wasm_bindgen(js_name = clearDeterministicSeed)]
+pub fn clear_deterministic_seed() {
+    DETERMINISTIC_RNG.with(|rng| *rng.borrow_mut() = None);
+}
+
+/// Next value in 0.0..1.0 from the seeded RNG, or None when not in deterministic mode
+fn deterministic_random() -> Option<f64> {
+    DETERMINISTIC_RNG.with(|rng| rng.borrow_mut().as_mut().map(|r| r.gen::<f64>()))
+}
+
 // ============================================================================
 // Data Types (matching Angular models)
 // ============================================================================
@@ -134,10 +181,119 @@ pub fn calculate_fleet_statistics(bikes_js: JsValue) -> Result<JsValue, JsValue>
         fleet_center_latitude,
     };
 
-    serde_wasm_bindgen::to_value(&stats)
+    to_js_value(&stats)
         .map_err(|e| JsValue::from_str(&format!("Failed to serialize statistics: {}", e)))
 }
 
+/// Weighted geographic center of the fleet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WeightedCenterResult {
+    pub longitude: f64,
+    pub latitude: f64,
+    /// 0.0 when every weight was zero and the plain centroid was used
+    pub total_weight: f64,
+}
+
+/// `sum(weight_i * coord_i) / sum(weight_i)`, or the plain centroid if
+/// all weights are zero
+fn weighted_center(bikes: &[BikePosition], weights: &[f64]) -> Result<WeightedCenterResult, String> {
+    if bikes.is_empty() {
+        return Err("Cannot calculate the center of an empty fleet".to_string());
+    }
+    if bikes.len() != weights.len() {
+        return Err(format!(
+            "Expected one weight per bike ({}), got {}",
+            bikes.len(),
+            weights.len()
+        ));
+    }
+    if let Some(weight) = weights.iter().find(|w| !w.is_finite() || **w < 0.0) {
+        return Err(format!("Weights must be non-negative numbers, got {}", weight));
+    }
+
+    let total_weight: f64 = weights.iter().sum();
+    if total_weight == 0.0 {
+        let n = bikes.len() as f64;
+        return Ok(WeightedCenterResult {
+            longitude: bikes.iter().map(|b| b.longitude).sum::<f64>() / n,
+            latitude: bikes.iter().map(|b| b.latitude).sum::<f64>() / n,
+            total_weight,
+        });
+    }
+
+    let weighted = |coord: fn(&BikePosition) -> f64| {
+        bikes.iter().zip(weights).map(|(b, w)| w * coord(b)).sum::<f64>() / total_weight
+    };
+    Ok(WeightedCenterResult {
+        longitude: weighted(|b| b.longitude),
+        latitude: weighted(|b| b.latitude),
+        total_weight,
+    })
+}
+
+/// Calculate the fleet center weighted per bike
+///
+/// Unlike the centroid of `calculateFleetStatistics`, busier bikes pull
+/// the center toward where the activity is.
+///
+/// # Arguments
+/// * `bikes_js` - Array of bike positions
+/// * `weights_js` - Array of non-negative weights, one per bike, in the same order
+///
+/// # Returns
+/// WeightedCenterResult; with all weights zero, the unweighted centroid
+///
+/// @throws if the fleet is empty, the lengths differ or a weight is negative
+#[wasm_bindgen(js_name = calculateWeightedFleetCenter)]
+pub fn calculate_weighted_fleet_center(bikes_js: JsValue, weights_js: JsValue) -> Result<JsValue, JsValue> {
+    let bikes: Vec<BikePosition> = serde_wasm_bindgen::from_value(bikes_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse bikes: {}", e)))?;
+
+    let weights: Vec<f64> = serde_wasm_bindgen::from_value(weights_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse weights: {}", e)))?;
+
+    let center = weighted_center(&bikes, &weights).map_err(|e| JsValue::from_str(&e))?;
+
+    to_js_value(&center)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+/// Calculate the fleet center weighted by each bike's delivery count
+///
+/// Counts are normalized to shares of all deliveries, so `totalWeight`
+/// is 1.0 (or 0.0 without any deliveries, giving the plain centroid).
+///
+/// # Arguments
+/// * `bikes_js` - Array of bike positions
+/// * `delivery_counts_js` - Array of delivery counts, one per bike
+#[wasm_bindgen(js_name = calculateFleetCenterByDeliveryCount)]
+pub fn calculate_fleet_center_by_delivery_count(
+    bikes_js: JsValue,
+    delivery_counts_js: JsValue,
+) -> Result<JsValue, JsValue> {
+    let bikes: Vec<BikePosition> = serde_wasm_bindgen::from_value(bikes_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse bikes: {}", e)))?;
+
+    let delivery_counts: Vec<u32> = serde_wasm_bindgen::from_value(delivery_counts_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse delivery counts: {}", e)))?;
+
+    let center = weighted_center(&bikes, &delivery_count_weights(&delivery_counts))
+        .map_err(|e| JsValue::from_str(&e))?;
+
+    to_js_value(&center)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+/// Each count as a share of the total; all zeros if there are no deliveries
+fn delivery_count_weights(delivery_counts: &[u32]) -> Vec<f64> {
+    let total: f64 = delivery_counts.iter().map(|&c| c as f64).sum();
+    delivery_counts
+        .iter()
+        .map(|&c| if total == 0.0 { 0.0 } else { c as f64 / total })
+        .collect()
+}
+
 // ============================================================================
 // Data Validation and Transformation
 // ============================================================================
@@ -166,6 +322,7 @@ pub fn validate_bike_data(bike_js: JsValue) -> Result<JsValue, JsValue> {
     let mut errors: Vec<String> = Vec::new();
     let mut warnings: Vec<String> = Vec::new();
     let mut sanitized = bike.clone();
+    let max_bike_speed = simulation_config().max_bike_speed;
 
     // Validate ID
     if bike.id.is_empty() {
@@ -199,12 +356,12 @@ pub fn validate_bike_data(bike_js: JsValue) -> Result<JsValue, JsValue> {
     if bike.speed < 0.0 {
         errors.push("Speed cannot be negative".to_string());
         sanitized.speed = 0.0;
-    } else if bike.speed > MAX_BIKE_SPEED {
+    } else if bike.speed > max_bike_speed {
         warnings.push(format!(
             "Speed {} km/h exceeds maximum reasonable speed, clamped to {}",
-            bike.speed, MAX_BIKE_SPEED
+            bike.speed, max_bike_speed
         ));
-        sanitized.speed = MAX_BIKE_SPEED;
+        sanitized.speed = max_bike_speed;
     }
 
     // Check speed vs status consistency
@@ -222,7 +379,7 @@ pub fn validate_bike_data(bike_js: JsValue) -> Result<JsValue, JsValue> {
         sanitized_data: if is_valid { Some(sanitized) } else { None },
     };
 
-    serde_wasm_bindgen::to_value(&result)
+    to_js_value(&result)
         .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
 }
 
@@ -235,13 +392,13 @@ pub fn validate_bike_data_batch(bikes_js: JsValue) -> Result<JsValue, JsValue> {
     let results: Vec<ValidationResult> = bikes
         .into_iter()
         .map(|bike| {
-            let bike_js = serde_wasm_bindgen::to_value(&bike).unwrap();
+            let bike_js = to_js_value(&bike).unwrap();
             let result_js = validate_bike_data(bike_js).unwrap();
             serde_wasm_bindgen::from_value(result_js).unwrap()
         })
         .collect();
 
-    serde_wasm_bindgen::to_value(&results)
+    to_js_value(&results)
         .map_err(|e| JsValue::from_str(&format!("Failed to serialize results: {}", e)))
 }
 
@@ -334,7 +491,7 @@ pub fn calculate_distance(from_js: JsValue, to_js: JsValue) -> Result<JsValue, J
         bearing_degrees,
     };
 
-    serde_wasm_bindgen::to_value(&result)
+    to_js_value(&result)
         .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
 }
 
@@ -352,9 +509,9 @@ pub fn calculate_bike_distance(bike_js: JsValue, target_js: JsValue) -> Result<J
         latitude: bike.latitude,
     };
 
-    let from_js = serde_wasm_bindgen::to_value(&from)
+    let from_js = to_js_value(&from)
         .map_err(|e| JsValue::from_str(&format!("Failed to serialize from: {}", e)))?;
-    let target_js = serde_wasm_bindgen::to_value(&target)
+    let target_js = to_js_value(&target)
         .map_err(|e| JsValue::from_str(&format!("Failed to serialize target: {}", e)))?;
 
     calculate_distance(from_js, target_js)
@@ -382,10 +539,91 @@ pub fn find_nearest_bike(bikes_js: JsValue, target_js: JsValue) -> Result<JsValu
         })
         .unwrap();
 
-    serde_wasm_bindgen::to_value(&nearest)
+    to_js_value(&nearest)
         .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
 }
 
+/// Average bike speed used for arrival estimates (km/h)
+const AVERAGE_DISPATCH_SPEED_KMH: f64 = 25.0;
+
+/// Nearby bike with distance, direction and arrival estimate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NearestBikeResult {
+    pub bike: BikePosition,
+    pub distance_km: f64,
+    pub bearing_degrees: f64,
+    pub estimated_arrival_minutes: f64,
+}
+
+/// Find the N nearest bikes to a given coordinate, closest first
+///
+/// # Arguments
+/// * `bikes_js` - Array of BikePosition objects
+/// * `target_js` - Target coordinate
+/// * `n` - Maximum number of bikes to return (must be at least 1)
+/// * `status_filter` - "idle", "delivering", "returning", or undefined for all
+///
+/// # Returns
+/// Array of NearestBikeResult; fewer than N if not enough bikes match the filter
+#[wasm_bindgen(js_name = findNearestNBikes)]
+pub fn find_nearest_n_bikes(
+    bikes_js: JsValue,
+    target_js: JsValue,
+    n: u32,
+    status_filter: Option<String>,
+) -> Result<JsValue, JsValue> {
+    let bikes: Vec<BikePosition> = serde_wasm_bindgen::from_value(bikes_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse bikes: {}", e)))?;
+
+    let target: Coordinate = serde_wasm_bindgen::from_value(target_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse target: {}", e)))?;
+
+    if n == 0 {
+        return Err(JsValue::from_str("n must be at least 1"));
+    }
+
+    let status = match status_filter.as_deref().map(str::to_lowercase).as_deref() {
+        None => None,
+        Some("idle") => Some(BikeStatus::Idle),
+        Some("delivering") => Some(BikeStatus::Delivering),
+        Some("returning") => Some(BikeStatus::Returning),
+        Some(other) => return Err(JsValue::from_str(&format!("Unknown status: {}", other))),
+    };
+
+    let results = nearest_n_bikes(bikes, &target, n as usize, status.as_ref());
+
+    to_js_value(&results)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+/// Core of `findNearestNBikes`, separated from JS conversion so it can be tested natively
+fn nearest_n_bikes(
+    bikes: Vec<BikePosition>,
+    target: &Coordinate,
+    n: usize,
+    status: Option<&BikeStatus>,
+) -> Vec<NearestBikeResult> {
+    let mut results: Vec<NearestBikeResult> = bikes
+        .into_iter()
+        .filter(|bike| status.map_or(true, |s| &bike.status == s))
+        .map(|bike| {
+            let distance_km = haversine_distance(bike.latitude, bike.longitude, target.latitude, target.longitude);
+            let bearing_degrees = calculate_bearing(bike.latitude, bike.longitude, target.latitude, target.longitude);
+            NearestBikeResult {
+                bike,
+                distance_km,
+                bearing_degrees,
+                estimated_arrival_minutes: distance_km / AVERAGE_DISPATCH_SPEED_KMH * 60.0,
+            }
+        })
+        .collect();
+
+    results.sort_by(|a, b| a.distance_km.total_cmp(&b.distance_km));
+    results.truncate(n);
+    results
+}
+
 /// Find all bikes within a given radius (km) of a coordinate
 #[wasm_bindgen(js_name = findBikesInRadius)]
 pub fn find_bikes_in_radius(bikes_js: JsValue, center_js: JsValue, radius_km: f64) -> Result<JsValue, JsValue> {
@@ -406,187 +644,1919 @@ pub fn find_bikes_in_radius(bikes_js: JsValue, center_js: JsValue, radius_km: f6
         })
         .collect();
 
-    serde_wasm_bindgen::to_value(&bikes_in_radius)
+    to_js_value(&bikes_in_radius)
         .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
 }
 
 // ============================================================================
-// Bike Movement Simulation
+// Dispatch
 // ============================================================================
 
-/// Configuration for Amsterdam operational bounds
-const AMSTERDAM_OPERATIONAL_BOUNDS: (f64, f64, f64, f64) = (
-    4.85,  // min longitude
-    4.95,  // max longitude
-    52.34, // min latitude
-    52.40, // max latitude
-);
-
-/// Movement speed in degrees per millisecond for different states
-/// Approximately: idle ~0.0002°, active ~0.001° per 5 seconds
-const MOVEMENT_IDLE: f64 = 0.0002;
-const MOVEMENT_ACTIVE: f64 = 0.001;
+/// Idle bike sent to a delivery pickup point
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BikePickupPair {
+    pub bike_id: String,
+    pub pickup_coordinate: Coordinate,
+    pub distance_km: f64,
+}
 
-/// Result of bike movement simulation
+/// Bike-to-pickup assignment for a set of pending deliveries
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct SimulationResult {
-    pub bikes: Vec<BikePosition>,
-    pub movements_applied: u32,
-    pub bounds_corrections: u32,
+pub struct DispatchAssignment {
+    /// In the order of the pickups
+    pub assignments: Vec<BikePickupPair>,
+    pub total_distance_km: f64,
+    /// Pickups left over when there are fewer idle bikes than pickups
+    pub unassigned_pickups: Vec<Coordinate>,
 }
 
-/// Simulate bike movement for one tick.
+/// Greedy assignment: repeatedly pair the closest idle bike and pickup
+/// that are both still free
 ///
-/// This function applies realistic movement physics to all bikes:
-/// - Idle bikes drift slightly (GPS jitter simulation)
-/// - Active bikes (delivering/returning) move purposefully
-/// - All positions are clamped to Amsterdam operational bounds
+/// Not always the minimum total distance (that needs the Hungarian
+/// algorithm, O(n³)), but close in practice and cheap enough to rerun on
+/// every simulation tick. The pickups left over are the ones furthest
+/// from any idle bike.
+fn greedy_dispatch(bikes: &[BikePosition], pickups: &[Coordinate]) -> DispatchAssignment {
+    let idle: Vec<&BikePosition> = bikes.iter().filter(|b| b.status == BikeStatus::Idle).collect();
+
+    let mut pairs: Vec<(f64, usize, usize)> = idle
+        .iter()
+        .enumerate()
+        .flat_map(|(b, bike)| {
+            pickups.iter().enumerate().map(move |(p, pickup)| {
+                let distance = haversine_distance(bike.latitude, bike.longitude, pickup.latitude, pickup.longitude);
+                (distance, b, p)
+            })
+        })
+        .collect();
+    pairs.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut bike_taken = vec![false; idle.len()];
+    let mut bike_for_pickup: Vec<Option<(usize, f64)>> = vec![None; pickups.len()];
+    for (distance, b, p) in pairs {
+        if !bike_taken[b] && bike_for_pickup[p].is_none() {
+            bike_taken[b] = true;
+            bike_for_pickup[p] = Some((b, distance));
+        }
+    }
+
+    let mut assignments = Vec::new();
+    let mut unassigned_pickups = Vec::new();
+    for (pickup, assigned) in pickups.iter().zip(bike_for_pickup) {
+        match assigned {
+            Some((b, distance_km)) => assignments.push(BikePickupPair {
+                bike_id: idle[b].id.clone(),
+                pickup_coordinate: pickup.clone(),
+                distance_km,
+            }),
+            None => unassigned_pickups.push(pickup.clone()),
+        }
+    }
+
+    DispatchAssignment {
+        total_distance_km: assignments.iter().map(|a| a.distance_km).sum(),
+        assignments,
+        unassigned_pickups,
+    }
+}
+
+/// Assign idle bikes to pending delivery pickup points
+///
+/// Each pickup gets at most one bike and each idle bike at most one
+/// pickup; delivering and returning bikes are never dispatched.
 ///
 /// # Arguments
-/// * `bikes_js` - Array of current bike positions
-/// * `seed` - Random seed for deterministic movement (use timestamp)
+/// * `bikes_js` - Array of bike positions
+/// * `pickups_js` - Array of pickup coordinates
 ///
 /// # Returns
-/// SimulationResult with updated bike positions
-#[wasm_bindgen(js_name = simulateBikeMovement)]
-pub fn simulate_bike_movement(bikes_js: JsValue, seed: f64) -> Result<JsValue, JsValue> {
+/// DispatchAssignment, with pickups that got no bike in `unassignedPickups`
+#[wasm_bindgen(js_name = computeOptimalDispatch)]
+pub fn compute_optimal_dispatch(bikes_js: JsValue, pickups_js: JsValue) -> Result<JsValue, JsValue> {
     let bikes: Vec<BikePosition> = serde_wasm_bindgen::from_value(bikes_js)
         .map_err(|e| JsValue::from_str(&format!("Failed to parse bikes: {}", e)))?;
 
-    let mut bounds_corrections: u32 = 0;
-    let movements_applied = bikes.len() as u32;
+    let pickups: Vec<Coordinate> = serde_wasm_bindgen::from_value(pickups_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse pickups: {}", e)))?;
 
-    // Use seed to create pseudo-random but deterministic movement
-    let updated_bikes: Vec<BikePosition> = bikes
-        .into_iter()
-        .enumerate()
-        .map(|(idx, bike)| {
-            // Create per-bike variation using index and seed
-            let variation = ((seed + idx as f64 * 1000.0) % 1000.0) / 1000.0;
-            let angle = variation * std::f64::consts::PI * 2.0;
+    to_js_value(&greedy_dispatch(&bikes, &pickups))
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
 
-            // Movement magnitude based on status
-            let movement = match bike.status {
-                BikeStatus::Idle => MOVEMENT_IDLE,
-                BikeStatus::Delivering | BikeStatus::Returning => MOVEMENT_ACTIVE,
-            };
+// ============================================================================
+// Service Zones
+// ============================================================================
 
-            let mut new_lng = bike.longitude + angle.cos() * movement;
-            let mut new_lat = bike.latitude + angle.sin() * movement;
+/// Zone a bike falls in: the hub (depot) closest to it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BikeZone {
+    pub bike_id: String,
+    /// Index into the hubs array
+    pub hub_index: u32,
+    pub hub_distance_km: f64,
+}
 
-            // Clamp to Amsterdam operational bounds
-            let (min_lng, max_lng, min_lat, max_lat) = AMSTERDAM_OPERATIONAL_BOUNDS;
+/// Zone of every bike, in the order of the bikes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ZoneAssignment {
+    pub assignments: Vec<BikeZone>,
+}
 
-            if new_lng < min_lng || new_lng > max_lng || new_lat < min_lat || new_lat > max_lat {
-                bounds_corrections += 1;
-            }
+/// How evenly bikes are spread over the hubs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ZoneLoad {
+    /// Bikes per hub, indexed like the hubs
+    pub bike_counts: Vec<u32>,
+    pub mean_load: f64,
+    /// Population variance of `bike_counts`; 0 when perfectly balanced
+    pub load_variance: f64,
+}
 
-            new_lng = new_lng.clamp(min_lng, max_lng);
-            new_lat = new_lat.clamp(min_lat, max_lat);
+/// Nearest-hub assignment, i.e. the Voronoi cell of each bike
+///
+/// With a handful of hubs, comparing every bike against every hub is
+/// cheaper than building the diagram. A bike equally close to several
+/// hubs goes to the one with the lowest index.
+fn assign_zones(bikes: &[BikePosition], hubs: &[Coordinate]) -> Result<ZoneAssignment, String> {
+    if hubs.is_empty() {
+        return Err("At least one hub is required".to_string());
+    }
 
-            BikePosition {
-                id: bike.id,
-                name: bike.name,
-                longitude: new_lng,
-                latitude: new_lat,
-                status: bike.status,
-                speed: bike.speed,
+    let assignments = bikes
+        .iter()
+        .map(|bike| {
+            let mut nearest = (0, f64::INFINITY);
+            for (index, hub) in hubs.iter().enumerate() {
+                let distance = haversine_distance(bike.latitude, bike.longitude, hub.latitude, hub.longitude);
+                // Strictly closer, so ties stay with the lower index
+                if distance < nearest.1 {
+                    nearest = (index, distance);
+                }
+            }
+            BikeZone {
+                bike_id: bike.id.clone(),
+                hub_index: nearest.0 as u32,
+                hub_distance_km: nearest.1,
             }
         })
         .collect();
 
-    let result = SimulationResult {
-        bikes: updated_bikes,
-        movements_applied,
-        bounds_corrections,
-    };
-
-    serde_wasm_bindgen::to_value(&result)
-        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+    Ok(ZoneAssignment { assignments })
 }
 
-// ============================================================================
-// Status Transition Logic
-// ============================================================================
+/// Bikes per hub and the variance of those counts
+fn zone_load(assignments: &[BikeZone], hub_count: u32) -> Result<ZoneLoad, String> {
+    if hub_count == 0 {
+        return Err("At least one hub is required".to_string());
+    }
 
-/// Status transition probabilities
-/// Format: (probability_to_delivering, probability_to_returning, probability_to_idle)
-fn get_transition_probabilities(current: &BikeStatus) -> (f64, f64, f64) {
-    match current {
-        // Delivering bikes usually stay delivering or go idle
-        BikeStatus::Delivering => (0.70, 0.15, 0.15),
-        // Returning bikes usually stay returning or become idle
-        BikeStatus::Returning => (0.10, 0.65, 0.25),
-        // Idle bikes usually stay idle or start delivering
-        BikeStatus::Idle => (0.30, 0.10, 0.60),
+    let mut bike_counts = vec![0u32; hub_count as usize];
+    for zone in assignments {
+        let count = bike_counts.get_mut(zone.hub_index as usize).ok_or_else(|| {
+            format!(
+                "Bike {} is assigned to hub {}, but there are only {} hubs",
+                zone.bike_id, zone.hub_index, hub_count
+            )
+        })?;
+        *count += 1;
     }
-}
 
-/// Status transition result
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct StatusTransitionResult {
-    pub new_status: BikeStatus,
-    pub transition_occurred: bool,
-    pub probability_used: f64,
+    let mean_load = assignments.len() as f64 / hub_count as f64;
+    let load_variance = bike_counts
+        .iter()
+        .map(|&count| (count as f64 - mean_load).powi(2))
+        .sum::<f64>()
+        / hub_count as f64;
+
+    Ok(ZoneLoad {
+        bike_counts,
+        mean_load,
+        load_variance,
+    })
 }
 
-/// Determine next status based on current state and transition probabilities.
-///
-/// Uses a Markov chain model for realistic status transitions:
-/// - Delivering bikes tend to stay delivering (70%) or go idle (15%) or returning (15%)
-/// - Returning bikes tend to stay returning (65%) or go idle (25%)
-/// - Idle bikes tend to stay idle (60%) or start delivering (30%)
+/// Assign each bike to the service zone of its nearest hub
 ///
 /// # Arguments
-/// * `current_status` - Current bike status string ("delivering", "returning", "idle")
-/// * `random_value` - Random value between 0.0 and 1.0 (use Math.random())
+/// * `bikes_js` - Array of bike positions
+/// * `hubs_js` - Array of hub (depot) coordinates
 ///
 /// # Returns
-/// StatusTransitionResult with new status and whether transition occurred
-#[wasm_bindgen(js_name = transitionBikeStatus)]
-pub fn transition_bike_status(current_status: &str, random_value: f64) -> Result<JsValue, JsValue> {
-    let current = match current_status.to_lowercase().as_str() {
-        "delivering" => BikeStatus::Delivering,
-        "returning" => BikeStatus::Returning,
-        "idle" => BikeStatus::Idle,
-        _ => return Err(JsValue::from_str(&format!("Unknown status: {}", current_status))),
-    };
-
-    let (p_delivering, p_returning, _p_idle) = get_transition_probabilities(&current);
-    let clamped_random = random_value.clamp(0.0, 1.0);
-
-    let new_status = if clamped_random < p_delivering {
-        BikeStatus::Delivering
-    } else if clamped_random < p_delivering + p_returning {
-        BikeStatus::Returning
-    } else {
-        BikeStatus::Idle
-    };
+/// ZoneAssignment; a bike equidistant from two hubs goes to the lower index
+///
+/// @throws if there are no hubs
+#[wasm_bindgen(js_name = assignBikesToZones)]
+pub fn assign_bikes_to_zones(bikes_js: JsValue, hubs_js: JsValue) -> Result<JsValue, JsValue> {
+    let bikes: Vec<BikePosition> = serde_wasm_bindgen::from_value(bikes_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse bikes: {}", e)))?;
 
-    let transition_occurred = new_status != current;
+    let hubs: Vec<Coordinate> = serde_wasm_bindgen::from_value(hubs_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse hubs: {}", e)))?;
 
-    let result = StatusTransitionResult {
-        new_status,
-        transition_occurred,
-        probability_used: clamped_random,
-    };
+    let zones = assign_zones(&bikes, &hubs).map_err(|e| JsValue::from_str(&e))?;
 
-    serde_wasm_bindgen::to_value(&result)
+    to_js_value(&zones)
         .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
 }
 
-/// Batch transition statuses for multiple bikes
+/// Count the bikes in each zone and how unevenly they are spread
 ///
 /// # Arguments
-/// * `statuses` - Array of current status strings
-/// * `random_values` - Array of random values (same length as statuses)
+/// * `assignments_js` - The `assignments` of a ZoneAssignment
+/// * `hub_count` - Number of hubs the bikes were assigned to
 ///
 /// # Returns
-/// Array of new status strings
-#[wasm_bindgen(js_name = transitionBikeStatusBatch)]
-pub fn transition_bike_status_batch(statuses_js: JsValue, random_values_js: JsValue) -> Result<JsValue, JsValue> {
+/// ZoneLoad with per-hub counts, their mean and variance
+///
+/// @throws if `hub_count` is 0 or an assignment points past the last hub
+#[wasm_bindgen(js_name = calculateZoneLoad)]
+pub fn calculate_zone_load(assignments_js: JsValue, hub_count: u32) -> Result<JsValue, JsValue> {
+    let assignments: Vec<BikeZone> = serde_wasm_bindgen::from_value(assignments_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse assignments: {}", e)))?;
+
+    let load = zone_load(&assignments, hub_count).map_err(|e| JsValue::from_str(&e))?;
+
+    to_js_value(&load)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+// ============================================================================
+// Geographic Calculations (Vincenty Inverse Formula)
+// ============================================================================
+
+/// WGS-84 semi-major axis in meters
+const WGS84_A: f64 = 6378137.0;
+
+/// WGS-84 flattening
+const WGS84_F: f64 = 1.0 / 298.257223563;
+
+/// Iterations after which Vincenty gives up (near-antipodal points)
+const VINCENTY_MAX_ITERATIONS: u32 = 100;
+
+/// Change in lambda below which the iteration has converged (~0.06 mm)
+const VINCENTY_TOLERANCE: f64 = 1e-12;
+
+/// Ellipsoidal distance result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VincentyResult {
+    pub distance_m: f64,
+    /// Forward azimuth at the start, 0-360
+    pub initial_bearing_deg: f64,
+    /// Forward azimuth on arrival, 0-360
+    pub final_bearing_deg: f64,
+    /// False if the haversine fallback was used
+    pub converged: bool,
+}
+
+/// Vincenty's A and B series coefficients for a geodesic with `cos²(α)`
+fn vincenty_series(cos_sq_alpha: f64) -> (f64, f64) {
+    let b = (1.0 - WGS84_F) * WGS84_A;
+    let u_sq = cos_sq_alpha * (WGS84_A.powi(2) - b.powi(2)) / b.powi(2);
+    let big_a = 1.0 + u_sq / 16384.0 * (4096.0 + u_sq * (-768.0 + u_sq * (320.0 - 175.0 * u_sq)));
+    let big_b = u_sq / 1024.0 * (256.0 + u_sq * (-128.0 + u_sq * (74.0 - 47.0 * u_sq)));
+    (big_a, big_b)
+}
+
+/// Vincenty's Δσ correction to the angular distance
+fn vincenty_delta_sigma(big_b: f64, sin_sigma: f64, cos_sigma: f64, cos_2sigma_m: f64) -> f64 {
+    big_b
+        * sin_sigma
+        * (cos_2sigma_m
+            + big_b / 4.0
+                * (cos_sigma * (-1.0 + 2.0 * cos_2sigma_m.powi(2))
+                    - big_b / 6.0
+                        * cos_2sigma_m
+                        * (-3.0 + 4.0 * sin_sigma.powi(2))
+                        * (-3.0 + 4.0 * cos_2sigma_m.powi(2))))
+}
+
+/// Vincenty direct formula: the point `distance_m` from `from` along the
+/// initial bearing `bearing_deg`, on the WGS-84 ellipsoid
+///
+/// Unlike the inverse formula this converges for any distance below
+/// half the circumference.
+fn vincenty_direct(from: &Coordinate, bearing_deg: f64, distance_m: f64) -> Coordinate {
+    let b = (1.0 - WGS84_F) * WGS84_A;
+    let (sin_alpha1, cos_alpha1) = deg_to_rad(bearing_deg).sin_cos();
+    let tan_u1 = (1.0 - WGS84_F) * deg_to_rad(from.latitude).tan();
+    let cos_u1 = 1.0 / (1.0 + tan_u1 * tan_u1).sqrt();
+    let sin_u1 = tan_u1 * cos_u1;
+
+    // Angular distance on the sphere from the equator to the start point
+    let sigma1 = tan_u1.atan2(cos_alpha1);
+    let sin_alpha = cos_u1 * sin_alpha1;
+    let cos_sq_alpha = 1.0 - sin_alpha * sin_alpha;
+    let (big_a, big_b) = vincenty_series(cos_sq_alpha);
+
+    let mut sigma = distance_m / (b * big_a);
+    let (mut sin_sigma, mut cos_sigma, mut cos_2sigma_m);
+    let mut iterations = 0;
+    loop {
+        cos_2sigma_m = (2.0 * sigma1 + sigma).cos();
+        (sin_sigma, cos_sigma) = sigma.sin_cos();
+        let previous = sigma;
+        sigma = distance_m / (b * big_a)
+            + vincenty_delta_sigma(big_b, sin_sigma, cos_sigma, cos_2sigma_m);
+        iterations += 1;
+        if (sigma - previous).abs() < VINCENTY_TOLERANCE || iterations == VINCENTY_MAX_ITERATIONS {
+            break;
+        }
+    }
+    (sin_sigma, cos_sigma) = sigma.sin_cos();
+    cos_2sigma_m = (2.0 * sigma1 + sigma).cos();
+
+    let x = sin_u1 * sin_sigma - cos_u1 * cos_sigma * cos_alpha1;
+    let latitude = (sin_u1 * cos_sigma + cos_u1 * sin_sigma * cos_alpha1)
+        .atan2((1.0 - WGS84_F) * (sin_alpha * sin_alpha + x * x).sqrt());
+    let lambda = (sin_sigma * sin_alpha1).atan2(cos_u1 * cos_sigma - sin_u1 * sin_sigma * cos_alpha1);
+    let c = WGS84_F / 16.0 * cos_sq_alpha * (4.0 + WGS84_F * (4.0 - 3.0 * cos_sq_alpha));
+    let l = lambda
+        - (1.0 - c)
+            * WGS84_F
+            * sin_alpha
+            * (sigma
+                + c * sin_sigma * (cos_2sigma_m + c * cos_sigma * (-1.0 + 2.0 * cos_2sigma_m.powi(2))));
+
+    Coordinate {
+        // Back into -180..180
+        longitude: (from.longitude + rad_to_deg(l) + 540.0) % 360.0 - 180.0,
+        latitude: rad_to_deg(latitude),
+    }
+}
+
+/// Vincenty inverse formula on the WGS-84 ellipsoid
+///
+/// Accurate to well under a millimeter, but the iteration can fail for
+/// nearly antipodal points; those fall back to haversine (spherical)
+/// distance and bearings with `converged = false`.
+fn vincenty_inverse(from: &Coordinate, to: &Coordinate) -> VincentyResult {
+    let b = (1.0 - WGS84_F) * WGS84_A;
+    let l = deg_to_rad(to.longitude - from.longitude);
+    let u1 = ((1.0 - WGS84_F) * deg_to_rad(from.latitude).tan()).atan();
+    let u2 = ((1.0 - WGS84_F) * deg_to_rad(to.latitude).tan()).atan();
+    let (sin_u1, cos_u1) = u1.sin_cos();
+    let (sin_u2, cos_u2) = u2.sin_cos();
+
+    let mut lambda = l;
+    for _ in 0..VINCENTY_MAX_ITERATIONS {
+        let (sin_lambda, cos_lambda) = lambda.sin_cos();
+        let sin_sigma = ((cos_u2 * sin_lambda).powi(2)
+            + (cos_u1 * sin_u2 - sin_u1 * cos_u2 * cos_lambda).powi(2))
+        .sqrt();
+        if sin_sigma == 0.0 {
+            // Coincident points
+            return VincentyResult {
+                distance_m: 0.0,
+                initial_bearing_deg: 0.0,
+                final_bearing_deg: 0.0,
+                converged: true,
+            };
+        }
+        let cos_sigma = sin_u1 * sin_u2 + cos_u1 * cos_u2 * cos_lambda;
+        let sigma = sin_sigma.atan2(cos_sigma);
+        let sin_alpha = cos_u1 * cos_u2 * sin_lambda / sin_sigma;
+        let cos_sq_alpha = 1.0 - sin_alpha * sin_alpha;
+        // Both points on the equator: cos_sq_alpha is 0
+        let cos_2sigma_m = if cos_sq_alpha == 0.0 {
+            0.0
+        } else {
+            cos_sigma - 2.0 * sin_u1 * sin_u2 / cos_sq_alpha
+        };
+        let c = WGS84_F / 16.0 * cos_sq_alpha * (4.0 + WGS84_F * (4.0 - 3.0 * cos_sq_alpha));
+
+        let previous = lambda;
+        lambda = l
+            + (1.0 - c)
+                * WGS84_F
+                * sin_alpha
+                * (sigma
+                    + c * sin_sigma
+                        * (cos_2sigma_m + c * cos_sigma * (-1.0 + 2.0 * cos_2sigma_m.powi(2))));
+
+        if (lambda - previous).abs() < VINCENTY_TOLERANCE {
+            let (big_a, big_b) = vincenty_series(cos_sq_alpha);
+            let delta_sigma = vincenty_delta_sigma(big_b, sin_sigma, cos_sigma, cos_2sigma_m);
+
+            let (sin_lambda, cos_lambda) = lambda.sin_cos();
+            let initial = (cos_u2 * sin_lambda).atan2(cos_u1 * sin_u2 - sin_u1 * cos_u2 * cos_lambda);
+            let last = (cos_u1 * sin_lambda).atan2(-sin_u1 * cos_u2 + cos_u1 * sin_u2 * cos_lambda);
+
+            return VincentyResult {
+                distance_m: b * big_a * (sigma - delta_sigma),
+                initial_bearing_deg: (rad_to_deg(initial) + 360.0) % 360.0,
+                final_bearing_deg: (rad_to_deg(last) + 360.0) % 360.0,
+                converged: true,
+            };
+        }
+    }
+
+    VincentyResult {
+        distance_m: haversine_distance(from.latitude, from.longitude, to.latitude, to.longitude)
+            * 1000.0,
+        initial_bearing_deg: calculate_bearing(from.latitude, from.longitude, to.latitude, to.longitude),
+        final_bearing_deg: (calculate_bearing(to.latitude, to.longitude, from.latitude, from.longitude)
+            + 180.0)
+            % 360.0,
+        converged: false,
+    }
+}
+
+/// Ellipsoidal distance between two coordinates (Vincenty inverse formula).
+///
+/// Sub-millimeter accuracy on the WGS-84 ellipsoid, for route costing
+/// where the up to 0.5% error of `calculateDistance` matters. Nearly
+/// antipodal points, where the formula does not converge within 100
+/// iterations, fall back to haversine with `converged` set to false.
+///
+/// # Arguments
+///
+/// * `from_js` - Starting coordinate
+/// * `to_js` - Ending coordinate
+///
+/// # Returns
+///
+/// VincentyResult with distance in meters and initial and final bearings
+#[wasm_bindgen(js_name = vincentyDistance)]
+pub fn vincenty_distance(from_js: JsValue, to_js: JsValue) -> Result<JsValue, JsValue> {
+    let from: Coordinate = serde_wasm_bindgen::from_value(from_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse 'from' coordinate: {}", e)))?;
+
+    let to: Coordinate = serde_wasm_bindgen::from_value(to_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse 'to' coordinate: {}", e)))?;
+
+    to_js_value(&vincenty_inverse(&from, &to))
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+/// Ellipsoidal distances for many coordinate pairs at once
+///
+/// # Arguments
+///
+/// * `froms_js` - Array of starting coordinates
+/// * `tos_js` - Array of ending coordinates (same length as froms)
+///
+/// # Returns
+///
+/// Array of VincentyResult, one per pair
+#[wasm_bindgen(js_name = vincentyDistanceBatch)]
+pub fn vincenty_distance_batch(froms_js: JsValue, tos_js: JsValue) -> Result<JsValue, JsValue> {
+    let froms: Vec<Coordinate> = serde_wasm_bindgen::from_value(froms_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse 'froms' coordinates: {}", e)))?;
+
+    let tos: Vec<Coordinate> = serde_wasm_bindgen::from_value(tos_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse 'tos' coordinates: {}", e)))?;
+
+    if froms.len() != tos.len() {
+        return Err(JsValue::from_str("froms and tos must have same length"));
+    }
+
+    let results: Vec<VincentyResult> = froms
+        .iter()
+        .zip(tos.iter())
+        .map(|(from, to)| vincenty_inverse(from, to))
+        .collect();
+
+    to_js_value(&results)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize results: {}", e)))
+}
+
+// ============================================================================
+// Isochrones
+// ============================================================================
+
+/// Fewest directions that still make a polygon
+const MIN_ISOCHRONE_RESOLUTION: u32 = 3;
+
+/// Area a bike can reach in a given time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IsochroneResult {
+    /// Closed ring: the last point repeats the first
+    pub polygon: Vec<Coordinate>,
+    pub reach_km: f64,
+    pub reachable_area_km2: f64,
+}
+
+/// Area of a polygon in km², by the shoelace formula on radians
+///
+/// Scaled by the earth's radius and the cosine of the mean latitude,
+/// which is accurate for city-sized polygons. Works for open and closed
+/// rings alike (a repeated first point adds nothing).
+fn polygon_area_km2(polygon: &[Coordinate]) -> f64 {
+    if polygon.len() < 3 {
+        return 0.0;
+    }
+
+    let twice_area_rad2: f64 = polygon
+        .iter()
+        .zip(polygon.iter().cycle().skip(1))
+        .map(|(a, b)| {
+            deg_to_rad(a.longitude) * deg_to_rad(b.latitude)
+                - deg_to_rad(b.longitude) * deg_to_rad(a.latitude)
+        })
+        .sum();
+    let mean_latitude = polygon.iter().map(|p| p.latitude).sum::<f64>() / polygon.len() as f64;
+
+    twice_area_rad2.abs() / 2.0 * EARTH_RADIUS_KM.powi(2) * deg_to_rad(mean_latitude).cos()
+}
+
+/// Points `speed * minutes` away from the bike in `resolution` evenly
+/// spaced directions, starting due north
+fn isochrone(bike: &BikePosition, minutes: f64, resolution: u32) -> Result<IsochroneResult, String> {
+    if resolution < MIN_ISOCHRONE_RESOLUTION {
+        return Err(format!(
+            "Resolution must be at least {}, got {}",
+            MIN_ISOCHRONE_RESOLUTION, resolution
+        ));
+    }
+    if !minutes.is_finite() || minutes < 0.0 {
+        return Err(format!("Minutes must be a non-negative number, got {}", minutes));
+    }
+
+    let start = Coordinate { longitude: bike.longitude, latitude: bike.latitude };
+    let reach_km = bike.speed.max(0.0) * minutes / 60.0;
+
+    let mut polygon: Vec<Coordinate> = (0..resolution)
+        .map(|i| {
+            if reach_km == 0.0 {
+                // Stationary: every vertex on the bike, zero area
+                return start.clone();
+            }
+            let bearing_deg = 360.0 * i as f64 / resolution as f64;
+            vincenty_direct(&start, bearing_deg, reach_km * 1000.0)
+        })
+        .collect();
+    polygon.push(polygon[0].clone());
+
+    Ok(IsochroneResult {
+        reachable_area_km2: polygon_area_km2(&polygon),
+        polygon,
+        reach_km,
+    })
+}
+
+/// Approximate area a bike can reach within `minutes` at its current speed
+///
+/// Straight-line reach in every direction (no street network), so it is
+/// an upper bound for the map overlay rather than a routing result.
+///
+/// # Arguments
+/// * `bike_js` - Bike position; its `speed` (km/h) sets the reach
+/// * `minutes` - Travel time, at least 0
+/// * `resolution` - Number of directions sampled, at least 3
+///
+/// # Returns
+/// IsochroneResult with a closed polygon; a stationary bike gives a
+/// polygon collapsed onto its position and zero area
+#[wasm_bindgen(js_name = calculateIsochrone)]
+pub fn calculate_isochrone(bike_js: JsValue, minutes: f64, resolution: u32) -> Result<JsValue, JsValue> {
+    let bike: BikePosition = serde_wasm_bindgen::from_value(bike_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse bike: {}", e)))?;
+
+    let result = isochrone(&bike, minutes, resolution).map_err(|e| JsValue::from_str(&e))?;
+
+    to_js_value(&result)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+// ============================================================================
+// Encoded Polylines
+// ============================================================================
+
+/// Coordinates are stored as integers of 1e-5 degrees
+const POLYLINE_PRECISION: f64 = 1e5;
+
+/// Append one value in Google's encoding: zigzag, then 5-bit chunks
+/// (least significant first) offset into printable ASCII
+fn encode_polyline_value(value: i64, out: &mut String) {
+    let mut zigzag = if value < 0 { !(value << 1) } else { value << 1 } as u64;
+    while zigzag >= 0x20 {
+        out.push((((zigzag & 0x1f) | 0x20) as u8 + 63) as char);
+        zigzag >>= 5;
+    }
+    out.push((zigzag as u8 + 63) as char);
+}
+
+/// Encode coordinates as a Google Encoded Polyline (precision 5)
+fn encode_polyline_coords(coords: &[Coordinate]) -> String {
+    let mut encoded = String::new();
+    let (mut prev_lat, mut prev_lon) = (0i64, 0i64);
+
+    for coord in coords {
+        let lat = (coord.latitude * POLYLINE_PRECISION).round() as i64;
+        let lon = (coord.longitude * POLYLINE_PRECISION).round() as i64;
+        encode_polyline_value(lat - prev_lat, &mut encoded);
+        encode_polyline_value(lon - prev_lon, &mut encoded);
+        (prev_lat, prev_lon) = (lat, lon);
+    }
+
+    encoded
+}
+
+/// Read one value written by `encode_polyline_value` from (position, byte) pairs
+fn decode_polyline_value(bytes: &mut impl Iterator<Item = (usize, u8)>) -> Result<i64, String> {
+    let mut result: u64 = 0;
+    let mut shift = 0;
+    loop {
+        let (index, byte) = bytes.next().ok_or("Polyline ends in the middle of a value")?;
+        if !(63..=126).contains(&byte) {
+            return Err(format!("Invalid polyline character at position {}", index));
+        }
+        if shift > 60 {
+            return Err(format!("Polyline value too long at position {}", index));
+        }
+        let chunk = (byte - 63) as u64;
+        result |= (chunk & 0x1f) << shift;
+        shift += 5;
+        if chunk < 0x20 {
+            break;
+        }
+    }
+    let value = (result >> 1) as i64;
+    Ok(if result & 1 == 1 { !value } else { value })
+}
+
+/// Decode a Google Encoded Polyline (precision 5)
+fn decode_polyline_coords(encoded: &str) -> Result<Vec<Coordinate>, String> {
+    let mut bytes = encoded.bytes().enumerate().peekable();
+
+    let mut coords = Vec::new();
+    let (mut lat, mut lon) = (0i64, 0i64);
+    while bytes.peek().is_some() {
+        lat += decode_polyline_value(&mut bytes)?;
+        lon += decode_polyline_value(&mut bytes)?;
+        coords.push(Coordinate {
+            longitude: lon as f64 / POLYLINE_PRECISION,
+            latitude: lat as f64 / POLYLINE_PRECISION,
+        });
+    }
+
+    Ok(coords)
+}
+
+/// Decode a Google Encoded Polyline, as returned by external routing APIs.
+///
+/// # Arguments
+/// * `encoded` - Polyline string with precision 5 (1e-5 degrees)
+///
+/// # Returns
+/// Array of Coordinate, in route order
+///
+/// @throws if the string is not a valid polyline
+#[wasm_bindgen(js_name = decodePolyline)]
+pub fn decode_polyline(encoded: &str) -> Result<JsValue, JsValue> {
+    let coords = decode_polyline_coords(encoded).map_err(|e| JsValue::from_str(&e))?;
+
+    to_js_value(&coords)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize coordinates: {}", e)))
+}
+
+/// Encode coordinates as a Google Encoded Polyline.
+///
+/// Coordinates are rounded to 1e-5 degrees (about 1 m).
+///
+/// # Arguments
+/// * `coords_js` - Array of Coordinate, in route order
+///
+/// # Returns
+/// The polyline string
+#[wasm_bindgen(js_name = encodePolyline)]
+pub fn encode_polyline(coords_js: JsValue) -> Result<JsValue, JsValue> {
+    let coords: Vec<Coordinate> = serde_wasm_bindgen::from_value(coords_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse coordinates: {}", e)))?;
+
+    Ok(JsValue::from_str(&encode_polyline_coords(&coords)))
+}
+
+/// Total length of an encoded polyline.
+///
+/// # Arguments
+/// * `encoded` - Polyline string with precision 5
+///
+/// # Returns
+/// Sum of the haversine distances between consecutive points, in km
+///
+/// @throws if the string is not a valid polyline
+#[wasm_bindgen(js_name = polylineLength)]
+pub fn polyline_length(encoded: &str) -> Result<JsValue, JsValue> {
+    let coords = decode_polyline_coords(encoded).map_err(|e| JsValue::from_str(&e))?;
+
+    Ok(JsValue::from_f64(path_length_km(&coords)))
+}
+
+/// Total length of a recorded delivery route.
+///
+/// # Arguments
+/// * `waypoints_js` - Array of waypoints (anything with `latitude` and
+///   `longitude`), sorted by sequence
+///
+/// # Returns
+/// Sum of the haversine distances between consecutive waypoints, in km;
+/// 0 for fewer than two waypoints
+#[wasm_bindgen(js_name = calculateRouteLength)]
+pub fn calculate_route_length(waypoints_js: JsValue) -> Result<f64, JsValue> {
+    let waypoints: Vec<Coordinate> = serde_wasm_bindgen::from_value(waypoints_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse waypoints: {}", e)))?;
+
+    Ok(path_length_km(&waypoints))
+}
+
+/// Sum of the haversine distances between consecutive coordinates, in km
+fn path_length_km(coords: &[Coordinate]) -> f64 {
+    coords
+        .windows(2)
+        .map(|pair| haversine_distance(pair[0].latitude, pair[0].longitude, pair[1].latitude, pair[1].longitude))
+        .sum()
+}
+
+// ============================================================================
+// Geofencing
+// ============================================================================
+
+/// Tolerance (degrees, ~1 cm) for treating a point as lying on a polygon edge
+const POLYGON_EDGE_EPSILON: f64 = 1e-7;
+
+/// Reject polygons that cannot enclose an area
+fn validate_polygon(polygon: &[Coordinate]) -> Result<(), String> {
+    if polygon.len() < 3 {
+        return Err(format!(
+            "Polygon needs at least 3 vertices, got {}",
+            polygon.len()
+        ));
+    }
+    Ok(())
+}
+
+/// Whether `point` lies on the segment from `a` to `b`
+fn on_segment(point: &Coordinate, a: &Coordinate, b: &Coordinate) -> bool {
+    let cross = (b.longitude - a.longitude) * (point.latitude - a.latitude)
+        - (b.latitude - a.latitude) * (point.longitude - a.longitude);
+    cross.abs() <= POLYGON_EDGE_EPSILON
+        && point.longitude >= a.longitude.min(b.longitude) - POLYGON_EDGE_EPSILON
+        && point.longitude <= a.longitude.max(b.longitude) + POLYGON_EDGE_EPSILON
+        && point.latitude >= a.latitude.min(b.latitude) - POLYGON_EDGE_EPSILON
+        && point.latitude <= a.latitude.max(b.latitude) + POLYGON_EDGE_EPSILON
+}
+
+/// Ray-casting point-in-polygon test; points on an edge count as inside
+///
+/// The polygon is closed implicitly (last vertex connects to the first).
+/// Coordinates are treated as planar, which is fine at city scale. For
+/// self-intersecting polygons the result follows the even-odd rule and
+/// is not meaningful.
+fn point_in_polygon(point: &Coordinate, polygon: &[Coordinate]) -> bool {
+    let mut inside = false;
+    let mut j = polygon.len() - 1;
+
+    for i in 0..polygon.len() {
+        let (a, b) = (&polygon[i], &polygon[j]);
+        if on_segment(point, a, b) {
+            return true;
+        }
+        // Count crossings of a ray cast east from the point
+        if (a.latitude > point.latitude) != (b.latitude > point.latitude) {
+            let crossing_lon = a.longitude
+                + (point.latitude - a.latitude) / (b.latitude - a.latitude) * (b.longitude - a.longitude);
+            if point.longitude < crossing_lon {
+                inside = !inside;
+            }
+        }
+        j = i;
+    }
+
+    inside
+}
+
+/// Check whether a coordinate lies inside a polygon (geofence).
+///
+/// Uses ray casting; a point exactly on an edge counts as inside. The result
+/// for self-intersecting polygons is undefined.
+///
+/// # Arguments
+///
+/// * `point_js` - Coordinate to test
+/// * `polygon_js` - Array of at least 3 Coordinates; closed implicitly
+#[wasm_bindgen(js_name = isPointInPolygon)]
+pub fn is_point_in_polygon(point_js: JsValue, polygon_js: JsValue) -> Result<bool, JsValue> {
+    let point: Coordinate = serde_wasm_bindgen::from_value(point_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse point: {}", e)))?;
+
+    let polygon: Vec<Coordinate> = serde_wasm_bindgen::from_value(polygon_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse polygon: {}", e)))?;
+
+    validate_polygon(&polygon).map_err(|e| JsValue::from_str(&e))?;
+
+    Ok(point_in_polygon(&point, &polygon))
+}
+
+/// Find all bikes inside a zone, e.g. a neighborhood such as De Pijp
+///
+/// # Arguments
+///
+/// * `bikes_js` - Array of BikePosition objects
+/// * `polygon_js` - Zone outline, as for `isPointInPolygon`
+///
+/// # Returns
+///
+/// The bikes inside the zone, in input order
+#[wasm_bindgen(js_name = findBikesInZone)]
+pub fn find_bikes_in_zone(bikes_js: JsValue, polygon_js: JsValue) -> Result<JsValue, JsValue> {
+    let bikes: Vec<BikePosition> = serde_wasm_bindgen::from_value(bikes_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse bikes: {}", e)))?;
+
+    let polygon: Vec<Coordinate> = serde_wasm_bindgen::from_value(polygon_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse polygon: {}", e)))?;
+
+    validate_polygon(&polygon).map_err(|e| JsValue::from_str(&e))?;
+
+    let bikes_in_zone: Vec<&BikePosition> = bikes
+        .iter()
+        .filter(|bike| {
+            let position = Coordinate { longitude: bike.longitude, latitude: bike.latitude };
+            point_in_polygon(&position, &polygon)
+        })
+        .collect();
+
+    to_js_value(&bikes_in_zone)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+/// Length of a polygon's outline in km, including the closing edge
+fn polygon_perimeter_km(polygon: &[Coordinate]) -> f64 {
+    match (polygon.first(), polygon.last()) {
+        (Some(first), Some(last)) => {
+            path_length_km(polygon)
+                + haversine_distance(last.latitude, last.longitude, first.latitude, first.longitude)
+        }
+        _ => 0.0,
+    }
+}
+
+/// Area of a zone, e.g. a geofence or the fleet's convex hull.
+///
+/// Shoelace formula on radians, scaled by the earth's radius and the
+/// cosine of the centroid latitude; accurate for city-sized polygons.
+///
+/// # Arguments
+///
+/// * `polygon_js` - Array of at least 3 Coordinates; closed implicitly
+///
+/// # Returns
+///
+/// Area in km². Self-intersecting polygons give the absolute value of
+/// the signed area, so lobes of opposite orientation cancel out.
+#[wasm_bindgen(js_name = calculatePolygonArea)]
+pub fn calculate_polygon_area(polygon_js: JsValue) -> Result<f64, JsValue> {
+    let polygon: Vec<Coordinate> = serde_wasm_bindgen::from_value(polygon_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse polygon: {}", e)))?;
+
+    validate_polygon(&polygon).map_err(|e| JsValue::from_str(&e))?;
+
+    Ok(polygon_area_km2(&polygon))
+}
+
+/// Length of a zone's outline.
+///
+/// # Arguments
+///
+/// * `polygon_js` - Array of at least 3 Coordinates; closed implicitly
+///
+/// # Returns
+///
+/// Sum of the haversine distances between consecutive vertices, back to
+/// the first one, in km
+#[wasm_bindgen(js_name = calculatePolygonPerimeter)]
+pub fn calculate_polygon_perimeter(polygon_js: JsValue) -> Result<f64, JsValue> {
+    let polygon: Vec<Coordinate> = serde_wasm_bindgen::from_value(polygon_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse polygon: {}", e)))?;
+
+    validate_polygon(&polygon).map_err(|e| JsValue::from_str(&e))?;
+
+    Ok(polygon_perimeter_km(&polygon))
+}
+
+// ============================================================================
+// Fleet Coverage (Convex Hull)
+// ============================================================================
+
+/// Geographic envelope of a set of bikes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConvexHullResult {
+    /// Hull vertices, counter-clockwise, starting at the southernmost bike
+    pub hull_points: Vec<Coordinate>,
+    pub perimeter_km: f64,
+    pub area_km2: f64,
+}
+
+/// Cross product of `o -> a` and `o -> b`; positive for a left turn
+fn cross(o: &Coordinate, a: &Coordinate, b: &Coordinate) -> f64 {
+    (a.longitude - o.longitude) * (b.latitude - o.latitude)
+        - (a.latitude - o.latitude) * (b.longitude - o.longitude)
+}
+
+/// Graham scan over `(longitude, latitude)`, dropping collinear points
+fn graham_scan(points: &[Coordinate]) -> Vec<Coordinate> {
+    let Some(pivot) = points
+        .iter()
+        .min_by(|a, b| {
+            a.latitude
+                .total_cmp(&b.latitude)
+                .then(a.longitude.total_cmp(&b.longitude))
+        })
+        .cloned()
+    else {
+        return Vec::new();
+    };
+
+    // Sort by polar angle around the pivot, nearer points first on ties
+    let mut sorted: Vec<&Coordinate> = points.iter().collect();
+    let angle = |p: &Coordinate| (p.latitude - pivot.latitude).atan2(p.longitude - pivot.longitude);
+    let distance = |p: &Coordinate| {
+        (p.longitude - pivot.longitude).powi(2) + (p.latitude - pivot.latitude).powi(2)
+    };
+    sorted.sort_by(|a, b| {
+        angle(a)
+            .total_cmp(&angle(b))
+            .then(distance(a).total_cmp(&distance(b)))
+    });
+
+    let mut hull: Vec<Coordinate> = Vec::with_capacity(points.len());
+    for point in sorted {
+        while hull.len() >= 2 && cross(&hull[hull.len() - 2], &hull[hull.len() - 1], point) <= 0.0 {
+            hull.pop();
+        }
+        // The pivot and its duplicates collapse into one vertex
+        if hull.len() == 1 && distance(point) == 0.0 {
+            continue;
+        }
+        hull.push(point.clone());
+    }
+    hull
+}
+
+/// Convex hull of the bikes with its perimeter and area
+fn fleet_convex_hull(bikes: &[BikePosition]) -> Result<ConvexHullResult, String> {
+    if bikes.len() < 3 {
+        return Err(format!("Convex hull needs at least 3 bikes, got {}", bikes.len()));
+    }
+
+    let positions: Vec<Coordinate> = bikes
+        .iter()
+        .map(|bike| Coordinate { longitude: bike.longitude, latitude: bike.latitude })
+        .collect();
+    let hull_points = graham_scan(&positions);
+    if hull_points.len() < 3 {
+        return Err("Bikes are collinear; their hull has no area".to_string());
+    }
+
+    let edges = || hull_points.iter().zip(hull_points.iter().cycle().skip(1));
+
+    let perimeter_km = edges()
+        .map(|(a, b)| haversine_distance(a.latitude, a.longitude, b.latitude, b.longitude))
+        .sum();
+
+    // Shoelace in square degrees, scaled by the length of a degree at the
+    // fleet centroid (longitude degrees shrink with cos(latitude))
+    let area_deg2 = edges()
+        .map(|(a, b)| a.longitude * b.latitude - b.longitude * a.latitude)
+        .sum::<f64>()
+        .abs()
+        / 2.0;
+    let centroid_lat = bikes.iter().map(|bike| bike.latitude).sum::<f64>() / bikes.len() as f64;
+    let km_per_degree = deg_to_rad(1.0) * EARTH_RADIUS_KM;
+    let area_km2 = area_deg2 * km_per_degree.powi(2) * deg_to_rad(centroid_lat).cos();
+
+    Ok(ConvexHullResult {
+        hull_points,
+        perimeter_km,
+        area_km2,
+    })
+}
+
+/// Calculate the geographic envelope of the fleet.
+///
+/// Graham scan over the bike positions, for the "fleet coverage" widget.
+/// Pass only the bikes that should count, e.g. the active ones. The area
+/// uses the shoelace formula, corrected to km² with the scale at the
+/// fleet centroid.
+///
+/// # Arguments
+///
+/// * `bikes_js` - Array of at least 3 BikePosition objects, not all in a line
+///
+/// # Returns
+///
+/// ConvexHullResult with hull vertices, perimeter (km) and area (km²)
+#[wasm_bindgen(js_name = calculateFleetConvexHull)]
+pub fn calculate_fleet_convex_hull(bikes_js: JsValue) -> Result<JsValue, JsValue> {
+    let bikes: Vec<BikePosition> = serde_wasm_bindgen::from_value(bikes_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse bikes: {}", e)))?;
+
+    let result = fleet_convex_hull(&bikes).map_err(|e| JsValue::from_str(&e))?;
+
+    to_js_value(&result)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+/// Find the bikes outside a previously calculated hull
+///
+/// # Arguments
+///
+/// * `bikes_js` - Array of BikePosition objects
+/// * `hull_js` - `hullPoints` of a `calculateFleetConvexHull` result
+///
+/// # Returns
+///
+/// The bikes outside the hull, in input order; bikes on its edge count as inside
+#[wasm_bindgen(js_name = filterBikesOutsideHull)]
+pub fn filter_bikes_outside_hull(bikes_js: JsValue, hull_js: JsValue) -> Result<JsValue, JsValue> {
+    let bikes: Vec<BikePosition> = serde_wasm_bindgen::from_value(bikes_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse bikes: {}", e)))?;
+
+    let hull: Vec<Coordinate> = serde_wasm_bindgen::from_value(hull_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse hull: {}", e)))?;
+
+    validate_polygon(&hull).map_err(|e| JsValue::from_str(&e))?;
+
+    let outside: Vec<&BikePosition> = bikes
+        .iter()
+        .filter(|bike| {
+            let position = Coordinate { longitude: bike.longitude, latitude: bike.latitude };
+            !point_in_polygon(&position, &hull)
+        })
+        .collect();
+
+    to_js_value(&outside)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+// ============================================================================
+// Clustering
+// ============================================================================
+
+/// Group of nearby bikes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BikeCluster {
+    pub centroid: Coordinate,
+    pub bike_ids: Vec<String>,
+    pub count: u32,
+}
+
+/// Result of `clusterBikePositions`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClusterResult {
+    pub clusters: Vec<BikeCluster>,
+}
+
+/// Squared planar distance between two (longitude, latitude) points
+fn squared_distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    (a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)
+}
+
+/// Index of the centroid closest to `point`
+fn nearest_centroid(point: (f64, f64), centroids: &[(f64, f64)]) -> usize {
+    (0..centroids.len())
+        .min_by(|&a, &b| {
+            squared_distance(point, centroids[a]).total_cmp(&squared_distance(point, centroids[b]))
+        })
+        .unwrap()
+}
+
+/// k-means++ seeding: the first centroid is a random point, each next one
+/// a point picked with probability proportional to its squared distance
+/// from the closest centroid so far
+fn kmeans_plus_plus(points: &[(f64, f64)], k: usize, rng: &mut impl Rng) -> Vec<(f64, f64)> {
+    let mut centroids = vec![points[rng.gen_range(0..points.len())]];
+
+    while centroids.len() < k {
+        let weights: Vec<f64> = points
+            .iter()
+            .map(|&p| squared_distance(p, centroids[nearest_centroid(p, &centroids)]))
+            .collect();
+        let total: f64 = weights.iter().sum();
+
+        // All points coincide with a centroid (duplicate positions): pick any
+        let next = if total > 0.0 {
+            let mut target = rng.gen::<f64>() * total;
+            weights
+                .iter()
+                .position(|&w| {
+                    target -= w;
+                    target < 0.0
+                })
+                .unwrap_or(points.len() - 1)
+        } else {
+            rng.gen_range(0..points.len())
+        };
+        centroids.push(points[next]);
+    }
+
+    centroids
+}
+
+/// Core of `clusterBikePositions`, separated from JS conversion so it can be tested natively
+fn kmeans_clusters(bikes: &[BikePosition], k: u32, max_iterations: u32, rng: &mut impl Rng) -> ClusterResult {
+    let points: Vec<(f64, f64)> = bikes.iter().map(|b| (b.longitude, b.latitude)).collect();
+    let k = (k as usize).min(points.len());
+    if k == 0 {
+        return ClusterResult { clusters: Vec::new() };
+    }
+
+    let mut centroids = kmeans_plus_plus(&points, k, rng);
+    let mut assignments: Vec<usize> = points.iter().map(|&p| nearest_centroid(p, &centroids)).collect();
+
+    // Lloyd's algorithm: move centroids to their members' mean, reassign
+    for _ in 0..max_iterations {
+        let mut sums = vec![(0.0, 0.0, 0usize); k];
+        for (&(lon, lat), &cluster) in points.iter().zip(&assignments) {
+            sums[cluster].0 += lon;
+            sums[cluster].1 += lat;
+            sums[cluster].2 += 1;
+        }
+        for (centroid, (lon, lat, n)) in centroids.iter_mut().zip(sums) {
+            if n > 0 {
+                *centroid = (lon / n as f64, lat / n as f64);
+            }
+        }
+
+        let next: Vec<usize> = points.iter().map(|&p| nearest_centroid(p, &centroids)).collect();
+        if next == assignments {
+            break;
+        }
+        assignments = next;
+    }
+
+    let clusters = (0..k)
+        .filter_map(|cluster| {
+            let members: Vec<&BikePosition> = bikes
+                .iter()
+                .zip(&assignments)
+                .filter(|(_, &c)| c == cluster)
+                .map(|(bike, _)| bike)
+                .collect();
+            if members.is_empty() {
+                return None;
+            }
+            let n = members.len() as f64;
+            Some(BikeCluster {
+                centroid: Coordinate {
+                    longitude: members.iter().map(|b| b.longitude).sum::<f64>() / n,
+                    latitude: members.iter().map(|b| b.latitude).sum::<f64>() / n,
+                },
+                bike_ids: members.iter().map(|b| b.id.clone()).collect(),
+                count: members.len() as u32,
+            })
+        })
+        .collect();
+
+    ClusterResult { clusters }
+}
+
+/// Group bike positions into `k` clusters with k-means.
+///
+/// Lets dispatchers see where bikes gather across the city when zoomed
+/// out. Centroids are seeded with k-means++, then refined with Lloyd's
+/// algorithm until assignments stop changing or `max_iterations` is reached.
+///
+/// # Arguments
+///
+/// * `bikes_js` - Array of BikePosition objects
+/// * `k` - Number of clusters; at most one per bike
+/// * `max_iterations` - Upper bound on refinement rounds
+///
+/// # Returns
+///
+/// ClusterResult; a cluster left without bikes (only possible with
+/// duplicate positions) is omitted
+#[wasm_bindgen(js_name = clusterBikePositions)]
+pub fn cluster_bike_positions(bikes_js: JsValue, k: u32, max_iterations: u32) -> Result<JsValue, JsValue> {
+    let bikes: Vec<BikePosition> = serde_wasm_bindgen::from_value(bikes_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse bikes: {}", e)))?;
+
+    if k == 0 {
+        return Err(JsValue::from_str("k must be at least 1"));
+    }
+
+    let seed = deterministic_random().unwrap_or_else(js_sys::Math::random);
+    let mut rng = SmallRng::seed_from_u64((seed * u64::MAX as f64) as u64);
+    let result = kmeans_clusters(&bikes, k, max_iterations, &mut rng);
+
+    to_js_value(&result)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+// ============================================================================
+// Hexagonal Heat Map
+// ============================================================================
+
+/// Accepted hex cell sizes (km, center to corner)
+const HEX_CELL_SIZE_RANGE_KM: (f64, f64) = (0.05, 5.0);
+
+/// Bike counts per status
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct StatusBreakdown {
+    pub delivering: u32,
+    pub idle: u32,
+    pub returning: u32,
+}
+
+/// One occupied hexagon of the heat map
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HexCell {
+    pub center: Coordinate,
+    pub count: u32,
+    pub avg_speed: f64,
+    pub status_breakdown: StatusBreakdown,
+}
+
+/// Result of `generateHexGrid`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HexGridResult {
+    pub cells: Vec<HexCell>,
+}
+
+/// Core of the hex grid functions, separated from JS conversion so it can be tested natively
+///
+/// Positions are projected to kilometers around the middle of Amsterdam,
+/// with the km-per-degree scale measured by haversine, then binned into
+/// flat-top hexagons keyed by odd-q offset coordinates.
+fn hex_grid(bikes: &[BikePosition], cell_size_km: f64) -> Result<HexGridResult, String> {
+    let (min_size, max_size) = HEX_CELL_SIZE_RANGE_KM;
+    if !(min_size..=max_size).contains(&cell_size_km) {
+        return Err(format!(
+            "Cell size must be between {} and {} km, got {}",
+            min_size, max_size, cell_size_km
+        ));
+    }
+
+    let (min_lon, max_lon, min_lat, max_lat) = AMSTERDAM_BOUNDS;
+    let (origin_lon, origin_lat) = ((min_lon + max_lon) / 2.0, (min_lat + max_lat) / 2.0);
+    let km_per_deg_lon = haversine_distance(origin_lat, origin_lon, origin_lat, origin_lon + 1.0);
+    let km_per_deg_lat = haversine_distance(origin_lat, origin_lon, origin_lat + 1.0, origin_lon);
+    let sqrt3 = 3.0_f64.sqrt();
+
+    let mut cells: std::collections::BTreeMap<(i64, i64), (f64, Vec<&BikePosition>)> =
+        std::collections::BTreeMap::new();
+    for bike in bikes {
+        let x = (bike.longitude - origin_lon) * km_per_deg_lon;
+        let y = (bike.latitude - origin_lat) * km_per_deg_lat;
+
+        // Fractional axial coordinates, rounded via cube coordinates
+        let q = (2.0 / 3.0 * x) / cell_size_km;
+        let r = (-1.0 / 3.0 * x + sqrt3 / 3.0 * y) / cell_size_km;
+        let s = -q - r;
+        let (mut rq, mut rr, rs) = (q.round(), r.round(), s.round());
+        let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
+        if dq > dr && dq > ds {
+            rq = -rr - rs;
+        } else if dr > ds {
+            rr = -rq - rs;
+        }
+
+        let (q, r) = (rq as i64, rr as i64);
+        let offset = (q, r + (q - (q & 1)) / 2);
+        let cell = cells.entry(offset).or_insert_with(|| (0.0, Vec::new()));
+        cell.0 += bike.speed;
+        cell.1.push(bike);
+    }
+
+    let cells = cells
+        .into_iter()
+        .map(|((col, row), (speed_sum, members))| {
+            let r = row - (col - (col & 1)) / 2;
+            let x = cell_size_km * 1.5 * col as f64;
+            let y = cell_size_km * sqrt3 * (r as f64 + col as f64 / 2.0);
+
+            let mut status_breakdown = StatusBreakdown::default();
+            for bike in &members {
+                match bike.status {
+                    BikeStatus::Delivering => status_breakdown.delivering += 1,
+                    BikeStatus::Idle => status_breakdown.idle += 1,
+                    BikeStatus::Returning => status_breakdown.returning += 1,
+                }
+            }
+
+            HexCell {
+                center: Coordinate {
+                    longitude: origin_lon + x / km_per_deg_lon,
+                    latitude: origin_lat + y / km_per_deg_lat,
+                },
+                count: members.len() as u32,
+                avg_speed: speed_sum / members.len() as f64,
+                status_breakdown,
+            }
+        })
+        .collect();
+
+    Ok(HexGridResult { cells })
+}
+
+/// Bin bike positions into a hexagonal grid for density heat maps.
+///
+/// Same idea as deck.gl's HexagonLayer, computed here so the binning stays
+/// out of the JavaScript bundle. Hexagons are flat-top; only cells
+/// containing at least one bike are returned.
+///
+/// # Arguments
+///
+/// * `bikes_js` - Array of BikePosition objects
+/// * `cell_size_km` - Hexagon size, center to corner (0.05-5.0 km)
+///
+/// # Returns
+///
+/// HexGridResult with count, average speed and status breakdown per cell
+#[wasm_bindgen(js_name = generateHexGrid)]
+pub fn generate_hex_grid(bikes_js: JsValue, cell_size_km: f64) -> Result<JsValue, JsValue> {
+    let bikes: Vec<BikePosition> = serde_wasm_bindgen::from_value(bikes_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse bikes: {}", e)))?;
+
+    let grid = hex_grid(&bikes, cell_size_km).map_err(|e| JsValue::from_str(&e))?;
+
+    to_js_value(&grid)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+/// Hexagonal heat map of only the bikes with a given status
+///
+/// # Arguments
+///
+/// * `bikes_js` - Array of BikePosition objects
+/// * `cell_size_km` - Hexagon size, as for `generateHexGrid`
+/// * `status` - "idle", "delivering" or "returning"
+#[wasm_bindgen(js_name = generateHexGridForStatus)]
+pub fn generate_hex_grid_for_status(bikes_js: JsValue, cell_size_km: f64, status: &str) -> Result<JsValue, JsValue> {
+    let bikes: Vec<BikePosition> = serde_wasm_bindgen::from_value(bikes_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse bikes: {}", e)))?;
+
+    let status = match status.to_lowercase().as_str() {
+        "idle" => BikeStatus::Idle,
+        "delivering" => BikeStatus::Delivering,
+        "returning" => BikeStatus::Returning,
+        _ => return Err(JsValue::from_str(&format!("Unknown status: {}", status))),
+    };
+    let matching: Vec<BikePosition> = bikes.into_iter().filter(|bike| bike.status == status).collect();
+
+    let grid = hex_grid(&matching, cell_size_km).map_err(|e| JsValue::from_str(&e))?;
+
+    to_js_value(&grid)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+// ============================================================================
+// Search
+// ============================================================================
+
+/// Case-insensitive substring match; a blank query matches every name
+fn name_matches(name: &str, query: &str) -> bool {
+    name.to_lowercase().contains(&query.trim().to_lowercase())
+}
+
+/// Filter bikes whose name contains a search query.
+///
+/// Instant client-side pre-filter while the backend `search` command is in
+/// flight. Case-insensitive; a blank query keeps every bike. Works on both
+/// `BikePosition` and backend `Bike` objects, which are passed through
+/// unchanged and in input order.
+///
+/// # Arguments
+///
+/// * `bikes_js` - Array of objects with a string `name`
+/// * `query` - Text to look for
+///
+/// # Returns
+///
+/// The matching bikes
+#[wasm_bindgen(js_name = filterBikesByName)]
+pub fn filter_bikes_by_name(bikes_js: JsValue, query: &str) -> Result<JsValue, JsValue> {
+    let bikes: js_sys::Array = bikes_js
+        .dyn_into()
+        .map_err(|_| JsValue::from_str("Failed to parse bikes: expected an array"))?;
+
+    let matches = js_sys::Array::new();
+    for bike in bikes.iter() {
+        let name = js_sys::Reflect::get(&bike, &JsValue::from_str("name"))
+            .ok()
+            .and_then(|name| name.as_string())
+            .ok_or_else(|| JsValue::from_str("Failed to parse bikes: name must be a string"))?;
+        if name_matches(&name, query) {
+            matches.push(&bike);
+        }
+    }
+
+    Ok(matches.into())
+}
+
+// ============================================================================
+// Battery Alerts
+// ============================================================================
+
+/// Indices of the battery levels below `threshold`, lowest first
+///
+/// Missing readings are skipped; equal levels keep their input order.
+fn low_battery_order(levels: &[Option<f64>], threshold: u8) -> Vec<usize> {
+    let mut low: Vec<usize> = (0..levels.len())
+        .filter(|&i| matches!(levels[i], Some(level) if level < threshold as f64))
+        .collect();
+    low.sort_by(|&a, &b| levels[a].partial_cmp(&levels[b]).unwrap());
+    low
+}
+
+/// Filter bikes below a battery level, lowest first.
+///
+/// Client-side complement of the backend's `get_low_battery_bikes`. Works on
+/// the `Bike` records returned by the Tauri commands and passes them through
+/// unchanged; bikes whose `battery_level` is null or missing are left out.
+///
+/// # Arguments
+///
+/// * `bikes_js` - Array of Bike objects with a `battery_level` (0-100 or null)
+/// * `threshold` - Battery percentage to filter below
+///
+/// # Returns
+///
+/// The matching bikes, lowest battery first
+#[wasm_bindgen(js_name = filterBikesByBattery)]
+pub fn filter_bikes_by_battery(bikes_js: JsValue, threshold: u8) -> Result<JsValue, JsValue> {
+    let bikes: js_sys::Array = bikes_js
+        .dyn_into()
+        .map_err(|_| JsValue::from_str("Failed to parse bikes: expected an array"))?;
+
+    let levels = bikes
+        .iter()
+        .map(|bike| {
+            let level = js_sys::Reflect::get(&bike, &JsValue::from_str("battery_level"))
+                .map_err(|_| JsValue::from_str("Failed to parse bikes: expected objects"))?;
+            if level.is_null() || level.is_undefined() {
+                return Ok(None);
+            }
+            level
+                .as_f64()
+                .map(Some)
+                .ok_or_else(|| JsValue::from_str("Failed to parse bikes: battery_level must be a number"))
+        })
+        .collect::<Result<Vec<_>, JsValue>>()?;
+
+    Ok(low_battery_order(&levels, threshold)
+        .into_iter()
+        .map(|i| bikes.get(i as u32))
+        .collect::<js_sys::Array>()
+        .into())
+}
+
+// ============================================================================
+// Maintenance Priority
+// ============================================================================
+
+/// Indices ordered by due time (ms since epoch), earliest first
+///
+/// Unscheduled bikes go last; ties keep their input order.
+fn maintenance_priority_order(due: &[Option<f64>]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..due.len()).collect();
+    order.sort_by(|&a, &b| match (due[a], due[b]) {
+        (Some(a), Some(b)) => a.partial_cmp(&b).unwrap(),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => std::cmp::Ordering::Equal,
+    });
+    order
+}
+
+/// Sort bikes by scheduled maintenance, soonest (or most overdue) first.
+///
+/// Works on the `Bike` records returned by the Tauri commands and passes
+/// them through unchanged. Bikes whose `next_maintenance_at` is null or
+/// missing have nothing scheduled and go last.
+///
+/// # Arguments
+///
+/// * `bikes_js` - Array of Bike objects with a `next_maintenance_at`
+///   (RFC 3339 timestamp or null)
+///
+/// # Returns
+///
+/// The same bikes, ordered by `next_maintenance_at` ascending
+#[wasm_bindgen(js_name = sortByMaintenancePriority)]
+pub fn sort_by_maintenance_priority(bikes_js: JsValue) -> Result<JsValue, JsValue> {
+    let bikes: js_sys::Array = bikes_js
+        .dyn_into()
+        .map_err(|_| JsValue::from_str("Failed to parse bikes: expected an array"))?;
+
+    let due = bikes
+        .iter()
+        .map(|bike| {
+            let at = js_sys::Reflect::get(&bike, &JsValue::from_str("next_maintenance_at"))
+                .map_err(|_| JsValue::from_str("Failed to parse bikes: expected objects"))?;
+            if at.is_null() || at.is_undefined() {
+                return Ok(None);
+            }
+            // String timestamps only; Date::parse would accept numbers too
+            let millis = at.as_string().map(|s| js_sys::Date::parse(&s)).filter(|ms| !ms.is_nan());
+            millis.map(Some).ok_or_else(|| {
+                JsValue::from_str("Failed to parse bikes: next_maintenance_at must be a timestamp")
+            })
+        })
+        .collect::<Result<Vec<_>, JsValue>>()?;
+
+    Ok(maintenance_priority_order(&due)
+        .into_iter()
+        .map(|i| bikes.get(i as u32))
+        .collect::<js_sys::Array>()
+        .into())
+}
+
+// ============================================================================
+// Delivery ETA
+// ============================================================================
+
+/// Minutes standing still after which an ETA is only half as trustworthy
+const ETA_CONFIDENCE_HALF_LIFE_MINUTES: f64 = 15.0;
+
+/// Bike input for ETA estimates: a BikePosition, optionally with `idleMinutes`
+/// (how long it has not moved, 0 if omitted)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EtaBike {
+    #[serde(flatten)]
+    bike: BikePosition,
+    #[serde(default)]
+    idle_minutes: f64,
+}
+
+/// Estimated time for a bike to reach a destination
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EtaResult {
+    pub distance_km: f64,
+    pub estimated_minutes: f64,
+    pub confidence: f64, // 0.0-1.0
+}
+
+/// Core of the ETA functions, separated from JS conversion so it can be tested natively
+fn delivery_eta(bike: &BikePosition, idle_minutes: f64, destination: &Coordinate) -> EtaResult {
+    let distance_km = haversine_distance(bike.latitude, bike.longitude, destination.latitude, destination.longitude);
+    // A stopped bike (at a red light, handing over an order) will move again
+    let speed = if bike.speed > 0.0 {
+        bike.speed
+    } else {
+        (SPEED_DELIVERING.0 + SPEED_DELIVERING.1) / 2.0
+    };
+
+    EtaResult {
+        distance_km,
+        estimated_minutes: distance_km / speed * 60.0,
+        confidence: 0.5_f64.powf(idle_minutes.max(0.0) / ETA_CONFIDENCE_HALF_LIFE_MINUTES),
+    }
+}
+
+/// Estimate when a bike reaches its delivery destination.
+///
+/// Divides the haversine distance by the bike's current speed, or by the
+/// middle of the delivering speed range if the bike is stopped. Confidence
+/// halves for every 15 minutes the bike has been idle.
+///
+/// # Arguments
+///
+/// * `bike_js` - BikePosition, optionally with `idleMinutes`
+/// * `destination_js` - Delivery destination coordinate
+///
+/// # Returns
+///
+/// EtaResult with distance, estimated minutes and confidence (0.0-1.0)
+#[wasm_bindgen(js_name = calculateDeliveryEta)]
+pub fn calculate_delivery_eta(bike_js: JsValue, destination_js: JsValue) -> Result<JsValue, JsValue> {
+    let input: EtaBike = serde_wasm_bindgen::from_value(bike_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse bike: {}", e)))?;
+
+    let destination: Coordinate = serde_wasm_bindgen::from_value(destination_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse destination: {}", e)))?;
+
+    to_js_value(&delivery_eta(&input.bike, input.idle_minutes, &destination))
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+/// Estimate arrival times for many deliveries at once
+///
+/// # Arguments
+///
+/// * `bikes_js` - Array of bikes, as for `calculateDeliveryEta`
+/// * `destinations_js` - Array of destinations (same length as bikes)
+///
+/// # Returns
+///
+/// Array of EtaResult, one per bike
+#[wasm_bindgen(js_name = calculateFleetEtaBatch)]
+pub fn calculate_fleet_eta_batch(bikes_js: JsValue, destinations_js: JsValue) -> Result<JsValue, JsValue> {
+    let inputs: Vec<EtaBike> = serde_wasm_bindgen::from_value(bikes_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse bikes: {}", e)))?;
+
+    let destinations: Vec<Coordinate> = serde_wasm_bindgen::from_value(destinations_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse destinations: {}", e)))?;
+
+    if inputs.len() != destinations.len() {
+        return Err(JsValue::from_str("bikes and destinations must have same length"));
+    }
+
+    let results: Vec<EtaResult> = inputs
+        .iter()
+        .zip(destinations.iter())
+        .map(|(input, destination)| delivery_eta(&input.bike, input.idle_minutes, destination))
+        .collect();
+
+    to_js_value(&results)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize results: {}", e)))
+}
+
+// ============================================================================
+// Bike Movement Simulation
+// ============================================================================
+
+/// Configuration for Amsterdam operational bounds
+const AMSTERDAM_OPERATIONAL_BOUNDS: (f64, f64, f64, f64) = (
+    4.85,  // min longitude
+    4.95,  // max longitude
+    52.34, // min latitude
+    52.40, // max latitude
+);
+
+/// Movement speed in degrees per millisecond for different states
+/// Approximately: idle ~0.0002°, active ~0.001° per 5 seconds
+const MOVEMENT_IDLE: f64 = 0.0002;
+const MOVEMENT_ACTIVE: f64 = 0.001;
+
+/// Result of bike movement simulation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulationResult {
+    pub bikes: Vec<BikePosition>,
+    pub movements_applied: u32,
+    pub bounds_corrections: u32,
+}
+
+/// Simulate bike movement for one tick.
+///
+/// This function applies realistic movement physics to all bikes:
+/// - Idle bikes drift slightly (GPS jitter simulation)
+/// - Active bikes (delivering/returning) move purposefully
+/// - All positions are clamped to Amsterdam operational bounds
+///
+/// # Arguments
+/// * `bikes_js` - Array of current bike positions
+/// * `seed` - Random seed for deterministic movement (use timestamp)
+///
+/// # Returns
+/// SimulationResult with updated bike positions
+#[wasm_bindgen(js_name = simulateBikeMovement)]
+pub fn simulate_bike_movement(bikes_js: JsValue, seed: f64) -> Result<JsValue, JsValue> {
+    let bikes: Vec<BikePosition> = serde_wasm_bindgen::from_value(bikes_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse bikes: {}", e)))?;
+
+    let mut bounds_corrections: u32 = 0;
+    let movements_applied = bikes.len() as u32;
+    let config = simulation_config();
+
+    // Use seed to create pseudo-random but deterministic movement
+    let updated_bikes: Vec<BikePosition> = bikes
+        .into_iter()
+        .enumerate()
+        .map(|(idx, bike)| {
+            // Create per-bike variation using index and seed
+            let variation = deterministic_random()
+                .unwrap_or_else(|| ((seed + idx as f64 * 1000.0) % 1000.0) / 1000.0);
+            let angle = variation * std::f64::consts::PI * 2.0;
+
+            // Movement magnitude based on status
+            let movement = match bike.status {
+                BikeStatus::Idle => config.movement_idle,
+                BikeStatus::Delivering | BikeStatus::Returning => config.movement_active,
+            };
+
+            let mut new_lng = bike.longitude + angle.cos() * movement;
+            let mut new_lat = bike.latitude + angle.sin() * movement;
+
+            // Clamp to the operational bounds
+            let [min_lng, max_lng, min_lat, max_lat] = config.operational_bounds;
+
+            if new_lng < min_lng || new_lng > max_lng || new_lat < min_lat || new_lat > max_lat {
+                bounds_corrections += 1;
+            }
+
+            new_lng = new_lng.clamp(min_lng, max_lng);
+            new_lat = new_lat.clamp(min_lat, max_lat);
+
+            BikePosition {
+                id: bike.id,
+                name: bike.name,
+                longitude: new_lng,
+                latitude: new_lat,
+                status: bike.status,
+                speed: bike.speed,
+            }
+        })
+        .collect();
+
+    let result = SimulationResult {
+        bikes: updated_bikes,
+        movements_applied,
+        bounds_corrections,
+    };
+
+    to_js_value(&result)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+// ============================================================================
+// Trajectory Prediction
+// ============================================================================
+
+/// Confidence lost per predicted step: `confidence_decay[i] = 1 / (1 + 0.1 i)`
+const TRAJECTORY_CONFIDENCE_DECAY: f64 = 0.1;
+
+/// Predicted path of a bike
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrajectoryResult {
+    /// One position per step, `interval_ms` apart
+    pub positions: Vec<Coordinate>,
+    /// Distance covered along the predicted path
+    pub estimated_arrival_km: f64,
+    /// Confidence per position, 1.0 for the first and falling from there
+    pub confidence_decay: Vec<f64>,
+}
+
+/// Extrapolate a bike's velocity linearly, clamping every position to `bounds`
+///
+/// The previous position is taken to be one `interval_ms` old.
+fn bike_trajectory(
+    bike: &BikePosition,
+    previous: &Coordinate,
+    steps: u32,
+    interval_ms: f64,
+    bounds: [f64; 4],
+) -> Result<TrajectoryResult, String> {
+    if !(interval_ms.is_finite() && interval_ms > 0.0) {
+        return Err(format!("interval_ms must be positive, got {}", interval_ms));
+    }
+
+    // Degrees per millisecond
+    let velocity_lng = (bike.longitude - previous.longitude) / interval_ms;
+    let velocity_lat = (bike.latitude - previous.latitude) / interval_ms;
+    let [min_lng, max_lng, min_lat, max_lat] = bounds;
+
+    let positions: Vec<Coordinate> = (1..=steps)
+        .map(|step| {
+            let elapsed_ms = step as f64 * interval_ms;
+            Coordinate {
+                longitude: (bike.longitude + velocity_lng * elapsed_ms).clamp(min_lng, max_lng),
+                latitude: (bike.latitude + velocity_lat * elapsed_ms).clamp(min_lat, max_lat),
+            }
+        })
+        .collect();
+
+    let mut estimated_arrival_km = 0.0;
+    let mut from = (bike.latitude, bike.longitude);
+    for position in &positions {
+        estimated_arrival_km += haversine_distance(from.0, from.1, position.latitude, position.longitude);
+        from = (position.latitude, position.longitude);
+    }
+
+    let confidence_decay = (0..steps)
+        .map(|i| 1.0 / (1.0 + TRAJECTORY_CONFIDENCE_DECAY * i as f64))
+        .collect();
+
+    Ok(TrajectoryResult {
+        positions,
+        estimated_arrival_km,
+        confidence_decay,
+    })
+}
+
+/// Predict where a bike is heading by linear extrapolation.
+///
+/// Fits a velocity from the bike's previous and current position and
+/// extends it `steps` times, so the UI can animate a fading predicted path.
+/// Positions are clamped to the operational bounds of the simulation
+/// configuration; a stationary bike stays where it is.
+///
+/// # Arguments
+///
+/// * `bike_js` - BikePosition with the current position
+/// * `prev_position_js` - Coordinate of the bike `interval_ms` ago
+/// * `steps` - Number of positions to predict
+/// * `interval_ms` - Time between positions (must be positive)
+///
+/// # Returns
+///
+/// TrajectoryResult with positions, path length and per-step confidence
+#[wasm_bindgen(js_name = predictBikeTrajectory)]
+pub fn predict_bike_trajectory(
+    bike_js: JsValue,
+    prev_position_js: JsValue,
+    steps: u32,
+    interval_ms: f64,
+) -> Result<JsValue, JsValue> {
+    let bike: BikePosition = serde_wasm_bindgen::from_value(bike_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse bike: {}", e)))?;
+
+    let previous: Coordinate = serde_wasm_bindgen::from_value(prev_position_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse previous position: {}", e)))?;
+
+    let result = bike_trajectory(
+        &bike,
+        &previous,
+        steps,
+        interval_ms,
+        simulation_config().operational_bounds,
+    )
+    .map_err(|e| JsValue::from_str(&e))?;
+
+    to_js_value(&result)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+// ============================================================================
+// Status Transition Logic
+// ============================================================================
+
+/// Status transition probabilities (from the active simulation config)
+/// Format: (probability_to_delivering, probability_to_returning, probability_to_idle)
+fn get_transition_probabilities(current: &BikeStatus) -> (f64, f64, f64) {
+    simulation_config().transition_matrix.probabilities(current)
+}
+
+/// Status transition result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusTransitionResult {
+    pub new_status: BikeStatus,
+    pub transition_occurred: bool,
+    pub probability_used: f64,
+}
+
+/// Determine next status based on current state and transition probabilities.
+///
+/// Uses a Markov chain model for realistic status transitions:
+/// - Delivering bikes tend to stay delivering (70%) or go idle (15%) or returning (15%)
+/// - Returning bikes tend to stay returning (65%) or go idle (25%)
+/// - Idle bikes tend to stay idle (60%) or start delivering (30%)
+///
+/// # Arguments
+/// * `current_status` - Current bike status string ("delivering", "returning", "idle")
+/// * `random_value` - Random value between 0.0 and 1.0 (use Math.random())
+///
+/// # Returns
+/// StatusTransitionResult with new status and whether transition occurred
+#[wasm_bindgen(js_name = transitionBikeStatus)]
+pub fn transition_bike_status(current_status: &str, random_value: f64) -> Result<JsValue, JsValue> {
+    let current = match current_status.to_lowercase().as_str() {
+        "delivering" => BikeStatus::Delivering,
+        "returning" => BikeStatus::Returning,
+        "idle" => BikeStatus::Idle,
+        _ => return Err(JsValue::from_str(&format!("Unknown status: {}", current_status))),
+    };
+
+    let (p_delivering, p_returning, _p_idle) = get_transition_probabilities(&current);
+    let clamped_random = deterministic_random().unwrap_or(random_value).clamp(0.0, 1.0);
+
+    let new_status = if clamped_random < p_delivering {
+        BikeStatus::Delivering
+    } else if clamped_random < p_delivering + p_returning {
+        BikeStatus::Returning
+    } else {
+        BikeStatus::Idle
+    };
+
+    let transition_occurred = new_status != current;
+
+    let result = StatusTransitionResult {
+        new_status,
+        transition_occurred,
+        probability_used: clamped_random,
+    };
+
+    to_js_value(&result)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+/// Batch transition statuses for multiple bikes
+///
+/// # Arguments
+/// * `statuses` - Array of current status strings
+/// * `random_values` - Array of random values (same length as statuses)
+///
+/// # Returns
+/// Array of new status strings
+#[wasm_bindgen(js_name = transitionBikeStatusBatch)]
+pub fn transition_bike_status_batch(statuses_js: JsValue, random_values_js: JsValue) -> Result<JsValue, JsValue> {
     let statuses: Vec<String> = serde_wasm_bindgen::from_value(statuses_js)
         .map_err(|e| JsValue::from_str(&format!("Failed to parse statuses: {}", e)))?;
 
@@ -606,7 +2576,7 @@ pub fn transition_bike_status_batch(statuses_js: JsValue, random_values_js: JsVa
         })
         .collect();
 
-    serde_wasm_bindgen::to_value(&results)
+    to_js_value(&results)
         .map_err(|e| JsValue::from_str(&format!("Failed to serialize results: {}", e)))
 }
 
@@ -648,16 +2618,17 @@ pub struct SpeedResult {
 /// SpeedResult with calculated speed and breakdown
 #[wasm_bindgen(js_name = calculateBikeSpeed)]
 pub fn calculate_bike_speed(status: &str, is_in_traffic: bool, random_factor: f64) -> Result<JsValue, JsValue> {
-    let clamped_random = random_factor.clamp(0.0, 1.0);
+    let clamped_random = deterministic_random().unwrap_or(random_factor).clamp(0.0, 1.0);
+    let speeds = simulation_config().speed_config;
 
     let (base_speed, status_factor) = match status.to_lowercase().as_str() {
         "delivering" => {
-            let (min, max) = SPEED_DELIVERING;
+            let (min, max) = speeds.delivering;
             let speed = min + (max - min) * clamped_random;
             (speed, "delivering")
         }
         "returning" => {
-            let (min, max) = SPEED_RETURNING;
+            let (min, max) = speeds.returning;
             let speed = min + (max - min) * clamped_random;
             (speed, "returning")
         }
@@ -666,7 +2637,7 @@ pub fn calculate_bike_speed(status: &str, is_in_traffic: bool, random_factor: f6
     };
 
     let traffic_penalty = if is_in_traffic && base_speed > 0.0 {
-        base_speed * TRAFFIC_SPEED_REDUCTION
+        base_speed * speeds.traffic_reduction
     } else {
         0.0
     };
@@ -680,7 +2651,7 @@ pub fn calculate_bike_speed(status: &str, is_in_traffic: bool, random_factor: f6
         status_factor: status_factor.to_string(),
     };
 
-    serde_wasm_bindgen::to_value(&result)
+    to_js_value(&result)
         .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
 }
 
@@ -719,8 +2690,182 @@ pub fn calculate_bike_speed_batch(
         })
         .collect();
 
-    serde_wasm_bindgen::to_value(&speeds)
-        .map_err(|e| JsValue::from_str(&format!("Failed to serialize speeds: {}", e)))
+    to_js_value(&speeds)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize speeds: {}", e)))
+}
+
+// ============================================================================
+// Runtime Simulation Configuration
+// ============================================================================
+
+/// Speed ranges (min, max) in km/h and the traffic penalty; idle bikes stay at 0
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeedConfig {
+    pub delivering: (f64, f64),
+    pub returning: (f64, f64),
+    /// Share of the speed lost in traffic (0.0-1.0)
+    pub traffic_reduction: f64,
+}
+
+impl Default for SpeedConfig {
+    fn default() -> Self {
+        Self {
+            delivering: SPEED_DELIVERING,
+            returning: SPEED_RETURNING,
+            traffic_reduction: TRAFFIC_SPEED_REDUCTION,
+        }
+    }
+}
+
+/// Markov chain rows: `[to_delivering, to_returning, to_idle]` per current status
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct TransitionMatrix {
+    pub delivering: [f64; 3],
+    pub returning: [f64; 3],
+    pub idle: [f64; 3],
+}
+
+impl Default for TransitionMatrix {
+    fn default() -> Self {
+        Self {
+            // Delivering bikes usually stay delivering or go idle
+            delivering: [0.70, 0.15, 0.15],
+            // Returning bikes usually stay returning or become idle
+            returning: [0.10, 0.65, 0.25],
+            // Idle bikes usually stay idle or start delivering
+            idle: [0.30, 0.10, 0.60],
+        }
+    }
+}
+
+impl TransitionMatrix {
+    fn probabilities(&self, current: &BikeStatus) -> (f64, f64, f64) {
+        let [to_delivering, to_returning, to_idle] = match current {
+            BikeStatus::Delivering => self.delivering,
+            BikeStatus::Returning => self.returning,
+            BikeStatus::Idle => self.idle,
+        };
+        (to_delivering, to_returning, to_idle)
+    }
+}
+
+/// All runtime-configurable simulation parameters
+///
+/// Fields missing from the JS object keep their defaults, so a preset
+/// (e.g. "Amsterdam urban", "Rotterdam industrial") only lists what differs.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WasmSimulationConfig {
+    /// `[min_longitude, max_longitude, min_latitude, max_latitude]`
+    #[wasm_bindgen(skip)]
+    pub operational_bounds: [f64; 4],
+    #[wasm_bindgen(skip)]
+    pub speed_config: SpeedConfig,
+    #[wasm_bindgen(skip)]
+    pub transition_matrix: TransitionMatrix,
+    /// Degrees per tick
+    pub movement_idle: f64,
+    /// Degrees per tick
+    pub movement_active: f64,
+    /// km/h; faster reported speeds are clamped by `validateBikeData`
+    pub max_bike_speed: f64,
+}
+
+impl Default for WasmSimulationConfig {
+    fn default() -> Self {
+        let (min_lng, max_lng, min_lat, max_lat) = AMSTERDAM_OPERATIONAL_BOUNDS;
+        Self {
+            operational_bounds: [min_lng, max_lng, min_lat, max_lat],
+            speed_config: SpeedConfig::default(),
+            transition_matrix: TransitionMatrix::default(),
+            movement_idle: MOVEMENT_IDLE,
+            movement_active: MOVEMENT_ACTIVE,
+            max_bike_speed: MAX_BIKE_SPEED,
+        }
+    }
+}
+
+impl WasmSimulationConfig {
+    fn validate(&self) -> Result<(), String> {
+        let [min_lng, max_lng, min_lat, max_lat] = self.operational_bounds;
+        if min_lng >= max_lng || min_lat >= max_lat {
+            return Err("operationalBounds must be [minLng, maxLng, minLat, maxLat]".to_string());
+        }
+
+        let speeds = &self.speed_config;
+        for (name, (min, max)) in [("delivering", speeds.delivering), ("returning", speeds.returning)] {
+            if min < 0.0 || min > max {
+                return Err(format!("Invalid {} speed range: {} - {}", name, min, max));
+            }
+        }
+        if !(0.0..=1.0).contains(&speeds.traffic_reduction) {
+            return Err("trafficReduction must be between 0 and 1".to_string());
+        }
+
+        let matrix = &self.transition_matrix;
+        for (name, row) in [("delivering", matrix.delivering), ("returning", matrix.returning), ("idle", matrix.idle)] {
+            if row.iter().any(|p| *p < 0.0) || (row.iter().sum::<f64>() - 1.0).abs() > 1e-6 {
+                return Err(format!("Transition probabilities from {} must be non-negative and sum to 1", name));
+            }
+        }
+
+        if self.movement_idle < 0.0 || self.movement_active < 0.0 {
+            return Err("Movement per tick cannot be negative".to_string());
+        }
+        if self.max_bike_speed <= 0.0 {
+            return Err("maxBikeSpeed must be positive".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+thread_local! {
+    /// Configuration set by `applySimulationConfig`; `None` means defaults
+    static SIMULATION_CONFIG: RefCell<Option<WasmSimulationConfig>> = const { RefCell::new(None) };
+}
+
+/// The active simulation configuration
+fn simulation_config() -> WasmSimulationConfig {
+    SIMULATION_CONFIG.with(|config| config.borrow().clone().unwrap_or_default())
+}
+
+/// Validate and install a configuration; an invalid one changes nothing
+fn set_simulation_config(config: WasmSimulationConfig) -> Result<(), String> {
+    config.validate()?;
+    SIMULATION_CONFIG.with(|current| *current.borrow_mut() = Some(config));
+    Ok(())
+}
+
+/// Replace all simulation parameters at once.
+///
+/// Omitted fields take their defaults. The configuration is validated as a
+/// whole first, so a rejected config leaves the previous one in place.
+///
+/// # Arguments
+/// * `config_js` - WasmSimulationConfig (operationalBounds, speedConfig,
+///   transitionMatrix, movementIdle, movementActive, maxBikeSpeed)
+#[wasm_bindgen(js_name = applySimulationConfig)]
+pub fn apply_simulation_config(config_js: JsValue) -> Result<(), JsValue> {
+    let config: WasmSimulationConfig = serde_wasm_bindgen::from_value(config_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse simulation config: {}", e)))?;
+
+    set_simulation_config(config).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Get the simulation configuration currently in effect (defaults if none applied)
+#[wasm_bindgen(js_name = getSimulationConfig)]
+pub fn get_simulation_config() -> Result<JsValue, JsValue> {
+    to_js_value(&simulation_config())
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize config: {}", e)))
+}
+
+/// Restore the default simulation configuration
+#[wasm_bindgen(js_name = resetSimulationConfig)]
+pub fn reset_simulation_config() {
+    SIMULATION_CONFIG.with(|config| *config.borrow_mut() = None);
 }
 
 // ============================================================================
@@ -730,6 +2875,7 @@ pub fn calculate_bike_speed_batch(
 /// Fast hash of bike positions for change detection.
 ///
 /// Uses FNV-1a inspired algorithm for fast, deterministic hashing.
+/// Kept for existing callers; `hashBikePositionsSip` collides far less.
 /// This is used by deck.gl updateTriggers to detect position changes
 /// without expensive deep comparison.
 ///
@@ -792,6 +2938,112 @@ pub fn hash_bike_state(bikes_js: JsValue) -> Result<u32, JsValue> {
     Ok(hash)
 }
 
+/// SipHash key; change detection only needs a fixed key, not a secret one
+const SIPHASH_KEY: [u8; 16] = [
+    0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07,
+    0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f,
+];
+
+/// SipHash-2-4 of `data` (64-bit output, as in the reference implementation)
+fn siphash24(key: &[u8; 16], data: &[u8]) -> u64 {
+    let k0 = u64::from_le_bytes(key[..8].try_into().unwrap());
+    let k1 = u64::from_le_bytes(key[8..].try_into().unwrap());
+    let mut v = [
+        k0 ^ 0x736f_6d65_7073_6575,
+        k1 ^ 0x646f_7261_6e64_6f6d,
+        k0 ^ 0x6c79_6765_6e65_7261,
+        k1 ^ 0x7465_6462_7974_6573,
+    ];
+
+    fn sip_round(v: &mut [u64; 4]) {
+        v[0] = v[0].wrapping_add(v[1]);
+        v[1] = v[1].rotate_left(13) ^ v[0];
+        v[0] = v[0].rotate_left(32);
+        v[2] = v[2].wrapping_add(v[3]);
+        v[3] = v[3].rotate_left(16) ^ v[2];
+        v[0] = v[0].wrapping_add(v[3]);
+        v[3] = v[3].rotate_left(21) ^ v[0];
+        v[2] = v[2].wrapping_add(v[1]);
+        v[1] = v[1].rotate_left(17) ^ v[2];
+        v[2] = v[2].rotate_left(32);
+    }
+
+    let mut compress = |m: u64| {
+        v[3] ^= m;
+        sip_round(&mut v);
+        sip_round(&mut v);
+        v[0] ^= m;
+    };
+
+    let mut words = data.chunks_exact(8);
+    for word in &mut words {
+        compress(u64::from_le_bytes(word.try_into().unwrap()));
+    }
+    // Last word: the leftover bytes, with the length (mod 256) in the top byte
+    let mut last = [0u8; 8];
+    last[..words.remainder().len()].copy_from_slice(words.remainder());
+    last[7] = data.len() as u8;
+    compress(u64::from_le_bytes(last));
+
+    v[2] ^= 0xff;
+    for _ in 0..4 {
+        sip_round(&mut v);
+    }
+    v[0] ^ v[1] ^ v[2] ^ v[3]
+}
+
+/// Bytes hashed for change detection: each bike's coordinates in
+/// millionths of a degree and, with `include_state`, its status and speed
+/// in hundredths of km/h
+fn change_detection_bytes(bikes: &[BikePosition], include_state: bool) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(bikes.len() * 25);
+    for bike in bikes {
+        bytes.extend_from_slice(&((bike.longitude * 1_000_000.0).round() as i64).to_le_bytes());
+        bytes.extend_from_slice(&((bike.latitude * 1_000_000.0).round() as i64).to_le_bytes());
+        if include_state {
+            bytes.push(match bike.status {
+                BikeStatus::Delivering => 1,
+                BikeStatus::Returning => 2,
+                BikeStatus::Idle => 3,
+            });
+            bytes.extend_from_slice(&((bike.speed * 100.0).round() as i64).to_le_bytes());
+        }
+    }
+    bytes
+}
+
+/// Lower 32 bits of the SipHash-2-4 of the change detection bytes
+fn sip_change_hash(bikes: &[BikePosition], include_state: bool) -> u32 {
+    siphash24(&SIPHASH_KEY, &change_detection_bytes(bikes, include_state)) as u32
+}
+
+/// Hash of bike positions for change detection, using SipHash-2-4.
+///
+/// Same use as `hashBikePositions`, but far less likely to map two
+/// different frames to the same hash. Coordinates count to 6 decimal places.
+///
+/// # Arguments
+/// * `bikes_js` - Array of bike positions
+///
+/// # Returns
+/// Lower 32 bits of the 64-bit SipHash
+#[wasm_bindgen(js_name = hashBikePositionsSip)]
+pub fn hash_bike_positions_sip(bikes_js: JsValue) -> Result<u32, JsValue> {
+    let bikes: Vec<BikePosition> = serde_wasm_bindgen::from_value(bikes_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse bikes: {}", e)))?;
+
+    Ok(sip_change_hash(&bikes, false))
+}
+
+/// Hash bike positions, status and speed with SipHash-2-4; see `hashBikePositionsSip`
+#[wasm_bindgen(js_name = hashBikeStateSip)]
+pub fn hash_bike_state_sip(bikes_js: JsValue) -> Result<u32, JsValue> {
+    let bikes: Vec<BikePosition> = serde_wasm_bindgen::from_value(bikes_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse bikes: {}", e)))?;
+
+    Ok(sip_change_hash(&bikes, true))
+}
+
 // ============================================================================
 // Full Simulation Tick (combines all updates)
 // ============================================================================
@@ -837,31 +3089,50 @@ pub fn simulation_tick(
         return Err(JsValue::from_str("Cannot simulate empty fleet"));
     }
 
+    let result = run_simulation_tick(bikes, timestamp, transition_probability);
+
+    to_js_value(&result)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+/// Core of `simulationTick`, separated from JS conversion so it can be tested natively
+///
+/// Expects a non-empty fleet.
+fn run_simulation_tick(
+    bikes: Vec<BikePosition>,
+    timestamp: f64,
+    transition_probability: f64,
+) -> SimulationTickResult {
     let mut status_transitions: u32 = 0;
     let mut bounds_corrections: u32 = 0;
     let clamp_prob = transition_probability.clamp(0.0, 1.0);
+    let config = simulation_config();
 
     // Process each bike
     let updated_bikes: Vec<BikePosition> = bikes
         .into_iter()
         .enumerate()
         .map(|(idx, bike)| {
-            // Deterministic "random" values based on timestamp and index
-            let variation = ((timestamp + idx as f64 * 1000.0) % 1000.0) / 1000.0;
-            let status_random = ((timestamp * 7.0 + idx as f64 * 3000.0) % 1000.0) / 1000.0;
-            let speed_random = ((timestamp * 13.0 + idx as f64 * 5000.0) % 1000.0) / 1000.0;
+            // Deterministic "random" values based on timestamp and index,
+            // or drawn from the seeded RNG in deterministic mode
+            let variation = deterministic_random()
+                .unwrap_or_else(|| ((timestamp + idx as f64 * 1000.0) % 1000.0) / 1000.0);
+            let status_random = deterministic_random()
+                .unwrap_or_else(|| ((timestamp * 7.0 + idx as f64 * 3000.0) % 1000.0) / 1000.0);
+            let speed_random = deterministic_random()
+                .unwrap_or_else(|| ((timestamp * 13.0 + idx as f64 * 5000.0) % 1000.0) / 1000.0);
 
             // 1. Movement
             let angle = variation * std::f64::consts::PI * 2.0;
             let movement = match bike.status {
-                BikeStatus::Idle => MOVEMENT_IDLE,
-                _ => MOVEMENT_ACTIVE,
+                BikeStatus::Idle => config.movement_idle,
+                _ => config.movement_active,
             };
 
             let mut new_lng = bike.longitude + angle.cos() * movement;
             let mut new_lat = bike.latitude + angle.sin() * movement;
 
-            let (min_lng, max_lng, min_lat, max_lat) = AMSTERDAM_OPERATIONAL_BOUNDS;
+            let [min_lng, max_lng, min_lat, max_lat] = config.operational_bounds;
             if new_lng < min_lng || new_lng > max_lng || new_lat < min_lat || new_lat > max_lat {
                 bounds_corrections += 1;
             }
@@ -869,9 +3140,10 @@ pub fn simulation_tick(
             new_lat = new_lat.clamp(min_lat, max_lat);
 
             // 2. Status transition (only if random value is below threshold)
-            let should_transition = ((timestamp * 17.0 + idx as f64 * 7000.0) % 1000.0) / 1000.0;
+            let should_transition = deterministic_random()
+                .unwrap_or_else(|| ((timestamp * 17.0 + idx as f64 * 7000.0) % 1000.0) / 1000.0);
             let new_status = if should_transition < clamp_prob {
-                let (p_del, p_ret, _) = get_transition_probabilities(&bike.status);
+                let (p_del, p_ret, _) = config.transition_matrix.probabilities(&bike.status);
                 let new_s = if status_random < p_del {
                     BikeStatus::Delivering
                 } else if status_random < p_del + p_ret {
@@ -891,11 +3163,11 @@ pub fn simulation_tick(
             let new_speed = match new_status {
                 BikeStatus::Idle => 0.0,
                 BikeStatus::Delivering => {
-                    let (min, max) = SPEED_DELIVERING;
+                    let (min, max) = config.speed_config.delivering;
                     min + (max - min) * speed_random
                 }
                 BikeStatus::Returning => {
-                    let (min, max) = SPEED_RETURNING;
+                    let (min, max) = config.speed_config.returning;
                     min + (max - min) * speed_random
                 }
             };
@@ -911,6 +3183,15 @@ pub fn simulation_tick(
         })
         .collect();
 
+    summarize_tick(updated_bikes, status_transitions, bounds_corrections)
+}
+
+/// Statistics and hashes of a tick's bikes, wrapped into its result
+fn summarize_tick(
+    updated_bikes: Vec<BikePosition>,
+    status_transitions: u32,
+    bounds_corrections: u32,
+) -> SimulationTickResult {
     // Calculate statistics
     let total_bikes = updated_bikes.len() as u32;
     let delivering_count = updated_bikes.iter().filter(|b| b.status == BikeStatus::Delivering).count() as u32;
@@ -969,16 +3250,571 @@ pub fn simulation_tick(
         state_hash = state_hash.wrapping_mul(16777619);
     }
 
-    let result = SimulationTickResult {
-        bikes: updated_bikes,
-        statistics,
-        position_hash,
-        state_hash,
-        status_transitions,
-        bounds_corrections,
-    };
+    SimulationTickResult {
+        bikes: updated_bikes,
+        statistics,
+        position_hash,
+        state_hash,
+        status_transitions,
+        bounds_corrections,
+    }
+}
+
+// ============================================================================
+// Position Smoothing
+// ============================================================================
+
+/// Blend each bike's position and speed with its previous values.
+///
+/// Exponential moving average against the previous frame, to take the
+/// jitter out of simulated positions before they reach the map layer:
+/// `smoothed = alpha * current + (1 - alpha) * previous`.
+///
+/// # Arguments
+/// * `current_bikes_js` - Array of this frame's bike positions
+/// * `prev_bikes_js` - Array of the previous frame's (smoothed) positions
+/// * `alpha` - Weight of the current frame, clamped to 0.0-1.0
+///
+/// # Returns
+/// The bikes of `current_bikes_js`, in order. Bikes without a previous
+/// position are passed through; bikes only in `prev_bikes_js` are dropped.
+///
+/// @throws if `alpha` is NaN
+#[wasm_bindgen(js_name = smoothBikePositions)]
+pub fn smooth_bike_positions(
+    current_bikes_js: JsValue,
+    prev_bikes_js: JsValue,
+    alpha: f64,
+) -> Result<JsValue, JsValue> {
+    let current: Vec<BikePosition> = serde_wasm_bindgen::from_value(current_bikes_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse current bikes: {}", e)))?;
+    let prev: Vec<BikePosition> = serde_wasm_bindgen::from_value(prev_bikes_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse previous bikes: {}", e)))?;
+    if alpha.is_nan() {
+        return Err(JsValue::from_str("alpha must be a number"));
+    }
+
+    to_js_value(&smooth_positions(current, &prev, alpha))
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+/// Perform a simulation tick and smooth its positions and speeds.
+///
+/// Same as `simulationTick`, with the moved bikes passed through
+/// `smoothBikePositions` against the input bikes. Statistics and hashes
+/// describe the smoothed bikes.
+///
+/// # Arguments
+/// * `bikes_js` - Array of current bike positions
+/// * `timestamp` - Current timestamp (used as seed for determinism)
+/// * `transition_probability` - Probability (0.0-1.0) that any bike changes status
+/// * `alpha` - Weight of the new positions, clamped to 0.0-1.0
+///
+/// @throws if `bikes_js` is empty or `alpha` is NaN
+#[wasm_bindgen(js_name = smoothSimulationTick)]
+pub fn smooth_simulation_tick(
+    bikes_js: JsValue,
+    timestamp: f64,
+    transition_probability: f64,
+    alpha: f64,
+) -> Result<JsValue, JsValue> {
+    let bikes: Vec<BikePosition> = serde_wasm_bindgen::from_value(bikes_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse bikes: {}", e)))?;
+
+    if bikes.is_empty() {
+        return Err(JsValue::from_str("Cannot simulate empty fleet"));
+    }
+    if alpha.is_nan() {
+        return Err(JsValue::from_str("alpha must be a number"));
+    }
+
+    let raw = run_simulation_tick(bikes.clone(), timestamp, transition_probability);
+    let smoothed = smooth_positions(raw.bikes, &bikes, alpha);
+    let result = summarize_tick(smoothed, raw.status_transitions, raw.bounds_corrections);
+
+    to_js_value(&result)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+/// EMA of `current` against the bikes of `prev` with the same ID
+fn smooth_positions(
+    current: Vec<BikePosition>,
+    prev: &[BikePosition],
+    alpha: f64,
+) -> Vec<BikePosition> {
+    let alpha = alpha.clamp(0.0, 1.0);
+    let blend = |now: f64, before: f64| alpha * now + (1.0 - alpha) * before;
+    let prev_by_id: std::collections::HashMap<&str, &BikePosition> =
+        prev.iter().map(|bike| (bike.id.as_str(), bike)).collect();
+
+    current
+        .into_iter()
+        .map(|bike| match prev_by_id.get(bike.id.as_str()) {
+            Some(before) => BikePosition {
+                longitude: blend(bike.longitude, before.longitude),
+                latitude: blend(bike.latitude, before.latitude),
+                speed: blend(bike.speed, before.speed),
+                ..bike
+            },
+            None => bike,
+        })
+        .collect()
+}
+
+// ============================================================================
+// Correlation Analytics
+// ============================================================================
+
+/// Pearson correlation coefficient of two equally sized series
+///
+/// Returns NaN for fewer than 3 points or zero variance, matching the
+/// backend's `get_issue_rating_correlation` behavior.
+fn pearson_correlation(xs: &[f64], ys: &[f64]) -> f64 {
+    if xs.len() != ys.len() || xs.len() < 3 {
+        return f64::NAN;
+    }
+
+    let n = xs.len() as f64;
+    let mean_x = xs.iter().sum::<f64>() / n;
+    let mean_y = ys.iter().sum::<f64>() / n;
+
+    let mut covariance = 0.0;
+    let mut variance_x = 0.0;
+    let mut variance_y = 0.0;
+
+    for (x, y) in xs.iter().zip(ys.iter()) {
+        let dx = x - mean_x;
+        let dy = y - mean_y;
+        covariance += dx * dy;
+        variance_x += dx * dx;
+        variance_y += dy * dy;
+    }
+
+    let denominator = (variance_x * variance_y).sqrt();
+    if denominator == 0.0 {
+        return f64::NAN;
+    }
+
+    covariance / denominator
+}
+
+/// Compute the Pearson correlation between two numeric arrays.
+///
+/// Lets the frontend correlate locally held data (e.g. filtered views)
+/// without a round-trip to the backend.
+///
+/// # Arguments
+/// * `xs_js` - First numeric series
+/// * `ys_js` - Second numeric series (same length as `xs_js`)
+///
+/// # Returns
+/// Coefficient in -1.0..=1.0, or NaN when there is not enough data
+#[wasm_bindgen(js_name = computePearsonCorrelation)]
+pub fn compute_pearson_correlation(xs_js: JsValue, ys_js: JsValue) -> Result<f64, JsValue> {
+    let xs: Vec<f64> = serde_wasm_bindgen::from_value(xs_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse xs: {}", e)))?;
+
+    let ys: Vec<f64> = serde_wasm_bindgen::from_value(ys_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse ys: {}", e)))?;
+
+    if xs.len() != ys.len() {
+        return Err(JsValue::from_str("xs and ys must have same length"));
+    }
+
+    Ok(pearson_correlation(&xs, &ys))
+}
+
+// ============================================================================
+// Speed Anomaly Detection
+// ============================================================================
+
+/// A bike whose speed is far from the fleet's
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeedAnomaly {
+    pub bike_id: String,
+    pub speed: f64,
+    pub z_score: f64,
+}
+
+/// Speed anomalies of a fleet and the statistics they were measured against
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnomalyReport {
+    pub anomalies: Vec<SpeedAnomaly>,
+    pub fleet_mean_speed: f64,
+    /// Population standard deviation
+    pub fleet_std_dev: f64,
+}
+
+/// Flag bikes whose speed z-score exceeds `z_threshold` in absolute value
+///
+/// No bike is anomalous when all speeds are equal (zero standard deviation).
+fn speed_anomalies(bikes: &[BikePosition], z_threshold: f64) -> AnomalyReport {
+    if bikes.is_empty() {
+        return AnomalyReport {
+            anomalies: Vec::new(),
+            fleet_mean_speed: 0.0,
+            fleet_std_dev: 0.0,
+        };
+    }
+
+    let n = bikes.len() as f64;
+    let fleet_mean_speed = bikes.iter().map(|b| b.speed).sum::<f64>() / n;
+    let fleet_std_dev = (bikes
+        .iter()
+        .map(|b| (b.speed - fleet_mean_speed).powi(2))
+        .sum::<f64>()
+        / n)
+        .sqrt();
+
+    let anomalies = if fleet_std_dev == 0.0 {
+        Vec::new()
+    } else {
+        bikes
+            .iter()
+            .map(|b| SpeedAnomaly {
+                bike_id: b.id.clone(),
+                speed: b.speed,
+                z_score: (b.speed - fleet_mean_speed) / fleet_std_dev,
+            })
+            .filter(|anomaly| anomaly.z_score.abs() > z_threshold)
+            .collect()
+    };
+
+    AnomalyReport {
+        anomalies,
+        fleet_mean_speed,
+        fleet_std_dev,
+    }
+}
+
+/// Detect suspicious bike speeds, such as a GPS glitch reporting 200 km/h.
+///
+/// Compares every speed against the fleet mean and standard deviation
+/// (z-score). With a single outlier among `n` bikes the largest possible
+/// z-score is `(n - 1) / sqrt(n)`, so small fleets need a lower threshold.
+///
+/// # Arguments
+///
+/// * `bikes_js` - Array of BikePosition objects
+/// * `z_threshold` - Minimum absolute z-score to report, e.g. 3.0
+///
+/// # Returns
+///
+/// AnomalyReport with the flagged bikes in input order
+#[wasm_bindgen(js_name = detectSpeedAnomalies)]
+pub fn detect_speed_anomalies(bikes_js: JsValue, z_threshold: f64) -> Result<JsValue, JsValue> {
+    let bikes: Vec<BikePosition> = serde_wasm_bindgen::from_value(bikes_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse bikes: {}", e)))?;
+
+    if z_threshold <= 0.0 {
+        return Err(JsValue::from_str(&format!("z_threshold must be positive, got {}", z_threshold)));
+    }
+
+    to_js_value(&speed_anomalies(&bikes, z_threshold))
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+/// Replace a bike's speed with the fleet mean if it was flagged as anomalous
+///
+/// # Arguments
+///
+/// * `bike_js` - BikePosition to sanitize
+/// * `fleet_stats_js` - AnomalyReport from `detectSpeedAnomalies`
+///
+/// # Returns
+///
+/// The bike, unchanged unless its ID is among the report's anomalies
+#[wasm_bindgen(js_name = sanitizeAnomalousSpeed)]
+pub fn sanitize_anomalous_speed(bike_js: JsValue, fleet_stats_js: JsValue) -> Result<JsValue, JsValue> {
+    let mut bike: BikePosition = serde_wasm_bindgen::from_value(bike_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse bike: {}", e)))?;
+
+    let report: AnomalyReport = serde_wasm_bindgen::from_value(fleet_stats_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse anomaly report: {}", e)))?;
+
+    if report.anomalies.iter().any(|anomaly| anomaly.bike_id == bike.id) {
+        bike.speed = report.fleet_mean_speed;
+    }
+
+    to_js_value(&bike)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize bike: {}", e)))
+}
+
+// ============================================================================
+// Acceleration Detection
+// ============================================================================
+
+/// Largest plausible change in speed for a bike, km/h per second
+const MAX_ACCELERATION_KMH_PER_S: f64 = 5.0;
+
+/// Allowed gap between reported speed and speed implied by the position change, km/h
+const MAX_SPEED_DISCREPANCY_KMH: f64 = 10.0;
+
+/// Change in speed of one bike between two position samples
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccelerationData {
+    pub bike_id: String,
+    /// km/h per second; negative when braking
+    pub acceleration: f64,
+    pub is_anomalous: bool,
+    /// Speed implied by the distance travelled between the samples, km/h
+    pub computed_speed: f64,
+    /// Reported and computed speed differ by more than 10 km/h
+    pub speed_discrepancy: bool,
+}
+
+/// Acceleration of every bike present in both samples
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccelerationReport {
+    pub bikes: Vec<AccelerationData>,
+}
+
+/// Compare each bike with its previous sample `interval_s` seconds earlier
+fn accelerations(bikes: &[BikePosition], prev: &[BikePosition], interval_s: f64) -> AccelerationReport {
+    let previous: std::collections::HashMap<&str, &BikePosition> = prev.iter().map(|b| (b.id.as_str(), b)).collect();
+
+    let bikes = bikes
+        .iter()
+        .filter_map(|bike| {
+            let before = previous.get(bike.id.as_str())?;
+            let acceleration = (bike.speed - before.speed) / interval_s;
+            let distance_km = haversine_distance(before.latitude, before.longitude, bike.latitude, bike.longitude);
+            let computed_speed = distance_km / interval_s * 3600.0;
+
+            Some(AccelerationData {
+                bike_id: bike.id.clone(),
+                acceleration,
+                is_anomalous: acceleration.abs() > MAX_ACCELERATION_KMH_PER_S,
+                computed_speed,
+                speed_discrepancy: (computed_speed - bike.speed).abs() > MAX_SPEED_DISCREPANCY_KMH,
+            })
+        })
+        .collect();
+
+    AccelerationReport { bikes }
+}
+
+/// Detect sudden speed changes and speeds the positions don't back up.
+///
+/// Either can mean GPS spoofing or corrupted telemetry. Acceleration is
+/// `(speed - previous speed) / interval` and is anomalous above 5 km/h/s
+/// in either direction. Independently, the haversine distance between the
+/// two positions gives the speed the bike actually travelled at; a gap of
+/// more than 10 km/h to the reported speed is flagged as a discrepancy.
+///
+/// # Arguments
+/// * `bikes_js` - Array of current bike positions
+/// * `prev_bikes_js` - Array of the same bikes' previous positions
+/// * `interval_ms` - Time between the two samples in milliseconds
+///
+/// # Returns
+/// AccelerationReport in the order of `bikes_js`. Bikes without a previous
+/// sample are left out.
+///
+/// @throws if `interval_ms` is not positive
+#[wasm_bindgen(js_name = detectAcceleration)]
+pub fn detect_acceleration(bikes_js: JsValue, prev_bikes_js: JsValue, interval_ms: f64) -> Result<JsValue, JsValue> {
+    let bikes: Vec<BikePosition> = serde_wasm_bindgen::from_value(bikes_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse bikes: {}", e)))?;
+
+    let prev_bikes: Vec<BikePosition> = serde_wasm_bindgen::from_value(prev_bikes_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse previous bikes: {}", e)))?;
+
+    if interval_ms.is_nan() || interval_ms <= 0.0 {
+        return Err(JsValue::from_str(&format!("interval_ms must be positive, got {}", interval_ms)));
+    }
+
+    to_js_value(&accelerations(&bikes, &prev_bikes, interval_ms / 1000.0))
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+// ============================================================================
+// Peak Hours
+// ============================================================================
+
+/// Number of busiest hours reported by `calculatePeakHours`
+const PEAK_HOURS_COUNT: usize = 3;
+
+/// Delivery load in one hour of the day, as returned by the backend's
+/// `get_hourly_utilization`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HourlyUtilization {
+    pub hour: u8,
+    pub active_deliveries: u32,
+    pub completed_deliveries: u32,
+    pub utilization_ratio: f64,
+}
+
+/// One of the busiest hours of the day
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeakHour {
+    pub hour: u8,
+    /// Active plus completed deliveries
+    pub deliveries: u32,
+    pub utilization_ratio: f64,
+    /// Deliveries relative to the average hour (2.0 = twice as busy)
+    pub load_factor: f64,
+}
+
+/// Busiest hours and how to spread deliverers over the day
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeakHoursResult {
+    /// Up to 3 hours with deliveries, busiest first
+    pub peak_hours: Vec<PeakHour>,
+    /// Share of deliverer-hours to schedule per hour (index = hour, 24
+    /// entries), proportional to its deliveries; all zero without deliveries
+    pub staffing_shares: Vec<f64>,
+}
+
+/// Rank hours by delivery load
+fn peak_hours(hourly: &[HourlyUtilization]) -> Result<PeakHoursResult, String> {
+    let mut deliveries_per_hour = [0u32; 24];
+    let mut utilization_per_hour = [0.0f64; 24];
+    for entry in hourly {
+        let slot = deliveries_per_hour
+            .get_mut(entry.hour as usize)
+            .ok_or_else(|| format!("Hour must be 0-23, got {}", entry.hour))?;
+        *slot += entry.active_deliveries + entry.completed_deliveries;
+        utilization_per_hour[entry.hour as usize] = entry.utilization_ratio;
+    }
+
+    let total: u32 = deliveries_per_hour.iter().sum();
+    let average = total as f64 / 24.0;
+
+    let mut ranked: Vec<u8> = (0..24).filter(|&hour| deliveries_per_hour[hour as usize] > 0).collect();
+    // Busiest first; earlier hour wins ties
+    ranked.sort_by_key(|&hour| std::cmp::Reverse(deliveries_per_hour[hour as usize]));
+
+    let peak_hours = ranked
+        .into_iter()
+        .take(PEAK_HOURS_COUNT)
+        .map(|hour| {
+            let deliveries = deliveries_per_hour[hour as usize];
+            PeakHour {
+                hour,
+                deliveries,
+                utilization_ratio: utilization_per_hour[hour as usize],
+                load_factor: deliveries as f64 / average,
+            }
+        })
+        .collect();
+
+    let staffing_shares = deliveries_per_hour
+        .iter()
+        .map(|&deliveries| if total == 0 { 0.0 } else { deliveries as f64 / total as f64 })
+        .collect();
+
+    Ok(PeakHoursResult {
+        peak_hours,
+        staffing_shares,
+    })
+}
+
+/// Find the busiest hours of the day and recommend staffing levels.
+///
+/// Takes the output of the backend's `get_hourly_utilization` and ranks
+/// hours by active plus completed deliveries, so fleet managers can
+/// schedule more deliverers at peak times.
+///
+/// # Arguments
+///
+/// * `hourly_js` - Array of HourlyUtilization; missing hours count as idle
+///
+/// # Returns
+///
+/// PeakHoursResult with the top 3 hours and per-hour staffing shares
+#[wasm_bindgen(js_name = calculatePeakHours)]
+pub fn calculate_peak_hours(hourly_js: JsValue) -> Result<JsValue, JsValue> {
+    let hourly: Vec<HourlyUtilization> = serde_wasm_bindgen::from_value(hourly_js)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse hourly utilization: {}", e)))?;
+
+    let result = peak_hours(&hourly).map_err(|e| JsValue::from_str(&e))?;
+
+    to_js_value(&result)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+// ============================================================================
+// Issue Categorization
+// ============================================================================
+
+/// Keyword lists per issue category (snake_case names match the backend)
+///
+/// Keep in sync with `category_keywords()` in src-tauri/src/categorization.rs
+const CATEGORY_KEYWORDS: [(&str, &[&str]); 5] = [
+    ("late", &["late", "wait", "delay", "minutes", "slow", "hour"]),
+    ("damaged", &["damage", "crushed", "spill", "leak", "broken", "cold", "squashed"]),
+    ("wrong_order", &["wrong", "missing", "someone", "else", "incorrect", "mixed"]),
+    ("rude", &["rude", "impolite", "unfriendly", "shout", "yell", "attitude", "aggressive"]),
+    ("bike_problem", &["tire", "tyre", "flat", "brake", "battery", "broken", "chain", "puncture"]),
+];
+
+/// Minimum confidence for a keyword suggestion; below this "other" is returned
+const MIN_CATEGORY_CONFIDENCE: f64 = 0.3;
+
+/// Suggested issue category with confidence score
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CategorySuggestion {
+    pub category: String,
+    pub confidence: f64,
+}
+
+/// Score a description against the keyword tables
+fn categorize_description(description: &str) -> CategorySuggestion {
+    let words: Vec<String> = description
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect();
+
+    let mut best = ("other", 0.0);
+
+    if !words.is_empty() {
+        for (category, keywords) in CATEGORY_KEYWORDS.iter() {
+            let matches = words
+                .iter()
+                .filter(|w| keywords.iter().any(|k| w.starts_with(k)))
+                .count();
+            let score = (matches as f64 / words.len() as f64).min(1.0);
+
+            if score > best.1 {
+                best = (category, score);
+            }
+        }
+    }
+
+    let category = if best.1 < MIN_CATEGORY_CONFIDENCE { "other" } else { best.0 };
+
+    CategorySuggestion {
+        category: category.to_string(),
+        confidence: best.1,
+    }
+}
+
+/// Suggest an issue category from free-text description.
+///
+/// Uses the same keyword scoring as the backend so the UI can pre-select
+/// a category while the user is typing.
+///
+/// # Arguments
+/// * `description` - Issue description text
+///
+/// # Returns
+/// CategorySuggestion with category ("late", "damaged", "wrong_order",
+/// "rude", "bike_problem" or "other") and confidence (0.0-1.0)
+#[wasm_bindgen(js_name = suggestIssueCategory)]
+pub fn suggest_issue_category(description: &str) -> Result<JsValue, JsValue> {
+    let suggestion = categorize_description(description);
 
-    serde_wasm_bindgen::to_value(&result)
+    to_js_value(&suggestion)
         .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
 }
 
@@ -1011,6 +3847,53 @@ mod tests {
         assert!((bearing - 90.0).abs() < 1.0, "Bearing should be approximately 90 degrees (east)");
     }
 
+    fn coordinate(latitude: f64, longitude: f64) -> Coordinate {
+        Coordinate { longitude, latitude }
+    }
+
+    #[test]
+    fn test_vincenty_centraal_to_dam() {
+        // Ellipsoidal reference distance for these coordinates: 824.34 m
+        // (the haversine estimate above rounds to ~0.85 km)
+        let result = vincenty_inverse(&coordinate(52.3791, 4.9003), &coordinate(52.3731, 4.8932));
+
+        assert!(result.converged);
+        assert!((result.distance_m - 824.34).abs() < 5.0, "got {}", result.distance_m);
+        assert!((result.initial_bearing_deg - 215.9).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_vincenty_flinders_peak_to_buninyong() {
+        // Worked example from Vincenty (1975): 54 972.271 m,
+        // azimuths 306°52'05.37" and 127°10'25.07" (reverse)
+        let dms = |d: f64, m: f64, s: f64| d + m / 60.0 + s / 3600.0;
+        let from = coordinate(-dms(37.0, 57.0, 3.72030), dms(144.0, 25.0, 29.52440));
+        let to = coordinate(-dms(37.0, 39.0, 10.15610), dms(143.0, 55.0, 35.38390));
+
+        let result = vincenty_inverse(&from, &to);
+
+        assert!((result.distance_m - 54972.271).abs() < 0.001, "got {}", result.distance_m);
+        assert!((result.initial_bearing_deg - 306.868158).abs() < 1e-5);
+        assert!((result.final_bearing_deg - 307.173631).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_vincenty_antipodal_falls_back_to_haversine() {
+        let from = coordinate(0.0, 0.0);
+        let to = coordinate(0.5, 179.7);
+
+        let result = vincenty_inverse(&from, &to);
+
+        assert!(!result.converged);
+        assert_eq!(result.distance_m, haversine_distance(0.0, 0.0, 0.5, 179.7) * 1000.0);
+    }
+
+    #[test]
+    fn test_vincenty_same_point_is_zero() {
+        let point = coordinate(52.3791, 4.9003);
+        assert_eq!(vincenty_inverse(&point, &point).distance_m, 0.0);
+    }
+
     #[test]
     fn test_deg_to_rad() {
         assert!((deg_to_rad(180.0) - std::f64::consts::PI).abs() < 0.0001);
@@ -1115,6 +3998,51 @@ mod tests {
         assert_eq!(hash1, hash2, "Hash should be deterministic");
     }
 
+    #[test]
+    fn test_siphash24_reference_vectors() {
+        // From the SipHash paper's vectors.h, key 00..0f and message 00..(n-1)
+        let message: Vec<u8> = (0..64).collect();
+        assert_eq!(siphash24(&SIPHASH_KEY, &message[..0]), 0x726f_db47_dd0e_0e31);
+        assert_eq!(siphash24(&SIPHASH_KEY, &message[..7]), 0xab02_00f5_8b01_d137);
+        assert_eq!(siphash24(&SIPHASH_KEY, &message[..8]), 0x93f5_f579_9a93_2462);
+        assert_eq!(siphash24(&SIPHASH_KEY, &message[..15]), 0xa129_ca61_49be_45e5);
+        assert_eq!(siphash24(&SIPHASH_KEY, &message[..63]), 0x958a_324c_eb06_4572);
+    }
+
+    #[test]
+    fn test_sip_hash_detects_sixth_decimal_changes() {
+        let bike = |longitude: f64, latitude: f64, speed: f64| BikePosition {
+            id: "BIKE-0001".to_string(),
+            name: "Centraal".to_string(),
+            longitude,
+            latitude,
+            status: BikeStatus::Delivering,
+            speed,
+        };
+        let frame = vec![bike(4.900300, 52.379100, 18.0), bike(4.893200, 52.373100, 0.0)];
+
+        for include_state in [false, true] {
+            let hash = sip_change_hash(&frame, include_state);
+            assert_eq!(hash, sip_change_hash(&frame.clone(), include_state));
+
+            for i in 0..frame.len() {
+                for (d_lon, d_lat) in [(1e-6, 0.0), (-1e-6, 0.0), (0.0, 1e-6), (0.0, -1e-6)] {
+                    let mut moved = frame.clone();
+                    moved[i].longitude += d_lon;
+                    moved[i].latitude += d_lat;
+                    assert_ne!(sip_change_hash(&moved, include_state), hash);
+                }
+            }
+        }
+
+        // Only the state hash sees status and speed
+        let mut stopped = frame.clone();
+        stopped[0].speed = 0.0;
+        stopped[0].status = BikeStatus::Idle;
+        assert_eq!(sip_change_hash(&stopped, false), sip_change_hash(&frame, false));
+        assert_ne!(sip_change_hash(&stopped, true), sip_change_hash(&frame, true));
+    }
+
     #[test]
     fn test_movement_constants() {
         // Idle movement should be smaller than active movement
@@ -1131,4 +4059,780 @@ mod tests {
         assert!(TRAFFIC_SPEED_REDUCTION > 0.0, "Traffic should have some effect");
         assert!(TRAFFIC_SPEED_REDUCTION < 1.0, "Traffic shouldn't stop bikes completely");
     }
+
+    #[test]
+    fn test_pearson_correlation() {
+        let xs = [1.0, 2.0, 3.0, 4.0];
+        let ys = [10.0, 8.0, 6.0, 4.0];
+        assert!((pearson_correlation(&xs, &ys) + 1.0).abs() < 1e-9, "Should be perfectly negative");
+
+        // Not enough data points
+        assert!(pearson_correlation(&[1.0, 2.0], &[1.0, 2.0]).is_nan());
+    }
+
+    #[test]
+    fn test_categorize_description() {
+        assert_eq!(categorize_description("Delivery arrived 30 minutes late").category, "late");
+        assert_eq!(categorize_description("Flat tire during delivery").category, "bike_problem");
+        assert_eq!(categorize_description("General complaint about the service quality today").category, "other");
+    }
+
+    #[test]
+    fn test_deterministic_seed_reproducible() {
+        let initial = vec![
+            BikePosition {
+                id: "bike-1".to_string(),
+                name: "Jan".to_string(),
+                longitude: 4.9,
+                latitude: 52.37,
+                status: BikeStatus::Delivering,
+                speed: 20.0,
+            },
+            BikePosition {
+                id: "bike-2".to_string(),
+                name: "Pieter".to_string(),
+                longitude: 4.91,
+                latitude: 52.38,
+                status: BikeStatus::Idle,
+                speed: 0.0,
+            },
+        ];
+
+        let run = |seed: u64| {
+            set_deterministic_seed(seed);
+            let mut bikes = initial.clone();
+            let mut hashes = Vec::new();
+            for tick in 0..100 {
+                let result = run_simulation_tick(bikes, tick as f64, 0.1);
+                hashes.push(result.state_hash);
+                bikes = result.bikes;
+            }
+            clear_deterministic_seed();
+            let bits: Vec<(u64, u64, u64)> = bikes
+                .iter()
+                .map(|b| (b.longitude.to_bits(), b.latitude.to_bits(), b.speed.to_bits()))
+                .collect();
+            (bits, hashes)
+        };
+
+        assert_eq!(run(42), run(42), "Same seed should give bit-identical results");
+        assert_ne!(run(42).1, run(7).1, "Different seeds should diverge");
+        assert!(deterministic_random().is_none(), "Cleared seed should disable RNG");
+    }
+
+    #[test]
+    fn test_simulation_config_apply_and_reset() {
+        // Partial configs keep defaults for omitted fields
+        let config: WasmSimulationConfig = serde_json::from_str(
+            r#"{"operationalBounds": [4.40, 4.50, 51.88, 51.93], "speedConfig": {"delivering": [20.0, 20.0], "returning": [12.0, 12.0], "trafficReduction": 0.2}}"#,
+        )
+        .unwrap();
+        assert_eq!(config.transition_matrix, TransitionMatrix::default());
+
+        set_simulation_config(config.clone()).unwrap();
+        assert_eq!(simulation_config(), config);
+
+        let bike = BikePosition {
+            id: "bike-1".to_string(),
+            name: "Jan".to_string(),
+            longitude: 4.9,
+            latitude: 52.37,
+            status: BikeStatus::Delivering,
+            speed: 20.0,
+        };
+        let result = run_simulation_tick(vec![bike], 0.0, 0.0);
+        assert_eq!((result.bikes[0].longitude, result.bikes[0].latitude), (4.50, 51.93));
+        assert_eq!(result.bikes[0].speed, 20.0);
+        assert_eq!(result.bounds_corrections, 1);
+
+        // Invalid configs are rejected as a whole
+        let mut invalid = config.clone();
+        invalid.transition_matrix.idle = [0.5, 0.5, 0.5];
+        assert!(set_simulation_config(invalid).is_err());
+        assert_eq!(simulation_config(), config);
+
+        reset_simulation_config();
+        assert_eq!(simulation_config(), WasmSimulationConfig::default());
+    }
+
+    #[test]
+    fn test_nearest_n_bikes_ordering() {
+        let bike = |id: &str, latitude: f64, status: BikeStatus| BikePosition {
+            id: id.to_string(),
+            name: id.to_string(),
+            longitude: 4.9,
+            latitude,
+            status,
+            speed: 0.0,
+        };
+        let bikes = vec![
+            bike("far", 52.45, BikeStatus::Idle),
+            bike("nearest", 52.371, BikeStatus::Idle),
+            bike("middle", 52.39, BikeStatus::Delivering),
+            bike("second", 52.38, BikeStatus::Idle),
+            bike("farthest", 52.50, BikeStatus::Returning),
+        ];
+        let target = Coordinate { longitude: 4.9, latitude: 52.37 };
+
+        let nearest = nearest_n_bikes(bikes.clone(), &target, 3, None);
+        let ids: Vec<&str> = nearest.iter().map(|r| r.bike.id.as_str()).collect();
+        assert_eq!(ids, vec!["nearest", "second", "middle"]);
+        assert!((nearest[0].estimated_arrival_minutes - nearest[0].distance_km / 25.0 * 60.0).abs() < 1e-9);
+
+        // Fewer matches than requested returns what matches
+        let idle = nearest_n_bikes(bikes, &target, 5, Some(&BikeStatus::Idle));
+        assert_eq!(idle.len(), 3);
+        assert_eq!(idle[2].bike.id, "far");
+    }
+
+    #[test]
+    fn test_point_in_polygon_amsterdam_box() {
+        let coord = |longitude: f64, latitude: f64| Coordinate { longitude, latitude };
+        let (min_lon, max_lon, min_lat, max_lat) = AMSTERDAM_BOUNDS;
+        let amsterdam = vec![
+            coord(min_lon, min_lat),
+            coord(max_lon, min_lat),
+            coord(max_lon, max_lat),
+            coord(min_lon, max_lat),
+        ];
+
+        assert!(point_in_polygon(&coord(4.9003, 52.3791), &amsterdam)); // Centraal
+        assert!(!point_in_polygon(&coord(4.4777, 51.9244), &amsterdam)); // Rotterdam
+        assert!(!point_in_polygon(&coord(5.1214, 52.0907), &amsterdam)); // Utrecht
+        // On an edge and on a vertex
+        assert!(point_in_polygon(&coord(min_lon, 52.37), &amsterdam));
+        assert!(point_in_polygon(&coord(max_lon, max_lat), &amsterdam));
+
+        // Concave zone: an L-shape with the north-east quarter missing
+        let l_shape = vec![
+            coord(4.8, 52.3),
+            coord(5.0, 52.3),
+            coord(5.0, 52.35),
+            coord(4.9, 52.35),
+            coord(4.9, 52.4),
+            coord(4.8, 52.4),
+        ];
+        assert!(point_in_polygon(&coord(4.85, 52.38), &l_shape));
+        assert!(!point_in_polygon(&coord(4.95, 52.38), &l_shape));
+
+        assert!(validate_polygon(&amsterdam[..2]).unwrap_err().contains("at least 3"));
+    }
+
+    #[test]
+    fn test_polygon_area_and_perimeter_of_one_km_square() {
+        // 1 km sides around Dam Square
+        let lat_step = 1.0 / (deg_to_rad(1.0) * EARTH_RADIUS_KM);
+        let lon_step = lat_step / deg_to_rad(52.373).cos();
+        let (lat, lon) = (52.373 - lat_step / 2.0, 4.8932 - lon_step / 2.0);
+        let square = vec![
+            coordinate(lat, lon),
+            coordinate(lat, lon + lon_step),
+            coordinate(lat + lat_step, lon + lon_step),
+            coordinate(lat + lat_step, lon),
+        ];
+
+        assert!((polygon_area_km2(&square) - 1.0).abs() < 0.01);
+        assert!((polygon_perimeter_km(&square) - 4.0).abs() < 0.01);
+
+        // Orientation doesn't matter, and neither does repeating the first vertex
+        let clockwise: Vec<Coordinate> = square.iter().rev().cloned().collect();
+        assert!((polygon_area_km2(&clockwise) - polygon_area_km2(&square)).abs() < 1e-12);
+        let mut closed = square.clone();
+        closed.push(square[0].clone());
+        assert!((polygon_perimeter_km(&closed) - polygon_perimeter_km(&square)).abs() < 1e-12);
+
+        // A symmetric bow tie: the two lobes cancel out
+        let bow_tie = vec![square[0].clone(), square[2].clone(), square[1].clone(), square[3].clone()];
+        assert!(polygon_area_km2(&bow_tie) < 1e-9);
+    }
+
+    #[test]
+    fn test_kmeans_separates_two_groups() {
+        let bike = |id: String, longitude: f64, latitude: f64| BikePosition {
+            name: id.clone(),
+            id,
+            longitude,
+            latitude,
+            status: BikeStatus::Idle,
+            speed: 0.0,
+        };
+        // Five bikes around Sloterdijk (west), five around Amsterdam-Oost
+        let mut bikes: Vec<BikePosition> = (0..5)
+            .map(|i| bike(format!("WEST-{}", i), 4.82 + i as f64 * 0.002, 52.38 + i as f64 * 0.001))
+            .collect();
+        bikes.extend((0..5).map(|i| bike(format!("EAST-{}", i), 4.95 - i as f64 * 0.002, 52.36 - i as f64 * 0.001)));
+
+        for seed in 0..10 {
+            let result = kmeans_clusters(&bikes, 2, 20, &mut SmallRng::seed_from_u64(seed));
+
+            assert_eq!(result.clusters.len(), 2);
+            for cluster in &result.clusters {
+                assert_eq!(cluster.count, 5);
+                let prefix = &cluster.bike_ids[0][..4];
+                assert!(cluster.bike_ids.iter().all(|id| id.starts_with(prefix)));
+            }
+        }
+
+        // More clusters than bikes: one cluster per bike
+        let result = kmeans_clusters(&bikes[..3], 5, 20, &mut SmallRng::seed_from_u64(1));
+        assert_eq!(result.clusters.len(), 3);
+        assert!(result.clusters.iter().all(|c| c.count == 1));
+    }
+
+    #[test]
+    fn test_hex_grid_bins_same_position_together() {
+        let bikes: Vec<BikePosition> = (0..10)
+            .map(|i| BikePosition {
+                id: format!("BIKE-{:04}", i),
+                name: "Dam".to_string(),
+                longitude: 4.8932,
+                latitude: 52.3731,
+                status: if i < 4 { BikeStatus::Delivering } else { BikeStatus::Idle },
+                speed: if i < 4 { 20.0 } else { 0.0 },
+            })
+            .collect();
+
+        let grid = hex_grid(&bikes, 0.5).unwrap();
+
+        assert_eq!(grid.cells.len(), 1);
+        let cell = &grid.cells[0];
+        assert_eq!(cell.count, 10);
+        assert!((cell.avg_speed - 8.0).abs() < 1e-9);
+        assert_eq!(cell.status_breakdown, StatusBreakdown { delivering: 4, idle: 6, returning: 0 });
+        // The bike lies within one cell size of the cell center
+        let offset = haversine_distance(cell.center.latitude, cell.center.longitude, 52.3731, 4.8932);
+        assert!(offset <= 0.5, "bike {} km from center", offset);
+
+        assert!(hex_grid(&bikes, 0.01).is_err());
+        assert!(hex_grid(&bikes, 10.0).is_err());
+        assert!(hex_grid(&[], 1.0).unwrap().cells.is_empty());
+    }
+
+    #[test]
+    fn test_low_battery_order() {
+        let levels = [Some(45.0), Some(8.0), None, Some(15.0), Some(8.0), Some(20.0)];
+
+        assert_eq!(low_battery_order(&levels, 20), vec![1, 4, 3]);
+        assert!(low_battery_order(&levels, 0).is_empty());
+    }
+
+    #[test]
+    fn test_maintenance_priority_order() {
+        let due = [None, Some(3_000.0), Some(1_000.0), None, Some(3_000.0), Some(-5_000.0)];
+
+        assert_eq!(maintenance_priority_order(&due), vec![5, 2, 1, 4, 0, 3]);
+        assert!(maintenance_priority_order(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_delivery_eta() {
+        let bike = BikePosition {
+            id: "BIKE-0001".to_string(),
+            name: "Centraal".to_string(),
+            longitude: 4.9003,
+            latitude: 52.3791,
+            status: BikeStatus::Delivering,
+            speed: 20.0,
+        };
+        let dam = Coordinate { longitude: 4.8932, latitude: 52.3730 };
+        let distance = haversine_distance(52.3791, 4.9003, 52.3730, 4.8932);
+
+        let eta = delivery_eta(&bike, 0.0, &dam);
+        assert_eq!(eta.distance_km, distance);
+        assert!((eta.estimated_minutes - distance / 20.0 * 60.0).abs() < 1e-9);
+        assert_eq!(eta.confidence, 1.0);
+
+        // A stopped bike is assumed to ride at the middle of the delivering range
+        let stopped = BikePosition { speed: 0.0, ..bike };
+        let eta = delivery_eta(&stopped, ETA_CONFIDENCE_HALF_LIFE_MINUTES, &dam);
+        assert!((eta.estimated_minutes - distance / 25.0 * 60.0).abs() < 1e-9);
+        assert!(eta.estimated_minutes.is_finite());
+        assert!((eta.confidence - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_trajectory_clamps_to_bounds() {
+        let bounds = [4.85, 4.95, 52.35, 52.40];
+        let bike = BikePosition {
+            id: "BIKE-0001".to_string(),
+            name: "Centraal".to_string(),
+            longitude: 4.94,
+            latitude: 52.39,
+            status: BikeStatus::Delivering,
+            speed: 20.0,
+        };
+        // Heading north-east at 0.004 degrees per step
+        let previous = Coordinate { longitude: 4.936, latitude: 52.386 };
+
+        let result = bike_trajectory(&bike, &previous, 5, 1000.0, bounds).unwrap();
+
+        assert_eq!(result.positions.len(), 5);
+        assert!((result.positions[0].longitude - 4.944).abs() < 1e-9);
+        assert!((result.positions[1].latitude - 52.398).abs() < 1e-9);
+        // From step 3 on the bike would leave the bounds on both axes
+        for position in &result.positions[2..] {
+            assert_eq!((position.longitude, position.latitude), (4.95, 52.40));
+        }
+        assert_eq!(result.confidence_decay[0], 1.0);
+        assert!((result.confidence_decay[4] - 1.0 / 1.4).abs() < 1e-12);
+        assert!(bike_trajectory(&bike, &previous, 5, 0.0, bounds).is_err());
+    }
+
+    #[test]
+    fn test_trajectory_of_stationary_bike() {
+        let bike = BikePosition {
+            id: "BIKE-0002".to_string(),
+            name: "Dam".to_string(),
+            longitude: 4.8932,
+            latitude: 52.3731,
+            status: BikeStatus::Idle,
+            speed: 0.0,
+        };
+        let previous = Coordinate { longitude: 4.8932, latitude: 52.3731 };
+
+        let result = bike_trajectory(&bike, &previous, 3, 500.0, [4.7, 5.1, 52.2, 52.5]).unwrap();
+
+        assert!(result
+            .positions
+            .iter()
+            .all(|p| (p.longitude, p.latitude) == (4.8932, 52.3731)));
+        assert_eq!(result.estimated_arrival_km, 0.0);
+    }
+
+    #[test]
+    fn test_convex_hull_of_square() {
+        // ~1.11 km square around Dam Square: 0.01° of latitude, and the
+        // longitude span that covers the same distance at this latitude
+        let lng_span = 0.01 / deg_to_rad(52.37).cos();
+        let bike = |id: &str, longitude: f64, latitude: f64| BikePosition {
+            id: id.to_string(),
+            name: id.to_string(),
+            longitude,
+            latitude,
+            status: BikeStatus::Delivering,
+            speed: 15.0,
+        };
+        let bikes = vec![
+            bike("ne", 4.89 + lng_span, 52.38),
+            bike("sw", 4.89, 52.37),
+            bike("inside", 4.89 + lng_span / 2.0, 52.375),
+            bike("se", 4.89 + lng_span, 52.37),
+            bike("nw", 4.89, 52.38),
+            bike("on-edge", 4.89, 52.375),
+        ];
+
+        let result = fleet_convex_hull(&bikes).unwrap();
+
+        let side_km = deg_to_rad(0.01) * EARTH_RADIUS_KM;
+        let corners: Vec<(f64, f64)> =
+            result.hull_points.iter().map(|p| (p.longitude, p.latitude)).collect();
+        assert_eq!(
+            corners,
+            vec![(4.89, 52.37), (4.89 + lng_span, 52.37), (4.89 + lng_span, 52.38), (4.89, 52.38)]
+        );
+        assert!((result.perimeter_km - 4.0 * side_km).abs() < 0.01, "got {}", result.perimeter_km);
+        assert!((result.area_km2 - side_km * side_km).abs() < 0.01, "got {}", result.area_km2);
+    }
+
+    #[test]
+    fn test_convex_hull_needs_three_spread_bikes() {
+        let bike = |longitude: f64| BikePosition {
+            id: "BIKE".to_string(),
+            name: "Bike".to_string(),
+            longitude,
+            latitude: 52.37,
+            status: BikeStatus::Idle,
+            speed: 0.0,
+        };
+        assert!(fleet_convex_hull(&[bike(4.88), bike(4.89)]).is_err());
+        assert!(fleet_convex_hull(&[bike(4.88), bike(4.89), bike(4.90)]).is_err());
+    }
+
+    fn fleet_with_speeds(speeds: &[f64]) -> Vec<BikePosition> {
+        speeds
+            .iter()
+            .enumerate()
+            .map(|(i, &speed)| BikePosition {
+                id: format!("BIKE-{:04}", i + 1),
+                name: format!("Bike {}", i + 1),
+                longitude: 4.89,
+                latitude: 52.37,
+                status: BikeStatus::Delivering,
+                speed,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_speed_anomaly_flags_gps_glitch() {
+        let bikes = fleet_with_speeds(&[15.0, 18.0, 20.0, 22.0, 200.0, 17.0, 19.0, 21.0, 16.0, 23.0]);
+
+        // One outlier among 10 bikes can reach at most z = 9 / sqrt(10) ≈ 2.85
+        let report = speed_anomalies(&bikes, 2.5);
+
+        assert_eq!(report.anomalies.len(), 1);
+        assert_eq!(report.anomalies[0].bike_id, "BIKE-0005");
+        assert!(report.anomalies[0].z_score > 2.8);
+        assert!((report.fleet_mean_speed - 37.1).abs() < 1e-9);
+        assert!(speed_anomalies(&bikes, 3.0).anomalies.is_empty());
+    }
+
+    #[test]
+    fn test_speed_anomaly_zero_std_dev() {
+        let report = speed_anomalies(&fleet_with_speeds(&[18.0; 10]), 0.5);
+
+        assert!(report.anomalies.is_empty());
+        assert_eq!(report.fleet_std_dev, 0.0);
+    }
+
+    fn hour(hour: u8, active: u32, completed: u32) -> HourlyUtilization {
+        HourlyUtilization {
+            hour,
+            active_deliveries: active,
+            completed_deliveries: completed,
+            utilization_ratio: 0.5,
+        }
+    }
+
+    #[test]
+    fn test_peak_hours_ranks_busiest_first() {
+        let hourly = vec![hour(8, 2, 4), hour(12, 10, 14), hour(13, 3, 3), hour(18, 5, 19), hour(22, 0, 0)];
+
+        let result = peak_hours(&hourly).unwrap();
+
+        // 12:00 and 18:00 tie on 24 deliveries; the earlier hour comes first
+        let hours: Vec<u8> = result.peak_hours.iter().map(|p| p.hour).collect();
+        assert_eq!(hours, vec![12, 18, 8]);
+        assert!((result.peak_hours[0].load_factor - 24.0 / (60.0 / 24.0)).abs() < 1e-9);
+        assert_eq!(result.staffing_shares.len(), 24);
+        assert!((result.staffing_shares.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        assert_eq!(result.staffing_shares[22], 0.0);
+    }
+
+    #[test]
+    fn test_peak_hours_rejects_invalid_hour() {
+        assert!(peak_hours(&[hour(24, 1, 0)]).is_err());
+        assert!(peak_hours(&[]).unwrap().peak_hours.is_empty());
+    }
+
+    #[test]
+    fn test_smooth_positions_alpha_bounds() {
+        let bike = |id: &str, longitude: f64, latitude: f64, speed: f64| BikePosition {
+            id: id.to_string(),
+            name: id.to_string(),
+            longitude,
+            latitude,
+            status: BikeStatus::Delivering,
+            speed,
+        };
+        let prev = vec![bike("a", 4.90, 52.37, 10.0), bike("gone", 4.95, 52.40, 5.0)];
+        let current = vec![bike("a", 4.92, 52.38, 20.0), bike("new", 4.88, 52.36, 15.0)];
+        let coords = |bikes: &[BikePosition]| -> Vec<(String, f64, f64, f64)> {
+            bikes
+                .iter()
+                .map(|b| (b.id.clone(), b.longitude, b.latitude, b.speed))
+                .collect()
+        };
+
+        // alpha 0 keeps the previous frame, alpha 1 the current one
+        let all_prev = smooth_positions(current.clone(), &prev, 0.0);
+        assert_eq!(
+            coords(&all_prev),
+            vec![("a".to_string(), 4.90, 52.37, 10.0), ("new".to_string(), 4.88, 52.36, 15.0)]
+        );
+        let all_current = smooth_positions(current.clone(), &prev, 1.0);
+        assert_eq!(coords(&all_current), coords(&current));
+
+        // Out-of-range alpha is clamped
+        assert_eq!(coords(&smooth_positions(current.clone(), &prev, -3.0)), coords(&all_prev));
+        assert_eq!(coords(&smooth_positions(current.clone(), &prev, 7.0)), coords(&current));
+
+        let half = smooth_positions(current, &prev, 0.5);
+        assert!((half[0].longitude - 4.91).abs() < 1e-12);
+        assert!((half[0].latitude - 52.375).abs() < 1e-12);
+        assert!((half[0].speed - 15.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_accelerations_flag_sudden_stops_and_position_mismatches() {
+        let bike = |id: &str, latitude: f64, speed: f64| BikePosition {
+            id: id.to_string(),
+            name: id.to_string(),
+            longitude: 4.9,
+            latitude,
+            status: BikeStatus::Delivering,
+            speed,
+        };
+        let prev = vec![
+            bike("steady", 52.37, 20.0),
+            bike("stop", 52.36, 25.0),
+            bike("teleport", 52.35, 20.0),
+        ];
+        // 0.0001 degrees of latitude is about 11 m, i.e. 20 km/h over 2 s
+        let current = vec![
+            bike("steady", 52.3701, 22.0),
+            bike("stop", 52.36, 0.0),
+            bike("teleport", 52.36, 20.0),
+            bike("new", 52.37, 30.0),
+        ];
+
+        let report = accelerations(&current, &prev, 2.0);
+        let ids: Vec<&str> = report.bikes.iter().map(|b| b.bike_id.as_str()).collect();
+        assert_eq!(ids, vec!["steady", "stop", "teleport"]);
+
+        let steady = &report.bikes[0];
+        assert!((steady.acceleration - 1.0).abs() < 1e-12);
+        assert!(!steady.is_anomalous);
+        assert!((steady.computed_speed - 20.0).abs() < 0.1);
+        assert!(!steady.speed_discrepancy);
+
+        let stop = &report.bikes[1];
+        assert!((stop.acceleration + 12.5).abs() < 1e-12);
+        assert!(stop.is_anomalous);
+        assert_eq!(stop.computed_speed, 0.0);
+        assert!(!stop.speed_discrepancy);
+
+        let teleport = &report.bikes[2];
+        assert_eq!(teleport.acceleration, 0.0);
+        assert!(!teleport.is_anomalous);
+        assert!(teleport.computed_speed > 1000.0);
+        assert!(teleport.speed_discrepancy);
+    }
+
+    /// Google's reference polyline from the Encoded Polyline Algorithm docs
+    const GOOGLE_POLYLINE: &str = "_p~iF~ps|U_ulLnnqC_mqNvxq`@";
+
+    #[test]
+    fn test_decode_google_reference_polyline() {
+        let coords = decode_polyline_coords(GOOGLE_POLYLINE).unwrap();
+        let pairs: Vec<(f64, f64)> = coords.iter().map(|c| (c.latitude, c.longitude)).collect();
+        assert_eq!(pairs, vec![(38.5, -120.2), (40.7, -120.95), (43.252, -126.453)]);
+    }
+
+    #[test]
+    fn test_encode_polyline_round_trips() {
+        let coords = vec![
+            Coordinate { longitude: -120.2, latitude: 38.5 },
+            Coordinate { longitude: -120.95, latitude: 40.7 },
+            Coordinate { longitude: -126.453, latitude: 43.252 },
+        ];
+        assert_eq!(encode_polyline_coords(&coords), GOOGLE_POLYLINE);
+        assert_eq!(encode_polyline_coords(&[]), "");
+        assert!(decode_polyline_coords("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_decode_polyline_rejects_malformed_input() {
+        // Cut off in the middle of the last longitude
+        assert!(decode_polyline_coords("_p~iF~ps|U_ulLnnqC_mqNvxq").is_err());
+        // Latitude without a longitude
+        assert!(decode_polyline_coords("_p~iF").is_err());
+        assert!(decode_polyline_coords("_p~iF ps|U").is_err());
+    }
+
+    #[test]
+    fn test_polyline_path_length() {
+        let coords = decode_polyline_coords(GOOGLE_POLYLINE).unwrap();
+        let expected = haversine_distance(38.5, -120.2, 40.7, -120.95)
+            + haversine_distance(40.7, -120.95, 43.252, -126.453);
+        assert!((path_length_km(&coords) - expected).abs() < 1e-9);
+        // Roughly 250 km plus 540 km
+        assert!((780.0..800.0).contains(&path_length_km(&coords)));
+    }
+
+    #[test]
+    fn test_route_length_sums_consecutive_legs() {
+        let route = [
+            coordinate(52.3791, 4.9003),
+            coordinate(52.3731, 4.8932),
+            coordinate(52.3579, 4.8686),
+        ];
+        let expected = haversine_distance(52.3791, 4.9003, 52.3731, 4.8932)
+            + haversine_distance(52.3731, 4.8932, 52.3579, 4.8686);
+        assert!((path_length_km(&route) - expected).abs() < 1e-12);
+
+        // Going back the same way doubles it
+        let there_and_back: Vec<Coordinate> =
+            route.iter().chain(route.iter().rev().skip(1)).cloned().collect();
+        assert!((path_length_km(&there_and_back) - 2.0 * expected).abs() < 1e-12);
+
+        assert_eq!(path_length_km(&route[..1]), 0.0);
+        assert_eq!(path_length_km(&[]), 0.0);
+    }
+
+    #[test]
+    fn test_weighted_center_of_three_bikes() {
+        let bike = |longitude: f64, latitude: f64| BikePosition {
+            id: "bike".to_string(),
+            name: "Bike".to_string(),
+            longitude,
+            latitude,
+            status: BikeStatus::Idle,
+            speed: 0.0,
+        };
+        let bikes = vec![bike(4.88, 52.36), bike(4.90, 52.37), bike(4.92, 52.38)];
+
+        // (1 * 4.88 + 2 * 4.90 + 1 * 4.92) / 4 = 4.90; (1 * 52.36 + 2 * 52.37 + 1 * 52.38) / 4 = 52.37
+        let even = weighted_center(&bikes, &[1.0, 2.0, 1.0]).unwrap();
+        assert!((even.longitude - 4.90).abs() < 1e-12);
+        assert!((even.latitude - 52.37).abs() < 1e-12);
+        assert_eq!(even.total_weight, 4.0);
+
+        // (3 * 4.88 + 0 * 4.90 + 1 * 4.92) / 4 = 4.89; (3 * 52.36 + 1 * 52.38) / 4 = 52.365
+        let skewed = weighted_center(&bikes, &delivery_count_weights(&[6, 0, 2])).unwrap();
+        assert!((skewed.longitude - 4.89).abs() < 1e-12);
+        assert!((skewed.latitude - 52.365).abs() < 1e-12);
+        assert!((skewed.total_weight - 1.0).abs() < 1e-12);
+
+        // No weight at all: plain centroid
+        let plain = weighted_center(&bikes, &delivery_count_weights(&[0, 0, 0])).unwrap();
+        assert!((plain.longitude - 4.90).abs() < 1e-12);
+        assert!((plain.latitude - 52.37).abs() < 1e-12);
+        assert_eq!(plain.total_weight, 0.0);
+
+        assert!(weighted_center(&bikes, &[1.0, 1.0]).is_err());
+        assert!(weighted_center(&bikes, &[1.0, -1.0, 1.0]).is_err());
+        assert!(weighted_center(&[], &[]).is_err());
+    }
+
+    #[test]
+    fn test_zone_tie_goes_to_lower_hub_index() {
+        let bike = |id: &str, longitude: f64| BikePosition {
+            id: id.to_string(),
+            name: id.to_string(),
+            longitude,
+            latitude: 52.37,
+            status: BikeStatus::Idle,
+            speed: 0.0,
+        };
+        // Binary fractions, so MIDDLE is exactly as far from both hubs
+        let hubs = [coordinate(52.37, 4.875), coordinate(52.37, 4.9375)];
+        let bikes = [bike("WEST", 4.876), bike("MIDDLE", 4.90625), bike("EAST", 4.937)];
+
+        let zones = assign_zones(&bikes, &hubs).unwrap().assignments;
+        let hub_indexes: Vec<u32> = zones.iter().map(|z| z.hub_index).collect();
+        assert_eq!(hub_indexes, vec![0, 0, 1]);
+        assert_eq!(zones[1].bike_id, "MIDDLE");
+        assert_eq!(zones[1].hub_distance_km, haversine_distance(52.37, 4.90625, 52.37, 4.9375));
+        assert_eq!(zones[1].hub_distance_km, haversine_distance(52.37, 4.90625, 52.37, 4.875));
+
+        // Same tie with the hubs swapped still picks index 0
+        let swapped = [hubs[1].clone(), hubs[0].clone()];
+        assert_eq!(assign_zones(&bikes[1..2], &swapped).unwrap().assignments[0].hub_index, 0);
+
+        assert!(assign_zones(&bikes, &[]).is_err());
+    }
+
+    #[test]
+    fn test_zone_load_counts_and_variance() {
+        let zone = |hub_index: u32| BikeZone {
+            bike_id: format!("BIKE-{}", hub_index),
+            hub_index,
+            hub_distance_km: 1.0,
+        };
+        let assignments = [zone(0), zone(0), zone(0), zone(2)];
+
+        let load = zone_load(&assignments, 3).unwrap();
+        assert_eq!(load.bike_counts, vec![3, 0, 1]);
+        assert!((load.mean_load - 4.0 / 3.0).abs() < 1e-12);
+        // ((3 - 4/3)² + (4/3)² + (1 - 4/3)²) / 3 = 14/9
+        assert!((load.load_variance - 14.0 / 9.0).abs() < 1e-12);
+
+        let balanced = zone_load(&[zone(0), zone(1)], 2).unwrap();
+        assert_eq!(balanced.load_variance, 0.0);
+
+        assert!(zone_load(&assignments, 2).is_err());
+        assert!(zone_load(&[], 0).is_err());
+    }
+
+    #[test]
+    fn test_dispatch_only_sends_idle_bikes() {
+        let bike = |id: &str, longitude: f64, status: BikeStatus| BikePosition {
+            id: id.to_string(),
+            name: id.to_string(),
+            longitude,
+            latitude: 52.37,
+            status,
+            speed: 0.0,
+        };
+        let pickup = |longitude: f64| Coordinate { longitude, latitude: 52.37 };
+        let bikes = vec![
+            // Closest to both pickups, but busy
+            bike("BUSY", 4.900, BikeStatus::Delivering),
+            bike("NEAR", 4.901, BikeStatus::Idle),
+            bike("RETURNING", 4.899, BikeStatus::Returning),
+            bike("FAR", 4.950, BikeStatus::Idle),
+        ];
+        let pickups = vec![pickup(4.940), pickup(4.900), pickup(4.800)];
+
+        let dispatch = greedy_dispatch(&bikes, &pickups);
+
+        // NEAR takes the pickup next to it first, so FAR gets 4.940;
+        // 4.800 is left over with only two idle bikes
+        let ids: Vec<&str> = dispatch.assignments.iter().map(|a| a.bike_id.as_str()).collect();
+        assert_eq!(ids, ["FAR", "NEAR"]);
+        assert_eq!(dispatch.assignments[1].pickup_coordinate.longitude, 4.900);
+        assert_eq!(dispatch.unassigned_pickups.len(), 1);
+        assert_eq!(dispatch.unassigned_pickups[0].longitude, 4.800);
+
+        let expected = haversine_distance(52.37, 4.950, 52.37, 4.940) + haversine_distance(52.37, 4.901, 52.37, 4.900);
+        assert!((dispatch.total_distance_km - expected).abs() < 1e-12);
+
+        let no_idle = greedy_dispatch(&bikes[..1], &pickups);
+        assert!(no_idle.assignments.is_empty());
+        assert_eq!(no_idle.unassigned_pickups.len(), 3);
+    }
+
+    #[test]
+    fn test_vincenty_direct_reverses_inverse() {
+        // Flinders Peak: 54 972.271 m at 306°52'05.37" reaches Buninyong
+        let dms = |d: f64, m: f64, s: f64| d + m / 60.0 + s / 3600.0;
+        let from = coordinate(-dms(37.0, 57.0, 3.72030), dms(144.0, 25.0, 29.52440));
+
+        let to = vincenty_direct(&from, dms(306.0, 52.0, 5.37), 54972.271);
+
+        assert!((to.latitude + dms(37.0, 39.0, 10.15610)).abs() < 1e-7, "got {}", to.latitude);
+        assert!((to.longitude - dms(143.0, 55.0, 35.38390)).abs() < 1e-7, "got {}", to.longitude);
+    }
+
+    #[test]
+    fn test_isochrone_reaches_ten_km_in_half_an_hour() {
+        let bike = BikePosition {
+            id: "BIKE-0001".to_string(),
+            name: "Centraal".to_string(),
+            longitude: 4.9003,
+            latitude: 52.3791,
+            status: BikeStatus::Delivering,
+            speed: 20.0,
+        };
+
+        let result = isochrone(&bike, 30.0, 72).unwrap();
+
+        assert_eq!(result.polygon.len(), 73);
+        let (first, last) = (&result.polygon[0], &result.polygon[72]);
+        assert_eq!((first.longitude, first.latitude), (last.longitude, last.latitude));
+        assert!((result.reach_km - 10.0).abs() < 1e-12);
+        for vertex in &result.polygon {
+            let distance_m = vincenty_inverse(&coordinate(bike.latitude, bike.longitude), vertex).distance_m;
+            assert!((distance_m - 10_000.0).abs() < 0.001, "got {}", distance_m);
+        }
+        // A 72-gon covers 99.9% of its circle; the planar area within 1%
+        let circle_km2 = std::f64::consts::PI * 100.0;
+        assert!((result.reachable_area_km2 / circle_km2 - 1.0).abs() < 0.01, "got {}", result.reachable_area_km2);
+
+        let stationary = isochrone(&BikePosition { speed: 0.0, ..bike.clone() }, 30.0, 8).unwrap();
+        assert_eq!(stationary.polygon.len(), 9);
+        assert_eq!(stationary.reachable_area_km2, 0.0);
+
+        assert!(isochrone(&bike, 30.0, 2).is_err());
+        assert!(isochrone(&bike, -1.0, 8).is_err());
+    }
+
+    #[test]
+    fn test_name_matches() {
+        assert!(name_matches("Amsterdam Vondelpark Bike", "vondel"));
+        assert!(name_matches("Amsterdam Vondelpark Bike", "  PARK "));
+        assert!(name_matches("Dam", ""));
+        assert!(!name_matches("Dam", "Damrak"));
+    }
 }
@@ -0,0 +1,33 @@
+// JS-facing smoke test without wasm-bindgen-test: loads the Node.js build
+// (`npm run build:node`) exactly as another JavaScript project would.
+//
+// Run with: npm test
+
+import assert from 'node:assert/strict';
+import { createRequire } from 'node:module';
+import { test } from 'node:test';
+
+const require = createRequire(import.meta.url);
+const wasm = require('../pkg-node/amsterdam_bike_fleet_wasm.js');
+
+test('calculateFleetStatistics with a 2-bike fleet', () => {
+  const stats = wasm.calculateFleetStatistics([
+    { id: 'BIKE-0001', name: 'Centraal', longitude: 4.9003, latitude: 52.3791, status: 'delivering', speed: 20 },
+    { id: 'BIKE-0002', name: 'Dam', longitude: 4.8933, latitude: 52.3731, status: 'idle', speed: 0 },
+  ]);
+
+  assert.equal(stats.totalBikes, 2);
+  assert.equal(stats.deliveringCount, 1);
+  assert.equal(stats.idleCount, 1);
+  assert.equal(stats.returningCount, 0);
+  assert.equal(stats.averageSpeed, 10);
+  assert.equal(stats.maxSpeed, 20);
+  assert.equal(stats.minSpeed, 0);
+  assert.equal(stats.activePercentage, 50);
+  assert.ok(Math.abs(stats.fleetCenterLatitude - 52.3761) < 1e-9);
+  assert.ok(Math.abs(stats.fleetCenterLongitude - 4.8968) < 1e-9);
+});
+
+test('calculateFleetStatistics rejects malformed input', () => {
+  assert.throws(() => wasm.calculateFleetStatistics([{ id: 'BIKE-0001' }]));
+});
@@ -0,0 +1,467 @@
+//! JS-facing API tests, run as real WASM in Node.js
+//!
+//! The unit tests in `src/lib.rs` run natively and never go through
+//! `serde_wasm_bindgen`. These call the exported functions with `JsValue`s
+//! built from JSON, then `JSON.stringify` the result and parse it back,
+//! which is exactly what the Angular side sees.
+//!
+//! ```bash
+//! wasm-pack test --node
+//! ```
+//!
+//! Node is wasm-bindgen-test's default runner, so no
+//! `wasm_bindgen_test_configure!` is needed.
+
+#![cfg(target_arch = "wasm32")]
+
+use amsterdam_bike_fleet_wasm::*;
+use serde::de::DeserializeOwned;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::*;
+
+/// Centraal Station, Dam Square and Vondelpark, one bike per status
+const FLEET_JSON: &str = r#"[
+    {"id": "BIKE-0001", "name": "Centraal", "longitude": 4.9003, "latitude": 52.3791, "status": "delivering", "speed": 18.0},
+    {"id": "BIKE-0002", "name": "Dam", "longitude": 4.8932, "latitude": 52.3731, "status": "idle", "speed": 0.0},
+    {"id": "BIKE-0003", "name": "Vondelpark", "longitude": 4.8686, "latitude": 52.3579, "status": "returning", "speed": 12.0}
+]"#;
+
+const CENTRAAL_JSON: &str = r#"{"longitude": 4.9003, "latitude": 52.3791}"#;
+const DAM_JSON: &str = r#"{"longitude": 4.8932, "latitude": 52.3731}"#;
+
+fn js(json: &str) -> JsValue {
+    js_sys::JSON::parse(json).expect("valid test JSON")
+}
+
+/// JSON text of a returned value, as the frontend would serialize it
+fn stringify(value: &JsValue) -> String {
+    js_sys::JSON::stringify(value)
+        .expect("result is JSON-serializable")
+        .into()
+}
+
+fn parse<T: DeserializeOwned>(value: &JsValue) -> T {
+    serde_json::from_str(&stringify(value)).expect("result parses back to the Rust type")
+}
+
+#[wasm_bindgen_test]
+fn calculate_fleet_statistics_three_bikes() {
+    let stats: FleetStatistics = parse(&calculate_fleet_statistics(js(FLEET_JSON)).unwrap());
+
+    assert_eq!(stats.total_bikes, 3);
+    assert_eq!(stats.delivering_count, 1);
+    assert_eq!(stats.idle_count, 1);
+    assert_eq!(stats.returning_count, 1);
+    assert!((stats.average_speed - 10.0).abs() < 1e-9);
+    assert_eq!(stats.max_speed, 18.0);
+    assert_eq!(stats.min_speed, 0.0);
+}
+
+#[wasm_bindgen_test]
+fn fleet_center_by_delivery_count_skips_idle_bike() {
+    // Centraal 1 delivery, Dam none, Vondelpark 3: (4.9003 + 3 * 4.8686) / 4, (52.3791 + 3 * 52.3579) / 4
+    let center: WeightedCenterResult =
+        parse(&calculate_fleet_center_by_delivery_count(js(FLEET_JSON), js("[1, 0, 3]")).unwrap());
+
+    assert!((center.longitude - 4.876525).abs() < 1e-9);
+    assert!((center.latitude - 52.3632).abs() < 1e-9);
+    assert!((center.total_weight - 1.0).abs() < 1e-9);
+
+    assert!(calculate_weighted_fleet_center(js(FLEET_JSON), js("[1, 2]")).is_err());
+}
+
+#[wasm_bindgen_test]
+fn validate_bike_data_out_of_bounds() {
+    let bike = js(
+        r#"{"id": "BIKE-0001", "name": "Lost", "longitude": 5.5, "latitude": 52.37, "status": "idle", "speed": 0.0}"#,
+    );
+    let value = validate_bike_data(bike).unwrap();
+    let result: ValidationResult = parse(&value);
+
+    assert!(!result.is_valid);
+    assert_eq!(result.errors.len(), 1);
+    assert!(result.errors[0].contains("Longitude"));
+    assert!(result.sanitized_data.is_none());
+
+    // TypeScript declares `sanitizedData: BikePosition | null`
+    assert!(stringify(&value).contains(r#""sanitizedData":null"#));
+}
+
+#[wasm_bindgen_test]
+fn calculate_distance_centraal_to_dam() {
+    let result: DistanceResult = parse(&calculate_distance(js(CENTRAAL_JSON), js(DAM_JSON)).unwrap());
+
+    assert!(result.distance_km > 0.75 && result.distance_km < 0.9);
+    assert!((result.distance_miles - result.distance_km * 0.621371).abs() < 1e-9);
+    // Dam Square is south-west of Centraal Station
+    assert!(result.bearing_degrees > 180.0 && result.bearing_degrees < 270.0);
+}
+
+#[wasm_bindgen_test]
+fn vincenty_distance_centraal_to_dam() {
+    let result: VincentyResult = parse(&vincenty_distance(js(CENTRAAL_JSON), js(DAM_JSON)).unwrap());
+
+    assert!(result.converged);
+    assert!((result.distance_m - 824.34).abs() < 5.0);
+
+    let batch: Vec<VincentyResult> = parse(
+        &vincenty_distance_batch(
+            js(&format!("[{}, {}]", CENTRAAL_JSON, DAM_JSON)),
+            js(&format!("[{}, {}]", DAM_JSON, DAM_JSON)),
+        )
+        .unwrap(),
+    );
+    assert_eq!(batch[0].distance_m, result.distance_m);
+    assert_eq!(batch[1].distance_m, 0.0);
+    assert!(vincenty_distance_batch(js(&format!("[{}]", CENTRAAL_JSON)), js("[]")).is_err());
+}
+
+#[wasm_bindgen_test]
+fn calculate_isochrone_closed_ring() {
+    let bike = js(
+        r#"{"id": "BIKE-0001", "name": "Centraal", "longitude": 4.9003, "latitude": 52.3791, "status": "delivering", "speed": 20.0}"#,
+    );
+    let result: IsochroneResult = parse(&calculate_isochrone(bike, 30.0, 36).unwrap());
+
+    assert_eq!(result.polygon.len(), 37);
+    assert_eq!(result.polygon[0].latitude, result.polygon[36].latitude);
+    assert_eq!(result.polygon[0].longitude, result.polygon[36].longitude);
+    assert!((result.reach_km - 10.0).abs() < 1e-9);
+    assert!(result.reachable_area_km2 > 300.0 && result.reachable_area_km2 < 320.0);
+}
+
+#[wasm_bindgen_test]
+fn polyline_round_trip_through_js() {
+    let google = "_p~iF~ps|U_ulLnnqC_mqNvxq`@";
+    let coords = decode_polyline(google).unwrap();
+    let decoded: Vec<Coordinate> = parse(&coords);
+    assert_eq!(decoded.len(), 3);
+    assert_eq!((decoded[2].latitude, decoded[2].longitude), (43.252, -126.453));
+
+    assert_eq!(encode_polyline(coords).unwrap().as_string().as_deref(), Some(google));
+
+    let length_km = polyline_length(google).unwrap().as_f64().unwrap();
+    assert!((780.0..800.0).contains(&length_km));
+    assert!(polyline_length("_p~iF").is_err());
+}
+
+#[wasm_bindgen_test]
+fn calculate_route_length_of_recorded_waypoints() {
+    let waypoints = js(
+        r#"[
+            {"id": "WPT-1", "deliveryId": "DEL-0001", "sequence": 1, "latitude": 52.3791, "longitude": 4.9003, "recordedAt": "2026-01-01T12:00:00Z"},
+            {"id": "WPT-2", "deliveryId": "DEL-0001", "sequence": 2, "latitude": 52.3731, "longitude": 4.8932, "recordedAt": "2026-01-01T12:02:00Z"},
+            {"id": "WPT-3", "deliveryId": "DEL-0001", "sequence": 3, "latitude": 52.3579, "longitude": 4.8686, "recordedAt": "2026-01-01T12:06:00Z"}
+        ]"#,
+    );
+    let length_km = calculate_route_length(waypoints).unwrap();
+
+    // Centraal to Dam is about 0.85 km, Dam to Vondelpark about 2.4 km
+    assert!((3.0..3.5).contains(&length_km));
+    assert_eq!(calculate_route_length(js("[]")).unwrap(), 0.0);
+    assert!(calculate_route_length(js(r#"[{"latitude": 52.37}]"#)).is_err());
+}
+
+#[wasm_bindgen_test]
+fn find_bikes_in_radius_one_km() {
+    let bikes: Vec<BikePosition> =
+        parse(&find_bikes_in_radius(js(FLEET_JSON), js(CENTRAAL_JSON), 1.0).unwrap());
+
+    let ids: Vec<&str> = bikes.iter().map(|b| b.id.as_str()).collect();
+    assert_eq!(ids, vec!["BIKE-0001", "BIKE-0002"]);
+}
+
+#[wasm_bindgen_test]
+fn compute_optimal_dispatch_sends_only_idle_bike() {
+    // BIKE-0002 (Dam) is the only idle bike; Centraal's delivering bike stays put
+    let pickups = js(&format!("[{}, {}]", CENTRAAL_JSON, DAM_JSON));
+    let dispatch: DispatchAssignment = parse(&compute_optimal_dispatch(js(FLEET_JSON), pickups).unwrap());
+
+    assert_eq!(dispatch.assignments.len(), 1);
+    assert_eq!(dispatch.assignments[0].bike_id, "BIKE-0002");
+    assert_eq!(dispatch.assignments[0].distance_km, 0.0);
+    assert_eq!(dispatch.unassigned_pickups.len(), 1);
+    assert_eq!(dispatch.unassigned_pickups[0].latitude, 52.3791);
+}
+
+#[wasm_bindgen_test]
+fn assign_bikes_to_zones_and_load() {
+    // Hubs at Centraal and Vondelpark; Dam is closer to Centraal
+    let hubs = js(r#"[{"longitude": 4.9003, "latitude": 52.3791}, {"longitude": 4.8686, "latitude": 52.3579}]"#);
+    let zones: ZoneAssignment = parse(&assign_bikes_to_zones(js(FLEET_JSON), hubs).unwrap());
+
+    let hub_indexes: Vec<u32> = zones.assignments.iter().map(|z| z.hub_index).collect();
+    assert_eq!(hub_indexes, vec![0, 0, 1]);
+    assert_eq!(zones.assignments[2].hub_distance_km, 0.0);
+
+    let assignments = js(&serde_json::to_string(&zones.assignments).unwrap());
+    let load: ZoneLoad = parse(&calculate_zone_load(assignments, 2).unwrap());
+    assert_eq!(load.bike_counts, vec![2, 1]);
+    assert!((load.load_variance - 0.25).abs() < 1e-12);
+    assert!(assign_bikes_to_zones(js(FLEET_JSON), js("[]")).is_err());
+}
+
+#[wasm_bindgen_test]
+fn find_bikes_in_zone_city_centre() {
+    // Roughly the canal ring: Centraal and Dam inside, Vondelpark outside
+    let centre = js(r#"[
+        {"longitude": 4.88, "latitude": 52.365},
+        {"longitude": 4.91, "latitude": 52.365},
+        {"longitude": 4.91, "latitude": 52.385},
+        {"longitude": 4.88, "latitude": 52.385}
+    ]"#);
+
+    let bikes: Vec<BikePosition> = parse(&find_bikes_in_zone(js(FLEET_JSON), centre.clone()).unwrap());
+
+    let ids: Vec<&str> = bikes.iter().map(|b| b.id.as_str()).collect();
+    assert_eq!(ids, vec!["BIKE-0001", "BIKE-0002"]);
+    assert!(is_point_in_polygon(js(DAM_JSON), centre).unwrap());
+
+    let line = js(&format!("[{}, {}]", CENTRAAL_JSON, DAM_JSON));
+    let err = is_point_in_polygon(js(DAM_JSON), line).unwrap_err();
+    assert!(err.as_string().unwrap().contains("at least 3 vertices"));
+}
+
+#[wasm_bindgen_test]
+fn polygon_area_and_perimeter_near_dam() {
+    // About 1 km on each side: 0.009° of latitude, 0.01473° of longitude at 52.37°N
+    let square = js(r#"[
+        {"longitude": 4.8858, "latitude": 52.3685},
+        {"longitude": 4.90053, "latitude": 52.3685},
+        {"longitude": 4.90053, "latitude": 52.3775},
+        {"longitude": 4.8858, "latitude": 52.3775}
+    ]"#);
+
+    let area_km2 = calculate_polygon_area(square.clone()).unwrap();
+    assert!((0.99..1.01).contains(&area_km2));
+    let perimeter_km = calculate_polygon_perimeter(square).unwrap();
+    assert!((3.98..4.02).contains(&perimeter_km));
+
+    let line = js(&format!("[{}, {}]", CENTRAAL_JSON, DAM_JSON));
+    assert!(calculate_polygon_area(line.clone()).is_err());
+    assert!(calculate_polygon_perimeter(line).is_err());
+}
+
+#[wasm_bindgen_test]
+fn fleet_convex_hull_and_outliers() {
+    let hull: ConvexHullResult = parse(&calculate_fleet_convex_hull(js(FLEET_JSON)).unwrap());
+
+    assert_eq!(hull.hull_points.len(), 3);
+    assert!(hull.perimeter_km > 0.0 && hull.area_km2 > 0.0);
+    assert!(stringify(&calculate_fleet_convex_hull(js(FLEET_JSON)).unwrap()).contains("\"hullPoints\""));
+
+    let hull_js = js(&format!("[{}, {}, {{\"longitude\": 4.8686, \"latitude\": 52.3579}}]", CENTRAAL_JSON, DAM_JSON));
+    let outside: Vec<BikePosition> = parse(&filter_bikes_outside_hull(js(FLEET_JSON), hull_js).unwrap());
+    assert!(outside.is_empty());
+
+    let err = calculate_fleet_convex_hull(js("[]")).unwrap_err();
+    assert!(err.as_string().unwrap().contains("at least 3 bikes"));
+}
+
+#[wasm_bindgen_test]
+fn cluster_bike_positions_caps_k_at_fleet_size() {
+    let result: ClusterResult = parse(&cluster_bike_positions(js(FLEET_JSON), 10, 20).unwrap());
+
+    assert_eq!(result.clusters.len(), 3);
+    assert!(result.clusters.iter().all(|c| c.count == 1 && c.bike_ids.len() == 1));
+    assert!(stringify(&cluster_bike_positions(js(FLEET_JSON), 1, 20).unwrap()).contains(r#""bikeIds":"#));
+    assert!(cluster_bike_positions(js(FLEET_JSON), 0, 20).is_err());
+}
+
+#[wasm_bindgen_test]
+fn generate_hex_grid_for_status_counts_matching_bikes() {
+    let grid: HexGridResult = parse(&generate_hex_grid(js(FLEET_JSON), 5.0).unwrap());
+    assert_eq!(grid.cells.iter().map(|c| c.count).sum::<u32>(), 3);
+
+    let idle: HexGridResult = parse(&generate_hex_grid_for_status(js(FLEET_JSON), 0.5, "idle").unwrap());
+    assert_eq!(idle.cells.len(), 1);
+    assert_eq!(idle.cells[0].status_breakdown.idle, 1);
+    assert!(stringify(&generate_hex_grid(js(FLEET_JSON), 0.5).unwrap()).contains(r#""statusBreakdown":"#));
+
+    assert!(generate_hex_grid(js(FLEET_JSON), 0.01).is_err());
+    assert!(generate_hex_grid_for_status(js(FLEET_JSON), 0.5, "parked").is_err());
+}
+
+#[wasm_bindgen_test]
+fn filter_bikes_by_name_is_case_insensitive() {
+    let bikes: Vec<BikePosition> = parse(&filter_bikes_by_name(js(FLEET_JSON), "DAM").unwrap());
+
+    let ids: Vec<&str> = bikes.iter().map(|b| b.id.as_str()).collect();
+    assert_eq!(ids, vec!["BIKE-0002"]);
+}
+
+#[wasm_bindgen_test]
+fn filter_bikes_by_battery_keeps_records() {
+    let bikes = js(r#"[
+        {"id": "BIKE-0001", "battery_level": 35, "status": "available"},
+        {"id": "BIKE-0002", "battery_level": 12, "status": "in_use"},
+        {"id": "BIKE-0003", "battery_level": null, "status": "offline"},
+        {"id": "BIKE-0004", "battery_level": 4, "status": "charging"}
+    ]"#);
+
+    let low: Vec<serde_json::Value> = parse(&filter_bikes_by_battery(bikes, 20).unwrap());
+
+    let ids: Vec<&str> = low.iter().map(|b| b["id"].as_str().unwrap()).collect();
+    assert_eq!(ids, vec!["BIKE-0004", "BIKE-0002"]);
+    assert_eq!(low[0]["status"], "charging");
+    assert!(filter_bikes_by_battery(js(r#"{"id": "BIKE-0001"}"#), 20).is_err());
+}
+
+#[wasm_bindgen_test]
+fn sort_by_maintenance_priority_keeps_records() {
+    let bikes = js(r#"[
+        {"id": "BIKE-0001", "next_maintenance_at": null, "status": "available"},
+        {"id": "BIKE-0002", "next_maintenance_at": "2026-03-01T09:00:00Z", "status": "in_use"},
+        {"id": "BIKE-0003", "next_maintenance_at": "2026-02-01T09:00:00.5+01:00", "status": "maintenance"},
+        {"id": "BIKE-0004", "status": "charging"}
+    ]"#);
+
+    let sorted: Vec<serde_json::Value> = parse(&sort_by_maintenance_priority(bikes).unwrap());
+
+    let ids: Vec<&str> = sorted.iter().map(|b| b["id"].as_str().unwrap()).collect();
+    assert_eq!(ids, vec!["BIKE-0003", "BIKE-0002", "BIKE-0001", "BIKE-0004"]);
+    assert_eq!(sorted[0]["status"], "maintenance");
+    assert!(sort_by_maintenance_priority(js(r#"[{"next_maintenance_at": "soon"}]"#)).is_err());
+}
+
+#[wasm_bindgen_test]
+fn calculate_delivery_eta_reads_idle_minutes() {
+    let bike = js(
+        r#"{"id": "BIKE-0002", "name": "Dam", "longitude": 4.8932, "latitude": 52.3731, "status": "idle", "speed": 0.0, "idleMinutes": 30}"#,
+    );
+    let eta: EtaResult = parse(&calculate_delivery_eta(bike, js(CENTRAAL_JSON)).unwrap());
+
+    assert!(eta.distance_km > 0.75 && eta.distance_km < 0.9);
+    assert!((eta.estimated_minutes - eta.distance_km / 25.0 * 60.0).abs() < 1e-9);
+    assert!((eta.confidence - 0.25).abs() < 1e-9);
+
+    let etas: Vec<EtaResult> = parse(
+        &calculate_fleet_eta_batch(js(FLEET_JSON), js(&format!("[{0}, {0}, {0}]", DAM_JSON))).unwrap(),
+    );
+    assert_eq!(etas.len(), 3);
+    assert!(etas.iter().all(|eta| eta.confidence == 1.0));
+    assert!(calculate_fleet_eta_batch(js(FLEET_JSON), js(&format!("[{}]", DAM_JSON))).is_err());
+}
+
+#[wasm_bindgen_test]
+fn sip_hashes_are_stable_and_see_small_moves() {
+    let hash = hash_bike_positions_sip(js(FLEET_JSON)).unwrap();
+    assert_eq!(hash_bike_positions_sip(js(FLEET_JSON)).unwrap(), hash);
+
+    let moved = FLEET_JSON.replace("52.3731", "52.373101");
+    assert_ne!(hash_bike_positions_sip(js(&moved)).unwrap(), hash);
+    assert_ne!(
+        hash_bike_state_sip(js(&moved)).unwrap(),
+        hash_bike_state_sip(js(FLEET_JSON)).unwrap()
+    );
+    assert!(hash_bike_state_sip(js(r#"{"id": "BIKE-0001"}"#)).is_err());
+}
+
+#[wasm_bindgen_test]
+fn simulation_tick_is_deterministic() {
+    let tick = || stringify(&simulation_tick(js(FLEET_JSON), 1_700_000_000_000.0, 0.1).unwrap());
+    assert_eq!(tick(), tick());
+
+    let seeded = || {
+        set_deterministic_seed(42);
+        let json = tick();
+        clear_deterministic_seed();
+        json
+    };
+    let first = seeded();
+    assert_eq!(first, seeded());
+
+    let result: SimulationTickResult = serde_json::from_str(&first).unwrap();
+    assert_eq!(result.bikes.len(), 3);
+    assert_eq!(result.statistics.total_bikes, 3);
+}
+
+#[wasm_bindgen_test]
+fn predict_bike_trajectory_extrapolates_velocity() {
+    let bike = js(
+        r#"{"id": "BIKE-0002", "name": "Dam", "longitude": 4.8932, "latitude": 52.3731, "status": "delivering", "speed": 18.0}"#,
+    );
+    let result: TrajectoryResult =
+        parse(&predict_bike_trajectory(bike, js(r#"{"longitude": 4.8922, "latitude": 52.3731}"#), 3, 1000.0).unwrap());
+
+    let longitudes: Vec<f64> = result.positions.iter().map(|p| p.longitude).collect();
+    assert!((longitudes[2] - 4.8962).abs() < 1e-9);
+    assert!(result.positions.iter().all(|p| p.latitude == 52.3731));
+    assert_eq!(result.confidence_decay.len(), 3);
+}
+
+#[wasm_bindgen_test]
+fn simulation_tick_rejects_empty_fleet() {
+    assert!(simulation_tick(js("[]"), 0.0, 0.1).is_err());
+}
+
+#[wasm_bindgen_test]
+fn speed_anomaly_sanitized_to_fleet_mean() {
+    let report_js = detect_speed_anomalies(js(FLEET_JSON), 1.2).unwrap();
+    let report: AnomalyReport = parse(&report_js);
+
+    // Speeds 18, 0, 12: only the idle bike is more than 1.2σ from the mean of 10
+    let ids: Vec<&str> = report.anomalies.iter().map(|a| a.bike_id.as_str()).collect();
+    assert_eq!(ids, vec!["BIKE-0002"]);
+
+    let dam = js(
+        r#"{"id": "BIKE-0002", "name": "Dam", "longitude": 4.8932, "latitude": 52.3731, "status": "idle", "speed": 0.0}"#,
+    );
+    let sanitized: BikePosition = parse(&sanitize_anomalous_speed(dam, report_js).unwrap());
+    assert!((sanitized.speed - 10.0).abs() < 1e-9);
+
+    assert!(detect_speed_anomalies(js(FLEET_JSON), 0.0).is_err());
+}
+
+#[wasm_bindgen_test]
+fn detect_acceleration_flags_sudden_stop() {
+    // Centraal brakes from 18 to 0 within a second without moving
+    let current = js(r#"[
+        {"id": "BIKE-0001", "name": "Centraal", "longitude": 4.9003, "latitude": 52.3791, "status": "idle", "speed": 0.0}
+    ]"#);
+    let report: AccelerationReport = parse(&detect_acceleration(current, js(FLEET_JSON), 1000.0).unwrap());
+
+    assert_eq!(report.bikes.len(), 1);
+    let centraal = &report.bikes[0];
+    assert_eq!(centraal.bike_id, "BIKE-0001");
+    assert!((centraal.acceleration + 18.0).abs() < 1e-9);
+    assert!(centraal.is_anomalous);
+    assert!(!centraal.speed_discrepancy);
+
+    assert!(detect_acceleration(js(FLEET_JSON), js(FLEET_JSON), 0.0).is_err());
+}
+
+#[wasm_bindgen_test]
+fn calculate_peak_hours_from_backend_json() {
+    let hourly = js(r#"[
+        {"hour": 9, "activeDeliveries": 1, "completedDeliveries": 2, "utilizationRatio": 0.2},
+        {"hour": 12, "activeDeliveries": 4, "completedDeliveries": 8, "utilizationRatio": 0.6},
+        {"hour": 19, "activeDeliveries": 3, "completedDeliveries": 6, "utilizationRatio": 0.5}
+    ]"#);
+
+    let result: PeakHoursResult = parse(&calculate_peak_hours(hourly).unwrap());
+
+    let hours: Vec<u8> = result.peak_hours.iter().map(|p| p.hour).collect();
+    assert_eq!(hours, vec![12, 19, 9]);
+    assert!((result.staffing_shares[12] - 0.5).abs() < 1e-9);
+}
+
+#[wasm_bindgen_test]
+fn smooth_bike_positions_blends_with_previous_frame() {
+    let prev = js(FLEET_JSON);
+    let moved: SimulationResult = parse(&simulate_bike_movement(js(FLEET_JSON), 500.0).unwrap());
+    let current_js = js(&serde_json::to_string(&moved.bikes).unwrap());
+
+    let smoothed: Vec<BikePosition> =
+        parse(&smooth_bike_positions(current_js, prev, 0.0).unwrap());
+    let original: Vec<BikePosition> = parse(&js(FLEET_JSON));
+    for (s, o) in smoothed.iter().zip(&original) {
+        assert_eq!((s.longitude, s.latitude, s.speed), (o.longitude, o.latitude, o.speed));
+    }
+
+    let tick = smooth_simulation_tick(js(FLEET_JSON), 0.0, 0.1, 0.5).unwrap();
+    let result: SimulationTickResult = parse(&tick);
+    assert_eq!(result.bikes.len(), original.len());
+    assert!(smooth_simulation_tick(js("[]"), 0.0, 0.1, 0.5).is_err());
+    assert!(smooth_simulation_tick(js(FLEET_JSON), 0.0, 0.1, f64::NAN).is_err());
+}
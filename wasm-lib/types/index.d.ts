@@ -0,0 +1,729 @@
+/**
+ * Typed overrides for the wasm-pack output.
+ *
+ * wasm-bindgen types every `JsValue` parameter and result as `any`; the
+ * declarations below shadow those exports with the actual shapes (see the
+ * serde structs in src/lib.rs). Everything else is re-exported unchanged.
+ */
+export * from '../pkg/amsterdam_bike_fleet_wasm';
+
+// ============================================================================
+// Data Types
+// ============================================================================
+
+export type BikeStatus = 'delivering' | 'idle' | 'returning';
+
+/** Bike position as tracked by the frontend */
+export interface BikePosition {
+  id: string;
+  name: string;
+  longitude: number;
+  latitude: number;
+  status: BikeStatus;
+  /** km/h */
+  speed: number;
+}
+
+/** Geographic coordinate in degrees */
+export interface Coordinate {
+  longitude: number;
+  latitude: number;
+}
+
+export interface FleetStatistics {
+  totalBikes: number;
+  deliveringCount: number;
+  idleCount: number;
+  returningCount: number;
+  averageSpeed: number;
+  maxSpeed: number;
+  minSpeed: number;
+  /** Share of delivering + returning bikes, 0-100 */
+  activePercentage: number;
+  fleetCenterLongitude: number;
+  fleetCenterLatitude: number;
+}
+
+export interface WeightedCenterResult {
+  longitude: number;
+  latitude: number;
+  /** Sum of the weights; 0 when the unweighted centroid was used */
+  totalWeight: number;
+}
+
+export interface ValidationResult {
+  isValid: boolean;
+  errors: string[];
+  warnings: string[];
+  sanitizedData: BikePosition | null;
+}
+
+export interface DistanceResult {
+  distanceKm: number;
+  distanceMiles: number;
+  bearingDegrees: number;
+}
+
+export interface IsochroneResult {
+  /** Closed ring: the last point repeats the first */
+  polygon: Coordinate[];
+  reachKm: number;
+  reachableAreaKm2: number;
+}
+
+export interface VincentyResult {
+  /** Meters on the WGS-84 ellipsoid */
+  distanceM: number;
+  /** 0-360 */
+  initialBearingDeg: number;
+  /** 0-360, direction of travel on arrival */
+  finalBearingDeg: number;
+  /** false if the formula did not converge and haversine was used instead */
+  converged: boolean;
+}
+
+export interface NearestBikeResult {
+  bike: BikePosition;
+  distanceKm: number;
+  bearingDegrees: number;
+  estimatedArrivalMinutes: number;
+}
+
+export interface BikePickupPair {
+  bikeId: string;
+  pickupCoordinate: Coordinate;
+  distanceKm: number;
+}
+
+export interface DispatchAssignment {
+  /** In the order of the pickups */
+  assignments: BikePickupPair[];
+  totalDistanceKm: number;
+  /** Pickups left over when there are fewer idle bikes than pickups */
+  unassignedPickups: Coordinate[];
+}
+
+export interface BikeZone {
+  bikeId: string;
+  /** Index into the hubs array */
+  hubIndex: number;
+  hubDistanceKm: number;
+}
+
+export interface ZoneAssignment {
+  /** In the order of the bikes */
+  assignments: BikeZone[];
+}
+
+export interface ZoneLoad {
+  /** Bikes per hub, indexed like the hubs */
+  bikeCounts: number[];
+  meanLoad: number;
+  /** Population variance of `bikeCounts`; 0 when perfectly balanced */
+  loadVariance: number;
+}
+
+export interface SimulationResult {
+  bikes: BikePosition[];
+  movementsApplied: number;
+  boundsCorrections: number;
+}
+
+export interface StatusTransitionResult {
+  newStatus: BikeStatus;
+  transitionOccurred: boolean;
+  probabilityUsed: number;
+}
+
+export interface SpeedResult {
+  speed: number;
+  baseSpeed: number;
+  trafficPenalty: number;
+  statusFactor: string;
+}
+
+export interface SimulationTickResult {
+  bikes: BikePosition[];
+  statistics: FleetStatistics;
+  positionHash: number;
+  stateHash: number;
+  statusTransitions: number;
+  boundsCorrections: number;
+}
+
+export interface EtaResult {
+  distanceKm: number;
+  estimatedMinutes: number;
+  /** 0.0-1.0, halves for every 15 minutes idle */
+  confidence: number;
+}
+
+export interface ConvexHullResult {
+  /** Counter-clockwise, starting at the southernmost bike; closed implicitly */
+  hullPoints: Coordinate[];
+  perimeterKm: number;
+  areaKm2: number;
+}
+
+export interface TrajectoryResult {
+  /** One per step, `intervalMs` apart, clamped to the operational bounds */
+  positions: Coordinate[];
+  /** Length of the predicted path */
+  estimatedArrivalKm: number;
+  /** Per position: 1 / (1 + 0.1 * index) */
+  confidenceDecay: number[];
+}
+
+export interface BikeCluster {
+  centroid: Coordinate;
+  bikeIds: string[];
+  count: number;
+}
+
+export interface ClusterResult {
+  clusters: BikeCluster[];
+}
+
+export interface StatusBreakdown {
+  delivering: number;
+  idle: number;
+  returning: number;
+}
+
+export interface HexCell {
+  center: Coordinate;
+  count: number;
+  /** km/h */
+  avgSpeed: number;
+  statusBreakdown: StatusBreakdown;
+}
+
+export interface HexGridResult {
+  /** Only cells containing bikes */
+  cells: HexCell[];
+}
+
+export interface SpeedAnomaly {
+  bikeId: string;
+  speed: number;
+  zScore: number;
+}
+
+export interface AnomalyReport {
+  /** In input order; empty when every bike has the same speed */
+  anomalies: SpeedAnomaly[];
+  fleetMeanSpeed: number;
+  /** Population standard deviation */
+  fleetStdDev: number;
+}
+
+export interface AccelerationData {
+  bikeId: string;
+  /** km/h per second; negative when braking */
+  acceleration: number;
+  /** |acceleration| > 5 km/h/s */
+  isAnomalous: boolean;
+  /** Speed implied by the distance between the two positions, km/h */
+  computedSpeed: number;
+  /** Reported and computed speed differ by more than 10 km/h */
+  speedDiscrepancy: boolean;
+}
+
+export interface AccelerationReport {
+  /** In input order; bikes without a previous sample are left out */
+  bikes: AccelerationData[];
+}
+
+/** As returned by the backend's `get_hourly_utilization` */
+export interface HourlyUtilization {
+  /** 0-23 (UTC) */
+  hour: number;
+  activeDeliveries: number;
+  completedDeliveries: number;
+  utilizationRatio: number;
+}
+
+export interface PeakHour {
+  hour: number;
+  /** Active plus completed deliveries */
+  deliveries: number;
+  utilizationRatio: number;
+  /** Relative to the average hour (2.0 = twice as busy) */
+  loadFactor: number;
+}
+
+export interface PeakHoursResult {
+  /** Up to 3 hours with deliveries, busiest first */
+  peakHours: PeakHour[];
+  /** Share of deliverer-hours per hour of the day (24 entries, index = hour) */
+  staffingShares: number[];
+}
+
+export interface CategorySuggestion {
+  category: 'late' | 'damaged' | 'wrong_order' | 'rude' | 'bike_problem' | 'other';
+  /** 0.0-1.0 */
+  confidence: number;
+}
+
+/** Speed ranges `[min, max]` in km/h; idle bikes always have speed 0 */
+export interface SpeedConfig {
+  delivering: [number, number];
+  returning: [number, number];
+  /** Share of the speed lost in traffic, 0.0-1.0 */
+  trafficReduction: number;
+}
+
+/** Markov chain rows `[toDelivering, toReturning, toIdle]`, each summing to 1 */
+export interface TransitionMatrix {
+  delivering: [number, number, number];
+  returning: [number, number, number];
+  idle: [number, number, number];
+}
+
+export interface SimulationConfig {
+  /** [minLongitude, maxLongitude, minLatitude, maxLatitude] */
+  operationalBounds: [number, number, number, number];
+  speedConfig: SpeedConfig;
+  transitionMatrix: TransitionMatrix;
+  /** Degrees per tick */
+  movementIdle: number;
+  /** Degrees per tick */
+  movementActive: number;
+  /** km/h */
+  maxBikeSpeed: number;
+}
+
+// ============================================================================
+// Deterministic Mode
+// ============================================================================
+
+/**
+ * Enable deterministic mode: simulation and clustering functions draw their
+ * random values from an RNG seeded with `seed`, so a sequence of calls is
+ * reproducible.
+ */
+export function setDeterministicSeed(seed: bigint): void;
+
+/** Disable deterministic mode, restoring timestamp-based behavior. */
+export function clearDeterministicSeed(): void;
+
+// ============================================================================
+// Fleet Statistics & Validation
+// ============================================================================
+
+/**
+ * Counts by status, speed metrics and geographic center of the fleet.
+ * @throws if `bikes` is empty or malformed
+ */
+export function calculateFleetStatistics(bikes: BikePosition[]): FleetStatistics;
+
+/**
+ * Fleet center weighted per bike (`weights[i]` belongs to `bikes[i]`).
+ * All-zero weights give the unweighted centroid.
+ * @throws if the fleet is empty, the lengths differ or a weight is negative
+ */
+export function calculateWeightedFleetCenter(
+  bikes: BikePosition[],
+  weights: number[]
+): WeightedCenterResult;
+
+/**
+ * `calculateWeightedFleetCenter` with each bike's share of all deliveries
+ * as its weight, so `totalWeight` is 1 (or 0 with no deliveries at all).
+ */
+export function calculateFleetCenterByDeliveryCount(
+  bikes: BikePosition[],
+  deliveryCounts: number[]
+): WeightedCenterResult;
+
+/**
+ * Check coordinates are within Amsterdam bounds and speed is plausible.
+ * Returns sanitized data alongside errors and warnings.
+ */
+export function validateBikeData(bike: BikePosition): ValidationResult;
+
+/** `validateBikeData` for every bike, in input order. */
+export function validateBikeDataBatch(bikes: BikePosition[]): ValidationResult[];
+
+// ============================================================================
+// Geographic Calculations
+// ============================================================================
+
+/** Haversine distance (km and miles) and initial bearing between two points. */
+export function calculateDistance(from: Coordinate, to: Coordinate): DistanceResult;
+
+/** Distance and bearing from a bike to a target coordinate. */
+export function calculateBikeDistance(bike: BikePosition, target: Coordinate): DistanceResult;
+
+/**
+ * The bike closest to `target`.
+ * @throws if `bikes` is empty
+ */
+export function findNearestBike(bikes: BikePosition[], target: Coordinate): BikePosition;
+
+/**
+ * Up to `n` bikes closest to `target`, closest first, optionally only those
+ * with `statusFilter`.
+ */
+export function findNearestNBikes(
+  bikes: BikePosition[],
+  target: Coordinate,
+  n: number,
+  statusFilter?: BikeStatus | null
+): NearestBikeResult[];
+
+/** All bikes within `radiusKm` of `center`. */
+export function findBikesInRadius(bikes: BikePosition[], center: Coordinate, radiusKm: number): BikePosition[];
+
+/**
+ * Assign idle bikes to delivery pickups, greedily pairing the closest free
+ * bike and pickup first. Only idle bikes are dispatched.
+ */
+export function computeOptimalDispatch(bikes: BikePosition[], pickups: Coordinate[]): DispatchAssignment;
+
+/**
+ * Assign each bike to the zone of its nearest hub (depot). A bike equally
+ * close to two hubs goes to the lower index.
+ * @throws if `hubs` is empty
+ */
+export function assignBikesToZones(bikes: BikePosition[], hubs: Coordinate[]): ZoneAssignment;
+
+/**
+ * Bikes per hub for the `assignments` of a ZoneAssignment, with their
+ * mean and variance.
+ * @throws if `hubCount` is 0 or an assignment points past the last hub
+ */
+export function calculateZoneLoad(assignments: BikeZone[], hubCount: number): ZoneLoad;
+
+/**
+ * Vincenty inverse formula on the WGS-84 ellipsoid: sub-millimeter distance
+ * and bearings. Nearly antipodal points fall back to haversine.
+ */
+export function vincentyDistance(from: Coordinate, to: Coordinate): VincentyResult;
+
+/**
+ * `vincentyDistance` for every pair.
+ * @throws if `froms` and `tos` differ in length
+ */
+export function vincentyDistanceBatch(froms: Coordinate[], tos: Coordinate[]): VincentyResult[];
+
+// ============================================================================
+// Isochrones
+// ============================================================================
+
+/**
+ * Straight-line reach of a bike in every direction within `minutes` at
+ * its current speed, sampled in `resolution` (≥ 3) directions.
+ * A stationary bike gives a polygon collapsed onto its position.
+ * @throws if `resolution` is below 3 or `minutes` is negative
+ */
+export function calculateIsochrone(bike: BikePosition, minutes: number, resolution: number): IsochroneResult;
+
+// ============================================================================
+// Encoded Polylines
+// ============================================================================
+
+/**
+ * Decode a Google Encoded Polyline (precision 5), e.g. from a routing API.
+ * @throws if `encoded` is not a valid polyline
+ */
+export function decodePolyline(encoded: string): Coordinate[];
+
+/** Encode coordinates as a Google Encoded Polyline, rounded to 1e-5 degrees. */
+export function encodePolyline(coords: Coordinate[]): string;
+
+/**
+ * Total haversine length of an encoded polyline, in km.
+ * @throws if `encoded` is not a valid polyline
+ */
+export function polylineLength(encoded: string): number;
+
+/**
+ * Total haversine length of a recorded route, in km; 0 for fewer than
+ * two waypoints. Accepts the waypoints from `get_delivery_route`.
+ * @throws if a waypoint lacks `latitude` or `longitude`
+ */
+export function calculateRouteLength(waypoints: Coordinate[]): number;
+
+// ============================================================================
+// Geofencing
+// ============================================================================
+
+/**
+ * Ray-casting point-in-polygon test; points on an edge count as inside.
+ * The polygon is closed implicitly; self-intersecting polygons are unsupported.
+ * @throws if `polygon` has fewer than 3 vertices
+ */
+export function isPointInPolygon(point: Coordinate, polygon: Coordinate[]): boolean;
+
+/**
+ * Bikes inside `polygon` (e.g. a neighborhood), in input order.
+ * @throws if `polygon` has fewer than 3 vertices
+ */
+export function findBikesInZone(bikes: BikePosition[], polygon: Coordinate[]): BikePosition[];
+
+/**
+ * Area of `polygon` in km² (shoelace, scaled at the centroid latitude).
+ * Self-intersecting polygons give the absolute value of the signed area.
+ * @throws if `polygon` has fewer than 3 vertices
+ */
+export function calculatePolygonArea(polygon: Coordinate[]): number;
+
+/**
+ * Haversine length of the outline of `polygon` in km, including the closing edge.
+ * @throws if `polygon` has fewer than 3 vertices
+ */
+export function calculatePolygonPerimeter(polygon: Coordinate[]): number;
+
+// ============================================================================
+// Fleet Coverage (Convex Hull)
+// ============================================================================
+
+/**
+ * Graham-scan convex hull of the bike positions, with its perimeter and
+ * area (shoelace, scaled at the fleet centroid).
+ * @throws if there are fewer than 3 bikes or they all lie on one line
+ */
+export function calculateFleetConvexHull(bikes: BikePosition[]): ConvexHullResult;
+
+/**
+ * Bikes outside `hull` (the `hullPoints` of `calculateFleetConvexHull`), in
+ * input order; bikes on an edge count as inside.
+ * @throws if `hull` has fewer than 3 vertices
+ */
+export function filterBikesOutsideHull(bikes: BikePosition[], hull: Coordinate[]): BikePosition[];
+
+// ============================================================================
+// Clustering
+// ============================================================================
+
+/**
+ * k-means (k-means++ seeded) grouping of bike positions. Returns at most one
+ * cluster per bike; clusters left empty are omitted.
+ * @throws if `k` is 0
+ */
+export function clusterBikePositions(bikes: BikePosition[], k: number, maxIterations: number): ClusterResult;
+
+// ============================================================================
+// Hexagonal Heat Map
+// ============================================================================
+
+/**
+ * Bin bikes into flat-top hexagons (like deck.gl's HexagonLayer); empty
+ * cells are omitted.
+ * @param cellSizeKm Hexagon size, center to corner
+ * @throws if `cellSizeKm` is outside 0.05-5.0
+ */
+export function generateHexGrid(bikes: BikePosition[], cellSizeKm: number): HexGridResult;
+
+/**
+ * `generateHexGrid` for only the bikes with `status`.
+ * @throws if `status` is unknown or `cellSizeKm` is outside 0.05-5.0
+ */
+export function generateHexGridForStatus(bikes: BikePosition[], cellSizeKm: number, status: BikeStatus): HexGridResult;
+
+// ============================================================================
+// Search
+// ============================================================================
+
+/**
+ * Bikes whose `name` contains `query`, case-insensitive, in input order.
+ * A blank query keeps every bike.
+ * @throws if `bikes` is not an array of objects with a string `name`
+ */
+export function filterBikesByName<T extends { name: string }>(bikes: T[], query: string): T[];
+
+// ============================================================================
+// Battery Alerts
+// ============================================================================
+
+/**
+ * Bikes with `battery_level` below `threshold`, lowest first. Accepts the
+ * backend's `Bike` records and returns them unchanged; null levels are skipped.
+ * @throws if `bikes` is not an array of objects
+ */
+export function filterBikesByBattery<T extends { battery_level: number | null }>(bikes: T[], threshold: number): T[];
+
+// ============================================================================
+// Maintenance Priority
+// ============================================================================
+
+/**
+ * Sort bikes by `next_maintenance_at`, soonest or most overdue first.
+ * Takes the backend's `Bike` records and returns them unchanged; bikes
+ * with nothing scheduled go last.
+ * @throws if `bikes` is not an array of objects or a timestamp is invalid
+ */
+export function sortByMaintenancePriority<T extends { next_maintenance_at?: string | null }>(bikes: T[]): T[];
+
+// ============================================================================
+// Delivery ETA
+// ============================================================================
+
+/** A bike for ETA estimates, optionally with how long it has stood still. */
+export type EtaBike = BikePosition & { idleMinutes?: number };
+
+/**
+ * Time for a bike to reach `destination` at its current speed (or the middle
+ * of the delivering range if it is stopped).
+ */
+export function calculateDeliveryEta(bike: EtaBike, destination: Coordinate): EtaResult;
+
+/**
+ * `calculateDeliveryEta` for every bike.
+ * @throws if `bikes` and `destinations` differ in length
+ */
+export function calculateFleetEtaBatch(bikes: EtaBike[], destinations: Coordinate[]): EtaResult[];
+
+// ============================================================================
+// Simulation
+// ============================================================================
+
+/**
+ * Move every bike one tick: idle bikes jitter, active bikes travel, and all
+ * positions are clamped to Amsterdam's operational bounds.
+ * @param seed Random seed, e.g. `Date.now()`
+ */
+export function simulateBikeMovement(bikes: BikePosition[], seed: number): SimulationResult;
+
+/**
+ * Markov-chain status transition.
+ * @param randomValue 0.0-1.0, e.g. `Math.random()`
+ */
+export function transitionBikeStatus(currentStatus: BikeStatus, randomValue: number): StatusTransitionResult;
+
+/**
+ * `transitionBikeStatus` for every bike; both arrays must have the same length.
+ * Unknown statuses are dropped from the result.
+ */
+export function transitionBikeStatusBatch(statuses: BikeStatus[], randomValues: number[]): StatusTransitionResult[];
+
+/**
+ * Speed for a status, with a 40% penalty in traffic.
+ * @param randomFactor 0.0-1.0, picks a speed within the status range
+ */
+export function calculateBikeSpeed(status: BikeStatus, isInTraffic: boolean, randomFactor: number): SpeedResult;
+
+/** Speeds (km/h) for many bikes; all arrays must have the same length. */
+export function calculateBikeSpeedBatch(statuses: BikeStatus[], inTraffic: boolean[], randomFactors: number[]): number[];
+
+/**
+ * Linearly extrapolate a bike's path from its previous position, taken to be
+ * `intervalMs` old. A stationary bike stays in place.
+ * @throws if `intervalMs` is not positive
+ */
+export function predictBikeTrajectory(
+  bike: BikePosition,
+  prevPosition: Coordinate,
+  steps: number,
+  intervalMs: number
+): TrajectoryResult;
+
+/** 32-bit FNV-1a style hash of positions, for cheap change detection. */
+export function hashBikePositions(bikes: BikePosition[]): number;
+
+/** Like `hashBikePositions`, but also covers each bike's status. */
+export function hashBikeState(bikes: BikePosition[]): number;
+
+/**
+ * Lower 32 bits of a SipHash-2-4 of positions (6 decimal places).
+ * Collides far less than `hashBikePositions`.
+ */
+export function hashBikePositionsSip(bikes: BikePosition[]): number;
+
+/** Like `hashBikePositionsSip`, but also covers each bike's status and speed. */
+export function hashBikeStateSip(bikes: BikePosition[]): number;
+
+/**
+ * One full simulation step: movement, status transitions, speeds,
+ * statistics and change-detection hashes.
+ * @param timestamp Seed for this tick, e.g. `Date.now()`
+ * @param transitionProbability 0.0-1.0 chance that a bike changes status
+ * @throws if `bikes` is empty
+ */
+export function simulationTick(
+  bikes: BikePosition[],
+  timestamp: number,
+  transitionProbability: number
+): SimulationTickResult;
+
+/**
+ * Blend each bike's position and speed with its previous frame:
+ * `alpha * current + (1 - alpha) * previous`. Bikes missing from
+ * `previousBikes` pass through unchanged.
+ * @param alpha 0.0-1.0 weight of the current frame (clamped)
+ * @throws if `alpha` is NaN
+ */
+export function smoothBikePositions(
+  currentBikes: BikePosition[],
+  previousBikes: BikePosition[],
+  alpha: number
+): BikePosition[];
+
+/**
+ * `simulationTick` with its new positions smoothed against the input
+ * fleet; statistics and hashes describe the smoothed positions.
+ * @throws if `bikes` is empty or `alpha` is NaN
+ */
+export function smoothSimulationTick(
+  bikes: BikePosition[],
+  timestamp: number,
+  transitionProbability: number,
+  alpha: number
+): SimulationTickResult;
+
+// ============================================================================
+// Simulation Configuration
+// ============================================================================
+
+/**
+ * Replace all simulation parameters at once; omitted fields take defaults.
+ * @throws if the configuration is invalid (the previous one stays active)
+ */
+export function applySimulationConfig(config: Partial<SimulationConfig>): void;
+
+/** The configuration currently in effect. */
+export function getSimulationConfig(): SimulationConfig;
+
+/** Restore the default (Amsterdam) configuration. */
+export function resetSimulationConfig(): void;
+
+// ============================================================================
+// Analytics
+// ============================================================================
+
+/**
+ * Pearson correlation of two equally long series.
+ * @returns -1.0..1.0, or NaN when there is not enough data
+ */
+export function computePearsonCorrelation(xs: number[], ys: number[]): number;
+
+/**
+ * Bikes whose speed z-score exceeds `zThreshold` in absolute value.
+ * @throws if `zThreshold` is not positive
+ */
+export function detectSpeedAnomalies(bikes: BikePosition[], zThreshold: number): AnomalyReport;
+
+/** The bike with its speed set to the fleet mean if `report` flags it. */
+export function sanitizeAnomalousSpeed(bike: BikePosition, report: AnomalyReport): BikePosition;
+
+/**
+ * Acceleration between two samples of the same bikes, plus a check of the
+ * reported speed against the distance actually travelled.
+ * @param intervalMs Time between `previousBikes` and `bikes`
+ * @throws if `intervalMs` is not positive
+ */
+export function detectAcceleration(
+  bikes: BikePosition[],
+  previousBikes: BikePosition[],
+  intervalMs: number
+): AccelerationReport;
+
+/**
+ * Top 3 hours by delivery load, plus how to spread deliverers over the day.
+ * @throws if an hour is outside 0-23
+ */
+export function calculatePeakHours(hourly: HourlyUtilization[]): PeakHoursResult;
+
+/** Keyword-based issue category suggestion, matching the backend. */
+export function suggestIssueCategory(description: string): CategorySuggestion;